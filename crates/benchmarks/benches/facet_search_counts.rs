@@ -0,0 +1,85 @@
+//! Measures the facet searches sorted by count on facet search FSTs storing the document count
+//! of each facet value, and on the same FSTs written as sets like before the counts were stored.
+//!
+//! Without the counts the documents of every matching facet value must be fetched before the
+//! values are ranked, while the counts allow skipping the values that can't enter the page.
+
+mod datasets_paths;
+mod utils;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId};
+use milli::update::Settings;
+use milli::{FilterableAttributesRule, Index, OrderBy, SearchForFacetValues};
+use utils::Conf;
+
+#[cfg(not(windows))]
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+const FACETS: &[&str] = &["genre", "artist", "country"];
+const QUERIES: &[&str] = &["", "a", "ro", "the"];
+
+fn base_conf(builder: &mut Settings) {
+    let displayed_fields = ["id", "title", "album", "artist", "genre", "country"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    builder.set_displayed_fields(displayed_fields);
+
+    let faceted_fields =
+        FACETS.iter().map(|s| FilterableAttributesRule::Field(s.to_string())).collect();
+    builder.set_filterable_fields(faceted_fields);
+}
+
+#[rustfmt::skip]
+const BASE_CONF: Conf = Conf {
+    dataset: datasets_paths::SMOL_SONGS,
+    configure: base_conf,
+    ..Conf::BASE
+};
+
+/// Rewrites the facet search FSTs as sets, whose values are all read with an unknown count.
+fn drop_fst_counts(index: &Index) {
+    let mut wtxn = index.write_txn().unwrap();
+    let mut sets = Vec::new();
+    for result in index.facet_id_string_fst.iter(&wtxn).unwrap() {
+        let (field_id, fst) = result.unwrap();
+        let set = fst::Set::from_iter(fst.stream().into_byte_keys()).unwrap();
+        sets.push((field_id, set.as_fst().as_bytes().to_vec()));
+    }
+    for (field_id, bytes) in sets {
+        let map = fst::Map::new(bytes).unwrap();
+        index.facet_id_string_fst.put(&mut wtxn, &field_id, &map).unwrap();
+    }
+    wtxn.commit().unwrap();
+}
+
+fn bench_facet_search_counts(c: &mut criterion::Criterion) {
+    let with_counts = utils::base_setup(&BASE_CONF);
+    let without_counts =
+        utils::base_setup(&Conf { database_name: "without_counts.mmdb", ..BASE_CONF });
+    drop_fst_counts(&without_counts);
+
+    let mut group = c.benchmark_group("smol-songs.csv: facet search sorted by count");
+    for (name, index) in [("with counts", &with_counts), ("without counts", &without_counts)] {
+        let rtxn = index.read_txn().unwrap();
+        for &facet in FACETS {
+            for &query in QUERIES {
+                let id = BenchmarkId::new(name, format!("{facet}: {query:?}"));
+                group.bench_with_input(id, &query, |b, &query| {
+                    b.iter(|| {
+                        let search = index.search(&rtxn);
+                        let mut facet_search =
+                            SearchForFacetValues::new(facet.to_string(), search, false);
+                        facet_search.query(query).max_values(10).sort_by(OrderBy::Count);
+                        facet_search.execute().unwrap()
+                    });
+                });
+            }
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_facet_search_counts);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+//! Measures the first prefix search run right after an index is opened, with and without
+//! warming up the word prefixes of the queries beforehand.
+//!
+//! The pages of the index stay in the OS page cache between two iterations, drop it
+//! beforehand (e.g. `sync; echo 1 > /proc/sys/vm/drop_caches`) to measure the cold case.
+
+mod datasets_paths;
+mod utils;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId};
+use milli::heed::EnvOpenOptions;
+use milli::index::PrefixSearchWarmup;
+use milli::update::Settings;
+use milli::{Index, TermsMatchingStrategy};
+use utils::Conf;
+
+#[cfg(not(windows))]
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+const PREFIXES: &[&str] = &["ja", "fra", "mach"];
+
+fn base_conf(builder: &mut Settings) {
+    let displayed_fields = ["title", "body", "url"].iter().map(|s| s.to_string()).collect();
+    builder.set_displayed_fields(displayed_fields);
+
+    let searchable_fields = ["title", "body"].iter().map(|s| s.to_string()).collect();
+    builder.set_searchable_fields(searchable_fields);
+}
+
+#[rustfmt::skip]
+const BASE_CONF: Conf = Conf {
+    dataset: datasets_paths::SMOL_WIKI_ARTICLES,
+    configure: base_conf,
+    ..Conf::BASE
+};
+
+fn open_index(conf: &Conf) -> Index {
+    let options = EnvOpenOptions::new();
+    let mut options = options.read_txn_without_tls();
+    options.map_size(100 * 1024 * 1024 * 1024); // 100 GB
+    options.max_readers(100);
+    Index::new(options, conf.database_name, false).unwrap()
+}
+
+fn bench_prefix_warmup(c: &mut criterion::Criterion) {
+    let index = utils::base_setup(&BASE_CONF);
+    index.prepare_for_closing().wait();
+
+    let warmups = [
+        ("no warmup", None),
+        (
+            "warmup of the queried prefixes",
+            Some(PrefixSearchWarmup::Prefixes(PREFIXES.iter().map(|s| s.to_string()).collect())),
+        ),
+        ("warmup of the 100 largest prefixes", Some(PrefixSearchWarmup::Largest(100))),
+    ];
+
+    let mut group = c.benchmark_group("smol-wiki-articles.csv: first prefix search");
+    for (name, warmup) in &warmups {
+        for &prefix in PREFIXES {
+            group.bench_with_input(BenchmarkId::new(*name, prefix), &prefix, |b, &prefix| {
+                b.iter_batched(
+                    || {
+                        let index = open_index(&BASE_CONF);
+                        if let Some(warmup) = warmup {
+                            let rtxn = index.read_txn().unwrap();
+                            index.warmup_word_prefixes(&rtxn, warmup).unwrap();
+                        }
+                        index
+                    },
+                    |index| {
+                        {
+                            let rtxn = index.read_txn().unwrap();
+                            let mut search = index.search(&rtxn);
+                            search
+                                .query(prefix)
+                                .terms_matching_strategy(TermsMatchingStrategy::default());
+                            let _ids = search.execute().unwrap();
+                        }
+                        // closed out of the measurement, before the next iteration reopens it
+                        index
+                    },
+                    BatchSize::PerIteration,
+                );
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_prefix_warmup);
+criterion_main!(benches);
@@ -334,9 +334,18 @@ pub(crate) mod test {
             typo_tolerance: Setting::NotSet,
             faceting: Setting::Set(FacetingSettings {
                 max_values_per_facet: Setting::Set(111),
+                max_values_per_document_field: Setting::NotSet,
+                track_values_recency: Setting::NotSet,
+                max_facet_levels: Setting::NotSet,
+                expected_cardinality: Setting::NotSet,
+                update_method: Setting::NotSet,
+                value_weight_fields: Setting::NotSet,
                 sort_facet_values_by: Setting::Set(
                     btreemap! { S("age") => FacetValuesSort::Count },
                 ),
+                mode: Setting::NotSet,
+                warmup: Setting::NotSet,
+                pinned_distributions: Setting::NotSet,
             }),
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
@@ -368,6 +377,9 @@ pub(crate) mod test {
                 progress_trace: Default::default(),
                 write_channel_congestion: None,
                 internal_database_sizes: Default::default(),
+                facet_indexing_peak_memory: None,
+                facet_effective_group_size: None,
+                word_prefix_changes: None,
             },
             embedder_stats: Default::default(),
             enqueued_at: Some(BatchEnqueuedAt {
@@ -398,6 +410,7 @@ pub(crate) mod test {
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 12,
                         indexed_documents: Some(10),
+                        facet_updates: Default::default(),
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
@@ -424,6 +437,7 @@ pub(crate) mod test {
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 2,
                         indexed_documents: None,
+                        facet_updates: Default::default(),
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
@@ -135,11 +135,13 @@ impl CompatV5ToV6 {
                             v6::Details::DocumentAdditionOrUpdate {
                                 received_documents: received_documents as u64,
                                 indexed_documents,
+                                facet_updates: Default::default(),
                             }
                         }
-                        v5::Details::Settings { settings } => {
-                            v6::Details::SettingsUpdate { settings: Box::new(settings.into()) }
-                        }
+                        v5::Details::Settings { settings } => v6::Details::SettingsUpdate {
+                            settings: Box::new(settings.into()),
+                            warning: None,
+                        },
                         v5::Details::IndexInfo { primary_key } => v6::Details::IndexInfo {
                             primary_key,
                             new_index_uid: None,
@@ -398,7 +400,16 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             faceting: match settings.faceting {
                 v5::Setting::Set(faceting) => v6::Setting::Set(v6::FacetingSettings {
                     max_values_per_facet: faceting.max_values_per_facet.into(),
+                    max_values_per_document_field: v6::Setting::NotSet,
+                    track_values_recency: v6::Setting::NotSet,
+                    max_facet_levels: v6::Setting::NotSet,
+                    expected_cardinality: v6::Setting::NotSet,
+                    update_method: v6::Setting::NotSet,
+                    value_weight_fields: v6::Setting::NotSet,
                     sort_facet_values_by: v6::Setting::NotSet,
+                    mode: v6::Setting::NotSet,
+                    warmup: v6::Setting::NotSet,
+                    pinned_distributions: v6::Setting::NotSet,
                 }),
                 v5::Setting::Reset => v6::Setting::Reset,
                 v5::Setting::NotSet => v6::Setting::NotSet,
@@ -175,6 +175,19 @@ impl RoFeatures {
             .into())
         }
     }
+
+    pub fn check_facet_bitmaps(&self, disabled_action: &'static str) -> Result<()> {
+        if self.runtime.facet_bitmaps {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action,
+                feature: "facet bitmaps",
+                issue_link: "https://github.com/orgs/meilisearch/discussions",
+            }
+            .into())
+        }
+    }
 }
 
 impl FeatureData {
@@ -323,10 +323,56 @@ fn create_or_open_index(
         unsafe { options.flags(EnvFlags::WRITE_MAP) };
     }
 
-    if let Some((created, updated)) = date {
-        Ok(Index::new_with_creation_dates(options, path, created, updated, creation)?)
+    let index = if let Some((created, updated)) = date {
+        Index::new_with_creation_dates(options, path, created, updated, creation)?
     } else {
-        Ok(Index::new(options, path, creation)?)
+        Index::new(options, path, creation)?
+    };
+
+    if !creation {
+        warmup_facets(&index);
+        warmup_word_prefixes(&index);
+    }
+
+    Ok(index)
+}
+
+/// Loads the facet structures of the index in memory if the faceting warmup is enabled.
+///
+/// This is best effort, a failure is logged but doesn't prevent the index from being opened.
+fn warmup_facets(index: &Index) {
+    let result: Result<Option<u64>> = index.read_txn().map_err(Into::into).and_then(|rtxn| {
+        match index.faceting_warmup(&rtxn)? {
+            Some(warmup) => index.warmup_facets(&rtxn, warmup).map(Some),
+            None => Ok(None),
+        }
+    });
+
+    match result {
+        Ok(Some(bytes)) => tracing::debug!(bytes, "warmed up the facet structures"),
+        Ok(None) => (),
+        Err(error) => tracing::warn!(%error, "could not warm up the facet structures"),
+    }
+}
+
+/// Loads the word prefix entries of the index in memory if the prefix search warmup is enabled.
+///
+/// This is best effort, a failure is logged but doesn't prevent the index from being opened.
+fn warmup_word_prefixes(index: &Index) {
+    let result: Result<Option<Vec<String>>> =
+        index.read_txn().map_err(Into::into).and_then(|rtxn| {
+            match index.prefix_search_warmup(&rtxn)? {
+                Some(warmup) => index.warmup_word_prefixes(&rtxn, &warmup).map(Some),
+                None => Ok(None),
+            }
+        });
+
+    match result {
+        Ok(Some(prefixes)) => {
+            tracing::debug!(prefixes = prefixes.len(), "warmed up the word prefixes")
+        }
+        Ok(None) => (),
+        Err(error) => tracing::warn!(%error, "could not warm up the word prefixes"),
     }
 }
 
@@ -397,6 +443,22 @@ mod tests {
         assert_index_size(index, mapper.index_base_map_size + mapper.index_growth_amount * 2);
     }
 
+    #[test]
+    fn index_closings() {
+        let (mapper, env, _handle) = IndexMapper::test();
+        let closings = mapper.index_closings();
+        let index = mapper.create_index(env.write_txn().unwrap(), "index", None).unwrap();
+        assert!(!closings.is_closing(&index));
+
+        // the index cannot be closed while it is kept
+        mapper.resize_index(&env.read_txn().unwrap(), "index").unwrap();
+        assert!(closings.is_closing(&index));
+        drop(index);
+
+        let index = mapper.create_index(env.write_txn().unwrap(), "index", None).unwrap();
+        assert!(!closings.is_closing(&index));
+    }
+
     fn assert_index_size(index: Index, expected: usize) {
         let expected = clamp_to_page_size(expected);
         let index_map_size = index.map_size();
@@ -97,6 +97,33 @@ pub enum IndexStatus {
     Available(Index),
 }
 
+/// A handle telling whether the indexes are being closed or deleted.
+///
+/// The users keeping an `Index` for a long time must check it regularly and drop their handle when
+/// the index is closing, as its environment cannot be closed, and the scheduler waits, as long as
+/// the handle is alive.
+#[derive(Clone)]
+pub struct IndexClosings {
+    index_map: Arc<RwLock<IndexMap>>,
+}
+
+impl std::fmt::Debug for IndexClosings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexClosings").finish_non_exhaustive()
+    }
+}
+
+impl IndexClosings {
+    /// Whether the index is being closed, to be resized or evicted from the cache, or deleted.
+    pub fn is_closing(&self, index: &Index) -> bool {
+        let uuid = index.path().file_name().and_then(|name| name.to_str()?.parse::<Uuid>().ok());
+        match uuid {
+            Some(uuid) => !matches!(self.index_map.read().unwrap().get(&uuid), Available(_)),
+            None => false,
+        }
+    }
+}
+
 /// The statistics that can be computed from an `Index` object.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct IndexStats {
@@ -622,6 +649,10 @@ impl IndexMapper {
         &self.indexer_config
     }
 
+    pub fn index_closings(&self) -> IndexClosings {
+        IndexClosings { index_map: self.index_map.clone() }
+    }
+
     pub fn set_currently_updating_index(&self, index: Option<(String, Index)>) {
         *self.currently_updating_index.write().unwrap() = index;
     }
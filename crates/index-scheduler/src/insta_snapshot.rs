@@ -5,6 +5,7 @@ use meilisearch_types::batches::{Batch, BatchEnqueuedAt, BatchStats};
 use meilisearch_types::heed::types::{SerdeBincode, SerdeJson, Str};
 use meilisearch_types::heed::{Database, RoTxn};
 use meilisearch_types::milli::{CboRoaringBitmapCodec, RoaringBitmapCodec, BEU32};
+use meilisearch_types::task_view::DetailsView;
 use meilisearch_types::tasks::{Details, Kind, Status, Task};
 use meilisearch_types::versioning::{self, VERSION_MAJOR, VERSION_MINOR, VERSION_PATCH};
 use roaring::RoaringBitmap;
@@ -267,6 +268,7 @@ fn snapshot_details(d: &Details) -> String {
         Details::DocumentAdditionOrUpdate {
             received_documents,
             indexed_documents,
+            facet_updates: _,
         } => {
             format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?} }}")
         }
@@ -281,7 +283,7 @@ fn snapshot_details(d: &Details) -> String {
                 "{{ deleted_documents: {deleted_documents:?}, edited_documents: {edited_documents:?}, context: {context:?}, function: {function:?}, original_filter: {original_filter:?} }}"
             )
         }
-        Details::SettingsUpdate { settings } => {
+        Details::SettingsUpdate { settings, warning: _ } => {
             format!("{{ settings: {settings:?} }}")
         }
         Details::IndexInfo { primary_key, new_index_uid, old_index_uid } => {
@@ -397,6 +399,9 @@ pub fn snapshot_batch(batch: &Batch) -> String {
         progress_trace: Default::default(),
         internal_database_sizes: Default::default(),
         write_channel_congestion: None,
+        facet_indexing_peak_memory: None,
+        facet_effective_group_size: None,
+        word_prefix_changes: None,
         ..stats.clone()
     };
     if let Some(finished_at) = finished_at {
@@ -408,20 +413,16 @@ pub fn snapshot_batch(batch: &Batch) -> String {
 
     snap.push('{');
     snap.push_str(&format!("uid: {uid}, "));
-    let details = if let Some(upgrade_to) = &details.upgrade_to {
+    // the facet update decisions depend on the heuristic and are not snapshotted
+    let mut details = DetailsView { facet_updates: None, ..details.clone() };
+    if let Some(upgrade_to) = &details.upgrade_to {
         if upgrade_to.as_str()
             == format!("v{VERSION_MAJOR}.{VERSION_MINOR}.{VERSION_PATCH}").as_str()
         {
-            let mut details = details.clone();
-
             details.upgrade_to = Some("[current version]".into());
-            serde_json::to_string(&details).unwrap()
-        } else {
-            serde_json::to_string(details).unwrap()
         }
-    } else {
-        serde_json::to_string(details).unwrap()
-    };
+    }
+    let details = serde_json::to_string(&details).unwrap();
     snap.push_str(&format!("details: {details}, "));
     snap.push_str(&format!("stats: {}, ", serde_json::to_string(&stats).unwrap()));
     if !embedder_stats.skip_serializing() {
@@ -54,6 +54,7 @@ pub use error::Error;
 pub use features::RoFeatures;
 use flate2::bufread::GzEncoder;
 use flate2::Compression;
+pub use index_mapper::IndexClosings;
 use meilisearch_types::batches::Batch;
 use meilisearch_types::features::{
     ChatCompletionSettings, InstanceTogglableFeatures, RuntimeTogglableFeatures,
@@ -553,6 +554,12 @@ impl IndexScheduler {
         self.index_mapper.index(&rtxn, name)
     }
 
+    /// Returns a handle telling whether the indexes are being closed or deleted,
+    /// for the users keeping an `Index` opened longer than a request.
+    pub fn index_closings(&self) -> IndexClosings {
+        self.index_mapper.index_closings()
+    }
+
     /// Return the boolean referring if index exists.
     pub fn index_exists(&self, name: &str) -> Result<bool> {
         let rtxn = self.env.read_txn()?;
@@ -64,6 +64,7 @@ make_enum_progress! {
 
 make_enum_progress! {
     pub enum FinalizingIndexStep {
+        ComputingPinnedFacetDistributions,
         Committing,
         ComputingStats,
     }
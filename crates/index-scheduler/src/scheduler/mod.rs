@@ -361,7 +361,7 @@ impl IndexScheduler {
             // 3. resize it
             // 4. re-schedule tasks
             Err(Error::Milli {
-                error: milli::Error::UserError(milli::UserError::MaxDatabaseSizeReached),
+                error: milli::Error::UserError(milli::UserError::MaxDatabaseSizeReached { .. }),
                 ..
             }) if index_uid.is_some() => {
                 // fixme: add index_uid to match to avoid the unwrap
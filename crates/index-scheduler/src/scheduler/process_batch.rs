@@ -181,6 +181,18 @@ impl IndexScheduler {
                     network,
                 )?;
 
+                // the pinned facet distributions are only served while the index is not updated,
+                // so they must be computed in the same transaction as the update
+                {
+                    progress
+                        .update_progress(FinalizingIndexStep::ComputingPinnedFacetDistributions);
+                    let span = tracing::trace_span!(target: "indexing::scheduler", "pinned_facet_distributions");
+                    let _entered = span.enter();
+
+                    milli::refresh_pinned_facet_distributions(&index, &mut index_wtxn)
+                        .map_err(|e| Error::from_milli(e, Some(index_uid.clone())))?;
+                }
+
                 {
                     progress.update_progress(FinalizingIndexStep::Committing);
                     let span = tracing::trace_span!(target: "indexing::scheduler", "commit");
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use bumpalo::collections::CollectIn;
@@ -10,7 +11,7 @@ use meilisearch_types::milli::update::DocumentAdditionResult;
 use meilisearch_types::milli::{self, ChannelCongestion, Filter};
 use meilisearch_types::network::Network;
 use meilisearch_types::settings::apply_settings_to_builder;
-use meilisearch_types::tasks::{Details, KindWithContent, Status, Task};
+use meilisearch_types::tasks::{Details, FacetFieldUpdate, KindWithContent, Status, Task};
 use meilisearch_types::Index;
 use roaring::RoaringBitmap;
 
@@ -155,6 +156,7 @@ impl IndexScheduler {
                             Some(Details::DocumentAdditionOrUpdate {
                                 received_documents,
                                 indexed_documents: Some(stats.document_count),
+                                facet_updates: BTreeMap::new(),
                             })
                         }
                         Some(Details::DocumentDeletion { provided_ids, .. }) => {
@@ -192,6 +194,19 @@ impl IndexScheduler {
                         .map_err(|e| Error::from_milli(e, Some(index_uid.clone())))?,
                     );
 
+                    // The tasks of the batch are indexed together, they all report the same facet updates.
+                    let facet_updates =
+                        FacetFieldUpdate::from_decisions(progress.facet_update_decisions());
+                    for task in tasks.iter_mut().filter(|task| task.error.is_none()) {
+                        if let Some(Details::DocumentAdditionOrUpdate {
+                            facet_updates: task_facet_updates,
+                            ..
+                        }) = &mut task.details
+                        {
+                            task_facet_updates.clone_from(&facet_updates);
+                        }
+                    }
+
                     let addition = DocumentAdditionResult {
                         indexed_documents: candidates_count,
                         number_of_documents: index
@@ -474,7 +489,10 @@ impl IndexScheduler {
 
                 for (task, (_, settings)) in tasks.iter_mut().zip(settings) {
                     let checked_settings = settings.clone().check();
-                    task.details = Some(Details::SettingsUpdate { settings: Box::new(settings) });
+                    task.details = Some(Details::SettingsUpdate {
+                        settings: Box::new(settings),
+                        warning: None,
+                    });
                     apply_settings_to_builder(&checked_settings, &mut builder);
 
                     // We can apply the status right now and if an update fail later
@@ -487,6 +505,27 @@ impl IndexScheduler {
                     .execute(&|| must_stop_processing.get(), progress, embedder_stats)
                     .map_err(|err| Error::from_milli(err, Some(index_uid.clone())))?;
 
+                // Too many faceted fields make the facet indexing very slow, but it is not an error.
+                if let Some(max_filterable_attributes) = indexer_config.max_filterable_attributes {
+                    let faceted_fields_count = index
+                        .faceted_fields_count(index_wtxn)
+                        .map_err(|err| Error::from_milli(err, Some(index_uid.clone())))?;
+                    if faceted_fields_count > max_filterable_attributes {
+                        let message = format!(
+                            "The filterable attributes match {faceted_fields_count} fields, which is more than the soft limit of {max_filterable_attributes} fields. Indexing the facets of these fields may be very slow."
+                        );
+                        for task in tasks.iter_mut() {
+                            if let Some(Details::SettingsUpdate { settings, warning }) =
+                                &mut task.details
+                            {
+                                if settings.filterable_attributes.as_ref().set().is_some() {
+                                    *warning = Some(message.clone());
+                                }
+                            }
+                        }
+                    }
+                }
+
                 Ok((tasks, congestion))
             }
             IndexOperation::DocumentClearAndSetting {
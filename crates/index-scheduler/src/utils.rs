@@ -5,7 +5,7 @@ use std::ops::Bound;
 use std::sync::Arc;
 
 use convert_case::{Case, Casing as _};
-use meilisearch_types::batches::{Batch, BatchEnqueuedAt, BatchId, BatchStats};
+use meilisearch_types::batches::{Batch, BatchEnqueuedAt, BatchId, BatchStats, WordPrefixChanges};
 use meilisearch_types::heed::{Database, RoTxn, RwTxn};
 use meilisearch_types::milli::progress::Progress;
 use meilisearch_types::milli::{CboRoaringBitmapCodec, ChannelCongestion};
@@ -163,6 +163,12 @@ impl ProcessingBatch {
             congestion_info.insert("blocking_ratio".into(), congestion.congestion_ratio().into());
             congestion_info
         });
+        self.stats.facet_indexing_peak_memory =
+            Some(progress.facet_indexing_memory_peak() as u64).filter(|&peak| peak != 0);
+        self.stats.facet_effective_group_size = progress.facet_effective_group_size();
+        self.stats.word_prefix_changes = progress
+            .word_prefix_changes()
+            .map(|(recomputed, deleted)| WordPrefixChanges { recomputed, deleted });
         self.stats.internal_database_sizes = pre_commit_dabases_sizes
             .iter()
             .flat_map(|(dbname, pre_size)| {
@@ -514,7 +520,11 @@ impl crate::IndexScheduler {
                             assert_eq!(&sw1, sw2);
                         }
                     }
-                    Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
+                    Details::DocumentAdditionOrUpdate {
+                        received_documents,
+                        indexed_documents,
+                        facet_updates: _,
+                    } => {
                         assert_eq!(kind.as_kind(), Kind::DocumentAdditionOrUpdate);
                         match indexed_documents {
                             Some(indexed_documents) => {
@@ -552,7 +562,7 @@ impl crate::IndexScheduler {
                             }
                         }
                     }
-                    Details::SettingsUpdate { settings: _ } => {
+                    Details::SettingsUpdate { .. } => {
                         assert_eq!(kind.as_kind(), Kind::SettingsUpdate);
                     }
                     Details::IndexInfo { primary_key: pk1, .. } => match &kind {
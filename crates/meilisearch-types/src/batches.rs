@@ -86,6 +86,26 @@ pub struct BatchStats {
     pub write_channel_congestion: Option<serde_json::Map<String, serde_json::Value>>,
     #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
     pub internal_database_sizes: serde_json::Map<String, serde_json::Value>,
+    /// The largest number of bytes buffered at once while building the facet levels and FSTs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facet_indexing_peak_memory: Option<u64>,
+    /// The largest group size the facet levels were built with after being raised to respect
+    /// `faceting.maxFacetLevels`, absent when no group size had to be raised.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facet_effective_group_size: Option<u8>,
+    /// The number of word prefixes recomputed and deleted, absent when no word prefix changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_prefix_changes: Option<WordPrefixChanges>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct WordPrefixChanges {
+    /// The number of word prefixes whose documents were recomputed.
+    pub recomputed: usize,
+    /// The number of word prefixes removed from the prefix databases.
+    pub deleted: usize,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
@@ -257,6 +257,7 @@ InvalidSearchHybridQuery                       , InvalidRequest       , BAD_REQU
 InvalidIndexLimit                              , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexOffset                             , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexPrimaryKey                         , InvalidRequest       , BAD_REQUEST ;
+InvalidIndexStatsFacetTrees                    , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexCustomMetadata                     , InvalidRequest       , BAD_REQUEST ;
 InvalidSkipCreation                            , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexUid                                , InvalidRequest       , BAD_REQUEST ;
@@ -292,10 +293,53 @@ InvalidSearchRetrieveVectors                   , InvalidRequest       , BAD_REQU
 InvalidSearchCropLength                        , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropMarker                        , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFacets                            , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchMinCount                          , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetLevel                        , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetSampleSize                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetSampleSeed                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetRanges                       , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchGeoFacet                          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchSemanticRatio                     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchLocales                           , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetDistributionBooleanFacets          , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetDistributionFacetBitmaps           , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetDistributionFacets                 , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetDistributionFacetValues            , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetDistributionGranularityLevel       , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetDistributionIncludeZeroCounts      , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetDistributionNumericPrecision       , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchAccentSensitive              , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchAfter                        , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchAttachFields                 , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchBoostDocumentIds             , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchBrowseMode                   , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchCollation                    , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchDisjunctiveOnSelf            , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchExact                        , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchExhaustiveFacetCount         , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchFacetName                    , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchFacetQueries                 , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchFilter                       , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchGroupByFirstChar             , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchIncludeMatchType             , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchIncludeMissing               , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchIncludeNormalized            , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchIncludeSortKeys              , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchIncludeStats                 , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchIncludeZeroCounts            , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchLimit                        , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchMatchTokens                  , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchMaxResponseBytes             , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchMaxTypos                     , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchMinCount                     , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchMinSimilarity                , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchPrefixLastToken              , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchRankByRelevance              , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchRepresentativeValue          , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchSnapshot                     , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchSnapshotToken                , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchSortBy                       , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchSuggestCorrections           , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarId                               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFilter                            , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarFilter                           , InvalidRequest       , BAD_REQUEST ;
@@ -312,6 +356,9 @@ InvalidSearchQ                                 , InvalidRequest       , BAD_REQU
 InvalidFacetSearchQuery                        , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchName                         , InvalidRequest       , BAD_REQUEST ;
 FacetSearchDisabled                            , InvalidRequest       , BAD_REQUEST ;
+FacetSearchFacetNotFilterable                  , InvalidRequest       , BAD_REQUEST ;
+FacetSearchNumericFacet                        , InvalidRequest       , BAD_REQUEST ;
+FacetSearchQueryTooLong                        , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchVector                            , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchMedia                             , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowMatchesPosition               , InvalidRequest       , BAD_REQUEST ;
@@ -373,6 +420,7 @@ MissingApiKeyIndexes                           , InvalidRequest       , BAD_REQU
 MissingAuthorizationHeader                     , Auth                 , UNAUTHORIZED ;
 MissingContentType                             , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
 MissingDocumentId                              , InvalidRequest       , BAD_REQUEST ;
+MissingFacetDistributionFacets                 , InvalidRequest       , BAD_REQUEST ;
 MissingFacetSearchFacetName                    , InvalidRequest       , BAD_REQUEST ;
 MissingIndexUid                                , InvalidRequest       , BAD_REQUEST ;
 MissingMasterKey                               , Auth                 , UNAUTHORIZED ;
@@ -392,6 +440,7 @@ RemoteInvalidApiKey                            , Auth                 , FORBIDDE
 RemoteRemoteError                              , System               , BAD_GATEWAY ;
 RemoteTimeout                                  , System               , BAD_GATEWAY ;
 TooManySearchRequests                          , System               , SERVICE_UNAVAILABLE ;
+TooManyFacetSearchRequests                     , InvalidRequest       , TOO_MANY_REQUESTS ;
 TaskNotFound                                   , InvalidRequest       , NOT_FOUND ;
 TaskFileNotFound                               , InvalidRequest       , NOT_FOUND ;
 BatchNotFound                                  , InvalidRequest       , NOT_FOUND ;
@@ -476,7 +525,7 @@ impl ErrorCode for milli::Error {
                     | UserError::UnknownInternalDocumentId { .. } => Code::Internal,
                     UserError::InvalidStoreFile => Code::InvalidStoreFile,
                     UserError::NoSpaceLeftOnDevice => Code::NoSpaceLeftOnDevice,
-                    UserError::MaxDatabaseSizeReached => Code::DatabaseSizeLimitReached,
+                    UserError::MaxDatabaseSizeReached { .. } => Code::DatabaseSizeLimitReached,
                     UserError::AttributeLimitReached => Code::MaxFieldsLimitExceeded,
                     UserError::InvalidFilter(_) => Code::InvalidSearchFilter,
                     UserError::InvalidFilterExpression(..) => Code::InvalidSearchFilter,
@@ -485,7 +534,9 @@ impl ErrorCode for milli::Error {
                     UserError::InvalidDocumentId { .. } | UserError::TooManyDocumentIds { .. } => {
                         Code::InvalidDocumentId
                     }
-                    UserError::MissingDocumentField(_) => Code::InvalidDocumentFields,
+                    UserError::MissingDocumentField(_) | UserError::FacetValueTooLong { .. } => {
+                        Code::InvalidDocumentFields
+                    }
                     UserError::InvalidFieldForSource { .. }
                     | UserError::MissingFieldForSource { .. }
                     | UserError::InvalidOpenAiModel { .. }
@@ -510,15 +561,30 @@ impl ErrorCode for milli::Error {
                     UserError::PrimaryKeyCannotBeChanged(_) => Code::IndexPrimaryKeyAlreadyExists,
                     UserError::InvalidDistinctAttribute { .. } => Code::InvalidSearchDistinct,
                     UserError::SortRankingRuleMissing => Code::InvalidSearchSort,
-                    UserError::InvalidFacetsDistribution { .. } => Code::InvalidSearchFacets,
+                    UserError::InvalidFacetsDistribution { .. }
+                    | UserError::UnsupportedCompactFacetsOrder { .. } => Code::InvalidSearchFacets,
+                    UserError::InvalidFacetRangeBoundaries { .. } => Code::InvalidSearchFacetRanges,
+                    UserError::InvalidGeoFacetNotFilterable
+                    | UserError::InvalidGeoFacetCenter { .. }
+                    | UserError::InvalidGeoFacetBoundaries => Code::InvalidSearchGeoFacet,
                     UserError::InvalidSearchSortableAttribute { .. } => Code::InvalidSearchSort,
                     UserError::InvalidDocumentSortableAttribute { .. } => Code::InvalidDocumentSort,
                     UserError::InvalidSearchableAttribute { .. } => {
                         Code::InvalidSearchAttributesToSearchOn
                     }
-                    UserError::InvalidFacetSearchFacetName { .. } => {
-                        Code::InvalidFacetSearchFacetName
+                    // the field matches a filterable attributes rule that does not enable the facet search
+                    UserError::InvalidFacetSearchFacetName {
+                        matching_rule_index: Some(_), ..
+                    } => Code::FacetSearchDisabled,
+                    UserError::InvalidFacetSearchFacetName {
+                        matching_rule_index: None, ..
+                    } => Code::FacetSearchFacetNotFilterable,
+                    UserError::InvalidFacetSearchNumericFacet { .. } => {
+                        Code::FacetSearchNumericFacet
                     }
+                    UserError::FacetSearchQueryTooLong { .. } => Code::FacetSearchQueryTooLong,
+                    UserError::InvalidFacetSearchCursor(_)
+                    | UserError::InvalidFacetSearchCursorOrder => Code::InvalidFacetSearchAfter,
                     UserError::CriterionError(_) => Code::InvalidSettingsRankingRules,
                     UserError::InvalidGeoField { .. } | UserError::GeoJsonError(_) => {
                         Code::InvalidDocumentGeoField
@@ -684,6 +750,21 @@ impl fmt::Display for deserr_codes::InvalidSimilarRankingScoreThreshold {
     }
 }
 
+impl fmt::Display for deserr_codes::InvalidFacetSearchMaxTypos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the value of `maxTypos` is invalid, expected an integer between `0` and `2`.")
+    }
+}
+
+impl fmt::Display for deserr_codes::InvalidFacetSearchMinSimilarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `minSimilarity` is invalid, expected a float between `0.0` and `1.0`."
+        )
+    }
+}
+
 impl fmt::Display for deserr_codes::InvalidNetworkUrl {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "the value of `url` is invalid, expected a string.")
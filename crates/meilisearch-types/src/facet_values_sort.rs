@@ -1,11 +1,12 @@
-use deserr::Deserr;
-use milli::OrderBy;
+use std::convert::Infallible;
+
+use deserr::{DeserializeError, Deserr, ValuePointerRef};
+use milli::{FacetValuesWeights, OrderBy};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
-#[deserr(rename_all = camelCase)]
 pub enum FacetValuesSort {
     /// Facet values are sorted in alphabetical order, ascending from A to Z.
     #[default]
@@ -13,6 +14,94 @@ pub enum FacetValuesSort {
     /// Facet values are sorted by decreasing count.
     /// The count is the number of records containing this facet value in the results of the query.
     Count,
+    /// Facet values are sorted from the most recently associated with a document to the least
+    /// recently. Requires the index to track the recency of its facet values.
+    Recency,
+    /// Facet values are sorted by decreasing sum of the ranking scores of the documents of the search
+    /// results containing them, then by decreasing count. Sorted by count outside of a search.
+    ScoreSum,
+    /// Facet values are sorted by decreasing weight, read from the weight field configured for the
+    /// faceted field. The values without a weight come last, in alphabetical order.
+    Weight,
+    /// Facet values are sorted by a weighted combination of their count and alphabetical rank,
+    /// e.g. `{ "count": 0.7, "alpha": 0.3 }`.
+    #[serde(untagged)]
+    Weighted(FacetValuesSortWeights),
+}
+
+/// The weights of the count and of the alphabetical rank of the facet values.
+///
+/// Both signals are normalized between 0 and 1, the weights must be between 0 and 1 and sum up to 1.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(deny_unknown_fields, rename_all = camelCase)]
+pub struct FacetValuesSortWeights {
+    #[deserr(default)]
+    #[serde(default)]
+    pub count: f64,
+    #[deserr(default)]
+    #[serde(default)]
+    pub alpha: f64,
+}
+
+// The weights are validated to be finite numbers.
+impl Eq for FacetValuesSortWeights {}
+
+impl FacetValuesSortWeights {
+    /// The tolerance on the sum of the weights, to accept the rounding of decimal numbers.
+    const SUM_TOLERANCE: f64 = 1e-3;
+
+    fn validate(&self) -> Result<(), String> {
+        let FacetValuesSortWeights { count, alpha } = *self;
+        for (name, weight) in [("count", count), ("alpha", alpha)] {
+            if !(0.0..=1.0).contains(&weight) {
+                return Err(format!("the `{name}` weight must be between 0 and 1, got {weight}"));
+            }
+        }
+        if (count + alpha - 1.0).abs() > Self::SUM_TOLERANCE {
+            return Err(format!("the weights must sum up to 1, got {}", count + alpha));
+        }
+        Ok(())
+    }
+}
+
+/// The sorts that can be written as a string.
+#[derive(Deserr)]
+#[deserr(rename_all = camelCase)]
+enum FacetValuesSortName {
+    Alpha,
+    Count,
+    Recency,
+    ScoreSum,
+    Weight,
+}
+
+impl<E: DeserializeError> Deserr<E> for FacetValuesSort {
+    fn deserialize_from_value<V: deserr::IntoValue>(
+        value: deserr::Value<V>,
+        location: ValuePointerRef<'_>,
+    ) -> Result<Self, E> {
+        match value {
+            deserr::Value::Map(_) => {
+                let weights = FacetValuesSortWeights::deserialize_from_value(value, location)?;
+                match weights.validate() {
+                    Ok(()) => Ok(FacetValuesSort::Weighted(weights)),
+                    Err(msg) => Err(deserr::take_cf_content(E::error::<Infallible>(
+                        None,
+                        deserr::ErrorKind::Unexpected { msg },
+                        location,
+                    ))),
+                }
+            }
+            value => match FacetValuesSortName::deserialize_from_value(value, location)? {
+                FacetValuesSortName::Alpha => Ok(FacetValuesSort::Alpha),
+                FacetValuesSortName::Count => Ok(FacetValuesSort::Count),
+                FacetValuesSortName::Recency => Ok(FacetValuesSort::Recency),
+                FacetValuesSortName::ScoreSum => Ok(FacetValuesSort::ScoreSum),
+                FacetValuesSortName::Weight => Ok(FacetValuesSort::Weight),
+            },
+        }
+    }
 }
 
 impl From<FacetValuesSort> for OrderBy {
@@ -20,6 +109,12 @@ impl From<FacetValuesSort> for OrderBy {
         match val {
             FacetValuesSort::Alpha => OrderBy::Lexicographic,
             FacetValuesSort::Count => OrderBy::Count,
+            FacetValuesSort::Recency => OrderBy::Recency,
+            FacetValuesSort::ScoreSum => OrderBy::ScoreSum,
+            FacetValuesSort::Weight => OrderBy::Weight,
+            FacetValuesSort::Weighted(FacetValuesSortWeights { count, alpha }) => {
+                OrderBy::Weighted(FacetValuesWeights { count, alpha })
+            }
         }
     }
 }
@@ -29,6 +124,12 @@ impl From<OrderBy> for FacetValuesSort {
         match val {
             OrderBy::Lexicographic => FacetValuesSort::Alpha,
             OrderBy::Count => FacetValuesSort::Count,
+            OrderBy::Recency => FacetValuesSort::Recency,
+            OrderBy::ScoreSum => FacetValuesSort::ScoreSum,
+            OrderBy::Weight => FacetValuesSort::Weight,
+            OrderBy::Weighted(FacetValuesWeights { count, alpha }) => {
+                FacetValuesSort::Weighted(FacetValuesSortWeights { count, alpha })
+            }
         }
     }
 }
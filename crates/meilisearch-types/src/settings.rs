@@ -2,20 +2,23 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::convert::Infallible;
 use std::fmt;
 use std::marker::PhantomData;
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::ops::{ControlFlow, Deref};
 use std::str::FromStr;
 
 use deserr::{DeserializeError, Deserr, ErrorKind, MergeWithError, ValuePointerRef};
 use fst::IntoStreamer;
 use milli::disabled_typos_terms::DisabledTyposTerms;
-use milli::index::PrefixSearch;
+use milli::index::{FacetUpdateMethodSetting, FacetingMode, FacetingWarmup, PrefixSearch};
 use milli::proximity::ProximityPrecision;
 pub use milli::update::ChatSettings;
 use milli::update::Setting;
 use milli::vector::db::IndexEmbeddingConfig;
 use milli::vector::VectorStoreBackend;
-use milli::{Criterion, CriterionError, FilterableAttributesRule, Index, DEFAULT_VALUES_PER_FACET};
+use milli::{
+    Criterion, CriterionError, FilterableAttributesRule, Index, PinnedFacetDistribution,
+    DEFAULT_VALUES_PER_FACET,
+};
 use serde::{Deserialize, Serialize, Serializer};
 use utoipa::ToSchema;
 
@@ -122,10 +125,66 @@ pub struct FacetingSettings {
     #[deserr(default)]
     #[schema(value_type = Option<usize>, example = json!(10))]
     pub max_values_per_facet: Setting<usize>,
+    /// Maximum number of distinct values a single document can contribute to a faceted field.
+    /// The following values of the field are not indexed. Unlimited by default.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<usize>, example = json!(100))]
+    pub max_values_per_document_field: Setting<NonZeroUsize>,
+    /// Records the time the facet values are associated with documents, to sort them by
+    /// `recency`. The values associated before it is enabled have no recorded time.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<bool>, example = json!(true))]
+    pub track_values_recency: Setting<bool>,
+    /// Maximum number of levels built above the facet values to speed up filters and the facet
+    /// distribution. Fields with too many values use larger nodes to respect it. Unlimited by default.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<usize>, example = json!(4))]
+    pub max_facet_levels: Setting<NonZeroUsize>,
+    /// Expected number of distinct values of high-cardinality faceted fields. The facet levels of
+    /// these fields are built with larger nodes from the start, resulting in shallower trees.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<BTreeMap<String, u64>>, example = json!({ "sku": 1000000 }))]
+    pub expected_cardinality: Setting<BTreeMap<String, NonZeroU64>>,
+    /// Method used to update the facet levels of the fields, by field name. The fields without
+    /// one use the `auto` method, choosing between `bulk` and `incremental` from the update size.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<BTreeMap<String, FacetUpdateMethodSettings>>, example = json!({ "stock": "incremental" }))]
+    pub update_method: Setting<BTreeMap<String, FacetUpdateMethodSettings>>,
+    /// Field holding the sort weight of the values of the faceted fields, by faceted field name.
+    /// The weight of a value is the highest top-level number of this field in its documents.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<BTreeMap<String, String>>, example = json!({ "brand": "brandWeight" }))]
+    pub value_weight_fields: Setting<BTreeMap<String, String>>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
     #[schema(value_type = Option<BTreeMap<String, FacetValuesSort>>, example = json!({ "genre": FacetValuesSort::Count }))]
     pub sort_facet_values_by: Setting<BTreeMap<String, FacetValuesSort>>,
+    /// When the facet levels and the facet search structures are built: at indexing time with
+    /// `eager`, the default, or with `lazy` only the facet search structures, when first
+    /// needed by a search, for faster indexing and slower facet filters and distributions.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<FacetingModeSettings>, example = json!("lazy"))]
+    pub mode: Setting<FacetingModeSettings>,
+    /// Facet structures read when the index is opened, for the first searches not to wait for
+    /// them to be read from the disk. `null`, the default, reads nothing.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<FacetingWarmupSettings>, example = json!({ "maxBytes": 67108864, "topLevels": true }))]
+    pub warmup: Setting<FacetingWarmupSettings>,
+    /// Filter and facets combinations whose distribution is computed after each batch. The
+    /// placeholder searches requesting exactly these facets with this filter are served from it.
+    /// None by default.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<Vec<PinnedFacetDistributionSettings>>, example = json!([{ "filter": "category = shoes", "facets": ["brand", "size"] }]))]
+    pub pinned_distributions: Setting<Vec<PinnedFacetDistributionSettings>>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr, ToSchema)]
@@ -775,12 +834,63 @@ pub fn apply_settings_to_builder(
     }
 
     match faceting {
-        Setting::Set(FacetingSettings { max_values_per_facet, sort_facet_values_by }) => {
+        Setting::Set(FacetingSettings {
+            max_values_per_facet,
+            max_values_per_document_field,
+            track_values_recency,
+            max_facet_levels,
+            expected_cardinality,
+            update_method,
+            value_weight_fields,
+            sort_facet_values_by,
+            mode,
+            warmup,
+            pinned_distributions,
+        }) => {
             match max_values_per_facet {
                 Setting::Set(val) => builder.set_max_values_per_facet(*val),
                 Setting::Reset => builder.reset_max_values_per_facet(),
                 Setting::NotSet => (),
             }
+            match max_values_per_document_field {
+                Setting::Set(val) => builder.set_max_values_per_document_field(val.get()),
+                Setting::Reset => builder.reset_max_values_per_document_field(),
+                Setting::NotSet => (),
+            }
+            match track_values_recency {
+                Setting::Set(val) => builder.set_track_facet_values_recency(*val),
+                Setting::Reset => builder.reset_track_facet_values_recency(),
+                Setting::NotSet => (),
+            }
+            match max_facet_levels {
+                // there are never more than 32 levels, larger values don't cap anything
+                Setting::Set(val) => {
+                    builder.set_max_facet_levels(u8::try_from(val.get()).unwrap_or(u8::MAX))
+                }
+                Setting::Reset => builder.reset_max_facet_levels(),
+                Setting::NotSet => (),
+            }
+            match expected_cardinality {
+                Setting::Set(val) => builder.set_facet_expected_cardinality(
+                    val.iter()
+                        .map(|(name, cardinality)| (name.clone(), cardinality.get()))
+                        .collect(),
+                ),
+                Setting::Reset => builder.reset_facet_expected_cardinality(),
+                Setting::NotSet => (),
+            }
+            match update_method {
+                Setting::Set(val) => builder.set_facet_update_methods(
+                    val.iter().map(|(name, method)| (name.clone(), (*method).into())).collect(),
+                ),
+                Setting::Reset => builder.reset_facet_update_methods(),
+                Setting::NotSet => (),
+            }
+            match value_weight_fields {
+                Setting::Set(val) => builder.set_facet_value_weight_fields(val.clone()),
+                Setting::Reset => builder.reset_facet_value_weight_fields(),
+                Setting::NotSet => (),
+            }
             match sort_facet_values_by {
                 Setting::Set(val) => builder.set_sort_facet_values_by(
                     val.iter().map(|(name, order)| (name.clone(), (*order).into())).collect(),
@@ -788,10 +898,35 @@ pub fn apply_settings_to_builder(
                 Setting::Reset => builder.reset_sort_facet_values_by(),
                 Setting::NotSet => (),
             }
+            match mode {
+                Setting::Set(val) => builder.set_faceting_mode((*val).into()),
+                Setting::Reset => builder.reset_faceting_mode(),
+                Setting::NotSet => (),
+            }
+            match warmup {
+                Setting::Set(val) => builder.set_faceting_warmup((*val).into()),
+                Setting::Reset => builder.reset_faceting_warmup(),
+                Setting::NotSet => (),
+            }
+            match pinned_distributions {
+                Setting::Set(val) => builder
+                    .set_pinned_facet_distributions(val.iter().cloned().map(Into::into).collect()),
+                Setting::Reset => builder.reset_pinned_facet_distributions(),
+                Setting::NotSet => (),
+            }
         }
         Setting::Reset => {
             builder.reset_max_values_per_facet();
+            builder.reset_max_values_per_document_field();
+            builder.reset_track_facet_values_recency();
+            builder.reset_max_facet_levels();
+            builder.reset_facet_expected_cardinality();
+            builder.reset_facet_update_methods();
+            builder.reset_facet_value_weight_fields();
             builder.reset_sort_facet_values_by();
+            builder.reset_faceting_mode();
+            builder.reset_faceting_warmup();
+            builder.reset_pinned_facet_distributions();
         }
         Setting::NotSet => (),
     }
@@ -916,6 +1051,36 @@ pub fn settings(
                 .map(|x| x as usize)
                 .unwrap_or(DEFAULT_VALUES_PER_FACET),
         ),
+        max_values_per_document_field: match index
+            .max_values_per_document_field(rtxn)?
+            .and_then(|max| NonZeroUsize::new(max as usize))
+        {
+            Some(max) => Setting::Set(max),
+            None => Setting::Reset,
+        },
+        track_values_recency: Setting::Set(index.track_facet_values_recency(rtxn)?),
+        max_facet_levels: match index
+            .max_facet_levels(rtxn)?
+            .and_then(|max| NonZeroUsize::new(max as usize))
+        {
+            Some(max) => Setting::Set(max),
+            None => Setting::Reset,
+        },
+        expected_cardinality: Setting::Set(
+            index
+                .facet_expected_cardinality(rtxn)?
+                .into_iter()
+                .filter_map(|(name, cardinality)| Some((name, NonZeroU64::new(cardinality)?)))
+                .collect(),
+        ),
+        update_method: Setting::Set(
+            index
+                .facet_update_methods(rtxn)?
+                .into_iter()
+                .map(|(name, method)| (name, method.into()))
+                .collect(),
+        ),
+        value_weight_fields: Setting::Set(index.facet_value_weight_fields(rtxn)?),
         sort_facet_values_by: Setting::Set(
             index
                 .sort_facet_values_by(rtxn)?
@@ -923,6 +1088,14 @@ pub fn settings(
                 .map(|(name, sort)| (name, sort.into()))
                 .collect(),
         ),
+        mode: Setting::Set(index.faceting_mode(rtxn)?.into()),
+        warmup: match index.faceting_warmup(rtxn)? {
+            Some(warmup) => Setting::Set(warmup.into()),
+            None => Setting::Reset,
+        },
+        pinned_distributions: Setting::Set(
+            index.pinned_facet_distributions(rtxn)?.into_iter().map(Into::into).collect(),
+        ),
     };
 
     let pagination = PaginationSettings {
@@ -1176,6 +1349,9 @@ pub enum PrefixSearchSettings {
     #[default]
     IndexingTime,
     Disabled,
+    /// Only the prefixes of the recent queries are computed at indexing time,
+    /// the other prefixes are computed at search time.
+    HotPrefixes,
 }
 
 impl From<PrefixSearch> for PrefixSearchSettings {
@@ -1183,6 +1359,7 @@ impl From<PrefixSearch> for PrefixSearchSettings {
         match value {
             PrefixSearch::IndexingTime => PrefixSearchSettings::IndexingTime,
             PrefixSearch::Disabled => PrefixSearchSettings::Disabled,
+            PrefixSearch::HotPrefixes => PrefixSearchSettings::HotPrefixes,
         }
     }
 }
@@ -1191,9 +1368,129 @@ impl From<PrefixSearchSettings> for PrefixSearch {
         match value {
             PrefixSearchSettings::IndexingTime => PrefixSearch::IndexingTime,
             PrefixSearchSettings::Disabled => PrefixSearch::Disabled,
+            PrefixSearchSettings::HotPrefixes => PrefixSearch::HotPrefixes,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsFaceting>, rename_all = camelCase, deny_unknown_fields)]
+pub enum FacetingModeSettings {
+    /// Facet levels and facet search structures are built at indexing time.
+    #[default]
+    Eager,
+    /// Only the first facet level is maintained at indexing time,
+    /// the facet search structures are built when first needed. The filters, facet
+    /// distributions and sorts read every value of the modified fields until the mode
+    /// is switched back to `eager`.
+    Lazy,
+}
+
+impl From<FacetingMode> for FacetingModeSettings {
+    fn from(value: FacetingMode) -> Self {
+        match value {
+            FacetingMode::Eager => FacetingModeSettings::Eager,
+            FacetingMode::Lazy => FacetingModeSettings::Lazy,
+        }
+    }
+}
+impl From<FacetingModeSettings> for FacetingMode {
+    fn from(value: FacetingModeSettings) -> Self {
+        match value {
+            FacetingModeSettings::Eager => FacetingMode::Eager,
+            FacetingModeSettings::Lazy => FacetingMode::Lazy,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsFaceting>, rename_all = camelCase, deny_unknown_fields)]
+pub enum FacetUpdateMethodSettings {
+    /// The bulk or incremental method is chosen depending on the size of the update.
+    #[default]
+    Auto,
+    /// The facet levels are always rebuilt from scratch.
+    Bulk,
+    /// The facet levels are always updated in place.
+    Incremental,
+}
+
+impl From<FacetUpdateMethodSetting> for FacetUpdateMethodSettings {
+    fn from(value: FacetUpdateMethodSetting) -> Self {
+        match value {
+            FacetUpdateMethodSetting::Auto => FacetUpdateMethodSettings::Auto,
+            FacetUpdateMethodSetting::Bulk => FacetUpdateMethodSettings::Bulk,
+            FacetUpdateMethodSetting::Incremental => FacetUpdateMethodSettings::Incremental,
         }
     }
 }
+impl From<FacetUpdateMethodSettings> for FacetUpdateMethodSetting {
+    fn from(value: FacetUpdateMethodSettings) -> Self {
+        match value {
+            FacetUpdateMethodSettings::Auto => FacetUpdateMethodSetting::Auto,
+            FacetUpdateMethodSettings::Bulk => FacetUpdateMethodSetting::Bulk,
+            FacetUpdateMethodSettings::Incremental => FacetUpdateMethodSetting::Incremental,
+        }
+    }
+}
+
+/// The facet structures read when an index is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsFaceting>, rename_all = camelCase, deny_unknown_fields)]
+pub struct FacetingWarmupSettings {
+    /// The maximum number of bytes of facet structures read when the index is opened.
+    pub max_bytes: u64,
+    /// Whether to also read the highest levels of the facet trees.
+    #[serde(default)]
+    #[deserr(default)]
+    pub top_levels: bool,
+}
+
+impl From<FacetingWarmup> for FacetingWarmupSettings {
+    fn from(value: FacetingWarmup) -> Self {
+        let FacetingWarmup { max_bytes, top_levels } = value;
+        FacetingWarmupSettings { max_bytes, top_levels }
+    }
+}
+impl From<FacetingWarmupSettings> for FacetingWarmup {
+    fn from(value: FacetingWarmupSettings) -> Self {
+        let FacetingWarmupSettings { max_bytes, top_levels } = value;
+        FacetingWarmup { max_bytes, top_levels }
+    }
+}
+
+/// A filter and facets combination whose facet distribution is computed after each batch.
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsFaceting>, rename_all = camelCase, deny_unknown_fields)]
+pub struct PinnedFacetDistributionSettings {
+    /// The filter of the searches, empty to match the searches without a filter.
+    #[serde(default)]
+    #[deserr(default)]
+    pub filter: String,
+    /// The facets requested by the searches, in any order.
+    pub facets: Vec<String>,
+}
+
+impl From<PinnedFacetDistribution> for PinnedFacetDistributionSettings {
+    fn from(value: PinnedFacetDistribution) -> Self {
+        let PinnedFacetDistribution { filter, facets } = value;
+        PinnedFacetDistributionSettings { filter, facets }
+    }
+}
+impl From<PinnedFacetDistributionSettings> for PinnedFacetDistribution {
+    fn from(value: PinnedFacetDistributionSettings) -> Self {
+        let PinnedFacetDistributionSettings { filter, facets } = value;
+        PinnedFacetDistribution { filter, facets }
+    }
+}
 
 #[cfg(test)]
 pub(crate) mod test {
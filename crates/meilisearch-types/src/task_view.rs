@@ -11,7 +11,8 @@ use crate::error::ResponseError;
 use crate::settings::{Settings, Unchecked};
 use crate::tasks::network::DbTaskNetwork;
 use crate::tasks::{
-    serialize_duration, Details, DetailsExportIndexSettings, IndexSwap, Kind, Status, Task, TaskId,
+    serialize_duration, Details, DetailsExportIndexSettings, FacetFieldUpdate, IndexSwap, Kind,
+    Status, Task, TaskId,
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -156,6 +157,13 @@ pub struct DetailsView {
     pub moved_documents: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// Non-blocking issue detected while processing a settingsUpdate task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+    /// How the facet levels of the fields modified by a documentAdditionOrUpdate task were
+    /// updated, by field name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_updates: Option<BTreeMap<String, FacetFieldUpdate>>,
 }
 
 impl DetailsView {
@@ -177,6 +185,18 @@ impl DetailsView {
                 (Some(message), None) => Some(std::mem::take(message)),
                 (Some(message), Some(_)) => Some(std::mem::take(message)),
             },
+            warning: match (&self.warning, &other.warning) {
+                (None, None) => None,
+                (None, Some(warning)) | (Some(warning), None) => Some(warning.clone()),
+                // The warnings of a batch of settings updates are all the same.
+                (Some(left), Some(_right)) => Some(left.clone()),
+            },
+            facet_updates: match (&self.facet_updates, &other.facet_updates) {
+                (None, None) => None,
+                (None, Some(updates)) | (Some(updates), None) => Some(updates.clone()),
+                // The tasks of a batch are indexed together and share the same facet updates.
+                (Some(left), Some(_right)) => Some(left.clone()),
+            },
             indexed_documents: match (self.indexed_documents, other.indexed_documents) {
                 (None, None) => None,
                 (None, Some(None)) | (Some(None), None) | (Some(None), Some(None)) => Some(None),
@@ -364,13 +384,16 @@ impl DetailsView {
 impl From<Details> for DetailsView {
     fn from(details: Details) -> Self {
         match details {
-            Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
-                DetailsView {
-                    received_documents: Some(received_documents),
-                    indexed_documents: Some(indexed_documents),
-                    ..DetailsView::default()
-                }
-            }
+            Details::DocumentAdditionOrUpdate {
+                received_documents,
+                indexed_documents,
+                facet_updates,
+            } => DetailsView {
+                received_documents: Some(received_documents),
+                indexed_documents: Some(indexed_documents),
+                facet_updates: (!facet_updates.is_empty()).then_some(facet_updates),
+                ..DetailsView::default()
+            },
             Details::DocumentEdition {
                 deleted_documents,
                 edited_documents,
@@ -385,9 +408,9 @@ impl From<Details> for DetailsView {
                 function: Some(function),
                 ..DetailsView::default()
             },
-            Details::SettingsUpdate { mut settings } => {
+            Details::SettingsUpdate { mut settings, warning } => {
                 settings.hide_secrets();
-                DetailsView { settings: Some(settings), ..DetailsView::default() }
+                DetailsView { settings: Some(settings), warning, ..DetailsView::default() }
             }
             Details::IndexInfo { primary_key, new_index_uid, old_index_uid } => DetailsView {
                 primary_key: Some(primary_key),
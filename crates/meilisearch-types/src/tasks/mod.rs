@@ -5,7 +5,10 @@ use std::str::FromStr;
 
 use byte_unit::Byte;
 use enum_iterator::Sequence;
-use milli::update::{IndexDocumentsMethod, MissingDocumentPolicy};
+use milli::facet::FacetType;
+use milli::update::{
+    FacetUpdateDecision, FacetUpdateMethod, IndexDocumentsMethod, MissingDocumentPolicy,
+};
 use milli::Object;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize, Serializer};
@@ -272,6 +275,7 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    facet_updates: BTreeMap::new(),
                 })
             }
             KindWithContent::DocumentEdition { index_uid: _, filter_expr, context, function } => {
@@ -299,7 +303,7 @@ impl KindWithContent {
                 Some(Details::ClearAll { deleted_documents: None })
             }
             KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
+                Some(Details::SettingsUpdate { settings: new_settings.clone(), warning: None })
             }
             KindWithContent::IndexCreation { primary_key, .. } => Some(Details::IndexInfo {
                 primary_key: primary_key.clone(),
@@ -362,6 +366,7 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: Some(0),
+                    facet_updates: BTreeMap::new(),
                 })
             }
             KindWithContent::DocumentEdition { index_uid: _, filter_expr, context, function } => {
@@ -389,7 +394,7 @@ impl KindWithContent {
                 Some(Details::ClearAll { deleted_documents: None })
             }
             KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
+                Some(Details::SettingsUpdate { settings: new_settings.clone(), warning: None })
             }
             KindWithContent::IndexDeletion { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. } => Some(Details::IndexInfo {
@@ -454,6 +459,7 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    facet_updates: BTreeMap::new(),
                 })
             }
             KindWithContent::DocumentEdition { .. } => None,
@@ -461,7 +467,7 @@ impl From<&KindWithContent> for Option<Details> {
             KindWithContent::DocumentDeletionByFilter { .. } => None,
             KindWithContent::DocumentClear { .. } => None,
             KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
+                Some(Details::SettingsUpdate { settings: new_settings.clone(), warning: None })
             }
             KindWithContent::IndexDeletion { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. } => Some(Details::IndexInfo {
@@ -739,9 +745,15 @@ pub enum Details {
     DocumentAdditionOrUpdate {
         received_documents: u64,
         indexed_documents: Option<u64>,
+        /// How the facet levels of the fields modified by the task were updated, by field name.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        facet_updates: BTreeMap<String, FacetFieldUpdate>,
     },
     SettingsUpdate {
         settings: Box<Settings<Unchecked>>,
+        /// Non-blocking issue detected while applying the settings.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        warning: Option<String>,
     },
     IndexInfo {
         primary_key: Option<String>,
@@ -818,12 +830,70 @@ impl From<ExportIndexSettings> for DetailsExportIndexSettings {
     }
 }
 
+/// How the string and number facet levels of a field were updated.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct FacetFieldUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strings: Option<FacetUpdate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numbers: Option<FacetUpdate>,
+}
+
+impl FacetFieldUpdate {
+    /// Groups the decisions recorded while indexing by field name.
+    pub fn from_decisions(
+        decisions: impl IntoIterator<Item = (String, FacetType, FacetUpdateDecision)>,
+    ) -> BTreeMap<String, Self> {
+        let mut updates = BTreeMap::<String, Self>::new();
+        for (field_name, facet_type, decision) in decisions {
+            let update = updates.entry(field_name).or_default();
+            match facet_type {
+                FacetType::String => update.strings = Some(decision.into()),
+                FacetType::Number => update.numbers = Some(decision.into()),
+            }
+        }
+        updates
+    }
+}
+
+/// The method used to update the facet levels and the number of modified facet values
+/// that drove the decision.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct FacetUpdate {
+    pub method: FacetUpdateMethodView,
+    pub delta_size: u64,
+}
+
+impl From<FacetUpdateDecision> for FacetUpdate {
+    fn from(FacetUpdateDecision { method, delta_size }: FacetUpdateDecision) -> Self {
+        let method = match method {
+            FacetUpdateMethod::Bulk => FacetUpdateMethodView::Bulk,
+            FacetUpdateMethod::Incremental => FacetUpdateMethodView::Incremental,
+        };
+        FacetUpdate { method, delta_size }
+    }
+}
+
+/// The facet levels are rebuilt with the `bulk` method when many facet values are modified,
+/// and updated in place with the `incremental` method otherwise.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FacetUpdateMethodView {
+    Bulk,
+    Incremental,
+}
+
 impl Details {
     pub fn to_failed(&self) -> Self {
         let mut details = self.clone();
         match &mut details {
-            Self::DocumentAdditionOrUpdate { indexed_documents, .. } => {
-                *indexed_documents = Some(0)
+            Self::DocumentAdditionOrUpdate { indexed_documents, facet_updates, .. } => {
+                *indexed_documents = Some(0);
+                facet_updates.clear();
             }
             Self::DocumentEdition { edited_documents, .. } => *edited_documents = Some(0),
             Self::DocumentDeletion { deleted_documents, .. } => *deleted_documents = Some(0),
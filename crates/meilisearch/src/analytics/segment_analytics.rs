@@ -1,6 +1,7 @@
 use std::any::TypeId;
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::num::NonZeroU32;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -208,7 +209,10 @@ struct Infos {
     experimental_no_edition_2024_for_prefix_post_processing: bool,
     experimental_no_edition_2024_for_facet_post_processing: bool,
     experimental_vector_store_setting: bool,
+    experimental_facet_bitmaps: bool,
     experimental_personalization: bool,
+    experimental_facet_search_rate_limit: Option<u32>,
+    experimental_facet_search_rate_limit_per_index: usize,
     gpu_enabled: bool,
     db_path: bool,
     import_dump: bool,
@@ -228,6 +232,7 @@ struct Infos {
     log_level: String,
     max_indexing_memory: MaxMemory,
     max_indexing_threads: MaxThreads,
+    max_filterable_attributes: usize,
     with_configuration_file: bool,
     ssl_auth_path: bool,
     ssl_cert_path: bool,
@@ -288,6 +293,8 @@ impl Infos {
             config_file_path,
             no_analytics: _,
             experimental_personalization_api_key,
+            experimental_facet_search_rate_limit,
+            experimental_facet_search_rate_limit_per_index,
             s3_snapshot_options,
         } = options;
 
@@ -299,6 +306,7 @@ impl Infos {
         let IndexerOpts {
             max_indexing_memory,
             max_indexing_threads,
+            max_filterable_attributes,
             skip_index_budget: _,
             experimental_no_edition_2024_for_settings,
             experimental_no_edition_2024_for_dumps,
@@ -317,6 +325,7 @@ impl Infos {
             chat_completions,
             multimodal,
             vector_store_setting,
+            facet_bitmaps,
         } = features;
 
         // We're going to override every sensible information.
@@ -343,6 +352,7 @@ impl Infos {
             experimental_no_snapshot_compaction,
             experimental_no_edition_2024_for_dumps,
             experimental_vector_store_setting: vector_store_setting,
+            experimental_facet_bitmaps: facet_bitmaps,
             gpu_enabled: meilisearch_types::milli::vector::is_cuda_enabled(),
             db_path: db_path != Path::new("./data.ms"),
             import_dump: import_dump.is_some(),
@@ -365,6 +375,7 @@ impl Infos {
             log_level: log_level.to_string(),
             max_indexing_memory,
             max_indexing_threads,
+            max_filterable_attributes,
             with_configuration_file: config_file_path.is_some(),
             ssl_auth_path: ssl_auth_path.is_some(),
             ssl_cert_path: ssl_cert_path.is_some(),
@@ -377,6 +388,10 @@ impl Infos {
             experimental_no_edition_2024_for_prefix_post_processing,
             experimental_no_edition_2024_for_facet_post_processing,
             experimental_personalization: experimental_personalization_api_key.is_some(),
+            experimental_facet_search_rate_limit: experimental_facet_search_rate_limit
+                .map(NonZeroU32::get),
+            experimental_facet_search_rate_limit_per_index:
+                experimental_facet_search_rate_limit_per_index.len(),
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::num::NonZeroU32;
+
 use actix_web as aweb;
 use aweb::error::{JsonPayloadError, QueryPayloadError};
 use byte_unit::{Byte, UnitType};
@@ -58,6 +60,8 @@ pub enum MeilisearchHttpError {
     TooManySearchRequests(usize),
     #[error("Internal error: Search limiter is down.")]
     SearchLimiterIsDown,
+    #[error("Too many facet search requests on the index `{index_uid}`, the limit is {requests_per_second} requests per second. Retry later.")]
+    TooManyFacetSearchRequests { index_uid: String, requests_per_second: NonZeroU32 },
     #[error("The provided payload reached the size limit. The maximum accepted payload size is {}.", Byte::from_u64(*.0 as u64).get_appropriate_unit(if *.0 % 1024 == 0 { UnitType::Binary } else { UnitType::Decimal }))]
     PayloadTooLarge(usize),
     #[error("Two indexes must be given for each swap. The list `[{}]` contains {} indexes.",
@@ -171,6 +175,9 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::PayloadTooLarge(_) => Code::PayloadTooLarge,
             MeilisearchHttpError::TooManySearchRequests(_) => Code::TooManySearchRequests,
             MeilisearchHttpError::SearchLimiterIsDown => Code::Internal,
+            MeilisearchHttpError::TooManyFacetSearchRequests { .. } => {
+                Code::TooManyFacetSearchRequests
+            }
             MeilisearchHttpError::SwapIndexPayloadWrongLength(_) => Code::InvalidSwapIndexes,
             MeilisearchHttpError::IndexUid(e) => e.error_code(),
             MeilisearchHttpError::SerdeJson(_) => Code::Internal,
@@ -0,0 +1,119 @@
+//! This file implements the optional rate limit of the facet search route.
+//!
+//! Autocomplete clients send a facet search on every keystroke and a runaway client can flood
+//! a node with them. When a rate is set for an index, with
+//! `--experimental-facet-search-rate-limit-per-index` or for all of them with
+//! `--experimental-facet-search-rate-limit`, the index gets a token bucket holding at most
+//! that many requests and refilled at that many requests per second.
+//! A facet search takes one token from the bucket of its index or fails with a
+//! `429 Too Many Requests` when the bucket is empty.
+//!
+//! The buckets are only keyed by index, not by API key: the authentication extractor does not
+//! give the routes the key a request was authenticated with, and the limit per index already
+//! protects the node whatever keys the clients use.
+//!
+//! A bucket that was not used for a second is full again, it is then dropped to not keep
+//! a bucket per deleted or rarely searched index.
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::MeilisearchHttpError;
+use crate::option::FacetSearchRateLimits;
+
+#[derive(Debug)]
+pub struct FacetSearchRateLimiter {
+    /// The number of facet search requests allowed per second on the indexes without
+    /// a rate of their own, `None` when disabled.
+    requests_per_second: Option<NonZeroU32>,
+    /// The number of facet search requests allowed per second on some indexes.
+    requests_per_second_per_index: FacetSearchRateLimits,
+    buckets: Mutex<Buckets>,
+}
+
+/// The time after which an unused bucket is full again and can be dropped.
+const IDLE_BUCKET_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+struct Buckets {
+    by_index: HashMap<String, Bucket>,
+    evicted_at: Option<Instant>,
+}
+
+impl Buckets {
+    /// Drops the buckets that are full again, at most once per `IDLE_BUCKET_TIMEOUT`.
+    fn evict_idle(&mut self, now: Instant) {
+        if self.evicted_at.is_some_and(|evicted_at| {
+            now.saturating_duration_since(evicted_at) < IDLE_BUCKET_TIMEOUT
+        }) {
+            return;
+        }
+        self.by_index.retain(|_, bucket| {
+            now.saturating_duration_since(bucket.refilled_at) < IDLE_BUCKET_TIMEOUT
+        });
+        self.evicted_at = Some(now);
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl FacetSearchRateLimiter {
+    pub fn new(
+        requests_per_second: Option<NonZeroU32>,
+        requests_per_second_per_index: FacetSearchRateLimits,
+    ) -> Self {
+        let buckets = Buckets { by_index: HashMap::new(), evicted_at: None };
+        Self { requests_per_second, requests_per_second_per_index, buckets: Mutex::new(buckets) }
+    }
+
+    /// The number of indexes currently having a bucket.
+    #[cfg(test)]
+    pub(crate) fn bucket_count(&self) -> usize {
+        self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).by_index.len()
+    }
+
+    /// Takes a token from the bucket of the index, returns an error if there is none left.
+    pub fn try_acquire(&self, index_uid: &str) -> Result<(), MeilisearchHttpError> {
+        self.try_acquire_at(index_uid, Instant::now())
+    }
+
+    pub(crate) fn try_acquire_at(
+        &self,
+        index_uid: &str,
+        now: Instant,
+    ) -> Result<(), MeilisearchHttpError> {
+        let Some(requests_per_second) =
+            self.requests_per_second_per_index.get(index_uid).or(self.requests_per_second)
+        else {
+            return Ok(());
+        };
+        let capacity = requests_per_second.get() as f64;
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        buckets.evict_idle(now);
+        let bucket = buckets
+            .by_index
+            .entry(index_uid.to_string())
+            .or_insert(Bucket { tokens: capacity, refilled_at: now });
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(MeilisearchHttpError::TooManyFacetSearchRequests {
+                index_uid: index_uid.to_string(),
+                requests_per_second,
+            })
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::num::NonZeroU32;
+use std::time::{Duration, Instant};
+
+use crate::facet_search_rate_limiter::FacetSearchRateLimiter;
+use crate::option::FacetSearchRateLimits;
+
+#[test]
+fn buckets_are_refilled_over_time() {
+    let limiter = FacetSearchRateLimiter::new(NonZeroU32::new(2), Default::default());
+    let now = Instant::now();
+
+    assert!(limiter.try_acquire_at("movies", now).is_ok());
+    assert!(limiter.try_acquire_at("movies", now).is_ok());
+    assert!(limiter.try_acquire_at("movies", now).is_err());
+    // each index has its own bucket
+    assert!(limiter.try_acquire_at("books", now).is_ok());
+
+    // a token is refilled every half second
+    let later = now + Duration::from_millis(500);
+    assert!(limiter.try_acquire_at("movies", later).is_ok());
+    assert!(limiter.try_acquire_at("movies", later).is_err());
+
+    // the bucket never holds more than one second of requests
+    let much_later = now + Duration::from_secs(60);
+    assert!(limiter.try_acquire_at("movies", much_later).is_ok());
+    assert!(limiter.try_acquire_at("movies", much_later).is_ok());
+    assert!(limiter.try_acquire_at("movies", much_later).is_err());
+}
+
+#[test]
+fn disabled_by_default() {
+    let limiter = FacetSearchRateLimiter::new(None, Default::default());
+    let now = Instant::now();
+    for _ in 0..1000 {
+        assert!(limiter.try_acquire_at("movies", now).is_ok());
+    }
+}
+
+#[test]
+fn per_index_rates_override_the_default_rate() {
+    let rates: FacetSearchRateLimits = "movies=1, books = 3".parse().unwrap();
+    let limiter = FacetSearchRateLimiter::new(NonZeroU32::new(2), rates);
+    let now = Instant::now();
+
+    assert!(limiter.try_acquire_at("movies", now).is_ok());
+    assert!(limiter.try_acquire_at("movies", now).is_err());
+    for _ in 0..3 {
+        assert!(limiter.try_acquire_at("books", now).is_ok());
+    }
+    assert!(limiter.try_acquire_at("books", now).is_err());
+    // the indexes without a rate of their own get the default one
+    assert!(limiter.try_acquire_at("songs", now).is_ok());
+    assert!(limiter.try_acquire_at("songs", now).is_ok());
+    assert!(limiter.try_acquire_at("songs", now).is_err());
+
+    // only the listed indexes are limited when there is no default rate
+    let rates: FacetSearchRateLimits = "movies=1".parse().unwrap();
+    let limiter = FacetSearchRateLimiter::new(None, rates);
+    assert!(limiter.try_acquire_at("movies", now).is_ok());
+    assert!(limiter.try_acquire_at("movies", now).is_err());
+    for _ in 0..1000 {
+        assert!(limiter.try_acquire_at("songs", now).is_ok());
+    }
+
+    assert!("movies".parse::<FacetSearchRateLimits>().is_err());
+    assert!("movies=0".parse::<FacetSearchRateLimits>().is_err());
+}
+
+#[test]
+fn idle_buckets_are_evicted() {
+    let limiter = FacetSearchRateLimiter::new(NonZeroU32::new(2), Default::default());
+    let now = Instant::now();
+
+    for index in 0..100 {
+        assert!(limiter.try_acquire_at(&format!("index-{index}"), now).is_ok());
+    }
+    assert!(limiter.try_acquire_at("movies", now).is_ok());
+    assert!(limiter.try_acquire_at("movies", now).is_ok());
+    assert_eq!(limiter.bucket_count(), 101);
+
+    // the buckets not used for a second are full again and dropped
+    assert!(limiter.try_acquire_at("movies", now + Duration::from_millis(1100)).is_ok());
+    let later = now + Duration::from_millis(1500);
+    assert!(limiter.try_acquire_at("books", later).is_ok());
+    assert_eq!(limiter.bucket_count(), 2);
+
+    // the bucket of an active index is kept with its remaining tokens
+    assert!(limiter.try_acquire_at("movies", later).is_ok());
+    assert!(limiter.try_acquire_at("movies", later).is_err());
+}
@@ -0,0 +1,275 @@
+//! This file implements the point-in-time views the facet search can run against.
+//!
+//! A facet search with `snapshot: true` opens a read transaction on the index and keeps it
+//! alive under a token returned in the response. The following facet searches sending this
+//! `snapshotToken` read the index through the same transaction, and therefore see the same
+//! documents and facet counts whatever has been indexed since.
+//!
+//! A read transaction prevents LMDB from reusing the pages freed by the later writes and keeps
+//! the index opened. It also holds one of the reader slots of the index environment, which
+//! are shared with the searches and are 1024 by default (`MEILI_EXPERIMENTAL_INDEX_MAX_READERS`):
+//! a search failing to get a slot fails with an `MDB_READERS_FULL` error. This is why the
+//! snapshots only live for a bounded time after their creation, a background thread dropping
+//! them once expired even when no facet search is made, and why only a bounded number of them
+//! can be kept at once, in total and per index, the oldest being dropped first.
+//!
+//! A snapshot also keeps a handle on its index, and the environment of an index cannot be closed
+//! while a handle is alive: the deletion, the resizing and the eviction from the cache of an index
+//! wait for its snapshots to be dropped, blocking the scheduler meanwhile. The snapshots of an
+//! index are therefore dropped as soon as the scheduler starts closing or deleting it, at the
+//! latest one expiry interval later. They are also dropped when another index is swapped under
+//! their index uid, the following searches failing as if they had expired.
+//!
+//! LMDB forbids using a read transaction from several threads at once, so the searches sharing
+//! a snapshot lock its transaction and run one after the other rather than in parallel.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, Weak};
+use std::time::{Duration, Instant};
+
+use index_scheduler::IndexClosings;
+use meilisearch_types::heed::{RoTxn, WithoutTls};
+use meilisearch_types::milli::Index;
+use uuid::Uuid;
+
+/// The default number of snapshots that can be kept at once.
+const DEFAULT_CAPACITY: usize = 100;
+/// The default number of snapshots that can be kept at once on a single index.
+const DEFAULT_CAPACITY_PER_INDEX: usize = 10;
+/// The interval at which the expired snapshots are dropped in the background.
+const EXPIRY_INTERVAL: Duration = Duration::from_secs(1);
+/// The default time a snapshot lives after its creation.
+const DEFAULT_TIME_TO_LIVE: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub struct FacetSearchSnapshots {
+    snapshots: Arc<Mutex<HashMap<Uuid, StoredSnapshot>>>,
+    index_closings: Arc<OnceLock<IndexClosings>>,
+    capacity: usize,
+    capacity_per_index: usize,
+    time_to_live: Duration,
+}
+
+#[derive(Debug)]
+struct StoredSnapshot {
+    index_uid: String,
+    expires_at: Instant,
+    snapshot: FacetSearchSnapshot,
+}
+
+/// A read transaction kept alive on an index along with the index it was opened on.
+#[derive(Clone)]
+pub struct FacetSearchSnapshot {
+    token: Uuid,
+    index: Index,
+    rtxn: Arc<Mutex<RoTxn<'static, WithoutTls>>>,
+}
+
+impl std::fmt::Debug for FacetSearchSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FacetSearchSnapshot").field("token", &self.token).finish()
+    }
+}
+
+impl FacetSearchSnapshot {
+    pub fn token(&self) -> Uuid {
+        self.token
+    }
+
+    /// The index the snapshot was taken on, which must be the one searched with the transaction.
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Locks the read transaction of the snapshot.
+    ///
+    /// A read transaction cannot be used by several threads at once, so the searches sharing
+    /// a snapshot wait for each other here.
+    pub fn read_txn(&self) -> MutexGuard<'_, RoTxn<'static, WithoutTls>> {
+        self.rtxn.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for FacetSearchSnapshots {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, DEFAULT_CAPACITY_PER_INDEX, DEFAULT_TIME_TO_LIVE)
+    }
+}
+
+impl FacetSearchSnapshots {
+    /// Creates the snapshot store and starts the thread dropping its expired snapshots,
+    /// which stops once the store is dropped.
+    pub fn new(capacity: usize, capacity_per_index: usize, time_to_live: Duration) -> Self {
+        let snapshots = Arc::new(Mutex::new(HashMap::new()));
+        let index_closings = Arc::new(OnceLock::new());
+        let weak_snapshots = Arc::downgrade(&snapshots);
+        let expiry_index_closings = index_closings.clone();
+        std::thread::Builder::new()
+            .name("facet-search-snapshots-expiry".to_string())
+            .spawn(move || Self::run_expiry(weak_snapshots, expiry_index_closings))
+            .expect("failed to spawn the facet search snapshots expiry thread");
+
+        Self { snapshots, index_closings, capacity, capacity_per_index, time_to_live }
+    }
+
+    /// Drops the snapshots of the indexes the scheduler is closing or deleting,
+    /// so that it does not wait for them to expire.
+    pub fn with_index_closings(self, index_closings: IndexClosings) -> Self {
+        let _ = self.index_closings.set(index_closings);
+        self
+    }
+
+    fn run_expiry(
+        snapshots: Weak<Mutex<HashMap<Uuid, StoredSnapshot>>>,
+        index_closings: Arc<OnceLock<IndexClosings>>,
+    ) {
+        loop {
+            std::thread::sleep(EXPIRY_INTERVAL);
+            let Some(snapshots) = snapshots.upgrade() else { break };
+            let mut snapshots = snapshots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            remove_expired(&mut snapshots, Instant::now(), index_closings.get());
+        }
+    }
+
+    /// Opens a read transaction on the index and stores it under a new token.
+    pub fn create(
+        &self,
+        index_uid: &str,
+        index: Index,
+    ) -> meilisearch_types::heed::Result<FacetSearchSnapshot> {
+        let rtxn = index.static_read_txn()?;
+        let snapshot =
+            FacetSearchSnapshot { token: Uuid::new_v4(), index, rtxn: Arc::new(Mutex::new(rtxn)) };
+
+        let now = Instant::now();
+        let mut snapshots = self.lock();
+        remove_expired(&mut snapshots, now, self.index_closings.get());
+        while snapshots.values().filter(|stored| stored.index_uid == index_uid).count()
+            >= self.capacity_per_index
+        {
+            let Some(oldest) = oldest_snapshot(&snapshots, |stored| stored.index_uid == index_uid)
+            else {
+                break;
+            };
+            snapshots.remove(&oldest);
+        }
+        while snapshots.len() >= self.capacity {
+            let Some(oldest) = oldest_snapshot(&snapshots, |_| true) else { break };
+            snapshots.remove(&oldest);
+        }
+        if self.capacity > 0 && self.capacity_per_index > 0 {
+            snapshots.insert(
+                snapshot.token,
+                StoredSnapshot {
+                    index_uid: index_uid.to_string(),
+                    expires_at: now + self.time_to_live,
+                    snapshot: snapshot.clone(),
+                },
+            );
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Returns the snapshot stored under this token if it was taken on this index and has not expired.
+    ///
+    /// The snapshot is dropped if it was taken on another index than the one currently
+    /// named `index_uid`, another index having been swapped under this uid since.
+    pub fn get(
+        &self,
+        index_uid: &str,
+        current: &Index,
+        token: Uuid,
+    ) -> Option<FacetSearchSnapshot> {
+        let now = Instant::now();
+        let mut snapshots = self.lock();
+        remove_expired(&mut snapshots, now, self.index_closings.get());
+        let stored = snapshots.get(&token).filter(|stored| stored.index_uid == index_uid)?;
+        if stored.snapshot.index.path() != current.path() {
+            snapshots.remove(&token);
+            return None;
+        }
+        Some(stored.snapshot.clone())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Uuid, StoredSnapshot>> {
+        self.snapshots.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Drops the expired snapshots and the ones of the indexes being closed or deleted,
+/// releasing their read transaction unless a search still uses it.
+fn remove_expired(
+    snapshots: &mut HashMap<Uuid, StoredSnapshot>,
+    now: Instant,
+    index_closings: Option<&IndexClosings>,
+) {
+    snapshots.retain(|_, stored| {
+        stored.expires_at > now
+            && !index_closings.is_some_and(|closings| closings.is_closing(&stored.snapshot.index))
+    });
+}
+
+fn oldest_snapshot(
+    snapshots: &HashMap<Uuid, StoredSnapshot>,
+    filter: impl Fn(&StoredSnapshot) -> bool,
+) -> Option<Uuid> {
+    snapshots
+        .iter()
+        .filter(|(_, stored)| filter(stored))
+        .min_by_key(|(_, stored)| stored.expires_at)
+        .map(|(token, _)| *token)
+}
+
+#[cfg(test)]
+mod tests {
+    use meilisearch_types::heed::EnvOpenOptions;
+
+    use super::*;
+
+    fn index(dir: &tempfile::TempDir) -> Index {
+        let options = EnvOpenOptions::new().read_txn_without_tls();
+        Index::new(options, dir.path(), true).unwrap()
+    }
+
+    #[test]
+    fn snapshots_are_capped_per_index() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (index_a, index_b) = (index(&dir_a), index(&dir_b));
+        let snapshots = FacetSearchSnapshots::new(10, 2, Duration::from_secs(60));
+
+        let first = snapshots.create("a", index_a.clone()).unwrap().token();
+        let second = snapshots.create("a", index_a.clone()).unwrap().token();
+        let other = snapshots.create("b", index_b.clone()).unwrap().token();
+        let third = snapshots.create("a", index_a.clone()).unwrap().token();
+
+        // the oldest snapshot of the index is dropped, not the one of the other index
+        assert!(snapshots.get("a", &index_a, first).is_none());
+        assert!(snapshots.get("a", &index_a, second).is_some());
+        assert!(snapshots.get("a", &index_a, third).is_some());
+        assert!(snapshots.get("b", &index_b, other).is_some());
+    }
+
+    #[test]
+    fn snapshots_are_dropped_when_another_index_is_swapped_under_their_uid() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (index_a, index_b) = (index(&dir_a), index(&dir_b));
+        let snapshots = FacetSearchSnapshots::new(10, 10, Duration::from_secs(60));
+
+        let token = snapshots.create("a", index_a.clone()).unwrap().token();
+        assert!(snapshots.get("a", &index_b, token).is_none());
+        // the snapshot is not kept for when the indexes are swapped back
+        assert!(snapshots.get("a", &index_a, token).is_none());
+    }
+
+    #[test]
+    fn expired_snapshots_are_dropped_in_the_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = FacetSearchSnapshots::new(10, 10, Duration::from_millis(10));
+        drop(snapshots.create("a", index(&dir)).unwrap());
+
+        std::thread::sleep(EXPIRY_INTERVAL * 3);
+        // the store is inspected directly to not trigger the expiry of `get`
+        assert!(snapshots.lock().is_empty());
+    }
+}
@@ -6,6 +6,10 @@ pub mod error;
 pub mod analytics;
 #[macro_use]
 pub mod extractors;
+pub mod facet_search_rate_limiter;
+#[cfg(test)]
+mod facet_search_rate_limiter_test;
+pub mod facet_search_snapshots;
 pub mod metrics;
 pub mod middleware;
 pub mod option;
@@ -61,6 +65,8 @@ use tracing::{error, info_span};
 use tracing_subscriber::filter::Targets;
 
 use crate::error::MeilisearchHttpError;
+use crate::facet_search_rate_limiter::FacetSearchRateLimiter;
+use crate::facet_search_snapshots::FacetSearchSnapshots;
 use crate::personalization::PersonalizationService;
 
 /// Default number of simultaneously opened indexes.
@@ -703,6 +709,8 @@ pub fn configure_data(config: &mut web::ServiceConfig, services: ServicesData, o
         index_scheduler,
         auth,
         search_queue,
+        facet_search_snapshots,
+        facet_search_rate_limiter,
         personalization_service,
         logs_route_handle,
         logs_stderr_handle,
@@ -714,6 +722,8 @@ pub fn configure_data(config: &mut web::ServiceConfig, services: ServicesData, o
         .app_data(index_scheduler)
         .app_data(auth)
         .app_data(search_queue)
+        .app_data(facet_search_snapshots)
+        .app_data(facet_search_rate_limiter)
         .app_data(analytics)
         .app_data(personalization_service)
         .app_data(logs_route_handle)
@@ -784,6 +794,8 @@ pub struct ServicesData {
     pub index_scheduler: Data<IndexScheduler>,
     pub auth: Data<AuthController>,
     pub search_queue: Data<SearchQueue>,
+    pub facet_search_snapshots: Data<FacetSearchSnapshots>,
+    pub facet_search_rate_limiter: Data<FacetSearchRateLimiter>,
     pub personalization_service: Data<PersonalizationService>,
     pub logs_route_handle: Data<LogRouteHandle>,
     pub logs_stderr_handle: Data<LogStderrHandle>,
@@ -13,6 +13,8 @@ use actix_web::HttpServer;
 use index_scheduler::IndexScheduler;
 use is_terminal::IsTerminal;
 use meilisearch::analytics::Analytics;
+use meilisearch::facet_search_rate_limiter::FacetSearchRateLimiter;
+use meilisearch::facet_search_snapshots::FacetSearchSnapshots;
 use meilisearch::option::LogMode;
 use meilisearch::personalization::PersonalizationService;
 use meilisearch::search_queue::SearchQueue;
@@ -173,6 +175,13 @@ async fn run_http(
         usize::from(opt.experimental_drop_search_after) as u64
     ));
     let search_queue = Data::new(search_queue);
+    let facet_search_snapshots = Data::new(
+        FacetSearchSnapshots::default().with_index_closings(index_scheduler.index_closings()),
+    );
+    let facet_search_rate_limiter = Data::new(FacetSearchRateLimiter::new(
+        opt.experimental_facet_search_rate_limit,
+        opt.experimental_facet_search_rate_limit_per_index.clone(),
+    ));
     let (logs_route_handle, logs_stderr_handle) = logs;
     let logs_route_handle = Data::new(logs_route_handle);
     let logs_stderr_handle = Data::new(logs_stderr_handle);
@@ -181,6 +190,8 @@ async fn run_http(
         index_scheduler,
         auth,
         search_queue,
+        facet_search_snapshots,
+        facet_search_rate_limiter,
         personalization_service,
         logs_route_handle,
         logs_stderr_handle,
@@ -1,8 +1,9 @@
+use std::collections::BTreeMap;
 use std::env::VarError;
 use std::ffi::OsStr;
 use std::fmt::Display;
 use std::io::{BufReader, Read};
-use std::num::{NonZeroUsize, ParseIntError};
+use std::num::{NonZeroU32, NonZeroUsize, ParseIntError};
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -77,6 +78,10 @@ const MEILI_EXPERIMENTAL_NO_EDITION_2024_FOR_DUMPS: &str =
     "MEILI_EXPERIMENTAL_NO_EDITION_2024_FOR_DUMPS";
 const MEILI_EXPERIMENTAL_PERSONALIZATION_API_KEY: &str =
     "MEILI_EXPERIMENTAL_PERSONALIZATION_API_KEY";
+const MEILI_EXPERIMENTAL_FACET_SEARCH_RATE_LIMIT: &str =
+    "MEILI_EXPERIMENTAL_FACET_SEARCH_RATE_LIMIT";
+const MEILI_EXPERIMENTAL_FACET_SEARCH_RATE_LIMIT_PER_INDEX: &str =
+    "MEILI_EXPERIMENTAL_FACET_SEARCH_RATE_LIMIT_PER_INDEX";
 
 // Related to S3 snapshots
 const MEILI_S3_BUCKET_URL: &str = "MEILI_S3_BUCKET_URL";
@@ -103,6 +108,7 @@ const DEFAULT_SNAPSHOT_DIR: &str = "snapshots/";
 const DEFAULT_SNAPSHOT_INTERVAL_SEC: u64 = 86400;
 const DEFAULT_SNAPSHOT_INTERVAL_SEC_STR: &str = "86400";
 const DEFAULT_DUMP_DIR: &str = "dumps/";
+const DEFAULT_MAX_FILTERABLE_ATTRIBUTES: usize = 100;
 const DEFAULT_S3_SNAPSHOT_MAX_IN_FLIGHT_PARTS: NonZeroUsize = NonZeroUsize::new(10).unwrap();
 const DEFAULT_S3_SNAPSHOT_COMPRESSION_LEVEL: u32 = 0;
 const DEFAULT_S3_SNAPSHOT_SIGNATURE_DURATION_SECONDS: u64 = 8 * 3600; // 8 hours
@@ -110,6 +116,7 @@ const DEFAULT_S3_SNAPSHOT_MULTIPART_PART_SIZE: Byte = Byte::from_u64(375 * 1024
 
 const MEILI_MAX_INDEXING_MEMORY: &str = "MEILI_MAX_INDEXING_MEMORY";
 const MEILI_MAX_INDEXING_THREADS: &str = "MEILI_MAX_INDEXING_THREADS";
+const MEILI_MAX_FILTERABLE_ATTRIBUTES: &str = "MEILI_MAX_FILTERABLE_ATTRIBUTES";
 const DEFAULT_LOG_EVERY_N: usize = 100_000;
 
 // Each environment (index and task-db) is taking space in the virtual address space.
@@ -508,6 +515,27 @@ pub struct Opt {
     #[clap(long, env = MEILI_EXPERIMENTAL_PERSONALIZATION_API_KEY)]
     pub experimental_personalization_api_key: Option<String>,
 
+    /// Experimental facet search rate limit.
+    ///
+    /// Caps the number of facet search requests per second on each index, the requests
+    /// exceeding it fail with a `429 Too Many Requests`. Short bursts of at most that many
+    /// requests are allowed. There is no limit by default.
+    ///
+    /// The limit applies to every index of the instance that is not listed in
+    /// `--experimental-facet-search-rate-limit-per-index`, the requests of each index
+    /// being counted separately.
+    #[clap(long, env = MEILI_EXPERIMENTAL_FACET_SEARCH_RATE_LIMIT)]
+    pub experimental_facet_search_rate_limit: Option<NonZeroU32>,
+
+    /// Experimental per-index facet search rate limits.
+    ///
+    /// Caps the number of facet search requests per second on the listed indexes, overriding
+    /// `--experimental-facet-search-rate-limit` for them. Given as a comma-separated list of
+    /// `indexUid=requestsPerSecond`, e.g. `movies=10,products=50`.
+    #[clap(long, env = MEILI_EXPERIMENTAL_FACET_SEARCH_RATE_LIMIT_PER_INDEX)]
+    #[serde(default)]
+    pub experimental_facet_search_rate_limit_per_index: FacetSearchRateLimits,
+
     #[serde(flatten)]
     #[clap(flatten)]
     pub indexer_options: IndexerOpts,
@@ -618,6 +646,8 @@ impl Opt {
             experimental_embedding_cache_entries,
             experimental_no_snapshot_compaction,
             experimental_personalization_api_key,
+            experimental_facet_search_rate_limit,
+            experimental_facet_search_rate_limit_per_index,
             s3_snapshot_options,
         } = self;
         export_to_env_if_not_present(MEILI_DB_PATH, db_path);
@@ -727,6 +757,18 @@ impl Opt {
                 experimental_personalization_api_key,
             );
         }
+        if let Some(experimental_facet_search_rate_limit) = experimental_facet_search_rate_limit {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_FACET_SEARCH_RATE_LIMIT,
+                experimental_facet_search_rate_limit.to_string(),
+            );
+        }
+        if !experimental_facet_search_rate_limit_per_index.is_empty() {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_FACET_SEARCH_RATE_LIMIT_PER_INDEX,
+                experimental_facet_search_rate_limit_per_index.to_string(),
+            );
+        }
         indexer_options.export_to_env();
         if let Some(s3_snapshot_options) = s3_snapshot_options {
             #[cfg(not(unix))]
@@ -807,6 +849,13 @@ pub struct IndexerOpts {
     #[serde(default)]
     pub max_indexing_threads: MaxThreads,
 
+    /// Sets the number of fields matching the filterable attributes of an index above which
+    /// a warning is returned in the details of the settings update tasks. Indexing the facets
+    /// of that many fields can be very slow, but the settings are applied anyway.
+    #[clap(long, env = MEILI_MAX_FILTERABLE_ATTRIBUTES, default_value_t = default_max_filterable_attributes())]
+    #[serde(default = "default_max_filterable_attributes")]
+    pub max_filterable_attributes: usize,
+
     /// Whether or not we want to determine the budget of virtual memory address space we have available dynamically
     /// (the default), or statically.
     ///
@@ -856,6 +905,7 @@ impl IndexerOpts {
         let IndexerOpts {
             max_indexing_memory,
             max_indexing_threads,
+            max_filterable_attributes,
             skip_index_budget: _,
             experimental_no_edition_2024_for_settings,
             experimental_no_edition_2024_for_dumps,
@@ -874,6 +924,10 @@ impl IndexerOpts {
                 max_indexing_threads.to_string(),
             );
         }
+        export_to_env_if_not_present(
+            MEILI_MAX_FILTERABLE_ATTRIBUTES,
+            max_filterable_attributes.to_string(),
+        );
         if experimental_no_edition_2024_for_settings {
             export_to_env_if_not_present(
                 MEILI_EXPERIMENTAL_NO_EDITION_2024_FOR_SETTINGS,
@@ -908,6 +962,7 @@ impl TryFrom<&IndexerOpts> for IndexerConfig {
         let IndexerOpts {
             max_indexing_memory,
             max_indexing_threads,
+            max_filterable_attributes,
             skip_index_budget,
             experimental_no_edition_2024_for_settings,
             experimental_no_edition_2024_for_dumps,
@@ -936,6 +991,7 @@ impl TryFrom<&IndexerOpts> for IndexerConfig {
                 *experimental_no_edition_2024_for_prefix_post_processing,
             experimental_no_edition_2024_for_facet_post_processing:
                 *experimental_no_edition_2024_for_facet_post_processing,
+            max_filterable_attributes: Some(*max_filterable_attributes),
             s3_snapshot_options: None,
         })
     }
@@ -1127,6 +1183,55 @@ impl TryFrom<S3SnapshotOpts> for S3SnapshotOptions {
     }
 }
 
+/// The facet search rate limits of some indexes, in requests per second.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct FacetSearchRateLimits(BTreeMap<String, NonZeroU32>);
+
+impl FacetSearchRateLimits {
+    pub fn get(&self, index_uid: &str) -> Option<NonZeroU32> {
+        self.0.get(index_uid).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl FromStr for FacetSearchRateLimits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut limits = BTreeMap::new();
+        for limit in s.split(',').map(str::trim).filter(|limit| !limit.is_empty()) {
+            let Some((index_uid, rate)) = limit.split_once('=') else {
+                return Err(format!("`{limit}` is not of the form `indexUid=requestsPerSecond`"));
+            };
+            let rate = rate.trim().parse::<NonZeroU32>().map_err(|error| {
+                format!("invalid rate for the index `{}`: {error}", index_uid.trim())
+            })?;
+            limits.insert(index_uid.trim().to_string(), rate);
+        }
+        Ok(FacetSearchRateLimits(limits))
+    }
+}
+
+impl fmt::Display for FacetSearchRateLimits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (index_uid, rate)) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{index_uid}={rate}")?;
+        }
+        Ok(())
+    }
+}
+
 /// A type used to detect the max memory available and use 2/3 of it.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub struct MaxMemory(Option<Byte>);
@@ -1329,6 +1434,10 @@ fn default_embedding_cache_entries() -> usize {
     0
 }
 
+fn default_max_filterable_attributes() -> usize {
+    DEFAULT_MAX_FILTERABLE_ATTRIBUTES
+}
+
 fn default_snapshot_dir() -> PathBuf {
     PathBuf::from(DEFAULT_SNAPSHOT_DIR)
 }
@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, BinaryHeap, HashSet};
+
+use actix_web::web::Data;
+use actix_web::{web, HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use tracing::debug;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::analytics::{Aggregate, Analytics};
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::search::{add_search_rules, perform_facet_distribution, FacetDistributionResult};
+use crate::search_queue::SearchQueue;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(facet_distribution),
+    tags(
+        (
+            name = "Facet Distribution",
+            description = "The `/facet-distribution` route returns the facet distribution of the whole index without running a search. The facet values are sorted and limited according to the faceting index settings.",
+            external_docs(url = "https://www.meilisearch.com/docs/reference/api/search#facets"),
+        ),
+    ),
+)]
+pub struct FacetDistributionApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(facet_distribution)));
+}
+
+#[derive(Debug, Clone, Default, PartialEq, deserr::Deserr, ToSchema)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct FacetDistributionQuery {
+    #[deserr(error = DeserrJsonError<InvalidFacetDistributionFacets>, missing_field_error = DeserrJsonError::missing_facet_distribution_facets)]
+    pub facets: Vec<String>,
+    /// Only counts these values of the facets, e.g. `{ "brand": ["Nike", "Adidas"] }`.
+    /// The values that don't exist are counted as 0.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetDistributionFacetValues>)]
+    pub facet_values: Option<BTreeMap<String, Vec<String>>>,
+    /// Whether the `facetValues` matching no document are returned with a count of 0, so that
+    /// clients can render a stable list of values, or omitted. Defaults to `true`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetDistributionIncludeZeroCounts>)]
+    pub include_zero_counts: Option<bool>,
+    /// Also returns the nodes of this level of the number facet trees as `facetRanges` buckets,
+    /// going from the left bound of a node, included, to the left bound of the next one, excluded.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetDistributionGranularityLevel>)]
+    pub granularity_level: Option<u8>,
+    /// Rounds the number facet values to this number of decimals before counting them, merging
+    /// the values that only differ by floating point noise, e.g. `19.99` and `19.990000001`.
+    /// Only the returned values are rounded, the stored values and the filters are not affected.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetDistributionNumericPrecision>)]
+    pub numeric_precision: Option<u8>,
+    /// Also returns the internal document ids of the first values of the distribution
+    /// as base64 encoded `RoaringBitmap`s. Requires the `facetBitmaps` experimental feature.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetDistributionFacetBitmaps>)]
+    pub facet_bitmaps: bool,
+    /// Boolean fields of `facets` returned as `true`, `false` and `missing` buckets, always in
+    /// this order and whatever their count. The `missing` bucket counts the documents having
+    /// neither value. The fields having other values than booleans are returned as usual.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetDistributionBooleanFacets>)]
+    pub boolean_facets: Option<Vec<String>>,
+}
+
+#[derive(Default)]
+pub struct FacetDistributionAggregator {
+    // requests
+    total_received: usize,
+    total_succeeded: usize,
+    time_spent: BinaryHeap<usize>,
+
+    // The set of all facets that were requested
+    facet_names: HashSet<String>,
+
+    // The number of requests counting a subset of the facet values
+    total_with_facet_values: usize,
+
+    // The number of requests omitting the facet values counted as 0
+    total_without_zero_counts: usize,
+
+    // The number of requests returning the nodes of a facet tree level
+    total_with_granularity_level: usize,
+
+    // The number of requests rounding the number values
+    total_with_numeric_precision: usize,
+
+    // The number of requests returning the document ids of the values
+    total_with_facet_bitmaps: usize,
+
+    // The number of requests returning boolean buckets
+    total_with_boolean_facets: usize,
+}
+
+impl FacetDistributionAggregator {
+    pub fn from_query(query: &FacetDistributionQuery) -> Self {
+        let FacetDistributionQuery {
+            facets,
+            facet_values,
+            include_zero_counts,
+            granularity_level,
+            numeric_precision,
+            facet_bitmaps,
+            boolean_facets,
+        } = query;
+
+        Self {
+            total_received: 1,
+            facet_names: facets
+                .iter()
+                .chain(facet_values.iter().flat_map(|values| values.keys()))
+                .cloned()
+                .collect(),
+            total_with_facet_values: facet_values.is_some() as usize,
+            total_without_zero_counts: (*include_zero_counts == Some(false)) as usize,
+            total_with_granularity_level: granularity_level.is_some() as usize,
+            total_with_numeric_precision: numeric_precision.is_some() as usize,
+            total_with_facet_bitmaps: *facet_bitmaps as usize,
+            total_with_boolean_facets: boolean_facets.is_some() as usize,
+            ..Default::default()
+        }
+    }
+
+    pub fn succeed(&mut self, result: &FacetDistributionResult) {
+        let FacetDistributionResult {
+            facet_distribution: _,
+            facet_stats: _,
+            facet_ranges: _,
+            facet_bitmaps: _,
+            processing_time_ms,
+        } = result;
+        self.total_succeeded = 1;
+        self.time_spent.push(*processing_time_ms as usize);
+    }
+}
+
+impl Aggregate for FacetDistributionAggregator {
+    fn event_name(&self) -> &'static str {
+        "Facet Distribution POST"
+    }
+
+    fn aggregate(mut self: Box<Self>, new: Box<Self>) -> Box<Self> {
+        for time in new.time_spent {
+            self.time_spent.push(time);
+        }
+
+        Box::new(Self {
+            total_received: self.total_received.saturating_add(new.total_received),
+            total_succeeded: self.total_succeeded.saturating_add(new.total_succeeded),
+            time_spent: self.time_spent,
+            facet_names: self.facet_names.union(&new.facet_names).cloned().collect(),
+            total_with_facet_values: self
+                .total_with_facet_values
+                .saturating_add(new.total_with_facet_values),
+            total_without_zero_counts: self
+                .total_without_zero_counts
+                .saturating_add(new.total_without_zero_counts),
+            total_with_granularity_level: self
+                .total_with_granularity_level
+                .saturating_add(new.total_with_granularity_level),
+            total_with_numeric_precision: self
+                .total_with_numeric_precision
+                .saturating_add(new.total_with_numeric_precision),
+            total_with_facet_bitmaps: self
+                .total_with_facet_bitmaps
+                .saturating_add(new.total_with_facet_bitmaps),
+            total_with_boolean_facets: self
+                .total_with_boolean_facets
+                .saturating_add(new.total_with_boolean_facets),
+        })
+    }
+
+    fn into_event(self: Box<Self>) -> serde_json::Value {
+        let Self {
+            total_received,
+            total_succeeded,
+            time_spent,
+            facet_names,
+            total_with_facet_values,
+            total_without_zero_counts,
+            total_with_granularity_level,
+            total_with_numeric_precision,
+            total_with_facet_bitmaps,
+            total_with_boolean_facets,
+        } = *self;
+        // the index of the 99th percentage of value
+        let percentile_99th = 0.99 * (total_succeeded as f64 - 1.) + 1.;
+        // we get all the values in a sorted manner
+        let time_spent = time_spent.into_sorted_vec();
+        // We are only interested by the slowest value of the 99th fastest results
+        let time_spent = time_spent.get(percentile_99th as usize);
+
+        serde_json::json!({
+            "requests": {
+                "99th_response_time":  time_spent.map(|t| format!("{:.2}", t)),
+                "total_succeeded": total_succeeded,
+                "total_failed": total_received.saturating_sub(total_succeeded), // just to be sure we never panics
+                "total_received": total_received,
+            },
+            "facets": {
+                "total_distinct_facet_count": facet_names.len(),
+                "total_with_facet_values": total_with_facet_values,
+                "total_without_zero_counts": total_without_zero_counts,
+                "total_with_granularity_level": total_with_granularity_level,
+                "total_with_numeric_precision": total_with_numeric_precision,
+                "total_with_facet_bitmaps": total_with_facet_bitmaps,
+                "total_with_boolean_facets": total_with_boolean_facets,
+            },
+        })
+    }
+}
+
+/// Get the facet distribution
+///
+/// Get the distribution of the given facets over the whole index, without a search query.
+#[utoipa::path(
+    post,
+    path = "{indexUid}/facet-distribution",
+    tag = "Facet Distribution",
+    security(("Bearer" = ["search", "*"])),
+    params(("indexUid", example = "movies", description = "Index Unique Identifier", nullable = false)),
+    request_body = FacetDistributionQuery,
+    responses(
+        (status = 200, description = "The facet distribution is returned", body = FacetDistributionResult, content_type = "application/json", example = json!(
+            {
+              "facetDistribution": {
+                "genres": {
+                  "Action": 3,
+                  "Adventure": 2
+                }
+              },
+              "facetStats": {},
+              "processingTimeMs": 1
+            }
+        )),
+        (status = 404, description = "Index not found", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Index `movies` not found.",
+                "code": "index_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#index_not_found"
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn facet_distribution(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    search_queue: Data<SearchQueue>,
+    index_uid: web::Path<String>,
+    params: AwebJson<FacetDistributionQuery, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+
+    let query = params.into_inner();
+    debug!(parameters = ?query, "Facet distribution");
+
+    let mut aggregate = FacetDistributionAggregator::from_query(&query);
+
+    // Tenant token search_rules.
+    let mut filter = None;
+    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
+        add_search_rules(&mut filter, search_rules);
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+    let features = index_scheduler.features();
+    if query.facet_bitmaps {
+        features.check_facet_bitmaps("Using `facetBitmaps` in the facet distribution")?;
+    }
+    let permit = search_queue.try_get_search_permit().await?;
+    let result = tokio::task::spawn_blocking(move || {
+        perform_facet_distribution(&index, query, filter, features)
+    })
+    .await;
+    permit.drop().await;
+    let result = result?;
+
+    if let Ok(ref result) = result {
+        aggregate.succeed(result);
+    }
+    analytics.publish(aggregate, &req);
+
+    let result = result?;
+
+    debug!(returns = ?result, "Facet distribution");
+    Ok(HttpResponse::Ok().json(result))
+}
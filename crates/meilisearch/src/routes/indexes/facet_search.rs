@@ -1,4 +1,4 @@
-use std::collections::{BinaryHeap, HashSet};
+use std::collections::{BTreeMap, BinaryHeap, HashSet};
 
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
@@ -6,28 +6,38 @@ use deserr::actix_web::AwebJson;
 use index_scheduler::IndexScheduler;
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::deserr_codes::*;
-use meilisearch_types::error::ResponseError;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::facet_values_sort::FacetValuesSort;
+use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::locales::Locale;
+use meilisearch_types::milli::index::FacetNormalization;
+use meilisearch_types::milli::{FacetCollation, FacetRepresentativeValue};
+use serde::Serialize;
 use serde_json::Value;
 use tracing::debug;
 use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
 
 use crate::analytics::{Aggregate, Analytics};
+use crate::error::MeilisearchHttpError;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::facet_search_rate_limiter::FacetSearchRateLimiter;
+use crate::facet_search_snapshots::FacetSearchSnapshots;
 use crate::routes::indexes::search::search_kind;
 use crate::search::{
-    add_search_rules, perform_facet_search, FacetSearchResult, HybridQuery, MatchingStrategy,
-    RankingScoreThreshold, SearchQuery, SearchResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
-    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
-    DEFAULT_SEARCH_OFFSET,
+    add_search_rules, perform_facet_search, ExternalDocumentId, FacetSearchOptions,
+    FacetSearchResult, HybridQuery, MatchingStrategy, RankingScoreThreshold, SearchQuery,
+    SearchResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
+    DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET,
 };
 use crate::search_queue::SearchQueue;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(search),
+    paths(search, get_facet_normalization),
     tags(
         (
             name = "Facet Search",
@@ -39,7 +49,9 @@ use crate::search_queue::SearchQueue;
 pub struct FacetSearchApi;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::resource("").route(web::post().to(search)));
+    cfg.service(web::resource("").route(web::post().to(search))).service(
+        web::resource("/normalization").route(web::get().to(SeqHandler(get_facet_normalization))),
+    );
 }
 
 // # Important
@@ -50,6 +62,10 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 pub struct FacetSearchQuery {
     #[deserr(default, error = DeserrJsonError<InvalidFacetSearchQuery>)]
     pub facet_query: Option<String>,
+    /// Returns the facet values starting with any of these prefixes, matched without typos.
+    /// Cannot be used together with `facetQuery`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchFacetQueries>)]
+    pub facet_queries: Option<Vec<String>>,
     #[deserr(error = DeserrJsonError<InvalidFacetSearchFacetName>, missing_field_error = DeserrJsonError::missing_facet_search_facet_name)]
     pub facet_name: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
@@ -60,7 +76,7 @@ pub struct FacetSearchQuery {
     pub media: Option<Value>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
-    #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchFilter>)]
     pub filter: Option<Value>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchMatchingStrategy>, default)]
     pub matching_strategy: MatchingStrategy,
@@ -72,6 +88,157 @@ pub struct FacetSearchQuery {
     pub locales: Option<Vec<Locale>>,
     #[deserr(default, error = DeserrJsonError<InvalidFacetSearchExhaustiveFacetCount>, default)]
     pub exhaustive_facet_count: Option<bool>,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchIncludeNormalized>, default)]
+    pub include_normalized: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchPrefixLastToken>, default)]
+    pub prefix_last_token: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchMinCount>, default)]
+    pub min_count: Option<u64>,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchRankByRelevance>, default)]
+    pub rank_by_relevance: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchIncludeMatchType>, default)]
+    pub include_match_type: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchIncludeStats>, default)]
+    pub include_stats: bool,
+    /// Also returns the number of documents matching the search without the facet field,
+    /// the documents where it is `null` or an empty array are not counted.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchIncludeMissing>, default)]
+    pub include_missing: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchIncludeSortKeys>, default)]
+    pub include_sort_keys: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchMatchTokens>, default)]
+    pub match_tokens: bool,
+    /// Only returns the facet value equal to the facet query once both are normalized, at most
+    /// one hit.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchExact>, default)]
+    pub exact: bool,
+    /// Without a facet query, enumerates the facet values alphabetically whatever
+    /// `sortFacetValuesBy`. The following pages are fetched with `after` and the `nextCursor`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchBrowseMode>, default)]
+    pub browse_mode: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchSortBy>, default)]
+    pub sort_by: Option<FacetValuesSort>,
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchRepresentativeValue>, default)]
+    pub representative_value: Option<FacetSearchRepresentativeValue>,
+    /// Pages through the facet values from the `nextCursor` of the previous page, or from the
+    /// first value with an empty string. Requires the facet values to be sorted with `alpha`,
+    /// or `browseMode`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchAfter>, default)]
+    pub after: Option<String>,
+    /// The ids of the top documents of a prior search, the facet values of these documents are
+    /// returned before the other values.
+    #[schema(value_type = Option<Vec<String>>)]
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchBoostDocumentIds>, default)]
+    pub boost_document_ids: Option<Vec<Value>>,
+    /// Caps the number of typos tolerated on the facet query whatever its length, `0` only
+    /// returning the facet values starting with the query.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchMaxTypos>, default)]
+    pub max_typos: Option<FacetSearchMaxTypos>,
+    /// The maximum number of facet values to return, it cannot exceed the `maxValuesPerFacet`
+    /// faceting setting of the index.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchLimit>, default)]
+    pub limit: Option<usize>,
+    /// A locale identifier like `sv-SE` ordering the facet values sorted with `alpha` following
+    /// the conventions of its language, e.g. `å` after `z` in Swedish.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchCollation>, default)]
+    pub collation: Option<String>,
+    /// Returns the facet values grouped under the first character of their normalized form in
+    /// `facetGroups`, e.g. for an A-Z navigation. The values not starting with a letter are grouped under `#`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchGroupByFirstChar>, default)]
+    pub group_by_first_char: bool,
+    /// Runs the facet search against a point-in-time view of the index kept for a limited time,
+    /// the token of which is returned in `snapshotToken`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchSnapshot>, default)]
+    pub snapshot: bool,
+    /// Runs the facet search against the point-in-time view of a previous facet search made
+    /// with `snapshot`, the counts stay the same whatever documents have been indexed since.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchSnapshotToken>, default)]
+    pub snapshot_token: Option<String>,
+    /// Attaches these fields of a representative document to each facet value in
+    /// `attachedFields`, e.g. the logo of a brand. The representative document is the first
+    /// indexed document matching the search with the value. Only displayed fields are attached.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchAttachFields>, default)]
+    pub attach_fields: Option<Vec<String>>,
+    /// Matches the facet values by their similarity to the facet query, between `0.0` and `1.0`,
+    /// instead of the typo tolerance. Only the values at least this similar are returned, the
+    /// most similar first, along with their `similarity`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchMinSimilarity>, default)]
+    #[schema(value_type = Option<f64>)]
+    pub min_similarity: Option<FacetSearchMinSimilarity>,
+    /// Only returns the facet values matching the accents of the facet query, even when the
+    /// facet values are normalized lossily: `cafe` then matches `cafe` but not `café`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchAccentSensitive>, default)]
+    pub accent_sensitive: bool,
+    /// Also returns the facet values matching the facet query that no document of the search
+    /// has, with a count of `0`, so that the returned values don't change with the filter.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchIncludeZeroCounts>, default)]
+    pub include_zero_counts: bool,
+    /// Returns the facet values closest to the facet query in `suggestions` when no facet value
+    /// matches it, to offer "did you mean" corrections. Values within two typos are suggested.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchSuggestCorrections>, default)]
+    pub suggest_corrections: bool,
+    /// Counts the facet values without the clauses of the `filter` only constraining the searched
+    /// facet, so that the values can be added to the ones already selected with `OR`.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchDisjunctiveOnSelf>, default)]
+    pub disjunctive_on_self: bool,
+    /// Maximum size in bytes of the serialized response. The last `facetHits` not fitting are
+    /// removed and `truncatedBySize` is returned.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchMaxResponseBytes>, default)]
+    pub max_response_bytes: Option<usize>,
+}
+
+/// The maximum number of typos tolerated on a facet query, at most `2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, deserr::Deserr, ToSchema)]
+#[deserr(try_from(u8) = TryFrom::try_from -> InvalidFacetSearchMaxTypos)]
+pub struct FacetSearchMaxTypos(u8);
+
+impl TryFrom<u8> for FacetSearchMaxTypos {
+    type Error = InvalidFacetSearchMaxTypos;
+
+    fn try_from(max_typos: u8) -> Result<Self, Self::Error> {
+        if max_typos > 2 {
+            Err(InvalidFacetSearchMaxTypos)
+        } else {
+            Ok(FacetSearchMaxTypos(max_typos))
+        }
+    }
+}
+
+/// The minimum similarity of the facet values to the facet query, between `0.0` and `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, deserr::Deserr, ToSchema)]
+#[deserr(try_from(f64) = TryFrom::try_from -> InvalidFacetSearchMinSimilarity)]
+pub struct FacetSearchMinSimilarity(f64);
+
+impl TryFrom<f64> for FacetSearchMinSimilarity {
+    type Error = InvalidFacetSearchMinSimilarity;
+
+    fn try_from(min_similarity: f64) -> Result<Self, Self::Error> {
+        if (0.0..=1.0).contains(&min_similarity) {
+            Ok(FacetSearchMinSimilarity(min_similarity))
+        } else {
+            Err(InvalidFacetSearchMinSimilarity)
+        }
+    }
+}
+
+/// The value returned for the facet values that are equal once normalized, e.g. `Café` and `cafe`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, deserr::Deserr, ToSchema, serde::Serialize)]
+#[deserr(rename_all = camelCase)]
+#[serde(rename_all = "camelCase")]
+pub enum FacetSearchRepresentativeValue {
+    /// The first value in alphabetic order.
+    First,
+    /// The value matching the most documents.
+    MostFrequent,
+}
+
+impl From<FacetSearchRepresentativeValue> for FacetRepresentativeValue {
+    fn from(other: FacetSearchRepresentativeValue) -> Self {
+        match other {
+            FacetSearchRepresentativeValue::First => Self::First,
+            FacetSearchRepresentativeValue::MostFrequent => Self::MostFrequent,
+        }
+    }
 }
 
 #[derive(Default)]
@@ -86,13 +253,24 @@ pub struct FacetSearchAggregator {
 
     // As there been any other parameter than the facetName or facetQuery ones?
     additional_search_parameters_provided: bool,
+
+    // facetQuery
+    total_with_facet_query: usize,
+    facet_query_sum_of_lengths: usize,
+
+    // filter
+    total_with_filter: usize,
+
+    // results
+    total_zero_hits: usize,
 }
 
 impl FacetSearchAggregator {
     #[allow(clippy::field_reassign_with_default)]
     pub fn from_query(query: &FacetSearchQuery) -> Self {
         let FacetSearchQuery {
-            facet_query: _,
+            facet_query,
+            facet_queries,
             facet_name,
             vector,
             q,
@@ -104,12 +282,41 @@ impl FacetSearchAggregator {
             ranking_score_threshold,
             locales,
             exhaustive_facet_count,
+            include_normalized,
+            prefix_last_token,
+            min_count,
+            rank_by_relevance,
+            include_match_type,
+            include_stats,
+            include_missing,
+            include_sort_keys,
+            match_tokens,
+            exact,
+            browse_mode,
+            sort_by,
+            representative_value,
+            after,
+            boost_document_ids,
+            max_typos,
+            limit,
+            collation,
+            group_by_first_char,
+            snapshot,
+            snapshot_token,
+            attach_fields,
+            min_similarity,
+            accent_sensitive,
+            include_zero_counts,
+            suggest_corrections,
+            disjunctive_on_self,
+            max_response_bytes,
         } = query;
 
         Self {
             total_received: 1,
             facet_names: Some(facet_name.clone()).into_iter().collect(),
             additional_search_parameters_provided: q.is_some()
+                || facet_queries.is_some()
                 || vector.is_some()
                 || media.is_some()
                 || filter.is_some()
@@ -118,14 +325,60 @@ impl FacetSearchAggregator {
                 || hybrid.is_some()
                 || ranking_score_threshold.is_some()
                 || locales.is_some()
-                || exhaustive_facet_count.is_some(),
+                || exhaustive_facet_count.is_some()
+                || *include_normalized
+                || *prefix_last_token
+                || min_count.is_some()
+                || *rank_by_relevance
+                || *include_match_type
+                || *include_stats
+                || *include_missing
+                || *include_sort_keys
+                || *match_tokens
+                || *exact
+                || *browse_mode
+                || sort_by.is_some()
+                || representative_value.is_some()
+                || after.is_some()
+                || boost_document_ids.is_some()
+                || max_typos.is_some()
+                || limit.is_some()
+                || collation.is_some()
+                || *group_by_first_char
+                || *snapshot
+                || snapshot_token.is_some()
+                || attach_fields.is_some()
+                || min_similarity.is_some()
+                || *accent_sensitive
+                || *include_zero_counts
+                || *suggest_corrections
+                || *disjunctive_on_self
+                || max_response_bytes.is_some(),
+            total_with_facet_query: facet_query.is_some() as usize,
+            facet_query_sum_of_lengths: facet_query.as_ref().map_or(0, |q| q.chars().count()),
+            total_with_filter: filter.is_some() as usize,
             ..Default::default()
         }
     }
 
     pub fn succeed(&mut self, result: &FacetSearchResult) {
-        let FacetSearchResult { facet_hits: _, facet_query: _, processing_time_ms } = result;
+        let FacetSearchResult {
+            facet_hits,
+            facet_query: _,
+            processing_time_ms,
+            facet_search_enabled: _,
+            applied_limit: _,
+            limit_source: _,
+            stats: _,
+            missing_count: _,
+            facet_groups: _,
+            next_cursor: _,
+            snapshot_token: _,
+            suggestions: _,
+            truncated_by_size: _,
+        } = result;
         self.total_succeeded = 1;
+        self.total_zero_hits = facet_hits.is_empty() as usize;
         self.time_spent.push(*processing_time_ms as usize);
     }
 }
@@ -147,6 +400,14 @@ impl Aggregate for FacetSearchAggregator {
             facet_names: self.facet_names.union(&new.facet_names).cloned().collect(),
             additional_search_parameters_provided: self.additional_search_parameters_provided
                 | new.additional_search_parameters_provided,
+            total_with_facet_query: self
+                .total_with_facet_query
+                .saturating_add(new.total_with_facet_query),
+            facet_query_sum_of_lengths: self
+                .facet_query_sum_of_lengths
+                .saturating_add(new.facet_query_sum_of_lengths),
+            total_with_filter: self.total_with_filter.saturating_add(new.total_with_filter),
+            total_zero_hits: self.total_zero_hits.saturating_add(new.total_zero_hits),
         })
     }
 
@@ -157,6 +418,10 @@ impl Aggregate for FacetSearchAggregator {
             time_spent,
             facet_names,
             additional_search_parameters_provided,
+            total_with_facet_query,
+            facet_query_sum_of_lengths,
+            total_with_filter,
+            total_zero_hits,
         } = *self;
         // the index of the 99th percentage of value
         let percentile_99th = 0.99 * (total_succeeded as f64 - 1.) + 1.;
@@ -164,6 +429,14 @@ impl Aggregate for FacetSearchAggregator {
         let time_spent = time_spent.into_sorted_vec();
         // We are only interested by the slowest value of the 99th fastest results
         let time_spent = time_spent.get(percentile_99th as usize);
+        // the averages and rates are 0 rather than NaN when nothing was counted
+        let ratio = |count: usize, total: usize| {
+            if total > 0 {
+                count as f64 / total as f64
+            } else {
+                0.0
+            }
+        };
 
         serde_json::json!({
             "requests": {
@@ -176,6 +449,18 @@ impl Aggregate for FacetSearchAggregator {
                 "total_distinct_facet_count": facet_names.len(),
                 "additional_search_parameters_provided": additional_search_parameters_provided,
             },
+            "facet_query": {
+                "total_with_facet_query": total_with_facet_query,
+                "avg_length": format!("{:.2}", ratio(facet_query_sum_of_lengths, total_with_facet_query)),
+            },
+            "filter": {
+                "total_with_filter": total_with_filter,
+                "with_filter_rate": format!("{:.2}", ratio(total_with_filter, total_received)),
+            },
+            "results": {
+                "total_zero_hits": total_zero_hits,
+                "zero_hits_rate": format!("{:.2}", ratio(total_zero_hits, total_succeeded)),
+            },
         })
     }
 }
@@ -232,17 +517,29 @@ impl Aggregate for FacetSearchAggregator {
                 "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
             }
         )),
+        (status = 429, description = "The facet search rate limit of the index is exceeded", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Too many facet search requests on the index `movies`, the limit is 10 requests per second. Retry later.",
+                "code": "too_many_facet_search_requests",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#too_many_facet_search_requests"
+            }
+        )),
     )
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn search(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     search_queue: Data<SearchQueue>,
+    facet_search_snapshots: Data<FacetSearchSnapshots>,
+    facet_search_rate_limiter: Data<FacetSearchRateLimiter>,
     index_uid: web::Path<String>,
     params: AwebJson<FacetSearchQuery, DeserrJsonError>,
     req: HttpRequest,
     analytics: web::Data<Analytics>,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    facet_search_rate_limiter.try_acquire(&index_uid)?;
 
     let query = params.into_inner();
     debug!(parameters = ?query, "Facet search");
@@ -250,8 +547,108 @@ pub async fn search(
     let mut aggregate = FacetSearchAggregator::from_query(&query);
 
     let facet_query = query.facet_query.clone();
+    let facet_queries = query.facet_queries.clone();
+    if facet_query.is_some() && facet_queries.is_some() {
+        return Err(ResponseError::from_msg(
+            "`facetQueries` cannot be used together with `facetQuery`.".to_string(),
+            Code::InvalidFacetSearchFacetQueries,
+        ));
+    }
     let facet_name = query.facet_name.clone();
+    let include_normalized = query.include_normalized;
+    let prefix_last_token = query.prefix_last_token;
+    let min_count = query.min_count;
+    let rank_by_relevance = query.rank_by_relevance;
+    let include_match_type = query.include_match_type;
+    let include_stats = query.include_stats;
+    let include_missing = query.include_missing;
+    let include_sort_keys = query.include_sort_keys;
+    let match_tokens = query.match_tokens;
+    let exact = query.exact;
+    let browse_mode = query.browse_mode;
+    let sort_by = query.sort_by.map(Into::into);
+    let representative_value = query.representative_value.map(Into::into);
+    let after = query.after.clone();
+    let max_typos = query.max_typos.map(|FacetSearchMaxTypos(max_typos)| max_typos);
+    let limit = query.limit;
+    let collation = match &query.collation {
+        Some(locale) => Some(locale.parse::<FacetCollation>().map_err(|error| {
+            let msg = format!("Invalid value at `.collation`: {error}");
+            ResponseError::from_msg(msg, Code::InvalidFacetSearchCollation)
+        })?),
+        None => None,
+    };
+    let group_by_first_char = query.group_by_first_char;
+    let attach_fields = query.attach_fields.clone();
+    let min_similarity =
+        query.min_similarity.map(|FacetSearchMinSimilarity(min_similarity)| min_similarity);
+    let accent_sensitive = query.accent_sensitive;
+    let include_zero_counts = query.include_zero_counts;
+    let suggest_corrections = query.suggest_corrections;
+    let disjunctive_on_self = query.disjunctive_on_self;
+    let max_response_bytes = query.max_response_bytes;
+    if query.snapshot && query.snapshot_token.is_some() {
+        return Err(ResponseError::from_msg(
+            "`snapshot` cannot be used together with `snapshotToken`.".to_string(),
+            Code::InvalidFacetSearchSnapshot,
+        ));
+    }
+    let create_snapshot = query.snapshot;
+    let snapshot_token = match &query.snapshot_token {
+        Some(token) => Some(token.parse::<Uuid>().map_err(|_| {
+            let msg = format!(
+                "Invalid value at `.snapshotToken`: `{token}` is not a valid snapshot token."
+            );
+            ResponseError::from_msg(msg, Code::InvalidFacetSearchSnapshotToken)
+        })?),
+        None => None,
+    };
+    let boost_document_ids = match &query.boost_document_ids {
+        Some(ids) => {
+            let mut parsed_ids = Vec::with_capacity(ids.len());
+            for (index, id) in ids.iter().enumerate() {
+                let id: ExternalDocumentId = id.clone().try_into().map_err(|error| {
+                    let msg = format!("In `.boostDocumentIds[{index}]`: {error}");
+                    ResponseError::from_msg(msg, Code::InvalidFacetSearchBoostDocumentIds)
+                })?;
+                parsed_ids.push(id)
+            }
+            Some(parsed_ids)
+        }
+        None => None,
+    };
     let locales = query.locales.clone().map(|l| l.into_iter().map(Into::into).collect());
+    let options = FacetSearchOptions {
+        facet_query,
+        facet_queries,
+        facet_name,
+        locales,
+        include_normalized,
+        prefix_last_token,
+        min_count,
+        rank_by_relevance,
+        include_match_type,
+        include_stats,
+        include_missing,
+        include_sort_keys,
+        match_tokens,
+        exact,
+        browse_mode,
+        sort_by,
+        representative_value,
+        after,
+        boost_document_ids,
+        max_typos,
+        limit,
+        collation,
+        group_by_first_char,
+        attach_fields,
+        min_similarity,
+        accent_sensitive,
+        include_zero_counts,
+        suggest_corrections,
+        disjunctive_on_self,
+    };
     let mut search_query = SearchQuery::from(query);
 
     // Tenant token search_rules.
@@ -259,19 +656,42 @@ pub async fn search(
         add_search_rules(&mut search_query.filter, search_rules);
     }
 
+    // A snapshot must be searched through the index it was taken on.
     let index = index_scheduler.index(&index_uid)?;
+    let snapshot = match snapshot_token {
+        Some(token) => Some(facet_search_snapshots.get(&index_uid, &index, token).ok_or_else(|| {
+            let msg = format!(
+                "Invalid value at `.snapshotToken`: the snapshot `{token}` does not exist on the index `{index_uid}` or has expired."
+            );
+            ResponseError::from_msg(msg, Code::InvalidFacetSearchSnapshotToken)
+        })?),
+        None if create_snapshot => Some(facet_search_snapshots.create(&index_uid, index.clone())?),
+        None => None,
+    };
+    let index = match &snapshot {
+        Some(snapshot) => snapshot.index().clone(),
+        None => index,
+    };
     let search_kind = search_kind(&search_query, &index_scheduler, index_uid.to_string(), &index)?;
     let permit = search_queue.try_get_search_permit().await?;
     let search_result = tokio::task::spawn_blocking(move || {
-        perform_facet_search(
-            &index,
-            search_query,
-            facet_query,
-            facet_name,
-            search_kind,
-            index_scheduler.features(),
-            locales,
-        )
+        let search = |rtxn: &RoTxn| {
+            perform_facet_search(
+                &index,
+                rtxn,
+                search_query,
+                options,
+                search_kind,
+                index_scheduler.features(),
+            )
+        };
+        match snapshot {
+            Some(snapshot) => search(&*snapshot.read_txn()).map(|mut result| {
+                result.snapshot_token = Some(snapshot.token().to_string());
+                result
+            }),
+            None => search(&index.read_txn()?),
+        }
     })
     .await;
     permit.drop().await;
@@ -282,16 +702,111 @@ pub async fn search(
     }
     analytics.publish(aggregate, &req);
 
-    let search_result = search_result?;
+    let mut search_result = search_result?;
+    if let Some(max_bytes) = max_response_bytes {
+        search_result.truncate_to_size(max_bytes).map_err(MeilisearchHttpError::from)?;
+    }
 
     debug!(returns = ?search_result, "Facet search");
     Ok(HttpResponse::Ok().json(search_result))
 }
 
+/// How the string facet values of a field were normalized at indexing time.
+///
+/// The facet search queries are normalized the same way, clients preprocessing
+/// them must apply the same normalization to get the same matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetNormalizationView {
+    /// Whether the facet values of the field can be searched.
+    pub facet_search: bool,
+    /// Whether the values are decomposed and stripped of their accents, `café` matching `cafe`.
+    pub lossy: bool,
+    /// The locales of the field, `null` when the script and language are detected from each value.
+    pub locales: Option<Vec<Locale>>,
+    /// Whether the case of the values is kept, the values are currently always lowercased.
+    pub case_sensitive: bool,
+}
+
+impl From<FacetNormalization> for FacetNormalizationView {
+    fn from(
+        FacetNormalization { facet_search, lossy, locales, case_sensitive }: FacetNormalization,
+    ) -> Self {
+        Self {
+            facet_search,
+            lossy,
+            locales: locales.map(|locales| locales.into_iter().map(Locale::from).collect()),
+            case_sensitive,
+        }
+    }
+}
+
+/// Get the facet normalization
+///
+/// Get how the string facet values of every filterable attribute were normalized at indexing time.
+#[utoipa::path(
+    get,
+    path = "{indexUid}/facet-search/normalization",
+    tag = "Facet Search",
+    security(("Bearer" = ["search", "*"])),
+    params(("indexUid", example = "movies", description = "Index Unique Identifier", nullable = false)),
+    responses(
+        (status = 200, description = "The normalization of the filterable attributes", body = BTreeMap<String, FacetNormalizationView>, content_type = "application/json", example = json!(
+            {
+                "genres": {
+                    "facetSearch": true,
+                    "lossy": true,
+                    "locales": null,
+                    "caseSensitive": false
+                },
+                "title": {
+                    "facetSearch": false,
+                    "lossy": false,
+                    "locales": ["fra"],
+                    "caseSensitive": false
+                }
+            }
+        )),
+        (status = 404, description = "Index not found", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Index `movies` not found.",
+                "code": "index_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#index_not_found"
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn get_facet_normalization(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let index = index_scheduler.index(&index_uid)?;
+    let rtxn = index.read_txn()?;
+    let normalizations: BTreeMap<_, FacetNormalizationView> = index
+        .facet_normalizations(&rtxn)?
+        .into_iter()
+        .map(|(name, normalization)| (name, normalization.into()))
+        .collect();
+
+    debug!(returns = ?normalizations, "Get facet normalization");
+    Ok(HttpResponse::Ok().json(normalizations))
+}
+
 impl From<FacetSearchQuery> for SearchQuery {
     fn from(value: FacetSearchQuery) -> Self {
         let FacetSearchQuery {
             facet_query: _,
+            facet_queries: _,
             facet_name: _,
             q,
             vector,
@@ -303,6 +818,34 @@ impl From<FacetSearchQuery> for SearchQuery {
             ranking_score_threshold,
             locales,
             exhaustive_facet_count,
+            include_normalized: _,
+            prefix_last_token: _,
+            min_count: _,
+            rank_by_relevance: _,
+            include_match_type: _,
+            include_stats: _,
+            include_missing: _,
+            include_sort_keys: _,
+            match_tokens: _,
+            exact: _,
+            browse_mode: _,
+            sort_by: _,
+            representative_value: _,
+            after: _,
+            boost_document_ids: _,
+            max_typos: _,
+            limit: _,
+            collation: _,
+            group_by_first_char: _,
+            snapshot: _,
+            snapshot_token: _,
+            attach_fields: _,
+            min_similarity: _,
+            accent_sensitive: _,
+            include_zero_counts: _,
+            suggest_corrections: _,
+            disjunctive_on_self: _,
+            max_response_bytes: _,
         } = value;
 
         // If exhaustive_facet_count is true, we need to set the page to 0
@@ -334,6 +877,12 @@ impl From<FacetSearchQuery> for SearchQuery {
             sort: None,
             distinct: None,
             facets: None,
+            min_count: None,
+            facet_level: None,
+            facet_sample_size: None,
+            facet_sample_seed: None,
+            facet_ranges: None,
+            geo_facet: None,
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
             crop_marker: DEFAULT_CROP_MARKER(),
@@ -0,0 +1,81 @@
+use meilisearch_types::milli::FacetValueHit;
+use serde_json::json;
+
+use crate::analytics::Aggregate;
+use crate::routes::indexes::facet_search::{FacetSearchAggregator, FacetSearchQuery};
+use crate::search::{FacetSearchLimitSource, FacetSearchResult};
+
+fn facet_search_result(hits: &[&str]) -> FacetSearchResult {
+    FacetSearchResult {
+        facet_hits: hits
+            .iter()
+            .map(|value| FacetValueHit {
+                value: value.to_string(),
+                count: 1,
+                normalized_value: None,
+                match_type: None,
+                sort_key: None,
+                attached_fields: None,
+                similarity: None,
+            })
+            .collect(),
+        facet_query: None,
+        processing_time_ms: 1,
+        facet_search_enabled: true,
+        applied_limit: 100,
+        limit_source: FacetSearchLimitSource::System,
+        stats: None,
+        missing_count: None,
+        facet_groups: None,
+        next_cursor: None,
+        snapshot_token: None,
+        suggestions: None,
+        truncated_by_size: false,
+    }
+}
+
+#[test]
+fn test_facet_search_aggregator_counters() {
+    let query = FacetSearchQuery {
+        facet_name: "genres".to_string(),
+        facet_query: Some("adven".to_string()),
+        filter: Some(json!("year > 2000")),
+        ..Default::default()
+    };
+    let mut first = FacetSearchAggregator::from_query(&query);
+    first.succeed(&facet_search_result(&["Adventure"]));
+
+    let query = FacetSearchQuery {
+        facet_name: "genres".to_string(),
+        facet_query: Some("sf".to_string()),
+        ..Default::default()
+    };
+    let mut second = FacetSearchAggregator::from_query(&query);
+    second.succeed(&facet_search_result(&[]));
+
+    let query = FacetSearchQuery { facet_name: "author".to_string(), ..Default::default() };
+    let mut third = FacetSearchAggregator::from_query(&query);
+    third.succeed(&facet_search_result(&[]));
+
+    let aggregate = Box::new(first).aggregate(Box::new(second)).aggregate(Box::new(third));
+    let event = aggregate.into_event();
+
+    assert_eq!(event["requests"]["total_received"], json!(3));
+    assert_eq!(event["requests"]["total_succeeded"], json!(3));
+    assert_eq!(event["facets"]["total_distinct_facet_count"], json!(2));
+    assert_eq!(event["facet_query"], json!({ "total_with_facet_query": 2, "avg_length": "3.50" }));
+    assert_eq!(event["filter"], json!({ "total_with_filter": 1, "with_filter_rate": "0.33" }));
+    assert_eq!(event["results"], json!({ "total_zero_hits": 2, "zero_hits_rate": "0.67" }));
+}
+
+#[test]
+fn test_facet_search_aggregator_without_facet_query_nor_success() {
+    let query = FacetSearchQuery { facet_name: "genres".to_string(), ..Default::default() };
+    // the request failed, it is not counted as succeeded
+    let aggregate = Box::new(FacetSearchAggregator::from_query(&query));
+    let event = aggregate.into_event();
+
+    assert_eq!(event["requests"]["total_succeeded"], json!(0));
+    assert_eq!(event["facet_query"], json!({ "total_with_facet_query": 0, "avg_length": "0.00" }));
+    assert_eq!(event["results"], json!({ "total_zero_hits": 0, "zero_hits_rate": "0.00" }));
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::Infallible;
 
 use actix_web::web::Data;
@@ -11,6 +11,7 @@ use meilisearch_types::deserr::{immutable_field_error, DeserrJsonError, DeserrQu
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::index::FacetTreeStats;
 use meilisearch_types::milli::{self, FieldDistribution, Index};
 use meilisearch_types::tasks::KindWithContent;
 use serde::Serialize;
@@ -32,7 +33,10 @@ use crate::Opt;
 pub mod compact;
 pub mod documents;
 
+pub mod facet_distribution;
 pub mod facet_search;
+#[cfg(test)]
+mod facet_search_test;
 pub mod search;
 mod search_analytics;
 #[cfg(test)]
@@ -46,6 +50,7 @@ mod similar_analytics;
 #[openapi(
     nest(
         (path = "/", api = documents::DocumentsApi),
+        (path = "/", api = facet_distribution::FacetDistributionApi),
         (path = "/", api = facet_search::FacetSearchApi),
         (path = "/", api = similar::SimilarApi),
         (path = "/", api = settings::SettingsApi),
@@ -79,6 +84,7 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .service(web::resource("/stats").route(web::get().to(SeqHandler(get_index_stats))))
             .service(web::scope("/documents").configure(documents::configure))
             .service(web::scope("/search").configure(search::configure))
+            .service(web::scope("/facet-distribution").configure(facet_distribution::configure))
             .service(web::scope("/facet-search").configure(facet_search::configure))
             .service(web::scope("/similar").configure(similar::configure))
             .service(web::scope("/settings").configure(settings::configure))
@@ -579,6 +585,26 @@ pub struct IndexStats {
     /// Association of every field name with the number of times it occurs in the documents.
     #[schema(value_type = HashMap<String, u64>)]
     pub field_distribution: FieldDistribution,
+    /// Association of every faceted field name with the shape of its facet trees.
+    /// Only returned when the `facetTrees` query parameter is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_trees: Option<BTreeMap<String, FacetTreeView>>,
+}
+
+/// The shape of the facet trees of a field.
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetTreeView {
+    /// Number of levels of the deepest facet tree of the field, level 0 included.
+    pub facet_levels: u8,
+    /// Number of nodes in all the levels of the facet trees of the field.
+    pub facet_node_count: u64,
+}
+
+impl From<FacetTreeStats> for FacetTreeView {
+    fn from(FacetTreeStats { levels, node_count }: FacetTreeStats) -> Self {
+        FacetTreeView { facet_levels: levels, facet_node_count: node_count }
+    }
 }
 
 impl From<index_scheduler::IndexStats> for IndexStats {
@@ -594,10 +620,21 @@ impl From<index_scheduler::IndexStats> for IndexStats {
             number_of_embeddings: stats.inner_stats.number_of_embeddings,
             number_of_embedded_documents: stats.inner_stats.number_of_embedded_documents,
             field_distribution: stats.inner_stats.field_distribution,
+            facet_trees: None,
         }
     }
 }
 
+#[derive(Deserr, Debug, Clone, Copy, IntoParams)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+#[into_params(rename_all = "camelCase", parameter_in = Query)]
+pub struct IndexStatsQuery {
+    /// Also return the depth and number of nodes of the facet trees of every faceted field
+    #[param(value_type = Option<bool>, default, example = true)]
+    #[deserr(default, error = DeserrQueryParamError<InvalidIndexStatsFacetTrees>)]
+    pub facet_trees: Param<bool>,
+}
+
 /// Get stats of index
 ///
 /// Get the stats of an index.
@@ -606,7 +643,7 @@ impl From<index_scheduler::IndexStats> for IndexStats {
     path = "/{indexUid}/stats",
     tag = "Stats",
     security(("Bearer" = ["stats.get", "stats.*", "*"])),
-    params(("indexUid", example = "movies", description = "Index Unique Identifier", nullable = false)),
+    params(("indexUid", example = "movies", description = "Index Unique Identifier", nullable = false), IndexStatsQuery),
     responses(
         (status = OK, description = "The stats of the index", body = IndexStats, content_type = "application/json", example = json!(
             {
@@ -643,9 +680,20 @@ impl From<index_scheduler::IndexStats> for IndexStats {
 pub async fn get_index_stats(
     index_scheduler: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<IndexScheduler>>,
     index_uid: web::Path<String>,
+    params: AwebQueryParameter<IndexStatsQuery, DeserrQueryParamError>,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
-    let stats = IndexStats::from(index_scheduler.index_stats(&index_uid)?);
+    let index_stats = index_scheduler.index_stats(&index_uid)?;
+    let mut stats = IndexStats::from(index_stats);
+
+    // walking the facet trees reads every facet entry, so it is only done when asked for
+    if *params.facet_trees {
+        let index = index_scheduler.index(&index_uid)?;
+        let rtxn = index.read_txn()?;
+        let facet_tree_stats = index.facet_tree_stats(&rtxn)?;
+        stats.facet_trees =
+            Some(facet_tree_stats.into_iter().map(|(name, tree)| (name, tree.into())).collect());
+    }
 
     debug!(returns = ?stats, "Get index stats");
     Ok(HttpResponse::Ok().json(stats))
@@ -1,3 +1,5 @@
+use std::num::NonZeroUsize;
+
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
@@ -109,6 +111,18 @@ pub struct SearchQueryGet {
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacets>)]
     #[param(value_type = Vec<String>, explode = false)]
     facets: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchMinCount>)]
+    #[param(value_type = Option<u64>)]
+    min_count: Option<Param<u64>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacetLevel>)]
+    #[param(value_type = Option<u8>)]
+    facet_level: Option<Param<u8>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacetSampleSize>)]
+    #[param(value_type = Option<usize>)]
+    facet_sample_size: Option<Param<NonZeroUsize>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacetSampleSeed>)]
+    #[param(value_type = Option<u64>)]
+    facet_sample_seed: Option<Param<u64>>,
     #[deserr(default = DEFAULT_HIGHLIGHT_PRE_TAG(), error = DeserrQueryParamError<InvalidSearchHighlightPreTag>)]
     #[param(default = DEFAULT_HIGHLIGHT_PRE_TAG)]
     highlight_pre_tag: String,
@@ -231,6 +245,12 @@ impl TryFrom<SearchQueryGet> for SearchQuery {
             show_ranking_score: other.show_ranking_score.0,
             show_ranking_score_details: other.show_ranking_score_details.0,
             facets: other.facets.map(|o| o.into_iter().collect()),
+            min_count: other.min_count.as_deref().copied(),
+            facet_level: other.facet_level.as_deref().copied(),
+            facet_sample_size: other.facet_sample_size.as_deref().copied(),
+            facet_sample_seed: other.facet_sample_seed.as_deref().copied(),
+            facet_ranges: None,
+            geo_facet: None,
             highlight_pre_tag: other.highlight_pre_tag,
             highlight_post_tag: other.highlight_post_tag,
             crop_marker: other.crop_marker,
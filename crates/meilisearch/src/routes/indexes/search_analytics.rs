@@ -125,6 +125,12 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
             sort,
             distinct,
             facets: _,
+            min_count: _,
+            facet_level: _,
+            facet_sample_size: _,
+            facet_sample_seed: _,
+            facet_ranges: _,
+            geo_facet: _,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -243,6 +249,9 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
             semantic_hit_count: _,
             facet_distribution: _,
             facet_stats: _,
+            facet_distribution_stats: _,
+            facet_ranges: _,
+            geo_facet: _,
             degraded,
             used_negative_operator,
             request_uid: _,
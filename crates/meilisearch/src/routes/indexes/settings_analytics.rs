@@ -11,8 +11,9 @@ use meilisearch_types::milli::update::Setting;
 use meilisearch_types::milli::vector::VectorStoreBackend;
 use meilisearch_types::milli::FilterableAttributesRule;
 use meilisearch_types::settings::{
-    ChatSettings, FacetingSettings, PaginationSettings, PrefixSearchSettings,
-    ProximityPrecisionView, RankingRuleView, SettingEmbeddingSettings, TypoSettings,
+    ChatSettings, FacetingModeSettings, FacetingSettings, FacetingWarmupSettings,
+    PaginationSettings, PrefixSearchSettings, ProximityPrecisionView, RankingRuleView,
+    SettingEmbeddingSettings, TypoSettings,
 };
 use serde::Serialize;
 
@@ -129,6 +130,27 @@ impl Aggregate for SettingsAnalytics {
                     .faceting
                     .max_values_per_facet
                     .or(self.faceting.max_values_per_facet),
+                max_values_per_document_field: new
+                    .faceting
+                    .max_values_per_document_field
+                    .or(self.faceting.max_values_per_document_field),
+                track_values_recency: new
+                    .faceting
+                    .track_values_recency
+                    .or(self.faceting.track_values_recency),
+                max_facet_levels: new.faceting.max_facet_levels.or(self.faceting.max_facet_levels),
+                expected_cardinality_total: new
+                    .faceting
+                    .expected_cardinality_total
+                    .or(self.faceting.expected_cardinality_total),
+                update_method_total: new
+                    .faceting
+                    .update_method_total
+                    .or(self.faceting.update_method_total),
+                value_weight_fields_total: new
+                    .faceting
+                    .value_weight_fields_total
+                    .or(self.faceting.value_weight_fields_total),
                 sort_facet_values_by_star_count: new
                     .faceting
                     .sort_facet_values_by_star_count
@@ -137,6 +159,12 @@ impl Aggregate for SettingsAnalytics {
                     .faceting
                     .sort_facet_values_by_total
                     .or(self.faceting.sort_facet_values_by_total),
+                mode: new.faceting.mode.or(self.faceting.mode),
+                warmup: new.faceting.warmup.or(self.faceting.warmup),
+                pinned_distributions_total: new
+                    .faceting
+                    .pinned_distributions_total
+                    .or(self.faceting.pinned_distributions_total),
             },
             pagination: PaginationAnalytics {
                 max_total_hits: new.pagination.max_total_hits.or(self.pagination.max_total_hits),
@@ -430,14 +458,39 @@ impl TypoToleranceAnalytics {
 #[derive(Serialize, Default)]
 pub struct FacetingAnalytics {
     pub max_values_per_facet: Option<usize>,
+    pub max_values_per_document_field: Option<usize>,
+    pub track_values_recency: Option<bool>,
+    pub max_facet_levels: Option<usize>,
+    pub expected_cardinality_total: Option<usize>,
+    pub update_method_total: Option<usize>,
+    pub value_weight_fields_total: Option<usize>,
     pub sort_facet_values_by_star_count: Option<bool>,
     pub sort_facet_values_by_total: Option<usize>,
+    pub mode: Option<FacetingModeSettings>,
+    pub warmup: Option<FacetingWarmupSettings>,
+    pub pinned_distributions_total: Option<usize>,
 }
 
 impl FacetingAnalytics {
     pub fn new(setting: Option<&FacetingSettings>) -> Self {
         Self {
             max_values_per_facet: setting.as_ref().and_then(|s| s.max_values_per_facet.set()),
+            max_values_per_document_field: setting
+                .as_ref()
+                .and_then(|s| s.max_values_per_document_field.set().map(|max| max.get())),
+            track_values_recency: setting.as_ref().and_then(|s| s.track_values_recency.set()),
+            max_facet_levels: setting
+                .as_ref()
+                .and_then(|s| s.max_facet_levels.set().map(|max| max.get())),
+            expected_cardinality_total: setting
+                .as_ref()
+                .and_then(|s| s.expected_cardinality.as_ref().set().map(|s| s.len())),
+            update_method_total: setting
+                .as_ref()
+                .and_then(|s| s.update_method.as_ref().set().map(|s| s.len())),
+            value_weight_fields_total: setting
+                .as_ref()
+                .and_then(|s| s.value_weight_fields.as_ref().set().map(|s| s.len())),
             sort_facet_values_by_star_count: setting.as_ref().and_then(|s| {
                 s.sort_facet_values_by
                     .as_ref()
@@ -447,6 +500,11 @@ impl FacetingAnalytics {
             sort_facet_values_by_total: setting
                 .as_ref()
                 .and_then(|s| s.sort_facet_values_by.as_ref().set().map(|s| s.len())),
+            mode: setting.as_ref().and_then(|s| s.mode.set()),
+            warmup: setting.as_ref().and_then(|s| s.warmup.set()),
+            pinned_distributions_total: setting
+                .as_ref()
+                .and_then(|s| s.pinned_distributions.as_ref().set().map(|s| s.len())),
         }
     }
 
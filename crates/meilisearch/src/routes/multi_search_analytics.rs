@@ -59,6 +59,12 @@ impl MultiSearchAggregator {
             sort: _,
             distinct: _,
             facets: _,
+            min_count: _,
+            facet_level: _,
+            facet_sample_size: _,
+            facet_sample_seed: _,
+            facet_ranges: _,
+            geo_facet: _,
             highlight_pre_tag: _,
             highlight_post_tag: _,
             crop_marker: _,
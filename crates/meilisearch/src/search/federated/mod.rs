@@ -1,6 +1,8 @@
 mod perform;
 mod proxy;
 mod types;
+#[cfg(test)]
+mod types_test;
 mod weighted_scores;
 
 pub use perform::perform_federated_search;
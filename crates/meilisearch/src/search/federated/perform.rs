@@ -1081,6 +1081,7 @@ impl SearchByIndex {
             .map(|facets_by_index| {
                 compute_facet_distribution_stats(
                     &facets_by_index,
+                    Default::default(),
                     &index,
                     &rtxn,
                     candidates,
@@ -1147,6 +1148,7 @@ impl SearchByIndex {
             if let Some(facets) = facets {
                 if let Err(mut error) = compute_facet_distribution_stats(
                     &facets,
+                    Default::default(),
                     &index,
                     &rtxn,
                     Default::default(),
@@ -242,10 +242,13 @@ impl FederatedFacets {
                     Entry::Occupied(mut entry) => {
                         let distribution = entry.get_mut();
 
+                        // the counts of huge indexes must not wrap around once summed
                         for (value, index_count) in index_distribution {
                             distribution
                                 .entry(value)
-                                .and_modify(|count| *count += index_count)
+                                .and_modify(|count: &mut u64| {
+                                    *count = count.saturating_add(index_count)
+                                })
                                 .or_insert(index_count);
                         }
                     }
@@ -271,18 +274,7 @@ impl FederatedFacets {
         for (facet, values) in &mut distribution {
             let order_by = facet_order.get(facet).map(|(_, order)| *order).unwrap_or_default();
 
-            match order_by {
-                OrderBy::Lexicographic => {
-                    values.sort_unstable_by(|left, _, right, _| left.cmp(right))
-                }
-                OrderBy::Count => {
-                    values.sort_unstable_by(|_, left, _, right| {
-                        left.cmp(right)
-                            // biggest first
-                            .reverse()
-                    })
-                }
-            }
+            sort_facet_values(values, order_by);
 
             if let Some(max_values_per_facet) = max_values_per_facet {
                 values.truncate(max_values_per_facet)
@@ -312,8 +304,8 @@ impl FederatedFacets {
             for (remote_facet, remote_values) in remote_facets.distribution {
                 let merged_facet = merged_facets.distribution.entry(remote_facet).or_default();
                 for (remote_value, remote_count) in remote_values {
-                    let count = merged_facet.entry(remote_value).or_default();
-                    *count += remote_count;
+                    let count: &mut u64 = merged_facet.entry(remote_value).or_default();
+                    *count = count.saturating_add(remote_count);
                 }
             }
         }
@@ -325,20 +317,31 @@ impl FederatedFacets {
                 continue;
             };
             for (facet, values) in &mut facets.distribution {
-                match order_by.get(facet) {
-                    OrderBy::Lexicographic => {
-                        values.sort_unstable_by(|left, _, right, _| left.cmp(right))
-                    }
-                    OrderBy::Count => {
-                        values.sort_unstable_by(|_, left, _, right| {
-                            left.cmp(right)
-                                // biggest first
-                                .reverse()
-                        })
-                    }
-                }
+                sort_facet_values(values, order_by.get(facet));
                 values.truncate(*max_values_per_facet);
             }
         }
     }
 }
+
+fn sort_facet_values(values: &mut IndexMap<String, u64>, order_by: OrderBy) {
+    match order_by {
+        OrderBy::Lexicographic => values.sort_unstable_by(|left, _, right, _| left.cmp(right)),
+        // the recency and weight of the values are only known by their index, they keep the merged order
+        OrderBy::Recency | OrderBy::Weight => (),
+        // the scores of the documents of different indexes can't be summed together
+        OrderBy::Count | OrderBy::ScoreSum => {
+            values.sort_unstable_by(|_, left, _, right| {
+                left.cmp(right)
+                    // biggest first
+                    .reverse()
+            })
+        }
+        OrderBy::Weighted(weights) => {
+            values.sort_unstable_by(|left, _, right, _| left.cmp(right));
+            let mut sorted: Vec<_> = values.drain(..).collect();
+            weights.sort(&mut sorted, |(_, count)| *count);
+            values.extend(sorted);
+        }
+    }
+}
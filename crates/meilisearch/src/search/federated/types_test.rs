@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use indexmap::IndexMap;
+use meilisearch_types::milli::FacetValueHit;
+use serde_json::json;
+
+use super::types::FederatedFacets;
+use super::MergeFacets;
+use crate::search::ComputedFacets;
+
+/// More documents than a `u32` can count.
+const HUGE_COUNT: u64 = u32::MAX as u64 + 1;
+
+fn facets(count: u64) -> ComputedFacets {
+    let values = IndexMap::from([("Action".to_string(), count)]);
+    ComputedFacets {
+        distribution: BTreeMap::from([("genres".to_string(), values)]),
+        stats: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn facet_value_counts_are_not_truncated() {
+    let hit = FacetValueHit {
+        value: "Action".to_string(),
+        count: HUGE_COUNT,
+        normalized_value: None,
+        match_type: None,
+        sort_key: None,
+        attached_fields: None,
+        similarity: None,
+    };
+    assert_eq!(
+        serde_json::to_value(hit).unwrap(),
+        json!({ "value": "Action", "count": HUGE_COUNT })
+    );
+}
+
+#[test]
+fn merged_facet_counts_are_not_truncated() {
+    let mut federated = FederatedFacets::default();
+    federated.insert("movies".to_string(), Some(facets(HUGE_COUNT)));
+    federated.insert("series".to_string(), Some(facets(HUGE_COUNT)));
+    let merged = federated.merge(MergeFacets::default(), BTreeMap::new()).unwrap();
+    assert_eq!(merged.distribution["genres"]["Action"], 2 * HUGE_COUNT);
+    assert_eq!(
+        serde_json::to_value(&merged.distribution).unwrap(),
+        json!({ "genres": { "Action": 2 * HUGE_COUNT } })
+    );
+
+    // the counts saturate instead of wrapping around
+    let mut federated = FederatedFacets::default();
+    federated.insert("movies".to_string(), Some(facets(u64::MAX)));
+    federated.insert("series".to_string(), Some(facets(HUGE_COUNT)));
+    let merged = federated.merge(MergeFacets::default(), BTreeMap::new()).unwrap();
+    assert_eq!(merged.distribution["genres"]["Action"], u64::MAX);
+}
+
+#[test]
+fn remote_facet_counts_are_not_truncated() {
+    let mut local = FederatedFacets::default();
+    local.insert("movies".to_string(), Some(facets(HUGE_COUNT)));
+    let mut remote = FederatedFacets::default();
+    remote.insert("movies".to_string(), Some(facets(HUGE_COUNT)));
+    local.append(remote);
+    assert_eq!(local.0["movies"].distribution["genres"]["Action"], 2 * HUGE_COUNT);
+}
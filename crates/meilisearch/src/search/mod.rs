@@ -1,10 +1,13 @@
 use core::fmt;
 use std::cmp::min;
-use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use std::{io, mem};
 
+use base64::Engine as _;
 use deserr::Deserr;
 use either::Either;
 use index_scheduler::RoFeatures;
@@ -21,7 +24,9 @@ use meilisearch_types::milli::score_details::{ScoreDetails, ScoringStrategy};
 use meilisearch_types::milli::vector::parsed_vectors::ExplicitVectors;
 use meilisearch_types::milli::vector::Embedder;
 use meilisearch_types::milli::{
-    FacetValueHit, InternalError, OrderBy, PatternMatch, SearchForFacetValues, TimeBudget,
+    FacetCollation, FacetRangeBucket, FacetRepresentativeValue, FacetSearchPage, FacetSearchStats,
+    FacetValueGroup, FacetValueHit, GeoDistanceDistribution, InternalError, OrderBy, PatternMatch,
+    SearchForFacetValues, TimeBudget,
 };
 use meilisearch_types::settings::DEFAULT_PAGINATION_MAX_TOTAL_HITS;
 use meilisearch_types::{milli, Document};
@@ -39,6 +44,7 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::MeilisearchHttpError;
+use crate::routes::indexes::facet_distribution::FacetDistributionQuery;
 
 mod federated;
 pub use federated::{
@@ -57,6 +63,8 @@ pub const DEFAULT_CROP_MARKER: fn() -> String = || "…".to_string();
 pub const DEFAULT_HIGHLIGHT_PRE_TAG: fn() -> String = || "<em>".to_string();
 pub const DEFAULT_HIGHLIGHT_POST_TAG: fn() -> String = || "</em>".to_string();
 pub const DEFAULT_SEMANTIC_RATIO: fn() -> SemanticRatio = || SemanticRatio(0.5);
+/// The maximum number of values per facet for which `facetBitmaps` returns the document ids.
+pub const MAX_FACET_BITMAPS_VALUES: usize = 100;
 pub const INCLUDE_METADATA_HEADER: &str = "Meili-Include-Metadata";
 
 #[derive(Clone, Default, PartialEq, Deserr, ToSchema, Debug)]
@@ -112,6 +120,19 @@ pub struct SearchQuery {
     pub distinct: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchMinCount>)]
+    pub min_count: Option<u64>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetLevel>)]
+    pub facet_level: Option<u8>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetSampleSize>)]
+    #[schema(value_type = Option<usize>)]
+    pub facet_sample_size: Option<NonZeroUsize>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetSampleSeed>)]
+    pub facet_sample_seed: Option<u64>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetRanges>)]
+    pub facet_ranges: Option<BTreeMap<String, Vec<f64>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchGeoFacet>)]
+    pub geo_facet: Option<GeoFacet>,
     #[deserr(error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     #[schema(default = DEFAULT_HIGHLIGHT_PRE_TAG)]
     pub highlight_pre_tag: String,
@@ -174,6 +195,12 @@ impl From<SearchParameters> for SearchQuery {
             show_ranking_score_details: false,
             filter: None,
             facets: None,
+            min_count: None,
+            facet_level: None,
+            facet_sample_size: None,
+            facet_sample_seed: None,
+            facet_ranges: None,
+            geo_facet: None,
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
             crop_marker: DEFAULT_CROP_MARKER(),
@@ -253,6 +280,12 @@ impl fmt::Debug for SearchQuery {
             sort,
             distinct,
             facets,
+            min_count,
+            facet_level,
+            facet_sample_size,
+            facet_sample_seed,
+            facet_ranges,
+            geo_facet,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -312,6 +345,24 @@ impl fmt::Debug for SearchQuery {
         if let Some(facets) = facets {
             debug.field("facets", &facets);
         }
+        if let Some(min_count) = min_count {
+            debug.field("min_count", &min_count);
+        }
+        if let Some(facet_level) = facet_level {
+            debug.field("facet_level", &facet_level);
+        }
+        if let Some(facet_sample_size) = facet_sample_size {
+            debug.field("facet_sample_size", &facet_sample_size);
+        }
+        if let Some(facet_sample_seed) = facet_sample_seed {
+            debug.field("facet_sample_seed", &facet_sample_seed);
+        }
+        if let Some(facet_ranges) = facet_ranges {
+            debug.field("facet_ranges", &facet_ranges);
+        }
+        if let Some(geo_facet) = geo_facet {
+            debug.field("geo_facet", &geo_facet);
+        }
         debug.field("matching_strategy", &matching_strategy);
 
         // Then everything related to the formatting
@@ -369,6 +420,24 @@ pub struct HybridQuery {
     pub embedder: String,
 }
 
+/// Counts the hits in buckets of distances from a point, see the `geoFacet` search parameter.
+#[derive(Debug, Clone, PartialEq, Deserr, ToSchema, Serialize)]
+#[deserr(error = DeserrJsonError<InvalidSearchGeoFacet>, rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoFacet {
+    pub center: GeoFacetCenter,
+    /// The boundaries of the buckets, in meters.
+    pub boundaries: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserr, ToSchema, Serialize)]
+#[deserr(error = DeserrJsonError<InvalidSearchGeoFacet>, rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoFacetCenter {
+    pub lat: f64,
+    pub lng: f64,
+}
+
 #[derive(Clone)]
 pub enum SearchKind {
     KeywordOnly,
@@ -544,6 +613,19 @@ pub struct SearchQueryWithIndex {
     pub distinct: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchMinCount>)]
+    pub min_count: Option<u64>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetLevel>)]
+    pub facet_level: Option<u8>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetSampleSize>)]
+    #[schema(value_type = Option<usize>)]
+    pub facet_sample_size: Option<NonZeroUsize>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetSampleSeed>)]
+    pub facet_sample_seed: Option<u64>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetRanges>)]
+    pub facet_ranges: Option<BTreeMap<String, Vec<f64>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchGeoFacet>)]
+    pub geo_facet: Option<GeoFacet>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
@@ -615,6 +697,12 @@ impl SearchQueryWithIndex {
             sort,
             distinct,
             facets,
+            min_count,
+            facet_level,
+            facet_sample_size,
+            facet_sample_seed,
+            facet_ranges,
+            geo_facet,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -647,6 +735,12 @@ impl SearchQueryWithIndex {
             sort,
             distinct,
             facets,
+            min_count,
+            facet_level,
+            facet_sample_size,
+            facet_sample_seed,
+            facet_ranges,
+            geo_facet,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -682,6 +776,12 @@ impl SearchQueryWithIndex {
             sort,
             distinct,
             facets,
+            min_count,
+            facet_level,
+            facet_sample_size,
+            facet_sample_seed,
+            facet_ranges,
+            geo_facet,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -714,6 +814,12 @@ impl SearchQueryWithIndex {
                 sort,
                 distinct,
                 facets,
+                min_count,
+                facet_level,
+                facet_sample_size,
+                facet_sample_seed,
+                facet_ranges,
+                geo_facet,
                 highlight_pre_tag,
                 highlight_post_tag,
                 crop_marker,
@@ -887,6 +993,14 @@ pub struct SearchResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub facet_stats: Option<BTreeMap<String, FacetStats>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_distribution_stats: Option<BTreeMap<String, FacetDistributionStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<BTreeMap<String, Vec<Value>>>)]
+    pub facet_ranges: Option<BTreeMap<String, Vec<FacetRangeBucket>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Value>)]
+    pub geo_facet: Option<GeoDistanceDistribution>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub request_uid: Option<Uuid>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<SearchMetadata>,
@@ -911,6 +1025,9 @@ impl fmt::Debug for SearchResult {
             hits_info,
             facet_distribution,
             facet_stats,
+            facet_distribution_stats,
+            facet_ranges,
+            geo_facet,
             request_uid,
             metadata,
             semantic_hit_count,
@@ -939,6 +1056,15 @@ impl fmt::Debug for SearchResult {
         if let Some(facet_stats) = facet_stats {
             debug.field("facet_stats", &facet_stats);
         }
+        if let Some(facet_distribution_stats) = facet_distribution_stats {
+            debug.field("facet_distribution_stats", &facet_distribution_stats);
+        }
+        if let Some(facet_ranges) = facet_ranges {
+            debug.field("facet_ranges", &facet_ranges);
+        }
+        if let Some(geo_facet) = geo_facet {
+            debug.field("geo_facet", &geo_facet);
+        }
         if let Some(semantic_hit_count) = semantic_hit_count {
             debug.field("semantic_hit_count", &semantic_hit_count);
         }
@@ -989,12 +1115,133 @@ pub struct FacetStats {
     pub max: f64,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct FacetDistributionStats {
+    /// The number of distinct values of the facet among the documents matching the search.
+    pub distinct_values: u64,
+    /// Whether the counts of the distribution were extrapolated from a sample of the documents
+    /// matching the search, see `facetSampleSize`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub approximate: bool,
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FacetSearchResult {
     pub facet_hits: Vec<FacetValueHit>,
     pub facet_query: Option<String>,
     pub processing_time_ms: u128,
+    /// Whether the facet search is enabled on the index, the hits are always empty when it is not.
+    pub facet_search_enabled: bool,
+    /// The maximum number of facet values the search could return.
+    pub applied_limit: usize,
+    /// Where the `applied_limit` comes from.
+    pub limit_source: FacetSearchLimitSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<FacetSearchStats>,
+    /// The number of documents matching the search without the facet field,
+    /// only returned with `includeMissing`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_count: Option<u64>,
+    /// The facet values grouped by first character, only returned with `groupByFirstChar`
+    /// in which case `facet_hits` is empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_groups: Option<Vec<FacetValueGroup>>,
+    /// The cursor of the next page, only returned when paging with `after` or browsing
+    /// with `browseMode` and the page is full.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// The token of the point-in-time view the facet search ran against, only returned with
+    /// `snapshot` or `snapshotToken`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_token: Option<String>,
+    /// The facet values closest to the facet query, only returned with `suggestCorrections`
+    /// when no facet value matches it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestions: Option<Vec<String>>,
+    /// Whether facet hits were removed for the response to fit in `maxResponseBytes`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated_by_size: bool,
+}
+
+impl FacetSearchResult {
+    /// Removes the last facet hits until the serialized response is at most `max_bytes` long,
+    /// setting `truncated_by_size` when any hit is removed.
+    ///
+    /// The response without hits is measured once, the serialized size of each hit is then
+    /// added to a running tally until one no longer fits.
+    pub fn truncate_to_size(&mut self, max_bytes: usize) -> serde_json::Result<()> {
+        if serialized_len(&*self)? <= max_bytes {
+            return Ok(());
+        }
+
+        let hits = mem::take(&mut self.facet_hits);
+        self.truncated_by_size = true;
+        let mut tally = serialized_len(&*self)?;
+        for hit in hits {
+            // the hits are separated by a comma
+            let len = serialized_len(&hit)? + usize::from(!self.facet_hits.is_empty());
+            if tally + len > max_bytes {
+                break;
+            }
+            tally += len;
+            self.facet_hits.push(hit);
+        }
+
+        Ok(())
+    }
+}
+
+/// The length of the JSON serialization of a value, without allocating it.
+fn serialized_len<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<usize> {
+    struct ByteCounter(usize);
+
+    impl io::Write for ByteCounter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, value)?;
+    Ok(counter.0)
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FacetSearchLimitSource {
+    /// The `limit` of the request, lower than the one of the index.
+    Request,
+    /// The `maxValuesPerFacet` faceting setting of the index.
+    IndexSetting,
+    /// The default maximum number of values per facet.
+    System,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct FacetDistributionResult {
+    #[schema(value_type = BTreeMap<String, Value>)]
+    pub facet_distribution: BTreeMap<String, IndexMap<String, u64>>,
+    pub facet_stats: BTreeMap<String, FacetStats>,
+    /// The nodes of the requested level of the number facet trees.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<BTreeMap<String, Vec<Value>>>)]
+    pub facet_ranges: Option<BTreeMap<String, Vec<FacetRangeBucket>>>,
+    /// The base64 encoded `RoaringBitmap` of the internal document ids of the first values
+    /// of the distribution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<BTreeMap<String, BTreeMap<String, String>>>)]
+    pub facet_bitmaps: Option<BTreeMap<String, IndexMap<String, String>>>,
+    pub processing_time_ms: u128,
 }
 
 /// Incorporate search rules in search query
@@ -1207,6 +1454,8 @@ pub fn perform_search(
         semantic_hit_count,
     ) = search_from_kind(index_uid.clone(), search_kind, search)?;
 
+    let cached_facet_distribution = cached_facet_distribution(index, &rtxn, &query)?;
+
     let metadata = if include_metadata {
         let query_uid = Uuid::now_v7();
         let primary_key = index.primary_key(&rtxn)?.map(|pk| pk.to_string());
@@ -1236,6 +1485,12 @@ pub fn perform_search(
         show_ranking_score_details,
         sort,
         facets,
+        min_count,
+        facet_level,
+        facet_sample_size,
+        facet_sample_seed,
+        facet_ranges,
+        geo_facet,
         highlight_pre_tag,
         highlight_post_tag,
         crop_marker,
@@ -1276,6 +1531,12 @@ pub fn perform_search(
         matching_words,
         documents_ids.iter().copied().zip(document_scores.iter()),
     )?;
+    // the values of the facets sorted by `scoreSum` are ranked with the scores of the returned documents
+    let document_scores: HashMap<_, _> = documents_ids
+        .iter()
+        .copied()
+        .zip(document_scores.iter().map(|score| ScoreDetails::global_score(score.iter())))
+        .collect();
 
     let number_of_hits = min(candidates.len() as usize, max_total_hits);
     let hits_info = if is_finite_pagination {
@@ -1295,13 +1556,63 @@ pub fn perform_search(
         HitsInfo::OffsetLimit { limit, offset, estimated_total_hits: number_of_hits }
     };
 
-    let (facet_distribution, facet_stats) = facets
-        .map(move |facets| {
-            compute_facet_distribution_stats(&facets, index, &rtxn, candidates, Route::Search)
-        })
-        .transpose()?
-        .map(|ComputedFacets { distribution, stats }| (distribution, stats))
-        .unzip();
+    let facet_ranges = match facet_ranges {
+        Some(ranges) => Some(compute_facet_ranges(&ranges, index, &rtxn, candidates.clone())?),
+        None => None,
+    };
+
+    let geo_facet = match geo_facet {
+        Some(GeoFacet { center: GeoFacetCenter { lat, lng }, boundaries }) => {
+            let mut facet_distribution = index.facets_distribution(&rtxn);
+            let distribution = facet_distribution.candidates(candidates.clone());
+            Some(distribution.compute_geo_distances([lat, lng], &boundaries)?)
+        }
+        None => None,
+    };
+
+    let (facet_distribution, facet_stats, facet_distribution_stats) = match facets {
+        Some(facets) => {
+            let distribution_candidates = candidates.clone();
+            let facet_sample =
+                facet_sample_size.map(|size| (size, facet_sample_seed.unwrap_or_default()));
+            // the pinned distributions are exact
+            let approximate = cached_facet_distribution.is_none()
+                && facet_sample.is_some_and(|(size, _)| candidates.len() > size.get() as u64);
+            let ComputedFacets { distribution, stats } = match cached_facet_distribution {
+                Some(cached) => ComputedFacets {
+                    distribution: cached.distribution,
+                    stats: cached
+                        .stats
+                        .into_iter()
+                        .map(|(k, (min, max))| (k, FacetStats { min, max }))
+                        .collect(),
+                },
+                None => compute_facet_distribution_stats(
+                    &facets,
+                    FacetDistributionOptions {
+                        min_count,
+                        level: facet_level,
+                        sample: facet_sample,
+                        document_scores: Some(document_scores),
+                        ..Default::default()
+                    },
+                    index,
+                    &rtxn,
+                    candidates,
+                    Route::Search,
+                )?,
+            };
+            let distribution_stats = compute_facet_distribution_cardinality(
+                index,
+                &rtxn,
+                distribution.keys(),
+                &distribution_candidates,
+                approximate,
+            )?;
+            (Some(distribution), Some(stats), Some(distribution_stats))
+        }
+        None => (None, None, None),
+    };
     let result = SearchResult {
         hits: documents,
         hits_info,
@@ -1310,6 +1621,9 @@ pub fn perform_search(
         processing_time_ms: before_search.elapsed().as_millis(),
         facet_distribution,
         facet_stats,
+        facet_distribution_stats,
+        facet_ranges,
+        geo_facet,
         degraded,
         used_negative_operator,
         semantic_hit_count,
@@ -1332,13 +1646,34 @@ pub enum Route {
     Similar,
 }
 
+/// How the facet distribution of [`compute_facet_distribution_stats`] is computed,
+/// the options left to their default are not applied.
+#[derive(Default)]
+struct FacetDistributionOptions<'a> {
+    min_count: Option<u64>,
+    level: Option<u8>,
+    sample: Option<(NonZeroUsize, u64)>,
+    numeric_precision: Option<u8>,
+    document_scores: Option<HashMap<milli::DocumentId, f64>>,
+    boolean_facets: Option<&'a [String]>,
+}
+
 fn compute_facet_distribution_stats<S: AsRef<str>>(
     facets: &[S],
+    options: FacetDistributionOptions<'_>,
     index: &Index,
     rtxn: &RoTxn,
     candidates: roaring::RoaringBitmap,
     route: Route,
 ) -> Result<ComputedFacets, ResponseError> {
+    let FacetDistributionOptions {
+        min_count,
+        level,
+        sample,
+        numeric_precision,
+        document_scores,
+        boolean_facets,
+    } = options;
     let mut facet_distribution = index.facets_distribution(rtxn);
 
     let max_values_by_facet = index
@@ -1349,6 +1684,30 @@ fn compute_facet_distribution_stats<S: AsRef<str>>(
 
     facet_distribution.max_values_per_facet(max_values_by_facet);
 
+    if let Some(min_count) = min_count {
+        facet_distribution.min_count(min_count);
+    }
+
+    if let Some(level) = level {
+        facet_distribution.level(level);
+    }
+
+    if let Some((size, seed)) = sample {
+        facet_distribution.sample(size, seed);
+    }
+
+    if let Some(decimals) = numeric_precision {
+        facet_distribution.numeric_precision(decimals);
+    }
+
+    if let Some(scores) = document_scores {
+        facet_distribution.document_scores(scores);
+    }
+
+    if let Some(boolean_facets) = boolean_facets {
+        facet_distribution.boolean_facets(boolean_facets);
+    }
+
     let sort_facet_values_by = index.sort_facet_values_by(rtxn).map_err(milli::Error::from)?;
 
     // add specific facet if there is no placeholder
@@ -1376,6 +1735,75 @@ fn compute_facet_distribution_stats<S: AsRef<str>>(
     Ok(ComputedFacets { distribution, stats })
 }
 
+/// Returns the distribution pinned for the facets and filter of the query if it is up to date.
+///
+/// The pinned distributions are computed on all the documents matching the filter, so they are
+/// only served to the placeholder keyword searches whose candidates are exactly these documents.
+fn cached_facet_distribution(
+    index: &Index,
+    rtxn: &RoTxn,
+    query: &SearchQuery,
+) -> Result<Option<milli::CachedFacetDistribution>, ResponseError> {
+    let Some(facets) = &query.facets else { return Ok(None) };
+    let filter = match &query.filter {
+        Some(Value::String(filter)) => filter.as_str(),
+        None => "",
+        Some(_) => return Ok(None),
+    };
+
+    let is_placeholder = query.q.as_deref().is_none_or(str::is_empty)
+        && query.vector.is_none()
+        && query.media.is_none()
+        && query.hybrid.is_none()
+        && query.personalize.is_none();
+    let all_candidates_counted = query.ranking_score_threshold.is_none()
+        && query.min_count.is_none()
+        && query.facet_level.is_none()
+        && query.distinct.is_none()
+        && index.distinct_field(rtxn)?.is_none();
+    if !is_placeholder || !all_candidates_counted {
+        return Ok(None);
+    }
+    // the pinned distributions are not ranked with the scores of the returned documents
+    let sort_facet_values_by = index.sort_facet_values_by(rtxn).map_err(milli::Error::from)?;
+    if sort_facet_values_by.into_iter().any(|(_, order_by)| order_by == OrderBy::ScoreSum) {
+        return Ok(None);
+    }
+
+    Ok(index.cached_facet_distribution(rtxn, filter, facets)?)
+}
+
+/// Counts the candidates in the buckets delimited by the boundaries of each numeric facet.
+fn compute_facet_ranges(
+    ranges: &BTreeMap<String, Vec<f64>>,
+    index: &Index,
+    rtxn: &RoTxn,
+    candidates: roaring::RoaringBitmap,
+) -> Result<BTreeMap<String, Vec<FacetRangeBucket>>, ResponseError> {
+    let mut facet_distribution = index.facets_distribution(rtxn);
+    Ok(facet_distribution.candidates(candidates).compute_ranges(ranges)?)
+}
+
+/// Counts the distinct values of each facet of the distribution among the candidates.
+fn compute_facet_distribution_cardinality<'a>(
+    index: &Index,
+    rtxn: &RoTxn,
+    facets: impl IntoIterator<Item = &'a String>,
+    candidates: &roaring::RoaringBitmap,
+    approximate: bool,
+) -> Result<BTreeMap<String, FacetDistributionStats>, ResponseError> {
+    let fields_ids_map = index.fields_ids_map(rtxn).map_err(milli::Error::from)?;
+    let mut stats = BTreeMap::new();
+    for facet in facets {
+        if let Some(field_id) = fields_ids_map.id(facet) {
+            let distinct_values =
+                index.facet_value_cardinality(rtxn, field_id, Some(candidates))?;
+            stats.insert(facet.clone(), FacetDistributionStats { distinct_values, approximate });
+        }
+    }
+    Ok(stats)
+}
+
 pub fn search_from_kind(
     index_uid: String,
     search_kind: SearchKind,
@@ -1691,34 +2119,121 @@ fn make_hits<'a>(
     Ok(documents)
 }
 
+/// The parameters of a facet search request, except the ones of the search
+/// selecting the documents the facet values are counted on.
+#[derive(Debug, Clone, Default)]
+pub struct FacetSearchOptions {
+    pub facet_query: Option<String>,
+    pub facet_queries: Option<Vec<String>>,
+    pub facet_name: String,
+    pub locales: Option<Vec<Language>>,
+    pub include_normalized: bool,
+    pub prefix_last_token: bool,
+    pub min_count: Option<u64>,
+    pub rank_by_relevance: bool,
+    pub include_match_type: bool,
+    pub include_stats: bool,
+    pub include_missing: bool,
+    pub include_sort_keys: bool,
+    pub match_tokens: bool,
+    pub exact: bool,
+    pub browse_mode: bool,
+    pub sort_by: Option<OrderBy>,
+    pub representative_value: Option<FacetRepresentativeValue>,
+    pub after: Option<String>,
+    pub boost_document_ids: Option<Vec<ExternalDocumentId>>,
+    pub max_typos: Option<u8>,
+    pub limit: Option<usize>,
+    pub collation: Option<FacetCollation>,
+    pub group_by_first_char: bool,
+    pub attach_fields: Option<Vec<String>>,
+    pub min_similarity: Option<f64>,
+    pub accent_sensitive: bool,
+    pub include_zero_counts: bool,
+    pub suggest_corrections: bool,
+    pub disjunctive_on_self: bool,
+}
+
 pub fn perform_facet_search(
     index: &Index,
+    rtxn: &RoTxn,
     search_query: SearchQuery,
-    facet_query: Option<String>,
-    facet_name: String,
+    options: FacetSearchOptions,
     search_kind: SearchKind,
     features: RoFeatures,
-    locales: Option<Vec<Language>>,
 ) -> Result<FacetSearchResult, ResponseError> {
+    let FacetSearchOptions {
+        facet_query,
+        facet_queries,
+        facet_name,
+        locales,
+        include_normalized,
+        prefix_last_token,
+        min_count,
+        rank_by_relevance,
+        include_match_type,
+        include_stats,
+        include_missing,
+        include_sort_keys,
+        match_tokens,
+        exact,
+        browse_mode,
+        sort_by,
+        representative_value,
+        after,
+        boost_document_ids,
+        max_typos,
+        limit,
+        collation,
+        group_by_first_char,
+        attach_fields,
+        min_similarity,
+        accent_sensitive,
+        include_zero_counts,
+        suggest_corrections,
+        disjunctive_on_self,
+    } = options;
     let before_search = Instant::now();
-    let rtxn = index.read_txn()?;
-    let time_budget = match index.search_cutoff(&rtxn)? {
+    let time_budget = match index.search_cutoff(rtxn)? {
         Some(cutoff) => TimeBudget::new(Duration::from_millis(cutoff)),
         None => TimeBudget::default(),
     };
 
-    if !index.facet_search(&rtxn)? {
-        return Err(ResponseError::from_msg(
-            "The facet search is disabled for this index".to_string(),
-            Code::FacetSearchDisabled,
-        ));
+    // The limit of the request can only lower the maximum number of values of the index.
+    let (max_values, max_values_source) = match index.max_values_per_facet(rtxn)? {
+        Some(max_values) => (max_values as usize, FacetSearchLimitSource::IndexSetting),
+        None => (DEFAULT_VALUES_PER_FACET, FacetSearchLimitSource::System),
+    };
+    let (applied_limit, limit_source) = match limit {
+        Some(limit) if limit < max_values => (limit, FacetSearchLimitSource::Request),
+        _ => (max_values, max_values_source),
+    };
+
+    // An empty page lets the clients tell a disabled facet search apart from a query
+    // matching no facet values without having to handle an error.
+    if !index.facet_search(rtxn)? {
+        return Ok(FacetSearchResult {
+            facet_hits: Vec::new(),
+            facet_query,
+            processing_time_ms: before_search.elapsed().as_millis(),
+            facet_search_enabled: false,
+            applied_limit,
+            limit_source,
+            stats: None,
+            missing_count: None,
+            facet_groups: group_by_first_char.then(Vec::new),
+            next_cursor: None,
+            snapshot_token: None,
+            suggestions: None,
+            truncated_by_size: false,
+        });
     }
 
     // In the faceted search context, we want to use the intersection between the locales provided by the user
     // and the locales of the facet string.
     // If the facet string is not localized, we **ignore** the locales provided by the user because the facet data has no locale.
     // If the user does not provide locales, we use the locales of the facet string.
-    let localized_attributes = index.localized_attributes_rules(&rtxn)?.unwrap_or_default();
+    let localized_attributes = index.localized_attributes_rules(rtxn)?.unwrap_or_default();
     let localized_attributes_locales = localized_attributes
         .into_iter()
         .find(|attr| attr.match_str(&facet_name) == PatternMatch::Match);
@@ -1729,8 +2244,21 @@ pub fn perform_facet_search(
             .collect()
     });
 
-    let (search, _, _, _) =
-        prepare_search(index, &rtxn, &search_query, &search_kind, time_budget, features)?;
+    // The filter is parsed beforehand so that its errors are reported with the facet search code.
+    if let Some(filter) = &search_query.filter {
+        parse_filter(filter, Code::InvalidFacetSearchFilter, features)?;
+    }
+    let (mut search, _, _, _) =
+        prepare_search(index, rtxn, &search_query, &search_kind, time_budget, features)?;
+    if rank_by_relevance {
+        // The facet values are ranked by the scores of all the documents the search can return.
+        let max_total_hits = index
+            .pagination_max_total_hits(rtxn)
+            .map_err(milli::Error::from)?
+            .map(|x| x as usize)
+            .unwrap_or(DEFAULT_PAGINATION_MAX_TOTAL_HITS);
+        search.offset(0).limit(max_total_hits).scoring_strategy(ScoringStrategy::Detailed);
+    }
     let mut facet_search = SearchForFacetValues::new(
         facet_name,
         search,
@@ -1739,18 +2267,194 @@ pub fn perform_facet_search(
     if let Some(facet_query) = &facet_query {
         facet_search.query(facet_query);
     }
-    if let Some(max_facets) = index.max_values_per_facet(&rtxn)? {
-        facet_search.max_values(max_facets as usize);
+    if let Some(facet_queries) = facet_queries {
+        facet_search.prefixes(facet_queries);
     }
+    facet_search.max_values(applied_limit);
 
     if let Some(locales) = locales {
         facet_search.locales(locales);
     }
 
+    facet_search.include_normalized(include_normalized);
+    facet_search.prefix_last_token(prefix_last_token);
+    if let Some(min_count) = min_count {
+        facet_search.min_count(min_count);
+    }
+    facet_search.rank_by_relevance(rank_by_relevance);
+    facet_search.include_match_type(include_match_type);
+    facet_search.include_stats(include_stats);
+    facet_search.include_missing(include_missing);
+    facet_search.include_sort_keys(include_sort_keys);
+    facet_search.match_tokens(match_tokens);
+    facet_search.exact(exact);
+    facet_search.browse_mode(browse_mode);
+    if let Some(sort_by) = sort_by {
+        facet_search.sort_by(sort_by);
+    }
+    if let Some(representative_value) = representative_value {
+        facet_search.representative_value(representative_value);
+    }
+    if let Some(after) = after {
+        facet_search.after(after);
+    }
+    if let Some(ids) = boost_document_ids {
+        // the unknown documents, e.g. deleted since the prior search, are ignored
+        let external_document_ids = index.external_documents_ids();
+        let mut boosted_documents = roaring::RoaringBitmap::new();
+        for id in &ids {
+            if let Some(docid) = external_document_ids.get(rtxn, id)? {
+                boosted_documents.insert(docid);
+            }
+        }
+        facet_search.boost_documents(boosted_documents);
+    }
+    if let Some(max_typos) = max_typos {
+        facet_search.max_typos(max_typos);
+    }
+    if let Some(collation) = collation {
+        facet_search.collation(collation);
+    }
+    facet_search.group_by_first_char(group_by_first_char);
+    if let Some(attach_fields) = attach_fields {
+        facet_search.attach_fields(attach_fields);
+    }
+    if let Some(min_similarity) = min_similarity {
+        facet_search.min_similarity(min_similarity);
+    }
+    facet_search.accent_sensitive(accent_sensitive);
+    facet_search.include_zero_counts(include_zero_counts);
+    facet_search.suggest_corrections(suggest_corrections);
+    facet_search.disjunctive_on_self(disjunctive_on_self);
+
+    let FacetSearchPage { hits: facet_hits, groups, stats, missing, next_cursor, suggestions } =
+        facet_search.execute_page().map_err(|error| match error {
+            error @ milli::Error::UserError(
+                milli::UserError::InvalidFilter(_)
+                | milli::UserError::InvalidFilterExpression(..)
+                | milli::UserError::FilterOperatorNotAllowed { .. },
+            ) => ResponseError::from_msg(error.to_string(), Code::InvalidFacetSearchFilter),
+            error => error.into(),
+        })?;
     Ok(FacetSearchResult {
-        facet_hits: facet_search.execute()?,
+        facet_hits,
         facet_query,
         processing_time_ms: before_search.elapsed().as_millis(),
+        facet_search_enabled: true,
+        applied_limit,
+        limit_source,
+        stats,
+        missing_count: missing,
+        facet_groups: groups,
+        next_cursor,
+        snapshot_token: None,
+        suggestions,
+        truncated_by_size: false,
+    })
+}
+
+/// Computes the distribution of the facets over the whole index without running a search,
+/// or over the documents matching the `filter` of the tenant token when there is one.
+pub fn perform_facet_distribution(
+    index: &Index,
+    query: FacetDistributionQuery,
+    filter: Option<Value>,
+    features: RoFeatures,
+) -> Result<FacetDistributionResult, ResponseError> {
+    let before_distribution = Instant::now();
+    let FacetDistributionQuery {
+        facets,
+        facet_values,
+        include_zero_counts,
+        granularity_level,
+        numeric_precision,
+        facet_bitmaps,
+        boolean_facets,
+    } = query;
+    let include_zero_counts = include_zero_counts.unwrap_or(true);
+    let rtxn = index.read_txn()?;
+
+    let mut candidates = index.documents_ids(&rtxn)?;
+    if let Some(ref filter) = filter {
+        if let Some(filter) = parse_filter(filter, Code::InvalidSearchFilter, features)? {
+            candidates &= filter.evaluate(&rtxn, index)?;
+        }
+    }
+
+    let ComputedFacets { mut distribution, stats } = if facets.is_empty() {
+        ComputedFacets::default()
+    } else {
+        let options = FacetDistributionOptions {
+            numeric_precision,
+            boolean_facets: boolean_facets.as_deref(),
+            ..Default::default()
+        };
+        compute_facet_distribution_stats(
+            &facets,
+            options,
+            index,
+            &rtxn,
+            candidates.clone(),
+            Route::Search,
+        )?
+    };
+
+    let facet_ranges = match granularity_level {
+        Some(level) => {
+            let mut facet_distribution = index.facets_distribution(&rtxn);
+            facet_distribution
+                .facets(facets.iter().map(|facet| (facet, OrderBy::default())))
+                .candidates(candidates.clone());
+            Some(facet_distribution.compute_level_ranges(level)?)
+        }
+        None => None,
+    };
+
+    // The requested values are looked up directly instead of enumerating all the values.
+    if let Some(facet_values) = facet_values {
+        let mut facet_distribution = index.facets_distribution(&rtxn);
+        let mut values =
+            facet_distribution.candidates(candidates.clone()).compute_values(&facet_values)?;
+        if !include_zero_counts {
+            values.values_mut().for_each(|counts| counts.retain(|_, count| *count != 0));
+        }
+        distribution.extend(values);
+    }
+
+    let facet_bitmaps = if facet_bitmaps {
+        let values = distribution
+            .iter()
+            .map(|(name, values)| {
+                (name.clone(), values.keys().take(MAX_FACET_BITMAPS_VALUES).cloned().collect())
+            })
+            .collect();
+        let mut facet_distribution = index.facets_distribution(&rtxn);
+        let docids = facet_distribution.candidates(candidates).compute_value_docids(&values)?;
+        let bitmaps = docids
+            .into_iter()
+            .map(|(name, values)| {
+                let values = values
+                    .into_iter()
+                    .map(|(value, docids)| {
+                        let mut bytes = Vec::with_capacity(docids.serialized_size());
+                        docids.serialize_into(&mut bytes).map_err(milli::Error::from)?;
+                        Ok((value, base64::prelude::BASE64_STANDARD.encode(bytes)))
+                    })
+                    .collect::<Result<_, ResponseError>>()?;
+                Ok((name, values))
+            })
+            .collect::<Result<_, ResponseError>>()?;
+        Some(bitmaps)
+    } else {
+        None
+    };
+
+    Ok(FacetDistributionResult {
+        facet_distribution: distribution,
+        facet_stats: stats,
+        facet_ranges,
+        facet_bitmaps,
+        processing_time_ms: before_distribution.elapsed().as_millis(),
     })
 }
 
@@ -282,6 +282,101 @@ async fn list_batch_filter_error() {
     "#);
 }
 
+#[actix_web::test]
+async fn test_progress_trace_of_facet_search_structures() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+    let (task, _status_code) = index.update_settings_filterable_attributes(json!(["genre"])).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (task, _status_code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "genre": "fantasy", "title": "The Hobbit" },
+                { "id": 2, "genre": "romance", "title": "Pride and Prejudice" },
+            ]),
+            None,
+        )
+        .await;
+    let task = server.wait_task(task.uid()).await.succeeded();
+    let (batch, _) = index.get_batch(task.batch_uid()).await;
+
+    let steps: Vec<_> = batch["stats"]["progressTrace"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .filter(|step| step.ends_with("building facet search structures"))
+        .collect();
+    snapshot!(format!("{steps:?}"), @r###"["processing tasks > indexing > post processing facets > facet search > building facet search structures"]"###);
+}
+
+#[actix_web::test]
+async fn test_facet_indexing_peak_memory() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+    let (task, _status_code) = index.update_settings_filterable_attributes(json!(["genre"])).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (task, _status_code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "genre": "fantasy", "title": "The Hobbit" },
+                { "id": 2, "genre": "romance", "title": "Pride and Prejudice" },
+            ]),
+            None,
+        )
+        .await;
+    let task = server.wait_task(task.uid()).await.succeeded();
+    let (batch, _) = index.get_batch(task.batch_uid()).await;
+
+    let peak = batch["stats"]["facetIndexingPeakMemory"].as_u64().unwrap();
+    assert!(peak > 0, "{batch}");
+}
+
+#[actix_web::test]
+async fn test_facet_effective_group_size() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+    let (task, _status_code) = index
+        .update_settings(
+            json!({ "filterableAttributes": ["n"], "faceting": { "maxFacetLevels": 1 } }),
+        )
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // 100 values need two levels with the default group size, a group size of 5 builds only one
+    let documents: Vec<_> = (0..100).map(|i| json!({ "id": i, "n": i })).collect();
+    let (task, _status_code) = index.add_documents(json!(documents), None).await;
+    let task = server.wait_task(task.uid()).await.succeeded();
+    let (batch, _) = index.get_batch(task.batch_uid()).await;
+
+    snapshot!(batch["stats"]["facetEffectiveGroupSize"], @"5");
+}
+
+#[actix_web::test]
+async fn test_word_prefix_changes() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    // a prefix is materialized once it matches at least 100 words, here only `w`
+    let documents: Vec<_> = (0..150u8)
+        .map(|i| {
+            let word = format!("w{}{}", (b'a' + i / 26) as char, (b'a' + i % 26) as char);
+            json!({ "id": i, "text": word })
+        })
+        .collect();
+    let (task, _status_code) = index.add_documents(json!(documents), None).await;
+    let task = server.wait_task(task.uid()).await.succeeded();
+    let (batch, _) = index.get_batch(task.batch_uid()).await;
+    snapshot!(batch["stats"]["wordPrefixChanges"], @r###"{"recomputed":1,"deleted":0}"###);
+
+    // below 100 words the prefix is removed
+    let (task, _status_code) = index.delete_batch((0..60).collect()).await;
+    let task = server.wait_task(task.uid()).await.succeeded();
+    let (batch, _) = index.get_batch(task.batch_uid()).await;
+    snapshot!(batch["stats"]["wordPrefixChanges"], @r###"{"recomputed":0,"deleted":1}"###);
+}
+
 #[actix_web::test]
 async fn test_summarized_document_addition_or_update() {
     let server = Server::new_shared();
@@ -609,11 +609,21 @@ impl<State> Index<'_, State> {
         self.service.get(url).await
     }
 
+    pub async fn facet_distribution(&self, query: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/facet-distribution", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, query, self.encoder).await
+    }
+
     pub async fn facet_search(&self, query: Value) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/facet-search", urlencode(self.uid.as_ref()));
         self.service.post_encoded(url, query, self.encoder).await
     }
 
+    pub async fn facet_normalization(&self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/facet-search/normalization", urlencode(self.uid.as_ref()));
+        self.service.get(url).await
+    }
+
     pub async fn get_distinct_attribute(&self) -> (Value, StatusCode) {
         let url =
             format!("/indexes/{}/settings/{}", urlencode(self.uid.as_ref()), "distinct-attribute");
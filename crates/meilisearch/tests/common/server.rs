@@ -51,7 +51,7 @@ impl Server<Owned> {
         let options = default_settings(dir.path());
         let handle = tokio::runtime::Handle::current();
         let (index_scheduler, auth) = setup_meilisearch(&options, handle).unwrap();
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service::new(index_scheduler, auth, options);
 
         Server { service, _dir: Some(dir), _marker: PhantomData }
     }
@@ -68,7 +68,7 @@ impl Server<Owned> {
         let handle = tokio::runtime::Handle::current();
 
         let (index_scheduler, auth) = setup_meilisearch(&options, handle).unwrap();
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service::new(index_scheduler, auth, options);
 
         Server { service, _dir: Some(dir), _marker: PhantomData }
     }
@@ -83,7 +83,7 @@ impl Server<Owned> {
         let handle = tokio::runtime::Handle::current();
 
         let (index_scheduler, auth) = setup_meilisearch(&options, handle)?;
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service::new(index_scheduler, auth, options);
 
         Ok(Server { service, _dir: None, _marker: PhantomData })
     }
@@ -224,7 +224,7 @@ impl Server<Shared> {
         let handle = tokio::runtime::Handle::current();
 
         let (index_scheduler, auth) = setup_meilisearch(&options, handle).unwrap();
-        let service = Service { index_scheduler, auth, api_key: None, options };
+        let service = Service::new(index_scheduler, auth, options);
 
         Server { service, _dir: Some(dir), _marker: PhantomData }
     }
@@ -504,6 +504,7 @@ pub fn default_settings(dir: impl AsRef<Path>) -> Opt {
             skip_index_budget: true,
             // Having 2 threads makes the tests way faster
             max_indexing_threads: MaxThreads::from_str("2").unwrap(),
+            max_filterable_attributes: 100,
             experimental_no_edition_2024_for_settings: false,
             experimental_no_edition_2024_for_dumps: false,
             experimental_no_edition_2024_for_prefix_post_processing: false,
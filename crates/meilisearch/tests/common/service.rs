@@ -10,6 +10,8 @@ use actix_web::test::TestRequest;
 use actix_web::web::Data;
 use index_scheduler::IndexScheduler;
 use meilisearch::analytics::Analytics;
+use meilisearch::facet_search_rate_limiter::FacetSearchRateLimiter;
+use meilisearch::facet_search_snapshots::FacetSearchSnapshots;
 use meilisearch::personalization::PersonalizationService;
 use meilisearch::search_queue::SearchQueue;
 use meilisearch::{create_app, Opt, ServicesData, SubscriberForSecondLayer};
@@ -25,9 +27,33 @@ pub struct Service {
     pub auth: Arc<AuthController>,
     pub options: Opt,
     pub api_key: Option<String>,
+    /// Kept across the requests as the app is recreated for each of them.
+    pub facet_search_snapshots: Data<FacetSearchSnapshots>,
+    pub facet_search_rate_limiter: Data<FacetSearchRateLimiter>,
 }
 
 impl Service {
+    pub fn new(
+        index_scheduler: Arc<IndexScheduler>,
+        auth: Arc<AuthController>,
+        options: Opt,
+    ) -> Self {
+        let facet_search_rate_limiter = FacetSearchRateLimiter::new(
+            options.experimental_facet_search_rate_limit,
+            options.experimental_facet_search_rate_limit_per_index.clone(),
+        );
+        let facet_search_snapshots =
+            FacetSearchSnapshots::default().with_index_closings(index_scheduler.index_closings());
+        Self {
+            index_scheduler,
+            auth,
+            options,
+            api_key: None,
+            facet_search_snapshots: Data::new(facet_search_snapshots),
+            facet_search_rate_limiter: Data::new(facet_search_rate_limiter),
+        }
+    }
+
     pub async fn post(&self, url: impl AsRef<str>, body: Value) -> (Value, StatusCode) {
         self.post_encoded(url, body, Encoder::Plain).await
     }
@@ -162,6 +188,8 @@ impl Service {
                 index_scheduler: self.index_scheduler.clone().into(),
                 auth: self.auth.clone().into(),
                 search_queue: Data::new(search_queue),
+                facet_search_snapshots: self.facet_search_snapshots.clone(),
+                facet_search_rate_limiter: self.facet_search_rate_limiter.clone(),
                 personalization_service: Data::new(personalization_service),
                 logs_route_handle: Data::new(route_layer_handle),
                 logs_stderr_handle: Data::new(stderr_layer_handle),
@@ -92,9 +92,18 @@ async fn import_dump_v1_movie_raw() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -266,9 +275,18 @@ async fn import_dump_v1_movie_with_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -439,9 +457,18 @@ async fn import_dump_v1_rubygems_with_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -598,9 +625,18 @@ async fn import_dump_v2_movie_raw() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -769,9 +805,18 @@ async fn import_dump_v2_movie_with_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -939,9 +984,18 @@ async fn import_dump_v2_rubygems_with_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1098,9 +1152,18 @@ async fn import_dump_v3_movie_raw() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1269,9 +1332,18 @@ async fn import_dump_v3_movie_with_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1439,9 +1511,18 @@ async fn import_dump_v3_rubygems_with_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1598,9 +1679,18 @@ async fn import_dump_v4_movie_raw() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1769,9 +1859,18 @@ async fn import_dump_v4_movie_with_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1939,9 +2038,18 @@ async fn import_dump_v4_rubygems_with_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -2190,7 +2298,8 @@ async fn import_dump_v6_containing_experimental_features() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 
@@ -2232,9 +2341,18 @@ async fn import_dump_v6_containing_experimental_features() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -2318,7 +2436,8 @@ async fn import_dump_v6_containing_batches_and_enqueued_tasks() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 
@@ -2426,7 +2545,8 @@ async fn generate_and_import_dump_containing_vectors() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 
@@ -2471,9 +2591,18 @@ async fn generate_and_import_dump_containing_vectors() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -27,7 +27,8 @@ async fn experimental_features() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 
@@ -45,7 +46,8 @@ async fn experimental_features() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 
@@ -63,7 +65,8 @@ async fn experimental_features() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 
@@ -82,7 +85,8 @@ async fn experimental_features() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 
@@ -101,7 +105,8 @@ async fn experimental_features() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 }
@@ -127,7 +132,8 @@ async fn experimental_feature_metrics() {
       "compositeEmbedders": false,
       "chatCompletions": false,
       "multimodal": false,
-      "vectorStoreSetting": false
+      "vectorStoreSetting": false,
+      "facetBitmaps": false
     }
     "###);
 
@@ -174,7 +180,7 @@ async fn errors() {
     meili_snap::snapshot!(code, @"400 Bad Request");
     meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
     {
-      "message": "Unknown field `NotAFeature`: expected one of `metrics`, `logsRoute`, `editDocumentsByFunction`, `containsFilter`, `network`, `getTaskDocumentsRoute`, `compositeEmbedders`, `chatCompletions`, `multimodal`, `vectorStoreSetting`",
+      "message": "Unknown field `NotAFeature`: expected one of `metrics`, `logsRoute`, `editDocumentsByFunction`, `containsFilter`, `network`, `getTaskDocumentsRoute`, `compositeEmbedders`, `chatCompletions`, `multimodal`, `vectorStoreSetting`, `facetBitmaps`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
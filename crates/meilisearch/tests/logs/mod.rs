@@ -55,6 +55,8 @@ async fn basic_test_log_stream_route() {
             index_scheduler: server.service.index_scheduler.clone().into(),
             auth: server.service.auth.clone().into(),
             search_queue: Data::new(search_queue),
+            facet_search_snapshots: server.service.facet_search_snapshots.clone(),
+            facet_search_rate_limiter: server.service.facet_search_rate_limiter.clone(),
             personalization_service: Data::new(PersonalizationService::disabled()),
             logs_route_handle: Data::new(route_layer_handle),
             logs_stderr_handle: Data::new(stderr_layer_handle),
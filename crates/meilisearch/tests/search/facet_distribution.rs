@@ -0,0 +1,711 @@
+use base64::Engine as _;
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+use roaring::RoaringBitmap;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "genres": ["Action", "Adventure"], "year": 2019 },
+        { "id": 2, "genres": ["Action", "Adventure"], "year": 2019 },
+        { "id": 3, "genres": ["Horror", "Thriller"], "year": 2021 },
+        { "id": 4, "genres": ["Action", "Comedy"], "year": 2019 },
+        { "id": 5, "genres": ["Thriller"], "year": 2018 },
+    ])
+});
+
+#[actix_rt::test]
+async fn facet_distribution_matches_search() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres", "year"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_distribution(json!({ "facets": ["genres", "year"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".processingTimeMs" => "[duration]" }), @r###"
+    {
+      "facetDistribution": {
+        "genres": {
+          "Action": 3,
+          "Adventure": 2,
+          "Comedy": 1,
+          "Horror": 1,
+          "Thriller": 2
+        },
+        "year": {
+          "2018": 1,
+          "2019": 3,
+          "2021": 1
+        }
+      },
+      "facetStats": {
+        "year": {
+          "min": 2018.0,
+          "max": 2021.0
+        }
+      },
+      "processingTimeMs": "[duration]"
+    }
+    "###);
+
+    let (search, code) = index.search_post(json!({ "facets": ["genres", "year"] })).await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(response["facetDistribution"], search["facetDistribution"]);
+    assert_eq!(response["facetStats"], search["facetStats"]);
+
+    let (response, code) = index.facet_distribution(json!({ "facets": ["*"] })).await;
+    snapshot!(code, @"200 OK");
+    let (search, _code) = index.search_post(json!({ "facets": ["*"] })).await;
+    assert_eq!(response["facetDistribution"], search["facetDistribution"]);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_respects_faceting_settings() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index
+        .update_settings_faceting(
+            json!({ "maxValuesPerFacet": 1, "sortFacetValuesBy": { "genres": "count" } }),
+        )
+        .await;
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_distribution(json!({ "facets": ["genres"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genres": {
+        "Action": 3
+      }
+    }
+    "###);
+
+    let (search, _code) = index.search_post(json!({ "facets": ["genres"] })).await;
+    assert_eq!(response["facetDistribution"], search["facetDistribution"]);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_respects_max_values_per_document_field() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_faceting(json!({ "maxValuesPerDocumentField": 1 })).await;
+    index.update_settings_filterable_attributes(json!(["genres", "year"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["faceting"]), @r###"
+    {
+      "maxValuesPerFacet": 100,
+      "maxValuesPerDocumentField": 1,
+      "trackValuesRecency": false,
+      "maxFacetLevels": null,
+      "expectedCardinality": {},
+      "updateMethod": {},
+      "valueWeightFields": {},
+      "sortFacetValuesBy": {
+        "*": "alpha"
+      },
+      "mode": "eager",
+      "warmup": null,
+      "pinnedDistributions": []
+    }
+    "###);
+
+    // only the first genre of each document is indexed
+    let (response, code) = index.facet_distribution(json!({ "facets": ["genres", "year"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genres": {
+        "Action": 3,
+        "Horror": 1,
+        "Thriller": 1
+      },
+      "year": {
+        "2018": 1,
+        "2019": 3,
+        "2021": 1
+      }
+    }
+    "###);
+
+    let (task, _status_code) =
+        index.update_settings_faceting(json!({ "maxValuesPerDocumentField": null })).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_distribution(json!({ "facets": ["genres"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genres": {
+        "Action": 3,
+        "Adventure": 2,
+        "Comedy": 1,
+        "Horror": 1,
+        "Thriller": 2
+      }
+    }
+    "###);
+
+    let (response, code) =
+        index.update_settings_faceting(json!({ "maxValuesPerDocumentField": 0 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_settings_faceting""###);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_of_facet_values() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres", "year"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the values are counted like equality filters, the unknown ones count 0
+    let (response, code) = index
+        .facet_distribution(json!({
+            "facets": ["year"],
+            "facetValues": {
+                "genres": ["Thriller", "action", "Western"],
+                "year": ["2019", "1990"],
+            },
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".processingTimeMs" => "[duration]" }), @r###"
+    {
+      "facetDistribution": {
+        "genres": {
+          "Thriller": 2,
+          "action": 3,
+          "Western": 0
+        },
+        "year": {
+          "2019": 3,
+          "1990": 0
+        }
+      },
+      "facetStats": {
+        "year": {
+          "min": 2018.0,
+          "max": 2021.0
+        }
+      },
+      "processingTimeMs": "[duration]"
+    }
+    "###);
+
+    let (response, code) = index
+        .facet_distribution(json!({ "facets": [], "facetValues": { "title": ["Action"] } }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_search_facets""###);
+
+    let (response, code) = index
+        .facet_distribution(json!({ "facets": [], "facetValues": { "genres": "Action" } }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_distribution_facet_values""###);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_granularity_level() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres", "price"])).await;
+    let documents: Vec<_> =
+        (0..100).map(|id| json!({ "id": id, "genres": ["Action"], "price": id })).collect();
+    let (task, _status_code) = index.add_documents(json!(documents), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the 100 prices are grouped in a few level 2 nodes
+    let (response, code) = index
+        .facet_distribution(json!({ "facets": ["genres", "price"], "granularityLevel": 2 }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetRanges"].as_object().unwrap().keys().collect::<Vec<_>>()), @r###"
+    [
+      "price"
+    ]
+    "###);
+    let buckets = response["facetRanges"]["price"].as_array().unwrap();
+    assert!(buckets.len() > 1 && buckets.len() < 100, "{buckets:?}");
+    // the buckets follow each other, the last one without upper bound
+    assert_eq!(buckets[0]["from"], json!(0.0));
+    for pair in buckets.windows(2) {
+        assert_eq!(pair[0]["to"], pair[1]["from"]);
+    }
+    assert_eq!(buckets.last().unwrap()["to"], json!(null));
+    let total: u64 = buckets.iter().map(|bucket| bucket["count"].as_u64().unwrap()).sum();
+    assert_eq!(total, 100);
+
+    // a level higher than the highest one of the tree returns no buckets
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": ["price"], "granularityLevel": 200 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetRanges"], @"{}");
+
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": ["price"], "granularityLevel": -1 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_distribution_granularity_level""###);
+}
+
+#[actix_rt::test]
+async fn sampled_facet_distribution() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let genres = ["Action", "Action", "Comedy", "Horror"];
+    let documents: Vec<_> =
+        (0..2000).map(|id| json!({ "id": id, "genres": genres[id % 4] })).collect();
+    let (task, _status_code) = index.add_documents(json!(documents), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (exact, code) = index.search_post(json!({ "facets": ["genres"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(exact["facetDistributionStats"]), @r###"
+    {
+      "genres": {
+        "distinctValues": 3
+      }
+    }
+    "###);
+
+    let query = json!({ "facets": ["genres"], "facetSampleSize": 500, "facetSampleSeed": 7 });
+    let (sampled, code) = index.search_post(query.clone()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(sampled["facetDistributionStats"]), @r###"
+    {
+      "genres": {
+        "distinctValues": 3,
+        "approximate": true
+      }
+    }
+    "###);
+    for (genre, count) in exact["facetDistribution"]["genres"].as_object().unwrap() {
+        let count = count.as_u64().unwrap() as f64;
+        let sampled_count = sampled["facetDistribution"]["genres"][genre].as_u64().unwrap() as f64;
+        assert!(
+            (sampled_count - count).abs() / count < 0.2,
+            "{genre}: {sampled_count} for {count}"
+        );
+    }
+
+    // the same seed returns the same counts
+    let (again, _code) = index.search_post(query).await;
+    assert_eq!(sampled["facetDistribution"], again["facetDistribution"]);
+
+    // fewer candidates than the sample size are all counted
+    let (response, code) = index
+        .search_post(
+            json!({ "facets": ["genres"], "filter": "genres = Horror", "facetSampleSize": 1000 }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genres": {
+        "Horror": 500
+      }
+    }
+    "###);
+    snapshot!(json_string!(response["facetDistributionStats"]["genres"]), @r###"
+    {
+      "distinctValues": 1
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({ "facets": ["genres"], "facetSampleSize": 0 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_search_facet_sample_size""###);
+}
+
+#[actix_rt::test]
+async fn pinned_facet_distribution() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres", "year"])).await;
+    index
+        .update_settings_faceting(json!({
+            "pinnedDistributions": [{ "filter": "year = 2019", "facets": ["year", "genres"] }]
+        }))
+        .await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["faceting"]["pinnedDistributions"]), @r###"
+    [
+      {
+        "filter": "year = 2019",
+        "facets": [
+          "year",
+          "genres"
+        ]
+      }
+    ]
+    "###);
+
+    // served from the pinned distribution, the facets can be requested in any order
+    let (response, code) =
+        index.search_post(json!({ "filter": "year = 2019", "facets": ["genres", "year"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genres": {
+        "Action": 3,
+        "Adventure": 2,
+        "Comedy": 1
+      },
+      "year": {
+        "2019": 3
+      }
+    }
+    "###);
+    // computed for this search, the filter is written differently
+    let (computed, _code) =
+        index.search_post(json!({ "filter": "year=2019", "facets": ["genres", "year"] })).await;
+    assert_eq!(response["facetDistribution"], computed["facetDistribution"]);
+    assert_eq!(response["facetStats"], computed["facetStats"]);
+
+    // the pinned distribution is refreshed once the documents are updated
+    let (task, _status_code) =
+        index.add_documents(json!([{ "id": 6, "genres": ["Drama"], "year": 2019 }]), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.search_post(json!({ "filter": "year = 2019", "facets": ["genres", "year"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genres": {
+        "Action": 3,
+        "Adventure": 2,
+        "Comedy": 1,
+        "Drama": 1
+      },
+      "year": {
+        "2019": 4
+      }
+    }
+    "###);
+
+    // the searches that don't count all the documents matching the filter are not served from it
+    let (response, code) = index
+        .search_post(json!({ "q": "drama", "filter": "year = 2019", "facets": ["genres", "year"] }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]["year"]), @r###"
+    {
+      "2019": 1
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_errors() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_distribution(json!({})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""missing_facet_distribution_facets""###);
+
+    let (response, code) = index.facet_distribution(json!({ "facets": "genres" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_distribution_facets""###);
+
+    let (response, code) = index.facet_distribution(json!({ "facets": ["title"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_search_facets""###);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_bitmaps() {
+    let server = Server::new().await;
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres", "year"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let query = json!({ "facets": ["genres", "year"], "facetBitmaps": true });
+    let (response, code) = index.facet_distribution(query.clone()).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using `facetBitmaps` in the facet distribution requires enabling the `facet bitmaps` experimental feature. See https://github.com/orgs/meilisearch/discussions",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+
+    server.set_features(json!({ "facetBitmaps": true })).await;
+    let (response, code) = index.facet_distribution(query).await;
+    snapshot!(code, @"200 OK");
+    let distribution = response["facetDistribution"].as_object().unwrap();
+    let bitmaps = response["facetBitmaps"].as_object().unwrap();
+    snapshot!(json!(bitmaps["genres"].as_object().unwrap().keys().collect::<Vec<_>>()), @r###"["Action","Adventure","Comedy","Horror","Thriller"]"###);
+    for (facet, values) in distribution {
+        for (value, count) in values.as_object().unwrap() {
+            let encoded = bitmaps[facet][value].as_str().unwrap();
+            let bytes = base64::prelude::BASE64_STANDARD.decode(encoded).unwrap();
+            let bitmap = RoaringBitmap::deserialize_from(&bytes[..]).unwrap();
+            assert_eq!(bitmap.len(), count.as_u64().unwrap(), "{facet}: {value}");
+        }
+    }
+
+    // the bitmaps of disjoint values don't intersect
+    let decode = |value: &str| {
+        let bytes =
+            base64::prelude::BASE64_STANDARD.decode(bitmaps["genres"][value].as_str().unwrap());
+        RoaringBitmap::deserialize_from(&bytes.unwrap()[..]).unwrap()
+    };
+    snapshot!((decode("Action") & decode("Thriller")).len(), @"0");
+    snapshot!((decode("Action") & decode("Adventure")).len(), @"2");
+
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": ["genres"], "facetBitmaps": "yes" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_distribution_facet_bitmaps""###);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_boolean_facets() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["inStock", "label"])).await;
+    let documents = json!([
+        { "id": 1, "inStock": true, "label": "true" },
+        { "id": 2, "inStock": true, "label": "maybe" },
+        { "id": 3, "inStock": false },
+        { "id": 4, "label": "false" },
+        { "id": 5 },
+    ]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // without `booleanFacets` the values are returned as usual, the strings included
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": ["inStock", "label"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "inStock": {
+        "false": 1,
+        "true": 2
+      },
+      "label": {
+        "false": 1,
+        "maybe": 1,
+        "true": 1
+      }
+    }
+    "###);
+
+    // only the requested boolean field gets the three buckets
+    let (response, code) = index
+        .facet_distribution(json!({ "facets": ["inStock", "label"], "booleanFacets": ["inStock"] }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "inStock": {
+        "true": 2,
+        "false": 1,
+        "missing": 2
+      },
+      "label": {
+        "false": 1,
+        "maybe": 1,
+        "true": 1
+      }
+    }
+    "###);
+
+    // a requested field with other values than booleans is returned as usual
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": ["label"], "booleanFacets": ["label"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "label": {
+        "false": 1,
+        "maybe": 1,
+        "true": 1
+      }
+    }
+    "###);
+
+    let (response, code) = index
+        .facet_distribution(json!({ "facets": ["inStock"], "booleanFacets": "inStock" }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_distribution_boolean_facets""###);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_numeric_precision() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["price"])).await;
+    let documents = json!([
+        { "id": 1, "price": 19.99 },
+        { "id": 2, "price": 19.990000001 },
+        { "id": 3, "price": 19.989999999 },
+        { "id": 4, "price": [19.99, 19.990000001] },
+        { "id": 5, "price": 5.5 },
+        { "id": 6, "price": 20 },
+    ]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_distribution(json!({ "facets": ["price"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "price": {
+        "19.989999999": 1,
+        "19.99": 2,
+        "19.990000001": 2,
+        "20": 1,
+        "5.5": 1
+      }
+    }
+    "###);
+
+    // the values differing by floating point noise are merged, the documents counted once
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": ["price"], "numericPrecision": 2 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "price": {
+        "5.5": 1,
+        "19.99": 4,
+        "20": 1
+      }
+    }
+    "###);
+    // the stats are computed on the stored values
+    snapshot!(json_string!(response["facetStats"]), @r###"
+    {
+      "price": {
+        "min": 5.5,
+        "max": 20.0
+      }
+    }
+    "###);
+
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": ["price"], "numericPrecision": 0 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "price": {
+        "6": 1,
+        "20": 5
+      }
+    }
+    "###);
+
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": ["price"], "numericPrecision": -1 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_distribution_numeric_precision""###);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_include_zero_counts() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the values matching no document are returned with a count of 0 by default
+    let query = json!({ "facets": [], "facetValues": { "genres": ["Comedy", "Western"] } });
+    let (response, code) = index.facet_distribution(query).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistribution"], @r###"{"genres":{"Comedy":1,"Western":0}}"###);
+
+    let query = json!({
+        "facets": [],
+        "facetValues": { "genres": ["Comedy", "Western"] },
+        "includeZeroCounts": false,
+    });
+    let (response, code) = index.facet_distribution(query).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistribution"], @r###"{"genres":{"Comedy":1}}"###);
+
+    let (response, code) =
+        index.facet_distribution(json!({ "facets": [], "includeZeroCounts": 1 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_distribution_include_zero_counts""###);
+}
+
+#[actix_rt::test]
+async fn search_facet_distribution_sorted_by_score_sum() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _status_code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["genre"],
+            "faceting": { "sortFacetValuesBy": { "genre": "count" } },
+        }))
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "title": "space dragon", "genre": "Fantasy" },
+        { "id": 2, "title": "space", "genre": "Drama" },
+        { "id": 3, "title": "space", "genre": "Drama" },
+    ]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let query = json!({ "q": "space dragon", "facets": ["genre"] });
+    let (response, code) = index.search_post(query.clone()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistribution"], @r###"{"genre":{"Drama":2,"Fantasy":1}}"###);
+
+    // the single document matching all the words outweighs the two matching a single word
+    let (task, _status_code) = index
+        .update_settings_faceting(json!({ "sortFacetValuesBy": { "genre": "scoreSum" } }))
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) = index.search_post(query).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistribution"], @r###"{"genre":{"Fantasy":1,"Drama":2}}"###);
+
+    // the facet distribution route has no scores, the values are sorted by count
+    let (response, code) = index.facet_distribution(json!({ "facets": ["genre"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistribution"], @r###"{"genre":{"Drama":2,"Fantasy":1}}"###);
+}
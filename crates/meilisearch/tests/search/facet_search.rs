@@ -1,4 +1,7 @@
-use meili_snap::snapshot;
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+use meili_snap::{json_string, snapshot};
 use meilisearch::Opt;
 use once_cell::sync::Lazy;
 use tempfile::TempDir;
@@ -243,6 +246,237 @@ async fn advanced_facet_search() {
     snapshot!(response["facetHits"].as_array().unwrap().len(), @"1");
 }
 
+#[actix_rt::test]
+async fn facet_search_with_match_type() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "genres": ["Action"] },
+        { "id": 2, "genres": ["Acton"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "action"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1},{"value":"Acton","count":1}]"###);
+
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "genres", "facetQuery": "action", "includeMatchType": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1,"matchType":"exact"},{"value":"Acton","count":1,"matchType":"typo"}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "includeMatchType": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_include_match_type""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_stats() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "genres": ["Action", "Adventure"] },
+        { "id": 2, "genres": ["Action"] },
+        { "id": 3, "genres": ["Adventure", "Comedy"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["stats"], @"null");
+
+    // The two matching values are counted four times but only belong to three documents.
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "a", "includeStats": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":2},{"value":"Adventure","count":2}]"###);
+    snapshot!(response["stats"], @r###"{"matchingDocuments":3,"totalValueOccurrences":4}"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "includeStats": true})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["stats"], @r###"{"matchingDocuments":3,"totalValueOccurrences":5}"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "includeStats": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_include_stats""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_missing_count() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "genres": ["Action", "Adventure"] },
+        { "id": 2, "genres": ["Comedy"] },
+        { "id": 3, "title": "Untitled" },
+        { "id": 4, "genres": null },
+        { "id": 5, "genres": [] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_search(json!({"facetName": "genres"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["missingCount"], @"null");
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "includeMissing": true})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1},{"value":"Adventure","count":1},{"value":"Comedy","count":1}]"###);
+    // the documents with a `null` or empty field have it, as with the `EXISTS` filter
+    snapshot!(response["missingCount"], @"1");
+
+    // Only the documents matching the search are counted.
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "q": "untitled", "includeMissing": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["missingCount"], @"1");
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "includeMissing": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_include_missing""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_collation() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "name": "Örjan" },
+        { "id": 2, "name": "Zelda" },
+        { "id": 3, "name": "Åsa" },
+        { "id": 4, "name": "Anna" },
+        { "id": 5, "name": "Ärla" },
+    ]);
+    index.update_settings_filterable_attributes(json!(["name"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // in Swedish å, ä and ö come after z
+    let (response, code) =
+        index.facet_search(json!({"facetName": "name", "collation": "sv-SE"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Anna","count":1},{"value":"Zelda","count":1},{"value":"Åsa","count":1},{"value":"Ärla","count":1},{"value":"Örjan","count":1}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "name", "collation": "swedish"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_collation""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_grouped_by_first_char() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let mut documents = DOCUMENTS.clone();
+    documents
+        .as_array_mut()
+        .unwrap()
+        .push(json!({ "id": "1", "title": "Tron", "genres": ["80s"] }).0);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "groupByFirstChar": true})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @"[]");
+    snapshot!(json_string!(response["facetGroups"]), @r###"
+    [
+      {
+        "key": "#",
+        "count": 1,
+        "values": [
+          {
+            "value": "80s",
+            "count": 1
+          }
+        ]
+      },
+      {
+        "key": "a",
+        "count": 2,
+        "values": [
+          {
+            "value": "Action",
+            "count": 3
+          },
+          {
+            "value": "Adventure",
+            "count": 2
+          }
+        ]
+      },
+      {
+        "key": "c",
+        "count": 1,
+        "values": [
+          {
+            "value": "Comedy",
+            "count": 1
+          }
+        ]
+      },
+      {
+        "key": "h",
+        "count": 1,
+        "values": [
+          {
+            "value": "Horror",
+            "count": 1
+          }
+        ]
+      },
+      {
+        "key": "m",
+        "count": 1,
+        "values": [
+          {
+            "value": "Multiple Words",
+            "count": 1
+          }
+        ]
+      },
+      {
+        "key": "t",
+        "count": 1,
+        "values": [
+          {
+            "value": "Thriller",
+            "count": 2
+          }
+        ]
+      }
+    ]
+    "###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "groupByFirstChar": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_group_by_first_char""###);
+}
+
 #[actix_rt::test]
 async fn more_advanced_facet_search() {
     let server = Server::new_shared();
@@ -319,10 +553,57 @@ async fn non_filterable_facet_search_error() {
     let (response, code) =
         index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
     assert_eq!(code, 400, "{response}");
+    snapshot!(response["code"], @r###""facet_search_facet_not_filterable""###);
 
     let (response, code) =
         index.facet_search(json!({"facetName": "genres", "facetQuery": "adv"})).await;
     assert_eq!(code, 400, "{response}");
+    snapshot!(response["code"], @r###""facet_search_facet_not_filterable""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_query_and_filter_errors() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let long_query = "a".repeat(1000);
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": long_query})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "The facet query is 1000 bytes long and exceeds the maximum facet value length of 468 bytes.",
+      "code": "facet_search_query_too_long",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#facet_search_query_too_long"
+    }
+    "###);
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQueries": ["act", long_query]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""facet_search_query_too_long""###);
+
+    // the filter is invalid, or valid but on an attribute that is not filterable
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "a", "filter": "genres ="}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_filter""###);
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "a", "filter": "id = 1"}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_filter""###);
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a", "filter": 42})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_filter""###);
 }
 
 #[actix_rt::test]
@@ -364,339 +645,1267 @@ async fn simple_facet_search_with_sort_by_count() {
 }
 
 #[actix_rt::test]
-async fn add_documents_and_deactivate_facet_search() {
+async fn facet_search_with_weighted_sort() {
     let server = Server::new_shared();
     let index = server.unique_index();
 
-    let documents = DOCUMENTS.clone();
-    let (response, _code) = index.add_documents(documents, None).await;
-    server.wait_task(response.uid()).await.succeeded();
-    let (response, code) = index
-        .update_settings(json!({
-            "facetSearch": false,
-            "filterableAttributes": ["genres"],
-        }))
+    let documents = json!([
+        { "id": 0, "genres": ["Action", "Comedy", "Drama", "Western"] },
+        { "id": 1, "genres": ["Comedy", "Drama", "Western"] },
+        { "id": 2, "genres": ["Drama", "Western"] },
+        { "id": 3, "genres": ["Drama"] },
+    ]);
+    let (task, code) = index
+        .update_settings_faceting(
+            json!({ "sortFacetValuesBy": { "genres": { "count": 0.7, "alpha": 0.3 } } }),
+        )
         .await;
-    assert_eq!("202", code.as_str(), "{response:?}");
-    server.wait_task(response.uid()).await.succeeded();
-
-    let (response, code) =
-        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(task.uid()).await.succeeded();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
 
-    assert_eq!(code, 400, "{response}");
-    snapshot!(response, @r###"
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["faceting"]["sortFacetValuesBy"]), @r###"
     {
-      "message": "The facet search is disabled for this index",
-      "code": "facet_search_disabled",
-      "type": "invalid_request",
-      "link": "https://docs.meilisearch.com/errors#facet_search_disabled"
+      "*": "alpha",
+      "genres": {
+        "count": 0.7,
+        "alpha": 0.3
+      }
     }
     "###);
-}
 
-#[actix_rt::test]
-async fn deactivate_facet_search_and_add_documents() {
-    let server = Server::new_shared();
-    let index = server.unique_index();
+    // the counts and the alphabetical ranks are combined, neither is enough on its own
+    let (response, code) = index.facet_search(json!({"facetName": "genres"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Drama","count":4},{"value":"Comedy","count":2},{"value":"Western","count":3},{"value":"Action","count":1}]"###);
 
-    let (response, code) = index
-        .update_settings(json!({
-            "facetSearch": false,
-            "filterableAttributes": ["genres"],
-        }))
-        .await;
-    assert_eq!("202", code.as_str(), "{response:?}");
-    server.wait_task(response.uid()).await.succeeded();
-    let documents = DOCUMENTS.clone();
-    let (response, _code) = index.add_documents(documents, None).await;
-    server.wait_task(response.uid()).await.succeeded();
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "sortBy": "count"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Drama","count":4},{"value":"Western","count":3},{"value":"Comedy","count":2},{"value":"Action","count":1}]"###);
 
     let (response, code) =
-        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+        index.facet_search(json!({"facetName": "genres", "sortBy": "alpha"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1},{"value":"Comedy","count":2},{"value":"Drama","count":4},{"value":"Western","count":3}]"###);
 
-    assert_eq!(code, 400, "{response}");
-    snapshot!(response, @r###"
+    let (response, code) = index
+        .update_settings_faceting(
+            json!({ "sortFacetValuesBy": { "genres": { "count": 0.7, "alpha": 0.7 } } }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
     {
-      "message": "The facet search is disabled for this index",
-      "code": "facet_search_disabled",
+      "message": "Invalid value at `.sortFacetValuesBy.genres`: the weights must sum up to 1, got 1.4",
+      "code": "invalid_settings_faceting",
       "type": "invalid_request",
-      "link": "https://docs.meilisearch.com/errors#facet_search_disabled"
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_faceting"
     }
     "###);
+
+    let (response, code) = index
+        .update_settings_faceting(json!({ "sortFacetValuesBy": { "genres": { "count": -1 } } }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_settings_faceting""###);
 }
 
 #[actix_rt::test]
-async fn deactivate_facet_search_add_documents_and_activate_facet_search() {
+async fn facet_search_sort_by_overrides_setting() {
     let server = Server::new_shared();
     let index = server.unique_index();
 
-    let (response, code) = index
-        .update_settings(json!({
-            "facetSearch": false,
-            "filterableAttributes": ["genres"],
-        }))
-        .await;
-    assert_eq!("202", code.as_str(), "{response:?}");
-    server.wait_task(response.uid()).await.succeeded();
-    let documents = DOCUMENTS.clone();
-    let (response, _code) = index.add_documents(documents, None).await;
-    server.wait_task(response.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "genres": ["Action", "Comedy"] },
+        { "id": 2, "genres": ["Adventure", "Comedy"] },
+        { "id": 3, "genres": ["Adventure", "Comedy"] },
+    ]);
+    index.update_settings_faceting(json!({ "sortFacetValuesBy": { "*": "alpha" } })).await;
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
 
-    let (response, code) = index
-        .update_settings(json!({
-            "facetSearch": true,
-        }))
-        .await;
-    assert_eq!("202", code.as_str(), "{response:?}");
-    server.wait_task(response.uid()).await.succeeded();
+    let (response, code) = index.facet_search(json!({"facetName": "genres"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1},{"value":"Adventure","count":2},{"value":"Comedy","count":3}]"###);
 
     let (response, code) =
-        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+        index.facet_search(json!({"facetName": "genres", "sortBy": "count"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Comedy","count":3},{"value":"Adventure","count":2},{"value":"Action","count":1}]"###);
 
-    assert_eq!(code, 200, "{response}");
-    assert_eq!(response["facetHits"].as_array().unwrap().len(), 2);
+    // the index setting is left untouched
+    let (response, code) = index.facet_search(json!({"facetName": "genres"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1},{"value":"Adventure","count":2},{"value":"Comedy","count":3}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "sortBy": "random"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_sort_by""###);
 }
 
 #[actix_rt::test]
-async fn deactivate_facet_search_add_documents_and_reset_facet_search() {
+async fn facet_search_include_sort_keys() {
     let server = Server::new_shared();
     let index = server.unique_index();
 
+    let documents = json!([
+        { "id": 1, "genres": ["Action", "Comedy"] },
+        { "id": 2, "genres": ["Adventure", "Comedy"] },
+        { "id": 3, "genres": ["Adventure", "Comedy"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
     let (response, code) = index
-        .update_settings(json!({
-            "facetSearch": false,
-            "filterableAttributes": ["genres"],
-        }))
+        .facet_search(json!({"facetName": "genres", "sortBy": "count", "includeSortKeys": true}))
         .await;
-    assert_eq!("202", code.as_str(), "{response:?}");
-    server.wait_task(response.uid()).await.succeeded();
-    let documents = DOCUMENTS.clone();
-    let (response, _code) = index.add_documents(documents, None).await;
-    server.wait_task(response.uid()).await.succeeded();
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Comedy","count":3,"sortKey":"comedy"},{"value":"Adventure","count":2,"sortKey":"adventure"},{"value":"Action","count":1,"sortKey":"action"}]"###);
 
+    // sorting the values by their keys gives the order of the server
+    let mut by_count = response["facetHits"].as_array().unwrap().clone();
+    by_count.sort_by(|a, b| a["sortKey"].as_str().cmp(&b["sortKey"].as_str()));
     let (response, code) = index
-        .update_settings(json!({
-            "facetSearch": serde_json::Value::Null,
-        }))
+        .facet_search(json!({"facetName": "genres", "sortBy": "alpha", "includeSortKeys": true}))
         .await;
-    assert_eq!("202", code.as_str(), "{response:?}");
-    server.wait_task(response.uid()).await.succeeded();
-
-    let (response, code) =
-        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(response["facetHits"], json!(by_count));
 
-    assert_eq!(code, 200, "{response}");
-    assert_eq!(response["facetHits"].as_array().unwrap().len(), 2);
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "a", "includeSortKeys": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1,"sortKey":"action"},{"value":"Adventure","count":2,"sortKey":"adventure"}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "includeSortKeys": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_include_sort_keys""###);
 }
 
 #[actix_rt::test]
-async fn facet_search_with_filterable_attributes_rules() {
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &DOCUMENTS,
-        &json!({"filterableAttributes": ["genres"]}),
-        &json!({"facetName": "genres", "facetQuery": "a"}),
-        |response, code| {
-            snapshot!(code, @"200 OK");
-            snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2}]"###);
-        },
-    )
-    .await;
+async fn facet_search_on_numeric_facets() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &DOCUMENTS,
-        &json!({"filterableAttributes": [{"attributePatterns": ["genres"], "features": {"facetSearch": true, "filter": {"equality": false, "comparison": false}}}]}),
-        &json!({"facetName": "genres", "facetQuery": "a"}),
-        |response, code| {
-            snapshot!(code, @"200 OK");
-            snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2}]"###);
-        },
-    ).await;
+    let documents = json!([
+        { "id": 1, "price": 10, "genres": ["Action"], "tags": [1, "one"] },
+        { "id": 2, "price": 25.5, "genres": ["Adventure"], "tags": [2] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["price", "genres", "tags"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &NESTED_DOCUMENTS,
-        &json!({"filterableAttributes": ["doggos.name"]}),
-        &json!({"facetName": "doggos.name", "facetQuery": "b"}),
-        |response, code| {
-            snapshot!(code, @"200 OK");
-            snapshot!(response["facetHits"], @r###"[{"value":"bobby","count":1},{"value":"buddy","count":1}]"###);
-        },
-    )
-    .await;
+    // a field that only contains numbers is rejected instead of silently returning nothing
+    let (response, code) =
+        index.facet_search(json!({"facetName": "price", "facetQuery": "10"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Attribute `price` only contains numbers and cannot be used for a facet search. Facet search only applies to string facet values, use a `filter` to search for numbers instead.",
+      "code": "facet_search_numeric_facet",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#facet_search_numeric_facet"
+    }
+    "###);
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &NESTED_DOCUMENTS,
-        &json!({"filterableAttributes": [{"attributePatterns": ["doggos.name"], "features": {"facetSearch": true, "filter": {"equality": false, "comparison": false}}}]}),
-        &json!({"facetName": "doggos.name", "facetQuery": "b"}),
-        |response, code| {
-            snapshot!(code, @"200 OK");
-            snapshot!(response["facetHits"], @r###"[{"value":"bobby","count":1},{"value":"buddy","count":1}]"###);
-        },
-    ).await;
+    let (response, code) = index.facet_search(json!({"facetName": "price"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""facet_search_numeric_facet""###);
+
+    // string fields and fields mixing numbers and strings search the string values
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "adv"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Adventure","count":1}]"###);
+
+    let (response, code) = index.facet_search(json!({"facetName": "tags"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"one","count":1}]"###);
 }
 
 #[actix_rt::test]
-async fn facet_search_with_filterable_attributes_rules_errors() {
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &DOCUMENTS,
-        &json!({"filterableAttributes": ["genres"]}),
-        &json!({"facetName": "invalid", "facetQuery": "a"}),
-        |response, code| {
-            snapshot!(code, @"400 Bad Request");
-            snapshot!(response["message"], @r###""Attribute `invalid` is not facet-searchable. Available facet-searchable attributes patterns are: `genres`. To make it facet-searchable add it to the `filterableAttributes` index settings.""###);
-        },
-    )
-    .await;
+async fn facet_search_exact() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-      &DOCUMENTS,
-      &json!({"filterableAttributes": [{"attributePatterns": ["genres"]}]}),
-      &json!({"facetName": "genres", "facetQuery": "a"}),
-      |response, code| {
-          snapshot!(code, @"400 Bad Request");
-          snapshot!(response["message"], @r###""Attribute `genres` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching genres with facetSearch: true before rule #0""###);
-      },
-    )
-    .await;
+    let documents = json!([
+        { "id": 1, "genres": ["Action", "Adventure"] },
+        { "id": 2, "genres": ["Action"] },
+        { "id": 3, "genres": ["action"] },
+        { "id": 4, "genres": ["Action-Adventure"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &DOCUMENTS,
-        &json!({"filterableAttributes": [{"attributePatterns": ["genres"], "features": {"facetSearch": false, "filter": {"equality": true, "comparison": true}}}]}),
-        &json!({"facetName": "genres", "facetQuery": "a"}),
-        |response, code| {
-            snapshot!(code, @"400 Bad Request");
-            snapshot!(response["message"], @r###""Attribute `genres` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching genres with facetSearch: true before rule #0""###);
-        },
-    ).await;
+    // the colliding values are merged into the most frequent one
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "Action", "exact": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3}]"###);
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &DOCUMENTS,
-        &json!({"filterableAttributes": [{"attributePatterns": ["genres"], "features": {"facetSearch": false, "filter": {"equality": false, "comparison": false}}}]}),
-        &json!({"facetName": "genres", "facetQuery": "a"}),
-        |response, code| {
-            snapshot!(code, @"400 Bad Request");
-            snapshot!(response["message"], @r###""Attribute `genres` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching genres with facetSearch: true before rule #0""###);
-        },
-    ).await;
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "Act", "exact": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @"[]");
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &NESTED_DOCUMENTS,
-        &json!({"filterableAttributes": [{"attributePatterns": ["doggos.name"]}]}),
-        &json!({"facetName": "invalid.name", "facetQuery": "b"}),
-        |response, code| {
-            snapshot!(code, @"400 Bad Request");
-            snapshot!(response["message"], @r###""Attribute `invalid.name` is not facet-searchable. This index does not have configured facet-searchable attributes. To make it facet-searchable add it to the `filterableAttributes` index settings.""###);
-        },
-    )
-    .await;
+    let (response, code) = index.facet_search(json!({"facetName": "genres", "exact": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_exact""###);
+}
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &NESTED_DOCUMENTS,
-        &json!({"filterableAttributes": [{"attributePatterns": ["doggos.name"], "features": {"facetSearch": false, "filter": {"equality": true, "comparison": true}}}]}),
-        &json!({"facetName": "doggos.name", "facetQuery": "b"}),
-        |response, code| {
-            snapshot!(code, @"400 Bad Request");
-            snapshot!(response["message"], @r###""Attribute `doggos.name` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching doggos.name with facetSearch: true before rule #0""###);
-        },
-    ).await;
+#[actix_rt::test]
+async fn facet_search_match_tokens() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
 
-    test_settings_documents_indexing_swapping_and_facet_search(
-        &NESTED_DOCUMENTS,
-        &json!({"filterableAttributes": [{"attributePatterns": ["doggos.name"], "features": {"facetSearch": false, "filter": {"equality": false, "comparison": false}}}]}),
-        &json!({"facetName": "doggos.name", "facetQuery": "b"}),
-        |response, code| {
-            snapshot!(code, @"400 Bad Request");
-            snapshot!(response["message"], @r###""Attribute `doggos.name` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching doggos.name with facetSearch: true before rule #0""###);
-        },
-    ).await;
+    let documents = json!([
+        { "id": 1, "genres": ["Action-Adventure"] },
+        { "id": 2, "genres": ["Adventure"] },
+        { "id": 3, "genres": ["Sci-Fi", "Action"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "adventure"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Adventure","count":1}]"###);
+
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "genres", "facetQuery": "adventure", "matchTokens": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action-Adventure","count":1},{"value":"Adventure","count":1}]"###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "fi", "matchTokens": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Sci-Fi","count":1}]"###);
+
+    // the values are tokenized with the separators of the index
+    let (task, _status_code) = index.update_settings(json!({"nonSeparatorTokens": ["-"]})).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "genres", "facetQuery": "adventure", "matchTokens": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Adventure","count":1}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "matchTokens": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_match_tokens""###);
 }
 
 #[actix_rt::test]
-async fn distinct_facet_search_on_movies() {
+async fn facet_search_browse_mode() {
     let server = Server::new_shared();
     let index = server.unique_index();
 
     let documents = json!([
-      {
-        "id": 1,
-        "title": "Carol",
-        "genres": ["Romance", "Drama", "Blob"],
-        "color": "crimson"
-      },
-      {
-        "id": 2,
-        "title": "Wonder Woman",
-        "genres": ["Action", "Adventure", "Blob"],
-        "color": "emerald"
-      },
-      {
-        "id": 3,
-        "title": "Life of Pi",
-        "genres": ["Adventure", "Drama", "Blob"],
-        "color": "azure"
-      },
-      {
-        "id": 4,
-        "title": "Mad Max: Fury Road",
-        "genres": ["Adventure", "Science Fiction", "Blob"],
-        "color": "scarlet"
-      },
-      {
-        "id": 5,
-        "title": "Moana",
-        "genres": ["Fantasy", "Action", "Blob"],
-        "color": "coral"
-      },
-      {
-        "id": 6,
-        "title": "Philadelphia",
-        "genres": ["Drama", "Blob"],
-        "color": "navy"
-      },
-      {
-        "id": 7,
-        "title": "The Matrix",
-        "genres": ["Science Fiction", "Action", "Blob"],
-        "color": "onyx"
-      },
-      {
-        "id": 8,
-        "title": "Inception",
-        "genres": ["Science Fiction", "Thriller", "Blob"],
-        "color": "cerulean"
-      },
-      {
-        "id": 9,
-        "title": "The Shawshank Redemption",
-        "genres": ["Drama", "Blob"],
-        "color": "slate"
-      },
-      {
-        "id": 10,
-        "title": "Pulp Fiction",
-        "genres": ["Crime", "Drama", "Blob"],
-        "color": "gold"
-      },
-      {
-        "id": 11,
-        "title": "The Dark Knight",
-        "genres": ["Action", "Crime", "Blob"],
-        "color": "obsidian"
-      },
-      {
-        "id": 12,
-        "title": "Forrest Gump",
-        "genres": ["Drama", "Romance", "Blob"],
-        "color": "jade"
-      },
-      {
-        "id": 13,
-        "title": "The Godfather",
-        "genres": ["Crime", "Drama", "Blob"],
-        "color": "sepia"
-      },
-      {
-        "id": 14,
-        "title": "Fight Club",
+        { "id": 1, "genres": ["Horror", "Comedy"] },
+        { "id": 2, "genres": ["Drama", "Comedy"] },
+        { "id": 3, "genres": ["Action", "Adventure"] },
+        { "id": 4, "genres": ["Àdventure"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index
+        .update_settings(
+            json!({"faceting": {"maxValuesPerFacet": 2, "sortFacetValuesBy": {"*": "count"}}}),
+        )
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the values are enumerated alphabetically whatever `sortFacetValuesBy`, all the
+    // original values of a normalized value are returned in the same page
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "browseMode": true})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1,"sortKey":"action"},{"value":"Adventure","count":1,"sortKey":"adventure"},{"value":"Àdventure","count":1,"sortKey":"adventure"}]"###);
+
+    // the following pages are fetched with the cursors like with `after`
+    let cursor = response["nextCursor"].as_str().unwrap().to_string();
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "browseMode": true, "after": cursor}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Comedy","count":2,"sortKey":"comedy"},{"value":"Drama","count":1,"sortKey":"drama"}]"###);
+
+    let cursor = response["nextCursor"].as_str().unwrap().to_string();
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "browseMode": true, "after": cursor}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Horror","count":1,"sortKey":"horror"}]"###);
+    // a page that isn't full is the last one
+    snapshot!(response["nextCursor"], @"null");
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "browseMode": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_browse_mode""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_cursor_pagination() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "genres": ["Action", "Comedy"] },
+        { "id": 2, "genres": ["Drama", "Comedy"] },
+        { "id": 3, "genres": ["Adventure"] },
+        { "id": 4, "genres": ["Àdventure", "Horror"] },
+        { "id": 5, "genres": ["Thriller"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) =
+        index.update_settings(json!({"faceting": {"maxValuesPerFacet": 2}})).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the pages are walked with the cursors while the index is written between them
+    let mut values = Vec::new();
+    let mut pages = 0;
+    let mut after = Some(String::new());
+    while let Some(cursor) = after {
+        let (response, code) =
+            index.facet_search(json!({"facetName": "genres", "after": cursor})).await;
+        snapshot!(code, @"200 OK");
+        for hit in response["facetHits"].as_array().unwrap() {
+            values.push(hit["value"].as_str().unwrap().to_string());
+        }
+        after = response["nextCursor"].as_str().map(ToString::to_string);
+        pages += 1;
+
+        let (task, _status_code) =
+            index.add_documents(json!([{ "id": 5, "genres": ["Thriller"] }]), None).await;
+        server.wait_task(task.uid()).await.succeeded();
+    }
+    assert_eq!(pages, 4);
+    assert_eq!(
+        values,
+        ["Action", "Adventure", "Àdventure", "Comedy", "Drama", "Horror", "Thriller"]
+    );
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "after": "comedy"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_after""###);
+
+    // the cursors are positions in the alphabetical order
+    let (task, _status_code) =
+        index.update_settings(json!({"faceting": {"sortFacetValuesBy": {"*": "count"}}})).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) = index.facet_search(json!({"facetName": "genres", "after": ""})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Facet search cursors can only be used when the facet values are sorted alphabetically, not ranked by relevance and not boosted.",
+      "code": "invalid_facet_search_after",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_facet_search_after"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn facet_search_include_normalized() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "genres": ["adventure"] },
+        { "id": 2, "genres": ["Àdventure"] },
+        { "id": 3, "genres": ["Action"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "genres", "facetQuery": "adv", "includeNormalized": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"adventure","count":1,"normalizedValue":"adventure"},{"value":"Àdventure","count":1,"normalizedValue":"adventure"}]"###);
+
+    // without a facet query the normalized form is computed from the original values
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "includeNormalized": true})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1,"normalizedValue":"action"},{"value":"adventure","count":1,"normalizedValue":"adventure"},{"value":"Àdventure","count":1,"normalizedValue":"adventure"}]"###);
+
+    // the normalized form is not returned by default
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "adv"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"adventure","count":1},{"value":"Àdventure","count":1}]"###);
+}
+
+#[actix_rt::test]
+async fn facet_search_representative_value() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "genres": ["adventure"] },
+        { "id": 2, "genres": ["Àdventure"] },
+        { "id": 3, "genres": ["Àdventure", "Action"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the values equal once normalized are returned as a single hit counting all their documents
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "genres", "facetQuery": "adv", "representativeValue": "first"}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"adventure","count":3}]"###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "representativeValue": "mostFrequent"}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1},{"value":"Àdventure","count":3}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "representativeValue": "last"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_representative_value""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_boost_document_ids() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "genres": ["Drama"] },
+        { "id": 2, "genres": ["Drama"] },
+        { "id": 3, "genres": ["Drama", "Romance"] },
+        { "id": 4, "genres": ["Comedy"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "sortBy": "count"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Drama","count":3},{"value":"Romance","count":1},{"value":"Comedy","count":1}]"###);
+
+    // the values of the top hits come first, the unknown documents are ignored
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "genres", "sortBy": "count", "boostDocumentIds": [4, "404"]}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Comedy","count":1},{"value":"Drama","count":3},{"value":"Romance","count":1}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "boostDocumentIds": [{ "id": 4 }]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_boost_document_ids""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_prefix_last_token() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "tags": ["Multiple Words"] },
+        { "id": 2, "tags": ["Single Word"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["tags"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "tags", "facetQuery": "Multiple Wo", "prefixLastToken": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Multiple Words","count":1}]"###);
+
+    // the leading words must match exactly
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "tags", "facetQuery": "Multiple X", "prefixLastToken": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @"[]");
+
+    // by default the whole query is typo tolerant
+    let (response, code) =
+        index.facet_search(json!({"facetName": "tags", "facetQuery": "Multiple X"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Multiple Words","count":1}]"###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_multiple_prefixes() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQueries": ["a", "t"]})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2},{"value":"Thriller","count":2}]"###);
+
+    // the values matching several prefixes are only returned once
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQueries": ["T", "ad", "a", "ac"]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2},{"value":"Thriller","count":2}]"###);
+
+    let (task, _status_code) =
+        index.update_settings_faceting(json!({ "maxValuesPerFacet": 2 })).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQueries": ["t", "a"]})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2}]"###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "a", "facetQueries": ["t"]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_facet_queries""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_and_distribution_with_min_count() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_search(json!({"facetName": "genres"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2},{"value":"Comedy","count":1},{"value":"Horror","count":1},{"value":"Multiple Words","count":1},{"value":"Thriller","count":2}]"###);
+
+    let (response, code) = index.facet_search(json!({"facetName": "genres", "minCount": 2})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2},{"value":"Thriller","count":2}]"###);
+
+    let (response, code) = index.search_post(json!({"facets": ["genres"], "minCount": 2})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistribution"], @r###"{"genres":{"Action":3,"Adventure":2,"Thriller":2}}"###);
+
+    let (response, code) = index.facet_search(json!({"facetName": "genres", "minCount": -1})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_min_count""###);
+}
+
+#[actix_rt::test]
+async fn facet_distribution_stats_count_distinct_values() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({"facets": ["genres"]})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistributionStats"], @r###"{"genres":{"distinctValues":6}}"###);
+
+    let (response, code) =
+        index.search_post(json!({"facets": ["genres"], "filter": "genres = Thriller"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistributionStats"], @r###"{"genres":{"distinctValues":3}}"###);
+
+    let (response, code) = index.search_post(json!({"q": "Captain"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistributionStats"], @"null");
+}
+
+#[actix_rt::test]
+async fn facet_trees_in_index_stats() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.stats().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetTrees"], @"null");
+
+    let (response, code) =
+        index.service.get(format!("/indexes/{}/stats?facetTrees=true", index.uid)).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetTrees"], @r###"{"genres":{"facetLevels":1,"facetNodeCount":6}}"###);
+
+    let (response, code) =
+        index.service.get(format!("/indexes/{}/stats?facetTrees=maybe", index.uid)).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_index_stats_facet_trees""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_ranked_by_relevance() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "title": "kefir", "tags": ["Zebra"] },
+        { "id": 2, "title": "kefyr", "tags": ["Aardvark"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["tags"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_search(json!({"facetName": "tags", "q": "kefir"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Aardvark","count":1},{"value":"Zebra","count":1}]"###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "tags", "q": "kefir", "rankByRelevance": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Zebra","count":1},{"value":"Aardvark","count":1}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "tags", "rankByRelevance": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_rank_by_relevance""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_lazy_faceting_mode() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _status_code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["genres"],
+            "faceting": { "mode": "lazy" },
+        }))
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let documents = json!([
+        { "id": 1, "genres": ["adventure"] },
+        { "id": 2, "genres": ["Àdventure", "Action"] },
+        { "id": 3, "genres": ["Action"] },
+    ]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["faceting"]["mode"], @r###""lazy""###);
+
+    // the facet search structures are built on the first query
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "adv"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"adventure","count":1},{"value":"Àdventure","count":1}]"###);
+
+    let (response, code) =
+        index.search_post(json!({"filter": "genres = Action", "facets": ["genres"]})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetDistribution"], @r###"{"genres":{"Action":2,"Àdventure":1}}"###);
+
+    // switching back to the eager mode builds the deferred structures
+    let (task, _status_code) =
+        index.update_settings(json!({ "faceting": { "mode": "eager" } })).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "act"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":2}]"###);
+}
+
+#[actix_rt::test]
+async fn add_documents_and_deactivate_facet_search() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    let (response, _code) = index.add_documents(documents, None).await;
+    server.wait_task(response.uid()).await.succeeded();
+    let (response, code) = index
+        .update_settings(json!({
+            "facetSearch": false,
+            "filterableAttributes": ["genres"],
+        }))
+        .await;
+    assert_eq!("202", code.as_str(), "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+
+    assert_eq!(code, 200, "{response}");
+    snapshot!(response, @r###"
+    {
+      "facetHits": [],
+      "facetQuery": "a",
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": false,
+      "appliedLimit": 100,
+      "limitSource": "system"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn deactivate_facet_search_and_add_documents() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index
+        .update_settings(json!({
+            "facetSearch": false,
+            "filterableAttributes": ["genres"],
+        }))
+        .await;
+    assert_eq!("202", code.as_str(), "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+    let documents = DOCUMENTS.clone();
+    let (response, _code) = index.add_documents(documents, None).await;
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+
+    assert_eq!(code, 200, "{response}");
+    snapshot!(response, @r###"
+    {
+      "facetHits": [],
+      "facetQuery": "a",
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": false,
+      "appliedLimit": 100,
+      "limitSource": "system"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn facet_search_enabled_distinguishes_disabled_from_no_matches() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the facet search is enabled but no facet value matches
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "xyz"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    {
+      "facetHits": [],
+      "facetQuery": "xyz",
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": true,
+      "appliedLimit": 100,
+      "limitSource": "system"
+    }
+    "###);
+
+    // an unknown facet is still an error
+    let (response, code) =
+        index.facet_search(json!({"facetName": "unknown", "facetQuery": "a"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""facet_search_facet_not_filterable""###);
+
+    // the facet search is disabled, even the values that would match are not returned
+    let (task, _status_code) = index.update_settings(json!({"facetSearch": false})).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    {
+      "facetHits": [],
+      "facetQuery": "a",
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": false,
+      "appliedLimit": 100,
+      "limitSource": "system"
+    }
+    "###);
+
+    // enabling it back returns the matching values again
+    let (task, _status_code) = index.update_settings(json!({"facetSearch": true})).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetSearchEnabled"], @"true");
+    snapshot!(response["facetHits"].as_array().unwrap().len(), @"2");
+}
+
+#[actix_rt::test]
+async fn deactivate_facet_search_add_documents_and_activate_facet_search() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index
+        .update_settings(json!({
+            "facetSearch": false,
+            "filterableAttributes": ["genres"],
+        }))
+        .await;
+    assert_eq!("202", code.as_str(), "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+    let documents = DOCUMENTS.clone();
+    let (response, _code) = index.add_documents(documents, None).await;
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index
+        .update_settings(json!({
+            "facetSearch": true,
+        }))
+        .await;
+    assert_eq!("202", code.as_str(), "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+
+    assert_eq!(code, 200, "{response}");
+    assert_eq!(response["facetHits"].as_array().unwrap().len(), 2);
+}
+
+#[actix_rt::test]
+async fn deactivate_facet_search_add_documents_and_reset_facet_search() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index
+        .update_settings(json!({
+            "facetSearch": false,
+            "filterableAttributes": ["genres"],
+        }))
+        .await;
+    assert_eq!("202", code.as_str(), "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+    let documents = DOCUMENTS.clone();
+    let (response, _code) = index.add_documents(documents, None).await;
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index
+        .update_settings(json!({
+            "facetSearch": serde_json::Value::Null,
+        }))
+        .await;
+    assert_eq!("202", code.as_str(), "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+
+    assert_eq!(code, 200, "{response}");
+    assert_eq!(response["facetHits"].as_array().unwrap().len(), 2);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_filterable_attributes_rules() {
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &DOCUMENTS,
+        &json!({"filterableAttributes": ["genres"]}),
+        &json!({"facetName": "genres", "facetQuery": "a"}),
+        |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2}]"###);
+        },
+    )
+    .await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &DOCUMENTS,
+        &json!({"filterableAttributes": [{"attributePatterns": ["genres"], "features": {"facetSearch": true, "filter": {"equality": false, "comparison": false}}}]}),
+        &json!({"facetName": "genres", "facetQuery": "a"}),
+        |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2}]"###);
+        },
+    ).await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &NESTED_DOCUMENTS,
+        &json!({"filterableAttributes": ["doggos.name"]}),
+        &json!({"facetName": "doggos.name", "facetQuery": "b"}),
+        |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(response["facetHits"], @r###"[{"value":"bobby","count":1},{"value":"buddy","count":1}]"###);
+        },
+    )
+    .await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &NESTED_DOCUMENTS,
+        &json!({"filterableAttributes": [{"attributePatterns": ["doggos.name"], "features": {"facetSearch": true, "filter": {"equality": false, "comparison": false}}}]}),
+        &json!({"facetName": "doggos.name", "facetQuery": "b"}),
+        |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(response["facetHits"], @r###"[{"value":"bobby","count":1},{"value":"buddy","count":1}]"###);
+        },
+    ).await;
+}
+
+#[actix_rt::test]
+async fn facet_search_on_nested_object_array_agrees_with_filter() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "attributes": [{ "name": "color", "value": "Red" }, { "name": "size", "value": "XL" }] },
+        { "id": 2, "attributes": [{ "name": "color", "value": "Red" }, { "name": "size", "value": "M" }] },
+        { "id": 3, "attributes": [{ "name": "color", "value": "Blue" }] },
+        { "id": 4, "attributes": { "name": "color", "value": "Green" } },
+        { "id": 5, "attributes": [] },
+        // arrays of arrays are flattened like a single array
+        { "id": 6, "attributes": [[{ "name": "color", "value": "Purple" }], [{ "name": "size", "value": "S" }]] },
+        // the scalars of a mixed array are values of the array field itself
+        { "id": 7, "attributes": ["loose", { "name": "color", "value": "Red" }, 42] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["attributes"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_search(json!({"facetName": "attributes.value"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Blue","count":1},{"value":"Green","count":1},{"value":"M","count":1},{"value":"Purple","count":1},{"value":"Red","count":3},{"value":"S","count":1},{"value":"XL","count":1}]"###);
+
+    let (response, code) = index.facet_search(json!({"facetName": "attributes"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"loose","count":1}]"###);
+
+    // the facet search, the facet distribution and the filters must see the same values
+    for facet in ["attributes.value", "attributes"] {
+        let (response, code) = index.facet_search(json!({"facetName": facet})).await;
+        assert_eq!(code, 200, "{response}");
+        let hits: Vec<_> = response["facetHits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| (hit["value"].as_str().unwrap().to_string(), hit["count"].clone()))
+            .collect();
+
+        let (response, code) =
+            index.search_post(json!({"facets": [facet], "hitsPerPage": 0})).await;
+        assert_eq!(code, 200, "{response}");
+        let distribution: Vec<_> = response["facetDistribution"][facet]
+            .as_object()
+            .unwrap()
+            .iter()
+            .filter(|(value, _)| value.parse::<f64>().is_err())
+            .map(|(value, count)| (value.clone(), count.clone()))
+            .collect();
+        assert_eq!(hits, distribution, "{facet}");
+
+        for (value, count) in distribution {
+            let filter = format!("{facet} = {value:?}");
+            let (response, code) =
+                index.search_post(json!({"filter": filter, "hitsPerPage": 0})).await;
+            assert_eq!(code, 200, "{response}");
+            assert_eq!(response["totalHits"], count, "{filter}: {response}");
+        }
+    }
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "attributes.value", "facetQuery": "re", "filter": "attributes.name = size"}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Red","count":2}]"###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_lossy_and_strict_normalizations() {
+    let documents = json!([
+        { "id": 1, "title": "Été", "code": "Été" },
+        { "id": 2, "title": "Hiver", "code": "Hiver" },
+    ]);
+    let settings = json!({"filterableAttributes": [
+        {"attributePatterns": ["title"], "features": {"facetSearch": true}},
+        {"attributePatterns": ["code"], "features": {"facetSearch": true, "lossyNormalization": false}},
+    ]});
+
+    // the accents are removed from the values and the queries of the lossy field
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &documents,
+        &settings,
+        &json!({"facetName": "title", "facetQuery": "ete"}),
+        |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(response["facetHits"], @r###"[{"value":"Été","count":1}]"###);
+        },
+    )
+    .await;
+
+    // but kept for the strict field
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &documents,
+        &settings,
+        &json!({"facetName": "code", "facetQuery": "ete"}),
+        |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(response["facetHits"], @"[]");
+        },
+    )
+    .await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &documents,
+        &settings,
+        &json!({"facetName": "code", "facetQuery": "été"}),
+        |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(response["facetHits"], @r###"[{"value":"Été","count":1}]"###);
+        },
+    )
+    .await;
+}
+
+#[actix_rt::test]
+async fn changing_the_facet_search_normalization_rebuilds_the_facet_values() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([{ "id": 1, "code": "Été" }]);
+    let (response, _code) = index
+        .update_settings(json!({"filterableAttributes": [
+            {"attributePatterns": ["code"], "features": {"facetSearch": true, "lossyNormalization": false}},
+        ]}))
+        .await;
+    server.wait_task(response.uid()).await.succeeded();
+    let (response, _code) = index.add_documents(documents, None).await;
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "code", "facetQuery": "ete"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @"[]");
+
+    let (response, _code) = index
+        .update_settings(json!({"filterableAttributes": [
+            {"attributePatterns": ["code"], "features": {"facetSearch": true}},
+        ]}))
+        .await;
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "code", "facetQuery": "ete"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Été","count":1}]"###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_filterable_attributes_rules_errors() {
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &DOCUMENTS,
+        &json!({"filterableAttributes": ["genres"]}),
+        &json!({"facetName": "invalid", "facetQuery": "a"}),
+        |response, code| {
+            snapshot!(code, @"400 Bad Request");
+            snapshot!(response["message"], @r###""Attribute `invalid` is not facet-searchable. Available facet-searchable attributes patterns are: `genres`. To make it facet-searchable add it to the `filterableAttributes` index settings.""###);
+            snapshot!(response["code"], @r###""facet_search_facet_not_filterable""###);
+        },
+    )
+    .await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+      &DOCUMENTS,
+      &json!({"filterableAttributes": [{"attributePatterns": ["genres"]}]}),
+      &json!({"facetName": "genres", "facetQuery": "a"}),
+      |response, code| {
+          snapshot!(code, @"400 Bad Request");
+          snapshot!(response["message"], @r###""Attribute `genres` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching genres with facetSearch: true before rule #0""###);
+          snapshot!(response["code"], @r###""facet_search_disabled""###);
+      },
+    )
+    .await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &DOCUMENTS,
+        &json!({"filterableAttributes": [{"attributePatterns": ["genres"], "features": {"facetSearch": false, "filter": {"equality": true, "comparison": true}}}]}),
+        &json!({"facetName": "genres", "facetQuery": "a"}),
+        |response, code| {
+            snapshot!(code, @"400 Bad Request");
+            snapshot!(response["message"], @r###""Attribute `genres` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching genres with facetSearch: true before rule #0""###);
+        },
+    ).await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &DOCUMENTS,
+        &json!({"filterableAttributes": [{"attributePatterns": ["genres"], "features": {"facetSearch": false, "filter": {"equality": false, "comparison": false}}}]}),
+        &json!({"facetName": "genres", "facetQuery": "a"}),
+        |response, code| {
+            snapshot!(code, @"400 Bad Request");
+            snapshot!(response["message"], @r###""Attribute `genres` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching genres with facetSearch: true before rule #0""###);
+        },
+    ).await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &NESTED_DOCUMENTS,
+        &json!({"filterableAttributes": [{"attributePatterns": ["doggos.name"]}]}),
+        &json!({"facetName": "invalid.name", "facetQuery": "b"}),
+        |response, code| {
+            snapshot!(code, @"400 Bad Request");
+            snapshot!(response["message"], @r###""Attribute `invalid.name` is not facet-searchable. This index does not have configured facet-searchable attributes. To make it facet-searchable add it to the `filterableAttributes` index settings.""###);
+        },
+    )
+    .await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &NESTED_DOCUMENTS,
+        &json!({"filterableAttributes": [{"attributePatterns": ["doggos.name"], "features": {"facetSearch": false, "filter": {"equality": true, "comparison": true}}}]}),
+        &json!({"facetName": "doggos.name", "facetQuery": "b"}),
+        |response, code| {
+            snapshot!(code, @"400 Bad Request");
+            snapshot!(response["message"], @r###""Attribute `doggos.name` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching doggos.name with facetSearch: true before rule #0""###);
+        },
+    ).await;
+
+    test_settings_documents_indexing_swapping_and_facet_search(
+        &NESTED_DOCUMENTS,
+        &json!({"filterableAttributes": [{"attributePatterns": ["doggos.name"], "features": {"facetSearch": false, "filter": {"equality": false, "comparison": false}}}]}),
+        &json!({"facetName": "doggos.name", "facetQuery": "b"}),
+        |response, code| {
+            snapshot!(code, @"400 Bad Request");
+            snapshot!(response["message"], @r###""Attribute `doggos.name` is not facet-searchable. Note: this attribute matches rule #0 in filterableAttributes, but this rule does not enable facetSearch.\nHint: enable facetSearch in rule #0 by adding `\"facetSearch\": true` to the rule.\nHint: prepend another rule matching doggos.name with facetSearch: true before rule #0""###);
+        },
+    ).await;
+}
+
+#[actix_rt::test]
+async fn distinct_facet_search_on_movies() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+      {
+        "id": 1,
+        "title": "Carol",
+        "genres": ["Romance", "Drama", "Blob"],
+        "color": "crimson"
+      },
+      {
+        "id": 2,
+        "title": "Wonder Woman",
+        "genres": ["Action", "Adventure", "Blob"],
+        "color": "emerald"
+      },
+      {
+        "id": 3,
+        "title": "Life of Pi",
+        "genres": ["Adventure", "Drama", "Blob"],
+        "color": "azure"
+      },
+      {
+        "id": 4,
+        "title": "Mad Max: Fury Road",
+        "genres": ["Adventure", "Science Fiction", "Blob"],
+        "color": "scarlet"
+      },
+      {
+        "id": 5,
+        "title": "Moana",
+        "genres": ["Fantasy", "Action", "Blob"],
+        "color": "coral"
+      },
+      {
+        "id": 6,
+        "title": "Philadelphia",
+        "genres": ["Drama", "Blob"],
+        "color": "navy"
+      },
+      {
+        "id": 7,
+        "title": "The Matrix",
+        "genres": ["Science Fiction", "Action", "Blob"],
+        "color": "onyx"
+      },
+      {
+        "id": 8,
+        "title": "Inception",
+        "genres": ["Science Fiction", "Thriller", "Blob"],
+        "color": "cerulean"
+      },
+      {
+        "id": 9,
+        "title": "The Shawshank Redemption",
+        "genres": ["Drama", "Blob"],
+        "color": "slate"
+      },
+      {
+        "id": 10,
+        "title": "Pulp Fiction",
+        "genres": ["Crime", "Drama", "Blob"],
+        "color": "gold"
+      },
+      {
+        "id": 11,
+        "title": "The Dark Knight",
+        "genres": ["Action", "Crime", "Blob"],
+        "color": "obsidian"
+      },
+      {
+        "id": 12,
+        "title": "Forrest Gump",
+        "genres": ["Drama", "Romance", "Blob"],
+        "color": "jade"
+      },
+      {
+        "id": 13,
+        "title": "The Godfather",
+        "genres": ["Crime", "Drama", "Blob"],
+        "color": "sepia"
+      },
+      {
+        "id": 14,
+        "title": "Fight Club",
         "genres": ["Drama", "Thriller", "Blob"],
         "color": "ruby"
       },
@@ -918,27 +2127,596 @@ async fn distinct_facet_search_on_movies() {
       }
     ]);
     let (response, code) =
-        index.update_settings_filterable_attributes(json!(["genres", "color"])).await;
-    assert_eq!(202, code, "{response:?}");
-    server.wait_task(response.uid()).await.succeeded();
-    let (response, code) = index.update_settings_distinct_attribute(json!("color")).await;
-    assert_eq!(202, code, "{response:?}");
-    server.wait_task(response.uid()).await.succeeded();
+        index.update_settings_filterable_attributes(json!(["genres", "color"])).await;
+    assert_eq!(202, code, "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+    let (response, code) = index.update_settings_distinct_attribute(json!("color")).await;
+    assert_eq!(202, code, "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, _code) = index.add_documents(documents, None).await;
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetQuery": "blob", "facetName": "genres", "q": "" })).await;
+
+    // non-exhaustive facet count is counting 27 documents with the facet query "blob" but there are only 23 documents with a distinct color.
+    assert_eq!(code, 200, "{response}");
+    snapshot!(response["facetHits"], @r###"[{"value":"Blob","count":27}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetQuery": "blob", "facetName": "genres", "q": "", "exhaustiveFacetCount": true })).await;
+
+    // exhaustive facet count is counting 23 documents with the facet query "blob" which is the number of distinct colors.
+    assert_eq!(code, 200, "{response}");
+    snapshot!(response["facetHits"], @r###"[{"value":"Blob","count":23}]"###);
+}
+
+#[actix_rt::test]
+async fn facet_search_max_typos() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "genres": ["Adventure"] },
+        { "id": 2, "genres": ["Action"] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the index settings tolerate one typo on a query of this length
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "adventre"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Adventure","count":1}]"###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "adventre", "maxTypos": 0}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @"[]");
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "adventre", "maxTypos": 1}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Adventure","count":1}]"###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "adv", "maxTypos": 3}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value at `.maxTypos`: the value of `maxTypos` is invalid, expected an integer between `0` and `2`.",
+      "code": "invalid_facet_search_max_typos",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_facet_search_max_typos"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn facet_search_applied_limit() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json!([response["appliedLimit"], response["limitSource"]]), @r###"[100,"system"]"###);
+
+    // only the global cap of the index is set
+    let (task, _status_code) =
+        index.update_settings_faceting(json!({ "maxValuesPerFacet": 1 })).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json!([response["appliedLimit"], response["limitSource"]]), @r###"[1,"indexSetting"]"###);
+    snapshot!(response["facetHits"].as_array().unwrap().len(), @"1");
+
+    // the limit of the request cannot exceed the cap of the index
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a", "limit": 10})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json!([response["appliedLimit"], response["limitSource"]]), @r###"[1,"indexSetting"]"###);
+
+    let (task, _status_code) =
+        index.update_settings_faceting(json!({ "maxValuesPerFacet": 10 })).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "a", "limit": 1})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json!([response["appliedLimit"], response["limitSource"]]), @r###"[1,"request"]"###);
+    snapshot!(response["facetHits"].as_array().unwrap().len(), @"1");
+}
+
+#[actix_rt::test]
+async fn facet_search_snapshot() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "act", "snapshot": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3}]"###);
+    let token = response["snapshotToken"].as_str().unwrap().to_string();
+
+    let (task, _status_code) = index
+        .add_documents(json!([{ "id": "1", "title": "Tron", "genres": ["Action"] }]), None)
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the counts of the snapshot stay the same whatever has been indexed since
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "facetQuery": "act", "snapshotToken": token}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3}]"###);
+    assert_eq!(response["snapshotToken"], json!(token));
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "act"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":4}]"###);
+    snapshot!(response["snapshotToken"], @"null");
+
+    // a snapshot cannot be used on another index
+    let other_index = server.unique_index();
+    let (task, _status_code) = other_index.create(None).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (response, code) =
+        other_index.facet_search(json!({"facetName": "genres", "snapshotToken": token})).await;
+    snapshot!(code, @"400 Bad Request");
+    assert_eq!(response["code"], json!("invalid_facet_search_snapshot_token"));
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "snapshotToken": "not-a-token"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value at `.snapshotToken`: `not-a-token` is not a valid snapshot token.",
+      "code": "invalid_facet_search_snapshot_token",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_facet_search_snapshot_token"
+    }
+    "###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "genres", "snapshot": true, "snapshotToken": token}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    assert_eq!(response["code"], json!("invalid_facet_search_snapshot"));
+}
+
+#[actix_rt::test]
+async fn facet_search_rate_limit() {
+    let temp = TempDir::new().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_facet_search_rate_limit: NonZeroU32::new(1),
+        ..default_settings(temp.path())
+    })
+    .await
+    .unwrap();
+    let index = server.index("test");
+
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    let (task, _status_code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let query = json!({"facetName": "genres", "facetQuery": "a"});
+    let (_response, code) = index.facet_search(query.clone()).await;
+    snapshot!(code, @"200 OK");
+    let (response, code) = index.facet_search(query.clone()).await;
+    snapshot!(code, @"429 Too Many Requests");
+    snapshot!(response, @r###"
+    {
+      "message": "Too many facet search requests on the index `test`, the limit is 1 requests per second. Retry later.",
+      "code": "too_many_facet_search_requests",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#too_many_facet_search_requests"
+    }
+    "###);
+
+    // the other indexes are not limited
+    let (_response, code) =
+        server.index("other").facet_search(json!({"facetName": "genres"})).await;
+    snapshot!(code, @"404 Not Found");
+
+    // the limit resets over time
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let (_response, code) = index.facet_search(query).await;
+    snapshot!(code, @"200 OK");
+}
+
+#[actix_rt::test]
+async fn facet_search_attach_fields() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _status_code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["brand", "price"],
+            "displayedAttributes": ["id", "brand", "logoUrl", "price"],
+        }))
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "brand": "Nike", "logoUrl": "https://nike.png", "price": 80, "secret": "a" },
+        { "id": 2, "brand": "Adidas", "logoUrl": "https://adidas.png", "price": 60, "secret": "b" },
+        { "id": 3, "brand": "Nike", "logoUrl": "https://nike-old.png", "price": 20, "secret": "c" },
+        { "id": 4, "brand": "Puma", "price": 40, "secret": "d" },
+    ]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    // the fields of the first indexed document with the value are attached, the hidden
+    // and missing fields are left out
+    let (response, code) = index
+        .facet_search(json!({ "facetName": "brand", "attachFields": ["logoUrl", "secret"] }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Adidas","count":1,"attachedFields":{"logoUrl":"https://adidas.png"}},{"value":"Nike","count":2,"attachedFields":{"logoUrl":"https://nike.png"}},{"value":"Puma","count":1,"attachedFields":{}}]"###);
+
+    // the representative document matches the search
+    let (response, code) = index
+        .facet_search(json!({
+            "facetName": "brand",
+            "facetQuery": "nik",
+            "filter": "price < 50",
+            "attachFields": ["logoUrl", "price"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Nike","count":1,"attachedFields":{"logoUrl":"https://nike-old.png","price":20}}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({ "facetName": "brand", "attachFields": "logoUrl" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_attach_fields""###);
+}
 
-    let (response, _code) = index.add_documents(documents, None).await;
-    server.wait_task(response.uid()).await.succeeded();
+#[actix_rt::test]
+async fn facet_normalization() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _status_code) = index
+        .update_settings(json!({
+            "filterableAttributes": [
+                "genres",
+                {
+                    "attributePatterns": ["title"],
+                    "features": { "facetSearch": true, "lossyNormalization": false }
+                },
+            ],
+            "localizedAttributes": [{ "attributePatterns": ["title"], "locales": ["fra"] }],
+        }))
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+    let documents = json!([{ "id": 1, "genres": ["Action"], "title": "Léon" }]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_normalization().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"{"genres":{"facetSearch":true,"lossy":true,"locales":null,"caseSensitive":false},"title":{"facetSearch":true,"lossy":false,"locales":["fra"],"caseSensitive":false}}"###);
+
+    // the reported normalization follows the settings
+    let (task, _status_code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["title"],
+            "localizedAttributes": null,
+            "facetSearch": false,
+        }))
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.facet_normalization().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"{"title":{"facetSearch":false,"lossy":true,"locales":null,"caseSensitive":false}}"###);
+
+    let (response, code) = server.index("unknown").facet_normalization().await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""index_not_found""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_min_similarity() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _status_code) =
+        index.update_settings(json!({ "filterableAttributes": ["brand"] })).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "brand": "Samsung" },
+        { "id": 2, "brand": "Samsung Galaxy" },
+        { "id": 3, "brand": "Sansung" },
+        { "id": 4, "brand": "Smasung" },
+        { "id": 5, "brand": "Sony" },
+    ]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let values = |response: &Value| -> Vec<String> {
+        response["facetHits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| hit["value"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // a low threshold also returns the near-misses, after the closest matches
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "brand", "facetQuery": "samsung", "minSimilarity": 0.5 }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits = values(&response);
+    snapshot!(json!(hits[..2]), @r###"["Samsung","Samsung Galaxy"]"###);
+    let mut near_misses = hits[2..].to_vec();
+    near_misses.sort();
+    snapshot!(json!(near_misses), @r###"["Sansung","Smasung"]"###);
+    snapshot!(response["facetHits"][0]["similarity"], @"1.0");
+    assert!(response["facetHits"][3]["similarity"].as_f64().unwrap() < 1.0);
+
+    // a high threshold only returns the close matches
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "brand", "facetQuery": "samsung", "minSimilarity": 0.99 }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json!(values(&response)), @r###"["Samsung","Samsung Galaxy"]"###);
+
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "brand", "facetQuery": "samsung", "minSimilarity": 1.5 }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_min_similarity""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_accent_sensitive() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _status_code) =
+        index.update_settings(json!({ "filterableAttributes": ["place"] })).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "place": "Café" },
+        { "id": 2, "place": "Cafe" },
+        { "id": 3, "place": "Cafeteria" },
+        { "id": 4, "place": "Café Noir" },
+    ]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let values = |response: &Value| -> Vec<String> {
+        response["facetHits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| hit["value"].as_str().unwrap().to_string())
+            .collect()
+    };
 
     let (response, code) =
-        index.facet_search(json!({"facetQuery": "blob", "facetName": "genres", "q": "" })).await;
+        index.facet_search(json!({ "facetName": "place", "facetQuery": "cafe" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json!(values(&response)), @r###"["Cafe","Café","Café Noir","Cafeteria"]"###);
 
-    // non-exhaustive facet count is counting 27 documents with the facet query "blob" but there are only 23 documents with a distinct color.
-    assert_eq!(code, 200, "{response}");
-    snapshot!(response["facetHits"], @r###"[{"value":"Blob","count":27}]"###);
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "place", "facetQuery": "cafe", "accentSensitive": true }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json!(values(&response)), @r###"["Cafe","Cafeteria"]"###);
+
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "place", "facetQuery": "café", "accentSensitive": true }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json!(values(&response)), @r###"["Café","Café Noir"]"###);
+
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "place", "facetQuery": "cafe", "accentSensitive": "yes" }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_accent_sensitive""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_include_zero_counts() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _status_code) =
+        index.update_settings(json!({ "filterableAttributes": ["brand", "price"] })).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "brand": "Nike", "price": 80 },
+        { "id": 2, "brand": "Nikon", "price": 150 },
+        { "id": 3, "brand": "Adidas", "price": 60 },
+    ]);
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .facet_search(json!({ "facetName": "brand", "facetQuery": "nik", "filter": "price < 100" }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Nike","count":1}]"###);
+
+    // the values matching the query but filtered out are returned with a count of 0
+    let (response, code) = index
+        .facet_search(json!({
+            "facetName": "brand",
+            "facetQuery": "nik",
+            "filter": "price < 100",
+            "includeZeroCounts": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Nike","count":1},{"value":"Nikon","count":0}]"###);
+
+    // even when the filter matches nothing
+    let (response, code) = index
+        .facet_search(json!({
+            "facetName": "brand",
+            "facetQuery": "nik",
+            "filter": "price > 1000",
+            "includeZeroCounts": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Nike","count":0},{"value":"Nikon","count":0}]"###);
 
     let (response, code) =
-        index.facet_search(json!({"facetQuery": "blob", "facetName": "genres", "q": "", "exhaustiveFacetCount": true })).await;
+        index.facet_search(json!({ "facetName": "brand", "includeZeroCounts": "yes" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_include_zero_counts""###);
+}
 
-    // exhaustive facet count is counting 23 documents with the facet query "blob" which is the number of distinct colors.
-    assert_eq!(code, 200, "{response}");
-    snapshot!(response["facetHits"], @r###"[{"value":"Blob","count":23}]"###);
+#[actix_rt::test]
+async fn facet_search_suggest_corrections() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    index.update_settings_typo_tolerance(json!({ "disableOnWords": ["adventre"] })).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "genres", "facetQuery": "adventre", "suggestCorrections": true }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @"[]");
+    snapshot!(response["suggestions"], @r###"["Adventure"]"###);
+
+    // the corrections are only suggested when nothing matches
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "genres", "facetQuery": "adventure", "suggestCorrections": true }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Adventure","count":2}]"###);
+    snapshot!(response["suggestions"], @"null");
+
+    // and only the values close enough to the query are suggested
+    let (response, code) = index
+        .facet_search(
+            json!({ "facetName": "genres", "facetQuery": "western", "suggestCorrections": true }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["suggestions"], @"[]");
+
+    let (response, code) =
+        index.facet_search(json!({ "facetName": "genres", "suggestCorrections": "yes" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_suggest_corrections""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_disjunctive_on_self() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres", "title"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({ "facetName": "genres", "filter": "genres = Thriller" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Horror","count":1},{"value":"Multiple Words","count":1},{"value":"Thriller","count":2}]"###);
+
+    // the selected genre doesn't restrict the counts of the other genres
+    let (response, code) = index
+        .facet_search(json!({
+            "facetName": "genres",
+            "filter": "genres = Thriller",
+            "disjunctiveOnSelf": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2},{"value":"Comedy","count":1},{"value":"Horror","count":1},{"value":"Multiple Words","count":1},{"value":"Thriller","count":2}]"###);
+
+    // but the clauses on the other fields still do
+    let (response, code) = index
+        .facet_search(json!({
+            "facetName": "genres",
+            "filter": ["genres = Thriller", "title != 'Escape Room'"],
+            "disjunctiveOnSelf": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3},{"value":"Adventure","count":2},{"value":"Comedy","count":1},{"value":"Thriller","count":1}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({ "facetName": "genres", "disjunctiveOnSelf": "yes" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_disjunctive_on_self""###);
+}
+
+#[actix_rt::test]
+async fn facet_search_max_response_bytes() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 0, "tag": "a".repeat(100) },
+        { "id": 1, "tag": "b".repeat(100) },
+        { "id": 2, "tag": "c".repeat(100) },
+    ]);
+    index.update_settings_filterable_attributes(json!(["tag"])).await;
+    let (task, _status_code) = index.add_documents(documents, None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        index.facet_search(json!({ "facetName": "tag", "maxResponseBytes": 10000 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"].as_array().unwrap().len(), @"3");
+    snapshot!(response["truncatedBySize"], @"null");
+
+    // a hit of a hundred characters takes more than a hundred bytes, only one fits
+    let (response, code) =
+        index.facet_search(json!({ "facetName": "tag", "maxResponseBytes": 300 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"].as_array().unwrap().len(), @"1");
+    snapshot!(response["truncatedBySize"], @"true");
+    assert!(serde_json::to_string(&*response).unwrap().len() <= 300);
+
+    let (response, code) =
+        index.facet_search(json!({ "facetName": "tag", "maxResponseBytes": -1 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_facet_search_max_response_bytes""###);
 }
@@ -317,3 +317,98 @@ async fn geo_sort_with_words() {
     )
     .await;
 }
+
+#[actix_rt::test]
+async fn geo_facet_counts_hits_by_distance() {
+    let index = shared_index_with_geo_documents().await;
+
+    index
+        .search(
+            json!({
+                "geoFacet": {
+                    "center": { "lat": 45.4777599, "lng": 9.1967508 },
+                    "boundaries": [1000, 1000000]
+                }
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{response}");
+                snapshot!(json_string!(response["geoFacet"]), @r###"
+                {
+                  "buckets": [
+                    {
+                      "from": null,
+                      "to": 1000.0,
+                      "count": 1
+                    },
+                    {
+                      "from": 1000.0,
+                      "to": 1000000.0,
+                      "count": 0
+                    },
+                    {
+                      "from": 1000000.0,
+                      "to": null,
+                      "count": 1
+                    }
+                  ],
+                  "withoutGeo": 1
+                }
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "filter": "_geoRadius(45.4777599, 9.1967508, 1000)",
+                "geoFacet": {
+                    "center": { "lat": 45.4777599, "lng": 9.1967508 },
+                    "boundaries": [1000]
+                }
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{response}");
+                snapshot!(json_string!(response["geoFacet"]), @r###"
+                {
+                  "buckets": [
+                    {
+                      "from": null,
+                      "to": 1000.0,
+                      "count": 1
+                    },
+                    {
+                      "from": 1000.0,
+                      "to": null,
+                      "count": 0
+                    }
+                  ],
+                  "withoutGeo": 0
+                }
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "geoFacet": {
+                    "center": { "lat": 45.4777599, "lng": 9.1967508 },
+                    "boundaries": [5000, 1000]
+                }
+            }),
+            |response, code| {
+                assert_eq!(code, 400, "{response}");
+                snapshot!(json_string!(response), @r###"
+                {
+                  "message": "Invalid geo facet: the distance boundaries must be non-negative finite numbers sorted in strictly ascending order.",
+                  "code": "invalid_search_geo_facet",
+                  "type": "invalid_request",
+                  "link": "https://docs.meilisearch.com/errors#invalid_search_geo_facet"
+                }
+                "###);
+            },
+        )
+        .await;
+}
@@ -1067,7 +1067,10 @@ async fn simple_facet_search() {
         }
       ],
       "facetQuery": "進撃",
-      "processingTimeMs": "[duration]"
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": true,
+      "appliedLimit": 100,
+      "limitSource": "system"
     }
     "###);
 
@@ -1084,7 +1087,10 @@ async fn simple_facet_search() {
         }
       ],
       "facetQuery": "進撃",
-      "processingTimeMs": "[duration]"
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": true,
+      "appliedLimit": 100,
+      "limitSource": "system"
     }
     "###);
 }
@@ -1124,7 +1130,10 @@ async fn facet_search_with_localized_attributes() {
     {
       "facetHits": [],
       "facetQuery": "进击",
-      "processingTimeMs": "[duration]"
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": true,
+      "appliedLimit": 100,
+      "limitSource": "system"
     }
     "###);
 
@@ -1141,7 +1150,10 @@ async fn facet_search_with_localized_attributes() {
         }
       ],
       "facetQuery": "进击",
-      "processingTimeMs": "[duration]"
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": true,
+      "appliedLimit": 100,
+      "limitSource": "system"
     }
     "###);
 
@@ -1157,7 +1169,10 @@ async fn facet_search_with_localized_attributes() {
         }
       ],
       "facetQuery": "进击",
-      "processingTimeMs": "[duration]"
+      "processingTimeMs": "[duration]",
+      "facetSearchEnabled": true,
+      "appliedLimit": 100,
+      "limitSource": "system"
     }
     "###);
 }
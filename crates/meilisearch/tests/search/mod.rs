@@ -3,6 +3,7 @@
 
 mod distinct;
 mod errors;
+mod facet_distribution;
 mod facet_search;
 mod filters;
 mod formatted;
@@ -1,6 +1,6 @@
 use meili_snap::{json_string, snapshot};
 
-use crate::common::Server;
+use crate::common::{default_settings, Server};
 use crate::json;
 
 macro_rules! test_setting_routes {
@@ -147,7 +147,7 @@ test_setting_routes!(
     {
         setting: faceting,
         update_verb: patch,
-        default_value: {"maxValuesPerFacet": 100, "sortFacetValuesBy": {"*": "alpha"}}
+        default_value: {"maxValuesPerFacet": 100, "maxValuesPerDocumentField": null, "trackValuesRecency": false, "maxFacetLevels": null, "expectedCardinality": {}, "updateMethod": {}, "valueWeightFields": {}, "sortFacetValuesBy": {"*": "alpha"}, "mode": "eager", "warmup": null, "pinnedDistributions": []}
     },
     {
         setting: search_cutoff_ms,
@@ -236,9 +236,18 @@ async fn get_settings() {
         settings["faceting"],
         json!({
             "maxValuesPerFacet": 100,
+            "maxValuesPerDocumentField": null,
+            "trackValuesRecency": false,
+            "maxFacetLevels": null,
+            "expectedCardinality": {},
+            "updateMethod": {},
+            "valueWeightFields": {},
             "sortFacetValuesBy": {
                 "*": "alpha"
-            }
+            },
+            "mode": "eager",
+            "warmup": null,
+            "pinnedDistributions": []
         })
     );
     assert_eq!(
@@ -345,9 +354,18 @@ async fn secrets_are_hidden_in_settings() {
       },
       "faceting": {
         "maxValuesPerFacet": 100,
+        "maxValuesPerDocumentField": null,
+        "trackValuesRecency": false,
+        "maxFacetLevels": null,
+        "expectedCardinality": {},
+        "updateMethod": {},
+        "valueWeightFields": {},
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "mode": "eager",
+        "warmup": null,
+        "pinnedDistributions": []
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -762,3 +780,28 @@ async fn test_searchable_attributes_order() {
     assert_eq!(code, 200, "{response}");
     assert_eq!(response["searchableAttributes"], json!(["title", "overview"]));
 }
+
+#[actix_rt::test]
+async fn too_many_filterable_attributes_warning() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = default_settings(temp.path());
+    options.indexer_options.max_filterable_attributes = 2;
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("test");
+
+    let (task, _code) =
+        index.add_documents(json!([{ "id": 1, "a": 1, "b": 2, "c": 3 }]), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (task, _code) = index.update_settings_filterable_attributes(json!(["a", "b"])).await;
+    let task = server.wait_task(task.uid()).await.succeeded();
+    snapshot!(task["details"], @r###"{"filterableAttributes":["a","b"]}"###);
+
+    // the settings are applied anyway
+    let (task, _code) = index.update_settings_filterable_attributes(json!(["a", "b", "c"])).await;
+    let task = server.wait_task(task.uid()).await.succeeded();
+    snapshot!(task["details"], @r###"{"filterableAttributes":["a","b","c"],"warning":"The filterable attributes match 3 fields, which is more than the soft limit of 2 fields. Indexing the facets of these fields may be very slow."}"###);
+
+    let (response, _code) = index.settings().await;
+    snapshot!(response["filterableAttributes"], @r###"["a","b","c"]"###);
+}
@@ -456,3 +456,47 @@ async fn default_behavior() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn hot_prefixes() {
+    let server = Server::new_shared();
+    let index = server.unique_index_with_prefix("test");
+
+    let (response, code) = index
+        .update_settings(json!({
+            "prefixSearch": "hotPrefixes",
+            "rankingRules": ["words", "typo", "proximity"],
+        }))
+        .await;
+    assert_eq!("202", code.as_str(), "{response:?}");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["prefixSearch"], @r###""hotPrefixes""###);
+
+    let (response, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    server.wait_task(response.uid()).await.succeeded();
+
+    // the prefixes that are not materialized are still searched, all documents should match
+    for q in ["so", "manythe"] {
+        index
+            .search(json!({"q": q, "attributesToRetrieve": ["id"]}), |response, code| {
+                snapshot!(code, @"200 OK");
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  },
+                  {
+                    "id": 2
+                  },
+                  {
+                    "id": 3
+                  }
+                ]
+                "###);
+            })
+            .await;
+    }
+}
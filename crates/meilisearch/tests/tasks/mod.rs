@@ -335,6 +335,38 @@ async fn test_summarized_document_addition_or_update() {
     "###);
 }
 
+#[actix_web::test]
+async fn test_document_addition_reports_facet_updates() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+    let (task, _status_code) =
+        index.update_settings(json!({ "filterableAttributes": ["genre"] })).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (task, _status_code) = index
+        .add_documents(
+            json!([{ "id": 1, "genre": "horror" }, { "id": 2, "genre": "comedy" }]),
+            None,
+        )
+        .await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (task, _) = index.get_task(task.uid()).await;
+    snapshot!(json_string!(task["details"]), @r###"
+    {
+      "receivedDocuments": 2,
+      "indexedDocuments": 2,
+      "facetUpdates": {
+        "genre": {
+          "strings": {
+            "method": "incremental",
+            "deltaSize": 2
+          }
+        }
+      }
+    }
+    "###);
+}
+
 #[actix_web::test]
 async fn test_summarized_delete_documents_by_batch() {
     let server = Server::new_shared();
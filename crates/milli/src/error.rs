@@ -82,6 +82,8 @@ pub enum InternalError {
     AbortedIndexation,
     #[error("The matching words list contains at least one invalid member")]
     InvalidMatchingWords,
+    #[error("invalid facet config for the field id {field_id}: {reason}")]
+    InvalidFacetConfig { field_id: FieldId, reason: &'static str },
     #[error("Cannot upgrade to the following version: v{0}.{1}.{2}.")]
     CannotUpgradeToVersion(u32, u32, u32),
     #[error(transparent)]
@@ -173,6 +175,14 @@ and can not be more than 511 bytes.", .document_id.to_string()
         valid_patterns: BTreeSet<String>,
         matching_rule_indices: HashMap<String, usize>,
     },
+    #[error("Invalid facet ranges: the boundaries of `{field}` must be finite numbers sorted in strictly ascending order.")]
+    InvalidFacetRangeBoundaries { field: String },
+    #[error("Invalid geo facet: the `_geo` field must be filterable to compute the distances of the documents.")]
+    InvalidGeoFacetNotFilterable,
+    #[error("Invalid geo facet: the center point `[{lat}, {lng}]` must have a latitude between -90 and 90 and a longitude between -180 and 180.")]
+    InvalidGeoFacetCenter { lat: f64, lng: f64 },
+    #[error("Invalid geo facet: the distance boundaries must be non-negative finite numbers sorted in strictly ascending order.")]
+    InvalidGeoFacetBoundaries,
     #[error(transparent)]
     InvalidGeoField(#[from] Box<GeoError>),
     #[error(transparent)]
@@ -280,6 +290,18 @@ and can not be more than 511 bytes.", .document_id.to_string()
         hidden_fields: bool,
         matching_rule_index: Option<usize>,
     },
+    #[error("Attribute `{field}` only contains numbers and cannot be used for a facet search. Facet search only applies to string facet values, use a `filter` to search for numbers instead.")]
+    InvalidFacetSearchNumericFacet { field: String },
+    #[error("The facet query is {length} bytes long and exceeds the maximum facet value length of {} bytes.", crate::MAX_FACET_VALUE_LENGTH)]
+    FacetSearchQueryTooLong { length: usize },
+    #[error("Invalid facet search cursor `{0}`. It must be the `nextCursor` of a previous facet search, or an empty string to start from the first facet value.")]
+    InvalidFacetSearchCursor(String),
+    #[error("Facet search cursors can only be used when the facet values are sorted alphabetically, not ranked by relevance and not boosted.")]
+    InvalidFacetSearchCursorOrder,
+    #[error("Compact facets cannot sort the facet values {order}. They can only be sorted alphabetically, by count or by weighted count and alphabetical rank.")]
+    UnsupportedCompactFacetsOrder { order: String },
+    #[error("The facet value `{value}` of the attribute `{field}` reaches the maximum facet value length of {} bytes and may have been truncated.", crate::MAX_FACET_VALUE_LENGTH)]
+    FacetValueTooLong { field: String, value: String },
     #[error("Attribute `{}` is not searchable. Available searchable attributes are: `{}{}`.",
         .field,
         .valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
@@ -296,8 +318,11 @@ and can not be more than 511 bytes.", .document_id.to_string()
     SortRankingRuleMissing,
     #[error("The database file is in an invalid state.")]
     InvalidStoreFile,
-    #[error("Maximum database size has been reached.")]
-    MaxDatabaseSizeReached,
+    #[error("Maximum database size has been reached.{}", match .map_size {
+        Some(map_size) => format!(" The index is limited to {map_size} bytes by its map size, it must be opened with a larger map size."),
+        None => String::new(),
+    })]
+    MaxDatabaseSizeReached { map_size: Option<usize> },
     #[error("Document doesn't have a `{}` attribute: `{}`.", .primary_key, serde_json::to_string(.document).unwrap())]
     MissingDocumentId { primary_key: String, document: Object },
     #[error("Document have too many matching `{}` attribute: `{}`.", .primary_key, serde_json::to_string(.document).unwrap())]
@@ -630,6 +655,19 @@ impl From<Infallible> for Error {
     }
 }
 
+impl Error {
+    /// Adds the map size of the index to a [`UserError::MaxDatabaseSizeReached`] error, which
+    /// LMDB reports without it, to tell how large the map was when it got full.
+    pub fn with_map_size(self, map_size: usize) -> Error {
+        match self {
+            Error::UserError(UserError::MaxDatabaseSizeReached { map_size: None }) => {
+                Error::UserError(UserError::MaxDatabaseSizeReached { map_size: Some(map_size) })
+            }
+            error => error,
+        }
+    }
+}
+
 impl From<HeedError> for Error {
     fn from(error: HeedError) -> Error {
         use self::Error::*;
@@ -639,7 +677,9 @@ impl From<HeedError> for Error {
 
         match error {
             HeedError::Io(error) => Error::from(error),
-            HeedError::Mdb(MdbError::MapFull) => UserError(MaxDatabaseSizeReached),
+            HeedError::Mdb(MdbError::MapFull) => {
+                UserError(MaxDatabaseSizeReached { map_size: None })
+            }
             HeedError::Mdb(MdbError::Invalid) => UserError(InvalidStoreFile),
             HeedError::Mdb(error) => InternalError(Store(error)),
             // TODO use the encoding
@@ -1,6 +1,9 @@
 mod facet_type;
 mod facet_value;
 pub mod value_encoding;
+pub(crate) mod values_cap;
+pub(crate) mod values_recency;
+pub(crate) mod values_weight;
 
 pub use self::facet_type::FacetType;
 pub use self::facet_value::FacetValue;
@@ -0,0 +1,112 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::facet::value_encoding::f64_into_bytes;
+use crate::FieldId;
+
+/// Limits the number of distinct facet values a single document contributes to each field.
+///
+/// The values are considered in the order they appear in the document: the first `max`
+/// distinct values of a field are indexed and the following ones are ignored. Both indexing
+/// pipelines apply this exact rule so that the values removed when a document is updated
+/// or deleted are the ones that were indexed in the first place.
+#[derive(Debug, Default)]
+pub(crate) struct FacetValuesCap {
+    max: Option<usize>,
+    values: HashMap<FieldId, HashSet<Vec<u8>>>,
+    truncated: BTreeSet<FieldId>,
+}
+
+impl FacetValuesCap {
+    /// Creates a cap for the values of one document, `None` meaning unlimited.
+    pub fn new(max: Option<usize>) -> Self {
+        FacetValuesCap { max, values: HashMap::new(), truncated: BTreeSet::new() }
+    }
+
+    /// Returns whether the number must be indexed in the given field.
+    pub fn accept_number(&mut self, fid: FieldId, number: f64) -> bool {
+        match f64_into_bytes(number) {
+            Some(bytes) => self.accept(fid, ValueKind::Number, &bytes),
+            // numbers that cannot be encoded are never indexed
+            None => true,
+        }
+    }
+
+    /// Returns whether the string, normalized and truncated like in the facet databases,
+    /// must be indexed in the given field.
+    pub fn accept_string(&mut self, fid: FieldId, truncated: &str) -> bool {
+        // empty strings are never indexed and don't count toward the cap
+        if truncated.is_empty() {
+            return true;
+        }
+        self.accept(fid, ValueKind::String, truncated.as_bytes())
+    }
+
+    /// The fields of the document that had more distinct values than the cap.
+    pub fn truncated_fields(&self) -> &BTreeSet<FieldId> {
+        &self.truncated
+    }
+
+    fn accept(&mut self, fid: FieldId, kind: ValueKind, value: &[u8]) -> bool {
+        let Some(max) = self.max else { return true };
+
+        let mut key = Vec::with_capacity(1 + value.len());
+        key.push(kind as u8);
+        key.extend_from_slice(value);
+
+        let values = self.values.entry(fid).or_default();
+        if values.contains(&key) {
+            true
+        } else if values.len() < max {
+            values.insert(key);
+            true
+        } else {
+            self.truncated.insert(fid);
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(u8)]
+enum ValueKind {
+    Number = 0,
+    String = 1,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_cap_accepts_everything() {
+        let mut cap = FacetValuesCap::new(None);
+        for i in 0..1000 {
+            assert!(cap.accept_number(0, i as f64));
+            assert!(cap.accept_string(0, &i.to_string()));
+        }
+        assert!(cap.truncated_fields().is_empty());
+    }
+
+    #[test]
+    fn cap_keeps_the_first_distinct_values_of_each_field() {
+        let mut cap = FacetValuesCap::new(Some(2));
+
+        assert!(cap.accept_string(0, "blue"));
+        assert!(cap.accept_number(0, 1.0));
+        // duplicates don't count toward the cap
+        assert!(cap.accept_string(0, "blue"));
+        assert!(cap.accept_number(0, 1.0));
+        assert!(!cap.accept_string(0, "red"));
+        assert!(!cap.accept_number(0, 2.0));
+        // the values already seen are still accepted once the cap is reached
+        assert!(cap.accept_string(0, "blue"));
+        // empty strings are not indexed and do not count
+        assert!(cap.accept_string(0, ""));
+
+        // the other fields have their own cap
+        assert!(cap.accept_string(1, "red"));
+        assert!(cap.accept_string(1, "green"));
+
+        assert_eq!(cap.truncated_fields().iter().copied().collect::<Vec<_>>(), vec![0]);
+    }
+}
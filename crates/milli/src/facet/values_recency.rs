@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use heed::types::Bytes;
+use heed::{RoTxn, RwTxn};
+use time::OffsetDateTime;
+
+use crate::update::new::truncate_str;
+use crate::{normalize_facet, FieldId, Index};
+
+/// The time the string facet values of a field were last associated with a document,
+/// as recorded in [`Index::facet_id_string_recency`].
+///
+/// The values without a recorded time, e.g. indexed before the index tracked the recency
+/// of its facet values, are considered older than all the others.
+#[derive(Debug, Default)]
+pub(crate) struct FacetValuesRecency {
+    timestamps: HashMap<String, u64>,
+}
+
+impl FacetValuesRecency {
+    /// Reads the recorded times of the string facet values of the field.
+    pub fn new(index: &Index, rtxn: &RoTxn<'_>, field_id: FieldId) -> heed::Result<Self> {
+        let mut timestamps = HashMap::new();
+        for result in index.facet_id_string_recency.prefix_iter(rtxn, &(field_id, ""))? {
+            let ((_, normalized), timestamp) = result?;
+            timestamps.insert(normalized.to_string(), timestamp);
+        }
+        Ok(FacetValuesRecency { timestamps })
+    }
+
+    /// The recorded time of a value as stored in the facet databases, normalized and truncated.
+    pub fn of_normalized(&self, normalized: &str) -> u64 {
+        self.timestamps.get(normalized).copied().unwrap_or(0)
+    }
+
+    /// The recorded time of an original value, normalized like at indexing time.
+    pub fn of_original(&self, original: &str) -> u64 {
+        self.of_normalized(truncate_str(&normalize_facet(original)))
+    }
+}
+
+/// The string facet values associated with or removed from all their documents by
+/// an indexing operation, keyed like in [`Index::facet_id_string_recency`].
+#[derive(Debug, Default)]
+pub(crate) struct FacetValuesRecencyDelta {
+    associated: Vec<Box<[u8]>>,
+    removed: Vec<Box<[u8]>>,
+}
+
+impl FacetValuesRecencyDelta {
+    pub fn associate(&mut self, field_id: FieldId, normalized: &[u8]) {
+        self.associated.push(recency_key(field_id, normalized));
+    }
+
+    pub fn remove(&mut self, field_id: FieldId, normalized: &[u8]) {
+        self.removed.push(recency_key(field_id, normalized));
+    }
+
+    pub fn merge(&mut self, mut other: Self) {
+        self.associated.append(&mut other.associated);
+        self.removed.append(&mut other.removed);
+    }
+
+    /// Records the current time for the associated values and forgets the removed ones.
+    pub fn write(self, index: &Index, wtxn: &mut RwTxn<'_>) -> heed::Result<()> {
+        let FacetValuesRecencyDelta { associated, removed } = self;
+        if associated.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+
+        // all the values of an indexing operation are associated at the same time
+        let now = u64::try_from(OffsetDateTime::now_utc().unix_timestamp_nanos()).unwrap_or(0);
+        let database = index.facet_id_string_recency.remap_key_type::<Bytes>();
+        for key in removed {
+            database.delete(wtxn, &key)?;
+        }
+        for key in associated {
+            database.put(wtxn, &key, &now)?;
+        }
+        Ok(())
+    }
+}
+
+fn recency_key(field_id: FieldId, normalized: &[u8]) -> Box<[u8]> {
+    let mut key = Vec::with_capacity(2 + normalized.len());
+    key.extend_from_slice(&field_id.to_be_bytes());
+    key.extend_from_slice(normalized);
+    key.into_boxed_slice()
+}
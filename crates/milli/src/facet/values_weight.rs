@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use heed::{RoTxn, RwTxn};
+
+use crate::update::new::truncate_str;
+use crate::{normalize_facet, FieldId, Index, Result};
+
+/// The sort weights of the string facet values of a field, as recorded in
+/// [`Index::facet_id_string_weight`].
+///
+/// The values without a weight are sorted after all the weighted ones.
+#[derive(Debug, Default)]
+pub(crate) struct FacetValuesWeight {
+    weights: HashMap<String, f64>,
+}
+
+impl FacetValuesWeight {
+    /// Reads the recorded weights of the string facet values of the field.
+    pub fn new(index: &Index, rtxn: &RoTxn<'_>, field_id: FieldId) -> heed::Result<Self> {
+        let mut weights = HashMap::new();
+        for result in index.facet_id_string_weight.prefix_iter(rtxn, &(field_id, ""))? {
+            let ((_, normalized), weight) = result?;
+            weights.insert(normalized.to_string(), weight);
+        }
+        Ok(FacetValuesWeight { weights })
+    }
+
+    /// The weight of a value as stored in the facet databases, normalized and truncated.
+    pub fn of_normalized(&self, normalized: &str) -> Option<f64> {
+        self.weights.get(normalized).copied()
+    }
+
+    /// The weight of an original value, normalized like at indexing time.
+    pub fn of_original(&self, original: &str) -> Option<f64> {
+        self.of_normalized(truncate_str(&normalize_facet(original)))
+    }
+}
+
+/// Recomputes the weights of the string facet values of the fields configured with
+/// [`Index::facet_value_weight_fields`], removing the weights of the other fields.
+///
+/// The weight of a value is the highest number found in the weight field of the documents
+/// containing it. Only the top-level fields are read and the whole documents database is
+/// scanned, it is only done when the documents or the configured fields change.
+pub(crate) fn rebuild_facet_values_weights(index: &Index, wtxn: &mut RwTxn<'_>) -> Result<()> {
+    let weight_fields = index.facet_value_weight_fields(wtxn)?;
+    index.facet_id_string_weight.clear(wtxn)?;
+    if weight_fields.is_empty() {
+        return Ok(());
+    }
+
+    let fields_ids_map = index.fields_ids_map(wtxn)?;
+    let field_ids: Vec<(FieldId, FieldId)> = weight_fields
+        .iter()
+        .filter_map(|(facet, weight)| Some((fields_ids_map.id(facet)?, fields_ids_map.id(weight)?)))
+        .collect();
+
+    let mut weights: HashMap<(FieldId, String), f64> = HashMap::new();
+    for result in index.documents.iter(wtxn)? {
+        let (_docid, obkv) = result?;
+        for &(facet_fid, weight_fid) in &field_ids {
+            let Some(weight) = obkv
+                .get(weight_fid)
+                .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(bytes).ok())
+                .and_then(|value| value.as_f64())
+                .filter(|weight| weight.is_finite())
+            else {
+                continue;
+            };
+            let Some(value) =
+                obkv.get(facet_fid).and_then(|bytes| serde_json::from_slice(bytes).ok())
+            else {
+                continue;
+            };
+            let strings = match value {
+                serde_json::Value::String(s) => vec![s],
+                serde_json::Value::Array(values) => values
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        serde_json::Value::String(s) => Some(s),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => continue,
+            };
+            for original in strings {
+                let normalized = truncate_str(&normalize_facet(&original)).to_string();
+                let entry = weights.entry((facet_fid, normalized)).or_insert(weight);
+                *entry = entry.max(weight);
+            }
+        }
+    }
+
+    for ((field_id, normalized), weight) in weights {
+        index.facet_id_string_weight.put(wtxn, &(field_id, &normalized), &weight)?;
+    }
+
+    Ok(())
+}
@@ -72,7 +72,6 @@ impl FilterableAttributesPatterns {
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Deserr, ToSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 #[deserr(rename_all = camelCase, deny_unknown_fields)]
-#[derive(Default)]
 pub struct FilterableAttributesFeatures {
     #[serde(default)]
     #[deserr(default)]
@@ -80,6 +79,17 @@ pub struct FilterableAttributesFeatures {
     #[serde(default)]
     #[deserr(default)]
     filter: FilterFeatures,
+    /// Whether the facet search normalizes the values and the queries lossily, e.g. removing
+    /// their accents. Disabling it keeps apart the values only differing by their accents.
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    #[deserr(default = true)]
+    lossy_normalization: bool,
+}
+
+impl Default for FilterableAttributesFeatures {
+    fn default() -> Self {
+        Self { facet_search: false, filter: FilterFeatures::default(), lossy_normalization: true }
+    }
 }
 
 impl FilterableAttributesFeatures {
@@ -88,12 +98,20 @@ impl FilterableAttributesFeatures {
     /// This is the default behavior for `FilterableAttributesRule::Field`.
     /// This will set the facet search to true and activate all the filter operators.
     pub fn legacy_default() -> Self {
-        Self { facet_search: true, filter: FilterFeatures::legacy_default() }
+        Self {
+            facet_search: true,
+            filter: FilterFeatures::legacy_default(),
+            lossy_normalization: true,
+        }
     }
 
     /// Create a new `FilterableAttributesFeatures` with no features.
     pub fn no_features() -> Self {
-        Self { facet_search: false, filter: FilterFeatures::no_features() }
+        Self {
+            facet_search: false,
+            filter: FilterFeatures::no_features(),
+            lossy_normalization: true,
+        }
     }
 
     pub fn is_filterable(&self) -> bool {
@@ -130,6 +148,11 @@ impl FilterableAttributesFeatures {
         self.facet_search
     }
 
+    /// Check if the facet search normalizes the values and the queries lossily
+    pub fn is_lossy_normalization(&self) -> bool {
+        self.lossy_normalization
+    }
+
     pub fn allowed_filter_operators(&self) -> Vec<String> {
         self.filter.allowed_operators()
     }
@@ -166,6 +189,10 @@ fn default_true() -> bool {
     true
 }
 
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
 impl FilterFeatures {
     /// Get the allowed operators for the filter.
     pub fn allowed_operators(&self) -> Vec<String> {
@@ -90,6 +90,10 @@ where
     }
 }
 
+/// Encodes the group size followed by the bitmap of the group.
+///
+/// The bitmap goes through the [`CboRoaringBitmapCodec`], the bitmaps of the sparse facet
+/// values are therefore stored as a plain list of native-endian `u32`s rather than a roaring bitmap.
 pub struct FacetGroupValueCodec;
 
 impl<'a> heed::BytesEncode<'a> for FacetGroupValueCodec {
@@ -121,3 +125,33 @@ impl<'a> heed::BytesDecode<'a> for FacetGroupLazyValueCodec {
         Ok(FacetGroupLazyValue { size: bytes[0], bitmap_bytes: &bytes[1..] })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use heed::{BytesDecode, BytesEncode};
+    use roaring::RoaringBitmap;
+
+    use super::{FacetGroupValue, FacetGroupValueCodec};
+    use crate::heed_codec::roaring_bitmap::cbo_roaring_bitmap_codec::THRESHOLD;
+
+    #[test]
+    fn sparse_group_values_are_stored_as_u32_lists() {
+        let bitmap = RoaringBitmap::from_iter([3, 42, 1_000_000]);
+        let value = FacetGroupValue { size: 1, bitmap: bitmap.clone() };
+        let bytes = FacetGroupValueCodec::bytes_encode(&value).unwrap();
+        assert_eq!(bytes.len(), 1 + 3 * std::mem::size_of::<u32>());
+
+        let decoded = FacetGroupValueCodec::bytes_decode(&bytes).unwrap();
+        assert_eq!(decoded.size, 1);
+        assert_eq!(decoded.bitmap, bitmap);
+
+        let bitmap = RoaringBitmap::from_iter(0..=THRESHOLD as u32);
+        let value = FacetGroupValue { size: 4, bitmap: bitmap.clone() };
+        let bytes = FacetGroupValueCodec::bytes_encode(&value).unwrap();
+        assert_eq!(bytes.len(), 1 + bitmap.serialized_size());
+
+        let decoded = FacetGroupValueCodec::bytes_decode(&bytes).unwrap();
+        assert_eq!(decoded.size, 4);
+        assert_eq!(decoded.bitmap, bitmap);
+    }
+}
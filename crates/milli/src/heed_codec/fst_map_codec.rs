@@ -0,0 +1,23 @@
+use std::borrow::Cow;
+
+use fst::Map;
+use heed::{BoxedError, BytesDecode, BytesEncode};
+
+/// A codec for values of type `Map<&[u8]>`.
+pub struct FstMapCodec;
+
+impl<'a> BytesEncode<'a> for FstMapCodec {
+    type EItem = Map<Vec<u8>>;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, BoxedError> {
+        Ok(Cow::Borrowed(item.as_fst().as_bytes()))
+    }
+}
+
+impl<'a> BytesDecode<'a> for FstMapCodec {
+    type DItem = Map<&'a [u8]>;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        Map::new(bytes).map_err(Into::into)
+    }
+}
@@ -1,35 +1,48 @@
 use std::borrow::Cow;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
 use std::fs::File;
+use std::io::Write;
 use std::path::Path;
 
 use cellulite::Cellulite;
+use charabia::Language;
 use deserr::Deserr;
+use fst::Streamer;
 use heed::types::*;
-use heed::{CompactionOption, Database, DatabaseStat, RoTxn, RwTxn, Unspecified, WithoutTls};
+use heed::{
+    BytesDecode, CompactionOption, Database, DatabaseStat, RoTxn, RwTxn, Unspecified, WithoutTls,
+};
 use indexmap::IndexMap;
 use roaring::RoaringBitmap;
 use rstar::RTree;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::attribute_patterns::PatternMatch;
 use crate::constants::{self, RESERVED_GEO_FIELD_NAME, RESERVED_VECTORS_FIELD_NAME};
 use crate::database_stats::DatabaseStats;
 use crate::documents::PrimaryKey;
 use crate::error::{InternalError, UserError};
+use crate::facet::FacetType;
 use crate::fields_ids_map::metadata::{FieldIdMapWithMetadata, MetadataBuilder};
 use crate::fields_ids_map::FieldsIdsMap;
+use crate::filterable_attributes_rules::matching_features;
 use crate::heed_codec::facet::{
-    FacetGroupKeyCodec, FacetGroupValueCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec,
-    FieldIdCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec,
+    FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec, FieldIdCodec, OrderedF64Codec,
 };
 use crate::heed_codec::version::VersionCodec;
-use crate::heed_codec::{BEU16StrCodec, FstSetCodec, StrBEU16Codec, StrRefCodec};
+use crate::heed_codec::{BEU16StrCodec, BytesRefCodec, FstMapCodec, StrBEU16Codec, StrRefCodec};
 use crate::order_by_map::OrderByMap;
 use crate::prompt::PromptData;
 use crate::proximity::ProximityPrecision;
+use crate::search::facet::{
+    get_highest_level, CachedFacetDistribution, LazyFacetFsts, PinnedFacetDistribution,
+};
+use crate::search::new::hot_prefixes::HotPrefixes;
 use crate::update::new::StdResult;
 use crate::vector::db::IndexEmbeddingConfigs;
 use crate::vector::{Embedding, VectorStore, VectorStoreBackend, VectorStoreStats};
@@ -37,12 +50,15 @@ use crate::{
     default_criteria, CboRoaringBitmapCodec, Criterion, DocumentId, ExternalDocumentsIds,
     FacetDistribution, FieldDistribution, FieldId, FieldIdMapMissingEntry, FieldIdWordCountCodec,
     FieldidsWeightsMap, FilterableAttributesRule, GeoPoint, LocalizedAttributesRule, ObkvCodec,
-    Result, RoaringBitmapCodec, RoaringBitmapLenCodec, Search, U8StrStrCodec, Weight, BEU16, BEU32,
-    BEU64,
+    Prefix, Result, RoaringBitmapCodec, RoaringBitmapLenCodec, Search, U8StrStrCodec, Weight,
+    BEU16, BEU32, BEU64,
 };
 
 pub const DEFAULT_MIN_WORD_LEN_ONE_TYPO: u8 = 5;
 pub const DEFAULT_MIN_WORD_LEN_TWO_TYPOS: u8 = 9;
+pub const DEFAULT_MIN_PREFIX_LENGTH: u8 = 1;
+/// The maximum number of word prefixes read by a [`PrefixSearchWarmup`].
+pub const MAX_WARMUP_PREFIXES: usize = 1000;
 
 pub mod main_key {
     pub const VERSION_KEY: &str = "version";
@@ -77,6 +93,17 @@ pub mod main_key {
     pub const EXACT_WORDS: &str = "exact-words";
     pub const EXACT_ATTRIBUTES: &str = "exact-attributes";
     pub const MAX_VALUES_PER_FACET: &str = "max-values-per-facet";
+    pub const MAX_VALUES_PER_DOCUMENT_FIELD: &str = "max-values-per-document-field";
+    pub const TRACK_FACET_VALUES_RECENCY: &str = "track-facet-values-recency";
+    pub const MAX_FACET_LEVELS: &str = "max-facet-levels";
+    pub const FACET_REBALANCING_THRESHOLD: &str = "facet-rebalancing-threshold";
+    pub const FACET_STRING_INCREMENTAL_UPDATES: &str = "facet-string-incremental-updates";
+    pub const FACET_NUMBER_INCREMENTAL_UPDATES: &str = "facet-number-incremental-updates";
+    pub const FACET_EXPECTED_CARDINALITY: &str = "facet-expected-cardinality";
+    pub const FACET_UPDATE_METHODS: &str = "facet-update-methods";
+    pub const FACET_VALUE_WEIGHT_FIELDS: &str = "facet-value-weight-fields";
+    pub const PINNED_FACET_DISTRIBUTIONS: &str = "pinned-facet-distributions";
+    pub const CACHED_FACET_DISTRIBUTIONS: &str = "cached-facet-distributions";
     pub const SORT_FACET_VALUES_BY: &str = "sort-facet-values-by";
     pub const PAGINATION_MAX_TOTAL_HITS: &str = "pagination-max-total-hits";
     pub const PROXIMITY_PRECISION: &str = "proximity-precision";
@@ -84,7 +111,13 @@ pub mod main_key {
     pub const SEARCH_CUTOFF: &str = "search_cutoff";
     pub const LOCALIZED_ATTRIBUTES_RULES: &str = "localized_attributes_rules";
     pub const FACET_SEARCH: &str = "facet_search";
+    pub const FACETING_MODE: &str = "faceting_mode";
+    pub const FACET_SEARCH_FSTS_HAVE_COUNTS: &str = "facet_search_fsts_have_counts";
+    pub const HOT_PREFIXES_QUERY_LOG: &str = "hot_prefixes_query_log";
+    pub const FACETING_WARMUP: &str = "faceting_warmup";
     pub const PREFIX_SEARCH: &str = "prefix_search";
+    pub const MIN_PREFIX_LENGTH: &str = "min_prefix_length";
+    pub const PREFIX_SEARCH_WARMUP: &str = "prefix_search_warmup";
     pub const DOCUMENTS_STATS: &str = "documents_stats";
     pub const DISABLED_TYPOS_TERMS: &str = "disabled_typos_terms";
     pub const CHAT: &str = "chat";
@@ -112,6 +145,8 @@ pub mod db_name {
     pub const FACET_ID_STRING_DOCIDS: &str = "facet-id-string-docids";
     pub const FACET_ID_NORMALIZED_STRING_STRINGS: &str = "facet-id-normalized-string-strings";
     pub const FACET_ID_STRING_FST: &str = "facet-id-string-fst";
+    pub const FACET_ID_STRING_RECENCY: &str = "facet-id-string-recency";
+    pub const FACET_ID_STRING_WEIGHT: &str = "facet-id-string-weight";
     pub const FIELD_ID_DOCID_FACET_F64S: &str = "field-id-docid-facet-f64s";
     pub const FIELD_ID_DOCID_FACET_STRINGS: &str = "field-id-docid-facet-strings";
     pub const VECTOR_EMBEDDER_CATEGORY_ID: &str = "vector-embedder-category-id";
@@ -119,7 +154,7 @@ pub mod db_name {
     pub const CELLULITE: &str = "cellulite";
     pub const DOCUMENTS: &str = "documents";
 }
-const NUMBER_OF_DBS: u32 = 25 + Cellulite::nb_dbs();
+const NUMBER_OF_DBS: u32 = 27 + Cellulite::nb_dbs();
 
 #[derive(Clone)]
 pub struct Index {
@@ -172,8 +207,17 @@ pub struct Index {
     pub facet_id_string_docids: Database<FacetGroupKeyCodec<StrRefCodec>, FacetGroupValueCodec>,
     /// Maps the facet field id of the normalized-for-search string facets with their original versions.
     pub facet_id_normalized_string_strings: Database<BEU16StrCodec, SerdeJson<BTreeSet<String>>>,
-    /// Maps the facet field id of the string facets with an FST containing all the facets values.
-    pub facet_id_string_fst: Database<BEU16, FstSetCodec>,
+    /// Maps the facet field id of the string facets with an FST map associating each of their
+    /// normalized facets values with the number of documents of its original values, `0` meaning
+    /// it is unknown as in the FSTs written before the counts were stored.
+    pub facet_id_string_fst: Database<BEU16, FstMapCodec>,
+    /// Maps the facet field id and the string facets with the time, in nanoseconds since the
+    /// Unix epoch, they were last associated with a document. Only filled when the index
+    /// tracks the recency of its facet values.
+    pub facet_id_string_recency: Database<BEU16StrCodec, BEU64>,
+    /// Maps the facet field id and the string facets with their sort weight, read from the
+    /// weight field configured for the facet field. Only filled for the configured fields.
+    pub facet_id_string_weight: Database<BEU16StrCodec, OrderedF64Codec>,
 
     /// Maps the document id, the facet field id and the numbers.
     pub field_id_docid_facet_f64s: Database<FieldDocIdFacetF64Codec, Unit>,
@@ -190,6 +234,12 @@ pub struct Index {
 
     /// Maps the document id to the document as an obkv store.
     pub(crate) documents: Database<BEU32, ObkvCodec>,
+
+    /// The facet search FSTs built at search time when the faceting mode is lazy.
+    pub(crate) lazy_facet_fsts: LazyFacetFsts,
+
+    /// The prefixes queried by the searches when the prefix search computes the hot prefixes.
+    pub(crate) hot_prefixes: HotPrefixes,
 }
 
 impl Index {
@@ -230,6 +280,10 @@ impl Index {
         let facet_id_normalized_string_strings =
             env.create_database(&mut wtxn, Some(FACET_ID_NORMALIZED_STRING_STRINGS))?;
         let facet_id_string_fst = env.create_database(&mut wtxn, Some(FACET_ID_STRING_FST))?;
+        let facet_id_string_recency =
+            env.create_database(&mut wtxn, Some(FACET_ID_STRING_RECENCY))?;
+        let facet_id_string_weight =
+            env.create_database(&mut wtxn, Some(FACET_ID_STRING_WEIGHT))?;
         let facet_id_exists_docids =
             env.create_database(&mut wtxn, Some(FACET_ID_EXISTS_DOCIDS))?;
         let facet_id_is_null_docids =
@@ -266,6 +320,8 @@ impl Index {
             facet_id_string_docids,
             facet_id_normalized_string_strings,
             facet_id_string_fst,
+            facet_id_string_recency,
+            facet_id_string_weight,
             facet_id_exists_docids,
             facet_id_is_null_docids,
             facet_id_is_empty_docids,
@@ -275,6 +331,8 @@ impl Index {
             embedder_category_id,
             cellulite,
             documents,
+            lazy_facet_fsts: LazyFacetFsts::default(),
+            hot_prefixes: HotPrefixes::default(),
         };
         if this.get_version(&wtxn)?.is_none() && creation {
             this.put_version(
@@ -284,7 +342,9 @@ impl Index {
             // The database before v1.29 defaulted to using arroy, so we
             // need to set it explicitly because the new default is hannoy.
             this.put_vector_store(&mut wtxn, VectorStoreBackend::Hannoy)?;
+            this.put_facet_search_fsts_have_counts(&mut wtxn)?;
         }
+        this.hot_prefixes.restore_query_log(this.hot_prefixes_query_log(&wtxn)?);
         wtxn.commit()?;
 
         Index::set_creation_dates(&this.env, this.main, created_at, updated_at)?;
@@ -1086,6 +1146,47 @@ impl Index {
         Ok(asc_desc_fields)
     }
 
+    /// Returns the number of fields of the index matching a filterable attributes rule.
+    pub fn faceted_fields_count(&self, rtxn: &RoTxn<'_>) -> Result<usize> {
+        let rules = self.filterable_attributes_rules(rtxn)?;
+        let fields_ids_map = self.fields_ids_map_with_metadata(rtxn)?;
+        Ok(fields_ids_map.iter_metadata().filter(|metadata| metadata.is_faceted(&rules)).count())
+    }
+
+    /// Returns the faceted fields ids along with the type of the facet values they store.
+    ///
+    /// A field storing both strings and numbers is returned once for each type,
+    /// a faceted field without any value yet is not returned.
+    pub fn facet_fields_with_types(&self, rtxn: &RoTxn<'_>) -> Result<Vec<(FieldId, FacetType)>> {
+        let rules = self.filterable_attributes_rules(rtxn)?;
+        let fields_ids_map = self.fields_ids_map_with_metadata(rtxn)?;
+        let mut faceted_fields: Vec<_> = fields_ids_map
+            .iter_id_metadata()
+            .filter(|(_, metadata)| metadata.is_faceted(&rules))
+            .map(|(field_id, _)| field_id)
+            .collect();
+        faceted_fields.sort_unstable();
+
+        let mut fields_with_types = Vec::new();
+        for field_id in faceted_fields {
+            // we only need to look at the first level to know if the field holds any value
+            let mut prefix = [0; 3];
+            prefix[..2].copy_from_slice(&field_id.to_be_bytes());
+
+            let string_docids = self.facet_id_string_docids.remap_key_type::<Bytes>();
+            if string_docids.prefix_iter(rtxn, &prefix)?.next().transpose()?.is_some() {
+                fields_with_types.push((field_id, FacetType::String));
+            }
+
+            let f64_docids = self.facet_id_f64_docids.remap_key_type::<Bytes>();
+            if f64_docids.prefix_iter(rtxn, &prefix)?.next().transpose()?.is_some() {
+                fields_with_types.push((field_id, FacetType::Number));
+            }
+        }
+
+        Ok(fields_with_types)
+    }
+
     /* faceted documents ids */
 
     /// Retrieve all the documents which contain this field id set as null
@@ -1652,6 +1753,308 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(txn, main_key::MAX_VALUES_PER_FACET)
     }
 
+    /// Returns the maximum number of distinct facet values a document can contribute
+    /// to a single field, `None` when it is unlimited.
+    pub fn max_values_per_document_field(&self, txn: &RoTxn<'_>) -> heed::Result<Option<u64>> {
+        self.main.remap_types::<Str, BEU64>().get(txn, main_key::MAX_VALUES_PER_DOCUMENT_FIELD)
+    }
+
+    pub(crate) fn put_max_values_per_document_field(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: u64,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, BEU64>().put(
+            txn,
+            main_key::MAX_VALUES_PER_DOCUMENT_FIELD,
+            &val,
+        )
+    }
+
+    pub(crate) fn delete_max_values_per_document_field(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::MAX_VALUES_PER_DOCUMENT_FIELD)
+    }
+
+    /// Returns whether the time the string facet values were last associated
+    /// with a document is recorded, see [`Index::facet_id_string_recency`].
+    pub fn track_facet_values_recency(&self, txn: &RoTxn<'_>) -> heed::Result<bool> {
+        self.main
+            .remap_types::<Str, SerdeBincode<bool>>()
+            .get(txn, main_key::TRACK_FACET_VALUES_RECENCY)
+            .map(|v| v.unwrap_or(false))
+    }
+
+    pub(crate) fn put_track_facet_values_recency(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: bool,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<bool>>().put(
+            txn,
+            main_key::TRACK_FACET_VALUES_RECENCY,
+            &val,
+        )
+    }
+
+    pub(crate) fn delete_track_facet_values_recency(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::TRACK_FACET_VALUES_RECENCY)
+    }
+
+    /// Returns the maximum number of levels the bulk method can build above the level 0
+    /// of the facet databases, `None` when only the default parameters bound it.
+    pub fn max_facet_levels(&self, txn: &RoTxn<'_>) -> heed::Result<Option<u8>> {
+        self.main.remap_types::<Str, U8>().get(txn, main_key::MAX_FACET_LEVELS)
+    }
+
+    pub(crate) fn put_max_facet_levels(&self, txn: &mut RwTxn<'_>, val: u8) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(txn, main_key::MAX_FACET_LEVELS, &val)
+    }
+
+    pub(crate) fn delete_max_facet_levels(&self, txn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::MAX_FACET_LEVELS)
+    }
+
+    /// Returns the number of incremental updates after which the facet levels of a field
+    /// are rebuilt with the bulk method, `None` when they are only rebuilt on large deltas.
+    pub fn facet_rebalancing_threshold(&self, txn: &RoTxn<'_>) -> heed::Result<Option<u64>> {
+        self.main.remap_types::<Str, BEU64>().get(txn, main_key::FACET_REBALANCING_THRESHOLD)
+    }
+
+    pub(crate) fn put_facet_rebalancing_threshold(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: u64,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, BEU64>().put(txn, main_key::FACET_REBALANCING_THRESHOLD, &val)
+    }
+
+    pub(crate) fn delete_facet_rebalancing_threshold(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::FACET_REBALANCING_THRESHOLD)
+    }
+
+    /// Returns, for each field, the number of incremental updates applied to its facet levels
+    /// of the given type since they were last rebuilt with the bulk method.
+    pub fn facet_incremental_updates(
+        &self,
+        txn: &RoTxn<'_>,
+        facet_type: FacetType,
+    ) -> heed::Result<BTreeMap<FieldId, u64>> {
+        let key = match facet_type {
+            FacetType::String => main_key::FACET_STRING_INCREMENTAL_UPDATES,
+            FacetType::Number => main_key::FACET_NUMBER_INCREMENTAL_UPDATES,
+        };
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeMap<FieldId, u64>>>()
+            .get(txn, key)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_facet_incremental_updates(
+        &self,
+        txn: &mut RwTxn<'_>,
+        facet_type: FacetType,
+        val: &BTreeMap<FieldId, u64>,
+    ) -> heed::Result<()> {
+        let key = match facet_type {
+            FacetType::String => main_key::FACET_STRING_INCREMENTAL_UPDATES,
+            FacetType::Number => main_key::FACET_NUMBER_INCREMENTAL_UPDATES,
+        };
+        self.main.remap_types::<Str, SerdeJson<BTreeMap<FieldId, u64>>>().put(txn, key, val)
+    }
+
+    pub(crate) fn delete_facet_incremental_updates(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        let string = self
+            .main
+            .remap_key_type::<Str>()
+            .delete(txn, main_key::FACET_STRING_INCREMENTAL_UPDATES)?;
+        let number = self
+            .main
+            .remap_key_type::<Str>()
+            .delete(txn, main_key::FACET_NUMBER_INCREMENTAL_UPDATES)?;
+        Ok(string || number)
+    }
+
+    /// Returns the number of values the fields are expected to have, by field name. The bulk
+    /// method starts from larger groups for these fields to build shallower facet trees.
+    pub fn facet_expected_cardinality(
+        &self,
+        txn: &RoTxn<'_>,
+    ) -> heed::Result<BTreeMap<String, u64>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(txn, main_key::FACET_EXPECTED_CARDINALITY)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_facet_expected_cardinality(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: &BTreeMap<String, u64>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            txn,
+            main_key::FACET_EXPECTED_CARDINALITY,
+            val,
+        )
+    }
+
+    pub(crate) fn delete_facet_expected_cardinality(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::FACET_EXPECTED_CARDINALITY)
+    }
+
+    /// Returns the method used to update the facet levels of the fields, by field name.
+    /// The fields without one use the [`FacetUpdateMethodSetting::Auto`] method.
+    pub fn facet_update_methods(
+        &self,
+        txn: &RoTxn<'_>,
+    ) -> heed::Result<BTreeMap<String, FacetUpdateMethodSetting>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(txn, main_key::FACET_UPDATE_METHODS)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_facet_update_methods(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: &BTreeMap<String, FacetUpdateMethodSetting>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(txn, main_key::FACET_UPDATE_METHODS, val)
+    }
+
+    pub(crate) fn delete_facet_update_methods(&self, txn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::FACET_UPDATE_METHODS)
+    }
+
+    /// Returns the field holding the sort weight of the values of the facet fields, by facet
+    /// field name. The weights are recorded in [`Index::facet_id_string_weight`].
+    pub fn facet_value_weight_fields(
+        &self,
+        txn: &RoTxn<'_>,
+    ) -> heed::Result<BTreeMap<String, String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(txn, main_key::FACET_VALUE_WEIGHT_FIELDS)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_facet_value_weight_fields(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: &BTreeMap<String, String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            txn,
+            main_key::FACET_VALUE_WEIGHT_FIELDS,
+            val,
+        )
+    }
+
+    pub(crate) fn delete_facet_value_weight_fields(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::FACET_VALUE_WEIGHT_FIELDS)
+    }
+
+    /// Returns the filter and facets combinations whose distribution is computed after each batch.
+    pub fn pinned_facet_distributions(
+        &self,
+        txn: &RoTxn<'_>,
+    ) -> heed::Result<Vec<PinnedFacetDistribution>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(txn, main_key::PINNED_FACET_DISTRIBUTIONS)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_pinned_facet_distributions(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: &[PinnedFacetDistribution],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            txn,
+            main_key::PINNED_FACET_DISTRIBUTIONS,
+            &val,
+        )
+    }
+
+    pub(crate) fn delete_pinned_facet_distributions(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::PINNED_FACET_DISTRIBUTIONS)
+    }
+
+    /// Returns the distributions of the pinned combinations as computed by the last batch,
+    /// whether or not the index has been updated since.
+    pub fn cached_facet_distributions(
+        &self,
+        txn: &RoTxn<'_>,
+    ) -> heed::Result<Vec<CachedFacetDistribution>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(txn, main_key::CACHED_FACET_DISTRIBUTIONS)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the distribution computed for this filter and these facets if it is pinned
+    /// and the index has not been updated since it was computed.
+    pub fn cached_facet_distribution<S: AsRef<str>>(
+        &self,
+        txn: &RoTxn<'_>,
+        filter: &str,
+        facets: &[S],
+    ) -> Result<Option<CachedFacetDistribution>> {
+        let updated_at = self.updated_at(txn)?;
+        Ok(self
+            .cached_facet_distributions(txn)?
+            .into_iter()
+            .find(|cached| cached.matches(filter, facets))
+            .filter(|cached| cached.updated_at == updated_at))
+    }
+
+    pub(crate) fn put_cached_facet_distributions(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: &[CachedFacetDistribution],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            txn,
+            main_key::CACHED_FACET_DISTRIBUTIONS,
+            &val,
+        )
+    }
+
+    pub(crate) fn delete_cached_facet_distributions(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::CACHED_FACET_DISTRIBUTIONS)
+    }
+
     pub fn sort_facet_values_by(&self, txn: &RoTxn<'_>) -> heed::Result<OrderByMap> {
         let orders = self
             .main
@@ -1734,6 +2137,23 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(txn, main_key::PREFIX_SEARCH)
     }
 
+    /// The minimum length, in bytes, of the prefixes stored in the prefix databases.
+    pub fn min_prefix_length(&self, txn: &RoTxn<'_>) -> heed::Result<u8> {
+        Ok(self
+            .main
+            .remap_types::<Str, U8>()
+            .get(txn, main_key::MIN_PREFIX_LENGTH)?
+            .unwrap_or(DEFAULT_MIN_PREFIX_LENGTH))
+    }
+
+    pub(crate) fn put_min_prefix_length(&self, txn: &mut RwTxn<'_>, val: u8) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(txn, main_key::MIN_PREFIX_LENGTH, &val)
+    }
+
+    pub(crate) fn delete_min_prefix_length(&self, txn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::MIN_PREFIX_LENGTH)
+    }
+
     pub fn facet_search(&self, txn: &RoTxn<'_>) -> heed::Result<bool> {
         self.main
             .remap_types::<Str, SerdeBincode<bool>>()
@@ -1749,6 +2169,207 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(txn, main_key::FACET_SEARCH)
     }
 
+    pub fn faceting_mode(&self, txn: &RoTxn<'_>) -> heed::Result<FacetingMode> {
+        self.main
+            .remap_types::<Str, SerdeBincode<FacetingMode>>()
+            .get(txn, main_key::FACETING_MODE)
+            .map(|v| v.unwrap_or_default())
+    }
+
+    pub(crate) fn put_faceting_mode(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: FacetingMode,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<FacetingMode>>().put(
+            txn,
+            main_key::FACETING_MODE,
+            &val,
+        )
+    }
+
+    pub(crate) fn delete_faceting_mode(&self, txn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::FACETING_MODE)
+    }
+
+    /// Whether all the facet search FSTs store the document count of each facet value.
+    ///
+    /// The indexes written before the FSTs stored the counts have the same version as the
+    /// ones written after, this marker tells them apart.
+    pub fn facet_search_fsts_have_counts(&self, txn: &RoTxn<'_>) -> heed::Result<bool> {
+        self.main
+            .remap_types::<Str, SerdeBincode<bool>>()
+            .get(txn, main_key::FACET_SEARCH_FSTS_HAVE_COUNTS)
+            .map(|v| v.unwrap_or(false))
+    }
+
+    pub(crate) fn put_facet_search_fsts_have_counts(
+        &self,
+        txn: &mut RwTxn<'_>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<bool>>().put(
+            txn,
+            main_key::FACET_SEARCH_FSTS_HAVE_COUNTS,
+            &true,
+        )
+    }
+
+    /// Returns the facet warmup to run when the index is opened, `None` when it is disabled.
+    pub fn faceting_warmup(&self, txn: &RoTxn<'_>) -> heed::Result<Option<FacetingWarmup>> {
+        self.main
+            .remap_types::<Str, SerdeBincode<FacetingWarmup>>()
+            .get(txn, main_key::FACETING_WARMUP)
+    }
+
+    pub(crate) fn put_faceting_warmup(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: FacetingWarmup,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<FacetingWarmup>>().put(
+            txn,
+            main_key::FACETING_WARMUP,
+            &val,
+        )
+    }
+
+    pub(crate) fn delete_faceting_warmup(&self, txn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::FACETING_WARMUP)
+    }
+
+    /// Reads the facet search FSTs, and optionally the highest levels of the facet trees,
+    /// so that the pages backing them are loaded in the page cache before the first facet search.
+    ///
+    /// Stops as soon as `warmup.max_bytes` bytes have been read and returns the number of bytes read.
+    pub fn warmup_facets(&self, rtxn: &RoTxn<'_>, warmup: FacetingWarmup) -> Result<u64> {
+        let mut read = 0u64;
+        let mut touch = |bytes: &[u8]| -> bool {
+            let remaining = warmup.max_bytes.saturating_sub(read);
+            let len = bytes.len().min(usize::try_from(remaining).unwrap_or(usize::MAX));
+            touch_pages(&bytes[..len]);
+            read += len as u64;
+            read < warmup.max_bytes
+        };
+
+        for result in self.facet_id_string_fst.remap_data_type::<Bytes>().iter(rtxn)? {
+            let (_field_id, fst) = result?;
+            if !touch(fst) {
+                return Ok(read);
+            }
+        }
+
+        if warmup.top_levels {
+            let databases = [
+                self.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                self.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+            ];
+            let mut field_ids: Vec<_> = self
+                .facet_fields_with_types(rtxn)?
+                .into_iter()
+                .map(|(field_id, _)| field_id)
+                .collect();
+            field_ids.dedup();
+            for field_id in field_ids {
+                for database in databases {
+                    let level = get_highest_level(rtxn, database, field_id)?;
+                    if level == 0 {
+                        continue;
+                    }
+                    let prefix = [&field_id.to_be_bytes()[..], &[level]].concat();
+                    for result in
+                        database.remap_types::<Bytes, Bytes>().prefix_iter(rtxn, &prefix)?
+                    {
+                        let (key, value) = result?;
+                        if !touch(key) || !touch(value) {
+                            return Ok(read);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(read)
+    }
+
+    /// Returns the word prefixes to read when the index is opened, `None` when it is disabled.
+    pub fn prefix_search_warmup(
+        &self,
+        txn: &RoTxn<'_>,
+    ) -> heed::Result<Option<PrefixSearchWarmup>> {
+        self.main
+            .remap_types::<Str, SerdeJson<PrefixSearchWarmup>>()
+            .get(txn, main_key::PREFIX_SEARCH_WARMUP)
+    }
+
+    pub(crate) fn put_prefix_search_warmup(
+        &self,
+        txn: &mut RwTxn<'_>,
+        val: &PrefixSearchWarmup,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<PrefixSearchWarmup>>().put(
+            txn,
+            main_key::PREFIX_SEARCH_WARMUP,
+            val,
+        )
+    }
+
+    pub(crate) fn delete_prefix_search_warmup(&self, txn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::PREFIX_SEARCH_WARMUP)
+    }
+
+    /// Reads the `word_prefix_docids` and `exact_word_prefix_docids` entries of the prefixes
+    /// selected by the warmup so that the pages backing them are loaded in the page cache
+    /// before the first prefix search.
+    ///
+    /// Returns the prefixes that were found in the databases, in the order they were read.
+    pub fn warmup_word_prefixes(
+        &self,
+        rtxn: &RoTxn<'_>,
+        warmup: &PrefixSearchWarmup,
+    ) -> Result<Vec<String>> {
+        let word_prefix_docids = self.word_prefix_docids.remap_data_type::<Bytes>();
+        let exact_word_prefix_docids = self.exact_word_prefix_docids.remap_data_type::<Bytes>();
+
+        let prefixes: Vec<String> = match warmup {
+            PrefixSearchWarmup::Prefixes(prefixes) => {
+                prefixes.iter().take(MAX_WARMUP_PREFIXES).cloned().collect()
+            }
+            PrefixSearchWarmup::Largest(count) => {
+                // the length of the entries is known without reading their pages
+                let count = (*count).min(MAX_WARMUP_PREFIXES);
+                let mut largest = BinaryHeap::with_capacity(count + 1);
+                for result in word_prefix_docids.iter(rtxn)? {
+                    let (prefix, docids) = result?;
+                    largest.push(Reverse((docids.len(), prefix)));
+                    if largest.len() > count {
+                        largest.pop();
+                    }
+                }
+                largest
+                    .into_sorted_vec()
+                    .into_iter()
+                    .map(|Reverse((_, prefix))| prefix.to_string())
+                    .collect()
+            }
+        };
+
+        let mut warmed_up = Vec::with_capacity(prefixes.len());
+        for prefix in prefixes {
+            let mut found = false;
+            for database in [word_prefix_docids, exact_word_prefix_docids] {
+                if let Some(docids) = database.get(rtxn, &prefix)? {
+                    touch_pages(docids);
+                    found = true;
+                }
+            }
+            if found {
+                warmed_up.push(prefix);
+            }
+        }
+
+        Ok(warmed_up)
+    }
+
     pub fn chat_config(&self, txn: &RoTxn<'_>) -> heed::Result<ChatConfig> {
         self.main
             .remap_types::<Str, SerdeJson<_>>()
@@ -1842,7 +2463,54 @@ impl Index {
 
     pub fn prefix_settings(&self, rtxn: &RoTxn<'_>) -> Result<PrefixSettings> {
         let compute_prefixes = self.prefix_search(rtxn)?.unwrap_or_default();
-        Ok(PrefixSettings { compute_prefixes, max_prefix_length: 4, prefix_count_threshold: 100 })
+        let min_prefix_length = self.min_prefix_length(rtxn)?.max(1) as usize;
+        let hot_prefixes = match compute_prefixes {
+            PrefixSearch::HotPrefixes => Some(self.hot_prefixes_to_materialize(rtxn)?),
+            PrefixSearch::IndexingTime | PrefixSearch::Disabled => None,
+        };
+        Ok(PrefixSettings {
+            compute_prefixes,
+            min_prefix_length,
+            max_prefix_length: 4,
+            prefix_count_threshold: 100,
+            hot_prefixes,
+        })
+    }
+
+    /// The query log of the [`PrefixSearch::HotPrefixes`] persisted by the last indexing
+    /// operation, from the least to the most recently queried prefix.
+    fn hot_prefixes_query_log(&self, rtxn: &RoTxn<'_>) -> heed::Result<Vec<String>> {
+        self.main
+            .remap_types::<Str, SerdeBincode<Vec<String>>>()
+            .get(rtxn, main_key::HOT_PREFIXES_QUERY_LOG)
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Persists the query log of the [`PrefixSearch::HotPrefixes`], to restore it when
+    /// the index is opened again.
+    pub(crate) fn put_hot_prefixes_query_log(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<Vec<String>>>().put(
+            wtxn,
+            main_key::HOT_PREFIXES_QUERY_LOG,
+            &self.hot_prefixes.query_log(),
+        )
+    }
+
+    /// The prefixes recently queried or, when none is logged, e.g. when the prefix search
+    /// was just switched to [`PrefixSearch::HotPrefixes`], the prefixes already materialized.
+    fn hot_prefixes_to_materialize(&self, rtxn: &RoTxn<'_>) -> Result<BTreeSet<Prefix>> {
+        let recent_queries = self.hot_prefixes.recent_queries();
+        if !recent_queries.is_empty() {
+            return Ok(recent_queries);
+        }
+
+        let mut materialized = BTreeSet::new();
+        let words_prefixes_fst = self.words_prefixes_fst(rtxn)?;
+        let mut stream = words_prefixes_fst.stream();
+        while let Some(prefix) = stream.next() {
+            materialized.insert(Prefix::from(std::str::from_utf8(prefix)?));
+        }
+        Ok(materialized)
     }
 
     pub fn vector_store_stats(&self, rtxn: &RoTxn<'_>) -> Result<VectorStoreStats> {
@@ -1863,6 +2531,192 @@ impl Index {
         Ok(stats)
     }
 
+    /// Returns the number of levels and nodes of the facet trees of every faceted field.
+    ///
+    /// This walks the whole facet databases and must only be called on demand.
+    pub fn facet_tree_stats(&self, rtxn: &RoTxn<'_>) -> Result<BTreeMap<String, FacetTreeStats>> {
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        let databases = [
+            self.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+            self.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+        ];
+
+        let mut stats = BTreeMap::new();
+        for (field_id, name) in fields_ids_map.iter() {
+            let mut field_stats = FacetTreeStats::default();
+            for database in databases {
+                let mut node_count = 0;
+                let prefix = field_id.to_be_bytes();
+                for result in
+                    database.remap_types::<Bytes, DecodeIgnore>().prefix_iter(rtxn, &prefix)?
+                {
+                    result?;
+                    node_count += 1;
+                }
+
+                if node_count != 0 {
+                    let levels = get_highest_level(rtxn, database, field_id)? + 1;
+                    field_stats.levels = field_stats.levels.max(levels);
+                    field_stats.node_count += node_count;
+                }
+            }
+
+            if field_stats.node_count != 0 {
+                stats.insert(name.to_string(), field_stats);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Returns how the string facet values of every filterable field are normalized, as
+    /// the facet search queries must be normalized the same way to match them.
+    pub fn facet_normalizations(
+        &self,
+        rtxn: &RoTxn<'_>,
+    ) -> Result<BTreeMap<String, FacetNormalization>> {
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        let filterable_attributes_rules = self.filterable_attributes_rules(rtxn)?;
+        let localized_attributes_rules = self.localized_attributes_rules(rtxn)?.unwrap_or_default();
+        let facet_search = self.facet_search(rtxn)?;
+
+        let mut normalizations = BTreeMap::new();
+        for (_, name) in fields_ids_map.iter() {
+            let Some((_, features)) = matching_features(name, &filterable_attributes_rules) else {
+                continue;
+            };
+            let locales = localized_attributes_rules
+                .iter()
+                .find(|rule| rule.match_str(name) == PatternMatch::Match)
+                .map(|rule| rule.locales.clone());
+            let normalization = FacetNormalization {
+                facet_search: facet_search && features.is_facet_searchable(),
+                lossy: features.is_lossy_normalization(),
+                locales,
+                case_sensitive: false,
+            };
+            normalizations.insert(name.to_string(), normalization);
+        }
+
+        Ok(normalizations)
+    }
+
+    /// Returns the number of distinct facet values of the field, string and number values included.
+    ///
+    /// When `candidates` is given, only the values associated to at least one of the candidates are counted,
+    /// otherwise it is a count of the level 0 nodes of the facet trees of the field.
+    pub fn facet_value_cardinality(
+        &self,
+        rtxn: &RoTxn<'_>,
+        field_id: FieldId,
+        candidates: Option<&RoaringBitmap>,
+    ) -> Result<u64> {
+        let databases = [
+            self.facet_id_string_docids.remap_key_type::<Bytes>(),
+            self.facet_id_f64_docids.remap_key_type::<Bytes>(),
+        ];
+        let mut prefix = [0; 3];
+        prefix[..2].copy_from_slice(&field_id.to_be_bytes());
+
+        let mut cardinality = 0;
+        for database in databases {
+            match candidates {
+                Some(candidates) => {
+                    for result in database.prefix_iter(rtxn, &prefix)? {
+                        let (_, FacetGroupValue { bitmap, .. }) = result?;
+                        if !candidates.is_disjoint(&bitmap) {
+                            cardinality += 1;
+                        }
+                    }
+                }
+                None => {
+                    for result in
+                        database.remap_data_type::<DecodeIgnore>().prefix_iter(rtxn, &prefix)?
+                    {
+                        result?;
+                        cardinality += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(cardinality)
+    }
+
+    /// Writes every distinct facet value of the field to the writer, one per line, and returns
+    /// the number of values written. The numbers come first, then the strings.
+    ///
+    /// The strings are de-normalized: the spelling of the first document of a value is written,
+    /// e.g. `Science Fiction` rather than `science fiction`. They are read in the order of the
+    /// facet search normalization when the field is facet searchable, the values colliding in the
+    /// facet search being written one after the other, and in the level 0 order otherwise.
+    /// The values are streamed from the databases and never collected.
+    pub fn dump_facet_values(
+        &self,
+        rtxn: &RoTxn<'_>,
+        field_id: FieldId,
+        format: FacetValuesDumpFormat,
+        mut writer: impl Write,
+    ) -> Result<u64> {
+        let mut written = 0;
+        let mut write_line = |value: serde_json::Value| -> Result<()> {
+            match (format, value) {
+                (FacetValuesDumpFormat::Text, serde_json::Value::String(value)) => {
+                    writeln!(writer, "{value}")?
+                }
+                (_, value) => {
+                    serde_json::to_writer(&mut writer, &value).map_err(InternalError::SerdeJson)?;
+                    writeln!(writer)?;
+                }
+            }
+            written += 1;
+            Ok(())
+        };
+
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: &[][..] };
+        let numbers_db =
+            self.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        for result in numbers_db.remap_data_type::<DecodeIgnore>().prefix_iter(rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, ()) = result?;
+            let number =
+                OrderedF64Codec::bytes_decode(left_bound).map_err(heed::Error::Decoding)?;
+            write_line(serde_json::json!(number))?;
+        }
+
+        let strings_db = self.facet_id_string_docids;
+        let mut write_string = |normalized: &str| -> Result<()> {
+            let key = FacetGroupKey { field_id, level: 0, left_bound: normalized };
+            let Some(FacetGroupValue { bitmap, .. }) = strings_db.get(rtxn, &key)? else {
+                return Ok(());
+            };
+            let Some(docid) = bitmap.min() else { return Ok(()) };
+            let original =
+                self.field_id_docid_facet_strings.get(rtxn, &(field_id, docid, normalized))?;
+            write_line(serde_json::Value::String(original.unwrap_or(normalized).to_string()))
+        };
+
+        let mut is_facet_searchable = false;
+        let search_db = self.facet_id_normalized_string_strings.remap_key_type::<Bytes>();
+        for result in search_db.prefix_iter(rtxn, &field_id.to_be_bytes())? {
+            let (_, normalized_values) = result?;
+            is_facet_searchable = true;
+            for normalized in &normalized_values {
+                write_string(normalized)?;
+            }
+        }
+        if !is_facet_searchable {
+            let strings_db =
+                self.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+            for result in strings_db.remap_data_type::<DecodeIgnore>().prefix_iter(rtxn, &prefix)? {
+                let (FacetGroupKey { left_bound, .. }, ()) = result?;
+                write_string(std::str::from_utf8(left_bound)?)?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(written)
+    }
+
     /// Check if the word is indexed in the index.
     ///
     /// This function checks if the word is indexed in the index by looking at the word_docids and exact_word_docids.
@@ -1896,6 +2750,8 @@ impl Index {
             facet_id_string_docids,
             facet_id_normalized_string_strings,
             facet_id_string_fst,
+            facet_id_string_recency,
+            facet_id_string_weight,
             facet_id_exists_docids,
             facet_id_is_null_docids,
             facet_id_is_empty_docids,
@@ -1905,6 +2761,8 @@ impl Index {
             embedder_category_id,
             cellulite,
             documents,
+            lazy_facet_fsts: _,
+            hot_prefixes: _,
         } = self;
 
         fn compute_size(stats: DatabaseStat) -> usize {
@@ -1955,6 +2813,12 @@ impl Index {
             facet_id_normalized_string_strings.stat(rtxn).map(compute_size)?,
         );
         sizes.insert("facet_id_string_fst", facet_id_string_fst.stat(rtxn).map(compute_size)?);
+        sizes.insert(
+            "facet_id_string_recency",
+            facet_id_string_recency.stat(rtxn).map(compute_size)?,
+        );
+        sizes
+            .insert("facet_id_string_weight", facet_id_string_weight.stat(rtxn).map(compute_size)?);
         sizes
             .insert("facet_id_exists_docids", facet_id_exists_docids.stat(rtxn).map(compute_size)?);
         sizes.insert(
@@ -2072,8 +2936,11 @@ pub struct HybridQuery {
 #[serde(rename_all = "camelCase")]
 pub struct PrefixSettings {
     pub prefix_count_threshold: usize,
+    pub min_prefix_length: usize,
     pub max_prefix_length: usize,
     pub compute_prefixes: PrefixSearch,
+    /// The only prefixes to materialize, all the frequent prefixes when `None`.
+    pub hot_prefixes: Option<BTreeSet<Prefix>>,
 }
 
 /// This is unfortunately a duplication of the struct in <meilisearch/src/search/mod.rs>.
@@ -2097,6 +2964,105 @@ pub enum PrefixSearch {
     #[default]
     IndexingTime,
     Disabled,
+    /// Only the prefixes of the recent queries are computed at indexing time,
+    /// the words of the other prefixes are retrieved at search time.
+    HotPrefixes,
+}
+
+/// The shape of the facet trees of a field, see [`Index::facet_tree_stats`].
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetTreeStats {
+    /// Number of levels of the deepest facet tree of the field, level 0 included.
+    pub levels: u8,
+    /// Number of nodes in all the levels of the string and number facet trees of the field.
+    pub node_count: u64,
+}
+
+/// How the string facet values of a field are normalized at indexing time,
+/// see [`Index::facet_normalizations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FacetNormalization {
+    /// Whether the facet values of the field can be searched.
+    pub facet_search: bool,
+    /// Whether the values are decomposed and stripped of their accents, `café` matching `cafe`.
+    pub lossy: bool,
+    /// The locales of the field, `None` when the script and language are detected from each value.
+    pub locales: Option<Vec<Language>>,
+    /// Whether the case of the values is kept, the values are currently always lowercased.
+    pub case_sensitive: bool,
+}
+
+/// The format of the facet values written by [`Index::dump_facet_values`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FacetValuesDumpFormat {
+    /// The raw values, one per line. The values containing a newline span several lines.
+    #[default]
+    Text,
+    /// A JSON string or number per line.
+    NdJson,
+}
+
+/// When the facet levels and the facet search FSTs are computed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum FacetingMode {
+    /// The facet levels and FSTs are rebuilt with every indexing operation.
+    #[default]
+    Eager,
+    /// Only the level 0 of the facet databases is maintained at indexing time,
+    /// the FSTs are built at search time and the levels on explicit rebuilds.
+    ///
+    /// Every write removes the upper levels of the modified fields, the filters, facet
+    /// distributions and sorts on these fields then walk all their values at level 0,
+    /// which is linear in their number of distinct values, until the levels are rebuilt,
+    /// e.g. by switching back to [`FacetingMode::Eager`].
+    Lazy,
+}
+
+/// How the facet levels of a field are updated by the indexing operations.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum FacetUpdateMethodSetting {
+    /// The bulk or incremental method is chosen depending on the size of the update.
+    #[default]
+    Auto,
+    /// The levels are always rebuilt with the bulk method.
+    Bulk,
+    /// The levels are always updated incrementally, until they must be rebalanced.
+    Incremental,
+}
+
+/// The facet structures to load in memory when the index is opened.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetingWarmup {
+    /// The maximum number of bytes to read.
+    pub max_bytes: u64,
+    /// Whether to also read the highest level of the facet trees after the FSTs.
+    pub top_levels: bool,
+}
+
+/// The word prefixes to load in memory when the index is opened, at most
+/// [`MAX_WARMUP_PREFIXES`] of them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PrefixSearchWarmup {
+    /// These prefixes, the ones that are not materialized are ignored.
+    Prefixes(BTreeSet<String>),
+    /// The prefixes matching the largest number of documents.
+    Largest(usize),
+}
+
+/// Reads one byte of each page of `bytes` so that the OS loads them in the page cache.
+fn touch_pages(bytes: &[u8]) {
+    const PAGE_SIZE: usize = 4096;
+
+    let mut sum = 0u8;
+    for byte in bytes.iter().step_by(PAGE_SIZE) {
+        sum = sum.wrapping_add(*byte);
+    }
+    std::hint::black_box(sum);
 }
 
 #[derive(Debug)]
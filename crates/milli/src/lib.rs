@@ -79,11 +79,18 @@ pub use self::heed_codec::{
 };
 pub use self::index::Index;
 pub use self::localized_attributes_rules::LocalizedAttributesRule;
-pub use self::search::facet::{FacetValueHit, SearchForFacetValues};
+pub use self::search::facet::{
+    refresh_pinned_facet_distributions, CachedFacetDistribution, CompactFacets,
+    CompactFacetsBuilder, FacetCollation, FacetRepresentativeValue, FacetSearchPage,
+    FacetSearchStats, FacetValueGroup, FacetValueHit, FacetValueMatchType, InvalidFacetCollation,
+    PinnedFacetDistribution, SearchForFacetValues,
+};
 pub use self::search::similar::Similar;
 pub use self::search::{
-    FacetDistribution, Filter, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, OrderBy,
-    Search, SearchResult, SemanticSearch, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    diff_facet_distributions, FacetDistribution, FacetDistributionDiff, FacetRangeBucket,
+    FacetValuesWeights, Filter, FormatOptions, GeoDistanceDistribution, MatchBounds,
+    MatcherBuilder, MatchingWords, OrderBy, Search, SearchResult, SemanticSearch,
+    TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
 };
 pub use self::update::ChannelCongestion;
 
@@ -2,7 +2,7 @@ use std::any::TypeId;
 use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 
 use enum_iterator::Sequence as _;
@@ -11,6 +11,9 @@ use itertools::Itertools;
 use serde::Serialize;
 use utoipa::ToSchema;
 
+use crate::facet::FacetType;
+use crate::update::facet::FacetUpdateDecision;
+
 pub trait Step: 'static + Send + Sync {
     fn name(&self) -> Cow<'static, str>;
     fn current(&self) -> u32;
@@ -20,6 +23,17 @@ pub trait Step: 'static + Send + Sync {
 #[derive(Clone, Default)]
 pub struct Progress {
     steps: Arc<RwLock<InnerProgress>>,
+    /// The largest number of bytes buffered at once while building the facet levels and FSTs.
+    facet_indexing_memory_peak: Arc<AtomicUsize>,
+    /// The largest group size the facet levels were built with after being raised
+    /// to respect the maximum number of facet levels.
+    facet_effective_group_size: Arc<AtomicUsize>,
+    /// The method used to update the facet levels of each field, by field name and facet type.
+    facet_update_decisions: Arc<Mutex<Vec<(String, FacetType, FacetUpdateDecision)>>>,
+    /// The number of word prefixes whose documents were recomputed.
+    recomputed_word_prefixes: Arc<AtomicUsize>,
+    /// The number of word prefixes removed from the prefix databases.
+    deleted_word_prefixes: Arc<AtomicUsize>,
 }
 
 #[derive(Default)]
@@ -98,6 +112,67 @@ impl Progress {
         durations.drain(..).map(|(name, duration)| (name, format!("{duration:.2?}"))).collect()
     }
 
+    /// Registers the size of a buffer used to build the facet databases, only the
+    /// largest one is kept.
+    pub(crate) fn record_facet_indexing_memory(&self, bytes: usize) {
+        self.facet_indexing_memory_peak.fetch_max(bytes, Ordering::Relaxed);
+    }
+
+    /// Returns the largest number of bytes buffered at once while building the facet
+    /// levels and FSTs, or zero if no facet structure was built.
+    pub fn facet_indexing_memory_peak(&self) -> usize {
+        self.facet_indexing_memory_peak.load(Ordering::Relaxed)
+    }
+
+    /// Registers a group size raised to respect the maximum number of facet levels,
+    /// only the largest one is kept.
+    pub(crate) fn record_facet_effective_group_size(&self, group_size: u8) {
+        self.facet_effective_group_size.fetch_max(group_size as usize, Ordering::Relaxed);
+    }
+
+    /// Returns the largest group size the facet levels were built with after being raised
+    /// to respect the maximum number of facet levels, or `None` if no group size was raised.
+    pub fn facet_effective_group_size(&self) -> Option<u8> {
+        match self.facet_effective_group_size.load(Ordering::Relaxed) {
+            0 => None,
+            group_size => Some(group_size as u8),
+        }
+    }
+
+    /// Registers the method used to update the facet levels of a field.
+    pub(crate) fn record_facet_update_decision(
+        &self,
+        field_name: &str,
+        facet_type: FacetType,
+        decision: FacetUpdateDecision,
+    ) {
+        let mut decisions = self.facet_update_decisions.lock().unwrap_or_else(|p| p.into_inner());
+        decisions.push((field_name.to_string(), facet_type, decision));
+    }
+
+    /// Returns the method used to update the facet levels of each field, by field name and facet type.
+    pub fn facet_update_decisions(&self) -> Vec<(String, FacetType, FacetUpdateDecision)> {
+        self.facet_update_decisions.lock().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+
+    /// Registers the number of word prefixes recomputed and deleted by the indexing operation.
+    pub(crate) fn record_word_prefix_changes(&self, recomputed: usize, deleted: usize) {
+        self.recomputed_word_prefixes.fetch_add(recomputed, Ordering::Relaxed);
+        self.deleted_word_prefixes.fetch_add(deleted, Ordering::Relaxed);
+    }
+
+    /// Returns the number of word prefixes recomputed and deleted, in this order,
+    /// or `None` if no word prefix changed.
+    pub fn word_prefix_changes(&self) -> Option<(usize, usize)> {
+        match (
+            self.recomputed_word_prefixes.load(Ordering::Relaxed),
+            self.deleted_word_prefixes.load(Ordering::Relaxed),
+        ) {
+            (0, 0) => None,
+            changes => Some(changes),
+        }
+    }
+
     // TODO: ideally we should expose the progress in a way that let arroy use it directly
     pub(crate) fn update_progress_from_arroy(&self, progress: arroy::WriterProgress) {
         self.update_progress(progress.main);
@@ -212,6 +287,7 @@ macro_rules! make_atomic_progress {
 
 make_atomic_progress!(Document alias AtomicDocumentStep => "document");
 make_atomic_progress!(Payload alias AtomicPayloadStep => "payload");
+make_atomic_progress!(FacetSearchField alias AtomicFacetSearchFieldStep => "building facet search structures");
 
 make_enum_progress! {
     pub enum MergingWordCache {
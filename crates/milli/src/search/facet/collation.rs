@@ -0,0 +1,192 @@
+use std::str::FromStr;
+
+use charabia::normalizer::NormalizerOption;
+use charabia::{Normalize, StrDetection, Token};
+
+/// The alphabetical order of the facet values following the conventions of a language.
+///
+/// The letters are compared without their case and diacritics, `é` like `e`, except the
+/// letters the language considers distinct from their base letter, e.g. in Swedish `å`, `ä`
+/// and `ö` come after `z`. The values that are equal once collated keep their code point order.
+///
+/// The collation only orders the values returned to the users, the facet databases and FSTs
+/// are always sorted by code point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FacetCollation {
+    /// The conventions shared by the languages without tailored letters, e.g. English or French.
+    Root,
+    /// Danish and Norwegian, `æ`, `ø` and `å` come after `z`.
+    Danish,
+    /// Spanish, `ñ` comes between `n` and `o`.
+    Spanish,
+    /// Swedish and Finnish, `å`, `ä` and `ö` come after `z`.
+    Swedish,
+}
+
+/// A locale identifier that is not made of a two or three letters language subtag.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("`{0}` is not a valid locale identifier, expected a language code like `sv` or `sv-SE`.")]
+pub struct InvalidFacetCollation(String);
+
+impl FromStr for FacetCollation {
+    type Err = InvalidFacetCollation;
+
+    /// Parses the language subtag of a locale identifier like `sv` or `sv-SE`, the languages
+    /// without tailored letters falling back to the [`FacetCollation::Root`] collation.
+    fn from_str(locale: &str) -> Result<Self, Self::Err> {
+        let language = locale.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        if language == "root" {
+            return Ok(FacetCollation::Root);
+        }
+        if !(2..=3).contains(&language.len()) || !language.bytes().all(|b| b.is_ascii_alphabetic())
+        {
+            return Err(InvalidFacetCollation(locale.to_string()));
+        }
+
+        Ok(match language.as_str() {
+            "da" | "dan" | "nb" | "nob" | "nn" | "nno" | "no" | "nor" => FacetCollation::Danish,
+            "es" | "spa" => FacetCollation::Spanish,
+            "sv" | "swe" | "fi" | "fin" => FacetCollation::Swedish,
+            _ => FacetCollation::Root,
+        })
+    }
+}
+
+impl FacetCollation {
+    /// The letters sorted apart from their base letter, with the letter they directly follow.
+    ///
+    /// The letters following the same one are sorted in the order of the slice.
+    fn tailoring(&self) -> &'static [(char, char)] {
+        match self {
+            FacetCollation::Root => &[],
+            FacetCollation::Danish => &[('æ', 'z'), ('ä', 'z'), ('ø', 'z'), ('ö', 'z'), ('å', 'z')],
+            FacetCollation::Spanish => &[('ñ', 'n')],
+            FacetCollation::Swedish => {
+                &[('å', 'z'), ('ä', 'z'), ('æ', 'z'), ('ö', 'z'), ('ø', 'z')]
+            }
+        }
+    }
+
+    /// Returns a key ordering the strings following this collation.
+    pub fn sort_key(&self, value: &str) -> Vec<u64> {
+        let tailoring = self.tailoring();
+        let mut key = Vec::with_capacity(value.len());
+        let mut run = String::new();
+        for c in value.chars().flat_map(char::to_lowercase) {
+            match tailoring.iter().position(|&(tailored, _)| tailored == c) {
+                Some(position) => {
+                    push_normalized_weights(&run, &mut key);
+                    run.clear();
+                    // the tailored letters sort between the letter they follow and the next one
+                    let (_, follows) = tailoring[position];
+                    let rank = tailoring[..=position]
+                        .iter()
+                        .filter(|(_, other)| *other == follows)
+                        .count();
+                    key.push(letter_weight(follows) + rank as u64);
+                }
+                None => run.push(c),
+            }
+        }
+        push_normalized_weights(&run, &mut key);
+        key
+    }
+}
+
+/// Leaves room after each letter for the tailored letters following it.
+fn letter_weight(c: char) -> u64 {
+    (c as u64) << 8
+}
+
+/// Pushes the weights of the letters of the string once lowercased and without diacritics.
+fn push_normalized_weights(s: &str, key: &mut Vec<u64>) {
+    if s.is_empty() {
+        return;
+    }
+    if s.is_ascii() {
+        key.extend(s.chars().map(letter_weight));
+        return;
+    }
+
+    let options = NormalizerOption { lossy: true, ..Default::default() };
+    let mut detection = StrDetection::new(s, None);
+    let token = Token {
+        lemma: std::borrow::Cow::Borrowed(s),
+        script: detection.script(),
+        ..Default::default()
+    };
+    key.extend(token.normalize(&options).lemma.chars().map(letter_weight));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FacetCollation;
+
+    fn collate(collation: FacetCollation, values: &[&str]) -> Vec<String> {
+        let mut values: Vec<_> = values.iter().map(|s| s.to_string()).collect();
+        values.sort_by_cached_key(|value| (collation.sort_key(value), value.clone()));
+        values
+    }
+
+    #[test]
+    fn parse_locales() {
+        assert_eq!("sv".parse(), Ok(FacetCollation::Swedish));
+        assert_eq!("sv-SE".parse(), Ok(FacetCollation::Swedish));
+        assert_eq!("fi_FI".parse(), Ok(FacetCollation::Swedish));
+        assert_eq!("nb".parse(), Ok(FacetCollation::Danish));
+        assert_eq!("ES".parse(), Ok(FacetCollation::Spanish));
+        assert_eq!("en-US".parse(), Ok(FacetCollation::Root));
+        assert_eq!("root".parse(), Ok(FacetCollation::Root));
+        assert!("".parse::<FacetCollation>().is_err());
+        assert!("swedish".parse::<FacetCollation>().is_err());
+        assert!("s1".parse::<FacetCollation>().is_err());
+    }
+
+    #[test]
+    fn tailored_letters() {
+        let values = ["Örjan", "zebra", "Åsa", "anna", "Ärla", "Émile", "Ola"];
+        insta::assert_debug_snapshot!(collate(FacetCollation::Root, &values), @r###"
+        [
+            "anna",
+            "Ärla",
+            "Åsa",
+            "Émile",
+            "Ola",
+            "Örjan",
+            "zebra",
+        ]
+        "###);
+        insta::assert_debug_snapshot!(collate(FacetCollation::Swedish, &values), @r###"
+        [
+            "anna",
+            "Émile",
+            "Ola",
+            "zebra",
+            "Åsa",
+            "Ärla",
+            "Örjan",
+        ]
+        "###);
+        insta::assert_debug_snapshot!(collate(FacetCollation::Danish, &values), @r###"
+        [
+            "anna",
+            "Émile",
+            "Ola",
+            "zebra",
+            "Ärla",
+            "Örjan",
+            "Åsa",
+        ]
+        "###);
+
+        let values = ["ñu", "nube", "oso", "nada"];
+        insta::assert_debug_snapshot!(collate(FacetCollation::Spanish, &values), @r###"
+        [
+            "nada",
+            "nube",
+            "ñu",
+            "oso",
+        ]
+        "###);
+    }
+}
@@ -0,0 +1,504 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::iter;
+use std::path::Path;
+
+use fst::{IntoStreamer, Streamer};
+use heed::{BytesDecode, BytesEncode, RoTxn};
+use indexmap::IndexMap;
+use memmap2::Mmap;
+use roaring::RoaringBitmap;
+
+use super::facet_distribution::check_filterable_fields;
+use super::{FacetValuesWeights, OrderBy};
+use crate::error::{SerializationError, UserError};
+use crate::heed_codec::facet::{
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, OrderedF64Codec,
+};
+use crate::heed_codec::BytesRefCodec;
+use crate::{normalize_facet, DocumentId, Error, Index, Result};
+
+const MAGIC: &[u8; 4] = b"MCFI";
+const VERSION: u8 = 1;
+/// The magic bytes, the version and the length of the FST.
+const HEADER_LEN: usize = MAGIC.len() + 1 + 8;
+
+/// The FST keys are prefixed by the kind of the facet value, the numbers being returned first.
+const NUMBER: u8 = 0;
+const STRING: u8 = 1;
+
+/// Builds the [`CompactFacets`] of a field from the facet databases of an index.
+pub struct CompactFacetsBuilder<'a> {
+    index: &'a Index,
+    rtxn: &'a RoTxn<'a>,
+    field: String,
+}
+
+impl<'a> CompactFacetsBuilder<'a> {
+    pub fn new(index: &'a Index, rtxn: &'a RoTxn<'a>, field: impl Into<String>) -> Self {
+        CompactFacetsBuilder { index, rtxn, field: field.into() }
+    }
+
+    /// Writes the compact representation of the facet values of the field.
+    ///
+    /// Only the level 0 of the facet trees is kept: an FST maps every facet value to its
+    /// documents and, for the strings, to the original values the normalized value comes from.
+    pub fn build<W: Write>(&self, mut writer: W) -> Result<()> {
+        let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
+        check_filterable_fields(iter::once(&self.field), &filterable_attributes_rules)?;
+
+        let mut builder = fst::MapBuilder::memory();
+        let mut data = Vec::new();
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        if let Some(field_id) = fields_ids_map.id(&self.field) {
+            let prefix = FacetGroupKey { field_id, level: 0, left_bound: &[][..] };
+            let mut key = Vec::new();
+
+            let numbers_db = self
+                .index
+                .facet_id_f64_docids
+                .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+            for result in numbers_db.prefix_iter(self.rtxn, &prefix)? {
+                let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+                key.clear();
+                key.push(NUMBER);
+                key.extend_from_slice(left_bound);
+                builder.insert(&key, data.len() as u64)?;
+                write_entry(&mut data, &bitmap, &BTreeMap::new())?;
+            }
+
+            let strings_db = self
+                .index
+                .facet_id_string_docids
+                .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+            for result in strings_db.prefix_iter(self.rtxn, &prefix)? {
+                let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+                let normalized = std::str::from_utf8(left_bound)?;
+                let mut originals = BTreeMap::new();
+                for docid in &bitmap {
+                    let key = (field_id, docid, normalized);
+                    let original = self.index.field_id_docid_facet_strings.get(self.rtxn, &key)?;
+                    originals
+                        .entry(original.unwrap_or(normalized))
+                        .or_insert_with(RoaringBitmap::new)
+                        .insert(docid);
+                }
+                key.clear();
+                key.push(STRING);
+                key.extend_from_slice(left_bound);
+                builder.insert(&key, data.len() as u64)?;
+                write_entry(&mut data, &bitmap, &originals)?;
+            }
+        }
+
+        let fst = builder.into_inner()?;
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(fst.len() as u64).to_le_bytes())?;
+        writer.write_all(&fst)?;
+        writer.write_all(&data)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A compact and read-only representation of the facet values of a field, for the nodes that
+/// only answer queries and never index.
+///
+/// It is built once with a [`CompactFacetsBuilder`] and can be memory-mapped with
+/// [`CompactFacets::open`]. The functions return the same values as the [`FacetDistribution`]
+/// of the index it was built from, except for the facet values with the same count that
+/// are always returned in lexicographic order when ordered by count.
+///
+/// [`FacetDistribution`]: super::FacetDistribution
+pub struct CompactFacets<D = Mmap> {
+    bytes: D,
+    fst_end: usize,
+}
+
+impl CompactFacets<Mmap> {
+    /// Memory-maps the compact facets written at the given path.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        // Safe because the file is never modified once built.
+        let mmap = unsafe { Mmap::map(&file)? };
+        CompactFacets::new(mmap)
+    }
+}
+
+impl<D: AsRef<[u8]>> CompactFacets<D> {
+    /// Loads the compact facets from the bytes written by a [`CompactFacetsBuilder`].
+    pub fn new(bytes: D) -> Result<Self> {
+        let slice = bytes.as_ref();
+        let Some((header, _)) = slice.split_first_chunk::<HEADER_LEN>() else {
+            return Err(corrupted());
+        };
+        let (magic, header) = header.split_at(MAGIC.len());
+        let (version, fst_len) = header.split_at(1);
+        if magic != MAGIC || version[0] != VERSION {
+            return Err(corrupted());
+        }
+
+        let fst_len = u64::from_le_bytes(fst_len.try_into().unwrap()) as usize;
+        let fst_end = HEADER_LEN
+            .checked_add(fst_len)
+            .filter(|end| *end <= slice.len())
+            .ok_or_else(corrupted)?;
+        fst::Map::new(&slice[HEADER_LEN..fst_end])?;
+
+        Ok(CompactFacets { bytes, fst_end })
+    }
+
+    /// Returns the facet values of the candidates and their counts, mirroring
+    /// [`FacetDistribution::execute`](super::FacetDistribution::execute).
+    ///
+    /// All the documents are the candidates when none are given.
+    ///
+    /// Only the [`OrderBy::Lexicographic`], [`OrderBy::Count`] and [`OrderBy::Weighted`] orders
+    /// are supported: the recency, the weight and the ranking scores of the values are not part of
+    /// the compact representation, the other orders return an error.
+    pub fn distribution(
+        &self,
+        candidates: Option<&RoaringBitmap>,
+        order_by: OrderBy,
+        max_values: usize,
+        min_count: u64,
+    ) -> Result<IndexMap<String, u64>> {
+        let mut numbers = self.counted_values(NUMBER, candidates, min_count)?;
+        let mut strings = self.counted_values(STRING, candidates, min_count)?;
+
+        match order_by {
+            OrderBy::Lexicographic => (),
+            OrderBy::Count => {
+                // the sort is stable, values with the same count stay in lexicographic order
+                numbers.sort_by(|(_, left), (_, right)| right.cmp(left));
+                strings.sort_by(|(_, left), (_, right)| right.cmp(left));
+            }
+            OrderBy::Weighted(weights) => {
+                return Ok(weighted(numbers, strings, weights, max_values));
+            }
+            OrderBy::Recency | OrderBy::Weight | OrderBy::ScoreSum => {
+                let order = order_by.to_string();
+                return Err(UserError::UnsupportedCompactFacetsOrder { order }.into());
+            }
+        }
+
+        let mut distribution = IndexMap::new();
+        for values in [numbers, strings] {
+            let remaining = max_values.saturating_sub(distribution.len());
+            distribution.extend(values.into_iter().take(remaining));
+        }
+        Ok(distribution)
+    }
+
+    /// Returns the minimum and maximum numbers of the candidates, mirroring
+    /// [`FacetDistribution::compute_stats`](super::FacetDistribution::compute_stats).
+    pub fn stats(&self, candidates: Option<&RoaringBitmap>) -> Result<Option<(f64, f64)>> {
+        let map = self.map()?;
+        let mut stream = map.range().ge([NUMBER]).lt([NUMBER + 1]).into_stream();
+        let mut stats = None;
+        while let Some((key, offset)) = stream.next() {
+            let entry = self.entry(offset)?;
+            if entry.count(candidates) != 0 {
+                let number = decode_number(&key[1..])?;
+                // the numbers are iterated in ascending order
+                stats = Some((stats.map_or(number, |(min, _)| min), number));
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Counts the candidates of the given values, mirroring
+    /// [`FacetDistribution::compute_values`](super::FacetDistribution::compute_values).
+    pub fn values(
+        &self,
+        values: &[String],
+        candidates: Option<&RoaringBitmap>,
+    ) -> Result<IndexMap<String, u64>> {
+        let map = self.map()?;
+        let mut counts = IndexMap::with_capacity(values.len());
+        for value in values {
+            let mut docids = RoaringBitmap::new();
+            let mut key = vec![STRING];
+            key.extend_from_slice(normalize_facet(value).as_bytes());
+            if let Some(offset) = map.get(&key) {
+                docids |= self.entry(offset)?.docids;
+            }
+            if let Some(number) = value.parse::<f64>().ok().filter(|n| n.is_finite()) {
+                let number =
+                    OrderedF64Codec::bytes_encode(&number).map_err(heed::Error::Encoding)?;
+                let mut key = vec![NUMBER];
+                key.extend_from_slice(&number);
+                if let Some(offset) = map.get(&key) {
+                    docids |= self.entry(offset)?.docids;
+                }
+            }
+            let count = match candidates {
+                Some(candidates) => candidates.intersection_len(&docids),
+                None => docids.len(),
+            };
+            counts.insert(value.clone(), count);
+        }
+        Ok(counts)
+    }
+
+    /// The values of the given kind in lexicographic order with their number of candidates,
+    /// the values without candidates or below `min_count` are skipped.
+    fn counted_values(
+        &self,
+        kind: u8,
+        candidates: Option<&RoaringBitmap>,
+        min_count: u64,
+    ) -> Result<Vec<(String, u64)>> {
+        let map = self.map()?;
+        let mut stream = map.range().ge([kind]).lt([kind + 1]).into_stream();
+        let mut values = Vec::new();
+        while let Some((key, offset)) = stream.next() {
+            let entry = self.entry(offset)?;
+            let docids = match candidates {
+                Some(candidates) => &entry.docids & candidates,
+                None => entry.docids.clone(),
+            };
+            let count = docids.len();
+            if count == 0 || count < min_count {
+                continue;
+            }
+
+            let value = match kind {
+                NUMBER => decode_number(&key[1..])?.to_string(),
+                // like the distribution, the original value of the first candidate is returned
+                _ => entry.original(docids.min().unwrap()).ok_or_else(corrupted)?.to_string(),
+            };
+            values.push((value, count));
+        }
+        Ok(values)
+    }
+
+    fn map(&self) -> Result<fst::Map<&[u8]>> {
+        Ok(fst::Map::new(&self.bytes.as_ref()[HEADER_LEN..self.fst_end])?)
+    }
+
+    fn entry(&self, offset: u64) -> Result<Entry<'_>> {
+        let data = &self.bytes.as_ref()[self.fst_end..];
+        let bytes = usize::try_from(offset).ok().and_then(|offset| data.get(offset..));
+        Entry::decode(bytes.ok_or_else(corrupted)?)
+    }
+}
+
+/// The documents of a facet value and, for the strings, the original values they contain.
+struct Entry<'b> {
+    docids: RoaringBitmap,
+    /// The documents of an original value are omitted when it is the only one.
+    originals: Vec<(&'b str, Option<RoaringBitmap>)>,
+}
+
+impl<'b> Entry<'b> {
+    fn decode(bytes: &'b [u8]) -> Result<Self> {
+        let mut reader = Reader(bytes);
+        let docids = reader.bitmap()?;
+        let len = reader.u32()?;
+        let mut originals = Vec::new();
+        for _ in 0..len {
+            let original = std::str::from_utf8(reader.bytes()?)?;
+            let docids = if len > 1 { Some(reader.bitmap()?) } else { None };
+            originals.push((original, docids));
+        }
+        Ok(Entry { docids, originals })
+    }
+
+    fn count(&self, candidates: Option<&RoaringBitmap>) -> u64 {
+        match candidates {
+            Some(candidates) => candidates.intersection_len(&self.docids),
+            None => self.docids.len(),
+        }
+    }
+
+    fn original(&self, docid: DocumentId) -> Option<&'b str> {
+        self.originals
+            .iter()
+            .find(|(_, docids)| docids.as_ref().is_none_or(|docids| docids.contains(docid)))
+            .map(|(original, _)| *original)
+    }
+}
+
+fn write_entry(
+    data: &mut Vec<u8>,
+    docids: &RoaringBitmap,
+    originals: &BTreeMap<&str, RoaringBitmap>,
+) -> io::Result<()> {
+    write_bitmap(data, docids)?;
+    data.extend_from_slice(&(originals.len() as u32).to_le_bytes());
+    for (original, docids) in originals {
+        write_bytes(data, original.as_bytes());
+        if originals.len() > 1 {
+            write_bitmap(data, docids)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_bitmap(data: &mut Vec<u8>, bitmap: &RoaringBitmap) -> io::Result<()> {
+    data.extend_from_slice(&(bitmap.serialized_size() as u32).to_le_bytes());
+    bitmap.serialize_into(data)
+}
+
+fn write_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+    data.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    data.extend_from_slice(bytes);
+}
+
+struct Reader<'b>(&'b [u8]);
+
+impl<'b> Reader<'b> {
+    fn u32(&mut self) -> Result<u32> {
+        let (head, tail) = self.0.split_first_chunk::<4>().ok_or_else(corrupted)?;
+        self.0 = tail;
+        Ok(u32::from_le_bytes(*head))
+    }
+
+    fn bytes(&mut self) -> Result<&'b [u8]> {
+        let len = self.u32()? as usize;
+        if self.0.len() < len {
+            return Err(corrupted());
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn bitmap(&mut self) -> Result<RoaringBitmap> {
+        Ok(RoaringBitmap::deserialize_from(self.bytes()?)?)
+    }
+}
+
+/// Sorts the numbers and strings together, like the distribution does for the weighted order.
+fn weighted(
+    numbers: Vec<(String, u64)>,
+    strings: Vec<(String, u64)>,
+    weights: FacetValuesWeights,
+    max_values: usize,
+) -> IndexMap<String, u64> {
+    let all_values: IndexMap<_, _> = numbers.into_iter().chain(strings).collect();
+    let mut values: Vec<_> = all_values.into_iter().collect();
+    weights.sort(&mut values, |(_, count)| *count);
+    values.truncate(max_values);
+    values.into_iter().collect()
+}
+
+fn decode_number(bytes: &[u8]) -> Result<f64> {
+    Ok(OrderedF64Codec::bytes_decode(bytes).map_err(heed::Error::Decoding)?)
+}
+
+fn corrupted() -> Error {
+    SerializationError::Decoding { db_name: Some("compact facets") }.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use big_s::S;
+    use roaring::RoaringBitmap;
+
+    use super::{CompactFacets, CompactFacetsBuilder};
+    use crate::error::UserError;
+    use crate::index::tests::TempIndex;
+    use crate::{Error, FacetDistribution, FacetValuesWeights, FilterableAttributesRule, OrderBy};
+
+    #[test]
+    fn compact_facets_match_the_facet_databases() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field(S("genre")),
+                    FilterableAttributesRule::Field(S("year")),
+                ])
+            })
+            .unwrap();
+
+        let mut documents = Vec::new();
+        for id in 0..40u32 {
+            // the values have distinct counts, with or without the subset of candidates,
+            // as the ties are not ordered like in the facet databases when sorted by count
+            let (genre, year) = match id % 10 {
+                0 => ("Drama", 2001.0),
+                1 => ("Drama", 2002.5),
+                2 => ("drama", 2002.5),
+                3 => ("drama", 2003.0),
+                4 | 5 => ("Action", 2003.0),
+                6 => ("Action", 2004.0),
+                7 | 8 => ("Comedy", 2004.0),
+                _ => ("Romance", 2004.0),
+            };
+            let document = serde_json::json!({ "id": id, "genre": genre, "year": year });
+            documents.push(document.as_object().unwrap().clone());
+        }
+        index.add_documents(crate::documents::mmap_from_objects(documents)).unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let mut compact_facets = Vec::new();
+        for field in ["genre", "year"] {
+            let path = dir.path().join(field);
+            let file = std::fs::File::create(&path).unwrap();
+            CompactFacetsBuilder::new(&index, &rtxn, field).build(file).unwrap();
+            compact_facets.push((field, CompactFacets::open(&path).unwrap()));
+        }
+
+        let subset: RoaringBitmap = (0..40).filter(|id| id % 3 != 0).collect();
+        let weighted = OrderBy::Weighted(FacetValuesWeights { count: 1.0, alpha: 0.5 });
+        for (field, compact) in &compact_facets {
+            for candidates in [None, Some(&subset)] {
+                for order_by in [OrderBy::Lexicographic, OrderBy::Count, weighted] {
+                    for (max_values, min_count) in [(100, 0), (3, 0), (100, 5)] {
+                        let mut distribution = FacetDistribution::new(&rtxn, &index);
+                        distribution
+                            .facets(iter::once((field, order_by)))
+                            .max_values_per_facet(max_values)
+                            .min_count(min_count);
+                        if let Some(candidates) = candidates {
+                            distribution.candidates(candidates.clone());
+                        }
+                        let expected = distribution.execute().unwrap().remove(*field).unwrap();
+                        let actual = compact
+                            .distribution(candidates, order_by, max_values, min_count)
+                            .unwrap();
+                        assert_eq!(
+                            actual.into_iter().collect::<Vec<_>>(),
+                            expected.into_iter().collect::<Vec<_>>(),
+                            "{field} {order_by:?} {max_values} {min_count} {candidates:?}"
+                        );
+                    }
+                }
+            }
+
+            let mut distribution = FacetDistribution::new(&rtxn, &index);
+            distribution.facets(iter::once((field, OrderBy::default()))).candidates(subset.clone());
+            let expected = distribution.compute_stats().unwrap().remove(*field);
+            assert_eq!(compact.stats(Some(&subset)).unwrap(), expected);
+
+            let values = vec![S("DRAMA"), S("Comedy"), S("2001"), S("2004.0"), S("Western")];
+            let expected = FacetDistribution::new(&rtxn, &index)
+                .candidates(subset.clone())
+                .compute_values(&[(field.to_string(), values.clone())].into())
+                .unwrap()
+                .remove(*field)
+                .unwrap();
+            assert_eq!(compact.values(&values, Some(&subset)).unwrap(), expected);
+
+            for order_by in [OrderBy::Recency, OrderBy::Weight, OrderBy::ScoreSum] {
+                let error = compact.distribution(None, order_by, 100, 0).unwrap_err();
+                assert!(
+                    matches!(
+                        error,
+                        Error::UserError(UserError::UnsupportedCompactFacetsOrder { .. })
+                    ),
+                    "{field} {order_by:?}: {error}"
+                );
+            }
+        }
+    }
+}
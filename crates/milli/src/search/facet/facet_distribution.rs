@@ -1,25 +1,37 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Display;
-use std::ops::ControlFlow;
+use std::num::NonZeroUsize;
+use std::ops::{Bound, ControlFlow};
 use std::{fmt, mem};
 
 use heed::types::Bytes;
 use heed::BytesDecode;
 use indexmap::IndexMap;
+use ordered_float::OrderedFloat;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
 
 use crate::attribute_patterns::match_field_legacy;
+use crate::facet::values_recency::FacetValuesRecency;
+use crate::facet::values_weight::FacetValuesWeight;
 use crate::facet::FacetType;
 use crate::filterable_attributes_rules::{filtered_matching_patterns, matching_features};
 use crate::heed_codec::facet::{
-    FacetGroupKeyCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FieldDocIdFacetF64Codec,
+    FieldDocIdFacetStringCodec, OrderedF64Codec,
 };
 use crate::heed_codec::{BytesRefCodec, StrRefCodec};
 use crate::search::facet::facet_distribution_iter::{
     count_iterate_over_facet_distribution, lexicographically_iterate_over_facet_distribution,
 };
-use crate::{Error, FieldId, FilterableAttributesRule, Index, PatternMatch, Result, UserError};
+use crate::search::facet::facet_range_search::find_docids_of_facet_within_bounds;
+use crate::{
+    distance_between_two_points, DocumentId, Error, FieldId, FilterableAttributesRule, Index,
+    PatternMatch, Result, UserError,
+};
 
 /// The default number of values by facets that will
 /// be fetched from the key-value store.
@@ -37,6 +49,14 @@ pub enum OrderBy {
     Lexicographic,
     /// Or by number of docids in common?
     Count,
+    /// Or by a weighted combination of both?
+    Weighted(FacetValuesWeights),
+    /// Or by the last time they were associated with a document?
+    Recency,
+    /// Or by the sum of the ranking scores of the documents returned by a search?
+    ScoreSum,
+    /// Or by the sort weight read from the weight field of their documents?
+    Weight,
 }
 
 impl Display for OrderBy {
@@ -44,15 +64,89 @@ impl Display for OrderBy {
         match self {
             OrderBy::Lexicographic => f.write_str("alphabetically"),
             OrderBy::Count => f.write_str("by count"),
+            OrderBy::Weighted(_) => f.write_str("by weighted count and alphabetical rank"),
+            OrderBy::Recency => f.write_str("by recency"),
+            OrderBy::ScoreSum => f.write_str("by sum of the ranking scores"),
+            OrderBy::Weight => f.write_str("by weight"),
         }
     }
 }
 
+/// The weights of the signals combined to sort facet values with [`OrderBy::Weighted`].
+///
+/// Each signal is normalized to `[0, 1]` before being weighted: the count is divided by the
+/// highest count and the alphabetical rank goes from 1 for the first value to 0 for the last one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FacetValuesWeights {
+    pub count: f64,
+    pub alpha: f64,
+}
+
+// The weights are validated to be finite numbers when set.
+impl Eq for FacetValuesWeights {}
+
+impl FacetValuesWeights {
+    /// Returns the weighted score of each value, given the counts of the values in alphabetical order.
+    pub fn scores(&self, counts: &[u64]) -> Vec<f64> {
+        let max_count = counts.iter().copied().max().unwrap_or(0);
+        let last_rank = counts.len().saturating_sub(1);
+        counts
+            .iter()
+            .enumerate()
+            .map(|(rank, &count)| {
+                let count = if max_count == 0 { 0.0 } else { count as f64 / max_count as f64 };
+                let alpha = if last_rank == 0 { 1.0 } else { 1.0 - rank as f64 / last_rank as f64 };
+                self.count * count + self.alpha * alpha
+            })
+            .collect()
+    }
+
+    /// Sorts the values, given in alphabetical order, by decreasing weighted score.
+    pub fn sort<T>(&self, values: &mut Vec<T>, count: impl Fn(&T) -> u64) {
+        let counts: Vec<_> = values.iter().map(count).collect();
+        let mut scored: Vec<_> = self.scores(&counts).into_iter().zip(values.drain(..)).collect();
+        // the sort is stable, values with the same score stay in alphabetical order
+        scored.sort_by(|(left, _), (right, _)| right.total_cmp(left));
+        values.extend(scored.into_iter().map(|(_, value)| value));
+    }
+}
+
+/// A bucket of the distribution computed by [`FacetDistribution::compute_ranges`].
+///
+/// The `from` bound is included and the `to` bound excluded, a missing bound is open-ended.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetRangeBucket {
+    pub from: Option<f64>,
+    pub to: Option<f64>,
+    pub count: u64,
+}
+
+/// The distribution computed by [`FacetDistribution::compute_geo_distances`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoDistanceDistribution {
+    /// The buckets of distances from the center point, in meters.
+    pub buckets: Vec<FacetRangeBucket>,
+    /// The number of candidates that do not have geo coordinates.
+    pub without_geo: u64,
+}
+
+/// Computes the number of documents associated with the facet values of the filterable fields.
+///
+/// There is no soft deletion: a document deletion removes the document from the facet databases
+/// in the same write transaction, so the counts exclude it as soon as the deletion is committed.
 pub struct FacetDistribution<'a> {
     facets: Option<HashMap<String, OrderBy>>,
     candidates: Option<RoaringBitmap>,
     max_values_per_facet: usize,
+    min_count: u64,
+    level: Option<u8>,
+    sample: Option<(NonZeroUsize, u64)>,
+    numeric_precision: Option<u8>,
     default_order_by: OrderBy,
+    document_scores: Option<HashMap<DocumentId, f64>>,
+    boolean_facets: HashSet<String>,
     rtxn: &'a heed::RoTxn<'a>,
     index: &'a Index,
 }
@@ -63,7 +157,13 @@ impl<'a> FacetDistribution<'a> {
             facets: None,
             candidates: None,
             max_values_per_facet: DEFAULT_VALUES_PER_FACET,
+            min_count: 0,
+            level: None,
+            sample: None,
+            numeric_precision: None,
             default_order_by: OrderBy::default(),
+            document_scores: None,
+            boolean_facets: HashSet::new(),
             rtxn,
             index,
         }
@@ -87,11 +187,78 @@ impl<'a> FacetDistribution<'a> {
         self
     }
 
+    /// Excludes the facet values matching fewer than `min_count` candidates.
+    ///
+    /// The values are filtered before applying the `max_values_per_facet` limit.
+    pub fn min_count(&mut self, min_count: u64) -> &mut Self {
+        self.min_count = min_count;
+        self
+    }
+
+    /// Returns the nodes of the given level of the number facet trees instead of the number facet values.
+    ///
+    /// Each node is a bucket named after its left bound. A bucket contains the values greater than or
+    /// equal to its left bound and lower than the left bound of the next bucket of the same level,
+    /// the last bucket contains all the remaining values. The level 0 returns the facet values themselves.
+    pub fn level(&mut self, level: u8) -> &mut Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Counts the facet values of a random sample of `size` candidates instead of all of them
+    /// and scales the counts up by the ratio of the number of candidates to the sample size.
+    ///
+    /// The work is bounded by the sample size whatever the number of candidates, at the cost of
+    /// approximate counts. The values associated with a small part of the candidates vary the most:
+    /// the count of a value associated with a proportion `p` of the candidates has a relative
+    /// standard error of about `sqrt((1 - p) / (p * size))`, e.g. 10% for a value of 1% of the
+    /// candidates with a sample of 10 000 documents, and rare values may be missing.
+    ///
+    /// The same seed samples the same documents out of the same candidates, so the counts are
+    /// stable across requests. The candidates are not sampled when there are no more than `size`.
+    pub fn sample(&mut self, size: NonZeroUsize, seed: u64) -> &mut Self {
+        self.sample = Some((size, seed));
+        self
+    }
+
+    /// Rounds the number facet values to `decimals` decimals before counting them, the values
+    /// differing only by floating point noise, e.g. `19.99` and `19.990000001`, being counted
+    /// in a single value. A document having several of the merged values is only counted once.
+    ///
+    /// Only the returned values are rounded, the stored facet values and the filters are not
+    /// affected. The precision is ignored when the nodes of a [`Self::level`] are returned.
+    pub fn numeric_precision(&mut self, decimals: u8) -> &mut Self {
+        self.numeric_precision = Some(decimals);
+        self
+    }
+
     pub fn default_order_by(&mut self, order_by: OrderBy) -> &mut Self {
         self.default_order_by = order_by;
         self
     }
 
+    /// The ranking scores of the documents returned by the search the distribution is computed for,
+    /// used to sort the values of the facets ordered by [`OrderBy::ScoreSum`].
+    ///
+    /// The facets ordered by [`OrderBy::ScoreSum`] are ordered by [`OrderBy::Count`] when no scores are given.
+    pub fn document_scores(&mut self, scores: HashMap<DocumentId, f64>) -> &mut Self {
+        self.document_scores = Some(scores);
+        self
+    }
+
+    /// Returns the `true`, `false` and `missing` buckets of these fields, always in this order
+    /// and whatever their count, instead of their facet values.
+    ///
+    /// The booleans are indexed as the `true` and `false` strings, the fields must therefore be
+    /// explicitly declared as boolean. A field having other values is returned as usual.
+    pub fn boolean_facets<I: IntoIterator<Item = A>, A: AsRef<str>>(
+        &mut self,
+        names: I,
+    ) -> &mut Self {
+        self.boolean_facets = names.into_iter().map(|name| name.as_ref().to_string()).collect();
+        self
+    }
+
     pub fn candidates(&mut self, candidates: RoaringBitmap) -> &mut Self {
         self.candidates = Some(candidates);
         self
@@ -129,6 +296,7 @@ impl<'a> FacetDistribution<'a> {
                 distribution.extend(
                     lexicographic_distribution
                         .into_iter()
+                        .filter(|(_, count)| *count >= self.min_count)
                         .take(self.max_values_per_facet.saturating_sub(distribution.len())),
                 );
             }
@@ -159,6 +327,7 @@ impl<'a> FacetDistribution<'a> {
 
                 let iter = normalized_distribution
                     .into_iter()
+                    .filter(|(_, (_, count))| *count >= self.min_count)
                     .take(self.max_values_per_facet.saturating_sub(distribution.len()))
                     .map(|(_normalized, (original, count))| (original.to_string(), count));
                 distribution.extend(iter);
@@ -177,9 +346,23 @@ impl<'a> FacetDistribution<'a> {
         order_by: OrderBy,
         distribution: &mut IndexMap<String, u64>,
     ) -> heed::Result<()> {
+        if let Some(decimals) = self.numeric_precision {
+            return self.rounded_facet_numbers_distribution(
+                field_id,
+                decimals,
+                candidates,
+                order_by,
+                distribution,
+            );
+        }
+
         let search_function = match order_by {
-            OrderBy::Lexicographic => lexicographically_iterate_over_facet_distribution,
-            OrderBy::Count => count_iterate_over_facet_distribution,
+            // the weighted values are fetched in alphabetical order before being sorted
+            OrderBy::Lexicographic | OrderBy::Weighted(_) | OrderBy::Recency | OrderBy::Weight => {
+                lexicographically_iterate_over_facet_distribution
+            }
+            // without scores the values are ordered by count, see `score_sum_facet_values`
+            OrderBy::Count | OrderBy::ScoreSum => count_iterate_over_facet_distribution,
         };
 
         search_function(
@@ -188,6 +371,9 @@ impl<'a> FacetDistribution<'a> {
             field_id,
             candidates,
             |facet_key, nbr_docids, _| {
+                if let Some(flow) = self.skip_below_min_count(nbr_docids, order_by) {
+                    return Ok(flow);
+                }
                 let facet_key = OrderedF64Codec::bytes_decode(facet_key).unwrap();
                 distribution.insert(facet_key.to_string(), nbr_docids);
                 if distribution.len() == self.max_values_per_facet {
@@ -199,6 +385,84 @@ impl<'a> FacetDistribution<'a> {
         )
     }
 
+    /// Counts the number values of the field rounded to `decimals` decimals,
+    /// see [`FacetDistribution::numeric_precision`].
+    fn rounded_facet_numbers_distribution(
+        &self,
+        field_id: FieldId,
+        decimals: u8,
+        candidates: &RoaringBitmap,
+        order_by: OrderBy,
+        distribution: &mut IndexMap<String, u64>,
+    ) -> heed::Result<()> {
+        let db =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: &[][..] };
+
+        // The rounding keeps the order of the values, the merged values follow each other.
+        let mut rounded_values: Vec<(f64, RoaringBitmap)> = Vec::new();
+        for result in db.prefix_iter(self.rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+            let value = OrderedF64Codec::bytes_decode(left_bound).unwrap();
+            let rounded = round_to_decimals(value, decimals);
+            let docids = bitmap & candidates;
+            match rounded_values.last_mut() {
+                Some((last, last_docids)) if *last == rounded => *last_docids |= docids,
+                _ => rounded_values.push((rounded, docids)),
+            }
+        }
+
+        let mut buckets: Vec<_> = rounded_values
+            .into_iter()
+            .map(|(value, docids)| (value.to_string(), docids.len()))
+            .filter(|(_, count)| *count != 0 && *count >= self.min_count)
+            .collect();
+        if matches!(order_by, OrderBy::Count | OrderBy::ScoreSum) {
+            // the sort is stable, values with the same count stay in ascending order
+            buckets.sort_by(|(_, left), (_, right)| right.cmp(left));
+        }
+
+        let remaining = self.max_values_per_facet.saturating_sub(distribution.len());
+        distribution.extend(buckets.into_iter().take(remaining));
+
+        Ok(())
+    }
+
+    /// Returns the nodes of a single level of the number facet tree as buckets,
+    /// see [`FacetDistribution::level`].
+    fn facet_numbers_distribution_from_level(
+        &self,
+        field_id: FieldId,
+        level: u8,
+        candidates: &RoaringBitmap,
+        order_by: OrderBy,
+        distribution: &mut IndexMap<String, u64>,
+    ) -> heed::Result<()> {
+        let db =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let prefix = FacetGroupKey { field_id, level, left_bound: &[][..] };
+
+        let mut buckets = Vec::new();
+        for result in db.prefix_iter(self.rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+            let count = candidates.intersection_len(&bitmap);
+            if count != 0 && count >= self.min_count {
+                let left_bound = OrderedF64Codec::bytes_decode(left_bound).unwrap();
+                buckets.push((left_bound.to_string(), count));
+            }
+        }
+
+        if matches!(order_by, OrderBy::Count | OrderBy::ScoreSum) {
+            // the sort is stable, buckets with the same count stay in ascending order
+            buckets.sort_by(|(_, left), (_, right)| right.cmp(left));
+        }
+
+        let remaining = self.max_values_per_facet.saturating_sub(distribution.len());
+        distribution.extend(buckets.into_iter().take(remaining));
+
+        Ok(())
+    }
+
     fn facet_strings_distribution_from_facet_levels(
         &self,
         field_id: FieldId,
@@ -207,8 +471,12 @@ impl<'a> FacetDistribution<'a> {
         distribution: &mut IndexMap<String, u64>,
     ) -> heed::Result<()> {
         let search_function = match order_by {
-            OrderBy::Lexicographic => lexicographically_iterate_over_facet_distribution,
-            OrderBy::Count => count_iterate_over_facet_distribution,
+            // the weighted values are fetched in alphabetical order before being sorted
+            OrderBy::Lexicographic | OrderBy::Weighted(_) | OrderBy::Recency | OrderBy::Weight => {
+                lexicographically_iterate_over_facet_distribution
+            }
+            // without scores the values are ordered by count, see `score_sum_facet_values`
+            OrderBy::Count | OrderBy::ScoreSum => count_iterate_over_facet_distribution,
         };
 
         search_function(
@@ -217,6 +485,9 @@ impl<'a> FacetDistribution<'a> {
             field_id,
             candidates,
             |facet_key, nbr_docids, any_docid| {
+                if let Some(flow) = self.skip_below_min_count(nbr_docids, order_by) {
+                    return Ok(flow);
+                }
                 let facet_key = StrRefCodec::bytes_decode(facet_key).unwrap();
 
                 let key: (FieldId, _, &str) = (field_id, any_docid, facet_key);
@@ -244,6 +515,261 @@ impl<'a> FacetDistribution<'a> {
         )
     }
 
+    /// Returns how to continue the iteration when a facet value
+    /// doesn't reach the `min_count` threshold, `None` otherwise.
+    fn skip_below_min_count(&self, nbr_docids: u64, order_by: OrderBy) -> Option<ControlFlow<()>> {
+        if nbr_docids >= self.min_count {
+            None
+        } else if matches!(order_by, OrderBy::Count | OrderBy::ScoreSum) {
+            // the next facet values can only have fewer documents
+            Some(ControlFlow::Break(()))
+        } else {
+            Some(ControlFlow::Continue(()))
+        }
+    }
+
+    /// The weighted score of a value depends on all the other values,
+    /// they are all fetched in alphabetical order before being sorted.
+    fn weighted_facet_values(
+        &self,
+        field_id: FieldId,
+        weights: FacetValuesWeights,
+    ) -> heed::Result<IndexMap<String, u64>> {
+        let all_values = FacetDistribution {
+            facets: None,
+            candidates: self.candidates.clone(),
+            max_values_per_facet: usize::MAX,
+            min_count: self.min_count,
+            level: self.level,
+            sample: None,
+            numeric_precision: self.numeric_precision,
+            default_order_by: OrderBy::Lexicographic,
+            document_scores: None,
+            boolean_facets: HashSet::new(),
+            rtxn: self.rtxn,
+            index: self.index,
+        };
+        let values = all_values.facet_values(field_id, OrderBy::Lexicographic)?;
+
+        let mut values: Vec<_> = values.into_iter().collect();
+        weights.sort(&mut values, |(_, count)| *count);
+        values.truncate(self.max_values_per_facet);
+        Ok(values.into_iter().collect())
+    }
+
+    /// Returns the `true`, `false` and `missing` buckets of a field declared with
+    /// [`Self::boolean_facets`], in this order, or `None` when it has other values than booleans.
+    ///
+    /// The `missing` bucket counts the candidates having neither, e.g. because they lack the field.
+    /// The three buckets are always returned, whatever their count, the minimum count and the
+    /// maximum number of values per facet.
+    fn boolean_facet_values(
+        &self,
+        field_id: FieldId,
+    ) -> heed::Result<Option<IndexMap<String, u64>>> {
+        let numbers =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: &[][..] };
+        if numbers.prefix_iter(self.rtxn, &prefix)?.next().is_some() {
+            return Ok(None);
+        }
+
+        let mut true_docids = RoaringBitmap::new();
+        let mut false_docids = RoaringBitmap::new();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: "" };
+        for result in self.index.facet_id_string_docids.prefix_iter(self.rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+            match left_bound {
+                "true" => true_docids = bitmap,
+                "false" => false_docids = bitmap,
+                _ => return Ok(None),
+            }
+        }
+        if true_docids.is_empty() && false_docids.is_empty() {
+            return Ok(None);
+        }
+
+        let universe;
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates,
+            None => {
+                universe = self.index.documents_ids(self.rtxn)?;
+                &universe
+            }
+        };
+
+        // a document with both values, e.g. in an array, is counted in both buckets
+        let missing = candidates - (&true_docids | &false_docids);
+        Ok(Some(IndexMap::from([
+            (String::from("true"), true_docids.intersection_len(candidates)),
+            (String::from("false"), false_docids.intersection_len(candidates)),
+            (String::from("missing"), missing.len()),
+        ])))
+    }
+
+    /// The strings are sorted from the most recently associated with a document to the least
+    /// recently, the numbers have no recorded recency and follow them in ascending order.
+    fn recent_facet_values(&self, field_id: FieldId) -> heed::Result<IndexMap<String, u64>> {
+        let recency = FacetValuesRecency::new(self.index, self.rtxn, field_id)?;
+        // values associated at the same time stay in alphabetical order
+        self.string_facet_values_sorted_by_key(field_id, |normalized| {
+            Reverse(recency.of_normalized(normalized))
+        })
+    }
+
+    /// The strings are sorted by decreasing weight, the ones without a weight after them in
+    /// alphabetical order. The numbers have no weight and follow them in ascending order.
+    fn weighted_by_values_facet_values(
+        &self,
+        field_id: FieldId,
+    ) -> heed::Result<IndexMap<String, u64>> {
+        let weights = FacetValuesWeight::new(self.index, self.rtxn, field_id)?;
+        // `None` is lower than any weight, the reversed order puts it last
+        self.string_facet_values_sorted_by_key(field_id, |normalized| {
+            Reverse(weights.of_normalized(normalized).map(OrderedFloat))
+        })
+    }
+
+    /// Sorts the strings matching the candidates by the given key, keeping the alphabetical
+    /// order of the values with the same key, and appends the numbers in ascending order.
+    fn string_facet_values_sorted_by_key<K: Ord>(
+        &self,
+        field_id: FieldId,
+        sort_key: impl Fn(&str) -> K,
+    ) -> heed::Result<IndexMap<String, u64>> {
+        let universe;
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates,
+            None => {
+                universe = self.index.documents_ids(self.rtxn)?;
+                &universe
+            }
+        };
+
+        let mut strings = Vec::new();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: "" };
+        for result in self.index.facet_id_string_docids.prefix_iter(self.rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+            let docids = bitmap & candidates;
+            let count = docids.len();
+            if count != 0 && count >= self.min_count {
+                strings.push((sort_key(left_bound), left_bound, docids, count));
+            }
+        }
+        // the sort is stable, the values are read in alphabetical order
+        strings.sort_by(|(left, ..), (right, ..)| left.cmp(right));
+
+        let mut distribution = IndexMap::new();
+        for (_, normalized, docids, count) in strings.into_iter().take(self.max_values_per_facet) {
+            let key = (field_id, docids.min().unwrap(), normalized);
+            let original = match self.index.field_id_docid_facet_strings.get(self.rtxn, &key)? {
+                Some(original) => original.to_owned(),
+                None => normalized.to_string(),
+            };
+            distribution.insert(original, count);
+        }
+
+        if distribution.len() < self.max_values_per_facet {
+            self.facet_numbers_distribution_from_facet_levels(
+                field_id,
+                candidates,
+                OrderBy::Lexicographic,
+                &mut distribution,
+            )?;
+        }
+
+        Ok(distribution)
+    }
+
+    /// The values are sorted by the sum of the scores of the ranked documents they are associated with,
+    /// the values with the same sum, e.g. only associated with unranked candidates, by count.
+    /// The number values are ranked on their rounded values when a precision is set.
+    fn score_sum_facet_values(
+        &self,
+        field_id: FieldId,
+        scores: &HashMap<DocumentId, f64>,
+    ) -> heed::Result<IndexMap<String, u64>> {
+        let universe;
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates,
+            None => {
+                universe = self.index.documents_ids(self.rtxn)?;
+                &universe
+            }
+        };
+        let score_sum = |docids: &RoaringBitmap| -> f64 {
+            docids.iter().filter_map(|docid| scores.get(&docid)).sum()
+        };
+
+        let db =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: &[][..] };
+        let mut number_docids: Vec<(f64, RoaringBitmap)> = Vec::new();
+        for result in db.prefix_iter(self.rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+            let mut value = OrderedF64Codec::bytes_decode(left_bound).unwrap();
+            if let Some(decimals) = self.numeric_precision {
+                value = round_to_decimals(value, decimals);
+            }
+            let docids = bitmap & candidates;
+            match number_docids.last_mut() {
+                Some((last, last_docids)) if *last == value => *last_docids |= docids,
+                _ => number_docids.push((value, docids)),
+            }
+        }
+        let mut numbers = Vec::new();
+        for (value, docids) in number_docids {
+            let count = docids.len();
+            if count != 0 && count >= self.min_count {
+                numbers.push((score_sum(&docids), count, value.to_string()));
+            }
+        }
+
+        // the original strings are only fetched for the returned values
+        let mut strings = Vec::new();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: "" };
+        for result in self.index.facet_id_string_docids.prefix_iter(self.rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+            let docids = bitmap & candidates;
+            let count = docids.len();
+            if count != 0 && count >= self.min_count {
+                strings.push((score_sum(&docids), count, left_bound, docids.min().unwrap()));
+            }
+        }
+
+        // the sorts are stable, the values with the same sum and count stay in ascending order
+        numbers.sort_by(|(ls, lc, _), (rs, rc, _)| rs.total_cmp(ls).then(rc.cmp(lc)));
+        strings.sort_by(|(ls, lc, ..), (rs, rc, ..)| rs.total_cmp(ls).then(rc.cmp(lc)));
+
+        let mut ranked: Vec<(f64, u64, String)> = Vec::new();
+        let mut strings = strings.into_iter().peekable();
+        let mut numbers = numbers.into_iter().peekable();
+        while ranked.len() < self.max_values_per_facet {
+            let take_string = match (strings.peek(), numbers.peek()) {
+                // the numbers come first on ties, as in the other orders
+                (Some((ls, lc, ..)), Some((rs, rc, _))) => {
+                    rs.total_cmp(ls).then(rc.cmp(lc)).is_lt()
+                }
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            if take_string {
+                let (score, count, normalized, any_docid) = strings.next().unwrap();
+                let key = (field_id, any_docid, normalized);
+                let original = match self.index.field_id_docid_facet_strings.get(self.rtxn, &key)? {
+                    Some(original) => original.to_owned(),
+                    None => normalized.to_string(),
+                };
+                ranked.push((score, count, original));
+            } else {
+                ranked.push(numbers.next().unwrap());
+            }
+        }
+
+        Ok(ranked.into_iter().map(|(_, count, value)| (value, count)).collect())
+    }
+
     fn facet_values(
         &self,
         field_id: FieldId,
@@ -256,7 +782,29 @@ impl<'a> FacetDistribution<'a> {
             (OrderBy::Lexicographic, Some(cnd)) if cnd.len() <= CANDIDATES_THRESHOLD => {
                 // Classic search, candidates were specified, we must return facet values only related
                 // to those candidates. We also enter here for facet strings for performance reasons.
-                self.facet_distribution_from_documents(field_id, Number, cnd, &mut distribution)?;
+                match self.level {
+                    Some(level) => self.facet_numbers_distribution_from_level(
+                        field_id,
+                        level,
+                        cnd,
+                        order_by,
+                        &mut distribution,
+                    )?,
+                    // the rounded values are merged from the facet levels
+                    None if self.numeric_precision.is_some() => self
+                        .facet_numbers_distribution_from_facet_levels(
+                            field_id,
+                            cnd,
+                            order_by,
+                            &mut distribution,
+                        )?,
+                    None => self.facet_distribution_from_documents(
+                        field_id,
+                        Number,
+                        cnd,
+                        &mut distribution,
+                    )?,
+                }
                 self.facet_distribution_from_documents(field_id, String, cnd, &mut distribution)?;
             }
             _ => {
@@ -269,12 +817,21 @@ impl<'a> FacetDistribution<'a> {
                     }
                 };
 
-                self.facet_numbers_distribution_from_facet_levels(
-                    field_id,
-                    candidates,
-                    order_by,
-                    &mut distribution,
-                )?;
+                match self.level {
+                    Some(level) => self.facet_numbers_distribution_from_level(
+                        field_id,
+                        level,
+                        candidates,
+                        order_by,
+                        &mut distribution,
+                    )?,
+                    None => self.facet_numbers_distribution_from_facet_levels(
+                        field_id,
+                        candidates,
+                        order_by,
+                        &mut distribution,
+                    )?,
+                }
                 self.facet_strings_distribution_from_facet_levels(
                     field_id,
                     candidates,
@@ -288,10 +845,10 @@ impl<'a> FacetDistribution<'a> {
     }
 
     pub fn compute_stats(&self) -> Result<BTreeMap<String, (f64, f64)>> {
-        let candidates = if let Some(candidates) = self.candidates.clone() {
-            candidates
-        } else {
-            return Ok(Default::default());
+        let candidates = match self.candidates.clone() {
+            Some(candidates) if !candidates.is_empty() => candidates,
+            // there are no stats without candidates
+            _ => return Ok(Default::default()),
         };
 
         let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
@@ -329,7 +886,314 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
+    /// Counts the candidates in the buckets delimited by the boundaries of each numeric facet.
+    ///
+    /// `n` boundaries define `n + 1` buckets, the first and the last ones being open-ended.
+    /// The boundaries must be finite and sorted in strictly ascending order. The counts are
+    /// computed by range searches on the facet trees, only exploring the nodes across bounds.
+    pub fn compute_ranges(
+        &self,
+        ranges: &BTreeMap<String, Vec<f64>>,
+    ) -> Result<BTreeMap<String, Vec<FacetRangeBucket>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
+        check_filterable_fields(ranges.keys(), &filterable_attributes_rules)?;
+
+        let db =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<OrderedF64Codec>>();
+        let mut distribution = BTreeMap::new();
+        for (name, boundaries) in ranges {
+            let is_valid = boundaries.iter().all(|boundary| boundary.is_finite())
+                && boundaries.windows(2).all(|pair| pair[0] < pair[1]);
+            if !is_valid {
+                return Err(UserError::InvalidFacetRangeBoundaries { field: name.clone() }.into());
+            }
+
+            let froms = std::iter::once(None).chain(boundaries.iter().copied().map(Some));
+            let tos = boundaries.iter().copied().map(Some).chain(std::iter::once(None));
+            let mut buckets = Vec::with_capacity(boundaries.len() + 1);
+            for (from, to) in froms.zip(tos) {
+                let count = match fields_ids_map.id(name) {
+                    Some(field_id) => {
+                        let left = from.map_or(Bound::Unbounded, Bound::Included);
+                        let right = to.map_or(Bound::Unbounded, Bound::Excluded);
+                        let mut docids = RoaringBitmap::new();
+                        find_docids_of_facet_within_bounds(
+                            self.rtxn,
+                            db,
+                            field_id,
+                            &left,
+                            &right,
+                            self.candidates.as_ref(),
+                            &mut docids,
+                        )?;
+                        docids.len()
+                    }
+                    None => 0,
+                };
+                buckets.push(FacetRangeBucket { from, to, count });
+            }
+            distribution.insert(name.clone(), buckets);
+        }
+
+        Ok(distribution)
+    }
+
+    /// Counts the candidates in the nodes of the given level of the number facet trees, using the
+    /// boundaries of the trees instead of user-defined ones like [`Self::compute_ranges`].
+    ///
+    /// A bucket goes from the left bound of its node, included, to the left bound of the next node
+    /// of the same level, excluded. The last bucket has no upper bound and the first one starts at
+    /// the smallest value of the facet. The level 0 returns one bucket per value. The nodes without
+    /// candidates are skipped and the fields without any node at this level are not returned, e.g.
+    /// when the level is higher than the highest level of their tree.
+    pub fn compute_level_ranges(
+        &self,
+        level: u8,
+    ) -> Result<BTreeMap<String, Vec<FacetRangeBucket>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
+        self.check_faceted_fields(&filterable_attributes_rules)?;
+
+        let db =
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let mut distribution = BTreeMap::new();
+        for (field_id, name) in fields_ids_map.iter() {
+            if !self.select_field(name, &filterable_attributes_rules) {
+                continue;
+            }
+
+            let prefix = FacetGroupKey { field_id, level, left_bound: &[][..] };
+            let mut nodes = Vec::new();
+            for result in db.prefix_iter(self.rtxn, &prefix)? {
+                let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = result?;
+                let left_bound = OrderedF64Codec::bytes_decode(left_bound).unwrap();
+                let count = match &self.candidates {
+                    Some(candidates) => candidates.intersection_len(&bitmap),
+                    None => bitmap.len(),
+                };
+                nodes.push((left_bound, count));
+            }
+            if nodes.is_empty() {
+                continue;
+            }
+
+            let tos = nodes.iter().skip(1).map(|&(left_bound, _)| Some(left_bound));
+            let buckets = nodes
+                .iter()
+                .zip(tos.chain(std::iter::once(None)))
+                .filter(|((_, count), _)| *count != 0)
+                .map(|(&(from, count), to)| FacetRangeBucket { from: Some(from), to, count })
+                .collect();
+            distribution.insert(name.to_string(), buckets);
+        }
+
+        Ok(distribution)
+    }
+
+    /// Counts the candidates of each of the given facet values, without enumerating
+    /// the other values of the facets.
+    ///
+    /// The values are looked up like in the equality filters: a value counts the strings
+    /// equal to it once normalized and, if it is a number, the numbers equal to it.
+    /// The values that don't exist are counted as `0`.
+    pub fn compute_values(
+        &self,
+        values: &BTreeMap<String, Vec<String>>,
+    ) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
+        check_filterable_fields(values.keys(), &filterable_attributes_rules)?;
+
+        let mut distribution = BTreeMap::new();
+        for (name, values) in values {
+            let mut counts = IndexMap::with_capacity(values.len());
+            for value in values {
+                let count = match fields_ids_map.id(name) {
+                    Some(field_id) => {
+                        let docids = self.value_docids(field_id, value)?;
+                        match &self.candidates {
+                            Some(candidates) => candidates.intersection_len(&docids),
+                            None => docids.len(),
+                        }
+                    }
+                    None => 0,
+                };
+                counts.insert(value.clone(), count);
+            }
+            distribution.insert(name.clone(), counts);
+        }
+
+        Ok(distribution)
+    }
+
+    /// Returns the candidates of each of the given facet values, looked up like in
+    /// [`Self::compute_values`]. The values that don't exist have an empty bitmap.
+    pub fn compute_value_docids(
+        &self,
+        values: &BTreeMap<String, Vec<String>>,
+    ) -> Result<BTreeMap<String, IndexMap<String, RoaringBitmap>>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
+        check_filterable_fields(values.keys(), &filterable_attributes_rules)?;
+
+        let mut distribution = BTreeMap::new();
+        for (name, values) in values {
+            let mut bitmaps = IndexMap::with_capacity(values.len());
+            for value in values {
+                let docids = match fields_ids_map.id(name) {
+                    Some(field_id) => {
+                        let mut docids = self.value_docids(field_id, value)?;
+                        if let Some(candidates) = &self.candidates {
+                            docids &= candidates;
+                        }
+                        docids
+                    }
+                    None => RoaringBitmap::new(),
+                };
+                bitmaps.insert(value.clone(), docids);
+            }
+            distribution.insert(name.clone(), bitmaps);
+        }
+
+        Ok(distribution)
+    }
+
+    /// The documents of the strings equal to the value once normalized and,
+    /// if the value is a number, of the numbers equal to it.
+    fn value_docids(&self, field_id: FieldId, value: &str) -> heed::Result<RoaringBitmap> {
+        let normalized = crate::normalize_facet(value);
+        let key = FacetGroupKey { field_id, level: 0, left_bound: &*normalized };
+        let mut docids = self
+            .index
+            .facet_id_string_docids
+            .get(self.rtxn, &key)?
+            .map(|v| v.bitmap)
+            .unwrap_or_default();
+        if let Some(number) = value.parse::<f64>().ok().filter(|n| n.is_finite()) {
+            let key = FacetGroupKey { field_id, level: 0, left_bound: number };
+            if let Some(FacetGroupValue { bitmap, .. }) =
+                self.index.facet_id_f64_docids.get(self.rtxn, &key)?
+            {
+                docids |= bitmap;
+            }
+        }
+        Ok(docids)
+    }
+
+    /// Counts the candidates in the buckets of distances, in meters, from the `center` point.
+    ///
+    /// `n` boundaries define `n + 1` buckets, like in [`Self::compute_ranges`]. The distances
+    /// are computed with the same haversine formula as the `_geoRadius` filter and the
+    /// candidates without geo coordinates are counted apart from the buckets.
+    pub fn compute_geo_distances(
+        &self,
+        center: [f64; 2],
+        boundaries: &[f64],
+    ) -> Result<GeoDistanceDistribution> {
+        if !self.index.is_geo_filtering_enabled(self.rtxn)? {
+            return Err(UserError::InvalidGeoFacetNotFilterable.into());
+        }
+        let [lat, lng] = center;
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+            return Err(UserError::InvalidGeoFacetCenter { lat, lng }.into());
+        }
+        let is_valid = boundaries.iter().all(|boundary| boundary.is_finite() && *boundary >= 0.0)
+            && boundaries.windows(2).all(|pair| pair[0] < pair[1]);
+        if !is_valid {
+            return Err(UserError::InvalidGeoFacetBoundaries.into());
+        }
+
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates.clone(),
+            None => self.index.documents_ids(self.rtxn)?,
+        };
+        let geo_faceted = self.index.geo_faceted_documents_ids(self.rtxn)?;
+        let without_geo = (&candidates - &geo_faceted).len();
+
+        let mut counts = vec![0; boundaries.len() + 1];
+        if let Some(rtree) = self.index.geo_rtree(self.rtxn)? {
+            for point in rtree.iter() {
+                let (docid, coordinates) = point.data;
+                if candidates.contains(docid) {
+                    let distance = distance_between_two_points(&center, &coordinates);
+                    counts[boundaries.partition_point(|boundary| *boundary <= distance)] += 1;
+                }
+            }
+        }
+
+        let froms = std::iter::once(None).chain(boundaries.iter().copied().map(Some));
+        let tos = boundaries.iter().copied().map(Some).chain(std::iter::once(None));
+        let buckets = froms
+            .zip(tos)
+            .zip(counts)
+            .map(|((from, to), count)| FacetRangeBucket { from, to, count })
+            .collect();
+
+        Ok(GeoDistanceDistribution { buckets, without_geo })
+    }
+
     pub fn execute(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
+        let Some((sample, scale)) = self.sampled_candidates()? else {
+            return self.execute_on_candidates();
+        };
+
+        // the values are filtered on their scaled counts
+        let min_count = (self.min_count as f64 / scale).ceil() as u64;
+        let sampled = FacetDistribution {
+            facets: self.facets.clone(),
+            candidates: Some(sample),
+            max_values_per_facet: self.max_values_per_facet,
+            min_count,
+            level: self.level,
+            sample: None,
+            numeric_precision: self.numeric_precision,
+            default_order_by: self.default_order_by,
+            document_scores: self.document_scores.clone(),
+            boolean_facets: self.boolean_facets.clone(),
+            rtxn: self.rtxn,
+            index: self.index,
+        };
+        let mut distribution = sampled.execute_on_candidates()?;
+        for count in distribution.values_mut().flat_map(|values| values.values_mut()) {
+            *count = (*count as f64 * scale).round() as u64;
+        }
+
+        Ok(distribution)
+    }
+
+    /// Whether the distribution is computed over a sample of the candidates, see [`Self::sample`].
+    pub fn is_sampled(&self) -> Result<bool> {
+        let Some((size, _)) = self.sample else { return Ok(false) };
+        let candidates_len = match &self.candidates {
+            Some(candidates) => candidates.len(),
+            None => self.index.number_of_documents(self.rtxn)?,
+        };
+        Ok(candidates_len > size.get() as u64)
+    }
+
+    /// Returns the sampled candidates along with the factor to scale the counts up by,
+    /// or `None` when the distribution must be computed over all the candidates.
+    fn sampled_candidates(&self) -> Result<Option<(RoaringBitmap, f64)>> {
+        let Some((size, seed)) = self.sample else { return Ok(None) };
+        if !self.is_sampled()? {
+            return Ok(None);
+        }
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates.clone(),
+            None => self.index.documents_ids(self.rtxn)?,
+        };
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let sample: RoaringBitmap =
+            rand::seq::index::sample(&mut rng, candidates.len() as usize, size.get())
+                .into_iter()
+                .filter_map(|rank| candidates.select(rank as u32))
+                .collect();
+        Ok(Some((sample, candidates.len() as f64 / size.get() as f64)))
+    }
+
+    fn execute_on_candidates(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
         let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
         let filterable_attributes_rules = self.index.filterable_attributes_rules(self.rtxn)?;
         self.check_faceted_fields(&filterable_attributes_rules)?;
@@ -342,7 +1206,27 @@ impl<'a> FacetDistribution<'a> {
                     .as_ref()
                     .and_then(|facets| facets.get(name).copied())
                     .unwrap_or(self.default_order_by);
-                let values = self.facet_values(fid, order_by)?;
+                // the booleans always have the same buckets, whatever the order of the values
+                if self.boolean_facets.contains(name) {
+                    if let Some(buckets) = self.boolean_facet_values(fid)? {
+                        distribution.insert(name.to_string(), buckets);
+                        continue;
+                    }
+                }
+                let values = match order_by {
+                    // the filter matched nothing, the facet trees don't need to be walked
+                    _ if self.candidates.as_ref().is_some_and(RoaringBitmap::is_empty) => {
+                        IndexMap::new()
+                    }
+                    OrderBy::Weighted(weights) => self.weighted_facet_values(fid, weights)?,
+                    OrderBy::Recency => self.recent_facet_values(fid)?,
+                    OrderBy::Weight => self.weighted_by_values_facet_values(fid)?,
+                    OrderBy::ScoreSum => match &self.document_scores {
+                        Some(scores) => self.score_sum_facet_values(fid, scores)?,
+                        None => self.facet_values(fid, OrderBy::Count)?,
+                    },
+                    order_by => self.facet_values(fid, order_by)?,
+                };
                 distribution.insert(name.to_string(), values);
             }
         }
@@ -377,43 +1261,64 @@ impl<'a> FacetDistribution<'a> {
         &self,
         filterable_attributes_rules: &[FilterableAttributesRule],
     ) -> Result<()> {
-        let mut invalid_facets = BTreeSet::new();
-        let mut matching_rule_indices = HashMap::new();
-
-        if let Some(facets) = &self.facets {
-            for field in facets.keys() {
-                let matched_rule = matching_features(field, filterable_attributes_rules);
-                let is_filterable = matched_rule.is_some_and(|(_, f)| f.is_filterable());
+        match &self.facets {
+            Some(facets) => check_filterable_fields(facets.keys(), filterable_attributes_rules),
+            None => Ok(()),
+        }
+    }
+}
 
-                if !is_filterable {
-                    invalid_facets.insert(field.to_string());
+/// Rounds the value to the given number of decimals, the values too large
+/// to be scaled up are returned as is.
+fn round_to_decimals(value: f64, decimals: u8) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    let scaled = value * scale;
+    if scaled.is_finite() {
+        // adding zero turns the negative zero into a positive one
+        (scaled.round() / scale) + 0.0
+    } else {
+        value
+    }
+}
 
-                    // If the field matched a rule but that rule doesn't enable filtering,
-                    // store the rule index for better error messages
-                    if let Some((rule_index, _)) = matched_rule {
-                        matching_rule_indices.insert(field.to_string(), rule_index);
-                    }
-                }
+/// Check if the given fields are valid filterable fields.
+pub(super) fn check_filterable_fields<'f>(
+    fields: impl IntoIterator<Item = &'f String>,
+    filterable_attributes_rules: &[FilterableAttributesRule],
+) -> Result<()> {
+    let mut invalid_facets = BTreeSet::new();
+    let mut matching_rule_indices = HashMap::new();
+
+    for field in fields {
+        let matched_rule = matching_features(field, filterable_attributes_rules);
+        let is_filterable = matched_rule.is_some_and(|(_, f)| f.is_filterable());
+
+        if !is_filterable {
+            invalid_facets.insert(field.to_string());
+
+            // If the field matched a rule but that rule doesn't enable filtering,
+            // store the rule index for better error messages
+            if let Some((rule_index, _)) = matched_rule {
+                matching_rule_indices.insert(field.to_string(), rule_index);
             }
         }
+    }
 
-        if !invalid_facets.is_empty() {
-            let valid_patterns =
-                filtered_matching_patterns(filterable_attributes_rules, &|features| {
-                    features.is_filterable()
-                })
-                .into_iter()
-                .map(String::from)
-                .collect();
-            return Err(Error::UserError(UserError::InvalidFacetsDistribution {
-                invalid_facets_name: invalid_facets,
-                valid_patterns,
-                matching_rule_indices,
-            }));
-        }
-
-        Ok(())
+    if !invalid_facets.is_empty() {
+        let valid_patterns = filtered_matching_patterns(filterable_attributes_rules, &|features| {
+            features.is_filterable()
+        })
+        .into_iter()
+        .map(String::from)
+        .collect();
+        return Err(Error::UserError(UserError::InvalidFacetsDistribution {
+            invalid_facets_name: invalid_facets,
+            valid_patterns,
+            matching_rule_indices,
+        }));
     }
+
+    Ok(())
 }
 
 impl fmt::Debug for FacetDistribution<'_> {
@@ -422,7 +1327,13 @@ impl fmt::Debug for FacetDistribution<'_> {
             facets,
             candidates,
             max_values_per_facet,
+            min_count,
+            level,
+            sample,
+            numeric_precision,
             default_order_by,
+            document_scores,
+            boolean_facets,
             rtxn: _,
             index: _,
         } = self;
@@ -431,20 +1342,34 @@ impl fmt::Debug for FacetDistribution<'_> {
             .field("facets", facets)
             .field("candidates", candidates)
             .field("max_values_per_facet", max_values_per_facet)
+            .field("min_count", min_count)
+            .field("level", level)
+            .field("sample", sample)
+            .field("numeric_precision", numeric_precision)
             .field("default_order_by", default_order_by)
+            .field("document_scores", document_scores)
+            .field("boolean_facets", boolean_facets)
             .finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::iter;
+    use std::num::NonZeroUsize;
 
     use big_s::S;
+    use roaring::RoaringBitmap;
 
     use crate::documents::mmap_from_objects;
     use crate::index::tests::TempIndex;
-    use crate::{milli_snap, FacetDistribution, FilterableAttributesRule, OrderBy};
+    use crate::search::facet::FACET_WALKS;
+    use crate::update::facet::build_deferred_facet_structures;
+    use crate::{
+        milli_snap, FacetDistribution, FacetRangeBucket, Filter, FilterableAttributesRule,
+        GeoDistanceDistribution, OrderBy, SearchForFacetValues,
+    };
 
     #[test]
     fn few_candidates_few_facet_values() {
@@ -670,6 +1595,182 @@ mod tests {
         milli_snap!(format!("{map:?}"), "candidates_0_5_000", @"825f23a4090d05756f46176987b7d992");
     }
 
+    #[test]
+    fn facet_numbers_distribution_by_level() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("price"))])
+            })
+            .unwrap();
+
+        let documents = (0..20)
+            .map(|i| serde_json::json!({ "id": i, "price": i }).as_object().unwrap().clone());
+        index.add_documents(mmap_from_objects(documents)).unwrap();
+
+        // rebuild the levels in bulk to get predictable groups of `FACET_GROUP_SIZE` values
+        let mut wtxn = index.write_txn().unwrap();
+        build_deferred_facet_structures(&index, &mut wtxn).unwrap();
+        wtxn.commit().unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("price", OrderBy::default())))
+            .level(1)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "level_1", @r###"{"price": {"0": 4, "4": 4, "8": 4, "12": 4, "16": 4}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("price", OrderBy::default())))
+            .candidates((0..10).collect())
+            .level(1)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "level_1_candidates_0_10", @r###"{"price": {"0": 4, "4": 4, "8": 2}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("price", OrderBy::Count)))
+            .candidates((2..12).collect())
+            .level(1)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "level_1_by_count", @r###"{"price": {"4": 4, "8": 4, "0": 2}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("price", OrderBy::default())))
+            .max_values_per_facet(3)
+            .level(0)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "level_0", @r###"{"price": {"0": 1, "1": 1, "2": 1}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("price", OrderBy::default())))
+            .level(2)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "missing_level", @r###"{"price": {}}"###);
+    }
+
+    #[test]
+    fn facet_numbers_distribution_by_ranges() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field(S("price")),
+                    FilterableAttributesRule::Field(S("colour")),
+                ])
+            })
+            .unwrap();
+
+        let prices = [10.0, 25.0, 50.0, 75.0, 99.5, 100.0, 180.0];
+        let documents = prices.iter().enumerate().map(|(i, price)| {
+            serde_json::json!({ "id": i, "price": price }).as_object().unwrap().clone()
+        });
+        index.add_documents(mmap_from_objects(documents)).unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let ranges = BTreeMap::from([(S("price"), vec![50.0, 100.0])]);
+        let buckets = |map: &BTreeMap<String, Vec<FacetRangeBucket>>, name: &str| {
+            map[name].iter().map(|b| (b.from, b.to, b.count)).collect::<Vec<_>>()
+        };
+
+        let map = FacetDistribution::new(&txn, &index).compute_ranges(&ranges).unwrap();
+        milli_snap!(format!("{:?}", buckets(&map, "price")), "all", @"[(None, Some(50.0), 2), (Some(50.0), Some(100.0), 3), (Some(100.0), None, 2)]");
+
+        let map = FacetDistribution::new(&txn, &index)
+            .candidates((0..4).collect())
+            .compute_ranges(&ranges)
+            .unwrap();
+        milli_snap!(format!("{:?}", buckets(&map, "price")), "candidates_0_4", @"[(None, Some(50.0), 2), (Some(50.0), Some(100.0), 2), (Some(100.0), None, 0)]");
+
+        // a filterable field without any number value has empty buckets
+        let ranges = BTreeMap::from([(S("colour"), vec![1.0])]);
+        let map = FacetDistribution::new(&txn, &index).compute_ranges(&ranges).unwrap();
+        milli_snap!(format!("{:?}", buckets(&map, "colour")), "no_numbers", @"[(None, Some(1.0), 0), (Some(1.0), None, 0)]");
+
+        let ranges = BTreeMap::from([(S("price"), vec![100.0, 50.0])]);
+        let error = FacetDistribution::new(&txn, &index).compute_ranges(&ranges).unwrap_err();
+        milli_snap!(error.to_string(), "unsorted_boundaries", @"Invalid facet ranges: the boundaries of `price` must be finite numbers sorted in strictly ascending order.");
+
+        let ranges = BTreeMap::from([(S("id"), vec![1.0])]);
+        let error = FacetDistribution::new(&txn, &index).compute_ranges(&ranges).unwrap_err();
+        milli_snap!(error.to_string(), "not_filterable", @"Invalid facet distribution: Attribute `id` is not filterable. Available filterable attributes patterns are: `colour, price`.");
+    }
+
+    #[test]
+    fn geo_distances_distribution() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("_geo"))])
+            })
+            .unwrap();
+
+        let documents = [
+            serde_json::json!({ "id": 0, "_geo": { "lat": 48.8566, "lng": 2.3522 } }),
+            serde_json::json!({ "id": 1, "_geo": { "lat": 48.8606, "lng": 2.3376 } }),
+            serde_json::json!({ "id": 2, "_geo": { "lat": 48.8049, "lng": 2.1204 } }),
+            serde_json::json!({ "id": 3, "_geo": { "lat": 45.764, "lng": 4.8357 } }),
+            serde_json::json!({ "id": 4 }),
+        ];
+        let documents = documents.iter().map(|doc| doc.as_object().unwrap().clone());
+        index.add_documents(mmap_from_objects(documents)).unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let center = [48.8566, 2.3522];
+        let boundaries = [1000.0, 5000.0, 50000.0];
+        let buckets = |distribution: &GeoDistanceDistribution| {
+            distribution.buckets.iter().map(|b| (b.from, b.to, b.count)).collect::<Vec<_>>()
+        };
+
+        let distribution = FacetDistribution::new(&txn, &index)
+            .compute_geo_distances(center, &boundaries)
+            .unwrap();
+        milli_snap!(format!("{:?}", buckets(&distribution)), "all", @"[(None, Some(1000.0), 1), (Some(1000.0), Some(5000.0), 1), (Some(5000.0), Some(50000.0), 1), (Some(50000.0), None, 1)]");
+        assert_eq!(distribution.without_geo, 1);
+
+        let distribution = FacetDistribution::new(&txn, &index)
+            .candidates([1, 3].into_iter().collect())
+            .compute_geo_distances(center, &boundaries)
+            .unwrap();
+        milli_snap!(format!("{:?}", buckets(&distribution)), "candidates_1_3", @"[(None, Some(1000.0), 0), (Some(1000.0), Some(5000.0), 1), (Some(5000.0), Some(50000.0), 0), (Some(50000.0), None, 1)]");
+        assert_eq!(distribution.without_geo, 0);
+
+        let error = FacetDistribution::new(&txn, &index)
+            .compute_geo_distances([95.0, 2.0], &boundaries)
+            .unwrap_err();
+        milli_snap!(error.to_string(), "bad_center", @"Invalid geo facet: the center point `[95, 2]` must have a latitude between -90 and 90 and a longitude between -180 and 180.");
+
+        let error = FacetDistribution::new(&txn, &index)
+            .compute_geo_distances(center, &[-1.0, 1000.0])
+            .unwrap_err();
+        milli_snap!(error.to_string(), "bad_boundaries", @"Invalid geo facet: the distance boundaries must be non-negative finite numbers sorted in strictly ascending order.");
+        drop(txn);
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("id"))])
+            })
+            .unwrap();
+        let txn = index.read_txn().unwrap();
+        let error = FacetDistribution::new(&txn, &index)
+            .compute_geo_distances(center, &boundaries)
+            .unwrap_err();
+        milli_snap!(error.to_string(), "not_filterable", @"Invalid geo facet: the `_geo` field must be filterable to compute the distances of the documents.");
+    }
+
     #[test]
     fn facet_stats() {
         let index = TempIndex::new_with_map_size(4096 * 10_000);
@@ -886,4 +1987,202 @@ mod tests {
 
         milli_snap!(format!("{map:?}"), "candidates_217_777", @r###"{"colour": (218.0, 1776.0)}"###);
     }
+
+    #[test]
+    fn empty_candidates_skip_the_facet_walks() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("colour"))])
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "colour": "Blue" },
+                { "id": 1, "colour": "Red" },
+                { "id": 2, "colour": "Green" }
+            ]))
+            .unwrap();
+        let txn = index.read_txn().unwrap();
+        let walks = || FACET_WALKS.with(|walks| walks.replace(0));
+        walks();
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::Count)))
+            .candidates(RoaringBitmap::new())
+            .execute()
+            .unwrap();
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {}}"###);
+        assert_eq!(walks(), 0);
+
+        // a filter matching no documents doesn't walk the FST of the facet search either
+        let mut search = index.search(&txn);
+        search.filter(Filter::from_str("colour = Purple").unwrap().unwrap());
+        let hits = SearchForFacetValues::new(S("colour"), search, false).execute().unwrap();
+        assert!(hits.is_empty());
+        assert_eq!(walks(), 0);
+
+        // the walks are still counted with candidates
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::Count)))
+            .candidates((0..3).collect())
+            .execute()
+            .unwrap();
+        assert_eq!(map["colour"].len(), 3);
+        // the number and string trees
+        assert_eq!(walks(), 2);
+
+        let search = index.search(&txn);
+        let hits = SearchForFacetValues::new(S("colour"), search, false).execute().unwrap();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(walks(), 1);
+    }
+
+    #[test]
+    fn sampled_distribution_approximates_the_exact_one() {
+        let index = TempIndex::new_with_map_size(4096 * 10_000);
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("colour"))])
+            })
+            .unwrap();
+
+        let facet_values = ["Red", "Red", "Blue", "Green"];
+
+        let mut documents = vec![];
+        for i in 0..10_000 {
+            let document = serde_json::json!({
+                "id": i,
+                "colour": facet_values[i % 4],
+            })
+            .as_object()
+            .unwrap()
+            .clone();
+            documents.push(document);
+        }
+
+        let documents = mmap_from_objects(documents);
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let size = NonZeroUsize::new(1000).unwrap();
+
+        let exact = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .execute()
+            .unwrap();
+        let mut sampled = FacetDistribution::new(&txn, &index);
+        sampled.facets(iter::once(("colour", OrderBy::default()))).sample(size, 42);
+        assert!(sampled.is_sampled().unwrap());
+        let sampled = sampled.execute().unwrap();
+
+        for (value, exact_count) in &exact["colour"] {
+            let sampled_count = sampled["colour"][value];
+            let error = (sampled_count as f64 - *exact_count as f64).abs() / *exact_count as f64;
+            assert!(error < 0.2, "{value}: {sampled_count} sampled for {exact_count}");
+        }
+
+        // the same seed samples the same documents
+        let again = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .sample(size, 42)
+            .execute()
+            .unwrap();
+        assert_eq!(sampled, again);
+
+        // the candidates are not sampled when there are fewer of them than the sample size
+        let mut distribution = FacetDistribution::new(&txn, &index);
+        distribution
+            .facets(iter::once(("colour", OrderBy::default())))
+            .candidates((0..500).collect())
+            .sample(size, 42);
+        assert!(!distribution.is_sampled().unwrap());
+        let few = distribution.execute().unwrap();
+        assert_eq!(few["colour"].values().sum::<u64>(), 500);
+    }
+
+    #[test]
+    fn facet_values_sorted_by_score_sum() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("colour"))])
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "colour": "Blue" },
+                { "id": 1, "colour": "Blue" },
+                { "id": 2, "colour": "Blue" },
+                { "id": 3, "colour": "Red" },
+                { "id": 4, "colour": ["Red", 7] },
+                { "id": 5, "colour": "green" }
+            ]))
+            .unwrap();
+        let txn = index.read_txn().unwrap();
+        let scores = [(0, 0.1), (1, 0.1), (3, 0.9), (4, 0.8), (5, 0.5)].into_iter().collect();
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::ScoreSum)))
+            .document_scores(scores)
+            .execute()
+            .unwrap();
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"Red": 2, "7": 1, "green": 1, "Blue": 3}}"###);
+
+        // without the scores of a search the values are sorted by count
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::ScoreSum)))
+            .execute()
+            .unwrap();
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"7": 1, "Blue": 3, "Red": 2, "green": 1}}"###);
+    }
+
+    #[test]
+    fn boolean_facet_buckets() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field(S("inStock")),
+                    FilterableAttributesRule::Field(S("label")),
+                ])
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "inStock": true, "label": "true" },
+                { "id": 1, "inStock": true, "label": "maybe" },
+                { "id": 2, "inStock": false },
+                { "id": 3 },
+                { "id": 4, "inStock": null },
+                { "id": 5, "inStock": true }
+            ]))
+            .unwrap();
+        let txn = index.read_txn().unwrap();
+
+        // the booleans are only bucketed on demand
+        let map = FacetDistribution::new(&txn, &index)
+            .facets([("inStock", OrderBy::Count), ("label", OrderBy::Lexicographic)])
+            .execute()
+            .unwrap();
+        milli_snap!(format!("{map:?}"), @r###"{"inStock": {"true": 3, "false": 1}, "label": {"maybe": 1, "true": 1}}"###);
+
+        // the documents without a boolean are counted as missing, a field with other values is unchanged
+        let map = FacetDistribution::new(&txn, &index)
+            .facets([("inStock", OrderBy::Count), ("label", OrderBy::Lexicographic)])
+            .boolean_facets(["inStock", "label"])
+            .execute()
+            .unwrap();
+        milli_snap!(format!("{map:?}"), @r###"{"inStock": {"true": 3, "false": 1, "missing": 2}, "label": {"maybe": 1, "true": 1}}"###);
+
+        // the empty buckets are returned too
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("inStock", OrderBy::Lexicographic)))
+            .boolean_facets(["inStock"])
+            .candidates([2, 3, 4].into_iter().collect())
+            .execute()
+            .unwrap();
+        milli_snap!(format!("{map:?}"), @r###"{"inStock": {"true": 0, "false": 1, "missing": 2}}"###);
+    }
 }
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::normalize_facet;
+
+/// How the distribution of a field changed between two index states, computed by
+/// [`diff_facet_distributions`].
+///
+/// The values are ordered by their normalized form, the one the facet databases sort them with.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetDistributionDiff {
+    /// The values only present in the new distribution, with their new count.
+    pub added: IndexMap<String, u64>,
+    /// The values only present in the old distribution, with their old count.
+    pub removed: IndexMap<String, u64>,
+    /// The difference between the new and the old count of the values present in both
+    /// distributions whose count changed.
+    pub changed: IndexMap<String, i64>,
+}
+
+impl FacetDistributionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Computes how the distributions returned by [`FacetDistribution::execute`] changed between
+/// two index states, e.g. before and after a batch.
+///
+/// The values are matched by their normalized form, so a value whose original form changed,
+/// e.g. from `Blue` to `blue`, is not reported as removed and added again but under its new form.
+/// The fields whose distribution did not change are not returned.
+///
+/// [`FacetDistribution::execute`]: super::FacetDistribution::execute
+pub fn diff_facet_distributions(
+    old: &BTreeMap<String, IndexMap<String, u64>>,
+    new: &BTreeMap<String, IndexMap<String, u64>>,
+) -> BTreeMap<String, FacetDistributionDiff> {
+    let empty = IndexMap::new();
+    let fields = old.keys().chain(new.keys());
+
+    let mut diffs = BTreeMap::new();
+    for field in fields {
+        if diffs.contains_key(field) {
+            continue;
+        }
+        let old_values = normalized_values(old.get(field).unwrap_or(&empty));
+        let new_values = normalized_values(new.get(field).unwrap_or(&empty));
+
+        let mut diff = FacetDistributionDiff::default();
+        for (normalized, (value, old_count)) in &old_values {
+            match new_values.get(normalized) {
+                Some((value, new_count)) if new_count != old_count => {
+                    let delta = i128::from(*new_count) - i128::from(*old_count);
+                    let delta = delta.clamp(i64::MIN.into(), i64::MAX.into()) as i64;
+                    diff.changed.insert(value.to_string(), delta);
+                }
+                Some(_) => (),
+                None => {
+                    diff.removed.insert(value.to_string(), *old_count);
+                }
+            }
+        }
+        for (normalized, (value, new_count)) in &new_values {
+            if !old_values.contains_key(normalized) {
+                diff.added.insert(value.to_string(), *new_count);
+            }
+        }
+
+        if !diff.is_empty() {
+            diffs.insert(field.clone(), diff);
+        }
+    }
+
+    diffs
+}
+
+/// Indexes the values of a distribution by their normalized form, summing the counts of the
+/// values that are equal once normalized.
+fn normalized_values(values: &IndexMap<String, u64>) -> BTreeMap<String, (&str, u64)> {
+    let mut normalized_values: BTreeMap<String, (&str, u64)> = BTreeMap::new();
+    for (value, &count) in values {
+        let (_, total) =
+            normalized_values.entry(normalize_facet(value)).or_insert((value.as_str(), 0));
+        *total = total.saturating_add(count);
+    }
+    normalized_values
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use indexmap::IndexMap;
+
+    use super::{diff_facet_distributions, FacetDistributionDiff};
+
+    fn distribution(fields: &[(&str, &[(&str, u64)])]) -> BTreeMap<String, IndexMap<String, u64>> {
+        fields
+            .iter()
+            .map(|(field, values)| {
+                let values = values.iter().map(|(value, count)| (value.to_string(), *count));
+                (field.to_string(), values.collect())
+            })
+            .collect()
+    }
+
+    fn values<T: Copy>(values: &[(&str, T)]) -> IndexMap<String, T> {
+        values.iter().map(|(value, count)| (value.to_string(), *count)).collect()
+    }
+
+    #[test]
+    fn diff_two_distributions() {
+        let old = distribution(&[
+            ("colour", &[("Red", 3), ("Blue", 2), ("green", 1)]),
+            ("size", &[("xl", 4)]),
+            ("brand", &[("Acme", 1)]),
+        ]);
+        let new = distribution(&[
+            ("colour", &[("blue", 5), ("Red", 1), ("Yellow", 2), ("Black", 1)]),
+            ("size", &[("xl", 4)]),
+            ("material", &[("wood", 7)]),
+        ]);
+
+        let diffs = diff_facet_distributions(&old, &new);
+        assert_eq!(diffs.keys().collect::<Vec<_>>(), ["brand", "colour", "material"]);
+
+        // `Blue` became `blue` and is matched once normalized, the values are ordered by their
+        // normalized form whatever their order in the distributions
+        assert_eq!(
+            diffs["colour"],
+            FacetDistributionDiff {
+                added: values(&[("Black", 1), ("Yellow", 2)]),
+                removed: values(&[("green", 1)]),
+                changed: values(&[("blue", 3), ("Red", -2)]),
+            }
+        );
+        assert_eq!(diffs["colour"].changed.keys().collect::<Vec<_>>(), ["blue", "Red"]);
+        assert_eq!(
+            diffs["brand"],
+            FacetDistributionDiff { removed: values(&[("Acme", 1)]), ..Default::default() }
+        );
+        assert_eq!(
+            diffs["material"],
+            FacetDistributionDiff { added: values(&[("wood", 7)]), ..Default::default() }
+        );
+
+        // the diff is symmetric
+        let diffs = diff_facet_distributions(&new, &old);
+        assert_eq!(diffs["colour"].added, values(&[("green", 1)]));
+        assert_eq!(diffs["colour"].changed, values(&[("Blue", -3), ("Red", 2)]));
+        assert!(diff_facet_distributions(&old, &old).is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use std::ops::ControlFlow;
 use heed::Result;
 use roaring::RoaringBitmap;
 
-use super::{get_first_facet_value, get_highest_level};
+use super::{get_first_facet_value, get_highest_level, record_facet_walk};
 use crate::heed_codec::facet::{
     FacetGroupKey, FacetGroupKeyCodec, FacetGroupLazyValueCodec, FacetGroupValueCodec,
 };
@@ -33,6 +33,12 @@ pub fn lexicographically_iterate_over_facet_distribution<'t, CB>(
 where
     CB: FnMut(&'t [u8], u64, DocumentId) -> Result<ControlFlow<()>>,
 {
+    // no facet value is associated with an empty set of candidates
+    if candidates.is_empty() {
+        return Ok(());
+    }
+    record_facet_walk();
+
     let db = db.remap_data_type::<FacetGroupLazyValueCodec>();
     let mut fd = LexicographicFacetDistribution { rtxn, db, field_id, callback };
     let highest_level = get_highest_level(rtxn, db, field_id)?;
@@ -72,6 +78,12 @@ where
         any_docid: u32,
     }
 
+    // no facet value is associated with an empty set of candidates
+    if candidates.is_empty() {
+        return Ok(());
+    }
+    record_facet_walk();
+
     // Represents the list of keys that we must explore.
     let mut heap = BinaryHeap::new();
     let db = db.remap_data_type::<FacetGroupLazyValueCodec>();
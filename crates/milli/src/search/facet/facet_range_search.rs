@@ -1,11 +1,12 @@
 use std::ops::{Bound, RangeBounds};
 
-use heed::BytesEncode;
+use heed::{BytesDecode, BytesEncode};
 use roaring::RoaringBitmap;
 
 use super::{get_first_facet_value, get_highest_level, get_last_facet_value};
 use crate::heed_codec::facet::{
-    FacetGroupKey, FacetGroupKeyCodec, FacetGroupLazyValueCodec, FacetGroupValueCodec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupLazyValueCodec, FacetGroupValue,
+    FacetGroupValueCodec, OrderedF64Codec,
 };
 use crate::heed_codec::BytesRefCodec;
 use crate::{CboRoaringBitmapCodec, Result};
@@ -66,6 +67,76 @@ where
     }
 }
 
+/// Iterate over the numeric values of the given field contained within the two bounds in
+/// ascending order, along with the running count of the document ids of the values seen so far.
+///
+/// Only the document ids of the `universe` are counted when it is given and the values without
+/// any of them are skipped. A document with several values within the bounds is counted once for
+/// each of them, the last running count is therefore the sum of the counts of all the values.
+pub fn iterate_facet_numbers_with_cumulative_counts<'t>(
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<OrderedF64Codec>, FacetGroupValueCodec>,
+    field_id: u16,
+    left: Bound<f64>,
+    right: Bound<f64>,
+    universe: Option<&'t RoaringBitmap>,
+) -> Result<impl Iterator<Item = Result<(f64, u64)>> + 't> {
+    let encode = |bound: &f64| OrderedF64Codec::bytes_encode(bound).map_err(heed::Error::Encoding);
+    let left_bound;
+    let left = match left {
+        Bound::Included(left) => {
+            left_bound = encode(&left)?;
+            Bound::Included(FacetGroupKey { field_id, level: 0, left_bound: left_bound.as_ref() })
+        }
+        Bound::Excluded(left) => {
+            left_bound = encode(&left)?;
+            Bound::Excluded(FacetGroupKey { field_id, level: 0, left_bound: left_bound.as_ref() })
+        }
+        // the first key of the level 0 of the field
+        Bound::Unbounded => {
+            Bound::Included(FacetGroupKey { field_id, level: 0, left_bound: &[][..] })
+        }
+    };
+    let right_bound;
+    let right = match right {
+        Bound::Included(right) => {
+            right_bound = encode(&right)?;
+            Bound::Included(FacetGroupKey { field_id, level: 0, left_bound: right_bound.as_ref() })
+        }
+        Bound::Excluded(right) => {
+            right_bound = encode(&right)?;
+            Bound::Excluded(FacetGroupKey { field_id, level: 0, left_bound: right_bound.as_ref() })
+        }
+        // the first key of the level 1 of the field
+        Bound::Unbounded => {
+            Bound::Excluded(FacetGroupKey { field_id, level: 1, left_bound: &[][..] })
+        }
+    };
+
+    let db = db.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+    let mut cumulative_count = 0;
+    let iter = db.range(rtxn, &(left, right))?.filter_map(move |result| {
+        let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) = match result {
+            Ok(entry) => entry,
+            Err(e) => return Some(Err(e.into())),
+        };
+        let count = match universe {
+            Some(universe) => bitmap.intersection_len(universe),
+            None => bitmap.len(),
+        };
+        if count == 0 {
+            return None;
+        }
+        cumulative_count += count;
+        match OrderedF64Codec::bytes_decode(left_bound) {
+            Ok(value) => Some(Ok((value, cumulative_count))),
+            Err(e) => Some(Err(heed::Error::Decoding(e).into())),
+        }
+    });
+
+    Ok(iter)
+}
+
 /// Fetch the document ids that have a facet with a value between the two given bounds
 struct FacetRangeSearch<'t, 'b, 'bitmap> {
     rtxn: &'t heed::RoTxn<'t>,
@@ -337,7 +408,7 @@ mod tests {
 
     use roaring::RoaringBitmap;
 
-    use super::find_docids_of_facet_within_bounds;
+    use super::{find_docids_of_facet_within_bounds, iterate_facet_numbers_with_cumulative_counts};
     use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
     use crate::milli_snap;
     use crate::search::facet::tests::{
@@ -346,6 +417,36 @@ mod tests {
     };
     use crate::snapshot_tests::display_bitmap;
 
+    #[test]
+    fn cumulative_counts_of_facet_numbers() {
+        let index = get_simple_index_with_multiple_field_ids();
+        let txn = index.env.read_txn().unwrap();
+        let db = index.content.remap_key_type::<FacetGroupKeyCodec<OrderedF64Codec>>();
+
+        let universe: RoaringBitmap = (0..256).step_by(3).collect();
+        for (left, right, universe) in [
+            (Bound::Unbounded, Bound::Unbounded, None),
+            (Bound::Included(10.), Bound::Excluded(200.), None),
+            (Bound::Excluded(10.), Bound::Included(200.), Some(&universe)),
+            (Bound::Unbounded, Bound::Included(100.), Some(&universe)),
+        ] {
+            let counts: Vec<(f64, u64)> =
+                iterate_facet_numbers_with_cumulative_counts(&txn, db, 0, left, right, universe)
+                    .unwrap()
+                    .collect::<crate::Result<_>>()
+                    .unwrap();
+
+            // each document has a single value, the running counts increase one by one
+            assert!(counts.windows(2).all(|pair| pair[0].0 < pair[1].0));
+            assert!(counts.iter().enumerate().all(|(i, &(_, count))| count == i as u64 + 1));
+
+            let mut docids = RoaringBitmap::new();
+            find_docids_of_facet_within_bounds(&txn, db, 0, &left, &right, universe, &mut docids)
+                .unwrap();
+            assert_eq!(counts.last().map_or(0, |&(_, count)| count), docids.len());
+        }
+    }
+
     #[test]
     fn random_looking_index_snap() {
         let index = get_random_looking_index();
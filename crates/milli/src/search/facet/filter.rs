@@ -242,6 +242,35 @@ impl<'a> Filter<'a> {
     pub fn use_vector_filter(&self) -> Option<&Token<'_>> {
         self.condition.use_vector_filter()
     }
+
+    /// Returns the filter without the clauses only constraining the given field, e.g. to count the
+    /// values of a facet as if none of them were selected. A clause is only dropped when it is one
+    /// of the conditions combined with `AND`, the `OR` mixing several fields are kept as is.
+    ///
+    /// Returns `None` when no clause remains.
+    pub fn without_field(&self, field: &str) -> Option<Self> {
+        without_field(&self.condition, field).map(|condition| Self { condition })
+    }
+}
+
+fn without_field<'a>(condition: &FilterCondition<'a>, field: &str) -> Option<FilterCondition<'a>> {
+    match condition {
+        FilterCondition::And(conditions) => {
+            let mut conditions: Vec<_> =
+                conditions.iter().filter_map(|condition| without_field(condition, field)).collect();
+            match conditions.len() {
+                0 => None,
+                1 => conditions.pop(),
+                _ => Some(FilterCondition::And(conditions)),
+            }
+        }
+        condition => {
+            let mut fids = condition.fids(MAX_FILTER_DEPTH).peekable();
+            // the geo filters don't constrain any named field
+            let only_field = fids.peek().is_some() && fids.all(|fid| fid.value() == field);
+            (!only_field).then(|| condition.clone())
+        }
+    }
 }
 
 impl<'a> Filter<'a> {
@@ -1053,6 +1082,22 @@ mod tests {
         assert_eq!(condition, expected);
     }
 
+    #[test]
+    fn without_field() {
+        let without_genre = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            filter.without_field("genre").map(|filter| filter.condition.to_string())
+        };
+
+        snapshot!(format!("{:?}", without_genre("genre = horror")), @"None");
+        snapshot!(format!("{:?}", without_genre("genre = horror OR genre IN [drama]")), @"None");
+        snapshot!(format!("{:?}", without_genre("NOT genre = horror AND year > 2000")), @r###"Some("{year} > {2000}")"###);
+        snapshot!(format!("{:?}", without_genre("genre = horror AND (year > 2000 AND _geoRadius(0, 0, 10))")), @r###"Some("AND[{year} > {2000}, _geoRadius({0}, {0}, {10}), ]")"###);
+        // the clauses constraining other fields too are kept
+        snapshot!(format!("{:?}", without_genre("genre = horror OR year > 2000")), @r###"Some("OR[{genre} = {horror}, {year} > {2000}, ]")"###);
+        snapshot!(format!("{:?}", without_genre("genres = horror")), @r###"Some("{genres} = {horror}")"###);
+    }
+
     #[test]
     fn not_filterable() {
         let index = TempIndex::new();
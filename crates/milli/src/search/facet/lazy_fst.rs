@@ -0,0 +1,93 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, RwLock};
+
+use heed::RoTxn;
+use roaring::RoaringBitmap;
+use time::OffsetDateTime;
+
+use crate::heed_codec::facet::{FacetGroupKey, FacetGroupValue};
+use crate::{FieldId, Index, Result};
+
+/// The facet search FSTs of an index in [`FacetingMode::Lazy`](crate::index::FacetingMode::Lazy).
+///
+/// The FSTs are built from the `facet_id_normalized_string_strings` database the first
+/// time a facet search is made on a field and are kept until the index is written again.
+#[derive(Clone, Default)]
+pub(crate) struct LazyFacetFsts {
+    fsts: Arc<RwLock<HashMap<FieldId, (OffsetDateTime, Arc<fst::Map<Vec<u8>>>)>>>,
+}
+
+impl LazyFacetFsts {
+    /// Returns the FST of the given field, building it if it isn't cached
+    /// or if the index has been updated since it was built.
+    pub(crate) fn get_or_build(
+        &self,
+        index: &Index,
+        rtxn: &RoTxn<'_>,
+        field_id: FieldId,
+    ) -> Result<Option<Arc<fst::Map<Vec<u8>>>>> {
+        let updated_at = index.updated_at(rtxn)?;
+
+        if let Some((built_at, fst)) = self.fsts.read().unwrap().get(&field_id) {
+            if *built_at == updated_at {
+                return Ok(Some(fst.clone()));
+            }
+        }
+
+        let Some(fst) = build_facet_string_fst(index, rtxn, field_id)? else {
+            self.fsts.write().unwrap().remove(&field_id);
+            return Ok(None);
+        };
+
+        let fst = Arc::new(fst);
+        self.fsts.write().unwrap().insert(field_id, (updated_at, fst.clone()));
+        Ok(Some(fst))
+    }
+}
+
+/// Builds the facet search FST of a field from the normalized facet strings.
+///
+/// Every normalized string is associated with the number of documents
+/// of its original values, as stored in the level 0 of `facet_id_string_docids`.
+///
+/// Returns `None` if the field doesn't have any normalized facet string.
+pub(crate) fn build_facet_string_fst(
+    index: &Index,
+    rtxn: &RoTxn<'_>,
+    field_id: FieldId,
+) -> Result<Option<fst::Map<Vec<u8>>>> {
+    let mut builder = fst::MapBuilder::memory();
+    let mut is_empty = true;
+    for result in index.facet_id_normalized_string_strings.prefix_iter(rtxn, &(field_id, ""))? {
+        let ((_, normalized_facet), original_facets) = result?;
+        let count = original_facets_count(index, rtxn, field_id, &original_facets)?;
+        builder.insert(normalized_facet, count)?;
+        is_empty = false;
+    }
+
+    if is_empty {
+        Ok(None)
+    } else {
+        Ok(Some(builder.into_map()))
+    }
+}
+
+/// The number of documents of the original values of a normalized facet string, as stored
+/// in the level 0 of `facet_id_string_docids`.
+pub(crate) fn original_facets_count(
+    index: &Index,
+    rtxn: &RoTxn<'_>,
+    field_id: FieldId,
+    original_facets: &BTreeSet<String>,
+) -> heed::Result<u64> {
+    let mut docids = RoaringBitmap::new();
+    for original in original_facets {
+        let key = FacetGroupKey { field_id, level: 0, left_bound: original.as_str() };
+        if let Some(FacetGroupValue { bitmap, .. }) =
+            index.facet_id_string_docids.get(rtxn, &key)?
+        {
+            docids |= bitmap;
+        }
+    }
+    Ok(docids.len())
+}
@@ -4,22 +4,55 @@ use heed::types::{Bytes, DecodeIgnore};
 use heed::{BytesDecode, RoTxn};
 use roaring::RoaringBitmap;
 
-pub use self::facet_distribution::{FacetDistribution, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::collation::{FacetCollation, InvalidFacetCollation};
+pub use self::compact_facets::{CompactFacets, CompactFacetsBuilder};
+pub use self::facet_distribution::{
+    FacetDistribution, FacetRangeBucket, FacetValuesWeights, GeoDistanceDistribution, OrderBy,
+    DEFAULT_VALUES_PER_FACET,
+};
+pub use self::facet_distribution_diff::{diff_facet_distributions, FacetDistributionDiff};
+pub use self::facet_range_search::iterate_facet_numbers_with_cumulative_counts;
 pub use self::filter::{BadGeoError, Filter};
-pub use self::search::{FacetValueHit, SearchForFacetValues};
+pub(crate) use self::lazy_fst::{build_facet_string_fst, original_facets_count, LazyFacetFsts};
+pub use self::pinned_facet_distributions::{
+    refresh_pinned_facet_distributions, CachedFacetDistribution, PinnedFacetDistribution,
+};
+pub(crate) use self::search::normalize_facet_string_for_search;
+pub use self::search::{
+    FacetRepresentativeValue, FacetSearchPage, FacetSearchStats, FacetValueGroup, FacetValueHit,
+    FacetValueMatchType, SearchForFacetValues,
+};
 use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
 use crate::heed_codec::BytesRefCodec;
 use crate::{Index, Result};
 
+mod collation;
+mod compact_facets;
 mod facet_distribution;
+mod facet_distribution_diff;
 mod facet_distribution_iter;
 mod facet_range_search;
 mod facet_sort_ascending;
 mod facet_sort_descending;
 mod filter;
 mod filter_vector;
+mod lazy_fst;
+mod pinned_facet_distributions;
 mod search;
 
+#[cfg(test)]
+thread_local! {
+    /// The number of facet trees and FSTs walked by the current thread, to check that the
+    /// walks are skipped when they cannot find anything.
+    pub(crate) static FACET_WALKS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Counts a walk of a facet tree or FST in the tests.
+fn record_facet_walk() {
+    #[cfg(test)]
+    FACET_WALKS.with(|walks| walks.set(walks.get() + 1));
+}
+
 fn facet_extreme_value<'t>(
     mut extreme_it: impl Iterator<Item = heed::Result<(RoaringBitmap, &'t [u8])>> + 't,
 ) -> Result<Option<f64>> {
@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use heed::{RoTxn, RwTxn};
+use indexmap::IndexMap;
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use super::{Filter, DEFAULT_VALUES_PER_FACET};
+use crate::order_by_map::OrderByMap;
+use crate::{Index, Result};
+
+/// A combination of a filter and facets whose distribution is computed after each batch
+/// and served to the placeholder searches requesting it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedFacetDistribution {
+    /// The filter expression, empty to count all the documents of the index.
+    pub filter: String,
+    pub facets: Vec<String>,
+}
+
+/// The distribution of a [`PinnedFacetDistribution`] as computed by the last batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedFacetDistribution {
+    pub filter: String,
+    pub facets: Vec<String>,
+    pub distribution: BTreeMap<String, IndexMap<String, u64>>,
+    pub stats: BTreeMap<String, (f64, f64)>,
+    /// The update date of the index when the distribution was computed. The distribution
+    /// is only served while the index has not been updated since.
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl CachedFacetDistribution {
+    /// Whether the distribution was computed for this filter and these facets, in any order.
+    pub fn matches<S: AsRef<str>>(&self, filter: &str, facets: &[S]) -> bool {
+        self.filter == filter
+            && self.facets.iter().map(String::as_str).collect::<BTreeSet<_>>()
+                == facets.iter().map(AsRef::as_ref).collect::<BTreeSet<_>>()
+    }
+}
+
+/// Computes the distributions of the pinned combinations of the index and stores them
+/// along with the current update date of the index.
+///
+/// Must be called once the batch is applied, right before committing the transaction.
+/// The combinations whose filter or facets are invalid are skipped. Returns `false` when
+/// the stored distributions were already up to date.
+pub fn refresh_pinned_facet_distributions(index: &Index, wtxn: &mut RwTxn<'_>) -> Result<bool> {
+    let pinned = index.pinned_facet_distributions(wtxn)?;
+    if pinned.is_empty() {
+        return Ok(index.delete_cached_facet_distributions(wtxn)?);
+    }
+
+    let updated_at = index.updated_at(wtxn)?;
+    let cached = index.cached_facet_distributions(wtxn)?;
+    let up_to_date = cached.len() == pinned.len()
+        && cached.iter().zip(&pinned).all(|(cached, pinned)| {
+            cached.updated_at == updated_at
+                && cached.filter == pinned.filter
+                && cached.facets == pinned.facets
+        });
+    if up_to_date {
+        return Ok(false);
+    }
+
+    let max_values_per_facet = index
+        .max_values_per_facet(wtxn)?
+        .map(|max| max as usize)
+        .unwrap_or(DEFAULT_VALUES_PER_FACET);
+    let sort_facet_values_by = index.sort_facet_values_by(wtxn)?;
+    let documents_ids = index.documents_ids(wtxn)?;
+
+    let mut cached = Vec::with_capacity(pinned.len());
+    for pinned in pinned {
+        let computed = compute_distribution(
+            index,
+            wtxn,
+            &pinned,
+            updated_at,
+            documents_ids.clone(),
+            max_values_per_facet,
+            &sort_facet_values_by,
+        );
+        match computed {
+            Ok(distribution) => cached.push(distribution),
+            Err(error) => {
+                let PinnedFacetDistribution { filter, facets } = pinned;
+                tracing::warn!(%filter, ?facets, %error, "Skipped a pinned facet distribution")
+            }
+        }
+    }
+
+    index.put_cached_facet_distributions(wtxn, &cached)?;
+    Ok(true)
+}
+
+/// Computes the distribution of the facets among the documents matching the filter the same
+/// way the search does for a placeholder search.
+fn compute_distribution(
+    index: &Index,
+    rtxn: &RoTxn<'_>,
+    pinned: &PinnedFacetDistribution,
+    updated_at: OffsetDateTime,
+    documents_ids: RoaringBitmap,
+    max_values_per_facet: usize,
+    sort_facet_values_by: &OrderByMap,
+) -> Result<CachedFacetDistribution> {
+    let PinnedFacetDistribution { filter, facets } = pinned;
+    let candidates = match Filter::from_str(filter)? {
+        Some(condition) => condition.evaluate(rtxn, index)? & documents_ids,
+        None => documents_ids,
+    };
+
+    let mut facet_distribution = index.facets_distribution(rtxn);
+    facet_distribution.max_values_per_facet(max_values_per_facet);
+    // like the search, the placeholder returns the distribution of all the facets
+    if facets.iter().all(|facet| facet != "*") {
+        facet_distribution.facets(facets.iter().map(|f| (f, sort_facet_values_by.get(f))));
+    }
+    let distribution = facet_distribution
+        .candidates(candidates)
+        .default_order_by(sort_facet_values_by.get("*"))
+        .execute()?;
+    let stats = facet_distribution.compute_stats()?;
+
+    Ok(CachedFacetDistribution {
+        filter: filter.clone(),
+        facets: facets.clone(),
+        distribution,
+        stats,
+        updated_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use indexmap::IndexMap;
+
+    use super::{refresh_pinned_facet_distributions, PinnedFacetDistribution};
+    use crate::index::tests::TempIndex;
+    use crate::update::Settings;
+    use crate::FilterableAttributesRule;
+
+    fn counts(values: &[(&str, u64)]) -> IndexMap<String, u64> {
+        values.iter().map(|(value, count)| (value.to_string(), *count)).collect()
+    }
+
+    #[test]
+    fn pinned_distributions_follow_the_updates() {
+        let index = TempIndex::new();
+        let mut wtxn = index.write_txn().unwrap();
+        index
+            .update_settings_using_wtxn(&mut wtxn, |settings: &mut Settings| {
+                settings.set_primary_key(S("id"));
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field(S("colour")),
+                    FilterableAttributesRule::Field(S("size")),
+                ]);
+                settings.set_pinned_facet_distributions(vec![
+                    PinnedFacetDistribution { filter: S("size = xl"), facets: vec![S("colour")] },
+                    PinnedFacetDistribution { filter: S("unknown = 1"), facets: vec![S("colour")] },
+                ]);
+            })
+            .unwrap();
+        let documents = documents!([
+            { "id": 0, "colour": "Red", "size": "xl" },
+            { "id": 1, "colour": "Blue", "size": "xl" },
+            { "id": 2, "colour": "Red", "size": "s" },
+        ]);
+        index.add_documents_using_wtxn(&mut wtxn, documents).unwrap();
+        assert!(refresh_pinned_facet_distributions(&index, &mut wtxn).unwrap());
+        // nothing changed since the last refresh
+        assert!(!refresh_pinned_facet_distributions(&index, &mut wtxn).unwrap());
+
+        // the combination filtering on an attribute that is not filterable is skipped
+        assert_eq!(index.cached_facet_distributions(&wtxn).unwrap().len(), 1);
+        let cached = index.cached_facet_distribution(&wtxn, "size = xl", &["colour"]).unwrap();
+        assert_eq!(cached.unwrap().distribution["colour"], counts(&[("Blue", 1), ("Red", 1)]));
+        assert!(index.cached_facet_distribution(&wtxn, "size = s", &["colour"]).unwrap().is_none());
+
+        // the cache is stale as soon as the index is updated and until it is refreshed
+        let documents = documents!([{ "id": 3, "colour": "Red", "size": "xl" }]);
+        index.add_documents_using_wtxn(&mut wtxn, documents).unwrap();
+        assert!(index
+            .cached_facet_distribution(&wtxn, "size = xl", &["colour"])
+            .unwrap()
+            .is_none());
+
+        refresh_pinned_facet_distributions(&index, &mut wtxn).unwrap();
+        let cached = index.cached_facet_distribution(&wtxn, "size = xl", &["colour"]).unwrap();
+        assert_eq!(cached.unwrap().distribution["colour"], counts(&[("Blue", 1), ("Red", 2)]));
+
+        index
+            .update_settings_using_wtxn(&mut wtxn, |settings: &mut Settings| {
+                settings.reset_pinned_facet_distributions()
+            })
+            .unwrap();
+        refresh_pinned_facet_distributions(&index, &mut wtxn).unwrap();
+        assert!(index.cached_facet_distributions(&wtxn).unwrap().is_empty());
+    }
+}
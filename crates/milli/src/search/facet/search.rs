@@ -1,30 +1,69 @@
 use std::cmp::{Ordering, Reverse};
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
+use std::mem;
 use std::ops::ControlFlow;
 
 use charabia::normalizer::NormalizerOption;
-use charabia::{Language, Normalize, StrDetection, Token};
+use charabia::{Language, Normalize, StrDetection, Token, TokenizerBuilder};
 use fst::automaton::{Automaton, Str};
 use fst::{IntoStreamer, Streamer};
+use levenshtein_automata::Distance;
+use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
 use tracing::error;
 
+use super::{record_facet_walk, FacetCollation};
 use crate::error::UserError;
+use crate::facet::values_recency::FacetValuesRecency;
+use crate::facet::values_weight::FacetValuesWeight;
+use crate::facet::FacetType;
 use crate::filterable_attributes_rules::{filtered_matching_patterns, matching_features};
 use crate::heed_codec::facet::{FacetGroupKey, FacetGroupValue};
+use crate::index::FacetingMode;
+use crate::score_details::ScoreDetails;
 use crate::search::build_dfa;
-use crate::{DocumentId, FieldId, OrderBy, Result, Search};
+use crate::update::new::truncate_str;
+use crate::{
+    normalize_facet, obkv_to_json, DocumentId, FacetValuesWeights, FieldId, Object, OrderBy,
+    Result, Search, MAX_FACET_VALUE_LENGTH,
+};
 
 /// The maximum number of values per facet returned by the facet search route.
 const DEFAULT_MAX_NUMBER_OF_VALUES_PER_FACET: usize = 100;
+/// The maximum number of values suggested when a facet search returns no hit.
+const MAX_FACET_SEARCH_SUGGESTIONS: usize = 5;
 
 pub struct SearchForFacetValues<'a> {
     query: Option<String>,
+    prefixes: Option<Vec<String>>,
     facet: String,
     search_query: Search<'a>,
     max_values: usize,
     is_hybrid: bool,
     locales: Option<Vec<Language>>,
+    include_normalized: bool,
+    prefix_last_token: bool,
+    min_count: u64,
+    rank_by_relevance: bool,
+    include_match_type: bool,
+    include_stats: bool,
+    include_missing: bool,
+    include_sort_keys: bool,
+    match_tokens: bool,
+    exact: bool,
+    browse_mode: bool,
+    sort_by: Option<OrderBy>,
+    representative_value: Option<FacetRepresentativeValue>,
+    after: Option<String>,
+    boosted_documents: Option<RoaringBitmap>,
+    max_typos: Option<u8>,
+    collation: Option<FacetCollation>,
+    group_by_first_char: bool,
+    attach_fields: Option<Vec<String>>,
+    min_similarity: Option<f64>,
+    accent_sensitive: bool,
+    include_zero_counts: bool,
+    suggest_corrections: bool,
 }
 
 impl<'a> SearchForFacetValues<'a> {
@@ -35,11 +74,35 @@ impl<'a> SearchForFacetValues<'a> {
     ) -> SearchForFacetValues<'a> {
         SearchForFacetValues {
             query: None,
+            prefixes: None,
             facet,
             search_query,
             max_values: DEFAULT_MAX_NUMBER_OF_VALUES_PER_FACET,
             is_hybrid,
             locales: None,
+            include_normalized: false,
+            prefix_last_token: false,
+            min_count: 0,
+            rank_by_relevance: false,
+            include_match_type: false,
+            include_stats: false,
+            include_missing: false,
+            include_sort_keys: false,
+            match_tokens: false,
+            exact: false,
+            browse_mode: false,
+            sort_by: None,
+            representative_value: None,
+            after: None,
+            boosted_documents: None,
+            max_typos: None,
+            collation: None,
+            group_by_first_char: false,
+            attach_fields: None,
+            min_similarity: None,
+            accent_sensitive: false,
+            include_zero_counts: false,
+            suggest_corrections: false,
         }
     }
 
@@ -48,6 +111,14 @@ impl<'a> SearchForFacetValues<'a> {
         self
     }
 
+    /// Matches the facet values starting with any of the given prefixes instead of a single query,
+    /// e.g. to jump to several letters. The prefixes are matched exactly, without typos, and the
+    /// facet values are returned once in the requested order. Ignored when a query is set.
+    pub fn prefixes(&mut self, prefixes: Vec<String>) -> &mut Self {
+        self.prefixes = Some(prefixes);
+        self
+    }
+
     pub fn max_values(&mut self, max: usize) -> &mut Self {
         self.max_values = max;
         self
@@ -58,6 +129,280 @@ impl<'a> SearchForFacetValues<'a> {
         self
     }
 
+    /// Attaches the normalized form of the facet value to every returned hit.
+    pub fn include_normalized(&mut self, include_normalized: bool) -> &mut Self {
+        self.include_normalized = include_normalized;
+        self
+    }
+
+    /// Only considers the last word of the query as a prefix, the previous words
+    /// must exactly match the beginning of the facet values (search-as-you-type).
+    pub fn prefix_last_token(&mut self, prefix_last_token: bool) -> &mut Self {
+        self.prefix_last_token = prefix_last_token;
+        self
+    }
+
+    /// Excludes the facet values matching fewer than `min_count` documents.
+    pub fn min_count(&mut self, min_count: u64) -> &mut Self {
+        self.min_count = min_count;
+        self
+    }
+
+    /// Orders the facet values by the relevancy of their documents instead of the `sortFacetValuesBy` setting.
+    ///
+    /// The relevancy of a facet value is the sum of the global ranking scores of its documents among the
+    /// documents returned by the search, its limit and scoring strategy must therefore be set accordingly.
+    /// The documents are ranked by the keyword search, or by the vector search when a vector is provided.
+    pub fn rank_by_relevance(&mut self, rank_by_relevance: bool) -> &mut Self {
+        self.rank_by_relevance = rank_by_relevance;
+        self
+    }
+
+    /// Attaches to every returned hit whether it matched the query as is or only after typo correction.
+    pub fn include_match_type(&mut self, include_match_type: bool) -> &mut Self {
+        self.include_match_type = include_match_type;
+        self
+    }
+
+    /// Also computes the [`FacetSearchStats`] of every facet value matching the query, not only
+    /// the returned ones. The facet values are then all scanned even once enough were collected.
+    pub fn include_stats(&mut self, include_stats: bool) -> &mut Self {
+        self.include_stats = include_stats;
+        self
+    }
+
+    /// Also counts the candidates of the search without the facet field, e.g. to find
+    /// the incomplete documents. The count is returned in [`FacetSearchPage::missing`].
+    pub fn include_missing(&mut self, include_missing: bool) -> &mut Self {
+        self.include_missing = include_missing;
+        self
+    }
+
+    /// Attaches to every returned hit the key of its lexicographic order, letting
+    /// clients sort the returned values alphabetically when they are sorted by count.
+    pub fn include_sort_keys(&mut self, include_sort_keys: bool) -> &mut Self {
+        self.include_sort_keys = include_sort_keys;
+        self
+    }
+
+    /// Matches the query against every word of the facet values instead of only their beginning,
+    /// the values being tokenized with the separators and dictionary of the index like the
+    /// documents are. `adventure` then matches `action-adventure`.
+    ///
+    /// The query must exactly be a prefix of the remaining of the value from the start of one of
+    /// its words, typos are not tolerated and [`Self::prefix_last_token`] doesn't apply.
+    pub fn match_tokens(&mut self, match_tokens: bool) -> &mut Self {
+        self.match_tokens = match_tokens;
+        self
+    }
+
+    /// Only matches the facet value whose normalized form is exactly the normalized query,
+    /// typos and prefixes are not tolerated. It is looked up in the FST of the normalized values
+    /// and takes precedence over the other ways of matching the query.
+    ///
+    /// At most one hit is returned: the original values colliding on the matched normalized
+    /// value are merged like with [`Self::representative_value`], the most frequent one being
+    /// returned unless another representative is requested.
+    pub fn exact(&mut self, exact: bool) -> &mut Self {
+        self.exact = exact;
+        self
+    }
+
+    /// Without a query, enumerates the facet values in the alphabetic order of their normalized
+    /// form, whatever the requested order. The pages contain up to `max_values` normalized values
+    /// with all their original values and the hits always have their [`FacetValueHit::sort_key`].
+    ///
+    /// The pages are walked like any other with [`Self::after`], the
+    /// [`FacetSearchPage::next_cursor`] of a full page being returned even without a cursor.
+    pub fn browse_mode(&mut self, browse_mode: bool) -> &mut Self {
+        self.browse_mode = browse_mode;
+        self
+    }
+
+    /// Orders the facet values as requested instead of following the `sortFacetValuesBy` setting.
+    pub fn sort_by(&mut self, sort_by: OrderBy) -> &mut Self {
+        self.sort_by = Some(sort_by);
+        self
+    }
+
+    /// Returns a single hit per normalized facet value instead of one per original value, e.g.
+    /// for `Café` and `cafe`. The hit counts the documents of all the colliding values and its
+    /// `value` is the one chosen by the given [`FacetRepresentativeValue`].
+    pub fn representative_value(
+        &mut self,
+        representative_value: FacetRepresentativeValue,
+    ) -> &mut Self {
+        self.representative_value = Some(representative_value);
+        self
+    }
+
+    /// Pages through the facet values with a cursor: only the values after the given cursor are
+    /// returned and the [`FacetSearchPage::next_cursor`] of a full page must be given to fetch the
+    /// next one. An empty cursor starts from the first facet value.
+    ///
+    /// Unlike an offset, a cursor is a position in the lexicographic order of the values, the pages
+    /// therefore stay consistent when the facet values change between two requests. This requires
+    /// the facet values to be sorted lexicographically and not ranked by relevance.
+    pub fn after(&mut self, cursor: impl Into<String>) -> &mut Self {
+        self.after = Some(cursor.into());
+        self
+    }
+
+    /// Returns the facet values of the given documents first, e.g. the top hits of a prior search,
+    /// so that the suggestions match what is displayed. The values are counted as usual and both
+    /// the boosted and the other values keep the requested order. Ignored while browsing.
+    pub fn boost_documents(&mut self, documents: RoaringBitmap) -> &mut Self {
+        self.boosted_documents = Some(documents);
+        self
+    }
+
+    /// Caps the number of typos tolerated on the query whatever its length, `0` disabling
+    /// the typo tolerance. The typo settings of the index and of the facet still apply.
+    pub fn max_typos(&mut self, max_typos: u8) -> &mut Self {
+        self.max_typos = Some(max_typos);
+        self
+    }
+
+    /// Orders the returned values alphabetically following the conventions of a language
+    /// when they are sorted alphabetically, e.g. `å` after `z` in Swedish. The values are
+    /// still collected in the order of their code points, it has no effect when paging or
+    /// browsing, or when the values are boosted or ranked by relevance.
+    pub fn collation(&mut self, collation: FacetCollation) -> &mut Self {
+        self.collation = Some(collation);
+        self
+    }
+
+    /// Returns the facet values grouped under the first character of their normalized form in
+    /// [`FacetSearchPage::groups`] instead of [`FacetSearchPage::hits`], e.g. for an A-Z navigation.
+    /// The values starting with a character that isn't a letter are grouped under `#`.
+    pub fn group_by_first_char(&mut self, group_by_first_char: bool) -> &mut Self {
+        self.group_by_first_char = group_by_first_char;
+        self
+    }
+
+    /// Attaches these fields of a representative document to every returned hit, e.g. the logo
+    /// of a brand. The representative document of a facet value is the one with the lowest
+    /// internal id, usually the first indexed, among the documents matching the search with this
+    /// value. Only the displayed fields are attached, the other ones are ignored.
+    pub fn attach_fields(&mut self, fields: Vec<String>) -> &mut Self {
+        self.attach_fields = Some(fields);
+        self
+    }
+
+    /// Matches the facet values by their similarity to the query instead of the typo tolerance:
+    /// only the values whose [`FacetValueHit::similarity`] is at least `min_similarity`, between
+    /// `0.0` and `1.0`, are returned, the most similar first unless ranked by relevance.
+    ///
+    /// The similarity is the Jaro-Winkler similarity between the normalized query and the
+    /// beginning of the normalized value of the same length. It is computed over the values
+    /// within two typos of the query, whatever the typo settings of the index.
+    pub fn min_similarity(&mut self, min_similarity: f64) -> &mut Self {
+        self.min_similarity = Some(min_similarity);
+        self
+    }
+
+    /// Only returns the values matching the accents of the query, even when the field is
+    /// normalized lossily: `cafe` then matches `cafe` but not `café`.
+    ///
+    /// The values matched by the query are filtered by comparing their original form, that keeps
+    /// the accents, to the query normalized the same way. The query must then be a prefix of the
+    /// value, or of one of its words when matching the tokens, and no typo is tolerated.
+    pub fn accent_sensitive(&mut self, accent_sensitive: bool) -> &mut Self {
+        self.accent_sensitive = accent_sensitive;
+        self
+    }
+
+    /// Also returns the facet values matching the query that none of the documents of the search
+    /// are associated with, with a count of `0`, so that the returned values don't depend on the
+    /// filter or the search query. The `min_count` must be `0` for them to be returned.
+    pub fn include_zero_counts(&mut self, include_zero_counts: bool) -> &mut Self {
+        self.include_zero_counts = include_zero_counts;
+        self
+    }
+
+    /// Suggests the facet values closest to the query when the search returns no hit, to offer
+    /// "did you mean" corrections, see [`FacetSearchPage::suggestions`].
+    ///
+    /// The suggested values are within two typos of the whole query, whatever the typo settings
+    /// of the index, and associated with at least one of the documents of the search. The closest
+    /// values are suggested first, then the ones of the most documents.
+    pub fn suggest_corrections(&mut self, suggest_corrections: bool) -> &mut Self {
+        self.suggest_corrections = suggest_corrections;
+        self
+    }
+
+    /// Counts the facet values as if none of them were selected, by dropping the clauses of the
+    /// filter of the search only constraining the searched facet, see [`crate::Filter::without_field`].
+    /// This lets the values be added to a selection combined with `OR` (disjunctive faceting).
+    ///
+    /// The filter of the search is rewritten right away, setting it back to `false` has no effect.
+    pub fn disjunctive_on_self(&mut self, disjunctive_on_self: bool) -> &mut Self {
+        if disjunctive_on_self {
+            let filter = self.search_query.filter.take();
+            self.search_query.filter = filter.and_then(|filter| filter.without_field(&self.facet));
+        }
+        self
+    }
+
+    /// Whether the facet values are enumerated as requested with [`Self::browse_mode`].
+    fn is_browsing(&self) -> bool {
+        self.browse_mode
+            && self.query.as_deref().is_none_or(str::is_empty)
+            && self.prefixes.is_none()
+    }
+
+    /// Whether corrections of the query are suggested as requested with [`Self::suggest_corrections`].
+    fn suggests_corrections(&self) -> bool {
+        self.suggest_corrections
+            && self.query.as_deref().is_some_and(|query| !query.is_empty())
+            && !self.is_browsing()
+    }
+
+    /// The representative of the original values colliding on a normalized value, if they are
+    /// merged into a single hit, see [`Self::exact`].
+    fn collapsed_representative_value(&self) -> Option<FacetRepresentativeValue> {
+        match self.representative_value {
+            None if self.exact && self.query.is_some() => {
+                Some(FacetRepresentativeValue::MostFrequent)
+            }
+            representative_value => representative_value,
+        }
+    }
+
+    /// Whether the query is matched as requested with [`Self::min_similarity`].
+    fn matches_by_similarity(&self) -> bool {
+        self.min_similarity.is_some() && self.query.is_some() && !self.is_browsing()
+    }
+
+    /// Whether the original value matches the accents of the query as requested with
+    /// [`Self::accent_sensitive`], always true otherwise.
+    fn matches_accents(&self, original: &str) -> bool {
+        let query = match self.query.as_deref() {
+            Some(query) if self.accent_sensitive && !self.is_browsing() => query,
+            _ => return true,
+        };
+        // The original values are stored normalized without losing the accents, which are
+        // decomposed after their letter: `cafe` is a prefix of `café` but is followed by an accent.
+        let query = normalize_facet(query);
+        let is_prefix_of = |value: &str| {
+            value
+                .strip_prefix(query.as_str())
+                .is_some_and(|rest| !rest.starts_with(is_combining_mark))
+        };
+        is_prefix_of(original)
+            || (self.match_tokens && original.split_whitespace().any(is_prefix_of))
+    }
+
+    /// The order requested with [`Self::sort_by`], or the `sortFacetValuesBy` setting of the facet.
+    fn order_by(&self) -> Result<OrderBy> {
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+        match self.sort_by {
+            Some(sort_by) => Ok(sort_by),
+            None => Ok(index.sort_facet_values_by(rtxn)?.get(&self.facet)),
+        }
+    }
+
     fn one_original_value_of(
         &self,
         field_id: FieldId,
@@ -71,13 +416,29 @@ impl<'a> SearchForFacetValues<'a> {
     }
 
     pub fn execute(&self) -> Result<Vec<FacetValueHit>> {
+        self.execute_page().map(|page| page.hits)
+    }
+
+    /// Same as [`Self::execute`] but also returns the stats of the matching
+    /// facet values when they were requested with [`Self::include_stats`].
+    pub fn execute_with_stats(&self) -> Result<(Vec<FacetValueHit>, Option<FacetSearchStats>)> {
+        self.execute_page().map(|page| (page.hits, page.stats))
+    }
+
+    /// Same as [`Self::execute_with_stats`] but also returns the cursor of the next page
+    /// when paging with [`Self::after`].
+    pub fn execute_page(&self) -> Result<FacetSearchPage> {
         let index = self.search_query.index;
         let rtxn = self.search_query.rtxn;
 
+        // The settings are the source of truth: the facet databases may still contain the
+        // values of a field that is no longer filterable until the documents are reindexed.
         let filterable_attributes_rules = index.filterable_attributes_rules(rtxn)?;
         let matched_rule = matching_features(&self.facet, &filterable_attributes_rules);
         let is_facet_searchable =
             matched_rule.is_some_and(|(_, features)| features.is_facet_searchable());
+        // The queries are normalized like the facet values of the field.
+        let lossy = matched_rule.is_none_or(|(_, features)| features.is_lossy_normalization());
 
         if !is_facet_searchable {
             let matching_field_names =
@@ -99,41 +460,337 @@ impl<'a> SearchForFacetValues<'a> {
             .into());
         };
 
+        // The facet values are truncated to this length, longer queries cannot match any of them.
+        let mut queries = self.query.iter().chain(self.prefixes.iter().flatten());
+        if let Some(query) = queries.find(|query| query.len() > MAX_FACET_VALUE_LENGTH) {
+            return Err(UserError::FacetSearchQueryTooLong { length: query.len() }.into());
+        }
+
+        let after = match self.after.as_deref() {
+            None | Some("") => None,
+            Some(after) => match serde_json::from_str::<FacetSearchCursor>(after) {
+                Ok(cursor) => Some(cursor),
+                Err(_) => return Err(UserError::InvalidFacetSearchCursor(after.to_string()).into()),
+            },
+        };
+        let is_paging = self.after.is_some();
+        if is_paging
+            && !self.is_browsing()
+            && (self.rank_by_relevance
+                || self.boosted_documents.is_some()
+                || self.min_similarity.is_some()
+                || self.order_by()? != OrderBy::Lexicographic)
+        {
+            return Err(UserError::InvalidFacetSearchCursorOrder.into());
+        }
+
+        let has_vector_search = self.is_hybrid
+            || self
+                .search_query
+                .semantic
+                .as_ref()
+                .and_then(|semantic| semantic.vector.as_ref())
+                .is_some();
+
         let fields_ids_map = index.fields_ids_map(rtxn)?;
-        let Some(fid) = fields_ids_map.id(&self.facet) else {
-            return Ok(Vec::new());
+        let field_id = fields_ids_map.id(&self.facet);
+        let no_values = || -> Result<FacetSearchPage> {
+            let missing = if self.include_missing {
+                let candidates = self.search_query.execute_for_candidates(has_vector_search)?;
+                Some(self.count_missing(field_id, &candidates)?)
+            } else {
+                None
+            };
+            Ok(FacetSearchPage {
+                hits: Vec::new(),
+                groups: self.group_by_first_char.then(Vec::new),
+                stats: self.include_stats.then(FacetSearchStats::default),
+                missing,
+                next_cursor: None,
+                suggestions: self.suggests_corrections().then(Vec::new),
+            })
+        };
+        let Some(fid) = field_id else {
+            return no_values();
         };
 
+        let facet_types: Vec<_> = index
+            .facet_fields_with_types(rtxn)?
+            .into_iter()
+            .filter_map(|(field_id, facet_type)| (field_id == fid).then_some(facet_type))
+            .collect();
+        if facet_types == [FacetType::Number] {
+            return Err(
+                UserError::InvalidFacetSearchNumericFacet { field: self.facet.clone() }.into()
+            );
+        }
+
+        let lazy_fst;
+        // the values are matched on the keys of the map, its outputs are only read as counts
         let fst = match self.search_query.index.facet_id_string_fst.get(rtxn, &fid)? {
-            Some(fst) => fst,
-            None => return Ok(Vec::new()),
+            Some(fst) => fst::Set::from(fst.into_fst()),
+            None if index.faceting_mode(rtxn)? == FacetingMode::Lazy => {
+                lazy_fst = match index.lazy_facet_fsts.get_or_build(index, rtxn, fid)? {
+                    Some(fst) => fst,
+                    None => return no_values(),
+                };
+                fst::Set::new(lazy_fst.as_fst().as_bytes())?
+            }
+            None => return no_values(),
         };
 
-        let search_candidates = self.search_query.execute_for_candidates(
-            self.is_hybrid
-                || self
-                    .search_query
-                    .semantic
-                    .as_ref()
-                    .and_then(|semantic| semantic.vector.as_ref())
-                    .is_some(),
-        )?;
-
-        let mut results = match index.sort_facet_values_by(rtxn)?.get(&self.facet) {
-            OrderBy::Lexicographic => ValuesCollection::by_lexicographic(self.max_values),
-            OrderBy::Count => ValuesCollection::by_count(self.max_values),
+        let (search_candidates, document_scores) = if self.rank_by_relevance {
+            // reuse the scores of the ranked documents of the search instead of only its candidates
+            let result = self.search_query.execute()?;
+            let document_scores: HashMap<_, _> = result
+                .documents_ids
+                .iter()
+                .copied()
+                .zip(
+                    result
+                        .document_scores
+                        .iter()
+                        .map(|details| ScoreDetails::global_score(details.iter())),
+                )
+                .collect();
+            let candidates = if has_vector_search {
+                self.search_query.execute_for_candidates(has_vector_search)?
+            } else {
+                result.candidates
+            };
+            (candidates, Some(document_scores))
+        } else {
+            (self.search_query.execute_for_candidates(has_vector_search)?, None)
         };
+        let missing = if self.include_missing {
+            Some(self.count_missing(Some(fid), &search_candidates)?)
+        } else {
+            None
+        };
+        // no facet value can match when the search has no candidates, e.g. a filter matching nothing
+        if search_candidates.is_empty() && !self.include_zero_counts {
+            return Ok(FacetSearchPage {
+                hits: Vec::new(),
+                groups: self.group_by_first_char.then(Vec::new),
+                stats: self.include_stats.then(FacetSearchStats::default),
+                missing,
+                next_cursor: None,
+                suggestions: self.suggests_corrections().then(Vec::new),
+            });
+        }
 
+        let browse = self.is_browsing();
+        let new_values = |document_scores: Option<HashMap<DocumentId, f64>>| -> Result<_> {
+            Ok(match document_scores {
+                // the size of the page is counted in normalized values while browsing
+                _ if browse => ValuesCollection::by_lexicographic(usize::MAX),
+                Some(document_scores) => {
+                    ValuesCollection::by_relevance(self.max_values, document_scores)
+                }
+                None if self.matches_by_similarity() => {
+                    ValuesCollection::by_similarity(self.max_values)
+                }
+                None => match self.order_by()? {
+                    OrderBy::Lexicographic => ValuesCollection::by_lexicographic(self.max_values),
+                    // the facet search has no ranked documents to sum the scores of
+                    OrderBy::Count | OrderBy::ScoreSum => {
+                        ValuesCollection::by_count(self.max_values)
+                    }
+                    OrderBy::Weighted(weights) => {
+                        ValuesCollection::by_weights(self.max_values, weights)
+                    }
+                    OrderBy::Recency => ValuesCollection::by_recency(
+                        self.max_values,
+                        FacetValuesRecency::new(index, rtxn, fid)?,
+                    ),
+                    OrderBy::Weight => ValuesCollection::by_value_weight(
+                        self.max_values,
+                        FacetValuesWeight::new(index, rtxn, fid)?,
+                    ),
+                },
+            })
+        };
+        // the values of the boosted documents are collected apart to be returned first
+        let boosted = match &self.boosted_documents {
+            Some(documents) if !browse => {
+                Some((documents.clone(), new_values(document_scores.clone())?))
+            }
+            _ => None,
+        };
+        let values = new_values(document_scores)?;
+        let mut results = FacetValuesCollector::new(values, self.include_stats, after, boosted);
+        // the FST associates every normalized value with the number of documents of its original values
+        results.value_counts = Some(fst::Map::new(fst.as_fst().as_bytes())?);
+
+        record_facet_walk();
+        let mut browse_page_is_full = false;
         match self.query.as_ref() {
+            _ if browse => {
+                // The FST of the normalized values is sorted, the pages are read from it directly.
+                let mut stream = results.skip_before_cursor(fst.range()).into_stream();
+                let mut normalized_values = 0;
+                while normalized_values < self.max_values {
+                    let Some(facet_value) = stream.next() else { break };
+                    let value = std::str::from_utf8(facet_value)?;
+                    let hits_before = results.values.len();
+                    let _ = self.fetch_original_facets_using_normalized(
+                        fid,
+                        value,
+                        value,
+                        &search_candidates,
+                        &mut results,
+                    )?;
+                    if results.values.len() > hits_before {
+                        normalized_values += 1;
+                    }
+                }
+                browse_page_is_full = normalized_values >= self.max_values;
+            }
             Some(query) => {
-                let query = normalize_facet_string(query, self.locales.as_deref());
+                let query =
+                    normalize_facet_string_for_search(query, self.locales.as_deref(), lossy);
                 let query = query.as_ref();
 
                 let authorize_typos = self.search_query.index.authorize_typos(rtxn)?;
                 let field_authorizes_typos =
                     !self.search_query.index.exact_attributes_ids(rtxn)?.contains(&fid);
 
-                if authorize_typos && field_authorizes_typos {
+                let last_token = match query.rsplit_once(char::is_whitespace) {
+                    Some((_, last_token)) if self.prefix_last_token => Some(last_token),
+                    _ => None,
+                };
+
+                if self.exact {
+                    if fst.contains(query) {
+                        let _ = self.fetch_original_facets_using_normalized(
+                            fid,
+                            query,
+                            query,
+                            &search_candidates,
+                            &mut results,
+                        )?;
+                    }
+                } else if let Some(min_similarity) = self.min_similarity {
+                    // The broadest typo tolerance prefilters the values to compare to the query.
+                    let is_prefix = true;
+                    let automaton = build_dfa(query, 2, is_prefix);
+                    let mut stream =
+                        results.skip_before_cursor(fst.search(automaton)).into_stream();
+                    while let Some(facet_value) = stream.next() {
+                        let value = std::str::from_utf8(facet_value)?;
+                        if facet_value_similarity(query, value) < min_similarity {
+                            continue;
+                        }
+                        if self
+                            .fetch_original_facets_using_normalized(
+                                fid,
+                                value,
+                                query,
+                                &search_candidates,
+                                &mut results,
+                            )?
+                            .is_break()
+                        {
+                            break;
+                        }
+                    }
+                } else if self.match_tokens {
+                    let separators = index.allowed_separators(rtxn)?;
+                    let separators: Option<Vec<_>> =
+                        separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+                    let dictionary = index.dictionary(rtxn)?;
+                    let dictionary: Option<Vec<_>> =
+                        dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+                    let mut builder = TokenizerBuilder::default();
+                    if let Some(ref separators) = separators {
+                        builder.separators(separators);
+                    }
+                    if let Some(ref dictionary) = dictionary {
+                        builder.words_dict(dictionary);
+                    }
+                    let tokenizer = builder.build();
+
+                    // Every facet value must be tokenized, the whole FST is scanned.
+                    let mut stream = results.skip_before_cursor(fst.range()).into_stream();
+                    while let Some(facet_value) = stream.next() {
+                        let value = std::str::from_utf8(facet_value)?;
+                        let is_match = value.starts_with(query)
+                            || tokenizer.tokenize(value).any(|token| {
+                                !token.is_separator()
+                                    && value[token.byte_start..].starts_with(query)
+                            });
+                        if !is_match {
+                            continue;
+                        }
+
+                        if self
+                            .fetch_original_facets_using_normalized(
+                                fid,
+                                value,
+                                query,
+                                &search_candidates,
+                                &mut results,
+                            )?
+                            .is_break()
+                        {
+                            break;
+                        }
+                    }
+                } else if let Some(last_token) = last_token {
+                    // The leading words must be exactly contained in the facet values,
+                    // typos are only allowed on the last word that is used as a prefix.
+                    let head = &query[..query.len() - last_token.len()];
+                    let exact_words_fst = self.search_query.index.exact_words(rtxn)?;
+                    let is_exact_word = exact_words_fst.is_some_and(|fst| fst.contains(last_token));
+                    let mut max_typos = 0;
+                    if authorize_typos && field_authorizes_typos && !is_exact_word {
+                        let one_typo = self.search_query.index.min_word_len_one_typo(rtxn)?;
+                        let two_typos = self.search_query.index.min_word_len_two_typos(rtxn)?;
+                        if last_token.len() >= two_typos as usize {
+                            max_typos = 2;
+                        } else if last_token.len() >= one_typo as usize {
+                            max_typos = 1;
+                        }
+                    }
+                    if let Some(cap) = self.max_typos {
+                        max_typos = max_typos.min(cap);
+                    }
+
+                    let is_prefix = true;
+                    let last_token_automaton = build_dfa(last_token, max_typos, is_prefix);
+                    let automaton = Str::new(head).starts_with();
+                    let mut stream =
+                        results.skip_before_cursor(fst.search(automaton)).into_stream();
+                    while let Some(facet_value) = stream.next() {
+                        let remaining = &facet_value[head.len()..];
+                        let mut state = last_token_automaton.start();
+                        for &byte in remaining {
+                            if last_token_automaton.is_match(&state)
+                                || !last_token_automaton.can_match(&state)
+                            {
+                                break;
+                            }
+                            state = last_token_automaton.accept(&state, byte);
+                        }
+                        if !last_token_automaton.is_match(&state) {
+                            continue;
+                        }
+
+                        let value = std::str::from_utf8(facet_value)?;
+                        if self
+                            .fetch_original_facets_using_normalized(
+                                fid,
+                                value,
+                                query,
+                                &search_candidates,
+                                &mut results,
+                            )?
+                            .is_break()
+                        {
+                            break;
+                        }
+                    }
+                } else if authorize_typos && field_authorizes_typos {
                     let exact_words_fst = self.search_query.index.exact_words(rtxn)?;
                     if exact_words_fst.is_some_and(|fst| fst.contains(query)) {
                         if fst.contains(query) {
@@ -149,16 +806,20 @@ impl<'a> SearchForFacetValues<'a> {
                         let one_typo = self.search_query.index.min_word_len_one_typo(rtxn)?;
                         let two_typos = self.search_query.index.min_word_len_two_typos(rtxn)?;
 
-                        let is_prefix = true;
-                        let automaton = if query.len() < one_typo as usize {
-                            build_dfa(query, 0, is_prefix)
+                        let max_typos = if query.len() < one_typo as usize {
+                            0
                         } else if query.len() < two_typos as usize {
-                            build_dfa(query, 1, is_prefix)
+                            1
                         } else {
-                            build_dfa(query, 2, is_prefix)
+                            2
                         };
+                        let max_typos = self.max_typos.map_or(max_typos, |cap| max_typos.min(cap));
+
+                        let is_prefix = true;
+                        let automaton = build_dfa(query, max_typos, is_prefix);
 
-                        let mut stream = fst.search(automaton).into_stream();
+                        let mut stream =
+                            results.skip_before_cursor(fst.search(automaton)).into_stream();
                         while let Some(facet_value) = stream.next() {
                             let value = std::str::from_utf8(facet_value)?;
                             if self
@@ -177,7 +838,8 @@ impl<'a> SearchForFacetValues<'a> {
                     }
                 } else {
                     let automaton = Str::new(query).starts_with();
-                    let mut stream = fst.search(automaton).into_stream();
+                    let mut stream =
+                        results.skip_before_cursor(fst.search(automaton)).into_stream();
                     while let Some(facet_value) = stream.next() {
                         let value = std::str::from_utf8(facet_value)?;
                         if self
@@ -195,17 +857,94 @@ impl<'a> SearchForFacetValues<'a> {
                     }
                 }
             }
+            None if self.prefixes.is_some() => {
+                let prefixes = self.prefixes.iter().flatten().map(|prefix| {
+                    normalize_facet_string_for_search(prefix, self.locales.as_deref(), lossy)
+                });
+                // The prefixes starting with a shorter one are skipped, the remaining ones match
+                // disjoint ranges of the sorted FST that are walked in order, once each.
+                let mut prefixes: Vec<_> = prefixes.collect();
+                prefixes.sort_unstable();
+                prefixes.dedup();
+                let mut disjoint_prefixes: Vec<String> = Vec::new();
+                for prefix in prefixes {
+                    if !disjoint_prefixes
+                        .last()
+                        .is_some_and(|last| prefix.starts_with(last.as_str()))
+                    {
+                        disjoint_prefixes.push(prefix);
+                    }
+                }
+
+                'prefixes: for prefix in &disjoint_prefixes {
+                    let automaton = Str::new(prefix).starts_with();
+                    let mut stream =
+                        results.skip_before_cursor(fst.search(automaton)).into_stream();
+                    while let Some(facet_value) = stream.next() {
+                        let value = std::str::from_utf8(facet_value)?;
+                        if self
+                            .fetch_original_facets_using_normalized(
+                                fid,
+                                value,
+                                prefix,
+                                &search_candidates,
+                                &mut results,
+                            )?
+                            .is_break()
+                        {
+                            break 'prefixes;
+                        }
+                    }
+                }
+            }
+            None if self.representative_value.is_some() || is_paging => {
+                // The colliding values are only known from the normalized values of the FST,
+                // which are also the order of the cursors.
+                let mut stream = results.skip_before_cursor(fst.range()).into_stream();
+                while let Some(facet_value) = stream.next() {
+                    let value = std::str::from_utf8(facet_value)?;
+                    if self
+                        .fetch_original_facets_using_normalized(
+                            fid,
+                            value,
+                            value,
+                            &search_candidates,
+                            &mut results,
+                        )?
+                        .is_break()
+                    {
+                        break;
+                    }
+                }
+            }
             None => {
                 let prefix = FacetGroupKey { field_id: fid, level: 0, left_bound: "" };
                 for result in index.facet_id_string_docids.prefix_iter(rtxn, &prefix)? {
                     let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) =
                         result?;
                     let count = search_candidates.intersection_len(&bitmap);
-                    if count != 0 {
+                    if (count != 0 || self.include_zero_counts) && count >= self.min_count {
                         let value = self
                             .one_original_value_of(fid, left_bound, bitmap.min().unwrap())?
                             .unwrap_or_else(|| left_bound.to_string());
-                        if results.insert(FacetValueHit { value, count }).is_break() {
+                        let normalized_value = self.include_normalized.then(|| {
+                            normalize_facet_string_for_search(
+                                left_bound,
+                                self.locales.as_deref(),
+                                lossy,
+                            )
+                        });
+                        let sort_key = self.include_sort_keys.then(|| left_bound.to_string());
+                        let hit = FacetValueHit {
+                            value,
+                            count,
+                            normalized_value,
+                            match_type: None,
+                            sort_key,
+                            attached_fields: None,
+                            similarity: None,
+                        };
+                        if results.insert(hit, &bitmap, &search_candidates).is_break() {
                             break;
                         }
                     }
@@ -213,22 +952,147 @@ impl<'a> SearchForFacetValues<'a> {
             }
         }
 
-        Ok(results.into_sorted_vec())
+        let (mut hits, stats, last_position) = results.finish();
+        let suggestions = match self.query.as_deref() {
+            Some(query) if self.suggests_corrections() && hits.is_empty() => {
+                let query =
+                    normalize_facet_string_for_search(query, self.locales.as_deref(), lossy);
+                Some(self.closest_facet_values(fid, &fst, &query, &search_candidates)?)
+            }
+            _ => None,
+        };
+        if let Some(fields) = &self.attach_fields {
+            self.attach_representative_fields(fid, fields, &mut hits, &search_candidates)?;
+        }
+        if let Some(collation) = self.collation {
+            if !browse
+                && !is_paging
+                && !self.rank_by_relevance
+                && self.boosted_documents.is_none()
+                && self.order_by()? == OrderBy::Lexicographic
+            {
+                hits.sort_by_cached_key(|hit| (collation.sort_key(&hit.value), hit.value.clone()));
+            }
+        }
+        // A page that isn't full is the last one, the browsed pages are counted in normalized values.
+        let page_is_full = if browse { browse_page_is_full } else { hits.len() >= self.max_values };
+        let next_cursor = match last_position {
+            Some(position) if (is_paging || browse) && page_is_full => {
+                Some(serde_json::to_string(&position).unwrap())
+            }
+            _ => None,
+        };
+        let groups = if self.group_by_first_char {
+            Some(self.group_hits_by_first_char(mem::take(&mut hits), lossy))
+        } else {
+            None
+        };
+        Ok(FacetSearchPage { hits, groups, stats, missing, next_cursor, suggestions })
+    }
+
+    /// Returns the original facet values closest to the normalized query,
+    /// see [`Self::suggest_corrections`].
+    fn closest_facet_values(
+        &self,
+        fid: FieldId,
+        fst: &fst::Set<&[u8]>,
+        query: &str,
+        search_candidates: &RoaringBitmap,
+    ) -> Result<Vec<String>> {
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+
+        let automaton = build_dfa(query, 2, false);
+        let mut stream = fst.search(&automaton).into_stream();
+        let mut closest = Vec::new();
+        while let Some(facet_value) = stream.next() {
+            let Distance::Exact(distance) = automaton.eval(facet_value) else { continue };
+            let normalized = std::str::from_utf8(facet_value)?;
+            let Some(original_strings) =
+                index.facet_id_normalized_string_strings.get(rtxn, &(fid, normalized))?
+            else {
+                continue;
+            };
+            // The colliding values are suggested once, under their first original value.
+            let mut count = 0;
+            for original in &original_strings {
+                let key = FacetGroupKey { field_id: fid, level: 0, left_bound: original.as_str() };
+                if let Some(FacetGroupValue { bitmap, .. }) =
+                    index.facet_id_string_docids.get(rtxn, &key)?
+                {
+                    count += search_candidates.intersection_len(&bitmap);
+                }
+            }
+            if let Some(original) = original_strings.into_iter().next().filter(|_| count != 0) {
+                closest.push((distance, Reverse(count), original));
+            }
+        }
+
+        closest.sort_unstable();
+        closest.truncate(MAX_FACET_SEARCH_SUGGESTIONS);
+        Ok(closest.into_iter().map(|(_, _, original)| original).collect())
+    }
+
+    /// Groups the hits under the first character of their normalized value, keeping their order
+    /// in each group. The groups are sorted by character, the `#` group coming first.
+    ///
+    /// The hits sorted lexicographically are read from the facet databases in the order of their
+    /// normalized value, the values of a group are then contiguous and the groups already sorted.
+    fn group_hits_by_first_char(
+        &self,
+        hits: Vec<FacetValueHit>,
+        lossy: bool,
+    ) -> Vec<FacetValueGroup> {
+        let mut groups: Vec<FacetValueGroup> = Vec::new();
+        for hit in hits {
+            let normalized =
+                normalize_facet_string_for_search(&hit.value, self.locales.as_deref(), lossy);
+            let key = match normalized.chars().next() {
+                Some(c) if c.is_alphabetic() => c.to_string(),
+                _ => String::from("#"),
+            };
+            let group = match groups.iter().rposition(|group| group.key == key) {
+                Some(position) => &mut groups[position],
+                None => {
+                    groups.push(FacetValueGroup { key, count: 0, values: Vec::new() });
+                    groups.last_mut().unwrap()
+                }
+            };
+            group.count += 1;
+            group.values.push(hit);
+        }
+        groups.sort_by(|a, b| a.key.cmp(&b.key));
+        groups
+    }
+
+    /// Counts the candidates that don't have the field, as the `NOT field EXISTS` filter:
+    /// the candidates whose value is `null` or an empty array are not counted.
+    fn count_missing(&self, field_id: Option<FieldId>, candidates: &RoaringBitmap) -> Result<u64> {
+        let Some(field_id) = field_id else {
+            return Ok(candidates.len());
+        };
+        let index = self.search_query.index;
+        let exists = index.exists_faceted_documents_ids(self.search_query.rtxn, field_id)?;
+        Ok(candidates.difference_len(&exists))
     }
 
     fn fetch_original_facets_using_normalized(
         &self,
         fid: FieldId,
-        value: &str,
+        normalized: &str,
         query: &str,
         search_candidates: &RoaringBitmap,
-        results: &mut ValuesCollection,
+        results: &mut FacetValuesCollector,
     ) -> Result<ControlFlow<()>> {
         let index = self.search_query.index;
         let rtxn = self.search_query.rtxn;
 
+        if results.cannot_collect(normalized) {
+            return Ok(ControlFlow::Continue(()));
+        }
+
         let database = index.facet_id_normalized_string_strings;
-        let key = (fid, value);
+        let key = (fid, normalized);
         let original_strings = match database.get(rtxn, &key)? {
             Some(original_strings) => original_strings,
             None => {
@@ -236,7 +1100,11 @@ impl<'a> SearchForFacetValues<'a> {
                 return Ok(ControlFlow::Continue(()));
             }
         };
+        let mut colliding_values = Vec::new();
         for original in original_strings {
+            if !results.is_after_cursor(normalized, &original) || !self.matches_accents(&original) {
+                continue;
+            }
             let key = FacetGroupKey { field_id: fid, level: 0, left_bound: original.as_str() };
             let docids = match index.facet_id_string_docids.get(rtxn, &key)? {
                 Some(FacetGroupValue { bitmap, .. }) => bitmap,
@@ -246,18 +1114,149 @@ impl<'a> SearchForFacetValues<'a> {
                 }
             };
             let count = search_candidates.intersection_len(&docids);
-            if count != 0 {
-                let value = self
-                    .one_original_value_of(fid, &original, docids.min().unwrap())?
-                    .unwrap_or_else(|| query.to_string());
-                if results.insert(FacetValueHit { value, count }).is_break() {
+            if count == 0 && !self.include_zero_counts {
+                continue;
+            }
+            if self.collapsed_representative_value().is_some() {
+                colliding_values.push((original, docids, count));
+            } else if count >= self.min_count {
+                let hit =
+                    self.facet_value_hit(fid, &original, &docids, count, normalized, query)?;
+                let position = FacetSearchCursor { normalized: normalized.to_string(), original };
+                if results.insert_at(position, hit, &docids, search_candidates).is_break() {
                     break;
                 }
             }
         }
 
-        Ok(ControlFlow::Continue(()))
+        // The ties are explicitly broken on the original values so that the representative
+        // doesn't depend on the order in which the colliding values were stored.
+        let representative = match self.collapsed_representative_value() {
+            None => return Ok(ControlFlow::Continue(())),
+            Some(FacetRepresentativeValue::First) => {
+                colliding_values.iter().min_by(|(left, _, _), (right, _, _)| left.cmp(right))
+            }
+            Some(FacetRepresentativeValue::MostFrequent) => {
+                colliding_values.iter().min_by(|(left, _, left_count), (right, _, right_count)| {
+                    Reverse(left_count).cmp(&Reverse(right_count)).then_with(|| left.cmp(right))
+                })
+            }
+        };
+        let Some((original, docids, _)) = representative else {
+            return Ok(ControlFlow::Continue(()));
+        };
+
+        let mut all_docids = RoaringBitmap::new();
+        for (_, docids, _) in &colliding_values {
+            all_docids |= docids;
+        }
+        let count = search_candidates.intersection_len(&all_docids);
+        if count < self.min_count {
+            return Ok(ControlFlow::Continue(()));
+        }
+        let hit = self.facet_value_hit(fid, original, docids, count, normalized, query)?;
+        // The next page starts after all the colliding values.
+        let (last_original, _, _) = colliding_values.last().unwrap();
+        let position = FacetSearchCursor {
+            normalized: normalized.to_string(),
+            original: last_original.clone(),
+        };
+        Ok(results.insert_at(position, hit, &all_docids, search_candidates))
+    }
+
+    /// Attaches the fields of the representative document of each hit to it,
+    /// see [`Self::attach_fields`].
+    fn attach_representative_fields(
+        &self,
+        fid: FieldId,
+        fields: &[String],
+        hits: &mut [FacetValueHit],
+        search_candidates: &RoaringBitmap,
+    ) -> Result<()> {
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+
+        let fields_ids_map = index.fields_ids_map(rtxn)?;
+        let displayed_fields_ids = index.displayed_fields_ids(rtxn)?;
+        let fields_ids: Vec<_> = fields
+            .iter()
+            .filter_map(|field| fields_ids_map.id(field))
+            .filter(|field_id| {
+                displayed_fields_ids.as_ref().is_none_or(|ids| ids.contains(field_id))
+            })
+            .collect();
+
+        for hit in hits {
+            // the hits are keyed in the facet databases like the values at indexing time
+            let normalized = normalize_facet(&hit.value);
+            let key =
+                FacetGroupKey { field_id: fid, level: 0, left_bound: truncate_str(&normalized) };
+            let representative = match index.facet_id_string_docids.get(rtxn, &key)? {
+                Some(FacetGroupValue { bitmap, .. }) => (bitmap & search_candidates).min(),
+                None => None,
+            };
+            let attached_fields = match representative {
+                Some(docid) => {
+                    obkv_to_json(&fields_ids, &fields_ids_map, index.document(rtxn, docid)?)?
+                }
+                None => Object::new(),
+            };
+            hit.attached_fields = Some(attached_fields);
+        }
+
+        Ok(())
     }
+
+    fn facet_value_hit(
+        &self,
+        fid: FieldId,
+        original: &str,
+        docids: &RoaringBitmap,
+        count: u64,
+        normalized: &str,
+        query: &str,
+    ) -> Result<FacetValueHit> {
+        let value = self
+            .one_original_value_of(fid, original, docids.min().unwrap())?
+            .unwrap_or_else(|| query.to_string());
+        let normalized_value = self.include_normalized.then(|| normalized.to_string());
+        // The facet values are matched as prefixes of the query, typos excepted,
+        // and the words of the values are only matched exactly.
+        let include_match_type = self.include_match_type && !self.is_browsing();
+        let match_type = include_match_type.then(|| {
+            if self.match_tokens || normalized.starts_with(query) {
+                FacetValueMatchType::Exact
+            } else {
+                FacetValueMatchType::Typo
+            }
+        });
+        let include_sort_key = self.include_sort_keys || self.is_browsing();
+        let sort_key = include_sort_key.then(|| normalized.to_string());
+        let similarity =
+            self.matches_by_similarity().then(|| facet_value_similarity(query, normalized));
+        Ok(FacetValueHit {
+            value,
+            count,
+            normalized_value,
+            match_type,
+            sort_key,
+            attached_fields: None,
+            similarity,
+        })
+    }
+}
+
+/// The value returned for the facet values sharing the same normalized form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FacetRepresentativeValue {
+    /// The first of the colliding values in alphabetic order.
+    First,
+    /// The colliding value matching the most documents, the first in alphabetic order on ties.
+    ///
+    /// The documents are counted per facet value as stored in the facet databases, which are
+    /// case-insensitive: `USA` and `usa` are a single value and the spelling of its document
+    /// with the lowest id is returned. Counting every spelling would require another database.
+    MostFrequent,
 }
 
 #[derive(Debug, Clone, serde::Serialize, PartialEq)]
@@ -266,6 +1265,93 @@ pub struct FacetValueHit {
     pub value: String,
     /// The number of documents associated to this facet
     pub count: u64,
+    /// The normalized form of the facet value, shared by all the
+    /// original values that are considered equal by the facet search
+    #[serde(rename = "normalizedValue", skip_serializing_if = "Option::is_none")]
+    pub normalized_value: Option<String>,
+    /// Whether the facet value matched the query as is or only after typo correction
+    #[serde(rename = "matchType", skip_serializing_if = "Option::is_none")]
+    pub match_type: Option<FacetValueMatchType>,
+    /// The key the facet values are ordered by when sorted lexicographically
+    #[serde(rename = "sortKey", skip_serializing_if = "Option::is_none")]
+    pub sort_key: Option<String>,
+    /// The fields of a document representative of the facet value, see
+    /// [`SearchForFacetValues::attach_fields`]
+    #[serde(rename = "attachedFields", skip_serializing_if = "Option::is_none")]
+    pub attached_fields: Option<Object>,
+    /// The similarity between the query and the facet value, see
+    /// [`SearchForFacetValues::min_similarity`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+}
+
+/// The Jaro-Winkler similarity between the normalized query and the beginning
+/// of the normalized facet value with as many characters as the query.
+fn facet_value_similarity(query: &str, normalized: &str) -> f64 {
+    let end =
+        normalized.char_indices().nth(query.chars().count()).map_or(normalized.len(), |(i, _)| i);
+    strsim::jaro_winkler(query, &normalized[..end])
+}
+
+/// The facet values starting with the same normalized character.
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+pub struct FacetValueGroup {
+    /// The first character of the normalized values, `#` when it isn't a letter
+    pub key: String,
+    /// The number of facet values in the group
+    pub count: u64,
+    pub values: Vec<FacetValueHit>,
+}
+
+/// How a facet value matched the query of a facet search.
+#[derive(Debug, Clone, Copy, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FacetValueMatchType {
+    /// The normalized facet value starts with the normalized query.
+    Exact,
+    /// The facet value only matched the query after typo correction.
+    Typo,
+}
+
+/// The facet values returned by [`SearchForFacetValues::execute_page`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FacetSearchPage {
+    pub hits: Vec<FacetValueHit>,
+    /// The hits grouped by first character, requested with
+    /// [`SearchForFacetValues::group_by_first_char`].
+    pub groups: Option<Vec<FacetValueGroup>>,
+    /// The stats requested with [`SearchForFacetValues::include_stats`].
+    pub stats: Option<FacetSearchStats>,
+    /// The number of candidates without the facet field, requested with
+    /// [`SearchForFacetValues::include_missing`].
+    pub missing: Option<u64>,
+    /// The cursor to give to [`SearchForFacetValues::after`] to fetch the next page,
+    /// only returned when paging with a cursor and the page is full.
+    pub next_cursor: Option<String>,
+    /// The values closest to the query when there is no hit, requested with
+    /// [`SearchForFacetValues::suggest_corrections`].
+    pub suggestions: Option<Vec<String>>,
+}
+
+/// The position of a facet value in the lexicographic order of the facet search.
+///
+/// The original values sharing a normalized value are ordered between themselves,
+/// a page can therefore end in the middle of them.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct FacetSearchCursor {
+    normalized: String,
+    original: String,
+}
+
+/// Statistics about the facet values matching a facet search.
+#[derive(Debug, Clone, Default, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetSearchStats {
+    /// The number of distinct documents associated to the matching facet values
+    pub matching_documents: u64,
+    /// The sum of the counts of the matching facet values, a document
+    /// having several matching values is counted once for each of them
+    pub total_value_occurrences: u64,
 }
 
 impl PartialOrd for FacetValueHit {
@@ -283,7 +1369,7 @@ impl Ord for FacetValueHit {
 impl Eq for FacetValueHit {}
 
 /// A wrapper type that collects the best facet values by
-/// lexicographic or number of associated values.
+/// lexicographic, number of associated values or relevancy.
 enum ValuesCollection {
     /// Keeps the top values according to the lexicographic order.
     Lexicographic { max: usize, content: Vec<FacetValueHit> },
@@ -292,6 +1378,30 @@ enum ValuesCollection {
     /// Note that it is a max heap and we need to move the smallest counts
     /// at the top to be able to pop them when we reach the max_values limit.
     Count { max: usize, content: BinaryHeap<Reverse<FacetValueHit>> },
+    /// Keeps all the values along with the sum of the scores of their ranked documents,
+    /// the top values are only known once every value has been collected.
+    Relevance {
+        max: usize,
+        ranked: RoaringBitmap,
+        document_scores: HashMap<DocumentId, f64>,
+        content: Vec<(f64, FacetValueHit)>,
+    },
+    /// Keeps all the values in lexicographic order, the weighted scores
+    /// are only known once every value has been collected.
+    Weighted { max: usize, weights: FacetValuesWeights, content: Vec<FacetValueHit> },
+    /// Keeps all the values along with the last time they were associated with a document,
+    /// the most recent values are only known once every value has been collected.
+    Recency { max: usize, recency: FacetValuesRecency, content: Vec<(u64, FacetValueHit)> },
+    /// Keeps all the values along with their sort weight,
+    /// the heaviest values are only known once every value has been collected.
+    ValueWeight {
+        max: usize,
+        weights: FacetValuesWeight,
+        content: Vec<(Option<f64>, FacetValueHit)>,
+    },
+    /// Keeps all the values in lexicographic order, the most similar
+    /// to the query are only known once every value has been collected.
+    Similarity { max: usize, content: Vec<FacetValueHit> },
 }
 
 impl ValuesCollection {
@@ -303,7 +1413,29 @@ impl ValuesCollection {
         ValuesCollection::Count { max, content: BinaryHeap::new() }
     }
 
-    pub fn insert(&mut self, value: FacetValueHit) -> ControlFlow<()> {
+    pub fn by_relevance(max: usize, document_scores: HashMap<DocumentId, f64>) -> Self {
+        let ranked = document_scores.keys().copied().collect();
+        ValuesCollection::Relevance { max, ranked, document_scores, content: Vec::new() }
+    }
+
+    pub fn by_weights(max: usize, weights: FacetValuesWeights) -> Self {
+        ValuesCollection::Weighted { max, weights, content: Vec::new() }
+    }
+
+    pub fn by_recency(max: usize, recency: FacetValuesRecency) -> Self {
+        ValuesCollection::Recency { max, recency, content: Vec::new() }
+    }
+
+    pub fn by_value_weight(max: usize, weights: FacetValuesWeight) -> Self {
+        ValuesCollection::ValueWeight { max, weights, content: Vec::new() }
+    }
+
+    pub fn by_similarity(max: usize) -> Self {
+        ValuesCollection::Similarity { max, content: Vec::new() }
+    }
+
+    /// Inserts a facet value hit, `docids` are the documents associated to the facet value.
+    pub fn insert(&mut self, value: FacetValueHit, docids: &RoaringBitmap) -> ControlFlow<()> {
         match self {
             ValuesCollection::Lexicographic { max, content } => {
                 if content.len() < *max {
@@ -329,11 +1461,54 @@ impl ValuesCollection {
                 }
                 ControlFlow::Continue(())
             }
+            ValuesCollection::Relevance { max: _, ranked, document_scores, content } => {
+                let relevancy: f64 =
+                    (docids & &*ranked).iter().map(|docid| document_scores[&docid]).sum();
+                content.push((relevancy, value));
+                ControlFlow::Continue(())
+            }
+            ValuesCollection::Weighted { content, .. }
+            | ValuesCollection::Similarity { content, .. } => {
+                content.push(value);
+                ControlFlow::Continue(())
+            }
+            ValuesCollection::Recency { recency, content, .. } => {
+                content.push((recency.of_original(&value.value), value));
+                ControlFlow::Continue(())
+            }
+            ValuesCollection::ValueWeight { weights, content, .. } => {
+                content.push((weights.of_original(&value.value), value));
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            ValuesCollection::Lexicographic { content, .. } => content.len(),
+            ValuesCollection::Count { content, .. } => content.len(),
+            ValuesCollection::Relevance { content, .. } => content.len(),
+            ValuesCollection::Weighted { content, .. } => content.len(),
+            ValuesCollection::Recency { content, .. } => content.len(),
+            ValuesCollection::ValueWeight { content, .. } => content.len(),
+            ValuesCollection::Similarity { content, .. } => content.len(),
+        }
+    }
+
+    fn max(&self) -> usize {
+        match self {
+            ValuesCollection::Lexicographic { max, .. }
+            | ValuesCollection::Count { max, .. }
+            | ValuesCollection::Relevance { max, .. }
+            | ValuesCollection::Weighted { max, .. }
+            | ValuesCollection::Recency { max, .. }
+            | ValuesCollection::ValueWeight { max, .. }
+            | ValuesCollection::Similarity { max, .. } => *max,
         }
     }
 
     /// Returns the list of facet values in descending order of, either,
-    /// count or lexicographic order of the value depending on the type.
+    /// count, relevancy, weighted score, recency, weight, similarity or lexicographic order of the value depending on the type.
     pub fn into_sorted_vec(self) -> Vec<FacetValueHit> {
         match self {
             ValuesCollection::Lexicographic { content, .. } => content.into_iter().collect(),
@@ -343,11 +1518,191 @@ impl ValuesCollection {
                 // are output in ascending order.
                 content.into_sorted_vec().into_iter().map(|Reverse(hit)| hit).collect()
             }
+            ValuesCollection::Relevance { max, mut content, .. } => {
+                // The sort is stable, values with the same relevancy keep their lexicographic order.
+                content.sort_by(|(left, _), (right, _)| right.total_cmp(left));
+                content.into_iter().take(max).map(|(_, hit)| hit).collect()
+            }
+            ValuesCollection::Weighted { max, weights, mut content } => {
+                weights.sort(&mut content, |hit| hit.count);
+                content.truncate(max);
+                content
+            }
+            ValuesCollection::Recency { max, mut content, .. } => {
+                // The sort is stable, values associated at the same time keep their lexicographic order.
+                content.sort_by_key(|(timestamp, _)| Reverse(*timestamp));
+                content.into_iter().take(max).map(|(_, hit)| hit).collect()
+            }
+            ValuesCollection::ValueWeight { max, mut content, .. } => {
+                // The sort is stable, values with the same weight keep their lexicographic order
+                // and the values without a weight, lower than any weight, come last.
+                content.sort_by_key(|(weight, _)| Reverse(weight.map(OrderedFloat)));
+                content.into_iter().take(max).map(|(_, hit)| hit).collect()
+            }
+            ValuesCollection::Similarity { max, mut content } => {
+                // The sort is stable, values as similar to the query keep their lexicographic order.
+                content.sort_by(|left, right| {
+                    let left = left.similarity.unwrap_or_default();
+                    right.similarity.unwrap_or_default().total_cmp(&left)
+                });
+                content.truncate(max);
+                content
+            }
+        }
+    }
+}
+
+/// Collects the best facet values and, if requested, the stats of all the matching facet values.
+struct FacetValuesCollector<'f> {
+    values: ValuesCollection,
+    values_are_complete: bool,
+    matching_documents: Option<RoaringBitmap>,
+    total_value_occurrences: u64,
+    /// The values up to this position were returned by the previous pages.
+    after: Option<FacetSearchCursor>,
+    /// The position of the last collected value.
+    last_position: Option<FacetSearchCursor>,
+    /// The boosted documents and the values associated to them, returned before the other values.
+    boosted: Option<(RoaringBitmap, ValuesCollection)>,
+    /// The number of documents associated to every normalized value, `0` when it is unknown.
+    value_counts: Option<fst::Map<&'f [u8]>>,
+}
+
+impl<'f> FacetValuesCollector<'f> {
+    fn new(
+        values: ValuesCollection,
+        include_stats: bool,
+        after: Option<FacetSearchCursor>,
+        boosted: Option<(RoaringBitmap, ValuesCollection)>,
+    ) -> Self {
+        FacetValuesCollector {
+            values,
+            values_are_complete: false,
+            matching_documents: include_stats.then(RoaringBitmap::new),
+            total_value_occurrences: 0,
+            after,
+            last_position: None,
+            boosted,
+            value_counts: None,
+        }
+    }
+
+    /// Whether the original values of the given normalized value can't be collected, without
+    /// fetching their documents. This is only known when sorting by count without computing stats,
+    /// the page is full and the normalized value has fewer documents than the worst collected value.
+    fn cannot_collect(&self, normalized: &str) -> bool {
+        if self.matching_documents.is_some() || self.boosted.is_some() {
+            return false;
+        }
+        let ValuesCollection::Count { max, content } = &self.values else { return false };
+        if content.len() < *max {
+            return false;
+        }
+        let Some(worst) = content.peek() else { return false };
+        match self.value_counts.as_ref().and_then(|counts| counts.get(normalized)) {
+            Some(0) | None => false,
+            Some(max_count) => max_count < worst.0.count,
+        }
+    }
+
+    /// The number of collected values, boosted or not.
+    fn len(&self) -> usize {
+        let boosted = self.boosted.as_ref().map_or(0, |(_, values)| values.len());
+        self.values.len() + boosted
+    }
+
+    /// Whether the original value of the given normalized value comes after the cursor.
+    fn is_after_cursor(&self, normalized: &str, original: &str) -> bool {
+        self.after.as_ref().is_none_or(|after| {
+            (normalized, original) > (after.normalized.as_str(), after.original.as_str())
+        })
+    }
+
+    /// Doesn't walk the normalized values the cursor is after.
+    fn skip_before_cursor<'s, A: Automaton>(
+        &self,
+        builder: fst::set::StreamBuilder<'s, A>,
+    ) -> fst::set::StreamBuilder<'s, A> {
+        match &self.after {
+            Some(after) => builder.ge(&after.normalized),
+            None => builder,
+        }
+    }
+
+    /// Same as [`Self::insert`] but also remembers the position of the value if it is collected.
+    fn insert_at(
+        &mut self,
+        position: FacetSearchCursor,
+        value: FacetValueHit,
+        docids: &RoaringBitmap,
+        candidates: &RoaringBitmap,
+    ) -> ControlFlow<()> {
+        let collected = self.len();
+        let flow = self.insert(value, docids, candidates);
+        if self.len() > collected {
+            self.last_position = Some(position);
+        }
+        flow
+    }
+
+    /// Inserts a facet value hit, `docids` are the documents associated to the facet value.
+    ///
+    /// Breaks once no more values are needed, which is never the case when stats must be computed.
+    fn insert(
+        &mut self,
+        value: FacetValueHit,
+        docids: &RoaringBitmap,
+        candidates: &RoaringBitmap,
+    ) -> ControlFlow<()> {
+        let Some(matching_documents) = &mut self.matching_documents else {
+            return self.insert_value(value, docids);
+        };
+
+        *matching_documents |= docids & candidates;
+        self.total_value_occurrences = self.total_value_occurrences.saturating_add(value.count);
+        if !self.values_are_complete {
+            self.values_are_complete = self.insert_value(value, docids).is_break();
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Inserts the value among the boosted values if it is associated to a boosted document.
+    ///
+    /// Only a full page of boosted values is enough, the following values may still be boosted.
+    fn insert_value(&mut self, value: FacetValueHit, docids: &RoaringBitmap) -> ControlFlow<()> {
+        match &mut self.boosted {
+            Some((documents, values)) if !docids.is_disjoint(documents) => {
+                values.insert(value, docids)
+            }
+            Some(_) => {
+                let _ = self.values.insert(value, docids);
+                ControlFlow::Continue(())
+            }
+            None => self.values.insert(value, docids),
         }
     }
+
+    fn finish(self) -> (Vec<FacetValueHit>, Option<FacetSearchStats>, Option<FacetSearchCursor>) {
+        let stats = self.matching_documents.map(|matching_documents| FacetSearchStats {
+            matching_documents: matching_documents.len(),
+            total_value_occurrences: self.total_value_occurrences,
+        });
+        let hits = match self.boosted {
+            Some((_, boosted_values)) => {
+                let max = self.values.max();
+                let mut hits = boosted_values.into_sorted_vec();
+                hits.extend(self.values.into_sorted_vec());
+                hits.truncate(max);
+                hits
+            }
+            None => self.values.into_sorted_vec(),
+        };
+        (hits, stats, self.last_position)
+    }
 }
-fn normalize_facet_string(facet_string: &str, locales: Option<&[Language]>) -> String {
-    let options = NormalizerOption { lossy: true, ..Default::default() };
+
+fn normalize_facet_string(facet_string: &str, locales: Option<&[Language]>, lossy: bool) -> String {
+    let options = NormalizerOption { lossy, ..Default::default() };
     let mut detection = StrDetection::new(facet_string, locales);
 
     // Detect the language of the facet string only if several locales are explicitly provided.
@@ -366,3 +1721,40 @@ fn normalize_facet_string(facet_string: &str, locales: Option<&[Language]>) -> S
 
     token.normalize(&options).lemma.into_owned()
 }
+
+/// Normalizes a facet string for the facet search.
+///
+/// This is used both to compute the keys of the `facet_id_normalized_string_strings` database
+/// and to normalize the facet search queries, so that a query always matches the values it
+/// is normalized like, e.g. `café` and `cafe`. The `lossy` normalization of the field can be
+/// disabled with its filterable attributes features, `café` and `cafe` are then kept apart.
+pub(crate) fn normalize_facet_string_for_search(
+    facet_string: &str,
+    locales: Option<&[Language]>,
+    lossy: bool,
+) -> String {
+    truncate_normalized_facet_string(normalize_facet_string(facet_string, locales, lossy))
+}
+
+/// Whether the character is one of the combining diacritical marks that the
+/// compatibility decomposition separates from the letters.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE20}'..='\u{FE2F}')
+}
+
+/// Truncates a normalized facet string the same way it is done
+/// when storing it in the `facet_id_normalized_string_strings` database.
+fn truncate_normalized_facet_string(normalized: String) -> String {
+    if normalized.len() <= MAX_FACET_VALUE_LENGTH {
+        return normalized;
+    }
+    normalized
+        .char_indices()
+        .take_while(|(idx, _)| *idx < MAX_FACET_VALUE_LENGTH)
+        .map(|(_, c)| c)
+        .collect()
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use heed::types::DecodeIgnore;
+use heed::RoTxn;
+use itertools::{merge_join_by, EitherOrBoth};
+use lru::LruCache;
+use time::OffsetDateTime;
+
+use super::limits;
+use crate::{Index, Prefix, Result};
+
+/// The number of distinct prefixes remembered by the query log.
+const QUERY_LOG_CAPACITY: NonZeroUsize = NonZeroUsize::new(10_000).unwrap();
+/// The number of prefixes whose words are kept in memory.
+const DERIVATIONS_CAPACITY: NonZeroUsize = NonZeroUsize::new(1_000).unwrap();
+
+/// The prefixes of an index in [`PrefixSearch::HotPrefixes`](crate::index::PrefixSearch::HotPrefixes).
+///
+/// The searches log the prefixes they could not find in the prefix databases, the next
+/// indexing operation only materializes the prefixes of this log. Until then, the words
+/// of the queried prefixes are computed at search time and kept until the index is written again.
+///
+/// The query log is only kept in memory by the searches: every indexing operation persists it
+/// in the index, and it is restored when the index is opened again. The prefixes queried since
+/// the last indexing operation are therefore lost when the index is closed, e.g. on restarts
+/// or when it is evicted from the opened indexes.
+#[derive(Clone)]
+pub(crate) struct HotPrefixes {
+    query_log: Arc<Mutex<LruCache<Prefix, ()>>>,
+    derivations: Arc<Mutex<LruCache<Prefix, (OffsetDateTime, Arc<Vec<String>>)>>>,
+}
+
+impl Default for HotPrefixes {
+    fn default() -> Self {
+        HotPrefixes {
+            query_log: Arc::new(Mutex::new(LruCache::new(QUERY_LOG_CAPACITY))),
+            derivations: Arc::new(Mutex::new(LruCache::new(DERIVATIONS_CAPACITY))),
+        }
+    }
+}
+
+impl HotPrefixes {
+    /// Logs a prefix queried by a search, evicting the least recently queried one when full.
+    pub(crate) fn record_query(&self, prefix: &str) {
+        self.query_log.lock().unwrap().put(Prefix::from(prefix), ());
+    }
+
+    /// The prefixes recently queried, the ones to materialize at indexing time.
+    pub(crate) fn recent_queries(&self) -> BTreeSet<Prefix> {
+        self.query_log.lock().unwrap().iter().map(|(prefix, ())| prefix.clone()).collect()
+    }
+
+    /// The query log, from the least to the most recently queried prefix.
+    pub(crate) fn query_log(&self) -> Vec<String> {
+        self.query_log.lock().unwrap().iter().rev().map(|(prefix, ())| prefix.to_string()).collect()
+    }
+
+    /// Logs the prefixes of a persisted query log, given from the least to the most recent one.
+    pub(crate) fn restore_query_log(&self, prefixes: Vec<String>) {
+        let mut query_log = self.query_log.lock().unwrap();
+        for prefix in prefixes {
+            query_log.put(Prefix::from(prefix.as_str()), ());
+        }
+    }
+
+    /// Returns the words starting with the given prefix, computing them if they aren't cached
+    /// or if the index has been updated since they were computed.
+    pub(crate) fn derivations(
+        &self,
+        index: &Index,
+        rtxn: &RoTxn<'_>,
+        prefix: &str,
+    ) -> Result<Arc<Vec<String>>> {
+        let updated_at = index.updated_at(rtxn)?;
+
+        if let Some((computed_at, words)) = self.derivations.lock().unwrap().get(prefix) {
+            if *computed_at == updated_at {
+                return Ok(words.clone());
+            }
+        }
+
+        let words = Arc::new(prefix_derivations(index, rtxn, prefix)?);
+        self.derivations.lock().unwrap().put(Prefix::from(prefix), (updated_at, words.clone()));
+        Ok(words)
+    }
+}
+
+/// Lists the words and exact words starting with the prefix, up to the maximum
+/// number of derivations a prefix can have in a query.
+fn prefix_derivations(index: &Index, rtxn: &RoTxn<'_>, prefix: &str) -> Result<Vec<String>> {
+    let words = index.word_docids.remap_data_type::<DecodeIgnore>().prefix_iter(rtxn, prefix)?;
+    let exact_words =
+        index.exact_word_docids.remap_data_type::<DecodeIgnore>().prefix_iter(rtxn, prefix)?;
+
+    let mut derivations = Vec::new();
+    for eob in merge_join_by(words, exact_words, |lhs, rhs| match (lhs, rhs) {
+        (Ok((word, _)), Ok((exact_word, _))) => word.cmp(exact_word),
+        (Err(_), _) | (_, Err(_)) => std::cmp::Ordering::Equal,
+    }) {
+        match eob {
+            EitherOrBoth::Both(kv, _) | EitherOrBoth::Left(kv) | EitherOrBoth::Right(kv) => {
+                let (word, _) = kv?;
+                if word != prefix {
+                    derivations.push(word.to_string());
+                    if derivations.len() >= limits::MAX_PREFIX_COUNT {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(derivations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_log_keeps_the_most_recent_prefixes() {
+        let hot_prefixes = HotPrefixes::default();
+        for i in 0..QUERY_LOG_CAPACITY.get() + 2 {
+            hot_prefixes.record_query(&i.to_string());
+        }
+        // querying a prefix again makes it the most recent one
+        hot_prefixes.record_query("2");
+        hot_prefixes.record_query("10002");
+
+        let recent = hot_prefixes.recent_queries();
+        assert_eq!(recent.len(), QUERY_LOG_CAPACITY.get());
+        assert!(!recent.contains("0"));
+        assert!(!recent.contains("1"));
+        assert!(!recent.contains("3"));
+        assert!(recent.contains("2"));
+        assert!(recent.contains("10001"));
+        assert!(recent.contains("10002"));
+
+        // the restored log keeps the order of the queries
+        let restored = HotPrefixes::default();
+        restored.restore_query_log(hot_prefixes.query_log());
+        assert_eq!(restored.query_log(), hot_prefixes.query_log());
+        assert_eq!(restored.query_log().last().map(String::as_str), Some("10002"));
+    }
+}
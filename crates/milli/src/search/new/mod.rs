@@ -3,6 +3,7 @@ mod db_cache;
 mod distinct;
 pub(crate) mod geo_sort;
 mod graph_based_ranking_rule;
+pub(crate) mod hot_prefixes;
 mod interner;
 mod limits;
 mod logger;
@@ -8,6 +8,7 @@ use heed::types::DecodeIgnore;
 use itertools::{merge_join_by, EitherOrBoth};
 
 use super::{OneTypoTerm, Phrase, QueryTerm, ZeroTypoTerm};
+use crate::index::PrefixSearch;
 use crate::search::fst_utils::{Complement, Intersection, StartsWith, Union};
 use crate::search::new::interner::{DedupInterner, Interned};
 use crate::search::new::query_term::{Lazy, TwoTypoTerm};
@@ -43,6 +44,15 @@ fn find_zero_typo_prefix_derivations(
     let word = ctx.word_interner.get(word_interned).to_owned();
     let word = word.as_str();
 
+    // The prefixes that are not hot are resolved from the words cached by the index.
+    if ctx.prefix_search == PrefixSearch::HotPrefixes {
+        let derivations = ctx.index.hot_prefixes.derivations(ctx.index, ctx.txn, word)?;
+        for derived_word in derivations.iter() {
+            prefix_of.insert(ctx.word_interner.insert(derived_word.clone()));
+        }
+        return Ok(());
+    }
+
     let words =
         ctx.index.word_docids.remap_data_type::<DecodeIgnore>().prefix_iter(ctx.txn, word)?;
     let exact_words =
@@ -188,6 +198,11 @@ pub fn partially_initialized_term_from_word(
         });
     }
 
+    // The queried prefixes, materialized or not, are the ones to materialize next.
+    if is_prefix && ctx.prefix_search == PrefixSearch::HotPrefixes {
+        ctx.index.hot_prefixes.record_query(word);
+    }
+
     let use_prefix_db = is_prefix
         && (ctx
             .index
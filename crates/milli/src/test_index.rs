@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::ops::Deref;
 
 use big_s::S;
@@ -6,12 +6,17 @@ use bumpalo::Bump;
 use heed::{EnvOpenOptions, RwTxn};
 use maplit::btreemap;
 use memmap2::Mmap;
+use roaring::RoaringBitmap;
 use tempfile::TempDir;
 
 use crate::constants::RESERVED_GEO_FIELD_NAME;
 use crate::error::{Error, InternalError};
-use crate::index::{DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS};
+use crate::index::{
+    FacetValuesDumpFormat, FacetingWarmup, PrefixSearch, PrefixSearchWarmup,
+    DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS,
+};
 use crate::progress::Progress;
+use crate::search::facet::normalize_facet_string_for_search;
 use crate::update::new::indexer;
 use crate::update::settings::InnerIndexSettings;
 use crate::update::{
@@ -20,7 +25,10 @@ use crate::update::{
 };
 use crate::vector::settings::{EmbedderSource, EmbeddingSettings};
 use crate::vector::RuntimeEmbedders;
-use crate::{db_snap, obkv_to_json, Filter, FilterableAttributesRule, Index, Search, SearchResult};
+use crate::{
+    db_snap, obkv_to_json, FacetRepresentativeValue, FacetValuesWeights, Filter,
+    FilterableAttributesRule, Index, OrderBy, Prefix, Search, SearchForFacetValues, SearchResult,
+};
 
 pub(crate) struct TempIndex {
     pub inner: Index,
@@ -59,6 +67,15 @@ impl TempIndex {
         &'t self,
         wtxn: &mut RwTxn<'t>,
         documents: Mmap,
+    ) -> Result<(), crate::error::Error> {
+        self.add_documents_using_wtxn_with_progress(wtxn, documents, &Progress::default())
+    }
+
+    pub fn add_documents_using_wtxn_with_progress<'t>(
+        &'t self,
+        wtxn: &mut RwTxn<'t>,
+        documents: Mmap,
+        progress: &Progress,
     ) -> Result<(), crate::error::Error> {
         let indexer_config = &self.indexer_config;
         let pool = &indexer_config.thread_pool;
@@ -106,7 +123,7 @@ impl TempIndex {
                 &document_changes,
                 embedders,
                 &|| false,
-                &Progress::default(),
+                progress,
                 &Default::default(),
             )
         })
@@ -116,8 +133,16 @@ impl TempIndex {
     }
 
     pub fn add_documents(&self, documents: Mmap) -> Result<(), crate::error::Error> {
+        self.add_documents_with_progress(documents, &Progress::default())
+    }
+
+    pub fn add_documents_with_progress(
+        &self,
+        documents: Mmap,
+        progress: &Progress,
+    ) -> Result<(), crate::error::Error> {
         let mut wtxn = self.write_txn().unwrap();
-        self.add_documents_using_wtxn(&mut wtxn, documents)?;
+        self.add_documents_using_wtxn_with_progress(&mut wtxn, documents, progress)?;
         wtxn.commit().unwrap();
         Ok(())
     }
@@ -212,6 +237,25 @@ impl TempIndex {
     }
 }
 
+/// Utility function to generate a string whose position in a lexicographically
+/// ordered list is `i`, useful to test the structures that keep their keys sorted.
+///
+/// The strings are only ordered up to `26^5`.
+pub(crate) fn ordered_string(mut i: usize) -> String {
+    // The first string is empty
+    if i == 0 {
+        return String::new();
+    }
+    // The others are 5 char long, each between 'a' and 'z'
+    let mut s = String::new();
+    for _ in 0..5 {
+        let (digit, next) = (i % 26, i / 26);
+        s.insert(0, char::from_u32('a' as u32 + digit as u32).unwrap());
+        i = next;
+    }
+    s
+}
+
 #[test]
 fn aborting_indexation() {
     use std::sync::atomic::AtomicBool;
@@ -1382,3 +1426,1145 @@ fn vectors_are_never_indexed_as_searchable_or_filterable() {
         .unwrap();
     assert!(results.candidates.is_empty());
 }
+
+#[test]
+fn facet_fields_with_types() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![
+                FilterableAttributesRule::Field(S("genre")),
+                FilterableAttributesRule::Field(S("price")),
+                FilterableAttributesRule::Field(S("mixed")),
+                FilterableAttributesRule::Field(S("empty")),
+            ]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "genre": "drama", "price": 10, "mixed": 1 },
+            { "id": 1, "genre": "comedy", "price": 12.5, "mixed": "one" },
+            { "id": 2, "genre": "drama", "title": "not faceted" },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+    let mut fields_with_types: Vec<_> = index
+        .facet_fields_with_types(&rtxn)
+        .unwrap()
+        .into_iter()
+        .map(|(field_id, facet_type)| (fields_ids_map.name(field_id).unwrap(), facet_type))
+        .collect();
+    fields_with_types.sort_unstable();
+    insta::assert_debug_snapshot!(fields_with_types, @r###"
+    [
+        (
+            "genre",
+            String,
+        ),
+        (
+            "mixed",
+            String,
+        ),
+        (
+            "mixed",
+            Number,
+        ),
+        (
+            "price",
+            Number,
+        ),
+    ]
+    "###);
+
+    // facet searching a field only containing numbers is an error
+    let search = index.search(&rtxn);
+    let mut facet_search = SearchForFacetValues::new(S("price"), search, false);
+    facet_search.query("1");
+    let error = facet_search.execute().unwrap_err();
+    insta::assert_snapshot!(error, @"Attribute `price` only contains numbers and cannot be used for a facet search. Facet search only applies to string facet values, use a `filter` to search for numbers instead.");
+
+    // but mixed fields can still be facet searched
+    let search = index.search(&rtxn);
+    let mut facet_search = SearchForFacetValues::new(S("mixed"), search, false);
+    facet_search.query("one");
+    let hits = facet_search.execute().unwrap();
+    assert_eq!(hits.len(), 1);
+}
+
+#[test]
+fn faceting_warmup() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![
+                FilterableAttributesRule::Field(S("genre")),
+                FilterableAttributesRule::Field(S("price")),
+            ]);
+        })
+        .unwrap();
+    let documents: Vec<_> = (0..1000)
+        .map(|i| serde_json::json!({ "id": i, "genre": format!("genre {i}"), "price": i }))
+        .collect();
+    index.add_documents(documents!(documents)).unwrap();
+
+    // the warmup is disabled by default
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.faceting_warmup(&rtxn).unwrap(), None);
+    drop(rtxn);
+
+    let warmup = FacetingWarmup { max_bytes: u64::MAX, top_levels: false };
+    index.update_settings(|settings| settings.set_faceting_warmup(warmup)).unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.faceting_warmup(&rtxn).unwrap(), Some(warmup));
+
+    // without a cap the whole FSTs are read
+    let fst_bytes: u64 = index
+        .facet_id_string_fst
+        .remap_data_type::<heed::types::Bytes>()
+        .iter(&rtxn)
+        .unwrap()
+        .map(|result| result.unwrap().1.len() as u64)
+        .sum();
+    assert_ne!(fst_bytes, 0);
+    assert_eq!(index.warmup_facets(&rtxn, warmup).unwrap(), fst_bytes);
+
+    // the top levels of the facet trees are read after the FSTs
+    let with_levels = FacetingWarmup { max_bytes: u64::MAX, top_levels: true };
+    assert!(index.warmup_facets(&rtxn, with_levels).unwrap() > fst_bytes);
+
+    // the cap bounds the number of bytes read
+    let capped = FacetingWarmup { max_bytes: 100, top_levels: true };
+    assert_eq!(index.warmup_facets(&rtxn, capped).unwrap(), 100);
+    drop(rtxn);
+
+    index.update_settings(|settings| settings.reset_faceting_warmup()).unwrap();
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.faceting_warmup(&rtxn).unwrap(), None);
+}
+
+#[test]
+fn facet_search_query_and_values_are_normalized_alike() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("name"))]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "name": "Café" },
+            { "id": 1, "name": "Crème brûlée" },
+            { "id": 2, "name": "L'Œuf" },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let field_id = index.fields_ids_map(&rtxn).unwrap().id("name").unwrap();
+
+    // the stored keys are exactly the search normalization of the facet values
+    let database = index.facet_id_normalized_string_strings;
+    for result in database.prefix_iter(&rtxn, &(field_id, "")).unwrap() {
+        let ((_, normalized), values) = result.unwrap();
+        for value in values {
+            assert_eq!(normalize_facet_string_for_search(&value, None, true), normalized);
+        }
+    }
+
+    // both the accented and unaccented queries match
+    for (query, expected) in [
+        ("café", "Café"),
+        ("cafe", "Café"),
+        ("CAFE", "Café"),
+        ("creme brulee", "Crème brûlée"),
+        ("crème brûlée", "Crème brûlée"),
+        ("l'œuf", "L'Œuf"),
+    ] {
+        let search = index.search(&rtxn);
+        let mut facet_search = SearchForFacetValues::new(S("name"), search, false);
+        facet_search.query(query);
+        let hits = facet_search.execute().unwrap();
+        let values: Vec<_> = hits.iter().map(|hit| hit.value.as_str()).collect();
+        assert_eq!(values, [expected], "query: {query}");
+    }
+}
+
+#[test]
+fn min_prefix_length_skips_short_prefixes() {
+    let index = TempIndex::new();
+    index.update_settings(|settings| settings.set_searchable_fields(vec![S("text")])).unwrap();
+
+    // enough words share the `abcd` prefix to materialize all of its prefixes
+    let words = |range: std::ops::Range<u32>| -> Vec<_> {
+        range
+            .map(|i| {
+                let suffix: String =
+                    [i / 26, i % 26].iter().map(|n| char::from(b'a' + *n as u8)).collect();
+                serde_json::json!({ "id": i, "text": format!("abcd{suffix}") })
+            })
+            .collect()
+    };
+    index.add_documents(documents!(words(0..150))).unwrap();
+
+    let prefixes = |index: &TempIndex| {
+        let rtxn = index.read_txn().unwrap();
+        let fst = index.words_prefixes_fst(&rtxn).unwrap();
+        let in_fst: Vec<_> = fst.stream().into_strs().unwrap();
+        let in_db: Vec<_> = index
+            .word_prefix_docids
+            .iter(&rtxn)
+            .unwrap()
+            .map(|result| result.unwrap().0.to_string())
+            .collect();
+        assert_eq!(in_fst, in_db);
+        in_db
+    };
+    assert_eq!(prefixes(&index), ["a", "ab", "abc", "abcd"]);
+
+    // raising the minimum deletes the previously materialized short prefixes
+    index.update_settings(|settings| settings.set_min_prefix_length(3)).unwrap();
+    assert_eq!(prefixes(&index), ["abc", "abcd"]);
+
+    let rtxn = index.read_txn().unwrap();
+    assert!(index.exact_word_prefix_docids.get(&rtxn, "ab").unwrap().is_none());
+    assert!(index.word_prefix_fid_docids.iter(&rtxn).unwrap().all(|result| {
+        let ((prefix, _), _) = result.unwrap();
+        prefix.len() >= 3
+    }));
+    drop(rtxn);
+
+    // they are not computed either when new documents are indexed
+    index.add_documents(documents!(words(150..200))).unwrap();
+    assert_eq!(prefixes(&index), ["abc", "abcd"]);
+
+    let rtxn = index.read_txn().unwrap();
+    let docids = index.word_prefix_docids.get(&rtxn, "abc").unwrap().unwrap();
+    assert_eq!(docids.len(), 200);
+    drop(rtxn);
+
+    // resetting the minimum computes them again
+    index.update_settings(|settings| settings.reset_min_prefix_length()).unwrap();
+    assert_eq!(prefixes(&index), ["a", "ab", "abc", "abcd"]);
+
+    let rtxn = index.read_txn().unwrap();
+    let docids = index.word_prefix_docids.get(&rtxn, "a").unwrap().unwrap();
+    assert_eq!(docids.len(), 200);
+}
+
+#[test]
+fn recompute_word_prefixes_repairs_only_the_given_prefixes() {
+    let index = TempIndex::new();
+    index.update_settings(|settings| settings.set_searchable_fields(vec![S("text")])).unwrap();
+
+    // enough words share the `abcd` prefix to materialize all of its prefixes
+    let documents: Vec<_> = (0..150u32)
+        .map(|i| {
+            let suffix: String =
+                [i / 26, i % 26].iter().map(|n| char::from(b'a' + *n as u8)).collect();
+            serde_json::json!({ "id": i, "text": format!("abcd{suffix}") })
+        })
+        .collect();
+    index.add_documents(documents!(documents)).unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let fid = index.fields_ids_map(&rtxn).unwrap().id("text").unwrap();
+    let expected = index.word_prefix_docids.get(&rtxn, "abc").unwrap().unwrap();
+    assert_eq!(expected.len(), 150);
+    drop(rtxn);
+
+    // corrupt two prefixes and add an entry for a prefix that is not materialized
+    let corrupted = RoaringBitmap::from_iter([0]);
+    let mut wtxn = index.write_txn().unwrap();
+    index.word_prefix_docids.put(&mut wtxn, "abc", &corrupted).unwrap();
+    index.word_prefix_docids.put(&mut wtxn, "ab", &corrupted).unwrap();
+    index.word_prefix_fid_docids.put(&mut wtxn, &("abc", fid), &corrupted).unwrap();
+    index.word_prefix_docids.put(&mut wtxn, "zz", &corrupted).unwrap();
+
+    let prefixes: BTreeSet<_> = ["abc", "zz"].into_iter().map(Prefix::from).collect();
+    indexer::recompute_word_prefixes(
+        &index,
+        &mut wtxn,
+        &prefixes,
+        &update::GrenadParameters::default(),
+        &Progress::default(),
+    )
+    .unwrap();
+    wtxn.commit().unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.word_prefix_docids.get(&rtxn, "abc").unwrap(), Some(expected.clone()));
+    assert_eq!(index.word_prefix_fid_docids.get(&rtxn, &("abc", fid)).unwrap(), Some(expected));
+    assert!(index.word_prefix_docids.get(&rtxn, "zz").unwrap().is_none());
+    // the other prefixes are left untouched
+    assert_eq!(index.word_prefix_docids.get(&rtxn, "ab").unwrap(), Some(corrupted));
+    assert_eq!(index.word_prefix_docids.get(&rtxn, "abcd").unwrap().unwrap().len(), 150);
+}
+
+#[test]
+fn hot_prefixes_only_materializes_the_queried_prefixes() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_searchable_fields(vec![S("text")]);
+            settings.set_prefix_search(PrefixSearch::HotPrefixes);
+        })
+        .unwrap();
+
+    // enough words share the `ab` and `ac` prefixes to materialize them
+    let words = |range: std::ops::Range<u32>| -> Vec<_> {
+        range
+            .map(|i| {
+                let suffix: String =
+                    [i / 26, i % 26].iter().map(|n| char::from(b'a' + *n as u8)).collect();
+                let text = format!("ab{suffix} ac{suffix}");
+                serde_json::json!({ "id": i, "text": text })
+            })
+            .collect()
+    };
+    index.add_documents(documents!(words(0..150))).unwrap();
+
+    let prefixes = |index: &TempIndex| {
+        let rtxn = index.read_txn().unwrap();
+        let fst = index.words_prefixes_fst(&rtxn).unwrap();
+        let in_fst: Vec<_> = fst.stream().into_strs().unwrap();
+        let in_db: Vec<_> = index
+            .word_prefix_docids
+            .iter(&rtxn)
+            .unwrap()
+            .map(|result| result.unwrap().0.to_string())
+            .collect();
+        assert_eq!(in_fst, in_db);
+        in_db
+    };
+    // no prefix has been queried yet
+    assert_eq!(prefixes(&index), Vec::<String>::new());
+
+    // the prefixes that are not materialized are computed at search time
+    let search_candidates = |index: &TempIndex, query: &str| {
+        let rtxn = index.read_txn().unwrap();
+        let mut search = index.search(&rtxn);
+        search.query(query);
+        search.execute().unwrap().candidates.len()
+    };
+    assert_eq!(search_candidates(&index, "ab"), 150);
+    assert_eq!(search_candidates(&index, "ab"), 150);
+    assert!(index.hot_prefixes.recent_queries().contains("ab"));
+
+    // the next indexing operation only materializes the queried prefix
+    index.add_documents(documents!(words(150..200))).unwrap();
+    assert_eq!(prefixes(&index), ["ab"]);
+
+    let rtxn = index.read_txn().unwrap();
+    let docids = index.word_prefix_docids.get(&rtxn, "ab").unwrap().unwrap();
+    assert_eq!(docids.len(), 200);
+    assert!(index.word_prefix_fid_docids.iter(&rtxn).unwrap().all(|result| {
+        let ((prefix, _), _) = result.unwrap();
+        prefix == "ab"
+    }));
+    drop(rtxn);
+
+    // the materialized and the lazy prefixes return the same documents
+    assert_eq!(search_candidates(&index, "ab"), 200);
+    assert_eq!(search_candidates(&index, "ac"), 200);
+
+    // the prefixes queried since are materialized next
+    index.add_documents(documents!(words(200..210))).unwrap();
+    assert_eq!(prefixes(&index), ["ab", "ac"]);
+}
+
+#[test]
+fn hot_prefixes_query_log_is_restored_when_the_index_is_reopened() {
+    let mut index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_searchable_fields(vec![S("text")]);
+            settings.set_prefix_search(PrefixSearch::HotPrefixes);
+        })
+        .unwrap();
+    index.add_documents(documents!([{ "id": 0, "text": "abc abd" }])).unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let mut search = index.search(&rtxn);
+    search.query("ab");
+    assert_eq!(search.execute().unwrap().candidates.len(), 1);
+    drop(rtxn);
+
+    // the query log is persisted by the next indexing operation
+    index.add_documents(documents!([{ "id": 1, "text": "abe" }])).unwrap();
+
+    index.inner.prepare_for_closing().wait();
+    let options = EnvOpenOptions::new();
+    let mut options = options.read_txn_without_tls();
+    options.map_size(4096 * 2000);
+    index.inner = Index::new(options, index._tempdir.path(), false).unwrap();
+    assert!(index.hot_prefixes.recent_queries().contains("ab"));
+}
+
+#[test]
+fn facet_search_rejects_fields_removed_from_filterable() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![
+                FilterableAttributesRule::Field(S("genre")),
+                FilterableAttributesRule::Field(S("author")),
+            ]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "genre": "Horror", "author": "Stephen King" },
+            { "id": 1, "genre": "Fantasy", "author": "Ursula K. Le Guin" },
+        ]))
+        .unwrap();
+
+    // the rules are changed without reindexing to keep the facet data of `genre` around
+    let mut wtxn = index.write_txn().unwrap();
+    index
+        .put_filterable_attributes_rules(&mut wtxn, &[FilterableAttributesRule::Field(S("author"))])
+        .unwrap();
+    wtxn.commit().unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let genre = index.fields_ids_map(&rtxn).unwrap().id("genre").unwrap();
+    assert!(index.facet_id_string_fst.get(&rtxn, &genre).unwrap().is_some());
+
+    let search = Search::new(&rtxn, &index);
+    let error = SearchForFacetValues::new(S("genre"), search, false).execute().unwrap_err();
+    assert!(matches!(
+        error,
+        Error::UserError(crate::UserError::InvalidFacetSearchFacetName { ref field, .. }) if field == "genre"
+    ));
+    insta::assert_snapshot!(error, @"Attribute `genre` is not facet-searchable. Available facet-searchable attributes patterns are: `author`. To make it facet-searchable add it to the `filterableAttributes` index settings.");
+
+    let search = Search::new(&rtxn, &index);
+    let hits = SearchForFacetValues::new(S("author"), search, false).execute().unwrap();
+    assert_eq!(hits.len(), 2);
+}
+
+#[test]
+fn facet_counts_reflect_deletions_once_committed() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("genre"))]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "genre": "Horror" },
+            { "id": 1, "genre": "Horror" },
+            { "id": 2, "genre": "Fantasy" },
+        ]))
+        .unwrap();
+
+    let counts = |index: &TempIndex| {
+        let rtxn = index.read_txn().unwrap();
+        let distribution = index
+            .facets_distribution(&rtxn)
+            .facets([("genre", OrderBy::Lexicographic)])
+            .execute()
+            .unwrap();
+        let search = Search::new(&rtxn, index);
+        let hits = SearchForFacetValues::new(S("genre"), search, false).execute().unwrap();
+        let hits: Vec<_> = hits.into_iter().map(|hit| (hit.value, hit.count)).collect();
+        (distribution["genre"].clone().into_iter().collect::<Vec<_>>(), hits)
+    };
+
+    let (distribution, hits) = counts(&index);
+    assert_eq!(distribution, vec![(S("Fantasy"), 1), (S("Horror"), 2)]);
+    assert_eq!(hits, distribution);
+
+    // the facet databases are updated in the deletion transaction, there is no soft deletion
+    index.delete_documents(vec![S("1"), S("2")]);
+    let (distribution, hits) = counts(&index);
+    assert_eq!(distribution, vec![(S("Horror"), 1)]);
+    assert_eq!(hits, distribution);
+}
+
+#[test]
+fn indexing_on_a_full_map_is_a_max_database_size_error_with_the_map_size() {
+    use crate::documents::mmap_from_objects;
+
+    let index = TempIndex::new_with_map_size(4096 * 1000);
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("tag"))]);
+        })
+        .unwrap();
+
+    // the facet values and their facet search structures don't fit in the map
+    let documents = (0..50_000).map(|i| {
+        let tag = format!("facet value {i} of a document that does not fit in the map");
+        serde_json::json!({ "id": i, "tag": tag }).as_object().unwrap().clone()
+    });
+    let error = index.add_documents(mmap_from_objects(documents)).unwrap_err();
+
+    // the error is identified to let the caller resize the index instead of failing the task
+    let map_size = index.map_size();
+    assert!(matches!(
+        error,
+        Error::UserError(crate::UserError::MaxDatabaseSizeReached { map_size: Some(size) })
+            if size == map_size
+    ));
+    assert_eq!(
+        error.to_string(),
+        format!("Maximum database size has been reached. The index is limited to {map_size} bytes by its map size, it must be opened with a larger map size.")
+    );
+}
+
+#[test]
+fn merge_facet_databases_of_two_indexes() {
+    let filterable_fields = |settings: &mut Settings<'_, '_, '_>| {
+        settings.set_filterable_fields(vec![
+            FilterableAttributesRule::Field(S("genre")),
+            FilterableAttributesRule::Field(S("price")),
+        ]);
+    };
+    let index = TempIndex::new();
+    index.update_settings(filterable_fields).unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "genre": "Horror", "price": 10 },
+            { "id": 1, "genre": "Fantasy", "price": 20 },
+        ]))
+        .unwrap();
+
+    // the fields of the other index are created in another order
+    let other = TempIndex::new();
+    other.update_settings(filterable_fields).unwrap();
+    other
+        .add_documents(documents!([
+            { "id": 2, "price": 30, "genre": "Horror" },
+            { "id": 3, "price": 10, "genre": "Drama" },
+            { "id": 4, "price": 40, "genre": ["Drama", "Horror"] },
+        ]))
+        .unwrap();
+
+    // the documents of the other index are given the ids following the ones of the index
+    let docids_mapping = (0..3).map(|docid| (docid, docid + 2)).collect();
+    let mut wtxn = index.write_txn().unwrap();
+    let other_rtxn = other.read_txn().unwrap();
+    update::merge_facet_databases(&index, &mut wtxn, &other, &other_rtxn, &docids_mapping).unwrap();
+    // the documents are imported separately, only their ids are registered here
+    index.put_documents_ids(&mut wtxn, &(0..5).collect()).unwrap();
+    wtxn.commit().unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let distribution = index
+        .facets_distribution(&rtxn)
+        .facets([("genre", OrderBy::Lexicographic), ("price", OrderBy::Lexicographic)])
+        .execute()
+        .unwrap();
+    insta::assert_debug_snapshot!(distribution, @r###"
+    {
+        "genre": {
+            "Drama": 2,
+            "Fantasy": 1,
+            "Horror": 3,
+        },
+        "price": {
+            "10": 2,
+            "20": 1,
+            "30": 1,
+            "40": 1,
+        },
+    }
+    "###);
+
+    let filter = Filter::from_str("price >= 20 AND genre EXISTS").unwrap().unwrap();
+    insta::assert_debug_snapshot!(filter.evaluate(&rtxn, &index).unwrap(), @"RoaringBitmap<[1, 2, 4]>");
+
+    let search = index.search(&rtxn);
+    let mut facet_search = SearchForFacetValues::new(S("genre"), search, false);
+    facet_search.query("dr");
+    let hits = facet_search.execute().unwrap();
+    let hits: Vec<_> = hits.into_iter().map(|hit| (hit.value, hit.count)).collect();
+    assert_eq!(hits, vec![(S("Drama"), 2)]);
+}
+
+#[test]
+fn facet_search_representative_value() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("name"))]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "name": "Café" },
+            { "id": 1, "name": "Café" },
+            { "id": 2, "name": "cafe" },
+            { "id": 3, "name": "Tea" },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let facet_search = |query: Option<&str>, representative_value| {
+        let search = index.search(&rtxn);
+        let mut facet_search = SearchForFacetValues::new(S("name"), search, false);
+        if let Some(query) = query {
+            facet_search.query(query);
+        }
+        if let Some(representative_value) = representative_value {
+            facet_search.representative_value(representative_value);
+        }
+        let hits = facet_search.execute().unwrap();
+        hits.into_iter().map(|hit| (hit.value, hit.count)).collect::<Vec<_>>()
+    };
+
+    // without a representative, every original value is returned
+    assert_eq!(facet_search(Some("caf"), None), vec![(S("cafe"), 1), (S("Café"), 2)]);
+
+    for query in [Some("caf"), None] {
+        let hits = facet_search(query, Some(FacetRepresentativeValue::First));
+        assert_eq!(hits[0], (S("cafe"), 3), "query: {query:?}");
+
+        let hits = facet_search(query, Some(FacetRepresentativeValue::MostFrequent));
+        assert_eq!(hits[0], (S("Café"), 3), "query: {query:?}");
+        assert_eq!(hits.len(), 2 - query.is_some() as usize, "query: {query:?}");
+    }
+}
+
+#[test]
+fn facet_search_representative_value_is_stable_across_rebuilds() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("name"))]);
+        })
+        .unwrap();
+    // all the values collide on `cafe` and the most frequent ones are tied
+    let documents = vec![
+        serde_json::json!({ "id": 0, "name": "Café" }),
+        serde_json::json!({ "id": 1, "name": "cafè" }),
+        serde_json::json!({ "id": 2, "name": "Cafe" }),
+        serde_json::json!({ "id": 3, "name": "café" }),
+        serde_json::json!({ "id": 4, "name": "CAFÈ" }),
+    ];
+    index.add_documents(documents!((documents.clone()))).unwrap();
+
+    let facet_search_results = |index: &TempIndex| {
+        let rtxn = index.read_txn().unwrap();
+        let mut results = Vec::new();
+        for query in [Some("caf"), None] {
+            for representative_value in [
+                None,
+                Some(FacetRepresentativeValue::First),
+                Some(FacetRepresentativeValue::MostFrequent),
+            ] {
+                let search = index.search(&rtxn);
+                let mut facet_search = SearchForFacetValues::new(S("name"), search, false);
+                if let Some(query) = query {
+                    facet_search.query(query);
+                }
+                if let Some(representative_value) = representative_value {
+                    facet_search.representative_value(representative_value);
+                }
+                let hits = facet_search.execute().unwrap();
+                results
+                    .push(hits.into_iter().map(|hit| (hit.value, hit.count)).collect::<Vec<_>>());
+            }
+        }
+        results
+    };
+
+    let expected = facet_search_results(&index);
+    assert_eq!(expected[1], vec![(S("Cafe"), 5)]);
+    // `cafè` and `café` match two documents each, the first in alphabetic order wins
+    assert_eq!(expected[2], vec![(S("cafè"), 5)]);
+
+    for _ in 0..2 {
+        // rebuild the facet search databases from scratch
+        index.update_settings(|settings| settings.set_facet_search(false)).unwrap();
+        index.update_settings(|settings| settings.set_facet_search(true)).unwrap();
+        assert_eq!(facet_search_results(&index), expected);
+
+        // reindex the same documents in another order
+        let mut documents = documents.clone();
+        documents.reverse();
+        index.add_documents(documents!(documents)).unwrap();
+        assert_eq!(facet_search_results(&index), expected);
+    }
+}
+
+#[test]
+fn facet_search_boosted_documents() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("genre"))]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "genre": "Drama" },
+            { "id": 1, "genre": "Drama" },
+            { "id": 2, "genre": "Drama" },
+            { "id": 3, "genre": "Comedy" },
+            { "id": 4, "genre": "Horror" },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let facet_search = |boosted: Option<&[u32]>, max_values| {
+        let search = index.search(&rtxn);
+        let mut facet_search = SearchForFacetValues::new(S("genre"), search, false);
+        facet_search.sort_by(OrderBy::Count).max_values(max_values);
+        if let Some(boosted) = boosted {
+            facet_search.boost_documents(boosted.iter().copied().collect());
+        }
+        let hits = facet_search.execute().unwrap();
+        hits.into_iter().map(|hit| (hit.value, hit.count)).collect::<Vec<_>>()
+    };
+
+    assert_eq!(facet_search(None, 10), vec![(S("Drama"), 3), (S("Horror"), 1), (S("Comedy"), 1)]);
+    // the value of a top hit outranks the values with a higher count
+    assert_eq!(
+        facet_search(Some(&[3]), 10),
+        vec![(S("Comedy"), 1), (S("Drama"), 3), (S("Horror"), 1)]
+    );
+    assert_eq!(facet_search(Some(&[3]), 1), vec![(S("Comedy"), 1)]);
+    // the boosted values keep the requested order between themselves
+    assert_eq!(facet_search(Some(&[4, 0]), 2), vec![(S("Drama"), 3), (S("Horror"), 1)]);
+}
+
+#[test]
+fn facet_search_by_count_with_the_fst_document_counts() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("genre"))]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "genre": "Action" },
+            { "id": 1, "genre": "Comedy" },
+            { "id": 2, "genre": "Comedy" },
+            { "id": 3, "genre": "Drama" },
+            { "id": 4, "genre": "Drama" },
+            { "id": 5, "genre": "Drama" },
+            { "id": 6, "genre": "Horror" },
+        ]))
+        .unwrap();
+
+    let facet_search = |index: &TempIndex| {
+        let rtxn = index.read_txn().unwrap();
+        let search = index.search(&rtxn);
+        let mut facet_search = SearchForFacetValues::new(S("genre"), search, false);
+        facet_search.sort_by(OrderBy::Count).max_values(2);
+        let hits = facet_search.execute().unwrap();
+        hits.into_iter().map(|hit| (hit.value, hit.count)).collect::<Vec<_>>()
+    };
+
+    // the values with fewer documents than the page are skipped without changing the results
+    assert_eq!(facet_search(&index), vec![(S("Drama"), 3), (S("Comedy"), 2)]);
+
+    // the FSTs written before the counts were stored, their values are unknown
+    let mut wtxn = index.write_txn().unwrap();
+    let field_id = index.fields_ids_map(&wtxn).unwrap().id("genre").unwrap();
+    let fst = fst::Set::from_iter(["action", "comedy", "drama", "horror"]).unwrap();
+    index
+        .facet_id_string_fst
+        .remap_data_type::<crate::heed_codec::FstSetCodec>()
+        .put(&mut wtxn, &field_id, &fst)
+        .unwrap();
+    wtxn.commit().unwrap();
+    assert_eq!(facet_search(&index), vec![(S("Drama"), 3), (S("Comedy"), 2)]);
+}
+
+#[test]
+fn facet_values_sorted_by_weights() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("genre"))]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "genre": "Action" },
+            { "id": 1, "genre": "Comedy" },
+            { "id": 2, "genre": "Comedy" },
+            { "id": 3, "genre": "Drama" },
+            { "id": 4, "genre": "Drama" },
+            { "id": 5, "genre": "Drama" },
+            { "id": 6, "genre": "Drama" },
+            { "id": 7, "genre": "Western" },
+            { "id": 8, "genre": "Western" },
+            { "id": 9, "genre": "Western" },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let facet_search = |order_by| {
+        let search = index.search(&rtxn);
+        let mut facet_search = SearchForFacetValues::new(S("genre"), search, false);
+        facet_search.sort_by(order_by);
+        let hits = facet_search.execute().unwrap();
+        hits.into_iter().map(|hit| hit.value).collect::<Vec<_>>()
+    };
+    let facet_distribution = |order_by| {
+        let distribution =
+            index.facets_distribution(&rtxn).facets([("genre", order_by)]).execute().unwrap();
+        distribution["genre"].keys().cloned().collect::<Vec<_>>()
+    };
+    let weighted = |count, alpha| OrderBy::Weighted(FacetValuesWeights { count, alpha });
+
+    for facet_values in [&facet_search as &dyn Fn(OrderBy) -> Vec<String>, &facet_distribution] {
+        let by_count = facet_values(OrderBy::Count);
+        assert_eq!(by_count, ["Drama", "Western", "Comedy", "Action"]);
+        let by_alpha = facet_values(OrderBy::Lexicographic);
+        assert_eq!(by_alpha, ["Action", "Comedy", "Drama", "Western"]);
+
+        // a single signal gives the same order as the sort it generalizes
+        assert_eq!(facet_values(weighted(1.0, 0.0)), by_count);
+        assert_eq!(facet_values(weighted(0.0, 1.0)), by_alpha);
+
+        // the counts are divided by 4 and the alphabetical ranks go from 1 to 0 by steps of 1/3
+        // Action: 0.7 * 0.25 + 0.3 * 1   = 0.475
+        // Comedy: 0.7 * 0.5  + 0.3 * 2/3 = 0.55
+        // Drama:  0.7 * 1    + 0.3 * 1/3 = 0.8
+        // Western: 0.7 * 0.75 + 0.3 * 0  = 0.525
+        assert_eq!(facet_values(weighted(0.7, 0.3)), ["Drama", "Comedy", "Western", "Action"]);
+        assert_eq!(facet_values(weighted(0.5, 0.5)), ["Drama", "Action", "Comedy", "Western"]);
+    }
+
+    // the values are only truncated once they are all sorted
+    let mut facet_search = SearchForFacetValues::new(S("genre"), index.search(&rtxn), false);
+    facet_search.sort_by(weighted(0.7, 0.3)).max_values(2);
+    let hits: Vec<_> = facet_search.execute().unwrap().into_iter().map(|hit| hit.value).collect();
+    assert_eq!(hits, ["Drama", "Comedy"]);
+}
+
+#[test]
+fn sort_facet_values_by_recency() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("genre"))]);
+            settings.set_track_facet_values_recency(true);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([{ "id": 0, "genre": "Drama" }, { "id": 1, "genre": "Action" }]))
+        .unwrap();
+    index
+        .add_documents(
+            documents!([{ "id": 2, "genre": "Western" }, { "id": 3, "genre": "Comedy" }]),
+        )
+        .unwrap();
+    // associating an older value with a new document makes it the most recent one
+    index
+        .add_documents(documents!([{ "id": 4, "genre": "Drama" }, { "id": 5, "genre": 42 }]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let mut facet_search = SearchForFacetValues::new(S("genre"), index.search(&rtxn), false);
+    facet_search.sort_by(OrderBy::Recency);
+    let hits: Vec<_> = facet_search.execute().unwrap().into_iter().map(|hit| hit.value).collect();
+    // the values associated at the same time stay in alphabetical order
+    assert_eq!(hits, ["Drama", "Comedy", "Western", "Action"]);
+
+    let distribution =
+        index.facets_distribution(&rtxn).facets([("genre", OrderBy::Recency)]).execute().unwrap();
+    // the numbers have no recorded recency and come last
+    let values: Vec<_> = distribution["genre"].keys().cloned().collect();
+    assert_eq!(values, ["Drama", "Comedy", "Western", "Action", "42"]);
+
+    // the values removed from all their documents are forgotten
+    let fid = index.fields_ids_map(&rtxn).unwrap().id("genre").unwrap();
+    drop(rtxn);
+    index.delete_documents(vec![S("2")]);
+    let rtxn = index.read_txn().unwrap();
+    assert!(index.facet_id_string_recency.get(&rtxn, &(fid, "western")).unwrap().is_none());
+    assert!(index.facet_id_string_recency.get(&rtxn, &(fid, "comedy")).unwrap().is_some());
+    drop(rtxn);
+
+    // and all of them once the tracking is disabled
+    index.update_settings(|settings| settings.set_track_facet_values_recency(false)).unwrap();
+    let rtxn = index.read_txn().unwrap();
+    assert!(index.facet_id_string_recency.is_empty(&rtxn).unwrap());
+}
+
+#[test]
+fn sort_facet_values_by_weight() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("brand"))]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "brand": "Nike" },
+            { "id": 1, "brand": ["Nike", "Asics"] },
+            { "id": 2, "brand": "Nike", "brandWeight": "heavy" },
+            { "id": 3, "brand": "Nike" },
+            { "id": 4, "brand": "Adidas", "brandWeight": 1 },
+            { "id": 5, "brand": ["Adidas", "Asics"] },
+            { "id": 6, "brand": 42 },
+        ]))
+        .unwrap();
+    // the weights of the documents already indexed are read when the weight field is configured
+    index
+        .update_settings(|settings| {
+            settings.set_facet_value_weight_fields(btreemap! { S("brand") => S("brandWeight") });
+        })
+        .unwrap();
+    // and the ones of the new documents after each indexing
+    index
+        .add_documents(documents!([
+            { "id": 7, "brand": "Puma", "brandWeight": 10 },
+            { "id": 8, "brand": "Adidas", "brandWeight": 5 },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let distribution =
+        index.facets_distribution(&rtxn).facets([("brand", OrderBy::Count)]).execute().unwrap();
+    let values: Vec<_> = distribution["brand"].keys().cloned().collect();
+    assert_eq!(values, ["42", "Nike", "Adidas", "Asics", "Puma"]);
+
+    let mut facet_search = SearchForFacetValues::new(S("brand"), index.search(&rtxn), false);
+    facet_search.sort_by(OrderBy::Weight);
+    let hits: Vec<_> = facet_search.execute().unwrap().into_iter().map(|hit| hit.value).collect();
+    // the weights override the counts, the values without a weight come last in alphabetical order
+    assert_eq!(hits, ["Puma", "Adidas", "Asics", "Nike"]);
+
+    let distribution =
+        index.facets_distribution(&rtxn).facets([("brand", OrderBy::Weight)]).execute().unwrap();
+    // the numbers have no weight and come after the strings
+    let values: Vec<_> = distribution["brand"].keys().cloned().collect();
+    assert_eq!(values, ["Puma", "Adidas", "Asics", "Nike", "42"]);
+
+    let fid = index.fields_ids_map(&rtxn).unwrap().id("brand").unwrap();
+    // the highest weight of the documents of a value is kept
+    assert_eq!(index.facet_id_string_weight.get(&rtxn, &(fid, "adidas")).unwrap(), Some(5.0));
+    drop(rtxn);
+
+    // the weights are removed with the weight field
+    index.update_settings(|settings| settings.reset_facet_value_weight_fields()).unwrap();
+    let rtxn = index.read_txn().unwrap();
+    assert!(index.facet_id_string_weight.is_empty(&rtxn).unwrap());
+}
+
+#[test]
+fn prefix_search_warmup() {
+    let index = TempIndex::new();
+    index.update_settings(|settings| settings.set_searchable_fields(vec![S("text")])).unwrap();
+
+    // enough words share the `abcd` and `xyz` prefixes to materialize them
+    let words = |stem: &str, range: std::ops::Range<u32>| -> Vec<_> {
+        range
+            .map(|i| {
+                let suffix: String =
+                    [i / 26, i % 26].iter().map(|n| char::from(b'a' + *n as u8)).collect();
+                serde_json::json!({ "id": format!("{stem}{i}"), "text": format!("{stem}{suffix}") })
+            })
+            .collect()
+    };
+    index.add_documents(documents!(words("abcd", 0..150))).unwrap();
+    index.add_documents(documents!(words("xyz", 0..120))).unwrap();
+
+    // the warmup is disabled by default
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.prefix_search_warmup(&rtxn).unwrap(), None);
+    drop(rtxn);
+
+    // the prefixes that are not materialized are ignored
+    let warmup = PrefixSearchWarmup::Prefixes(["ab", "xy", "zz"].map(String::from).into());
+    index.update_settings(|settings| settings.set_prefix_search_warmup(warmup.clone())).unwrap();
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.prefix_search_warmup(&rtxn).unwrap(), Some(warmup.clone()));
+    assert_eq!(index.warmup_word_prefixes(&rtxn, &warmup).unwrap(), ["ab", "xy"]);
+
+    // the prefixes matching the most documents come first
+    let largest = index.warmup_word_prefixes(&rtxn, &PrefixSearchWarmup::Largest(4)).unwrap();
+    assert_eq!(largest.len(), 4);
+    assert!(largest.iter().all(|prefix| prefix.starts_with('a')), "{largest:?}");
+    let all = index.warmup_word_prefixes(&rtxn, &PrefixSearchWarmup::Largest(100)).unwrap();
+    assert_eq!(all.len(), 7);
+    assert!(all[4..].iter().all(|prefix| prefix.starts_with('x')), "{all:?}");
+    drop(rtxn);
+
+    index.update_settings(|settings| settings.reset_prefix_search_warmup()).unwrap();
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.prefix_search_warmup(&rtxn).unwrap(), None);
+}
+
+#[test]
+fn dump_facet_values() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("genres"))]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "genres": ["Science Fiction", "Drama"] },
+            { "id": 1, "genres": ["science fiction", "Comedy", 42] },
+            { "id": 2, "genres": ["Café", "Cafe"] },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let fid = index.fields_ids_map(&rtxn).unwrap().id("genres").unwrap();
+
+    // the strings colliding in the facet search follow each other, with their original spelling
+    let mut text = Vec::new();
+    let written =
+        index.dump_facet_values(&rtxn, fid, FacetValuesDumpFormat::Text, &mut text).unwrap();
+    assert_eq!(written, 6);
+    assert_eq!(
+        std::str::from_utf8(&text).unwrap(),
+        "42.0\nCafe\nCafé\nComedy\nDrama\nScience Fiction\n"
+    );
+
+    let mut ndjson = Vec::new();
+    index.dump_facet_values(&rtxn, fid, FacetValuesDumpFormat::NdJson, &mut ndjson).unwrap();
+    let values: Vec<serde_json::Value> = serde_json::Deserializer::from_slice(&ndjson)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        values,
+        [
+            serde_json::json!(42.0),
+            serde_json::json!("Cafe"),
+            serde_json::json!("Café"),
+            serde_json::json!("Comedy"),
+            serde_json::json!("Drama"),
+            serde_json::json!("Science Fiction"),
+        ]
+    );
+}
+
+#[test]
+fn lazy_faceting_mode_returns_the_same_results_as_eager_mode() {
+    use crate::heed_codec::facet::FacetGroupKeyCodec;
+    use crate::heed_codec::BytesRefCodec;
+    use crate::index::FacetingMode;
+    use crate::search::facet::get_highest_level;
+    use crate::{AscDesc, Member};
+
+    let eager = TempIndex::new();
+    let lazy = TempIndex::new();
+    for (index, mode) in [(&eager, FacetingMode::Eager), (&lazy, FacetingMode::Lazy)] {
+        index
+            .update_settings(|settings| {
+                settings.set_faceting_mode(mode);
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field(S("price")),
+                    FilterableAttributesRule::Field(S("color")),
+                ]);
+                settings.set_sortable_fields(HashSet::from([S("price")]));
+            })
+            .unwrap();
+        // enough values for the eager mode to build several levels
+        let documents = (0..200).map(|i| {
+            let color = ["red", "blue", "green"][i % 3];
+            serde_json::json!({ "id": i, "price": (i * 7) % 101, "color": color })
+                .as_object()
+                .unwrap()
+                .clone()
+        });
+        index.add_documents(crate::documents::mmap_from_objects(documents)).unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 1000, "color": "purple" },
+                { "id": 1, "color": "red" },
+            ]))
+            .unwrap();
+        index.delete_documents(vec![S("2"), S("3")]);
+    }
+
+    let eager_rtxn = eager.read_txn().unwrap();
+    let lazy_rtxn = lazy.read_txn().unwrap();
+    let price = lazy.fields_ids_map(&lazy_rtxn).unwrap().id("price").unwrap();
+    let levels = |index: &TempIndex, rtxn: &heed::RoTxn<'_>| {
+        let db = index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        get_highest_level(rtxn, db, price).unwrap()
+    };
+    // the lazy index is queried through the level 0 only
+    assert!(levels(&eager, &eager_rtxn) > 0);
+    assert_eq!(levels(&lazy, &lazy_rtxn), 0);
+
+    for filter in ["price 10 TO 50", "price > 90 AND color != blue", "color = purple OR price = 1"]
+    {
+        let filter = Filter::from_str(filter).unwrap().unwrap();
+        assert_eq!(
+            filter.evaluate(&lazy_rtxn, &lazy).unwrap(),
+            filter.evaluate(&eager_rtxn, &eager).unwrap(),
+        );
+    }
+
+    for order_by in [OrderBy::Lexicographic, OrderBy::Count] {
+        let facets = [("price", order_by), ("color", order_by)];
+        assert_eq!(
+            lazy.facets_distribution(&lazy_rtxn).facets(facets).execute().unwrap(),
+            eager.facets_distribution(&eager_rtxn).facets(facets).execute().unwrap(),
+        );
+    }
+
+    let sorted_ids = |index: &TempIndex, rtxn: &heed::RoTxn<'_>| {
+        let mut search = index.search(rtxn);
+        search.sort_criteria(vec![AscDesc::Desc(Member::Field(S("price")))]).limit(20);
+        search.execute().unwrap().documents_ids
+    };
+    assert_eq!(sorted_ids(&lazy, &lazy_rtxn), sorted_ids(&eager, &eager_rtxn));
+}
+
+#[test]
+fn renamed_facet_values_are_consistent_once_the_documents_are_reindexed() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![FilterableAttributesRule::Field(S("color"))])
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "color": "red" },
+            { "id": 1, "color": "red" },
+            { "id": 2, "color": "blue" },
+        ]))
+        .unwrap();
+
+    let mut wtxn = index.write_txn().unwrap();
+    let field_id = index.fields_ids_map(&wtxn).unwrap().id("color").unwrap();
+    update::facet::FacetsUpdate::rename_value(&index, &mut wtxn, field_id, "red", "crimson")
+        .unwrap();
+    wtxn.commit().unwrap();
+
+    let docids = |filter: &str| {
+        let rtxn = index.read_txn().unwrap();
+        Filter::from_str(filter).unwrap().unwrap().evaluate(&rtxn, &index).unwrap()
+    };
+    assert_eq!(docids("color = crimson"), RoaringBitmap::from_iter([0, 1]));
+    assert!(docids("color = red").is_empty());
+
+    // the documents still hold the old value until they are reindexed with the new one
+    index
+        .add_documents(documents!([
+            { "id": 0, "color": "crimson" },
+            { "id": 1, "color": "crimson" },
+        ]))
+        .unwrap();
+    index.delete_documents(vec![S("0")]);
+    assert_eq!(docids("color = crimson"), RoaringBitmap::from_iter([1]));
+    assert!(docids("color = red").is_empty());
+    assert_eq!(docids("color = blue"), RoaringBitmap::from_iter([2]));
+}
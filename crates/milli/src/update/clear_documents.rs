@@ -41,6 +41,8 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
             facet_id_string_docids,
             facet_id_normalized_string_strings,
             facet_id_string_fst,
+            facet_id_string_recency,
+            facet_id_string_weight,
             facet_id_exists_docids,
             facet_id_is_null_docids,
             facet_id_is_empty_docids,
@@ -50,6 +52,8 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
             embedder_category_id: _,
             cellulite,
             documents,
+            lazy_facet_fsts: _,
+            hot_prefixes: _,
         } = self.index;
 
         let empty_roaring = RoaringBitmap::default();
@@ -64,6 +68,7 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         self.index.put_field_distribution(self.wtxn, &FieldDistribution::default())?;
         self.index.delete_geo_rtree(self.wtxn)?;
         self.index.delete_geo_faceted_documents_ids(self.wtxn)?;
+        self.index.delete_facet_incremental_updates(self.wtxn)?;
 
         // Remove all user-provided bits from the configs
         self.index.embedding_configs().clear_embedder_info_docids(self.wtxn)?;
@@ -83,6 +88,8 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         facet_id_f64_docids.clear(self.wtxn)?;
         facet_id_normalized_string_strings.clear(self.wtxn)?;
         facet_id_string_fst.clear(self.wtxn)?;
+        facet_id_string_recency.clear(self.wtxn)?;
+        facet_id_string_weight.clear(self.wtxn)?;
         facet_id_exists_docids.clear(self.wtxn)?;
         facet_id_is_null_docids.clear(self.wtxn)?;
         facet_id_is_empty_docids.clear(self.wtxn)?;
@@ -1,35 +1,144 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
 use std::io::BufReader;
 
-use grenad::{CompressionType, Merger};
-use heed::types::Bytes;
+use grenad::{CompressionType, Merger, MergerIter};
+use heed::types::{Bytes, DecodeIgnore};
 use heed::{BytesDecode, BytesEncode, Error, PutFlags, RoTxn, RwTxn};
 use roaring::RoaringBitmap;
 
-use super::{clear_facet_levels, FACET_GROUP_SIZE, FACET_MIN_LEVEL_SIZE};
+use super::{
+    apply_expected_cardinalities, capped_group_size, clear_facet_levels, validate_field_configs,
+    FacetConfig, FACET_GROUP_SIZE, FACET_MIN_LEVEL_SIZE,
+};
+use crate::error::InternalError;
 use crate::facet::FacetType;
 use crate::heed_codec::facet::{
     FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec,
 };
 use crate::heed_codec::BytesRefCodec;
-use crate::update::del_add::{DelAdd, KvReaderDelAdd};
+use crate::progress::Progress;
+use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::index_documents::{create_writer, valid_lmdb_key, writer_into_reader};
+use crate::update::new::DelAddRoaringBitmap;
 use crate::update::MergeDeladdCboRoaringBitmaps;
-use crate::{CboRoaringBitmapCodec, CboRoaringBitmapLenCodec, FieldId, Index, Result};
+use crate::{
+    try_split_array_at, CboRoaringBitmapCodec, CboRoaringBitmapLenCodec, FieldId, Index, Result,
+};
 
 /// Algorithm to insert elememts into the `facet_id_(string/f64)_docids` databases
 /// by rebuilding the database "from scratch".
 ///
 /// First, the new elements are inserted into the level 0 of the database. Then, the
 /// higher levels are cleared and recomputed from the content of level 0.
+///
+/// The delta is applied to the level 0 already in the database, the existing leaves are
+/// reused as they are and only the fields modified by the delta get their levels rebuilt.
+/// Appending values to a large tree therefore doesn't require extracting all the facet
+/// values of the documents again.
 pub struct FacetsUpdateBulk<'i> {
     index: &'i Index,
     group_size: u8,
     min_level_size: u8,
     facet_type: FacetType,
     field_ids: Vec<FieldId>,
+    field_configs: HashMap<FieldId, FacetConfig>,
     // None if level 0 does not need to be updated
-    delta_data: Option<Merger<BufReader<File>, MergeDeladdCboRoaringBitmaps>>,
+    delta_data: Option<FacetsDelta<'i, BufReader<File>>>,
+    progress: Option<&'i Progress>,
+    must_stop_processing: Option<&'i (dyn Fn() -> bool + Sync)>,
+    levels: LevelPolicy,
+}
+
+/// Which levels of the facet trees the bulk method builds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LevelPolicy {
+    /// The level 0 and the levels above it.
+    #[default]
+    Full,
+    /// Only the level 0, the existing upper levels are removed.
+    ///
+    /// The fields that are only filtered by exact values, e.g. with `=` or `IN`, don't use
+    /// the upper levels. The fields whose values can be compared or sorted are rejected,
+    /// as the range filters and the sort would have to scan their whole level 0.
+    LeafOnly,
+}
+
+/// A level 0 entry of the facet databases along with the documents to remove from and add to it.
+///
+/// The left bound of the key is already encoded with the codec of the facet type.
+pub type FacetDeltaItem = (FacetGroupKey<Vec<u8>>, DelAddRoaringBitmap);
+
+/// The changes to apply to the level 0 of a facet database.
+pub(crate) enum FacetsDelta<'a, R> {
+    /// The merged content of grenad readers.
+    Merger(Merger<R, MergeDeladdCboRoaringBitmaps>),
+    /// Entries read one at a time, sorted by key and without duplicates.
+    Stream(Box<dyn Iterator<Item = Result<FacetDeltaItem>> + 'a>),
+}
+
+impl<'a, R: std::io::Read + std::io::Seek> FacetsDelta<'a, R> {
+    fn into_entries(self) -> Result<FacetsDeltaIter<'a, R>> {
+        match self {
+            FacetsDelta::Merger(merger) => {
+                Ok(FacetsDeltaIter::Merger(merger.into_stream_merger_iter()?))
+            }
+            FacetsDelta::Stream(iter) => Ok(FacetsDeltaIter::Stream {
+                iter,
+                key: Vec::new(),
+                value: Vec::new(),
+                buffer: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// Yields the entries of a [`FacetsDelta`] with the same encoding whatever their source is.
+enum FacetsDeltaIter<'a, R> {
+    Merger(MergerIter<R, MergeDeladdCboRoaringBitmaps>),
+    Stream {
+        iter: Box<dyn Iterator<Item = Result<FacetDeltaItem>> + 'a>,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        buffer: Vec<u8>,
+    },
+}
+
+impl<R: std::io::Read + std::io::Seek> FacetsDeltaIter<'_, R> {
+    /// Returns the next encoded key and its `DelAdd` obkv of CboRoaringBitmaps.
+    fn next(&mut self) -> Result<Option<(&[u8], &[u8])>> {
+        match self {
+            FacetsDeltaIter::Merger(iter) => Ok(iter.next()?),
+            FacetsDeltaIter::Stream { iter, key, value, buffer } => {
+                let Some(item) = iter.next() else { return Ok(None) };
+                let (FacetGroupKey { field_id, level, left_bound }, deladd) = item?;
+
+                let group_key =
+                    FacetGroupKey { field_id, level, left_bound: left_bound.as_slice() };
+                let encoded = FacetGroupKeyCodec::<BytesRefCodec>::bytes_encode(&group_key)
+                    .map_err(Error::Encoding)?;
+                key.clear();
+                key.extend_from_slice(&encoded);
+
+                value.clear();
+                let mut writer = KvWriterDelAdd::new(&mut *value);
+                let DelAddRoaringBitmap { del, add } = deladd;
+                if let Some(del) = del {
+                    buffer.clear();
+                    CboRoaringBitmapCodec::serialize_into_vec(&del, buffer);
+                    writer.insert(DelAdd::Deletion, &*buffer)?;
+                }
+                if let Some(add) = add {
+                    buffer.clear();
+                    CboRoaringBitmapCodec::serialize_into_vec(&add, buffer);
+                    writer.insert(DelAdd::Addition, &*buffer)?;
+                }
+                writer.finish()?;
+
+                Ok(Some((key.as_slice(), value.as_slice())))
+            }
+        }
+    }
 }
 
 impl<'i> FacetsUpdateBulk<'i> {
@@ -44,10 +153,88 @@ impl<'i> FacetsUpdateBulk<'i> {
         FacetsUpdateBulk {
             index,
             field_ids,
+            field_configs: HashMap::new(),
             group_size,
             min_level_size,
             facet_type,
-            delta_data: Some(delta_data),
+            delta_data: Some(FacetsDelta::Merger(delta_data)),
+            progress: None,
+            must_stop_processing: None,
+            levels: LevelPolicy::default(),
+        }
+    }
+
+    /// Same as [`Self::new`] but the level 0 changes are read one by one from the given
+    /// iterator instead of being materialized in grenad files first, which keeps the
+    /// memory usage low when importing a huge number of facet values.
+    ///
+    /// The entries must be sorted by key and each key must only be given once.
+    pub fn new_from_stream(
+        index: &'i Index,
+        field_ids: Vec<FieldId>,
+        facet_type: FacetType,
+        delta_data: impl Iterator<Item = Result<FacetDeltaItem>> + 'i,
+        group_size: u8,
+        min_level_size: u8,
+    ) -> FacetsUpdateBulk<'i> {
+        FacetsUpdateBulk {
+            index,
+            field_ids,
+            field_configs: HashMap::new(),
+            group_size,
+            min_level_size,
+            facet_type,
+            delta_data: Some(FacetsDelta::Stream(Box::new(delta_data))),
+            progress: None,
+            must_stop_processing: None,
+            levels: LevelPolicy::default(),
+        }
+    }
+
+    /// Seeds the builder with the level 0 currently in the facet database of `facet_type`,
+    /// applies the given changes to these leaves and rebuilds the upper levels of the modified
+    /// fields from the result. This is a middle ground between the incremental method, which
+    /// updates the existing levels in place, and extracting the facet values of all the
+    /// documents again.
+    ///
+    /// Unlike [`Self::new_from_stream`], the entries are buffered: they don't have to be sorted
+    /// and a key can be given several times, its deletions and additions are then merged and
+    /// the deletions applied first. The keys must be level 0 keys, only the fields of the
+    /// entries are updated.
+    pub fn new_seeded(
+        index: &'i Index,
+        facet_type: FacetType,
+        delta_data: impl IntoIterator<Item = FacetDeltaItem>,
+    ) -> FacetsUpdateBulk<'i> {
+        let mut entries: BTreeMap<(FieldId, Vec<u8>), DelAddRoaringBitmap> = BTreeMap::new();
+        for (FacetGroupKey { field_id, level: _, left_bound }, deladd) in delta_data {
+            let entry =
+                entries.entry((field_id, left_bound)).or_insert_with(DelAddRoaringBitmap::empty);
+            if let Some(del) = deladd.del {
+                *entry.del.get_or_insert_with(RoaringBitmap::new) |= del;
+            }
+            if let Some(add) = deladd.add {
+                *entry.add.get_or_insert_with(RoaringBitmap::new) |= add;
+            }
+        }
+
+        // The keys are sorted by field id and then by left bound, like the encoded keys.
+        let field_ids: BTreeSet<FieldId> = entries.keys().map(|&(field_id, _)| field_id).collect();
+        let delta_data = entries.into_iter().map(|((field_id, left_bound), deladd)| {
+            Ok((FacetGroupKey { field_id, level: 0, left_bound }, deladd))
+        });
+
+        FacetsUpdateBulk {
+            index,
+            field_ids: field_ids.into_iter().collect(),
+            field_configs: HashMap::new(),
+            group_size: FACET_GROUP_SIZE,
+            min_level_size: FACET_MIN_LEVEL_SIZE,
+            facet_type,
+            delta_data: Some(FacetsDelta::Stream(Box::new(delta_data))),
+            progress: None,
+            must_stop_processing: None,
+            levels: LevelPolicy::default(),
         }
     }
 
@@ -59,16 +246,65 @@ impl<'i> FacetsUpdateBulk<'i> {
         FacetsUpdateBulk {
             index,
             field_ids,
+            field_configs: HashMap::new(),
             group_size: FACET_GROUP_SIZE,
             min_level_size: FACET_MIN_LEVEL_SIZE,
             facet_type,
             delta_data: None,
+            progress: None,
+            must_stop_processing: None,
+            levels: LevelPolicy::default(),
         }
     }
 
+    /// Sets the group size and min level size of specific fields, the other fields use the default ones.
+    pub fn field_configs(&mut self, field_configs: HashMap<FieldId, FacetConfig>) -> &mut Self {
+        self.field_configs = field_configs;
+        self
+    }
+
+    /// Sets which levels of the facet trees are built, all of them by default.
+    pub fn levels(&mut self, levels: LevelPolicy) -> &mut Self {
+        self.levels = levels;
+        self
+    }
+
+    /// Reports the memory buffered while building the levels to the given progress.
+    pub fn progress(&mut self, progress: &'i Progress) -> &mut Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Aborts the update with [`InternalError::AbortedIndexation`] as soon as the given
+    /// function returns `true`, the caller must then abort the write transaction.
+    pub fn must_stop_processing(
+        &mut self,
+        must_stop_processing: &'i (dyn Fn() -> bool + Sync),
+    ) -> &mut Self {
+        self.must_stop_processing = Some(must_stop_processing);
+        self
+    }
+
     #[tracing::instrument(level = "trace", skip_all, target = "indexing::facets::bulk")]
     pub fn execute(self, wtxn: &mut heed::RwTxn<'_>) -> Result<()> {
-        let Self { index, field_ids, group_size, min_level_size, facet_type, delta_data } = self;
+        let Self {
+            index,
+            field_ids,
+            mut field_configs,
+            group_size,
+            min_level_size,
+            facet_type,
+            delta_data,
+            progress,
+            must_stop_processing,
+            levels,
+        } = self;
+        validate_field_configs(&field_configs)?;
+        if levels == LevelPolicy::LeafOnly {
+            validate_leaf_only_fields(index, wtxn, &field_ids)?;
+        }
+        apply_expected_cardinalities(index, wtxn, &mut field_configs)?;
+        let max_levels = index.max_facet_levels(wtxn)?;
 
         let db = match facet_type {
             FacetType::String => {
@@ -79,27 +315,79 @@ impl<'i> FacetsUpdateBulk<'i> {
             }
         };
 
-        let inner = FacetsUpdateBulkInner { db, delta_data, group_size, min_level_size };
+        let inner = FacetsUpdateBulkInner {
+            db,
+            delta_data,
+            group_size,
+            min_level_size,
+            field_configs,
+            max_levels,
+            progress,
+            must_stop_processing,
+        };
 
-        inner.update(wtxn, &field_ids)?;
+        match levels {
+            LevelPolicy::Full => {
+                inner.update(wtxn, &field_ids)?;
+            }
+            LevelPolicy::LeafOnly => inner.update_level0_only(wtxn, &field_ids)?,
+        }
 
         Ok(())
     }
 }
 
+/// Checks that the fields can be queried without the levels above the level 0 of their facet trees.
+fn validate_leaf_only_fields(index: &Index, rtxn: &RoTxn<'_>, field_ids: &[FieldId]) -> Result<()> {
+    let fields_ids_map = index.fields_ids_map_with_metadata(rtxn)?;
+    let filterable_attributes_rules = index.filterable_attributes_rules(rtxn)?;
+    for &field_id in field_ids {
+        let Some(metadata) = fields_ids_map.metadata(field_id) else { continue };
+        let features = metadata.filterable_attributes_features(&filterable_attributes_rules);
+        let reason = if features.is_filterable_comparison() {
+            "the comparison filters need the levels above the level 0"
+        } else if metadata.is_sortable() || metadata.is_asc_desc() {
+            "the sort needs the levels above the level 0"
+        } else {
+            continue;
+        };
+        return Err(InternalError::InvalidFacetConfig { field_id, reason }.into());
+    }
+    Ok(())
+}
+
 /// Implementation of `FacetsUpdateBulk` that is independent of milli's `Index` type
-pub(crate) struct FacetsUpdateBulkInner<R: std::io::Read + std::io::Seek> {
+pub(crate) struct FacetsUpdateBulkInner<'a, R: std::io::Read + std::io::Seek> {
     pub db: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
-    pub delta_data: Option<Merger<R, MergeDeladdCboRoaringBitmaps>>,
+    pub delta_data: Option<FacetsDelta<'a, R>>,
     pub group_size: u8,
     pub min_level_size: u8,
+    /// The fields that don't use the default `group_size` and `min_level_size`.
+    pub field_configs: HashMap<FieldId, FacetConfig>,
+    /// The maximum number of levels to build above the level 0, the group size of
+    /// the fields is raised when needed to respect it.
+    pub max_levels: Option<u8>,
+    /// Where to report the memory buffered while building the levels and the raised group sizes.
+    pub progress: Option<&'a Progress>,
+    /// Polled while the level 0 and the levels of each field are built to abort the update.
+    pub must_stop_processing: Option<&'a (dyn Fn() -> bool + Sync)>,
 }
-impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
-    pub fn update(mut self, wtxn: &mut RwTxn<'_>, field_ids: &[u16]) -> Result<()> {
-        self.update_level0(wtxn)?;
-        clear_facet_levels(wtxn, &self.db.remap_data_type(), field_ids)?;
+impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<'_, R> {
+    /// Returns the ids of the fields whose levels were rebuilt.
+    pub fn update(mut self, wtxn: &mut RwTxn<'_>, field_ids: &[u16]) -> Result<Vec<FieldId>> {
+        // Only the fields whose level 0 changed need their levels to be rebuilt,
+        // when there is no delta data the levels of all the given fields are rebuilt.
+        let field_ids: Vec<_> = match self.update_level0(wtxn)? {
+            Some(updated_field_ids) => {
+                field_ids.iter().copied().filter(|fid| updated_field_ids.contains(fid)).collect()
+            }
+            None => field_ids.to_vec(),
+        };
+        clear_facet_levels(wtxn, &self.db.remap_data_type(), &field_ids)?;
 
         for &field_id in field_ids.iter() {
+            self.stop_if_required()?;
+            self.cap_levels(wtxn, field_id)?;
             let level_readers = self.compute_levels_for_field_id(field_id, wtxn)?;
 
             for level_reader in level_readers {
@@ -109,22 +397,39 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
                 }
             }
         }
-        Ok(())
+        Ok(field_ids)
     }
 
-    fn update_level0(&mut self, wtxn: &mut RwTxn<'_>) -> Result<()> {
+    /// Updates the level 0 and removes the upper levels of the given fields without
+    /// recomputing them, as it is done when the index is in lazy faceting mode.
+    ///
+    /// The levels are not rebuilt at search time: the queries on these fields walk
+    /// their whole level 0 until [`super::build_deferred_facet_structures`] is called.
+    pub fn update_level0_only(mut self, wtxn: &mut RwTxn<'_>, field_ids: &[u16]) -> Result<()> {
+        self.update_level0(wtxn)?;
+        clear_facet_levels(wtxn, &self.db.remap_data_type(), field_ids)
+    }
+
+    /// Applies the delta data to the level 0 and returns the ids of the fields it modified,
+    /// or `None` if there is no delta data.
+    fn update_level0(&mut self, wtxn: &mut RwTxn<'_>) -> Result<Option<BTreeSet<FieldId>>> {
         let delta_data = match self.delta_data.take() {
             Some(x) => x,
-            None => return Ok(()),
+            None => return Ok(None),
         };
+        let mut updated_field_ids = BTreeSet::new();
         if self.db.is_empty(wtxn)? {
             let mut buffer = Vec::new();
             let mut database = self.db.iter_mut(wtxn)?.remap_types::<Bytes, Bytes>();
-            let mut iter = delta_data.into_stream_merger_iter()?;
+            let mut iter = delta_data.into_entries()?;
             while let Some((key, value)) = iter.next()? {
+                self.stop_if_required()?;
                 if !valid_lmdb_key(key) {
                     continue;
                 }
+                if let Some((field_id, _)) = try_split_array_at(key) {
+                    updated_field_ids.insert(FieldId::from_be_bytes(field_id));
+                }
                 let value = KvReaderDelAdd::from_slice(value);
 
                 // DB is empty, it is safe to ignore Del operations
@@ -145,11 +450,15 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
             let mut buffer = Vec::new();
             let database = self.db.remap_types::<Bytes, Bytes>();
 
-            let mut iter = delta_data.into_stream_merger_iter()?;
+            let mut iter = delta_data.into_entries()?;
             while let Some((key, value)) = iter.next()? {
+                self.stop_if_required()?;
                 if !valid_lmdb_key(key) {
                     continue;
                 }
+                if let Some((field_id, _)) = try_split_array_at(key) {
+                    updated_field_ids.insert(FieldId::from_be_bytes(field_id));
+                }
 
                 let value = KvReaderDelAdd::from_slice(value);
 
@@ -183,8 +492,68 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
                 }
             }
         }
+        Ok(Some(updated_field_ids))
+    }
+    /// Returns an error if the update must be aborted.
+    fn stop_if_required(&self) -> Result<()> {
+        match self.must_stop_processing {
+            Some(must_stop_processing) if must_stop_processing() => {
+                Err(InternalError::AbortedIndexation.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the group size and min level size of the given field.
+    fn level_parameters(&self, field_id: FieldId) -> (u8, u8) {
+        match self.field_configs.get(&field_id) {
+            Some(config) => (config.group_size, config.min_level_size),
+            None => (self.group_size, self.min_level_size),
+        }
+    }
+
+    /// Raises the group size of the field if its levels would otherwise exceed `max_levels`.
+    fn cap_levels(&mut self, rtxn: &RoTxn<'_>, field_id: FieldId) -> Result<()> {
+        let Some(max_levels) = self.max_levels else {
+            return Ok(());
+        };
+
+        let mut level_0_prefix = vec![];
+        level_0_prefix.extend_from_slice(&field_id.to_be_bytes());
+        level_0_prefix.push(0);
+
+        let mut level_0_len = 0;
+        for result in
+            self.db.remap_types::<Bytes, DecodeIgnore>().prefix_iter(rtxn, &level_0_prefix)?
+        {
+            result?;
+            level_0_len += 1;
+        }
+
+        let (group_size, min_level_size) = self.level_parameters(field_id);
+        let capped = capped_group_size(level_0_len, group_size, min_level_size, max_levels);
+        if capped == group_size {
+            return Ok(());
+        }
+
+        tracing::info!(
+            %field_id,
+            level_0_len,
+            max_levels,
+            from = group_size,
+            to = capped,
+            "raising the facet group size to respect the maximum number of facet levels"
+        );
+        let config = self.field_configs.get(&field_id).copied().unwrap_or_default();
+        self.field_configs
+            .insert(field_id, FacetConfig { group_size: capped, min_level_size, ..config });
+        if let Some(progress) = self.progress {
+            progress.record_facet_effective_group_size(capped);
+        }
+
         Ok(())
     }
+
     fn compute_levels_for_field_id(
         &self,
         field_id: FieldId,
@@ -205,6 +574,7 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
         // 1. keep track of the left bound
         // 2. fill the `bitmaps` vector to give it to level 1 once `level_group_size` elements were read
         let mut bitmaps = vec![];
+        let (group_size, _) = self.level_parameters(field_id);
 
         let mut level_0_prefix = vec![];
         level_0_prefix.extend_from_slice(&field_id.to_be_bytes());
@@ -219,6 +589,7 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
         let mut left_bound: &[u8] = &[];
         let mut first_iteration_for_new_group = true;
         for el in level_0_iter {
+            self.stop_if_required()?;
             let (key, value) = el?;
             let bound = key.left_bound;
             let docids = value.bitmap;
@@ -229,7 +600,7 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
             }
             bitmaps.push(docids);
 
-            if bitmaps.len() == self.group_size as usize {
+            if bitmaps.len() == group_size as usize {
                 handle_group(&bitmaps, left_bound)?;
                 first_iteration_for_new_group = true;
                 bitmaps.clear();
@@ -266,8 +637,11 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
         // once we have computed `level_group_size` elements, we give the left bound
         // of those elements, and their bitmaps, to the level above
 
+        let (group_size, min_level_size) = self.level_parameters(field_id);
         let mut cur_writer = create_writer(CompressionType::None, None, tempfile::tempfile()?);
         let mut cur_writer_len: usize = 0;
+        // the size of the largest group of elements buffered before being written
+        let mut max_group_bytes: usize = 0;
 
         let mut group_sizes = vec![];
         let mut left_bounds = vec![];
@@ -290,12 +664,13 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
                 left_bounds.push(left_bound);
 
                 bitmaps.push(combined_bitmap);
-                if bitmaps.len() != self.group_size as usize {
+                if bitmaps.len() != group_size as usize {
                     return Ok(());
                 }
                 let left_bound = left_bounds.first().unwrap();
                 handle_group(&bitmaps, left_bound)?;
 
+                let mut group_bytes = 0;
                 for ((bitmap, left_bound), group_size) in
                     bitmaps.drain(..).zip(left_bounds.drain(..)).zip(group_sizes.drain(..))
                 {
@@ -305,9 +680,11 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
                     let value = FacetGroupValue { size: group_size, bitmap };
                     let value =
                         FacetGroupValueCodec::bytes_encode(&value).map_err(Error::Encoding)?;
+                    group_bytes += key.len() + value.len();
                     cur_writer.insert(key, value)?;
                     cur_writer_len += 1;
                 }
+                max_group_bytes = max_group_bytes.max(group_bytes);
                 Ok(())
             },
         )?;
@@ -316,9 +693,9 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
         // but only do so if the current number of elements to be inserted into this
         // levelcould grow to the minimum level size
 
-        if !bitmaps.is_empty() && (cur_writer_len >= self.min_level_size as usize - 1) {
+        if !bitmaps.is_empty() && (cur_writer_len >= min_level_size as usize - 1) {
             // the length of bitmaps is between 0 and group_size
-            assert!(bitmaps.len() < self.group_size as usize);
+            assert!(bitmaps.len() < group_size as usize);
             assert!(cur_writer_len > 0);
 
             let left_bound = left_bounds.first().unwrap();
@@ -337,8 +714,12 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
                 cur_writer_len += 1;
             }
         }
+        // the level is complete, only its largest group is reported to keep the overhead low
+        if let Some(progress) = self.progress {
+            progress.record_facet_indexing_memory(max_group_bytes);
+        }
         // if we inserted enough elements to reach the minimum level size, then we push the writer
-        if cur_writer_len >= self.min_level_size as usize {
+        if cur_writer_len >= min_level_size as usize {
             sub_writers.push(writer_into_reader(cur_writer)?);
         } else {
             // otherwise, if there are still leftover elements, we give them to the level above
@@ -354,15 +735,30 @@ impl<R: std::io::Read + std::io::Seek> FacetsUpdateBulkInner<R> {
 #[cfg(test)]
 mod tests {
     use std::iter::once;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::Relaxed;
 
+    use grenad::{CompressionType, MergerBuilder};
+    use heed::types::Bytes;
+    use heed::BytesEncode;
     use roaring::RoaringBitmap;
 
+    use super::FacetsUpdateBulk;
+
     use crate::documents::mmap_from_objects;
-    use crate::heed_codec::facet::OrderedF64Codec;
-    use crate::heed_codec::StrRefCodec;
-    use crate::index::tests::TempIndex;
-    use crate::update::facet::test_helpers::{ordered_string, FacetIndex};
-    use crate::{db_snap, milli_snap, FilterableAttributesRule};
+    use crate::facet::FacetType;
+    use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, OrderedF64Codec};
+    use crate::heed_codec::{BytesRefCodec, StrRefCodec};
+    use crate::index::tests::{ordered_string, TempIndex};
+    use crate::search::facet::get_highest_level;
+    use crate::update::del_add::{DelAdd, KvWriterDelAdd};
+    use crate::update::facet::test_helpers::FacetIndex;
+    use crate::update::index_documents::{create_writer, writer_into_reader};
+    use crate::update::new::DelAddRoaringBitmap;
+    use crate::update::MergeDeladdCboRoaringBitmaps;
+    use crate::{
+        db_snap, milli_snap, CboRoaringBitmapCodec, Error, FilterableAttributesRule, InternalError,
+    };
 
     #[test]
     fn insert() {
@@ -445,6 +841,31 @@ mod tests {
         test("odd_group_odd_min_level", 7, 3);
     }
 
+    #[test]
+    fn only_rebuild_the_levels_of_updated_fields() {
+        let index = FacetIndex::<OrderedF64Codec>::new(4, 0 /*NA*/, 5);
+
+        let mut elements = Vec::<((u16, f64), RoaringBitmap)>::new();
+        for field_id in 0..2 {
+            for i in 0..100u32 {
+                elements.push(((field_id, i as f64), once(i).collect()));
+            }
+        }
+        let mut wtxn = index.env.write_txn().unwrap();
+        index.bulk_insert(&mut wtxn, &[0, 1], elements.iter());
+        assert_eq!(get_highest_level(&wtxn, index.content, 0).unwrap(), 2);
+        assert_eq!(get_highest_level(&wtxn, index.content, 1).unwrap(), 2);
+
+        // Only the field 1 is rebuilt with the smaller group size,
+        // the level 0 of the field 0 didn't change and its levels are kept as is.
+        index.group_size.set(2);
+        let elements: Vec<((u16, f64), RoaringBitmap)> =
+            (100..110u32).map(|i| ((1, i as f64), once(i).collect())).collect();
+        index.bulk_insert(&mut wtxn, &[0, 1], elements.iter());
+        assert_eq!(get_highest_level(&wtxn, index.content, 0).unwrap(), 2);
+        assert_eq!(get_highest_level(&wtxn, index.content, 1).unwrap(), 4);
+    }
+
     #[test]
     fn bug_3165() {
         // Indexing a number of facet values that falls within certains ranges (e.g. 22_540 qualifies)
@@ -519,4 +940,243 @@ mod tests {
         test("large_group_small_min_level", 16, 2);
         test("odd_group_odd_min_level", 7, 3);
     }
+
+    #[test]
+    fn stream_and_reader_build_the_same_trees() {
+        // (field id, facet value, docid, deletion)
+        type Entry = (u16, f64, u32, bool);
+
+        let encoded_key = |field_id: u16, value: f64| FacetGroupKey {
+            field_id,
+            level: 0,
+            left_bound: OrderedF64Codec::bytes_encode(&value).unwrap().into_owned(),
+        };
+
+        let with_reader = |index: &TempIndex, entries: &[Entry]| {
+            let mut writer =
+                create_writer(CompressionType::None, None, tempfile::tempfile().unwrap());
+            for &(field_id, value, docid, deletion) in entries {
+                let key = encoded_key(field_id, value);
+                let key =
+                    FacetGroupKey { field_id, level: 0, left_bound: key.left_bound.as_slice() };
+                let key = FacetGroupKeyCodec::<BytesRefCodec>::bytes_encode(&key).unwrap();
+                let deladd = if deletion { DelAdd::Deletion } else { DelAdd::Addition };
+                let bitmap = CboRoaringBitmapCodec::bytes_encode(&RoaringBitmap::from([docid]));
+                let mut inner_writer = KvWriterDelAdd::memory();
+                inner_writer.insert(deladd, bitmap.unwrap()).unwrap();
+                writer.insert(&key, inner_writer.into_inner().unwrap()).unwrap();
+            }
+            let reader = writer_into_reader(writer).unwrap();
+            let mut builder = MergerBuilder::new(MergeDeladdCboRoaringBitmaps);
+            builder.push(reader.into_cursor().unwrap());
+
+            let mut wtxn = index.write_txn().unwrap();
+            FacetsUpdateBulk::new(index, vec![0, 1], FacetType::Number, builder.build(), 4, 5)
+                .execute(&mut wtxn)
+                .unwrap();
+            wtxn.commit().unwrap();
+        };
+
+        let with_stream = |index: &TempIndex, entries: &[Entry]| {
+            let stream = entries.iter().map(|&(field_id, value, docid, deletion)| {
+                let deladd = if deletion {
+                    DelAddRoaringBitmap::new_del_u32(docid)
+                } else {
+                    DelAddRoaringBitmap::new_add_u32(docid)
+                };
+                Ok((encoded_key(field_id, value), deladd))
+            });
+
+            let mut wtxn = index.write_txn().unwrap();
+            FacetsUpdateBulk::new_from_stream(index, vec![0, 1], FacetType::Number, stream, 4, 5)
+                .execute(&mut wtxn)
+                .unwrap();
+            wtxn.commit().unwrap();
+        };
+
+        let content = |index: &TempIndex| {
+            let rtxn = index.read_txn().unwrap();
+            let db = index.facet_id_f64_docids.remap_types::<Bytes, Bytes>();
+            db.iter(&rtxn)
+                .unwrap()
+                .map(|result| result.map(|(k, v)| (k.to_vec(), v.to_vec())).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let additions: Vec<Entry> = (0..20_000u32)
+            .map(|i| (0, i as f64, i, false))
+            .chain((0..500u32).map(|i| (1, i as f64, i, false)))
+            .collect();
+        let deletions: Vec<Entry> =
+            (0..20_000u32).step_by(3).map(|i| (0, i as f64, i, true)).collect();
+
+        let reader_index = TempIndex::new();
+        let stream_index = TempIndex::new();
+
+        // the first import writes in an empty database
+        with_reader(&reader_index, &additions);
+        with_stream(&stream_index, &additions);
+        let rtxn = stream_index.read_txn().unwrap();
+        let db =
+            stream_index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        assert!(get_highest_level(&rtxn, db, 0).unwrap() >= 5);
+        drop(rtxn);
+        assert_eq!(content(&reader_index), content(&stream_index));
+
+        // then the changes are merged with the existing entries
+        with_reader(&reader_index, &deletions);
+        with_stream(&stream_index, &deletions);
+        assert_eq!(content(&reader_index), content(&stream_index));
+    }
+
+    #[test]
+    fn appending_to_an_existing_tree_matches_a_rebuild_from_scratch() {
+        let elements = |values: std::ops::Range<u32>| {
+            let mut elements = Vec::<((u16, f64), RoaringBitmap)>::new();
+            for i in values {
+                // field id = 0, left_bound = i, docids = [i, i + 1000]
+                elements.push(((0, i as f64), RoaringBitmap::from([i, i + 1000])));
+            }
+            elements
+        };
+
+        // the second bulk update only gives the appended leaves, the existing ones are reused
+        let appended = FacetIndex::<OrderedF64Codec>::new(4, 0 /*NA*/, 5);
+        let mut wtxn = appended.env.write_txn().unwrap();
+        appended.bulk_insert(&mut wtxn, &[0], elements(0..600).iter());
+        appended.bulk_insert(&mut wtxn, &[0], elements(600..1000).iter());
+        appended.verify_structure_validity(&wtxn, 0);
+        wtxn.commit().unwrap();
+
+        let from_scratch = FacetIndex::<OrderedF64Codec>::new(4, 0 /*NA*/, 5);
+        let mut wtxn = from_scratch.env.write_txn().unwrap();
+        from_scratch.bulk_insert(&mut wtxn, &[0], elements(0..1000).iter());
+        wtxn.commit().unwrap();
+
+        assert_eq!(format!("{appended}"), format!("{from_scratch}"));
+    }
+
+    #[test]
+    fn seeded_update_matches_a_rebuild_from_scratch() {
+        let entry = |value: u32, deladd: DelAddRoaringBitmap| {
+            let left_bound = OrderedF64Codec::bytes_encode(&(value as f64)).unwrap().into_owned();
+            (FacetGroupKey { field_id: 0, level: 0, left_bound }, deladd)
+        };
+        let update = |index: &TempIndex, entries: Vec<_>| {
+            let mut wtxn = index.write_txn().unwrap();
+            FacetsUpdateBulk::new_seeded(index, FacetType::Number, entries)
+                .execute(&mut wtxn)
+                .unwrap();
+            wtxn.commit().unwrap();
+        };
+        let content = |index: &TempIndex| {
+            let rtxn = index.read_txn().unwrap();
+            let db = index.facet_id_f64_docids.remap_types::<Bytes, Bytes>();
+            db.iter(&rtxn)
+                .unwrap()
+                .map(|result| result.map(|(k, v)| (k.to_vec(), v.to_vec())).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // the existing tree, then unsorted appends, deletions and duplicated keys
+        let seeded = TempIndex::new();
+        update(&seeded, (0..600).map(|i| entry(i, DelAddRoaringBitmap::new_add_u32(i))).collect());
+        let mut delta: Vec<_> =
+            (600..1000).rev().map(|i| entry(i, DelAddRoaringBitmap::new_add_u32(i))).collect();
+        delta.extend((0..600).step_by(5).map(|i| entry(i, DelAddRoaringBitmap::new_del_u32(i))));
+        delta.extend(
+            (600..1000).step_by(7).map(|i| entry(i, DelAddRoaringBitmap::new_add_u32(i + 2000))),
+        );
+        update(&seeded, delta);
+
+        let from_scratch = TempIndex::new();
+        let mut entries = Vec::new();
+        for i in (0..1000).filter(|&i| i >= 600 || i % 5 != 0) {
+            let mut deladd = DelAddRoaringBitmap::new_add_u32(i);
+            if i >= 600 && (i - 600) % 7 == 0 {
+                deladd.insert_add_u32(i + 2000);
+            }
+            entries.push(entry(i, deladd));
+        }
+        update(&from_scratch, entries);
+
+        let rtxn = seeded.read_txn().unwrap();
+        let db = seeded.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        assert!(get_highest_level(&rtxn, db, 0).unwrap() >= 2);
+        drop(rtxn);
+        assert_eq!(content(&seeded), content(&from_scratch));
+    }
+
+    #[test]
+    fn canceled_update_leaves_the_previous_facets_intact() {
+        let entries = |values: std::ops::Range<u32>| {
+            values.map(|i| {
+                let left_bound = OrderedF64Codec::bytes_encode(&(i as f64)).unwrap().into_owned();
+                let key = FacetGroupKey { field_id: 0, level: 0, left_bound };
+                Ok((key, DelAddRoaringBitmap::new_add_u32(i)))
+            })
+        };
+        let content = |index: &TempIndex| {
+            let rtxn = index.read_txn().unwrap();
+            let db = index.facet_id_f64_docids.remap_types::<Bytes, Bytes>();
+            db.iter(&rtxn)
+                .unwrap()
+                .map(|result| result.map(|(k, v)| (k.to_vec(), v.to_vec())).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let index = TempIndex::new();
+        let mut wtxn = index.write_txn().unwrap();
+        FacetsUpdateBulk::new_from_stream(
+            &index,
+            vec![0],
+            FacetType::Number,
+            entries(0..2000),
+            4,
+            5,
+        )
+        .execute(&mut wtxn)
+        .unwrap();
+        wtxn.commit().unwrap();
+        let previous = content(&index);
+
+        // canceled while applying the changes to the level 0
+        let polls = AtomicUsize::new(0);
+        let must_stop_processing = || polls.fetch_add(1, Relaxed) >= 100;
+        let mut wtxn = index.write_txn().unwrap();
+        let mut update = FacetsUpdateBulk::new_from_stream(
+            &index,
+            vec![0],
+            FacetType::Number,
+            entries(2000..4000),
+            4,
+            5,
+        );
+        update.must_stop_processing(&must_stop_processing);
+        let error = update.execute(&mut wtxn).unwrap_err();
+        assert!(matches!(error, Error::InternalError(InternalError::AbortedIndexation)));
+        wtxn.abort();
+        assert_eq!(content(&index), previous);
+
+        // canceled while the upper levels are being rebuilt
+        let polls = AtomicUsize::new(0);
+        let must_stop_processing = || polls.fetch_add(1, Relaxed) >= 1000;
+        let mut wtxn = index.write_txn().unwrap();
+        let mut update =
+            FacetsUpdateBulk::new_not_updating_level_0(&index, vec![0], FacetType::Number);
+        update.must_stop_processing(&must_stop_processing);
+        let error = update.execute(&mut wtxn).unwrap_err();
+        assert!(matches!(error, Error::InternalError(InternalError::AbortedIndexation)));
+        wtxn.abort();
+        assert_eq!(content(&index), previous);
+
+        // the same update goes through when it isn't canceled
+        let mut wtxn = index.write_txn().unwrap();
+        let mut update =
+            FacetsUpdateBulk::new_not_updating_level_0(&index, vec![0], FacetType::Number);
+        update.must_stop_processing(&|| false);
+        update.execute(&mut wtxn).unwrap();
+        wtxn.commit().unwrap();
+        assert_eq!(content(&index), previous);
+    }
 }
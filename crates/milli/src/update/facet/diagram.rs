@@ -0,0 +1,166 @@
+use std::fmt::Write as _;
+use std::ops::Range;
+
+use heed::BytesDecode;
+use roaring::RoaringBitmap;
+
+use crate::facet::FacetType;
+use crate::heed_codec::facet::{
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, OrderedF64Codec,
+};
+use crate::heed_codec::BytesRefCodec;
+use crate::{FieldId, Index, Result};
+
+/// The number of document ids listed in a cell, the others are elided.
+const MAX_DISPLAYED_DOCIDS: usize = 8;
+
+/// A node of the facet tree, along with the range of leaves it spans in the diagram.
+struct Cell {
+    title: String,
+    docids: String,
+    leaves: Range<usize>,
+}
+
+/// Renders the facet tree of a field in the diagram format of the documentation of this module,
+/// the highest level at the top and the leaves at the bottom, each node spanning its children.
+///
+/// The first line of a cell contains the left bound of the node and its number of children,
+/// the last one its document ids. Returns an empty string when the field has no facet values.
+pub fn facet_tree_ascii(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+    facet_type: FacetType,
+    field_id: FieldId,
+) -> Result<String> {
+    let db = match facet_type {
+        FacetType::String => {
+            index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>()
+        }
+        FacetType::Number => {
+            index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>()
+        }
+    };
+
+    // the levels are read from the leaves, the nodes of a level span the children of the level below
+    let mut levels: Vec<Vec<Cell>> = Vec::new();
+    for level in 0..=u8::MAX {
+        let prefix = FacetGroupKey { field_id, level, left_bound: &[][..] };
+        let mut cells = Vec::new();
+        let mut next_child = 0;
+        for result in db.prefix_iter(rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, FacetGroupValue { size, bitmap }) = result?;
+            let bound = match facet_type {
+                FacetType::String => format!("{:?}", String::from_utf8_lossy(left_bound)),
+                FacetType::Number => OrderedF64Codec::bytes_decode(left_bound)
+                    .map_or_else(|_| format!("{left_bound:?}"), |value| value.to_string()),
+            };
+            let (title, leaves) = match levels.last() {
+                None => (bound, cells.len()..cells.len() + 1),
+                Some(children) => {
+                    let first = next_child.min(children.len().saturating_sub(1));
+                    next_child += size as usize;
+                    let last = next_child.clamp(first + 1, children.len()) - 1;
+                    (
+                        format!("{bound} ({size})"),
+                        children[first].leaves.start..children[last].leaves.end,
+                    )
+                }
+            };
+            cells.push(Cell { title, docids: display_docids(&bitmap), leaves });
+        }
+        if cells.is_empty() {
+            break;
+        }
+        levels.push(cells);
+    }
+    let Some(leaves) = levels.first().map(Vec::len) else { return Ok(String::new()) };
+
+    // the leaves are widened for the nodes of the upper levels to fit in them
+    let mut widths = vec![0; leaves];
+    for cells in &levels {
+        for cell in cells {
+            let needed = cell.title.chars().count().max(cell.docids.chars().count()) + 2;
+            let width = span_width(&widths, &cell.leaves);
+            if needed > width {
+                widths[cell.leaves.end - 1] += needed - width;
+            }
+        }
+    }
+
+    let label_width = format!("Level {}", levels.len() - 1).len();
+    let margin = " ".repeat(label_width + 5);
+    let mut diagram = String::new();
+    let mut above: Option<&[Cell]> = None;
+    for (level, cells) in levels.iter().enumerate().rev() {
+        let border = match above {
+            None => border_line(&widths, None, Some(cells.as_slice()), '┌', '┐'),
+            Some(above) => border_line(&widths, Some(above), Some(cells.as_slice()), '├', '┤'),
+        };
+        writeln!(diagram, "{margin}{border}").unwrap();
+
+        let label = format!("Level {level}");
+        let frame = "─".repeat(label_width);
+        let lines = [
+            (format!("┌{frame}┐"), cells.iter().map(|cell| cell.title.as_str()).collect()),
+            (format!("│{label:<label_width$}│"), vec![""; cells.len()]),
+            (format!("└{frame}┘"), cells.iter().map(|cell| cell.docids.as_str()).collect()),
+        ];
+        for (label, contents) in lines {
+            let row: Vec<_> = cells
+                .iter()
+                .zip(contents)
+                .map(|(cell, content)| {
+                    let width = span_width(&widths, &cell.leaves);
+                    format!("{content:^width$}")
+                })
+                .collect();
+            writeln!(diagram, "{label}   │{}│", row.join("│")).unwrap();
+        }
+        above = Some(cells.as_slice());
+    }
+    writeln!(diagram, "{margin}{}", border_line(&widths, above, None, '└', '┘')).unwrap();
+
+    Ok(diagram)
+}
+
+/// The number of characters between the borders of a cell spanning the given leaves.
+fn span_width(widths: &[usize], leaves: &Range<usize>) -> usize {
+    widths[leaves.clone()].iter().sum::<usize>() + leaves.len() - 1
+}
+
+/// Draws the horizontal border between two levels, joining the vertical borders of both.
+fn border_line(
+    widths: &[usize],
+    above: Option<&[Cell]>,
+    below: Option<&[Cell]>,
+    left: char,
+    right: char,
+) -> String {
+    let starts = |cells: Option<&[Cell]>, leaf: usize| {
+        cells.is_some_and(|cells| cells.iter().any(|cell| cell.leaves.start == leaf))
+    };
+
+    let mut line = String::from(left);
+    for (leaf, width) in widths.iter().enumerate() {
+        if leaf != 0 {
+            line.push(match (starts(above, leaf), starts(below, leaf)) {
+                (true, true) => '┼',
+                (true, false) => '┴',
+                (false, true) => '┬',
+                (false, false) => '─',
+            });
+        }
+        line.extend(std::iter::repeat_n('─', *width));
+    }
+    line.push(right);
+    line
+}
+
+fn display_docids(docids: &RoaringBitmap) -> String {
+    let mut ids: Vec<_> =
+        docids.iter().take(MAX_DISPLAYED_DOCIDS).map(|id| id.to_string()).collect();
+    if docids.len() > MAX_DISPLAYED_DOCIDS as u64 {
+        ids.push(format!("… {} more", docids.len() - MAX_DISPLAYED_DOCIDS as u64));
+    }
+    format!("[{}]", ids.join(", "))
+}
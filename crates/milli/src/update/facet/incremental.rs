@@ -1,22 +1,32 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
 use std::io::BufReader;
 
 use grenad::Merger;
 use heed::types::{Bytes, DecodeIgnore};
-use heed::{BytesDecode, Error, RoTxn, RwTxn};
+use heed::{BytesDecode, BytesEncode, Error, RoTxn, RwTxn};
 use obkv::KvReader;
 use roaring::RoaringBitmap;
+use time::OffsetDateTime;
 
+use super::bulk::FacetDeltaItem;
+use super::{
+    update_facet_string_fst, validate_field_configs, FacetConfig, FacetRebalancing,
+    FacetSearchNormalization, FacetUpdateMethod, FACET_GROUP_SIZE, FACET_MAX_GROUP_SIZE,
+    FACET_MIN_LEVEL_SIZE,
+};
 use crate::facet::FacetType;
 use crate::heed_codec::facet::{
     FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec,
 };
-use crate::heed_codec::BytesRefCodec;
-use crate::search::facet::get_highest_level;
+use crate::heed_codec::{BytesRefCodec, StrRefCodec};
+use crate::index::FacetingMode;
+use crate::search::facet::{get_highest_level, normalize_facet_string_for_search};
 use crate::update::del_add::DelAdd;
 use crate::update::index_documents::valid_lmdb_key;
+use crate::update::new::DelAddRoaringBitmap;
 use crate::update::MergeDeladdCboRoaringBitmaps;
-use crate::{CboRoaringBitmapCodec, Index, Result};
+use crate::{CboRoaringBitmapCodec, FieldId, Index, Result};
 
 /// Enum used as a return value for the facet incremental indexing.
 ///
@@ -83,13 +93,23 @@ impl FacetsUpdateIncremental {
                 group_size,
                 max_group_size,
                 min_level_size,
+                field_configs: HashMap::new(),
             },
             delta_data,
         }
     }
 
+    /// Sets the facet levels parameters of specific fields, the other fields use the default ones.
+    pub fn field_configs(&mut self, field_configs: HashMap<FieldId, FacetConfig>) -> &mut Self {
+        self.inner.field_configs = field_configs;
+        self
+    }
+
     #[tracing::instrument(level = "trace", skip_all, target = "indexing::facets::incremental")]
-    pub fn execute(self, wtxn: &mut RwTxn<'_>) -> crate::Result<()> {
+    /// Returns the ids of the fields the delta modified.
+    pub fn execute(self, wtxn: &mut RwTxn<'_>) -> crate::Result<BTreeSet<FieldId>> {
+        validate_field_configs(&self.inner.field_configs)?;
+        let mut modified_field_ids = BTreeSet::new();
         let mut current_field_id = None;
         let mut facet_level_may_be_updated = false;
         let mut iter = self.delta_data.into_stream_merger_iter()?;
@@ -108,6 +128,7 @@ impl FacetsUpdateIncremental {
                 facet_level_may_be_updated = false;
             }
             current_field_id = Some(key.field_id);
+            modified_field_ids.insert(key.field_id);
 
             let value = KvReader::from_slice(value);
             let docids_to_delete = value
@@ -143,18 +164,277 @@ impl FacetsUpdateIncremental {
             }
         }
 
-        Ok(())
+        Ok(modified_field_ids)
     }
 }
 
+/// The default number of level 0 changes applied and committed together by a
+/// [`FacetsUpdateIncrementalStream`].
+pub const DEFAULT_STREAM_CHUNK_SIZE: usize = 10_000;
+
+/// Incrementally applies level 0 changes read one at a time, e.g. from the receiver of a channel
+/// fed by a live ingestion pipeline, instead of waiting for a whole batch of documents.
+///
+/// The changes are applied by chunks of at most `chunk_size` entries in the write transaction
+/// given by the caller, who owns it: nothing is committed by the stream. Committing the
+/// transaction after every chunk makes the new facet values filterable as soon as possible, the
+/// trees are valid after every chunk as the levels of the modified fields are added or removed
+/// at the end of each chunk. In lazy faceting mode, only the level 0 is updated.
+///
+/// Along with the facet levels, each chunk updates the `facet_id_exists_docids` of the modified
+/// fields and, for the string facets, the normalized facet strings and the FSTs of the facet
+/// search. A document whose only remaining values of a field are not indexed, e.g. empty strings,
+/// is removed from the documents having the field. The documents themselves and
+/// `field_id_docid_facet_strings` or `field_id_docid_facet_f64s` are not updated.
+///
+/// A chunk is only applied once it is full or the iterator ends, which only happens for the
+/// receiver of a channel when all its senders are dropped.
+pub struct FacetsUpdateIncrementalStream<'i, I> {
+    index: &'i Index,
+    facet_type: FacetType,
+    inner: FacetsUpdateIncrementalInner,
+    delta_data: I,
+    chunk_size: usize,
+}
+
+impl<'i, I> FacetsUpdateIncrementalStream<'i, I>
+where
+    I: Iterator<Item = Result<FacetDeltaItem>>,
+{
+    /// The entries don't have to be sorted, but the keys must be level 0 keys.
+    pub fn new(
+        index: &'i Index,
+        facet_type: FacetType,
+        delta_data: impl IntoIterator<IntoIter = I>,
+    ) -> Self {
+        FacetsUpdateIncrementalStream {
+            index,
+            facet_type,
+            inner: FacetsUpdateIncrementalInner {
+                db: match facet_type {
+                    FacetType::String => index
+                        .facet_id_string_docids
+                        .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                    FacetType::Number => index
+                        .facet_id_f64_docids
+                        .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                },
+                group_size: FACET_GROUP_SIZE,
+                max_group_size: FACET_MAX_GROUP_SIZE,
+                min_level_size: FACET_MIN_LEVEL_SIZE,
+                field_configs: HashMap::new(),
+            },
+            delta_data: delta_data.into_iter(),
+            chunk_size: DEFAULT_STREAM_CHUNK_SIZE,
+        }
+    }
+
+    /// Sets the maximum number of entries applied together, at least one.
+    pub fn chunk_size(&mut self, chunk_size: usize) -> &mut Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Sets the facet levels parameters of specific fields, the other fields use the default ones.
+    pub fn field_configs(&mut self, field_configs: HashMap<FieldId, FacetConfig>) -> &mut Self {
+        self.inner.field_configs = field_configs;
+        self
+    }
+
+    /// Applies the next chunk of changes in `wtxn`, returning the ids of the fields it modified,
+    /// or `None` without writing anything if there are no more changes.
+    ///
+    /// The chunk is only visible to the readers once the caller commits `wtxn`.
+    pub fn apply_next_chunk(&mut self, wtxn: &mut RwTxn<'_>) -> Result<Option<BTreeSet<FieldId>>> {
+        validate_field_configs(&self.inner.field_configs)?;
+        let mut chunk = self.delta_data.by_ref().take(self.chunk_size).peekable();
+        if chunk.peek().is_none() {
+            return Ok(None);
+        }
+
+        let faceting_mode = self.index.faceting_mode(wtxn)?;
+        let mut modified_field_ids = BTreeSet::new();
+        let mut levels_may_be_updated = BTreeSet::new();
+        let mut modified_values: BTreeMap<FieldId, BTreeSet<Vec<u8>>> = BTreeMap::new();
+        let mut added_docids: BTreeMap<FieldId, RoaringBitmap> = BTreeMap::new();
+        let mut deleted_docids: BTreeMap<FieldId, RoaringBitmap> = BTreeMap::new();
+        for entry in chunk {
+            let (key, DelAddRoaringBitmap { del, add }) = entry?;
+            let FacetGroupKey { field_id, level: _, left_bound } = key;
+            let encoded_key =
+                FacetGroupKey { field_id, level: 0, left_bound: left_bound.as_slice() };
+            let encoded_key = FacetGroupKeyCodec::<BytesRefCodec>::bytes_encode(&encoded_key)
+                .map_err(heed::Error::Encoding)?;
+            if !valid_lmdb_key(encoded_key) {
+                continue;
+            }
+
+            modified_field_ids.insert(field_id);
+            if self.inner.modify(wtxn, field_id, &left_bound, add.as_ref(), del.as_ref())? {
+                levels_may_be_updated.insert(field_id);
+            }
+            if let Some(add) = add {
+                *added_docids.entry(field_id).or_default() |= add;
+            }
+            if let Some(del) = del {
+                *deleted_docids.entry(field_id).or_default() |= del;
+            }
+            modified_values.entry(field_id).or_default().insert(left_bound);
+        }
+
+        if modified_field_ids.is_empty() {
+            return Ok(Some(modified_field_ids));
+        }
+        self.index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
+
+        // In lazy mode the levels are only built on explicit rebuilds.
+        if faceting_mode == FacetingMode::Eager {
+            // The levels are only added or removed once all the changes of the chunk are applied.
+            for &field_id in &levels_may_be_updated {
+                self.inner.add_or_delete_level(wtxn, field_id)?;
+            }
+
+            let mut rebalancing = FacetRebalancing::new(self.index, wtxn, self.facet_type)?;
+            for &field_id in &modified_field_ids {
+                rebalancing.record(field_id, FacetUpdateMethod::Incremental);
+            }
+            rebalancing.finish(self.index, wtxn)?;
+        }
+
+        update_exists_docids(self.index, wtxn, added_docids, deleted_docids)?;
+        if self.facet_type == FacetType::String {
+            update_facet_search(self.index, wtxn, modified_values, faceting_mode)?;
+        }
+
+        Ok(Some(modified_field_ids))
+    }
+
+    /// Applies all the changes chunk by chunk in `wtxn`, returning the number of chunks.
+    pub fn execute(mut self, wtxn: &mut RwTxn<'_>) -> Result<usize> {
+        let mut chunks = 0;
+        while self.apply_next_chunk(wtxn)?.is_some() {
+            chunks += 1;
+        }
+        Ok(chunks)
+    }
+}
+
+/// Adds the added document ids to the `facet_id_exists_docids` of their fields and removes
+/// the deleted ones that don't have any value for their field anymore.
+fn update_exists_docids(
+    index: &Index,
+    wtxn: &mut RwTxn<'_>,
+    added_docids: BTreeMap<FieldId, RoaringBitmap>,
+    deleted_docids: BTreeMap<FieldId, RoaringBitmap>,
+) -> Result<()> {
+    let field_ids: BTreeSet<_> =
+        added_docids.keys().chain(deleted_docids.keys()).copied().collect();
+    for field_id in field_ids {
+        let mut docids = index.exists_faceted_documents_ids(wtxn, field_id)?;
+        if let Some(added) = added_docids.get(&field_id) {
+            docids |= added;
+        }
+        if let Some(deleted) = deleted_docids.get(&field_id) {
+            let mut remaining = index.null_faceted_documents_ids(wtxn, field_id)?
+                | index.empty_faceted_documents_ids(wtxn, field_id)?;
+            // The nodes of the highest level contain the document ids of all the values.
+            for db in [
+                index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+            ] {
+                let level = get_highest_level(wtxn, db, field_id)?;
+                let prefix = FacetGroupKey { field_id, level, left_bound: &[][..] };
+                for result in db.prefix_iter(wtxn, &prefix)? {
+                    let (_, FacetGroupValue { bitmap, .. }) = result?;
+                    remaining |= bitmap;
+                }
+            }
+            docids -= deleted - remaining;
+        }
+
+        if docids.is_empty() {
+            index.facet_id_exists_docids.delete(wtxn, &field_id)?;
+        } else {
+            index.facet_id_exists_docids.put(wtxn, &field_id, &docids)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Updates the normalized facet strings of the facet search for the given level 0 string values,
+/// once their document ids were updated, then the FSTs of their fields in eager faceting mode.
+fn update_facet_search(
+    index: &Index,
+    wtxn: &mut RwTxn<'_>,
+    modified_values: BTreeMap<FieldId, BTreeSet<Vec<u8>>>,
+    faceting_mode: FacetingMode,
+) -> Result<()> {
+    if !index.facet_search(wtxn)? {
+        return Ok(());
+    }
+
+    let database = index.facet_id_normalized_string_strings;
+    for (field_id, values) in modified_values {
+        let FacetSearchNormalization { searchable, locales, lossy } =
+            FacetSearchNormalization::of_field(index, wtxn, field_id)?;
+        if !searchable {
+            continue;
+        }
+
+        let mut normalized_values = BTreeSet::new();
+        for value in &values {
+            let value = StrRefCodec::bytes_decode(value).map_err(heed::Error::Encoding)?;
+            let key = FacetGroupKey { field_id, level: 0, left_bound: value };
+            let exists =
+                index.facet_id_string_docids.remap_data_type::<DecodeIgnore>().get(wtxn, &key)?;
+
+            let normalized = normalize_facet_string_for_search(value, locales.as_deref(), lossy);
+            let search_key = (field_id, normalized.as_str());
+            let mut facets = database.get(wtxn, &search_key)?.unwrap_or_default();
+            let modified = match exists {
+                Some(_) => facets.insert(value.to_string()),
+                None => facets.remove(value),
+            };
+            if modified && facets.is_empty() {
+                database.delete(wtxn, &search_key)?;
+            } else if modified {
+                database.put(wtxn, &search_key, &facets)?;
+            }
+            // The count of the value changed even when its original values didn't.
+            normalized_values.insert(normalized);
+        }
+
+        if faceting_mode == FacetingMode::Lazy {
+            // The FSTs are built at search time.
+            index.facet_id_string_fst.delete(wtxn, &field_id)?;
+        } else {
+            update_facet_string_fst(index, wtxn, field_id, &normalized_values)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Implementation of `FacetsUpdateIncremental` that is independent of milli's `Index` type
 pub struct FacetsUpdateIncrementalInner {
     pub db: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
     pub group_size: u8,
     pub min_level_size: u8,
     pub max_group_size: u8,
+    /// The fields that don't use the default group sizes and min level size.
+    pub field_configs: HashMap<FieldId, FacetConfig>,
 }
 impl FacetsUpdateIncrementalInner {
+    /// Returns the facet levels parameters of the given field.
+    fn config(&self, field_id: FieldId) -> FacetConfig {
+        self.field_configs.get(&field_id).copied().unwrap_or(FacetConfig {
+            group_size: self.group_size,
+            max_group_size: self.max_group_size,
+            min_level_size: self.min_level_size,
+        })
+    }
+
     /// Find the `FacetGroupKey`/`FacetGroupValue` in the database that
     /// should be used to insert the new `facet_value` for the given `field_id` and `level`
     /// where `level` must be strictly greater than 0.
@@ -468,7 +748,7 @@ impl FacetsUpdateIncrementalInner {
                 (new_insertion_key, key_modification)
             };
 
-        if updated_value.size < self.max_group_size {
+        if updated_value.size < self.config(field_id).max_group_size {
             // If there are docids to delete, trim them avoiding unexpected removal.
             if let Some(del_docids) = del_docids
                 .map(|ids| {
@@ -554,7 +834,37 @@ impl FacetsUpdateIncrementalInner {
         }
     }
 
-    /// Check whether the highest level has exceeded `min_level_size` * `self.group_size`.
+    /// Move the document ids of the `old_value` facet value to the `new_value` facet value.
+    /// If `new_value` already exists, the document ids of both values are merged.
+    ///
+    /// ## Return
+    /// Returns `true` if some tree-nodes of the highest level have been removed or added implying a potential
+    /// addition or deletion of a facet level, see [`Self::modify`].
+    pub fn rename(
+        &self,
+        txn: &mut RwTxn<'_>,
+        field_id: u16,
+        old_value: &[u8],
+        new_value: &[u8],
+    ) -> Result<bool> {
+        if old_value == new_value {
+            return Ok(false);
+        }
+
+        let old_key = FacetGroupKey { field_id, level: 0, left_bound: old_value };
+        let Some(FacetGroupValue { bitmap: docids, .. }) = self.db.get(txn, &old_key)? else {
+            return Ok(false);
+        };
+
+        // Removing the old value takes care of updating the left bounds of the
+        // upper levels when the old value was the first value of a group.
+        let removed = self.modify(txn, field_id, old_value, None, Some(&docids))?;
+        let inserted = self.modify(txn, field_id, new_value, Some(&docids), None)?;
+
+        Ok(removed || inserted)
+    }
+
+    /// Check whether the highest level has exceeded `min_level_size` * `group_size` of the field.
     /// If it has, we must build an addition level above it.
     /// Then check whether the highest level is under `min_level_size`.
     /// If it has, we must remove the complete level.
@@ -567,9 +877,10 @@ impl FacetsUpdateIncrementalInner {
         let size_highest_level =
             self.db.remap_types::<Bytes, Bytes>().prefix_iter(txn, &highest_level_prefix)?.count();
 
-        if size_highest_level >= self.group_size as usize * self.min_level_size as usize {
+        let FacetConfig { group_size, min_level_size, .. } = self.config(field_id);
+        if size_highest_level >= group_size as usize * min_level_size as usize {
             self.add_level(txn, field_id, highest_level, &highest_level_prefix, size_highest_level)
-        } else if size_highest_level < self.min_level_size as usize && highest_level != 0 {
+        } else if size_highest_level < min_level_size as usize && highest_level != 0 {
             self.delete_level(txn, &highest_level_prefix)
         } else {
             Ok(())
@@ -610,14 +921,15 @@ impl FacetsUpdateIncrementalInner {
             .remap_types::<Bytes, FacetGroupValueCodec>()
             .prefix_iter(txn, highest_level_prefix)?;
 
-        let nbr_new_groups = size_highest_level / self.group_size as usize;
-        let nbr_leftover_elements = size_highest_level % self.group_size as usize;
+        let group_size = self.config(field_id).group_size;
+        let nbr_new_groups = size_highest_level / group_size as usize;
+        let nbr_leftover_elements = size_highest_level % group_size as usize;
 
         let mut to_add = vec![];
         for _ in 0..nbr_new_groups {
             let mut first_key = None;
             let mut values = RoaringBitmap::new();
-            for _ in 0..self.group_size {
+            for _ in 0..group_size {
                 let (key_bytes, value_i) = groups_iter.next().unwrap()?;
                 let key_i = FacetGroupKeyCodec::<BytesRefCodec>::bytes_decode(key_bytes)
                     .map_err(Error::Encoding)?;
@@ -632,7 +944,7 @@ impl FacetsUpdateIncrementalInner {
                 level: highest_level + 1,
                 left_bound: first_key.unwrap().left_bound,
             };
-            let value = FacetGroupValue { size: self.group_size, bitmap: values };
+            let value = FacetGroupValue { size: group_size, bitmap: values };
             to_add.push((key.into_owned(), value));
         }
         // now we add the rest of the level, in case its size is > group_size * min_level_size
@@ -691,14 +1003,27 @@ impl FacetGroupKey<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+    use std::iter::once;
+    use std::sync::mpsc;
+
+    use heed::types::Bytes;
+    use heed::{BytesDecode, BytesEncode};
     use rand::seq::SliceRandom;
     use rand::{Rng, SeedableRng};
     use roaring::RoaringBitmap;
 
-    use crate::heed_codec::facet::OrderedF64Codec;
-    use crate::heed_codec::StrRefCodec;
-    use crate::milli_snap;
-    use crate::update::facet::test_helpers::FacetIndex;
+    use super::FacetsUpdateIncrementalStream;
+    use crate::facet::FacetType;
+    use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, OrderedF64Codec};
+    use crate::heed_codec::{BytesRefCodec, StrRefCodec};
+    use crate::index::tests::TempIndex;
+    use crate::index::FacetingMode;
+    use crate::search::facet::get_highest_level;
+    use crate::update::facet::test_helpers::{verify_structure_validity, FacetIndex};
+    use crate::update::facet::{check_facet_search_fsts, FacetConfig, FACET_MAX_GROUP_SIZE};
+    use crate::update::new::DelAddRoaringBitmap;
+    use crate::{milli_snap, FilterableAttributesRule};
 
     #[test]
     fn append() {
@@ -715,6 +1040,51 @@ mod tests {
         txn.commit().unwrap();
         milli_snap!(format!("{index}"));
     }
+    #[test]
+    fn per_field_configs() {
+        let index = FacetIndex::<OrderedF64Codec>::new(4, 8, 5);
+        index
+            .field_configs
+            .borrow_mut()
+            .insert(1, FacetConfig { group_size: 2, max_group_size: 4, min_level_size: 2 });
+
+        // The levels are built with the bulk method and then updated incrementally,
+        // both must use the parameters of each field.
+        let elements: Vec<((u16, f64), RoaringBitmap)> = (0..2)
+            .flat_map(|field_id| {
+                (0..100u32).map(move |i| ((field_id, i as f64), once(i).collect()))
+            })
+            .collect();
+        let mut txn = index.env.write_txn().unwrap();
+        index.bulk_insert(&mut txn, &[0, 1], elements.iter());
+        for field_id in 0..2 {
+            for i in 100..300u32 {
+                index.insert(&mut txn, field_id, &(i as f64), &once(i).collect());
+            }
+        }
+        index.verify_structure_validity(&txn, 0);
+        index.verify_structure_validity(&txn, 1);
+
+        let first_group_size = |field_id: u16| {
+            let [a, b] = field_id.to_be_bytes();
+            let mut iter =
+                index.content.remap_key_type::<Bytes>().prefix_iter(&txn, &[a, b, 1]).unwrap();
+            iter.next().unwrap().unwrap().1.size
+        };
+        assert_eq!(first_group_size(0), 4);
+        assert_eq!(first_group_size(1), 2);
+    }
+
+    #[test]
+    fn invalid_facet_config() {
+        assert!(FacetConfig::default().validate(0).is_ok());
+
+        let config = FacetConfig { group_size: 4, max_group_size: 6, min_level_size: 5 };
+        insta::assert_snapshot!(config.validate(3).unwrap_err(), @"internal: invalid facet config for the field id 3: the max group size must be at least twice the group size.");
+        let config = FacetConfig { group_size: 1, max_group_size: 8, min_level_size: 5 };
+        insta::assert_snapshot!(config.validate(3).unwrap_err(), @"internal: invalid facet config for the field id 3: the group size must be at least 2.");
+    }
+
     #[test]
     fn many_field_ids_append() {
         let index = FacetIndex::<OrderedF64Codec>::new(4, 8, 5);
@@ -1028,6 +1398,67 @@ mod tests {
         milli_snap!(format!("{index}"), "after_delete");
     }
 
+    #[test]
+    fn rename_to_new_value() {
+        let index = FacetIndex::<StrRefCodec>::new(4, 8, 5);
+        let mut txn = index.env.write_txn().unwrap();
+        for key in 1000..1256 {
+            let bitmap = RoaringBitmap::from_iter([key]);
+            index.insert(&mut txn, 0, &format!("{key:x}").as_str(), &bitmap);
+        }
+        index.verify_structure_validity(&txn, 0);
+
+        // the first value is the left bound of a node at every level
+        index.rename(&mut txn, 0, &"3e8", &"zzz");
+        index.verify_structure_validity(&txn, 0);
+        // a value in the middle of a group
+        index.rename(&mut txn, 0, &"42a", &"0");
+        index.verify_structure_validity(&txn, 0);
+
+        assert_eq!(index.level_0_docids(&txn, 0, &"3e8"), None);
+        assert_eq!(index.level_0_docids(&txn, 0, &"zzz"), Some(RoaringBitmap::from_iter([1000])));
+        assert_eq!(index.level_0_docids(&txn, 0, &"42a"), None);
+        assert_eq!(index.level_0_docids(&txn, 0, &"0"), Some(RoaringBitmap::from_iter([1066])));
+        txn.commit().unwrap();
+    }
+
+    #[test]
+    fn rename_to_existing_value() {
+        let index = FacetIndex::<StrRefCodec>::new(4, 8, 5);
+        let mut txn = index.env.write_txn().unwrap();
+        for key in 1000..1256 {
+            let bitmap = RoaringBitmap::from_iter([key, key + 1000]);
+            index.insert(&mut txn, 0, &format!("{key:x}").as_str(), &bitmap);
+        }
+        index.verify_structure_validity(&txn, 0);
+
+        // the bitmaps of both values must be merged
+        index.rename(&mut txn, 0, &"3e8", &"4e7");
+        index.verify_structure_validity(&txn, 0);
+        index.rename(&mut txn, 0, &"4e6", &"3e9");
+        index.verify_structure_validity(&txn, 0);
+
+        assert_eq!(index.level_0_docids(&txn, 0, &"3e8"), None);
+        assert_eq!(
+            index.level_0_docids(&txn, 0, &"4e7"),
+            Some(RoaringBitmap::from_iter([1000, 1255, 2000, 2255]))
+        );
+        assert_eq!(index.level_0_docids(&txn, 0, &"4e6"), None);
+        assert_eq!(
+            index.level_0_docids(&txn, 0, &"3e9"),
+            Some(RoaringBitmap::from_iter([1001, 1254, 2001, 2254]))
+        );
+
+        txn.commit().unwrap();
+
+        // renaming a missing value does nothing
+        let before = format!("{index}");
+        let mut txn = index.env.write_txn().unwrap();
+        index.rename(&mut txn, 0, &"missing", &"3e9");
+        txn.commit().unwrap();
+        assert_eq!(before, format!("{index}"));
+    }
+
     #[test]
     fn shuffle_merge_string_and_delete() {
         let index = FacetIndex::<StrRefCodec>::new(4, 8, 5);
@@ -1058,4 +1489,140 @@ mod tests {
         txn.commit().unwrap();
         milli_snap!(format!("{index}"), "after_delete");
     }
+
+    #[test]
+    fn stream_commits_valid_trees_after_each_chunk() {
+        let index = TempIndex::new();
+        let db = index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let entry = |value: u32, deladd: DelAddRoaringBitmap| {
+            let left_bound = OrderedF64Codec::bytes_encode(&(value as f64)).unwrap().into_owned();
+            Ok((FacetGroupKey { field_id: 0, level: 0, left_bound }, deladd))
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let mut update = FacetsUpdateIncrementalStream::new(&index, FacetType::Number, receiver);
+        update.chunk_size(100);
+
+        // the values are pushed in a random order, a full chunk at a time
+        let mut rng = rand::rngs::SmallRng::from_seed([0; 32]);
+        let mut values: Vec<u32> = (0..1000).collect();
+        values.shuffle(&mut rng);
+        for chunk in values.chunks(100) {
+            for &value in chunk {
+                sender.send(entry(value, DelAddRoaringBitmap::new_add_u32(value))).unwrap();
+            }
+            let mut wtxn = index.write_txn().unwrap();
+            let modified = update.apply_next_chunk(&mut wtxn).unwrap();
+            wtxn.commit().unwrap();
+            assert_eq!(modified, Some(BTreeSet::from([0])));
+            let rtxn = index.read_txn().unwrap();
+            verify_structure_validity(&rtxn, db, 0, FACET_MAX_GROUP_SIZE);
+        }
+        let rtxn = index.read_txn().unwrap();
+        assert!(get_highest_level(&rtxn, db, 0).unwrap() >= 2);
+        drop(rtxn);
+
+        // then the even values are removed, removing nodes across the chunks
+        let even: Vec<u32> = values.iter().copied().filter(|value| value % 2 == 0).collect();
+        for chunk in even.chunks(100) {
+            for &value in chunk {
+                sender.send(entry(value, DelAddRoaringBitmap::new_del_u32(value))).unwrap();
+            }
+            let mut wtxn = index.write_txn().unwrap();
+            update.apply_next_chunk(&mut wtxn).unwrap();
+            wtxn.commit().unwrap();
+            let rtxn = index.read_txn().unwrap();
+            verify_structure_validity(&rtxn, db, 0, FACET_MAX_GROUP_SIZE);
+        }
+
+        drop(sender);
+        let mut wtxn = index.write_txn().unwrap();
+        assert_eq!(update.apply_next_chunk(&mut wtxn).unwrap(), None);
+        wtxn.commit().unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        // the keys of the level 0 of the field 0 start with its id and the level
+        let level_0: Vec<u32> = db
+            .remap_types::<Bytes, Bytes>()
+            .prefix_iter(&rtxn, &[0, 0, 0])
+            .unwrap()
+            .map(|result| {
+                let (key, _) = result.unwrap();
+                let key = FacetGroupKeyCodec::<OrderedF64Codec>::bytes_decode(key).unwrap();
+                key.left_bound as u32
+            })
+            .collect();
+        assert_eq!(level_0, (0..1000).filter(|value| value % 2 == 1).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stream_updates_the_exists_docids_and_the_facet_search() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_filterable_fields(vec![FilterableAttributesRule::Field("tag".to_string())])
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([{ "id": 0, "tag": "blue" }, { "id": 1, "tag": "red" }]))
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let field_id = index.fields_ids_map(&rtxn).unwrap().id("tag").unwrap();
+        drop(rtxn);
+
+        let entry = |value: &str, deladd: DelAddRoaringBitmap| {
+            let left_bound = value.as_bytes().to_vec();
+            Ok((FacetGroupKey { field_id, level: 0, left_bound }, deladd))
+        };
+
+        // the document 1 moves from red to green
+        let entries = vec![
+            entry("red", DelAddRoaringBitmap::new_del_u32(1)),
+            entry("green", DelAddRoaringBitmap::new_add_u32(1)),
+            entry("green", DelAddRoaringBitmap::new_add_u32(2)),
+            entry("blue", DelAddRoaringBitmap::new_del_u32(0)),
+        ];
+        let mut wtxn = index.write_txn().unwrap();
+        let update = FacetsUpdateIncrementalStream::new(&index, FacetType::String, entries);
+        assert_eq!(update.execute(&mut wtxn).unwrap(), 1);
+        wtxn.commit().unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let exists = index.exists_faceted_documents_ids(&rtxn, field_id).unwrap();
+        assert_eq!(exists, RoaringBitmap::from_iter([1, 2]));
+        let strings = index.facet_id_normalized_string_strings;
+        assert_eq!(strings.get(&rtxn, &(field_id, "red")).unwrap(), None);
+        assert_eq!(strings.get(&rtxn, &(field_id, "blue")).unwrap(), None);
+        assert_eq!(
+            strings.get(&rtxn, &(field_id, "green")).unwrap(),
+            Some(["green".to_string()].into())
+        );
+        let fst = index.facet_id_string_fst.get(&rtxn, &field_id).unwrap().unwrap();
+        assert_eq!(fst.get("green"), Some(2));
+        assert_eq!(fst.len(), 1);
+        assert_eq!(check_facet_search_fsts(&index, &rtxn).unwrap(), vec![]);
+        drop(rtxn);
+
+        // in lazy mode only the level 0 and the normalized strings are written
+        index.update_settings(|settings| settings.set_faceting_mode(FacetingMode::Lazy)).unwrap();
+        let values: Vec<String> = (0..500).map(|i| format!("value-{i:03}")).collect();
+        let entries = values.iter().map(|value| entry(value, DelAddRoaringBitmap::new_add_u32(3)));
+        let mut wtxn = index.write_txn().unwrap();
+        let mut update = FacetsUpdateIncrementalStream::new(&index, FacetType::String, entries);
+        update.chunk_size(100);
+        assert_eq!(update.execute(&mut wtxn).unwrap(), 5);
+        wtxn.commit().unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let db = index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        assert_eq!(get_highest_level(&rtxn, db, field_id).unwrap(), 0);
+        assert!(index.facet_id_string_fst.get(&rtxn, &field_id).unwrap().is_none());
+        assert_eq!(
+            strings.get(&rtxn, &(field_id, "value-042")).unwrap(),
+            Some(["value-042".to_string()].into())
+        );
+        let exists = index.exists_faceted_documents_ids(&rtxn, field_id).unwrap();
+        assert_eq!(exists, RoaringBitmap::from_iter([1, 2, 3]));
+    }
 }
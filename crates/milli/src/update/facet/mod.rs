@@ -38,6 +38,8 @@ In the diagram above, the first cell of level 2 is `ab (2)`. Its range is `ab ..
 `gaf` is the left bound of the next node) and it has two children. Its document ids are `[a,b,d,f,z]`.
 These documents all contain a facet value that is contained within `ab .. gaf`.
 
+The trees of a field can be rendered in this format with [`facet_tree_ascii`] to inspect them.
+
 In the database, each node is represented by a key/value pair encoded as a [`FacetGroupKey`] and a
 [`FacetGroupValue`], which have the following format:
 
@@ -55,7 +57,8 @@ FacetGroupValue:
 When the database is first created using the "bulk" method, each node has a fixed number of children
 (except for possibly the last one) given by the `group_size` parameter (default to `FACET_GROUP_SIZE`).
 The tree is also built such that the highest level has more than `min_level_size`
-(default to `FACET_MIN_LEVEL_SIZE`) elements in it.
+(default to `FACET_MIN_LEVEL_SIZE`) elements in it. The fields with an expected cardinality in the
+`faceting` settings start from a larger group size, building a shallower tree from their first values.
 
 When the database is incrementally updated, the number of children of a node can vary between
 1 and `max_group_size`. This is done so that most incremental operations do not need to change
@@ -67,7 +70,12 @@ minimise indexing time. The incremental method is faster when adding few new fac
 bulk method is faster when a large part of the database is modified. Empirically, it seems that
 it takes 50x more time to incrementally add N facet values to an existing database than it is to
 construct a database of N facet values. This is the heuristic that is used to choose between the
-two methods.
+two methods, unless a field has its own update method in the `faceting` settings of the index.
+
+The incremental method keeps the trees valid but does not keep them balanced, the number of
+incremental updates applied to the levels of each field since their last bulk rebuild is therefore
+counted, and once it reaches the `facet_rebalancing_threshold` of the index the next update of the
+field uses the bulk method whatever the size of its delta, see [`FacetRebalancing`].
 
 Related PR: https://github.com/meilisearch/milli/pull/619
 */
@@ -76,34 +84,265 @@ pub const FACET_MAX_GROUP_SIZE: u8 = 8;
 pub const FACET_GROUP_SIZE: u8 = 4;
 pub const FACET_MIN_LEVEL_SIZE: u8 = 5;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter};
 use std::ops::Bound;
 
-use grenad::Merger;
+use charabia::Language;
+use fst::Streamer;
+use grenad::{CompressionType, Merger, MergerBuilder};
 use heed::types::{Bytes, DecodeIgnore};
 use heed::BytesDecode as _;
+use memmap2::Mmap;
 use roaring::RoaringBitmap;
 use time::OffsetDateTime;
 use tracing::debug;
 
-use self::incremental::FacetsUpdateIncremental;
+use self::bulk::{FacetDeltaItem, FacetsDelta, FacetsUpdateBulkInner};
+use self::incremental::{FacetsUpdateIncremental, FacetsUpdateIncrementalInner};
 use super::settings::{InnerIndexSettings, InnerIndexSettingsDiff};
 use super::{FacetsUpdateBulk, MergeDeladdBtreesetString, MergeDeladdCboRoaringBitmaps};
+use crate::attribute_patterns::PatternMatch;
+use crate::error::{InternalError, UserError};
 use crate::facet::FacetType;
+use crate::filterable_attributes_rules::matching_features;
 use crate::heed_codec::facet::{
-    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec, OrderedF64Codec,
 };
 use crate::heed_codec::BytesRefCodec;
-use crate::search::facet::get_highest_level;
+use crate::index::{FacetUpdateMethodSetting, FacetingMode};
+use crate::search::facet::{
+    build_facet_string_fst, get_highest_level, normalize_facet_string_for_search,
+    original_facets_count,
+};
 use crate::update::del_add::{DelAdd, KvReaderDelAdd};
-use crate::{try_split_array_at, FieldId, Index, Result};
+use crate::update::index_documents::{
+    create_sorter, create_writer, writer_into_reader, MergeIgnoreValues,
+};
+use crate::update::new::{truncate_str, DelAddRoaringBitmap};
+use crate::{
+    try_split_array_at, BEU16StrCodec, DocumentId, FieldId, Index, Result, MAX_FACET_VALUE_LENGTH,
+};
+
+pub use self::diagram::facet_tree_ascii;
 
 pub mod bulk;
+mod diagram;
 pub mod incremental;
 pub mod new_incremental;
 
+/// The parameters used to build the facet levels of a field.
+///
+/// The default configuration is applied to the fields without a specific one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FacetConfig {
+    /// The number of children of the nodes built by the bulk method.
+    pub group_size: u8,
+    /// The number of children from which the incremental method splits a node in two.
+    pub max_group_size: u8,
+    /// The minimum number of nodes of the highest level.
+    pub min_level_size: u8,
+}
+
+impl Default for FacetConfig {
+    fn default() -> Self {
+        FacetConfig {
+            group_size: FACET_GROUP_SIZE,
+            max_group_size: FACET_MAX_GROUP_SIZE,
+            min_level_size: FACET_MIN_LEVEL_SIZE,
+        }
+    }
+}
+
+impl FacetConfig {
+    /// Checks that the incremental method can keep updating the levels built by the bulk method.
+    ///
+    /// When the incremental method splits a node of `max_group_size` children, the two resulting
+    /// nodes must still be at least as large as the ones built with `group_size`.
+    pub fn validate(&self, field_id: FieldId) -> Result<()> {
+        let reason = if self.group_size < 2 {
+            "the group size must be at least 2"
+        } else if (self.max_group_size as u16) < self.group_size as u16 * 2 {
+            "the max group size must be at least twice the group size"
+        } else if self.min_level_size == 0 {
+            "the min level size must be at least 1"
+        } else {
+            return Ok(());
+        };
+
+        Err(InternalError::InvalidFacetConfig { field_id, reason }.into())
+    }
+}
+
+/// The number of levels the trees of the fields with an expected cardinality are built for.
+const EXPECTED_CARDINALITY_MAX_LEVELS: u8 = 3;
+
+impl FacetConfig {
+    /// Returns the configuration building at most a few levels above `expected_cardinality` values.
+    ///
+    /// The group size is kept under half of `u8::MAX` for the max group size to stay valid.
+    pub fn for_expected_cardinality(expected_cardinality: u64) -> FacetConfig {
+        let default = FacetConfig::default();
+        let group_size = capped_group_size(
+            expected_cardinality,
+            default.group_size,
+            default.min_level_size,
+            EXPECTED_CARDINALITY_MAX_LEVELS,
+        )
+        .min(u8::MAX / 2);
+
+        FacetConfig {
+            group_size,
+            max_group_size: default.max_group_size.max(group_size * 2),
+            ..default
+        }
+    }
+}
+
+/// Returns the configurations of the fields with an expected cardinality in the index settings.
+pub(crate) fn expected_cardinality_field_configs(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+) -> Result<HashMap<FieldId, FacetConfig>> {
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let expected_cardinality = index.facet_expected_cardinality(rtxn)?;
+
+    Ok(expected_cardinality
+        .into_iter()
+        .filter_map(|(name, cardinality)| {
+            let field_id = fields_ids_map.id(&name)?;
+            Some((field_id, FacetConfig::for_expected_cardinality(cardinality)))
+        })
+        .collect())
+}
+
+/// Adds the configurations of the fields with an expected cardinality to the given ones,
+/// the configurations that were explicitly given take precedence.
+pub(crate) fn apply_expected_cardinalities(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+    field_configs: &mut HashMap<FieldId, FacetConfig>,
+) -> Result<()> {
+    for (field_id, config) in expected_cardinality_field_configs(index, rtxn)? {
+        field_configs.entry(field_id).or_insert(config);
+    }
+    Ok(())
+}
+
+/// Validates the configurations of all the given fields, see [`FacetConfig::validate`].
+pub(crate) fn validate_field_configs(field_configs: &HashMap<FieldId, FacetConfig>) -> Result<()> {
+    field_configs.iter().try_for_each(|(&field_id, config)| config.validate(field_id))
+}
+
+/// The largest group size the bulk method can use to respect the maximum number of levels,
+/// the incremental method must still be able to add a child to the nodes before splitting them.
+pub const FACET_MAX_CAPPED_GROUP_SIZE: u8 = u8::MAX - 1;
+
+/// Returns the smallest group size, starting from `group_size`, with which the bulk method
+/// builds at most `max_levels` levels above a level 0 of `level_0_len` entries.
+///
+/// Fields with so many values that even [`FACET_MAX_CAPPED_GROUP_SIZE`] is not enough use
+/// this largest group size.
+pub(crate) fn capped_group_size(
+    level_0_len: u64,
+    group_size: u8,
+    min_level_size: u8,
+    max_levels: u8,
+) -> u8 {
+    let number_of_levels = |group_size: u8| {
+        let mut level_len = level_0_len;
+        // the bulk method never builds more than 32 levels
+        (0..32)
+            .take_while(|_| {
+                level_len = level_len.div_ceil(group_size as u64);
+                level_len >= min_level_size as u64
+            })
+            .count()
+    };
+
+    (group_size..=FACET_MAX_CAPPED_GROUP_SIZE)
+        .find(|&group_size| number_of_levels(group_size) <= max_levels as usize)
+        .unwrap_or(FACET_MAX_CAPPED_GROUP_SIZE.max(group_size))
+}
+
+/// The method used to update the facet levels, see the module documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FacetUpdateMethod {
+    Bulk,
+    Incremental,
+}
+
+/// The method used to update the facet levels along with the size of the delta that drove
+/// the decision, i.e. the number of modified level 0 entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FacetUpdateDecision {
+    pub method: FacetUpdateMethod,
+    pub delta_size: u64,
+}
+
+/// Returns the method configured in the `faceting` settings for the facet levels of the fields,
+/// the fields using the `auto` method are omitted.
+pub(crate) fn configured_update_methods(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+) -> Result<HashMap<FieldId, FacetUpdateMethod>> {
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let update_methods = index.facet_update_methods(rtxn)?;
+
+    Ok(update_methods
+        .into_iter()
+        .filter_map(|(name, method)| {
+            let method = match method {
+                FacetUpdateMethodSetting::Auto => return None,
+                FacetUpdateMethodSetting::Bulk => FacetUpdateMethod::Bulk,
+                FacetUpdateMethodSetting::Incremental => FacetUpdateMethod::Incremental,
+            };
+            Some((fields_ids_map.id(&name)?, method))
+        })
+        .collect())
+}
+
+/// The number of incremental updates applied to the facet levels of each field since their
+/// last bulk rebuild, used to rebuild the levels once they were incrementally updated too often.
+pub(crate) struct FacetRebalancing {
+    facet_type: FacetType,
+    threshold: Option<u64>,
+    incremental_updates: BTreeMap<FieldId, u64>,
+}
+
+impl FacetRebalancing {
+    pub fn new(index: &Index, rtxn: &heed::RoTxn<'_>, facet_type: FacetType) -> heed::Result<Self> {
+        Ok(Self {
+            facet_type,
+            threshold: index.facet_rebalancing_threshold(rtxn)?,
+            incremental_updates: index.facet_incremental_updates(rtxn, facet_type)?,
+        })
+    }
+
+    /// Whether the levels of the field must be rebuilt with the bulk method whatever the size of the delta.
+    pub fn requires_bulk(&self, field_id: FieldId) -> bool {
+        let count = self.incremental_updates.get(&field_id).copied().unwrap_or(0);
+        self.threshold.is_some_and(|threshold| count >= threshold)
+    }
+
+    /// Registers the method used to update the levels of the field.
+    pub fn record(&mut self, field_id: FieldId, method: FacetUpdateMethod) {
+        match method {
+            FacetUpdateMethod::Bulk => {
+                self.incremental_updates.remove(&field_id);
+            }
+            FacetUpdateMethod::Incremental => {
+                *self.incremental_updates.entry(field_id).or_default() += 1;
+            }
+        }
+    }
+
+    pub fn finish(self, index: &Index, wtxn: &mut heed::RwTxn<'_>) -> heed::Result<()> {
+        index.put_facet_incremental_updates(wtxn, self.facet_type, &self.incremental_updates)
+    }
+}
+
 /// A builder used to add new elements to the `facet_id_string_docids` or `facet_id_f64_docids` databases.
 ///
 /// Depending on the number of new elements and the existing size of the database, we use either
@@ -117,7 +356,11 @@ pub struct FacetsUpdate<'i> {
     group_size: u8,
     max_group_size: u8,
     min_level_size: u8,
+    field_configs: HashMap<FieldId, FacetConfig>,
+    strict_length: bool,
     data_size: u64,
+    max_nb_chunks: Option<usize>,
+    max_memory: Option<usize>,
 }
 
 impl<'i> FacetsUpdate<'i> {
@@ -142,71 +385,466 @@ impl<'i> FacetsUpdate<'i> {
             group_size: FACET_GROUP_SIZE,
             max_group_size: FACET_MAX_GROUP_SIZE,
             min_level_size: FACET_MIN_LEVEL_SIZE,
+            field_configs: HashMap::new(),
+            strict_length: false,
             facet_type,
             delta_data,
             normalized_delta_data,
             data_size,
+            max_nb_chunks: None,
+            max_memory: None,
         }
     }
 
+    /// Returns an error with the offending value instead of indexing a string facet value that
+    /// reaches the maximum facet value length, meaning it may have been truncated by the extraction.
+    ///
+    /// Truncated values are at most 3 bytes shorter than the limit, because a char is never longer
+    /// than 4 bytes, all the values in this range are therefore reported.
+    pub fn strict_length(&mut self, strict_length: bool) -> &mut Self {
+        self.strict_length = strict_length;
+        self
+    }
+
+    /// Sets the maximum number of chunks of the sorter collecting the modified normalized facet
+    /// values, whose facet search FSTs are updated once the normalized facet strings are written.
+    pub fn max_nb_chunks(&mut self, max_nb_chunks: Option<usize>) -> &mut Self {
+        self.max_nb_chunks = max_nb_chunks;
+        self
+    }
+
+    /// Sets the memory the sorter collecting the modified normalized facet values can use before
+    /// dumping them to disk, `None` keeps them all in memory.
+    pub fn max_memory(&mut self, max_memory: Option<usize>) -> &mut Self {
+        self.max_memory = max_memory;
+        self
+    }
+
+    /// Sets the facet levels parameters of specific fields, the other fields keep the default ones.
+    pub fn field_configs(&mut self, field_configs: HashMap<FieldId, FacetConfig>) -> &mut Self {
+        self.field_configs = field_configs;
+        self
+    }
+
+    /// Returns the method used to update the facet levels of each field modified by the delta,
+    /// nothing if the levels were not updated.
+    ///
+    /// The fields with an update method in the `faceting` settings always use it, the others
+    /// use the same method chosen from the size of the whole delta.
     pub fn execute(
-        self,
+        mut self,
         wtxn: &mut heed::RwTxn<'_>,
         new_settings: &InnerIndexSettings,
-    ) -> Result<()> {
+    ) -> Result<BTreeMap<FieldId, FacetUpdateDecision>> {
         if self.data_size == 0 {
-            return Ok(());
+            return Ok(BTreeMap::new());
+        }
+        validate_field_configs(&self.field_configs)?;
+        apply_expected_cardinalities(self.index, wtxn, &mut self.field_configs)?;
+        if self.strict_length && self.facet_type == FacetType::String {
+            self.delta_data = check_facet_values_length(self.index, wtxn, self.delta_data)?;
         }
         debug!("Computing and writing the facet values levels docids into LMDB on disk...");
         self.index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
 
-        // See self::comparison_bench::benchmark_facet_indexing
-        if self.data_size >= (self.database.len(wtxn)? / 500) {
-            let field_ids = facet_levels_field_ids(new_settings);
-            let bulk_update = FacetsUpdateBulk::new(
-                self.index,
-                field_ids,
-                self.facet_type,
-                self.delta_data,
-                self.group_size,
-                self.min_level_size,
-            );
-            bulk_update.execute(wtxn)?;
+        let faceting_mode = self.index.faceting_mode(wtxn)?;
+        let decisions = if faceting_mode == FacetingMode::Lazy {
+            // The upper levels are only computed on explicit rebuilds.
+            let field_ids: Vec<_> = facet_levels_field_ids(new_settings);
+            let bulk_update = FacetsUpdateBulkInner {
+                db: self.database,
+                delta_data: Some(FacetsDelta::Merger(self.delta_data)),
+                group_size: self.group_size,
+                min_level_size: self.min_level_size,
+                field_configs: self.field_configs,
+                max_levels: None,
+                progress: None,
+                must_stop_processing: None,
+            };
+            bulk_update.update_level0_only(wtxn, &field_ids)?;
+            BTreeMap::new()
         } else {
-            let incremental_update = FacetsUpdateIncremental::new(
-                self.index,
-                self.facet_type,
-                self.delta_data,
-                self.group_size,
-                self.min_level_size,
-                self.max_group_size,
-            );
-            incremental_update.execute(wtxn)?;
-        }
+            let field_ids: Vec<_> = facet_levels_field_ids(new_settings);
+            let update_methods = configured_update_methods(self.index, wtxn)?;
+            let mut rebalancing = FacetRebalancing::new(self.index, wtxn, self.facet_type)?;
+            // The fields without a configured method are updated with the same method, the levels
+            // of all these fields are rebuilt as soon as one of them was incrementally updated too often.
+            let rebalance = field_ids
+                .iter()
+                .filter(|fid| !update_methods.contains_key(fid))
+                .any(|&fid| rebalancing.requires_bulk(fid));
+            // See self::comparison_bench::benchmark_facet_indexing
+            let auto_method = if rebalance || self.data_size >= (self.database.len(wtxn)? / 500) {
+                FacetUpdateMethod::Bulk
+            } else {
+                FacetUpdateMethod::Incremental
+            };
+            let PartitionedFacetsDelta { bulk, incremental, decisions } =
+                partition_facets_delta(self.delta_data, |fid| {
+                    if rebalancing.requires_bulk(fid) {
+                        FacetUpdateMethod::Bulk
+                    } else {
+                        update_methods.get(&fid).copied().unwrap_or(auto_method)
+                    }
+                })?;
+
+            if let Some(delta_data) = bulk {
+                let bulk_update = FacetsUpdateBulkInner {
+                    db: self.database,
+                    delta_data: Some(FacetsDelta::Merger(delta_data)),
+                    group_size: self.group_size,
+                    min_level_size: self.min_level_size,
+                    field_configs: self.field_configs.clone(),
+                    max_levels: self.index.max_facet_levels(wtxn)?,
+                    progress: None,
+                    must_stop_processing: None,
+                };
+                let rebuilt = bulk_update.update(wtxn, &field_ids)?;
+                // The fields to rebalance that the delta did not modify are rebuilt too.
+                let unbalanced: Vec<_> = field_ids
+                    .iter()
+                    .copied()
+                    .filter(|fid| rebalancing.requires_bulk(*fid) && !rebuilt.contains(fid))
+                    .collect();
+                if !unbalanced.is_empty() {
+                    let mut bulk_update = FacetsUpdateBulk::new_not_updating_level_0(
+                        self.index,
+                        unbalanced.clone(),
+                        self.facet_type,
+                    );
+                    bulk_update.field_configs(self.field_configs.clone());
+                    bulk_update.execute(wtxn)?;
+                }
+                for fid in rebuilt.into_iter().chain(unbalanced) {
+                    rebalancing.record(fid, FacetUpdateMethod::Bulk);
+                }
+            }
+
+            if let Some(delta_data) = incremental {
+                let mut incremental_update = FacetsUpdateIncremental::new(
+                    self.index,
+                    self.facet_type,
+                    delta_data,
+                    self.group_size,
+                    self.min_level_size,
+                    self.max_group_size,
+                );
+                incremental_update.field_configs(self.field_configs);
+                for fid in incremental_update.execute(wtxn)? {
+                    rebalancing.record(fid, FacetUpdateMethod::Incremental);
+                }
+            }
+
+            rebalancing.finish(self.index, wtxn)?;
+            decisions
+        };
 
         if !self.index.facet_search(wtxn)? {
             // If facet search is disabled, we don't need to compute facet search databases.
             // We clear the facet search databases.
+            self.index.facet_id_string_fst.clear(wtxn)?;
+            self.index.facet_id_normalized_string_strings.clear(wtxn)?;
+            return Ok(decisions);
+        }
+
+        if let Some(data) = self.normalized_delta_data {
+            let (max_nb_chunks, max_memory) = (self.max_nb_chunks, self.max_memory);
+            index_facet_search(wtxn, data, self.index, faceting_mode, max_nb_chunks, max_memory)?;
+        }
+
+        Ok(decisions)
+    }
+
+    /// Same as [`Self::execute`] but the facet levels of the modified fields are always rebuilt
+    /// with the bulk method, whatever the size of the delta and the configured update methods.
+    ///
+    /// Like [`Self::execute`], everything is written in `wtxn` and nothing is committed. LMDB readers
+    /// only see committed transactions: they keep reading the whole old facet trees until `wtxn` is
+    /// committed and the whole new ones afterward, never a partially rebuilt tree. If any step fails,
+    /// aborting `wtxn` leaves the facet databases untouched.
+    pub fn execute_bulk(
+        mut self,
+        wtxn: &mut heed::RwTxn<'_>,
+        new_settings: &InnerIndexSettings,
+    ) -> Result<()> {
+        if self.data_size == 0 {
+            return Ok(());
+        }
+        validate_field_configs(&self.field_configs)?;
+        apply_expected_cardinalities(self.index, wtxn, &mut self.field_configs)?;
+        if self.strict_length && self.facet_type == FacetType::String {
+            self.delta_data = check_facet_values_length(self.index, wtxn, self.delta_data)?;
+        }
+        debug!("Rebuilding the facet values levels docids into LMDB on disk...");
+        self.index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
+
+        let faceting_mode = self.index.faceting_mode(wtxn)?;
+        let field_ids: Vec<_> = facet_levels_field_ids(new_settings);
+        let bulk_update = FacetsUpdateBulkInner {
+            db: self.database,
+            delta_data: Some(FacetsDelta::Merger(self.delta_data)),
+            group_size: self.group_size,
+            min_level_size: self.min_level_size,
+            field_configs: self.field_configs,
+            max_levels: self.index.max_facet_levels(wtxn)?,
+            progress: None,
+            must_stop_processing: None,
+        };
+        if faceting_mode == FacetingMode::Lazy {
+            // The upper levels are only computed on explicit rebuilds.
+            bulk_update.update_level0_only(wtxn, &field_ids)?;
+        } else {
+            let mut rebalancing = FacetRebalancing::new(self.index, wtxn, self.facet_type)?;
+            for fid in bulk_update.update(wtxn, &field_ids)? {
+                rebalancing.record(fid, FacetUpdateMethod::Bulk);
+            }
+            rebalancing.finish(self.index, wtxn)?;
+        }
+
+        if !self.index.facet_search(wtxn)? {
             self.index.facet_id_string_fst.clear(wtxn)?;
             self.index.facet_id_normalized_string_strings.clear(wtxn)?;
             return Ok(());
         }
 
         match self.normalized_delta_data {
-            Some(data) => index_facet_search(wtxn, data, self.index),
+            Some(data) => {
+                let (max_nb_chunks, max_memory) = (self.max_nb_chunks, self.max_memory);
+                index_facet_search(wtxn, data, self.index, faceting_mode, max_nb_chunks, max_memory)
+            }
             None => Ok(()),
         }
     }
+
+    /// Alias of [`Self::execute_bulk`], building the new facet trees without exposing them.
+    ///
+    /// No shadow databases are needed to swap the trees atomically: the readers only ever see
+    /// the trees of committed transactions, as described in [`Self::execute_bulk`].
+    pub fn execute_shadowed(
+        self,
+        wtxn: &mut heed::RwTxn<'_>,
+        new_settings: &InnerIndexSettings,
+    ) -> Result<()> {
+        self.execute_bulk(wtxn, new_settings)
+    }
+}
+
+impl FacetsUpdate<'_> {
+    /// Renames a string facet value of the given field without reindexing the documents.
+    ///
+    /// The document ids of the `old` value are moved to the `new` value, or merged with the
+    /// document ids of `new` if it already exists. The original facet strings and the facet
+    /// search structures are updated accordingly, but the documents themselves are untouched.
+    ///
+    /// The indexing computes the facet changes of an updated or deleted document from its
+    /// stored content, which still holds `old`: its id would then be removed from `old` and
+    /// stay in `new` forever. The renamed documents must therefore be reindexed with `new`,
+    /// e.g. by updating them, before being updated or deleted in any other way.
+    pub fn rename_value(
+        index: &Index,
+        wtxn: &mut heed::RwTxn<'_>,
+        field_id: FieldId,
+        old: &str,
+        new: &str,
+    ) -> Result<()> {
+        let old_normalized = crate::normalize_facet(old);
+        let old_key = truncate_str(&old_normalized);
+        let new_normalized = crate::normalize_facet(new);
+        let new_key = truncate_str(&new_normalized);
+
+        let key = FacetGroupKey { field_id, level: 0, left_bound: old_key };
+        let Some(FacetGroupValue { bitmap: docids, .. }) =
+            index.facet_id_string_docids.get(wtxn, &key)?
+        else {
+            return Ok(());
+        };
+
+        index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
+        let faceting_mode = index.faceting_mode(wtxn)?;
+
+        let inner = FacetsUpdateIncrementalInner {
+            db: index.facet_id_string_docids.remap_key_type(),
+            group_size: FACET_GROUP_SIZE,
+            min_level_size: FACET_MIN_LEVEL_SIZE,
+            max_group_size: FACET_MAX_GROUP_SIZE,
+            field_configs: HashMap::new(),
+        };
+        let level_size_changed =
+            inner.rename(wtxn, field_id, old_key.as_bytes(), new_key.as_bytes())?;
+        // In lazy mode the levels are only built on explicit rebuilds.
+        if level_size_changed && faceting_mode == FacetingMode::Eager {
+            inner.add_or_delete_level(wtxn, field_id)?;
+        }
+
+        for docid in &docids {
+            index.field_id_docid_facet_strings.delete(wtxn, &(field_id, docid, old_key))?;
+            index.field_id_docid_facet_strings.put(wtxn, &(field_id, docid, new_key), new)?;
+        }
+
+        // Update the normalized facet strings used by the facet search.
+        let FacetSearchNormalization { locales, lossy, .. } =
+            FacetSearchNormalization::of_field(index, wtxn, field_id)?;
+        let locales = locales.as_deref();
+
+        let database = index.facet_id_normalized_string_strings;
+        let old_search_key = normalize_facet_string_for_search(old_key, locales, lossy);
+        let Some(mut old_facets) = database.get(wtxn, &(field_id, old_search_key.as_str()))? else {
+            // The field is not facet searchable.
+            return Ok(());
+        };
+        old_facets.remove(old_key);
+        if old_facets.is_empty() {
+            database.delete(wtxn, &(field_id, old_search_key.as_str()))?;
+        } else {
+            database.put(wtxn, &(field_id, old_search_key.as_str()), &old_facets)?;
+        }
+
+        let new_search_key = normalize_facet_string_for_search(new_key, locales, lossy);
+        let mut new_facets =
+            database.get(wtxn, &(field_id, new_search_key.as_str()))?.unwrap_or_default();
+        new_facets.insert(new_key.to_string());
+        database.put(wtxn, &(field_id, new_search_key.as_str()), &new_facets)?;
+
+        // In lazy mode the FSTs are built at search time.
+        if faceting_mode == FacetingMode::Eager {
+            let normalized_values = BTreeSet::from([old_search_key, new_search_key]);
+            update_facet_string_fst(index, wtxn, field_id, &normalized_values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How the facet strings of a field are normalized for the facet search.
+pub(crate) struct FacetSearchNormalization {
+    /// Whether the field is facet searchable according to its filterable attributes rule.
+    pub searchable: bool,
+    pub locales: Option<Vec<Language>>,
+    pub lossy: bool,
+}
+
+impl FacetSearchNormalization {
+    pub fn of_field(index: &Index, rtxn: &heed::RoTxn<'_>, field_id: FieldId) -> Result<Self> {
+        let fields_ids_map = index.fields_ids_map(rtxn)?;
+        let Some(field_name) = fields_ids_map.name(field_id) else {
+            return Ok(FacetSearchNormalization { searchable: false, locales: None, lossy: true });
+        };
+
+        let localized_attributes_rules = index.localized_attributes_rules(rtxn)?;
+        let locales = localized_attributes_rules.iter().flatten().find_map(|rule| {
+            (rule.match_str(field_name) == PatternMatch::Match).then(|| rule.locales.clone())
+        });
+        let filterable_attributes_rules = index.filterable_attributes_rules(rtxn)?;
+        let features = matching_features(field_name, &filterable_attributes_rules);
+        Ok(FacetSearchNormalization {
+            searchable: features.is_some_and(|(_, features)| features.is_facet_searchable()),
+            locales,
+            lossy: features.is_none_or(|(_, features)| features.is_lossy_normalization()),
+        })
+    }
+}
+
+/// Returns an error if one of the added string facet values may have been truncated,
+/// see [`FacetsUpdate::strict_length`], otherwise returns the same delta data.
+fn check_facet_values_length(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+    delta_data: Merger<BufReader<File>, MergeDeladdCboRoaringBitmaps>,
+) -> Result<Merger<BufReader<File>, MergeDeladdCboRoaringBitmaps>> {
+    // The merger can only be read once, its content is copied while being checked.
+    let mut writer = create_writer(CompressionType::None, None, tempfile::tempfile()?);
+    let mut iter = delta_data.into_stream_merger_iter()?;
+    while let Some((key, value)) = iter.next()? {
+        let FacetGroupKey { field_id, left_bound, .. } =
+            FacetGroupKeyCodec::<BytesRefCodec>::bytes_decode(key)
+                .map_err(heed::Error::Encoding)?;
+        let is_added = KvReaderDelAdd::from_slice(value).get(DelAdd::Addition).is_some();
+        if is_added && left_bound.len() + 3 >= MAX_FACET_VALUE_LENGTH {
+            let fields_ids_map = index.fields_ids_map(rtxn)?;
+            let field = fields_ids_map.name(field_id).unwrap_or_default().to_string();
+            let value = String::from_utf8_lossy(left_bound).into_owned();
+            return Err(UserError::FacetValueTooLong { field, value }.into());
+        }
+        writer.insert(key, value)?;
+    }
+
+    let mut builder = MergerBuilder::new(MergeDeladdCboRoaringBitmaps);
+    builder.push(writer_into_reader(writer)?.into_cursor()?);
+    Ok(builder.build())
+}
+
+/// The entries of a facets delta split by the method used to update the levels of their field.
+struct PartitionedFacetsDelta {
+    bulk: Option<Merger<BufReader<File>, MergeDeladdCboRoaringBitmaps>>,
+    incremental: Option<Merger<BufReader<File>, MergeDeladdCboRoaringBitmaps>>,
+    /// The method and the number of entries of each field of the delta.
+    decisions: BTreeMap<FieldId, FacetUpdateDecision>,
+}
+
+/// Splits the delta between the bulk and incremental methods, depending on the field of the entries.
+fn partition_facets_delta(
+    delta_data: Merger<BufReader<File>, MergeDeladdCboRoaringBitmaps>,
+    method: impl Fn(FieldId) -> FacetUpdateMethod,
+) -> Result<PartitionedFacetsDelta> {
+    // The merger can only be read once, its entries are copied in the writer of their method.
+    let mut bulk = create_writer(CompressionType::None, None, tempfile::tempfile()?);
+    let mut incremental = create_writer(CompressionType::None, None, tempfile::tempfile()?);
+    let mut decisions = BTreeMap::new();
+    let mut iter = delta_data.into_stream_merger_iter()?;
+    while let Some((key, value)) = iter.next()? {
+        let Some((field_id, _)) = try_split_array_at(key) else { continue };
+        let field_id = FieldId::from_be_bytes(field_id);
+        let decision = decisions
+            .entry(field_id)
+            .or_insert_with(|| FacetUpdateDecision { method: method(field_id), delta_size: 0 });
+        decision.delta_size += 1;
+        match decision.method {
+            FacetUpdateMethod::Bulk => bulk.insert(key, value)?,
+            FacetUpdateMethod::Incremental => incremental.insert(key, value)?,
+        }
+    }
+
+    let has_method =
+        |method: FacetUpdateMethod| decisions.values().any(|decision| decision.method == method);
+    let into_merger = |writer| -> Result<_> {
+        let mut builder = MergerBuilder::new(MergeDeladdCboRoaringBitmaps);
+        builder.push(writer_into_reader(writer)?.into_cursor()?);
+        Ok(builder.build())
+    };
+    Ok(PartitionedFacetsDelta {
+        bulk: if has_method(FacetUpdateMethod::Bulk) { Some(into_merger(bulk)?) } else { None },
+        incremental: if has_method(FacetUpdateMethod::Incremental) {
+            Some(into_merger(incremental)?)
+        } else {
+            None
+        },
+        decisions,
+    })
 }
 
 fn index_facet_search(
     wtxn: &mut heed::RwTxn<'_>,
     normalized_delta_data: Merger<BufReader<File>, MergeDeladdBtreesetString>,
     index: &Index,
+    faceting_mode: FacetingMode,
+    max_nb_chunks: Option<usize>,
+    max_memory: Option<usize>,
 ) -> Result<()> {
+    // The normalized values of every modified document are in the delta, their counts changed.
+    // They are kept in a sorter until all the normalized facet strings are written.
+    let mut modified_values = create_sorter(
+        grenad::SortAlgorithm::Stable,
+        MergeIgnoreValues,
+        CompressionType::None,
+        None,
+        max_nb_chunks,
+        max_memory,
+        true,
+    );
     let mut iter = normalized_delta_data.into_stream_merger_iter()?;
     while let Some((key_bytes, delta_bytes)) = iter.next()? {
         let deladd_reader = KvReaderDelAdd::from_slice(delta_bytes);
+        modified_values.insert(key_bytes, [])?;
 
         let database_set = index
             .facet_id_normalized_string_strings
@@ -260,42 +898,534 @@ fn index_facet_search(
         }
     }
 
-    // We clear the FST of normalized-for-search to compute everything from scratch.
+    let update_fst = |wtxn: &mut heed::RwTxn<'_>,
+                      field_id: FieldId,
+                      normalized_values: &BTreeSet<String>|
+     -> Result<()> {
+        if faceting_mode == FacetingMode::Lazy {
+            // The FSTs are built at search time.
+            index.facet_id_string_fst.delete(wtxn, &field_id)?;
+        } else {
+            update_facet_string_fst(index, wtxn, field_id, normalized_values)?;
+        }
+        Ok(())
+    };
+
+    // The values are sorted by field, a FST is updated once all the values of its field are read.
+    let mut field_values: Option<(FieldId, BTreeSet<String>)> = None;
+    let mut iter = modified_values.into_stream_merger_iter()?;
+    while let Some((key_bytes, _)) = iter.next()? {
+        let (field_id, normalized) =
+            BEU16StrCodec::bytes_decode(key_bytes).map_err(heed::Error::Encoding)?;
+        match &mut field_values {
+            Some((current, normalized_values)) if *current == field_id => {
+                normalized_values.insert(normalized.to_string());
+            }
+            _ => {
+                let next = (field_id, BTreeSet::from([normalized.to_string()]));
+                if let Some((field_id, normalized_values)) = field_values.replace(next) {
+                    update_fst(wtxn, field_id, &normalized_values)?;
+                }
+            }
+        }
+    }
+    if let Some((field_id, normalized_values)) = field_values {
+        update_fst(wtxn, field_id, &normalized_values)?;
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the facet levels of every faceted field from the level 0 of the facet databases,
+/// e.g. when their parameters changed.
+pub fn rebuild_facet_levels(index: &Index, wtxn: &mut heed::RwTxn<'_>) -> Result<()> {
+    let fields_ids_map = index.fields_ids_map_with_metadata(wtxn)?;
+    let filterable_attributes_rules = index.filterable_attributes_rules(wtxn)?;
+    let field_ids: Vec<_> = fields_ids_map
+        .iter_id_metadata()
+        .filter(|(_, metadata)| metadata.require_facet_level_database(&filterable_attributes_rules))
+        .map(|(id, _)| id)
+        .collect();
+
+    for facet_type in [FacetType::String, FacetType::Number] {
+        FacetsUpdateBulk::new_not_updating_level_0(index, field_ids.clone(), facet_type)
+            .execute(wtxn)?;
+    }
+    index.delete_facet_incremental_updates(wtxn)?;
+
+    Ok(())
+}
+
+/// Builds the facet levels and the facet search FSTs of every faceted field from
+/// the level 0 of the facet databases.
+///
+/// In lazy faceting mode, these structures are not maintained at indexing time,
+/// this function must be called to persist them, e.g. when switching back to eager mode.
+pub fn build_deferred_facet_structures(index: &Index, wtxn: &mut heed::RwTxn<'_>) -> Result<()> {
+    rebuild_facet_levels(index, wtxn)?;
+
+    rebuild_facet_search_fsts(index, wtxn)
+}
+
+/// Rebuilds the facet search FSTs of every field from the normalized facet strings
+/// and the level 0 of `facet_id_string_docids`.
+///
+/// The FSTs are only removed when the facet search is disabled.
+pub fn rebuild_facet_search_fsts(index: &Index, wtxn: &mut heed::RwTxn<'_>) -> Result<()> {
+    index.facet_id_string_fst.clear(wtxn)?;
+    if !index.facet_search(wtxn)? {
+        return Ok(());
+    }
+
+    let field_ids: Vec<_> = index.fields_ids_map(wtxn)?.ids().collect();
+    for field_id in field_ids {
+        write_facet_string_fst(index, wtxn, field_id)?;
+    }
+
+    Ok(())
+}
+
+/// Builds the facet search FST of a field and writes it, or removes it
+/// when the field doesn't have any normalized facet string.
+///
+/// Returns the size in bytes of the written FST, `0` if it was removed.
+pub(crate) fn write_facet_string_fst(
+    index: &Index,
+    wtxn: &mut heed::RwTxn<'_>,
+    field_id: FieldId,
+) -> Result<usize> {
+    match build_facet_string_fst(index, wtxn, field_id)? {
+        Some(fst) => {
+            index.facet_id_string_fst.put(wtxn, &field_id, &fst)?;
+            Ok(fst.as_fst().as_bytes().len())
+        }
+        None => {
+            index.facet_id_string_fst.delete(wtxn, &field_id)?;
+            Ok(0)
+        }
+    }
+}
+
+/// Rebuilds the facet search FSTs written before they stored the document count of each
+/// facet value, then marks the index so that it is only done once.
+///
+/// These FSTs are sets, read as maps whose outputs are all `0` while a stored count is never `0`,
+/// and would otherwise keep their unknown counts as long as their values are merged.
+pub(crate) fn upgrade_facet_search_fsts_without_counts(
+    index: &Index,
+    wtxn: &mut heed::RwTxn<'_>,
+) -> Result<()> {
+    if index.facet_search_fsts_have_counts(wtxn)? {
+        return Ok(());
+    }
+
+    // In lazy faceting mode the FSTs are built at search time.
+    if index.faceting_mode(wtxn)? == FacetingMode::Eager {
+        let mut field_ids = Vec::new();
+        for result in index.facet_id_string_fst.iter(wtxn)? {
+            let (field_id, fst) = result?;
+            if fst.stream().next().is_some_and(|(_, count)| count == 0) {
+                field_ids.push(field_id);
+            }
+        }
+        for field_id in field_ids {
+            write_facet_string_fst(index, wtxn, field_id)?;
+        }
+    }
+
+    index.put_facet_search_fsts_have_counts(wtxn)?;
+    Ok(())
+}
+
+/// Updates the facet search FST of a field for the given normalized values only: their document
+/// counts are recomputed, the new ones are added and the ones without original values anymore are
+/// removed. The other values are copied from the `previous` FST with their counts.
+///
+/// Unlike [`write_facet_string_fst`], only the original values of the given normalized values are
+/// read. Returns the size in bytes of the written FST, `0` if it was removed because it was empty.
+pub(crate) fn merge_facet_string_fst<D: AsRef<[u8]>>(
+    index: &Index,
+    wtxn: &mut heed::RwTxn<'_>,
+    field_id: FieldId,
+    previous: Option<&fst::Map<D>>,
+    normalized_values: &BTreeSet<String>,
+) -> Result<usize> {
+    // The counts of a FST written without them can't be merged, the FSTs are rebuilt instead.
+    if !index.facet_search_fsts_have_counts(wtxn)? {
+        upgrade_facet_search_fsts_without_counts(index, wtxn)?;
+        return write_facet_string_fst(index, wtxn, field_id);
+    }
+
+    let mut updated = Vec::with_capacity(normalized_values.len());
+    for normalized in normalized_values {
+        let count = match index
+            .facet_id_normalized_string_strings
+            .get(wtxn, &(field_id, normalized.as_str()))?
+        {
+            Some(original_facets) => {
+                Some(original_facets_count(index, wtxn, field_id, &original_facets)?)
+            }
+            None => None,
+        };
+        updated.push((normalized.as_bytes(), count));
+    }
+
+    let mut builder = fst::MapBuilder::new(BufWriter::new(tempfile::tempfile()?))?;
+    let mut entries = 0;
+    let mut updated = updated.into_iter().peekable();
+    if let Some(previous) = previous {
+        let mut stream = previous.stream();
+        while let Some((normalized, count)) = stream.next() {
+            // the updated values sorted before this one are new values
+            while let Some((new_normalized, new_count)) =
+                updated.next_if(|(value, _)| *value < normalized)
+            {
+                if let Some(new_count) = new_count {
+                    builder.insert(new_normalized, new_count)?;
+                    entries += 1;
+                }
+            }
+            let count = match updated.next_if(|(value, _)| *value == normalized) {
+                Some((_, new_count)) => new_count,
+                None => Some(count),
+            };
+            if let Some(count) = count {
+                builder.insert(normalized, count)?;
+                entries += 1;
+            }
+        }
+    }
+    for (normalized, count) in updated {
+        if let Some(count) = count {
+            builder.insert(normalized, count)?;
+            entries += 1;
+        }
+    }
+
+    if entries == 0 {
+        index.facet_id_string_fst.delete(wtxn, &field_id)?;
+        return Ok(0);
+    }
+    let file = builder
+        .into_inner()?
+        .into_inner()
+        .map_err(|_| InternalError::IndexingMergingKeys { process: "building-fst" })?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    index.facet_id_string_fst.remap_data_type::<Bytes>().put(wtxn, &field_id, &mmap)?;
+
+    Ok(mmap.len())
+}
+
+/// Same as [`merge_facet_string_fst`] with the FST currently stored for the field.
+fn update_facet_string_fst(
+    index: &Index,
+    wtxn: &mut heed::RwTxn<'_>,
+    field_id: FieldId,
+    normalized_values: &BTreeSet<String>,
+) -> Result<usize> {
+    // The FST is read from the transaction it is then written in, it must be copied first.
+    let previous = match index.facet_id_string_fst.get(wtxn, &field_id)? {
+        Some(fst) => Some(fst::Map::new(fst.as_fst().as_bytes().to_vec())?),
+        None => None,
+    };
+    merge_facet_string_fst(index, wtxn, field_id, previous.as_ref(), normalized_values)
+}
+
+/// Removes the facet levels and the facet search FSTs of every field, only keeping
+/// the level 0 of the facet databases, as maintained in lazy faceting mode.
+pub fn clear_deferred_facet_structures(index: &Index, wtxn: &mut heed::RwTxn<'_>) -> Result<()> {
+    let field_ids: Vec<_> = index.fields_ids_map(wtxn)?.ids().collect();
+    clear_facet_levels(wtxn, &index.facet_id_string_docids.remap_types(), &field_ids)?;
+    clear_facet_levels(wtxn, &index.facet_id_f64_docids.remap_types(), &field_ids)?;
     index.facet_id_string_fst.clear(wtxn)?;
-    // We compute one FST by string facet
-    let mut text_fsts = vec![];
-    let mut current_fst: Option<(u16, fst::SetBuilder<Vec<u8>>)> = None;
-    let database = index.facet_id_normalized_string_strings.remap_data_type::<DecodeIgnore>();
-    for result in database.iter(wtxn)? {
-        let ((field_id, normalized_facet), _) = result?;
-        current_fst = match current_fst.take() {
-            Some((fid, fst_builder)) if fid != field_id => {
-                let fst = fst_builder.into_set();
-                text_fsts.push((fid, fst));
-                Some((field_id, fst::SetBuilder::memory()))
-            }
-            Some((field_id, fst_builder)) => Some((field_id, fst_builder)),
-            None => Some((field_id, fst::SetBuilder::memory())),
+    Ok(())
+}
+
+/// Merges the facet databases of `other` into the ones of `index`.
+///
+/// The fields of `other` are matched by name, the missing ones are added to the fields
+/// of `index`. The document ids of `other` are translated with `docids_mapping`, the ones
+/// that aren't part of it are kept as is. The facet levels and the facet search FSTs of the
+/// merged fields are then rebuilt according to the settings and faceting mode of `index`.
+///
+/// Only the facet databases are merged, the documents themselves must be imported separately.
+/// `other` must be a different index than `index`.
+pub fn merge_facet_databases(
+    index: &Index,
+    wtxn: &mut heed::RwTxn<'_>,
+    other: &Index,
+    other_rtxn: &heed::RoTxn<'_>,
+    docids_mapping: &HashMap<DocumentId, DocumentId>,
+) -> Result<()> {
+    let mut fields_ids_map = index.fields_ids_map(wtxn)?;
+    let other_fields_ids_map = other.fields_ids_map(other_rtxn)?;
+    let mut merged_fields = Vec::new();
+    for (other_field_id, name) in other_fields_ids_map.iter() {
+        let field_id = fields_ids_map.insert(name).ok_or(UserError::AttributeLimitReached)?;
+        merged_fields.push((field_id, other_field_id));
+    }
+    index.put_fields_ids_map(wtxn, &fields_ids_map)?;
+    // The level 0 entries must be given sorted by key to the bulk update.
+    merged_fields.sort_unstable();
+    let field_ids_of_other: HashMap<FieldId, FieldId> = merged_fields
+        .iter()
+        .map(|&(field_id, other_field_id)| (other_field_id, field_id))
+        .collect();
+
+    let faceting_mode = index.faceting_mode(wtxn)?;
+    let max_levels = index.max_facet_levels(wtxn)?;
+    let field_configs = expected_cardinality_field_configs(index, wtxn)?;
+    let fields_ids_map = index.fields_ids_map_with_metadata(wtxn)?;
+    let filterable_attributes_rules = index.filterable_attributes_rules(wtxn)?;
+    let level_field_ids: Vec<_> = fields_ids_map
+        .iter_id_metadata()
+        .filter(|(_, metadata)| metadata.require_facet_level_database(&filterable_attributes_rules))
+        .map(|(id, _)| id)
+        .collect();
+
+    for facet_type in [FacetType::String, FacetType::Number] {
+        let (db, other_db) = match facet_type {
+            FacetType::String => (
+                index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                other.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+            ),
+            FacetType::Number => (
+                index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                other.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+            ),
+        };
+
+        let entries = merged_fields.iter().flat_map(move |&(field_id, other_field_id)| {
+            let prefix = FacetGroupKey { field_id: other_field_id, level: 0, left_bound: &[][..] };
+            let entries: Box<dyn Iterator<Item = Result<FacetDeltaItem>> + '_> = match other_db
+                .prefix_iter(other_rtxn, &prefix)
+            {
+                Ok(iter) => Box::new(iter.map(move |result| {
+                    let (key, value) = result?;
+                    let key =
+                        FacetGroupKey { field_id, level: 0, left_bound: key.left_bound.to_vec() };
+                    let add = remap_docids(&value.bitmap, docids_mapping);
+                    Ok((key, DelAddRoaringBitmap { del: None, add: Some(add) }))
+                })),
+                Err(error) => Box::new(std::iter::once(Err(error.into()))),
+            };
+            entries
+        });
+        let delta_data: FacetsDelta<'_, BufReader<File>> = FacetsDelta::Stream(Box::new(entries));
+
+        let bulk_update = FacetsUpdateBulkInner {
+            db,
+            delta_data: Some(delta_data),
+            group_size: FACET_GROUP_SIZE,
+            min_level_size: FACET_MIN_LEVEL_SIZE,
+            field_configs: field_configs.clone(),
+            max_levels,
+            progress: None,
+            must_stop_processing: None,
         };
+        if faceting_mode == FacetingMode::Lazy {
+            bulk_update.update_level0_only(wtxn, &level_field_ids)?;
+        } else {
+            bulk_update.update(wtxn, &level_field_ids)?;
+        }
+    }
 
-        if let Some((_, fst_builder)) = current_fst.as_mut() {
-            fst_builder.insert(normalized_facet)?;
+    for (database, other_database) in [
+        (index.facet_id_exists_docids, other.facet_id_exists_docids),
+        (index.facet_id_is_null_docids, other.facet_id_is_null_docids),
+        (index.facet_id_is_empty_docids, other.facet_id_is_empty_docids),
+    ] {
+        for result in other_database.iter(other_rtxn)? {
+            let (other_field_id, docids) = result?;
+            let field_id = field_ids_of_other[&other_field_id];
+            let mut merged = database.get(wtxn, &field_id)?.unwrap_or_default();
+            merged |= remap_docids(&docids, docids_mapping);
+            database.put(wtxn, &field_id, &merged)?;
         }
     }
 
-    if let Some((field_id, fst_builder)) = current_fst {
-        let fst = fst_builder.into_set();
-        text_fsts.push((field_id, fst));
+    for result in other.field_id_docid_facet_f64s.iter(other_rtxn)? {
+        let ((other_field_id, docid, value), ()) = result?;
+        let field_id = field_ids_of_other[&other_field_id];
+        let docid = docids_mapping.get(&docid).copied().unwrap_or(docid);
+        index.field_id_docid_facet_f64s.put(wtxn, &(field_id, docid, value), &())?;
+    }
+
+    for result in other.field_id_docid_facet_strings.iter(other_rtxn)? {
+        let ((other_field_id, docid, normalized), original) = result?;
+        let field_id = field_ids_of_other[&other_field_id];
+        let docid = docids_mapping.get(&docid).copied().unwrap_or(docid);
+        index.field_id_docid_facet_strings.put(wtxn, &(field_id, docid, normalized), original)?;
+    }
+
+    let mut string_field_ids = BTreeSet::new();
+    for result in other.facet_id_normalized_string_strings.iter(other_rtxn)? {
+        let ((other_field_id, normalized), values) = result?;
+        let field_id = field_ids_of_other[&other_field_id];
+        let database = index.facet_id_normalized_string_strings;
+        let mut merged = database.get(wtxn, &(field_id, normalized))?.unwrap_or_default();
+        merged.extend(values);
+        database.put(wtxn, &(field_id, normalized), &merged)?;
+        string_field_ids.insert(field_id);
     }
 
-    // We write those FSTs in LMDB now
-    for (field_id, fst) in text_fsts {
-        index.facet_id_string_fst.put(wtxn, &field_id, &fst)?;
+    // In lazy faceting mode the FSTs are built at search time.
+    let rebuild_fsts = index.facet_search(wtxn)? && faceting_mode == FacetingMode::Eager;
+    for field_id in string_field_ids {
+        if rebuild_fsts {
+            write_facet_string_fst(index, wtxn, field_id)?;
+        } else {
+            index.facet_id_string_fst.delete(wtxn, &field_id)?;
+        }
     }
 
+    index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
+
     Ok(())
 }
 
+fn remap_docids(
+    docids: &RoaringBitmap,
+    docids_mapping: &HashMap<DocumentId, DocumentId>,
+) -> RoaringBitmap {
+    docids.iter().map(|docid| docids_mapping.get(&docid).copied().unwrap_or(docid)).collect()
+}
+
+/// A facet value present in only one of the facet search structures of a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FacetSearchMismatch {
+    /// A level 0 value of `facet_id_string_docids` whose normalized form isn't in the FST.
+    MissingFromFst { field_id: FieldId, value: String, normalized: String },
+    /// A normalized string of the FST that doesn't correspond to any level 0 value.
+    MissingFromDocids { field_id: FieldId, normalized: String },
+}
+
+impl FacetSearchMismatch {
+    pub fn field_id(&self) -> FieldId {
+        match self {
+            FacetSearchMismatch::MissingFromFst { field_id, .. }
+            | FacetSearchMismatch::MissingFromDocids { field_id, .. } => *field_id,
+        }
+    }
+}
+
+/// Verifies that the facet search FST of every facet searchable field contains exactly
+/// the normalized forms of the level 0 values of `facet_id_string_docids`.
+///
+/// Nothing is checked when the facet search is disabled or in lazy faceting mode,
+/// as the FSTs are not persisted then.
+pub fn check_facet_search_fsts(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+) -> Result<Vec<FacetSearchMismatch>> {
+    let mut mismatches = Vec::new();
+    if !index.facet_search(rtxn)? || index.faceting_mode(rtxn)? == FacetingMode::Lazy {
+        return Ok(mismatches);
+    }
+
+    for (field_id, expected) in expected_normalized_facet_strings(index, rtxn)? {
+        let mut in_fst = BTreeSet::new();
+        if let Some(fst) = index.facet_id_string_fst.get(rtxn, &field_id)? {
+            let mut stream = fst.stream();
+            while let Some((normalized, _count)) = stream.next() {
+                in_fst.insert(String::from_utf8_lossy(normalized).into_owned());
+            }
+        }
+
+        for (normalized, values) in &expected {
+            if !in_fst.contains(normalized) {
+                mismatches.extend(values.iter().map(|value| FacetSearchMismatch::MissingFromFst {
+                    field_id,
+                    value: value.clone(),
+                    normalized: normalized.clone(),
+                }));
+            }
+        }
+        for normalized in in_fst {
+            if !expected.contains_key(&normalized) {
+                mismatches.push(FacetSearchMismatch::MissingFromDocids { field_id, normalized });
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Checks the facet search FSTs with [`check_facet_search_fsts`] and rebuilds, from the level 0
+/// of `facet_id_string_docids`, the facet search structures of the fields having mismatches.
+///
+/// Returns the mismatches that were found before the repair.
+pub fn repair_facet_search_fsts(
+    index: &Index,
+    wtxn: &mut heed::RwTxn<'_>,
+) -> Result<Vec<FacetSearchMismatch>> {
+    let mismatches = check_facet_search_fsts(index, wtxn)?;
+    let field_ids: BTreeSet<_> = mismatches.iter().map(FacetSearchMismatch::field_id).collect();
+    if field_ids.is_empty() {
+        return Ok(mismatches);
+    }
+
+    let database = index.facet_id_normalized_string_strings;
+    for (field_id, expected) in expected_normalized_facet_strings(index, wtxn)? {
+        if !field_ids.contains(&field_id) {
+            continue;
+        }
+
+        // The normalized strings may have drifted too, they are rewritten before rebuilding the FST.
+        let prefix = (field_id, "");
+        let mut iter = database.remap_data_type::<DecodeIgnore>().prefix_iter_mut(wtxn, &prefix)?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the LMDB database.
+            unsafe { iter.del_current()? };
+        }
+        drop(iter);
+        for (normalized, values) in &expected {
+            database.put(wtxn, &(field_id, normalized.as_str()), values)?;
+        }
+
+        write_facet_string_fst(index, wtxn, field_id)?;
+    }
+
+    Ok(mismatches)
+}
+
+/// Returns, for every facet searchable field, the level 0 values of `facet_id_string_docids`
+/// grouped by their normalized form, as stored in `facet_id_normalized_string_strings`.
+fn expected_normalized_facet_strings(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+) -> Result<Vec<(FieldId, BTreeMap<String, BTreeSet<String>>)>> {
+    let fields_ids_map = index.fields_ids_map_with_metadata(rtxn)?;
+    let filterable_attributes_rules = index.filterable_attributes_rules(rtxn)?;
+    let localized_attributes_rules = index.localized_attributes_rules(rtxn)?.unwrap_or_default();
+
+    let mut fields = Vec::new();
+    for (field_id, metadata) in fields_ids_map.iter_id_metadata() {
+        if !metadata
+            .filterable_attributes_features(&filterable_attributes_rules)
+            .is_facet_searchable()
+        {
+            continue;
+        }
+
+        let locales = metadata.locales(&localized_attributes_rules);
+        let lossy = metadata
+            .filterable_attributes_features(&filterable_attributes_rules)
+            .is_lossy_normalization();
+        let mut expected: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: "" };
+        let database = index.facet_id_string_docids.remap_data_type::<DecodeIgnore>();
+        for result in database.prefix_iter(rtxn, &prefix)? {
+            let (FacetGroupKey { left_bound, .. }, _) = result?;
+            let normalized = normalize_facet_string_for_search(left_bound, locales, lossy);
+            expected.entry(normalized).or_default().insert(left_bound.to_string());
+        }
+        fields.push((field_id, expected));
+    }
+
+    Ok(fields)
+}
+
 /// Clear all the levels greater than 0 for given field ids.
 pub fn clear_facet_levels<'a, I>(
     wtxn: &mut heed::RwTxn<'_>,
@@ -345,7 +1475,8 @@ where
 
 #[cfg(test)]
 pub(crate) mod test_helpers {
-    use std::cell::Cell;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
     use std::fmt::Display;
     use std::iter::FromIterator;
     use std::marker::PhantomData;
@@ -356,7 +1487,8 @@ pub(crate) mod test_helpers {
     use heed::{BytesDecode, BytesEncode, Env, RoTxn, RwTxn, WithoutTls};
     use roaring::RoaringBitmap;
 
-    use super::bulk::FacetsUpdateBulkInner;
+    use super::bulk::{FacetsDelta, FacetsUpdateBulkInner};
+    use super::FacetConfig;
     use crate::heed_codec::facet::{
         FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec,
     };
@@ -368,23 +1500,6 @@ pub(crate) mod test_helpers {
     use crate::update::FacetsUpdateIncrementalInner;
     use crate::CboRoaringBitmapCodec;
 
-    /// Utility function to generate a string whose position in a lexicographically
-    /// ordered list is `i`.
-    pub fn ordered_string(mut i: usize) -> String {
-        // The first string is empty
-        if i == 0 {
-            return String::new();
-        }
-        // The others are 5 char long, each between 'a' and 'z'
-        let mut s = String::new();
-        for _ in 0..5 {
-            let (digit, next) = (i % 26, i / 26);
-            s.insert(0, char::from_u32('a' as u32 + digit as u32).unwrap());
-            i = next;
-        }
-        s
-    }
-
     /// A dummy index that only contains the facet database, used for testing
     pub struct FacetIndex<BoundCodec>
     where
@@ -396,6 +1511,7 @@ pub(crate) mod test_helpers {
         pub group_size: Cell<u8>,
         pub min_level_size: Cell<u8>,
         pub max_group_size: Cell<u8>,
+        pub field_configs: RefCell<HashMap<u16, FacetConfig>>,
         _tempdir: Rc<tempfile::TempDir>,
         _phantom: PhantomData<BoundCodec>,
     }
@@ -427,6 +1543,7 @@ pub(crate) mod test_helpers {
                 group_size: Cell::new(group_size),
                 max_group_size: Cell::new(max_group_size),
                 min_level_size: Cell::new(min_level_size),
+                field_configs: RefCell::new(HashMap::new()),
                 _tempdir: Rc::new(tempdir),
                 env,
                 _phantom: PhantomData,
@@ -445,6 +1562,7 @@ pub(crate) mod test_helpers {
                 group_size: self.group_size.get(),
                 min_level_size: self.min_level_size.get(),
                 max_group_size: self.max_group_size.get(),
+                field_configs: self.field_configs.borrow().clone(),
             };
             let key_bytes = BoundCodec::bytes_encode(key).unwrap();
             update.modify(wtxn, field_id, &key_bytes, Some(docids), None).unwrap();
@@ -472,12 +1590,44 @@ pub(crate) mod test_helpers {
                 group_size: self.group_size.get(),
                 min_level_size: self.min_level_size.get(),
                 max_group_size: self.max_group_size.get(),
+                field_configs: self.field_configs.borrow().clone(),
             };
             let key_bytes = BoundCodec::bytes_encode(key).unwrap();
             update.modify(wtxn, field_id, &key_bytes, None, Some(docids)).unwrap();
             update.add_or_delete_level(wtxn, field_id).unwrap();
         }
 
+        pub fn rename<'a>(
+            &self,
+            wtxn: &'a mut RwTxn<'_>,
+            field_id: u16,
+            old_key: &'a <BoundCodec as BytesEncode<'a>>::EItem,
+            new_key: &'a <BoundCodec as BytesEncode<'a>>::EItem,
+        ) {
+            let update = FacetsUpdateIncrementalInner {
+                db: self.content,
+                group_size: self.group_size.get(),
+                min_level_size: self.min_level_size.get(),
+                max_group_size: self.max_group_size.get(),
+                field_configs: self.field_configs.borrow().clone(),
+            };
+            let old_key_bytes = BoundCodec::bytes_encode(old_key).unwrap();
+            let new_key_bytes = BoundCodec::bytes_encode(new_key).unwrap();
+            update.rename(wtxn, field_id, &old_key_bytes, &new_key_bytes).unwrap();
+            update.add_or_delete_level(wtxn, field_id).unwrap();
+        }
+
+        pub fn level_0_docids<'a>(
+            &self,
+            txn: &RoTxn<'_>,
+            field_id: u16,
+            key: &'a <BoundCodec as BytesEncode<'a>>::EItem,
+        ) -> Option<RoaringBitmap> {
+            let key_bytes = BoundCodec::bytes_encode(key).unwrap();
+            let key = FacetGroupKey { field_id, level: 0, left_bound: key_bytes.as_ref() };
+            self.content.get(txn, &key).unwrap().map(|value| value.bitmap)
+        }
+
         pub fn bulk_insert<'a, 'b>(
             &self,
             wtxn: &'a mut RwTxn<'_>,
@@ -508,67 +1658,85 @@ pub(crate) mod test_helpers {
 
             let update = FacetsUpdateBulkInner {
                 db: self.content,
-                delta_data: Some(merger),
+                delta_data: Some(FacetsDelta::Merger(merger)),
                 group_size: self.group_size.get(),
                 min_level_size: self.min_level_size.get(),
+                field_configs: self.field_configs.borrow().clone(),
+                max_levels: None,
+                progress: None,
+                must_stop_processing: None,
             };
 
             update.update(wtxn, field_ids).unwrap();
         }
 
-        pub fn verify_structure_validity(&self, txn: &RoTxn<'_>, field_id: u16) {
-            let mut field_id_prefix = vec![];
-            field_id_prefix.extend_from_slice(&field_id.to_be_bytes());
-
-            let highest_level = get_highest_level(txn, self.content, field_id).unwrap();
-
-            for level_no in (1..=highest_level).rev() {
-                let mut level_no_prefix = vec![];
-                level_no_prefix.extend_from_slice(&field_id.to_be_bytes());
-                level_no_prefix.push(level_no);
+        fn max_group_size_of(&self, field_id: u16) -> u8 {
+            match self.field_configs.borrow().get(&field_id) {
+                Some(config) => config.max_group_size,
+                None => self.max_group_size.get(),
+            }
+        }
 
-                let iter = self
-                    .content
-                    .remap_types::<Bytes, FacetGroupValueCodec>()
-                    .prefix_iter(txn, &level_no_prefix)
-                    .unwrap();
-                for el in iter {
-                    let (key, value) = el.unwrap();
-                    let key = FacetGroupKeyCodec::<BytesRefCodec>::bytes_decode(key).unwrap();
-
-                    let mut prefix_start_below = vec![];
-                    prefix_start_below.extend_from_slice(&field_id.to_be_bytes());
-                    prefix_start_below.push(level_no - 1);
-                    prefix_start_below.extend_from_slice(key.left_bound);
-
-                    let start_below = {
-                        let mut start_below_iter = self
-                            .content
-                            .remap_types::<Bytes, FacetGroupValueCodec>()
-                            .prefix_iter(txn, &prefix_start_below)
-                            .unwrap();
-                        let (key_bytes, _) = start_below_iter.next().unwrap().unwrap();
-                        FacetGroupKeyCodec::<BytesRefCodec>::bytes_decode(key_bytes).unwrap()
-                    };
-
-                    assert!(value.size > 0);
-
-                    let mut actual_size = 0;
-                    let mut values_below = RoaringBitmap::new();
-                    let iter_below = self
-                        .content
-                        .range(txn, &(start_below..))
-                        .unwrap()
-                        .take(value.size as usize);
-                    for el in iter_below {
-                        let (_, value) = el.unwrap();
-                        actual_size += 1;
-                        values_below |= value.bitmap;
-                    }
-                    assert_eq!(actual_size, value.size, "{key:?} start_below: {start_below:?}");
+        pub fn verify_structure_validity(&self, txn: &RoTxn<'_>, field_id: u16) {
+            verify_structure_validity(txn, self.content, field_id, self.max_group_size_of(field_id))
+        }
+    }
 
-                    assert_eq!(value.bitmap, values_below);
+    /// Checks that the levels of the field are consistent with each other: the nodes of the upper
+    /// levels have between one and `max_group_size` children and contain the union of their docids.
+    pub fn verify_structure_validity(
+        txn: &RoTxn<'_>,
+        content: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
+        field_id: u16,
+        max_group_size: u8,
+    ) {
+        let mut field_id_prefix = vec![];
+        field_id_prefix.extend_from_slice(&field_id.to_be_bytes());
+
+        let highest_level = get_highest_level(txn, content, field_id).unwrap();
+
+        for level_no in (1..=highest_level).rev() {
+            let mut level_no_prefix = vec![];
+            level_no_prefix.extend_from_slice(&field_id.to_be_bytes());
+            level_no_prefix.push(level_no);
+
+            let iter = content
+                .remap_types::<Bytes, FacetGroupValueCodec>()
+                .prefix_iter(txn, &level_no_prefix)
+                .unwrap();
+            for el in iter {
+                let (key, value) = el.unwrap();
+                let key = FacetGroupKeyCodec::<BytesRefCodec>::bytes_decode(key).unwrap();
+
+                let mut prefix_start_below = vec![];
+                prefix_start_below.extend_from_slice(&field_id.to_be_bytes());
+                prefix_start_below.push(level_no - 1);
+                prefix_start_below.extend_from_slice(key.left_bound);
+
+                let start_below = {
+                    let mut start_below_iter = content
+                        .remap_types::<Bytes, FacetGroupValueCodec>()
+                        .prefix_iter(txn, &prefix_start_below)
+                        .unwrap();
+                    let (key_bytes, _) = start_below_iter.next().unwrap().unwrap();
+                    FacetGroupKeyCodec::<BytesRefCodec>::bytes_decode(key_bytes).unwrap()
+                };
+
+                assert!(value.size > 0);
+                assert!(value.size <= max_group_size);
+
+                let mut actual_size = 0;
+                let mut values_below = RoaringBitmap::new();
+                let iter_below =
+                    content.range(txn, &(start_below..)).unwrap().take(value.size as usize);
+                for el in iter_below {
+                    let (_, value) = el.unwrap();
+                    actual_size += 1;
+                    values_below |= value.bitmap;
                 }
+                assert_eq!(actual_size, value.size, "{key:?} start_below: {start_below:?}");
+
+                assert_eq!(value.bitmap, values_below);
             }
         }
     }
@@ -848,3 +2016,747 @@ fn facet_to_string(facet_value: &[u8], facet_type: FacetType) -> String {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use fst::Streamer;
+    use grenad::{CompressionType, MergerBuilder};
+    use heed::BytesEncode;
+    use maplit::btreemap;
+    use roaring::RoaringBitmap;
+
+    use super::{
+        check_facet_search_fsts, facet_tree_ascii, rebuild_facet_search_fsts,
+        repair_facet_search_fsts, sanity_checks, FacetSearchMismatch, FacetUpdateDecision,
+        FacetUpdateMethod, FacetsUpdate,
+    };
+    use crate::documents::mmap_from_objects;
+    use crate::error::InternalError;
+    use crate::facet::FacetType;
+    use crate::filterable_attributes_rules::FilterableAttributesRule;
+    use crate::heed_codec::facet::{
+        FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, OrderedF64Codec,
+    };
+    use crate::heed_codec::BytesRefCodec;
+    use crate::index::tests::TempIndex;
+    use crate::index::{FacetTreeStats, FacetUpdateMethodSetting};
+    use crate::progress::Progress;
+    use crate::update::del_add::{DelAdd, KvWriterDelAdd};
+    use crate::update::index_documents::{create_writer, writer_into_reader};
+    use crate::update::settings::InnerIndexSettings;
+    use crate::update::{
+        FacetsUpdateBulk, IndexerConfig, LevelPolicy, MergeDeladdCboRoaringBitmaps,
+    };
+    use crate::{CboRoaringBitmapCodec, Error, Index, UserError, MAX_FACET_VALUE_LENGTH};
+
+    fn facets_update<'i>(
+        index: &'i TempIndex,
+        field_ids: &[u16],
+        values: std::ops::Range<u32>,
+    ) -> FacetsUpdate<'i> {
+        let mut field_ids = field_ids.to_vec();
+        field_ids.sort_unstable();
+        let mut writer = create_writer(CompressionType::None, None, tempfile::tempfile().unwrap());
+        for &field_id in &field_ids {
+            for value in values.clone() {
+                let left_bound = OrderedF64Codec::bytes_encode(&(value as f64)).unwrap();
+                let key = FacetGroupKey { field_id, level: 0, left_bound: left_bound.as_ref() };
+                let key = FacetGroupKeyCodec::<BytesRefCodec>::bytes_encode(&key).unwrap();
+                let docids = RoaringBitmap::from_iter([value]);
+                let mut value_writer = KvWriterDelAdd::memory();
+                value_writer
+                    .insert(DelAdd::Addition, CboRoaringBitmapCodec::bytes_encode(&docids).unwrap())
+                    .unwrap();
+                writer.insert(&key, value_writer.into_inner().unwrap()).unwrap();
+            }
+        }
+        let reader = writer_into_reader(writer).unwrap();
+        let mut builder = MergerBuilder::new(MergeDeladdCboRoaringBitmaps);
+        builder.push(reader.into_cursor().unwrap());
+
+        let data_size = (values.len() * field_ids.len()) as u64;
+        FacetsUpdate::new(index, FacetType::Number, builder.build(), None, data_size)
+    }
+
+    #[test]
+    fn facet_tree_ascii_diagram() {
+        let index = TempIndex::new();
+        let mut wtxn = index.write_txn().unwrap();
+        let nodes: [(u8, &str, u8, &[u32]); 9] = [
+            (0, "ab", 1, &[0, 1]),
+            (0, "ac", 1, &[2, 3]),
+            (0, "ba", 1, &[1, 4]),
+            (0, "bac", 1, &[0, 4]),
+            (0, "gaf", 1, &[5]),
+            (1, "ab", 2, &[0, 1, 2, 3]),
+            (1, "ba", 2, &[0, 1, 4]),
+            (1, "gaf", 1, &[5]),
+            (2, "ab", 3, &[0, 1, 2, 3, 4, 5]),
+        ];
+        for (level, left_bound, size, docids) in nodes {
+            let key = FacetGroupKey { field_id: 0, level, left_bound };
+            let bitmap = docids.iter().copied().collect();
+            index
+                .facet_id_string_docids
+                .put(&mut wtxn, &key, &FacetGroupValue { size, bitmap })
+                .unwrap();
+        }
+
+        let diagram = facet_tree_ascii(&index, &wtxn, FacetType::String, 0).unwrap();
+        insta::assert_snapshot!(diagram, @r###"
+                ┌───────────────────────────────────────────────┐
+    ┌───────┐   │                   "ab" (3)                    │
+    │Level 2│   │                                               │
+    └───────┘   │              [0, 1, 2, 3, 4, 5]               │
+                ├─────────────────┬─────────────────┬───────────┤
+    ┌───────┐   │    "ab" (2)     │    "ba" (2)     │ "gaf" (1) │
+    │Level 1│   │                 │                 │           │
+    └───────┘   │  [0, 1, 2, 3]   │    [0, 1, 4]    │    [5]    │
+                ├────────┬────────┼────────┬────────┼───────────┤
+    ┌───────┐   │  "ab"  │  "ac"  │  "ba"  │ "bac"  │   "gaf"   │
+    │Level 0│   │        │        │        │        │           │
+    └───────┘   │ [0, 1] │ [2, 3] │ [1, 4] │ [0, 4] │    [5]    │
+                └────────┴────────┴────────┴────────┴───────────┘
+    "###);
+
+        // the number tree of the field is empty
+        assert_eq!(facet_tree_ascii(&index, &wtxn, FacetType::Number, 0).unwrap(), "");
+    }
+
+    #[test]
+    fn strict_length_rejects_truncated_values() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_filterable_fields(vec![FilterableAttributesRule::Field("tag".to_string())])
+            })
+            .unwrap();
+        index.add_documents(documents!([{ "id": 0, "tag": "fr" }])).unwrap();
+        let field_id = index.fields_ids_map(&index.read_txn().unwrap()).unwrap().id("tag").unwrap();
+
+        let long_value = "a".repeat(MAX_FACET_VALUE_LENGTH);
+        let string_facets_update = |values: &[&str]| {
+            let mut writer =
+                create_writer(CompressionType::None, None, tempfile::tempfile().unwrap());
+            let mut values = values.to_vec();
+            values.sort_unstable();
+            for (docid, value) in values.iter().enumerate() {
+                let key = FacetGroupKey { field_id, level: 0, left_bound: value.as_bytes() };
+                let key = FacetGroupKeyCodec::<BytesRefCodec>::bytes_encode(&key).unwrap();
+                let docids = RoaringBitmap::from_iter([docid as u32 + 1]);
+                let mut value_writer = KvWriterDelAdd::memory();
+                value_writer
+                    .insert(DelAdd::Addition, CboRoaringBitmapCodec::bytes_encode(&docids).unwrap())
+                    .unwrap();
+                writer.insert(&key, value_writer.into_inner().unwrap()).unwrap();
+            }
+            let reader = writer_into_reader(writer).unwrap();
+            let mut builder = MergerBuilder::new(MergeDeladdCboRoaringBitmaps);
+            builder.push(reader.into_cursor().unwrap());
+            FacetsUpdate::new(&index, FacetType::String, builder.build(), None, values.len() as u64)
+        };
+
+        // by default the value is indexed as is
+        let mut wtxn = index.write_txn().unwrap();
+        let settings = InnerIndexSettings::from_index(&index, &wtxn, None).unwrap();
+        string_facets_update(&["us", long_value.as_str()]).execute(&mut wtxn, &settings).unwrap();
+        wtxn.abort();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let settings = InnerIndexSettings::from_index(&index, &wtxn, None).unwrap();
+        let mut update = string_facets_update(&["us", long_value.as_str()]);
+        update.strict_length(true);
+        let error = update.execute(&mut wtxn, &settings).unwrap_err();
+        match error {
+            Error::UserError(UserError::FacetValueTooLong { field, value }) => {
+                assert_eq!(field, "tag");
+                assert_eq!(value, long_value);
+            }
+            error => panic!("unexpected error: {error}"),
+        }
+        wtxn.abort();
+
+        // short values are accepted in strict mode
+        let mut wtxn = index.write_txn().unwrap();
+        let settings = InnerIndexSettings::from_index(&index, &wtxn, None).unwrap();
+        let mut update = string_facets_update(&["us", "de"]);
+        update.strict_length(true);
+        update.execute(&mut wtxn, &settings).unwrap();
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn facet_search_fsts_consistency() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_filterable_fields(vec![FilterableAttributesRule::Field("tag".to_string())])
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([{ "id": 0, "tag": "Blue" }, { "id": 1, "tag": "red" }]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let field_id = index.fields_ids_map(&rtxn).unwrap().id("tag").unwrap();
+        assert_eq!(check_facet_search_fsts(&index, &rtxn).unwrap(), vec![]);
+        drop(rtxn);
+
+        // desync the FST and the normalized strings from the facet values
+        let mut wtxn = index.write_txn().unwrap();
+        let fst = fst::Map::from_iter([("blue", 1), ("green", 1)]).unwrap();
+        index.facet_id_string_fst.put(&mut wtxn, &field_id, &fst).unwrap();
+        index.facet_id_normalized_string_strings.delete(&mut wtxn, &(field_id, "red")).unwrap();
+
+        let expected = vec![
+            FacetSearchMismatch::MissingFromFst {
+                field_id,
+                value: "red".to_string(),
+                normalized: "red".to_string(),
+            },
+            FacetSearchMismatch::MissingFromDocids { field_id, normalized: "green".to_string() },
+        ];
+        assert_eq!(check_facet_search_fsts(&index, &wtxn).unwrap(), expected);
+
+        // the repair returns what it fixed and leaves consistent structures
+        assert_eq!(repair_facet_search_fsts(&index, &mut wtxn).unwrap(), expected);
+        assert_eq!(check_facet_search_fsts(&index, &wtxn).unwrap(), vec![]);
+        let values =
+            index.facet_id_normalized_string_strings.get(&wtxn, &(field_id, "red")).unwrap();
+        assert_eq!(values, Some(["red".to_string()].into()));
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn facet_search_fsts_of_the_settings_indexer_under_a_memory_budget() {
+        let mut index = TempIndex::new();
+        index.indexer_config = IndexerConfig {
+            experimental_no_edition_2024_for_settings: true,
+            max_memory: Some(1024 * 1024),
+            max_nb_chunks: Some(2),
+            ..Default::default()
+        };
+        let documents: Vec<_> = (0..500)
+            .map(|id| serde_json::json!({ "id": id, "tag": format!("Tag {}", id % 100) }))
+            .collect();
+        index.add_documents(mmap_from_objects(documents)).unwrap();
+
+        // the settings indexer writes the facet search FSTs with the old facets update
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_filterable_fields(vec![FilterableAttributesRule::Field("tag".to_string())])
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let field_id = index.fields_ids_map(&rtxn).unwrap().id("tag").unwrap();
+        let counts = fst_document_counts(&index, &rtxn, field_id);
+        assert_eq!(counts.len(), 100);
+        assert!(counts.iter().all(|(_, count)| *count == 5));
+        assert_eq!(check_facet_search_fsts(&index, &rtxn).unwrap(), vec![]);
+    }
+
+    /// Returns the values of the facet search FST of the field after checking
+    /// that they are the cardinalities of the level 0 bitmaps of their original values.
+    fn fst_document_counts(
+        index: &Index,
+        rtxn: &heed::RoTxn<'_>,
+        field_id: u16,
+    ) -> Vec<(String, u64)> {
+        let Some(fst) = index.facet_id_string_fst.get(rtxn, &field_id).unwrap() else {
+            return Vec::new();
+        };
+
+        let mut counts = Vec::new();
+        let mut stream = fst.stream();
+        while let Some((normalized, count)) = stream.next() {
+            let normalized = std::str::from_utf8(normalized).unwrap();
+            let originals = index
+                .facet_id_normalized_string_strings
+                .get(rtxn, &(field_id, normalized))
+                .unwrap()
+                .unwrap();
+            let mut docids = RoaringBitmap::new();
+            for original in &originals {
+                let key = FacetGroupKey { field_id, level: 0, left_bound: original.as_str() };
+                docids |= index.facet_id_string_docids.get(rtxn, &key).unwrap().unwrap().bitmap;
+            }
+            assert_eq!(count, docids.len(), "wrong count for {normalized:?}");
+            counts.push((normalized.to_string(), count));
+        }
+        counts
+    }
+
+    #[test]
+    fn facet_search_fst_values_are_the_leaf_cardinalities() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_filterable_fields(vec![FilterableAttributesRule::Field("tag".to_string())])
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "tag": "Blue" },
+                { "id": 1, "tag": "blue" },
+                { "id": 2, "tag": "red" },
+                { "id": 3, "tag": ["red", "green"] },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let field_id = index.fields_ids_map(&rtxn).unwrap().id("tag").unwrap();
+        let expected =
+            vec![("blue".to_string(), 2), ("green".to_string(), 1), ("red".to_string(), 2)];
+        assert_eq!(fst_document_counts(&index, &rtxn, field_id), expected);
+        drop(rtxn);
+
+        // the counts change even though no value is added or removed
+        index
+            .add_documents(documents!([{ "id": 0, "tag": "red" }, { "id": 4, "tag": "red" }]))
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let expected =
+            vec![("blue".to_string(), 1), ("green".to_string(), 1), ("red".to_string(), 4)];
+        assert_eq!(fst_document_counts(&index, &rtxn, field_id), expected);
+        drop(rtxn);
+
+        index.delete_documents(vec!["3".to_string()]);
+        let rtxn = index.read_txn().unwrap();
+        let expected = vec![("blue".to_string(), 1), ("red".to_string(), 3)];
+        assert_eq!(fst_document_counts(&index, &rtxn, field_id), expected);
+        drop(rtxn);
+
+        // the FSTs rebuilt from scratch, e.g. by the upgrade, are identical
+        let mut wtxn = index.write_txn().unwrap();
+        rebuild_facet_search_fsts(&index, &mut wtxn).unwrap();
+        assert_eq!(fst_document_counts(&index, &wtxn, field_id), expected);
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn facet_search_fsts_without_counts_are_rebuilt_by_the_first_update() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field("tag".to_string()),
+                    FilterableAttributesRule::Field("color".to_string()),
+                ])
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "tag": "Blue", "color": "green" },
+                { "id": 1, "tag": "red", "color": "green" },
+            ]))
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.facet_search_fsts_have_counts(&rtxn).unwrap());
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let (tag, color) = (fields_ids_map.id("tag").unwrap(), fields_ids_map.id("color").unwrap());
+        drop(rtxn);
+
+        // the FSTs are replaced by sets like the ones written before the counts were stored
+        let mut wtxn = index.write_txn().unwrap();
+        for (field_id, values) in [(tag, &["blue", "red"][..]), (color, &["green"][..])] {
+            let set = fst::Set::from_iter(values).unwrap();
+            let map = fst::Map::new(set.as_fst().as_bytes().to_vec()).unwrap();
+            index.facet_id_string_fst.put(&mut wtxn, &field_id, &map).unwrap();
+        }
+        index
+            .main
+            .remap_key_type::<heed::types::Str>()
+            .delete(&mut wtxn, crate::index::main_key::FACET_SEARCH_FSTS_HAVE_COUNTS)
+            .unwrap();
+        wtxn.commit().unwrap();
+
+        // the FSTs of all the fields are rebuilt, not only the one of the updated field
+        index.add_documents(documents!([{ "id": 2, "tag": "red" }])).unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.facet_search_fsts_have_counts(&rtxn).unwrap());
+        let expected = vec![("blue".to_string(), 1), ("red".to_string(), 2)];
+        assert_eq!(fst_document_counts(&index, &rtxn, tag), expected);
+        assert_eq!(fst_document_counts(&index, &rtxn, color), vec![("green".to_string(), 2)]);
+    }
+
+    #[test]
+    fn numeric_facet_changes_skip_the_facet_search_databases() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field("tag".to_string()),
+                    FilterableAttributesRule::Field("price".to_string()),
+                ])
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "tag": "Blue", "price": 10 },
+                { "id": 1, "tag": "red", "price": 20 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let field_id = index.fields_ids_map(&rtxn).unwrap().id("tag").unwrap();
+        let fst = index.facet_id_string_fst.get(&rtxn, &field_id).unwrap().unwrap();
+        let fst_bytes = fst.as_fst().as_bytes().to_vec();
+        let normalized_strings = index.facet_id_normalized_string_strings.len(&rtxn).unwrap();
+        drop(rtxn);
+
+        let computed_facet_search = |progress: &Progress| {
+            progress.accumulated_durations().keys().any(|step| step.ends_with("> facet search"))
+        };
+
+        // only the numbers changed
+        let progress = Progress::default();
+        index
+            .add_documents_with_progress(
+                documents!([
+                    { "id": 0, "tag": "Blue", "price": 15 },
+                    { "id": 2, "price": 30 },
+                ]),
+                &progress,
+            )
+            .unwrap();
+        assert!(!computed_facet_search(&progress));
+
+        let rtxn = index.read_txn().unwrap();
+        let fst = index.facet_id_string_fst.get(&rtxn, &field_id).unwrap().unwrap();
+        assert_eq!(fst.as_fst().as_bytes(), fst_bytes.as_slice());
+        assert_eq!(
+            index.facet_id_normalized_string_strings.len(&rtxn).unwrap(),
+            normalized_strings
+        );
+        assert_eq!(check_facet_search_fsts(&index, &rtxn).unwrap(), vec![]);
+        drop(rtxn);
+
+        // a new string is still searchable
+        let progress = Progress::default();
+        index
+            .add_documents_with_progress(documents!([{ "id": 3, "tag": "green" }]), &progress)
+            .unwrap();
+        assert!(computed_facet_search(&progress));
+        let rtxn = index.read_txn().unwrap();
+        let fst = index.facet_id_string_fst.get(&rtxn, &field_id).unwrap().unwrap();
+        assert!(fst.contains_key("green"));
+        assert_eq!(check_facet_search_fsts(&index, &rtxn).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn bulk_facets_update_is_atomic_for_readers() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_filterable_fields(vec![FilterableAttributesRule::Field("n".to_string())])
+            })
+            .unwrap();
+        let documents =
+            (0..10).map(|i| serde_json::json!({ "id": i, "n": i }).as_object().unwrap().clone());
+        index.add_documents(mmap_from_objects(documents)).unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let field_id = index.fields_ids_map(&rtxn).unwrap().id("n").unwrap();
+        let old_stats = index.facet_tree_stats(&rtxn).unwrap();
+        drop(rtxn);
+
+        // an aborted update leaves the facet database untouched
+        let mut wtxn = index.write_txn().unwrap();
+        let settings = InnerIndexSettings::from_index(&index, &wtxn, None).unwrap();
+        facets_update(&index, &[field_id], 10..20).execute_bulk(&mut wtxn, &settings).unwrap();
+        wtxn.abort();
+        assert_eq!(index.facet_tree_stats(&index.read_txn().unwrap()).unwrap(), old_stats);
+
+        let mut wtxn = index.write_txn().unwrap();
+        let settings = InnerIndexSettings::from_index(&index, &wtxn, None).unwrap();
+        facets_update(&index, &[field_id], 10..20).execute_shadowed(&mut wtxn, &settings).unwrap();
+
+        // readers running during the update only see the old facet trees
+        let reader: &Index = &index;
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let rtxn = reader.read_txn().unwrap();
+                assert_eq!(reader.facet_tree_stats(&rtxn).unwrap(), old_stats);
+            });
+        });
+        wtxn.commit().unwrap();
+
+        // the 20 values are grouped in 5 nodes of `FACET_GROUP_SIZE` values at level 1
+        let rtxn = index.read_txn().unwrap();
+        let new_stats = index.facet_tree_stats(&rtxn).unwrap();
+        assert_eq!(new_stats["n"], FacetTreeStats { levels: 2, node_count: 25 });
+    }
+
+    #[test]
+    fn max_facet_levels_caps_the_depth_of_the_trees() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field("n".to_string()),
+                    FilterableAttributesRule::Field("s".to_string()),
+                ])
+            })
+            .unwrap();
+        let documents = |ids: std::ops::Range<u32>| {
+            mmap_from_objects(ids.map(|i| {
+                serde_json::json!({ "id": i, "n": i, "s": format!("value {i:05}") })
+                    .as_object()
+                    .unwrap()
+                    .clone()
+            }))
+        };
+        let levels = |index: &TempIndex, name: &str| {
+            let rtxn = index.read_txn().unwrap();
+            index.facet_tree_stats(&rtxn).unwrap()[name].levels
+        };
+        let filter = |index: &TempIndex, filter: &str| {
+            let rtxn = index.read_txn().unwrap();
+            let filter = crate::Filter::from_str(filter).unwrap().unwrap();
+            filter.evaluate(&rtxn, index).unwrap().len()
+        };
+
+        // 2000 values are grouped in 500, 125, 32 and 8 nodes by default
+        index.add_documents(documents(0..2000)).unwrap();
+        assert_eq!(levels(&index, "n"), 5);
+        assert_eq!(levels(&index, "s"), 5);
+
+        // setting the cap rebuilds the existing trees
+        index.update_settings(|settings| settings.set_max_facet_levels(2)).unwrap();
+        assert!(levels(&index, "n") <= 3);
+        assert!(levels(&index, "s") <= 3);
+        assert_eq!(filter(&index, "n >= 1990"), 10);
+        assert_eq!(filter(&index, "s = \"value 00042\""), 1);
+
+        // and the trees built by the next indexing operations respect it as well
+        index.add_documents(documents(2000..10_000)).unwrap();
+        assert!(levels(&index, "n") <= 3);
+        assert!(levels(&index, "s") <= 3);
+        assert_eq!(filter(&index, "n >= 9990"), 10);
+
+        // resetting it goes back to the default group size
+        index.update_settings(|settings| settings.reset_max_facet_levels()).unwrap();
+        assert!(levels(&index, "n") > 3);
+    }
+
+    #[test]
+    fn facet_levels_are_rebuilt_after_too_many_incremental_updates() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_filterable_fields(vec![FilterableAttributesRule::Field("n".to_string())]);
+                settings.set_facet_rebalancing_threshold(3);
+            })
+            .unwrap();
+        let documents = |ids: std::ops::Range<u32>| {
+            mmap_from_objects(
+                ids.map(|i| serde_json::json!({ "id": i, "n": i }).as_object().unwrap().clone()),
+            )
+        };
+        let incremental_updates = |index: &TempIndex| {
+            let rtxn = index.read_txn().unwrap();
+            let fid = index.fields_ids_map(&rtxn).unwrap().id("n").unwrap();
+            index.facet_incremental_updates(&rtxn, FacetType::Number).unwrap().get(&fid).copied()
+        };
+
+        // the first batch builds the levels with the bulk method
+        index.add_documents(documents(0..2000)).unwrap();
+        assert_eq!(incremental_updates(&index), None);
+
+        // the small batches are applied incrementally until the threshold is reached
+        for (i, id) in (2000..2003).enumerate() {
+            index.add_documents(documents(id..id + 1)).unwrap();
+            assert_eq!(incremental_updates(&index), Some(i as u64 + 1));
+        }
+
+        // the next one, however small, rebuilds the levels with the bulk method
+        index.add_documents(documents(2003..2004)).unwrap();
+        assert_eq!(incremental_updates(&index), None);
+        index.add_documents(documents(2004..2005)).unwrap();
+        assert_eq!(incremental_updates(&index), Some(1));
+
+        // without threshold the updates are still counted but never trigger a rebuild
+        index.update_settings(|settings| settings.reset_facet_rebalancing_threshold()).unwrap();
+        for id in 2005..2010 {
+            index.add_documents(documents(id..id + 1)).unwrap();
+        }
+        assert_eq!(incremental_updates(&index), Some(6));
+
+        let rtxn = index.read_txn().unwrap();
+        let fid = index.fields_ids_map(&rtxn).unwrap().id("n").unwrap();
+        sanity_checks(&index, &rtxn, fid, FacetType::Number, 4, 5, 8).unwrap();
+    }
+
+    #[test]
+    fn leaf_only_bulk_update_only_keeps_the_level_0() {
+        let index = TempIndex::new();
+        let equality_only: FilterableAttributesRule = serde_json::from_value(serde_json::json!({
+            "attributePatterns": ["n"],
+            "features": { "filter": { "equality": true, "comparison": false } },
+        }))
+        .unwrap();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    equality_only,
+                    FilterableAttributesRule::Field("m".to_string()),
+                ])
+            })
+            .unwrap();
+        let documents =
+            mmap_from_objects((0..2000).map(|i| {
+                serde_json::json!({ "id": i, "n": i, "m": i }).as_object().unwrap().clone()
+            }));
+        index.add_documents(documents).unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let (n, m) = (fields_ids_map.id("n").unwrap(), fields_ids_map.id("m").unwrap());
+        assert_eq!(index.facet_tree_stats(&rtxn).unwrap()["n"].levels, 5);
+        drop(rtxn);
+
+        let mut wtxn = index.write_txn().unwrap();
+        let mut bulk_update =
+            FacetsUpdateBulk::new_not_updating_level_0(&index, vec![n], FacetType::Number);
+        bulk_update.levels(LevelPolicy::LeafOnly);
+        bulk_update.execute(&mut wtxn).unwrap();
+
+        let stats = index.facet_tree_stats(&wtxn).unwrap();
+        assert_eq!(stats["n"], FacetTreeStats { levels: 1, node_count: 2000 });
+        assert_eq!(stats["m"].levels, 5);
+        let filter = crate::Filter::from_str("n = 42 OR n IN [1990, 1999]").unwrap().unwrap();
+        assert_eq!(filter.evaluate(&wtxn, &index).unwrap().len(), 3);
+
+        // the fields that can be compared need their upper levels
+        let mut bulk_update =
+            FacetsUpdateBulk::new_not_updating_level_0(&index, vec![m], FacetType::Number);
+        bulk_update.levels(LevelPolicy::LeafOnly);
+        let error = bulk_update.execute(&mut wtxn).unwrap_err();
+        assert!(
+            matches!(
+                error,
+                Error::InternalError(InternalError::InvalidFacetConfig { field_id, .. })
+                    if field_id == m
+            ),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn expected_cardinality_builds_shallower_trees() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field("n".to_string()),
+                    FilterableAttributesRule::Field("m".to_string()),
+                ])
+            })
+            .unwrap();
+        let documents = |ids: std::ops::Range<u32>| {
+            mmap_from_objects(ids.map(|i| {
+                serde_json::json!({ "id": i, "n": i, "m": i }).as_object().unwrap().clone()
+            }))
+        };
+        let levels = |index: &TempIndex, name: &str| {
+            let rtxn = index.read_txn().unwrap();
+            index.facet_tree_stats(&rtxn).unwrap()[name].levels
+        };
+
+        index.add_documents(documents(0..2000)).unwrap();
+        assert_eq!(levels(&index, "n"), 5);
+        assert_eq!(levels(&index, "m"), 5);
+
+        // setting an expected cardinality rebuilds the tree of the field with larger groups
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_facet_expected_cardinality(BTreeMap::from([("n".to_string(), 100_000)]))
+            })
+            .unwrap();
+        assert!(levels(&index, "n") < levels(&index, "m"));
+        assert_eq!(levels(&index, "m"), 5);
+
+        // the following additions keep the tree shallow
+        index.add_documents(documents(2000..4000)).unwrap();
+        assert!(levels(&index, "n") < levels(&index, "m"));
+        let rtxn = index.read_txn().unwrap();
+        let filter = crate::Filter::from_str("n >= 3990").unwrap().unwrap();
+        assert_eq!(filter.evaluate(&rtxn, &index).unwrap().len(), 10);
+        drop(rtxn);
+
+        // resetting it goes back to the default group size
+        index.update_settings(|settings| settings.reset_facet_expected_cardinality()).unwrap();
+        assert_eq!(levels(&index, "n"), levels(&index, "m"));
+    }
+
+    #[test]
+    fn fields_use_their_configured_update_method() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(vec![
+                    FilterableAttributesRule::Field("static".to_string()),
+                    FilterableAttributesRule::Field("live".to_string()),
+                ]);
+                settings.set_facet_update_methods(btreemap! {
+                    "static".to_string() => FacetUpdateMethodSetting::Bulk,
+                    "live".to_string() => FacetUpdateMethodSetting::Incremental,
+                });
+            })
+            .unwrap();
+        let documents = (0..2000).map(|i| {
+            serde_json::json!({ "id": i, "static": i, "live": i }).as_object().unwrap().clone()
+        });
+        index.add_documents(mmap_from_objects(documents)).unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let fid_static = fields_ids_map.id("static").unwrap();
+        let fid_live = fields_ids_map.id("live").unwrap();
+        drop(rtxn);
+
+        let update = |values: std::ops::Range<u32>| {
+            let mut wtxn = index.write_txn().unwrap();
+            let settings = InnerIndexSettings::from_index(&index, &wtxn, None).unwrap();
+            let decisions = facets_update(&index, &[fid_static, fid_live], values)
+                .execute(&mut wtxn, &settings)
+                .unwrap();
+            wtxn.commit().unwrap();
+            decisions
+        };
+        let decision = |method, delta_size| FacetUpdateDecision { method, delta_size };
+
+        // a small delta would be applied incrementally, but not to the bulk field
+        assert_eq!(
+            update(2000..2001),
+            btreemap! {
+                fid_static => decision(FacetUpdateMethod::Bulk, 1),
+                fid_live => decision(FacetUpdateMethod::Incremental, 1),
+            }
+        );
+
+        // a large delta would rebuild the levels, but not the ones of the incremental field
+        assert_eq!(
+            update(2001..2201),
+            btreemap! {
+                fid_static => decision(FacetUpdateMethod::Bulk, 200),
+                fid_live => decision(FacetUpdateMethod::Incremental, 200),
+            }
+        );
+
+        let rtxn = index.read_txn().unwrap();
+        for fid in [fid_static, fid_live] {
+            sanity_checks(&index, &rtxn, fid, FacetType::Number, 4, 5, 8).unwrap();
+        }
+    }
+}
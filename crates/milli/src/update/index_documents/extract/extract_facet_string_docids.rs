@@ -4,20 +4,19 @@ use std::io::BufReader;
 use std::iter::FromIterator;
 use std::{io, str};
 
-use charabia::normalizer::{Normalize, NormalizerOption};
-use charabia::{Language, StrDetection, Token};
 use heed::types::SerdeJson;
 use heed::BytesEncode;
 
 use super::helpers::{create_sorter, sorter_into_reader, try_split_array_at, GrenadParameters};
 use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec};
 use crate::heed_codec::{BEU16StrCodec, StrRefCodec};
+use crate::search::facet::normalize_facet_string_for_search;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::index_documents::helpers::{
     MergeDeladdBtreesetString, MergeDeladdCboRoaringBitmaps,
 };
 use crate::update::settings::{InnerIndexSettings, InnerIndexSettingsDiff};
-use crate::{FieldId, Result, MAX_FACET_VALUE_LENGTH};
+use crate::{FieldId, Result};
 
 /// Extracts the facet string and the documents ids where this facet string appear.
 ///
@@ -106,7 +105,11 @@ fn extract_facet_string_docids_document_update<R: io::Read + io::Seek>(
             metadata.filterable_attributes_features(&settings.filterable_attributes_rules);
         if features.is_facet_searchable() && settings.facet_search {
             let locales = metadata.locales(&settings.localized_attributes_rules);
-            let hyper_normalized_value = normalize_facet_string(normalized_value, locales);
+            let hyper_normalized_value = normalize_facet_string_for_search(
+                normalized_value,
+                locales,
+                features.is_lossy_normalization(),
+            );
 
             let set = BTreeSet::from_iter(std::iter::once(normalized_value));
 
@@ -193,11 +196,19 @@ fn extract_facet_string_docids_settings<R: io::Read + io::Seek>(
         let old_locales = old_metadata.locales(&settings_diff.old.localized_attributes_rules);
         let new_locales = new_metadata.locales(&settings_diff.new.localized_attributes_rules);
 
-        let are_same_locales = old_locales == new_locales;
+        let old_lossy = old_metadata
+            .filterable_attributes_features(&settings_diff.old.filterable_attributes_rules)
+            .is_lossy_normalization();
+        let new_lossy = new_metadata
+            .filterable_attributes_features(&settings_diff.new.filterable_attributes_rules)
+            .is_lossy_normalization();
+
+        // the facet strings are normalized the same way for the facet search
+        let are_same_normalizations = old_locales == new_locales && old_lossy == new_lossy;
         let reindex_facet_search =
             settings_diff.new.facet_search && !settings_diff.old.facet_search;
 
-        if is_same_value && are_same_locales && !reindex_facet_search {
+        if is_same_value && are_same_normalizations && !reindex_facet_search {
             continue;
         }
 
@@ -211,7 +222,8 @@ fn extract_facet_string_docids_settings<R: io::Read + io::Seek>(
         if settings_diff.new.facet_search {
             let new_filterable_features = new_metadata
                 .filterable_attributes_features(&settings_diff.new.filterable_attributes_rules);
-            let new_hyper_normalized_value = normalize_facet_string(normalized_value, new_locales);
+            let new_hyper_normalized_value =
+                normalize_facet_string_for_search(normalized_value, new_locales, new_lossy);
             let old_hyper_normalized_value;
             let old_filterable_features = old_metadata
                 .filterable_attributes_features(&settings_diff.old.filterable_attributes_rules);
@@ -222,10 +234,11 @@ fn extract_facet_string_docids_settings<R: io::Read + io::Seek>(
                 // if the facet search is disabled in the old settings or if no facet string is deleted,
                 // we don't need to normalize the facet string.
                 None
-            } else if are_same_locales {
+            } else if are_same_normalizations {
                 Some(&new_hyper_normalized_value)
             } else {
-                old_hyper_normalized_value = normalize_facet_string(normalized_value, old_locales);
+                old_hyper_normalized_value =
+                    normalize_facet_string_for_search(normalized_value, old_locales, old_lossy);
                 Some(&old_hyper_normalized_value)
             };
 
@@ -305,33 +318,3 @@ fn extract_facet_string_docids_settings<R: io::Read + io::Seek>(
     let normalized = sorter_into_reader(normalized_facet_string_docids_sorter, indexer)?;
     sorter_into_reader(facet_string_docids_sorter, indexer).map(|s| (s, normalized))
 }
-
-/// Normalizes the facet string and truncates it to the max length.
-fn normalize_facet_string(facet_string: &str, locales: Option<&[Language]>) -> String {
-    let options: NormalizerOption = NormalizerOption { lossy: true, ..Default::default() };
-    let mut detection = StrDetection::new(facet_string, locales);
-
-    let script = detection.script();
-    // Detect the language of the facet string only if several locales are explicitly provided.
-    let language = match locales {
-        Some(&[language]) => Some(language),
-        Some(multiple_locales) if multiple_locales.len() > 1 => detection.language(),
-        _ => None,
-    };
-
-    let token = Token {
-        lemma: std::borrow::Cow::Borrowed(facet_string),
-        script,
-        language,
-        ..Default::default()
-    };
-
-    // truncate the facet string to the max length
-    token
-        .normalize(&options)
-        .lemma
-        .char_indices()
-        .take_while(|(idx, _)| *idx < MAX_FACET_VALUE_LENGTH)
-        .map(|(_, c)| c)
-        .collect()
-}
@@ -16,6 +16,7 @@ use FilterableValues::{Empty, Null, Values};
 use super::helpers::{create_sorter, sorter_into_reader, GrenadParameters, KeepFirst};
 use crate::error::InternalError;
 use crate::facet::value_encoding::f64_into_bytes;
+use crate::facet::values_cap::FacetValuesCap;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::index_documents::{create_writer, writer_into_reader};
 use crate::update::settings::InnerIndexSettingsDiff;
@@ -84,6 +85,11 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
         let mut cursor = obkv_documents.into_cursor()?;
         while let Some((docid_bytes, value)) = cursor.move_on_next()? {
             let obkv = obkv::KvReader::from_slice(value);
+            // The old and new versions of the document are capped with their own settings.
+            let mut del_values_cap =
+                FacetValuesCap::new(settings_diff.old.max_values_per_document_field);
+            let mut add_values_cap =
+                FacetValuesCap::new(settings_diff.new.max_values_per_document_field);
             let get_document_json_value = move |field_id, side| {
                 obkv.get(field_id)
                     .map(KvReaderDelAdd::from_slice)
@@ -165,10 +171,12 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                         .new
                         .geo_fields_ids
                         .is_some_and(|(lat, lng)| field_id == lat || field_id == lng);
-                    let del_filterable_values =
-                        del_value.map(|value| extract_facet_values(&value, del_geo_support));
-                    let add_filterable_values =
-                        add_value.map(|value| extract_facet_values(&value, add_geo_support));
+                    let del_filterable_values = del_value.map(|value| {
+                        extract_facet_values(&value, field_id, del_geo_support, &mut del_values_cap)
+                    });
+                    let add_filterable_values = add_value.map(|value| {
+                        extract_facet_values(&value, field_id, add_geo_support, &mut add_values_cap)
+                    });
 
                     // Those closures are just here to simplify things a bit.
                     let mut insert_numbers_diff = |del_numbers, add_numbers| {
@@ -257,6 +265,22 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                     }
                 }
             }
+
+            let truncated_fields = add_values_cap.truncated_fields();
+            if !truncated_fields.is_empty() {
+                let document: [u8; 4] = docid_bytes[..4].try_into().ok().unwrap();
+                let document = DocumentId::from_be_bytes(document);
+                let fields: Vec<_> = truncated_fields
+                    .iter()
+                    .filter_map(|&fid| settings_diff.new.fields_ids_map.name(fid))
+                    .collect();
+                tracing::warn!(
+                    document,
+                    ?fields,
+                    max = settings_diff.new.max_values_per_document_field,
+                    "Only the first distinct facet values of some fields of the document are indexed"
+                );
+            }
         }
     }
 
@@ -513,20 +537,35 @@ enum FilterableValues {
 }
 
 /// Extracts the facet values of a JSON field.
-fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
+///
+/// The values that exceed the cap of the document are ignored, except for the geo fields.
+fn extract_facet_values(
+    value: &Value,
+    field_id: FieldId,
+    geo_field: bool,
+    values_cap: &mut FacetValuesCap,
+) -> FilterableValues {
     fn inner_extract_facet_values(
         value: &Value,
         can_recurse: bool,
         output_numbers: &mut Vec<f64>,
         output_strings: &mut Vec<(String, String)>,
+        field_id: FieldId,
         geo_field: bool,
+        values_cap: &mut FacetValuesCap,
     ) {
         match value {
             Value::Null => (),
-            Value::Bool(b) => output_strings.push((b.to_string(), b.to_string())),
+            Value::Bool(b) => {
+                if geo_field || values_cap.accept_string(field_id, &b.to_string()) {
+                    output_strings.push((b.to_string(), b.to_string()))
+                }
+            }
             Value::Number(number) => {
                 if let Some(float) = number.as_f64() {
-                    output_numbers.push(float);
+                    if geo_field || values_cap.accept_number(field_id, float) {
+                        output_numbers.push(float);
+                    }
                 }
             }
             Value::String(original) => {
@@ -542,7 +581,9 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
                     }
                 }
                 let normalized = crate::normalize_facet(original);
-                output_strings.push((normalized, original.clone()));
+                if geo_field || values_cap.accept_string(field_id, truncate_str(&normalized)) {
+                    output_strings.push((normalized, original.clone()));
+                }
             }
             Value::Array(values) => {
                 if can_recurse {
@@ -552,7 +593,9 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
                             false,
                             output_numbers,
                             output_strings,
+                            field_id,
                             geo_field,
+                            values_cap,
                         );
                     }
                 }
@@ -569,7 +612,15 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
         otherwise => {
             let mut numbers = Vec::new();
             let mut strings = Vec::new();
-            inner_extract_facet_values(otherwise, true, &mut numbers, &mut strings, geo_field);
+            inner_extract_facet_values(
+                otherwise,
+                true,
+                &mut numbers,
+                &mut strings,
+                field_id,
+                geo_field,
+                values_cap,
+            );
             FilterableValues::Values { numbers, strings }
         }
     }
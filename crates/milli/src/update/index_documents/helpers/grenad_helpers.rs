@@ -39,7 +39,32 @@ pub fn create_sorter<MF: MergeFunction>(
     max_memory: Option<usize>,
     sort_in_parallel: bool,
 ) -> grenad::Sorter<MF> {
-    let mut builder = grenad::Sorter::builder(merge);
+    create_sorter_with_chunk_creator(
+        sort_algorithm,
+        merge,
+        chunk_compression_type,
+        chunk_compression_level,
+        max_nb_chunks,
+        max_memory,
+        sort_in_parallel,
+        grenad::DefaultChunkCreator,
+    )
+}
+
+/// Same as [`create_sorter`] but the chunks dumped by the sorter are created
+/// by the given chunk creator instead of being anonymous temporary files.
+#[allow(clippy::too_many_arguments)]
+pub fn create_sorter_with_chunk_creator<MF: MergeFunction, CC: grenad::ChunkCreator>(
+    sort_algorithm: grenad::SortAlgorithm,
+    merge: MF,
+    chunk_compression_type: grenad::CompressionType,
+    chunk_compression_level: Option<u32>,
+    max_nb_chunks: Option<usize>,
+    max_memory: Option<usize>,
+    sort_in_parallel: bool,
+    chunk_creator: CC,
+) -> grenad::Sorter<MF, CC> {
+    let mut builder = grenad::Sorter::builder(merge).chunk_creator(chunk_creator);
     builder.chunk_compression_type(chunk_compression_type);
     if let Some(level) = chunk_compression_level {
         builder.chunk_compression_level(level);
@@ -260,6 +260,8 @@ pub fn merge_deladd_cbo_roaring_bitmaps_into_cbo_roaring_bitmap<'a>(
 
 /// Do a union of BtreeSet on both sides of a DelAdd obkv
 /// separately and outputs a new DelAdd with both unions.
+///
+/// The unions are sorted sets, the output doesn't depend on the order of the merged values.
 pub struct MergeDeladdBtreesetString;
 
 impl MergeFunction for MergeDeladdBtreesetString {
@@ -381,7 +381,7 @@ where
                         Err(status) => {
                             if let Some(typed_chunks) = chunk_accumulator.pop_longest() {
                                 let (docids, is_merged_database) =
-                                    write_typed_chunk_into_index(self.wtxn, self.index, &settings_diff, typed_chunks, &mut modified_docids)?;
+                                    write_typed_chunk_into_index(self.wtxn, self.index, &settings_diff, typed_chunks, &mut modified_docids, pool_params)?;
                                 if !docids.is_empty() {
                                     final_documents_ids |= docids;
                                     let documents_seen_count = final_documents_ids.len();
@@ -607,14 +607,24 @@ where
             self.index.words_prefixes_fst(self.wtxn)?.map_data(|cow| cow.into_owned())?;
 
         // Run the words prefixes update operation.
-        let PrefixSettings { prefix_count_threshold, max_prefix_length, compute_prefixes } =
-            self.index.prefix_settings(self.wtxn)?;
+        let PrefixSettings {
+            prefix_count_threshold,
+            min_prefix_length,
+            max_prefix_length,
+            compute_prefixes,
+            hot_prefixes,
+        } = self.index.prefix_settings(self.wtxn)?;
+        if compute_prefixes == PrefixSearch::HotPrefixes {
+            self.index.put_hot_prefixes_query_log(self.wtxn)?;
+        }
 
         // If the prefix search is enabled at indexing time, we compute the prefixes.
-        if compute_prefixes == PrefixSearch::IndexingTime {
+        if compute_prefixes != PrefixSearch::Disabled {
             let mut builder = WordsPrefixesFst::new(self.wtxn, self.index);
             builder.threshold(prefix_count_threshold);
+            builder.min_prefix_length(min_prefix_length);
             builder.max_prefix_length(max_prefix_length);
+            builder.hot_prefixes(hot_prefixes);
             builder.execute()?;
         } else {
             // If the prefix search is disabled at indexing time, we delete the previous words prefixes fst.
@@ -13,8 +13,8 @@ use roaring::RoaringBitmap;
 
 use super::helpers::{
     self, merge_deladd_cbo_roaring_bitmaps_into_cbo_roaring_bitmap, valid_lmdb_key,
-    CursorClonableMmap, KeepFirst, MergeDeladdBtreesetString, MergeDeladdCboRoaringBitmaps,
-    MergeIgnoreValues,
+    CursorClonableMmap, GrenadParameters, KeepFirst, MergeDeladdBtreesetString,
+    MergeDeladdCboRoaringBitmaps, MergeIgnoreValues,
 };
 use crate::external_documents_ids::{DocumentOperation, DocumentOperationKind};
 use crate::facet::FacetType;
@@ -134,6 +134,7 @@ pub(crate) fn write_typed_chunk_into_index(
     settings_diff: &InnerIndexSettingsDiff,
     typed_chunks: Vec<TypedChunk>,
     modified_docids: &mut RoaringBitmap,
+    grenad_parameters: GrenadParameters,
 ) -> Result<(RoaringBitmap, bool)> {
     let mut is_merged_database = false;
     match typed_chunks[0] {
@@ -396,13 +397,16 @@ pub(crate) fn write_typed_chunk_into_index(
             let facet_id_string_merger = facet_id_string_builder.build();
             let normalized_facet_id_string_merger = normalized_facet_id_string_builder.build();
 
-            let indexer = FacetsUpdate::new(
+            let mut indexer = FacetsUpdate::new(
                 index,
                 FacetType::String,
                 facet_id_string_merger,
                 Some(normalized_facet_id_string_merger),
                 data_size,
             );
+            indexer
+                .max_nb_chunks(grenad_parameters.max_nb_chunks)
+                .max_memory(grenad_parameters.max_memory);
             indexer.execute(wtxn, &settings_diff.new)?;
             is_merged_database = true;
         }
@@ -24,6 +24,8 @@ pub struct IndexerConfig {
     pub experimental_no_edition_2024_for_dumps: bool,
     pub experimental_no_edition_2024_for_prefix_post_processing: bool,
     pub experimental_no_edition_2024_for_facet_post_processing: bool,
+    /// Soft limit on the number of faceted fields, exceeding it only produces a warning.
+    pub max_filterable_attributes: Option<usize>,
     pub s3_snapshot_options: Option<S3SnapshotOptions>,
 }
 
@@ -97,6 +99,7 @@ impl Default for IndexerConfig {
             experimental_no_edition_2024_for_dumps: false,
             experimental_no_edition_2024_for_prefix_post_processing: false,
             experimental_no_edition_2024_for_facet_post_processing: false,
+            max_filterable_attributes: None,
             s3_snapshot_options: None,
         }
     }
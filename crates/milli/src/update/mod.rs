@@ -2,8 +2,12 @@ pub use self::available_ids::AvailableIds;
 pub use self::chat::ChatSettings;
 pub use self::clear_documents::ClearDocuments;
 pub use self::concurrent_available_ids::ConcurrentAvailableIds;
-pub use self::facet::bulk::FacetsUpdateBulk;
-pub use self::facet::incremental::FacetsUpdateIncrementalInner;
+pub use self::facet::bulk::{FacetDeltaItem, FacetsUpdateBulk, LevelPolicy};
+pub use self::facet::incremental::{FacetsUpdateIncrementalInner, FacetsUpdateIncrementalStream};
+pub use self::facet::{
+    check_facet_search_fsts, facet_tree_ascii, merge_facet_databases, repair_facet_search_fsts,
+    FacetConfig, FacetSearchMismatch, FacetUpdateDecision, FacetUpdateMethod,
+};
 pub use self::index_documents::{request_threads, *};
 pub use self::indexer_config::{default_thread_pool_and_threads, IndexerConfig, S3SnapshotOptions};
 pub use self::new::ChannelCongestion;
@@ -10,6 +10,7 @@ use serde_json::Value;
 use super::super::cache::BalancedCaches;
 use super::facet_document::{extract_document_facets, extract_geo_document};
 use super::FacetKind;
+use crate::facet::values_cap::FacetValuesCap;
 use crate::fields_ids_map::metadata::Metadata;
 use crate::filterable_attributes_rules::match_faceted_field;
 use crate::heed_codec::facet::OrderedF64Codec;
@@ -36,6 +37,7 @@ pub struct FacetedExtractorData<'a, 'b> {
     asc_desc_fields: &'a HashSet<String>,
     distinct_field: &'a Option<String>,
     is_geo_enabled: bool,
+    max_values_per_document_field: Option<usize>,
 }
 
 impl<'extractor> Extractor<'extractor> for FacetedExtractorData<'_, '_> {
@@ -63,6 +65,7 @@ impl<'extractor> Extractor<'extractor> for FacetedExtractorData<'_, '_> {
                 self.asc_desc_fields,
                 self.distinct_field,
                 self.is_geo_enabled,
+                self.max_values_per_document_field,
                 change,
                 self.sender,
             )?
@@ -82,6 +85,7 @@ impl FacetedDocidsExtractor {
         asc_desc_fields: &HashSet<String>,
         distinct_field: &Option<String>,
         is_geo_enabled: bool,
+        max_values_per_document_field: Option<usize>,
         document_change: DocumentChange,
         sender: &FieldIdDocidFacetSender,
     ) -> Result<()> {
@@ -91,11 +95,18 @@ impl FacetedDocidsExtractor {
         let mut cached_sorter = context.data.borrow_mut_or_yield();
         let mut del_add_facet_value = DelAddFacetValue::new(&context.doc_alloc);
         let docid = document_change.docid();
+        let external_docid = document_change.external_docid();
+
+        // The old and new versions of the document are capped independently,
+        // the geo fields are never capped as they contain a single value.
+        let mut del_values_cap = FacetValuesCap::new(max_values_per_document_field);
+        let mut add_values_cap = FacetValuesCap::new(max_values_per_document_field);
+        let mut geo_values_cap = FacetValuesCap::new(None);
 
         // Using a macro avoid borrowing the parameters as mutable in both closures at
         // the same time by postponing their creation
         macro_rules! facet_fn {
-            (del) => {
+            (del, $values_cap:ident) => {
                 |fid: FieldId, meta: Metadata, depth: perm_json_p::Depth, value: &Value| {
                     Self::facet_fn_with_options(
                         &context.doc_alloc,
@@ -103,6 +114,7 @@ impl FacetedDocidsExtractor {
                         BalancedCaches::insert_del_u32,
                         &mut del_add_facet_value,
                         DelAddFacetValue::insert_del,
+                        &mut $values_cap,
                         docid,
                         fid,
                         meta,
@@ -112,7 +124,7 @@ impl FacetedDocidsExtractor {
                     )
                 }
             };
-            (add) => {
+            (add, $values_cap:ident) => {
                 |fid: FieldId, meta: Metadata, depth: perm_json_p::Depth, value: &Value| {
                     Self::facet_fn_with_options(
                         &context.doc_alloc,
@@ -120,6 +132,7 @@ impl FacetedDocidsExtractor {
                         BalancedCaches::insert_add_u32,
                         &mut del_add_facet_value,
                         DelAddFacetValue::insert_add,
+                        &mut $values_cap,
                         docid,
                         fid,
                         meta,
@@ -133,8 +146,6 @@ impl FacetedDocidsExtractor {
 
         match document_change {
             DocumentChange::Deletion(inner) => {
-                let mut del = facet_fn!(del);
-
                 extract_document_facets(
                     inner.current(rtxn, index, context.db_fields_ids_map)?,
                     new_fields_ids_map.deref_mut(),
@@ -142,7 +153,7 @@ impl FacetedDocidsExtractor {
                     sortable_fields,
                     asc_desc_fields,
                     distinct_field,
-                    &mut del,
+                    &mut facet_fn!(del, del_values_cap),
                 )?;
 
                 if is_geo_enabled {
@@ -150,7 +161,7 @@ impl FacetedDocidsExtractor {
                         inner.current(rtxn, index, context.db_fields_ids_map)?,
                         inner.external_document_id(),
                         new_fields_ids_map.deref_mut(),
-                        &mut del,
+                        &mut facet_fn!(del, geo_values_cap),
                     )?;
                 }
             }
@@ -179,7 +190,7 @@ impl FacetedDocidsExtractor {
                         sortable_fields,
                         asc_desc_fields,
                         distinct_field,
-                        &mut facet_fn!(del),
+                        &mut facet_fn!(del, del_values_cap),
                     )?;
 
                     extract_document_facets(
@@ -189,7 +200,7 @@ impl FacetedDocidsExtractor {
                         sortable_fields,
                         asc_desc_fields,
                         distinct_field,
-                        &mut facet_fn!(add),
+                        &mut facet_fn!(add, add_values_cap),
                     )?;
                 }
 
@@ -201,19 +212,17 @@ impl FacetedDocidsExtractor {
                         inner.current(rtxn, index, context.db_fields_ids_map)?,
                         inner.external_document_id(),
                         new_fields_ids_map.deref_mut(),
-                        &mut facet_fn!(del),
+                        &mut facet_fn!(del, geo_values_cap),
                     )?;
                     extract_geo_document(
                         inner.merged(rtxn, index, context.db_fields_ids_map)?,
                         inner.external_document_id(),
                         new_fields_ids_map.deref_mut(),
-                        &mut facet_fn!(add),
+                        &mut facet_fn!(add, geo_values_cap),
                     )?;
                 }
             }
             DocumentChange::Insertion(inner) => {
-                let mut add = facet_fn!(add);
-
                 extract_document_facets(
                     inner.inserted(),
                     new_fields_ids_map.deref_mut(),
@@ -221,7 +230,7 @@ impl FacetedDocidsExtractor {
                     sortable_fields,
                     asc_desc_fields,
                     distinct_field,
-                    &mut add,
+                    &mut facet_fn!(add, add_values_cap),
                 )?;
 
                 if is_geo_enabled {
@@ -229,12 +238,26 @@ impl FacetedDocidsExtractor {
                         inner.inserted(),
                         inner.external_document_id(),
                         new_fields_ids_map.deref_mut(),
-                        &mut add,
+                        &mut facet_fn!(add, geo_values_cap),
                     )?;
                 }
             }
         };
 
+        let truncated_fields = add_values_cap.truncated_fields();
+        if !truncated_fields.is_empty() {
+            let fields: Vec<_> = truncated_fields
+                .iter()
+                .filter_map(|&fid| new_fields_ids_map.name(fid).map(str::to_string))
+                .collect();
+            tracing::warn!(
+                document = external_docid,
+                ?fields,
+                max = max_values_per_document_field,
+                "Only the first distinct facet values of some fields of the document are indexed"
+            );
+        }
+
         del_add_facet_value.send_data(docid, sender, &context.doc_alloc).unwrap();
         Ok(())
     }
@@ -246,6 +269,7 @@ impl FacetedDocidsExtractor {
         cache_fn: impl Fn(&mut BalancedCaches<'extractor>, &[u8], u32) -> Result<()>,
         del_add_facet_value: &mut DelAddFacetValue<'doc>,
         facet_fn: impl Fn(&mut DelAddFacetValue<'doc>, FieldId, BVec<'doc, u8>, FacetKind),
+        values_cap: &mut FacetValuesCap,
         docid: DocumentId,
         fid: FieldId,
         meta: Metadata,
@@ -274,6 +298,7 @@ impl FacetedDocidsExtractor {
                 let mut ordered = [0u8; 16];
                 if number
                     .as_f64()
+                    .filter(|&n| values_cap.accept_number(fid, n))
                     .and_then(|n| OrderedF64Codec::serialize_into(n, &mut ordered).ok())
                     .is_some()
                 {
@@ -294,12 +319,16 @@ impl FacetedDocidsExtractor {
             // String
             // key: fid - level - truncated_string
             Value::String(s) if !s.is_empty() => {
+                let normalized = crate::normalize_facet(s);
+                let truncated = truncate_str(&normalized);
+                if !values_cap.accept_string(fid, truncated) {
+                    return Ok(());
+                }
+
                 let mut string = BVec::new_in(doc_alloc);
                 string.extend_from_slice(s.as_bytes());
                 facet_fn(del_add_facet_value, fid, string, FacetKind::String);
 
-                let normalized = crate::normalize_facet(s);
-                let truncated = truncate_str(&normalized);
                 buffer.clear();
                 buffer.push(FacetKind::String as u8);
                 buffer.extend_from_slice(&fid.to_be_bytes());
@@ -310,6 +339,10 @@ impl FacetedDocidsExtractor {
             // Bool is handled as a string
             Value::Bool(b) => {
                 let b = if *b { "true" } else { "false" };
+                if !values_cap.accept_string(fid, b) {
+                    return Ok(());
+                }
+
                 let mut string = BVec::new_in(doc_alloc);
                 string.extend_from_slice(b.as_bytes());
                 facet_fn(del_add_facet_value, fid, string, FacetKind::String);
@@ -459,7 +492,7 @@ impl<'doc> DelAddFacetValue<'doc> {
 }
 
 /// Truncates a string to the biggest valid LMDB key size.
-fn truncate_str(s: &str) -> &str {
+pub(crate) fn truncate_str(s: &str) -> &str {
     let index = s
         .char_indices()
         .map(|(idx, _)| idx)
@@ -489,6 +522,8 @@ impl FacetedDocidsExtractor {
         let asc_desc_fields = index.asc_desc_fields(&rtxn)?;
         let distinct_field = index.distinct_field(&rtxn)?.map(|s| s.to_string());
         let is_geo_enabled = index.is_geo_enabled(&rtxn)?;
+        let max_values_per_document_field =
+            index.max_values_per_document_field(&rtxn)?.map(|max| max as usize);
         let datastore = ThreadLocal::new();
 
         {
@@ -505,6 +540,7 @@ impl FacetedDocidsExtractor {
                 asc_desc_fields: &asc_desc_fields,
                 distinct_field: &distinct_field,
                 is_geo_enabled,
+                max_values_per_document_field,
             };
             extract(
                 document_changes,
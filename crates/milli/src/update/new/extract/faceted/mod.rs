@@ -1,6 +1,7 @@
 mod extract_facets;
 mod facet_document;
 
+pub(crate) use extract_facets::truncate_str;
 pub use extract_facets::FacetedDocidsExtractor;
 
 #[repr(u8)]
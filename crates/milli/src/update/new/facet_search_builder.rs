@@ -1,26 +1,32 @@
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::Ordering;
 
-use charabia::normalizer::NormalizerOption;
-use charabia::{Language, Normalize, StrDetection, Token};
-use grenad::Sorter;
+use charabia::Language;
+use grenad::{ChunkCreator, DefaultChunkCreator, Sorter};
 use heed::types::{Bytes, SerdeJson};
 use heed::{BytesDecode, BytesEncode, RoTxn, RwTxn};
 
-use super::fst_merger_builder::FstMergerBuilder;
 use super::KvReaderDelAdd;
 use crate::attribute_patterns::PatternMatch;
 use crate::heed_codec::facet::FacetGroupKey;
+use crate::index::FacetingMode;
+use crate::progress::{AtomicFacetSearchFieldStep, Progress};
+use crate::search::facet::normalize_facet_string_for_search;
 use crate::update::del_add::{DelAdd, KvWriterDelAdd};
-use crate::update::{create_sorter, MergeDeladdBtreesetString};
+use crate::update::facet::{merge_facet_string_fst, write_facet_string_fst};
+use crate::update::{create_sorter_with_chunk_creator, MergeDeladdBtreesetString};
 use crate::{
     BEU16StrCodec, FieldId, FieldIdMapMissingEntry, FilterableAttributesFeatures,
     FilterableAttributesRule, GlobalFieldsIdsMap, Index, InternalError, LocalizedAttributesRule,
-    Result, MAX_FACET_VALUE_LENGTH,
+    Result,
 };
 
 pub struct FacetSearchBuilder<'indexer> {
     registered_facets: HashMap<FieldId, usize>,
+    /// The normalized values whose document counts must be updated in the FST of each field,
+    /// `None` when the whole FST must be rebuilt.
+    modified_values: HashMap<FieldId, Option<BTreeSet<String>>>,
     normalized_facet_string_docids_sorter: Sorter<MergeDeladdBtreesetString>,
     global_fields_ids_map: GlobalFieldsIdsMap<'indexer>,
     localized_attributes_rules: Vec<LocalizedAttributesRule>,
@@ -35,20 +41,16 @@ impl<'indexer> FacetSearchBuilder<'indexer> {
         global_fields_ids_map: GlobalFieldsIdsMap<'indexer>,
         localized_attributes_rules: Vec<LocalizedAttributesRule>,
         filterable_attributes_rules: Vec<FilterableAttributesRule>,
+        max_nb_chunks: Option<usize>,
+        max_memory: Option<usize>,
     ) -> Self {
         let registered_facets = HashMap::new();
-        let normalized_facet_string_docids_sorter = create_sorter(
-            grenad::SortAlgorithm::Stable,
-            MergeDeladdBtreesetString,
-            grenad::CompressionType::None,
-            None,
-            None,
-            Some(0),
-            true,
-        );
+        let normalized_facet_string_docids_sorter =
+            normalized_facet_string_sorter(max_nb_chunks, max_memory, DefaultChunkCreator);
 
         Self {
             registered_facets,
+            modified_values: HashMap::new(),
             normalized_facet_string_docids_sorter,
             buffer: Vec::new(),
             global_fields_ids_map,
@@ -72,12 +74,15 @@ impl<'indexer> FacetSearchBuilder<'indexer> {
             return Ok(());
         };
 
+        // fields only losing values are registered too, their FST must be rebuilt as well.
+        let count = self.registered_facets.entry(field_id).or_insert(0);
         if deladd == DelAdd::Addition {
-            self.registered_facets.entry(field_id).and_modify(|count| *count += 1).or_insert(1);
+            *count += 1;
         }
 
+        let lossy = filterable_attributes_features.is_lossy_normalization();
         let locales = self.locales(field_id);
-        let hyper_normalized_value = normalize_facet_string(left_bound, locales);
+        let hyper_normalized_value = normalize_facet_string_for_search(left_bound, locales, lossy);
 
         let set = BTreeSet::from_iter(std::iter::once(left_bound));
 
@@ -91,10 +96,44 @@ impl<'indexer> FacetSearchBuilder<'indexer> {
         let key: (u16, &str) = (field_id, hyper_normalized_value.as_ref());
         let key_bytes = BEU16StrCodec::bytes_encode(&key).map_err(heed::Error::Encoding)?;
         self.normalized_facet_string_docids_sorter.insert(key_bytes, &self.buffer)?;
+        self.insert_modified_value(field_id, hyper_normalized_value);
 
         Ok(())
     }
 
+    /// Registers a string facet value added to or removed from documents, the document count of
+    /// its normalized value in the FST must be recomputed even if the value itself is unchanged.
+    pub fn register_modified_value(&mut self, field_id: FieldId, facet_value: &str) -> Result<()> {
+        let filterable_attributes_features = self.filterable_attributes_features(field_id)?;
+        if !filterable_attributes_features.is_facet_searchable() {
+            return Ok(());
+        }
+
+        let lossy = filterable_attributes_features.is_lossy_normalization();
+        let locales = self.locales(field_id);
+        let hyper_normalized_value = normalize_facet_string_for_search(facet_value, locales, lossy);
+        self.insert_modified_value(field_id, hyper_normalized_value);
+
+        Ok(())
+    }
+
+    /// Registers a field with too many modified string facet values to list them,
+    /// its whole FST is rebuilt instead of being merged with the modified values.
+    pub fn register_modified_field(&mut self, field_id: FieldId) -> Result<()> {
+        if self.filterable_attributes_features(field_id)?.is_facet_searchable() {
+            self.modified_values.insert(field_id, None);
+        }
+        Ok(())
+    }
+
+    fn insert_modified_value(&mut self, field_id: FieldId, normalized_value: String) {
+        if let Some(values) =
+            self.modified_values.entry(field_id).or_insert_with(|| Some(BTreeSet::new()))
+        {
+            values.insert(normalized_value);
+        }
+    }
+
     fn filterable_attributes_features(
         &mut self,
         field_id: u16,
@@ -131,10 +170,28 @@ impl<'indexer> FacetSearchBuilder<'indexer> {
         self.localized_field_ids.get(&field_id).unwrap().as_deref()
     }
 
+    /// Writes the normalized facet strings and, when `faceting_mode` is eager, merges the
+    /// modified values and their document counts into the facet search FSTs. In lazy mode the
+    /// FSTs of the modified fields are deleted, they will be rebuilt at search time.
     #[tracing::instrument(level = "trace", skip_all, target = "indexing::facet_fst")]
-    pub fn merge_and_write(self, index: &Index, wtxn: &mut RwTxn, rtxn: &RoTxn) -> Result<()> {
+    pub fn merge_and_write<MSP>(
+        self,
+        index: &Index,
+        wtxn: &mut RwTxn,
+        rtxn: &RoTxn,
+        faceting_mode: FacetingMode,
+        must_stop_processing: &MSP,
+        progress: &Progress,
+    ) -> Result<()>
+    where
+        MSP: Fn() -> bool + Sync,
+    {
         tracing::trace!("merge facet strings for facet search: {:?}", self.registered_facets);
 
+        let (fields_processed, progress_step) =
+            AtomicFacetSearchFieldStep::new(self.modified_values.len() as u32);
+        progress.update_progress(progress_step);
+
         let reader = self.normalized_facet_string_docids_sorter.into_reader_cursors()?;
         let mut builder = grenad::MergerBuilder::new(MergeDeladdBtreesetString);
         builder.extend(reader);
@@ -142,83 +199,77 @@ impl<'indexer> FacetSearchBuilder<'indexer> {
         let database = index.facet_id_normalized_string_strings.remap_types::<Bytes, Bytes>();
 
         let mut merger_iter = builder.build().into_stream_merger_iter()?;
-        let mut current_field_id = None;
-        let mut fst;
-        let mut fst_merger_builder: Option<FstMergerBuilder> = None;
         while let Some((key, deladd)) = merger_iter.next()? {
-            let (field_id, normalized_facet_string) =
-                BEU16StrCodec::bytes_decode(key).map_err(heed::Error::Encoding)?;
-
-            if current_field_id != Some(field_id) {
-                if let (Some(current_field_id), Some(fst_merger_builder)) =
-                    (current_field_id, fst_merger_builder)
-                {
-                    let mmap = fst_merger_builder.build(&mut callback)?;
-                    index.facet_id_string_fst.remap_data_type::<Bytes>().put(
-                        wtxn,
-                        &current_field_id,
-                        &mmap,
-                    )?;
-                }
-
-                fst = index.facet_id_string_fst.get(rtxn, &field_id)?;
-                fst_merger_builder = Some(FstMergerBuilder::new(fst.as_ref())?);
-                current_field_id = Some(field_id);
+            if must_stop_processing() {
+                return Err(InternalError::AbortedIndexation.into());
             }
-
             let previous = database.get(rtxn, key)?;
             let deladd: &KvReaderDelAdd = deladd.into();
             let del = deladd.get(DelAdd::Deletion);
             let add = deladd.get(DelAdd::Addition);
 
             match merge_btreesets(previous, del, add)? {
-                Operation::Write(value) => {
-                    match fst_merger_builder.as_mut() {
-                        Some(fst_merger_builder) => {
-                            fst_merger_builder.register(
-                                DelAdd::Addition,
-                                normalized_facet_string.as_bytes(),
-                                &mut callback,
-                            )?;
-                        }
-                        None => unreachable!(),
-                    }
-                    let key = (field_id, normalized_facet_string);
-                    let key_bytes =
-                        BEU16StrCodec::bytes_encode(&key).map_err(heed::Error::Encoding)?;
-                    database.put(wtxn, &key_bytes, &value)?;
-                }
+                Operation::Write(value) => database.put(wtxn, key, &value)?,
                 Operation::Delete => {
-                    match fst_merger_builder.as_mut() {
-                        Some(fst_merger_builder) => {
-                            fst_merger_builder.register(
-                                DelAdd::Deletion,
-                                normalized_facet_string.as_bytes(),
-                                &mut callback,
-                            )?;
-                        }
-                        None => unreachable!(),
-                    }
-                    let key = (field_id, normalized_facet_string);
-                    let key_bytes =
-                        BEU16StrCodec::bytes_encode(&key).map_err(heed::Error::Encoding)?;
-                    database.delete(wtxn, &key_bytes)?;
+                    database.delete(wtxn, key)?;
                 }
                 Operation::Ignore => (),
             }
         }
 
-        if let (Some(field_id), Some(fst_merger_builder)) = (current_field_id, fst_merger_builder) {
-            let mmap = fst_merger_builder.build(&mut callback)?;
-            index.facet_id_string_fst.remap_data_type::<Bytes>().put(wtxn, &field_id, &mmap)?;
+        // The counts of the values that weren't added or removed may have changed too, they are
+        // recomputed from the up to date normalized strings and facet values.
+        let modified_values: BTreeMap<_, _> = self.modified_values.into_iter().collect();
+        for (field_id, normalized_values) in modified_values {
+            if must_stop_processing() {
+                return Err(InternalError::AbortedIndexation.into());
+            }
+            let fst_size = match (faceting_mode, normalized_values) {
+                (FacetingMode::Eager, Some(normalized_values)) => {
+                    // the FSTs are only written here, the one of the read transaction is current
+                    let previous = index.facet_id_string_fst.get(rtxn, &field_id)?;
+                    merge_facet_string_fst(
+                        index,
+                        wtxn,
+                        field_id,
+                        previous.as_ref(),
+                        &normalized_values,
+                    )?
+                }
+                (FacetingMode::Eager, None) => write_facet_string_fst(index, wtxn, field_id)?,
+                (FacetingMode::Lazy, _) => {
+                    index.facet_id_string_fst.delete(wtxn, &field_id)?;
+                    0
+                }
+            };
+            progress.record_facet_indexing_memory(fst_size);
+            fields_processed.fetch_add(1, Ordering::Relaxed);
         }
 
         Ok(())
     }
 }
 
-fn callback(_bytes: &[u8], _deladd: DelAdd, _is_modified: bool) -> Result<()> {
-    Ok(())
+/// Creates the sorter used to accumulate the normalized facet strings before
+/// they are merged into the facet search databases and FSTs.
+///
+/// A `max_memory` of `None` lets the sorter keep everything in memory and
+/// only write to disk once, when it is turned into readers.
+fn normalized_facet_string_sorter<CC: ChunkCreator>(
+    max_nb_chunks: Option<usize>,
+    max_memory: Option<usize>,
+    chunk_creator: CC,
+) -> Sorter<MergeDeladdBtreesetString, CC> {
+    create_sorter_with_chunk_creator(
+        grenad::SortAlgorithm::Stable,
+        MergeDeladdBtreesetString,
+        grenad::CompressionType::None,
+        None,
+        max_nb_chunks,
+        max_memory,
+        true,
+        chunk_creator,
+    )
 }
 
 fn merge_btreesets(
@@ -251,37 +302,77 @@ fn merge_btreesets(
 }
 
 /// Normalizes the facet string and truncates it to the max length.
-fn normalize_facet_string(facet_string: &str, locales: Option<&[Language]>) -> String {
-    let options: NormalizerOption = NormalizerOption { lossy: true, ..Default::default() };
-    let mut detection = StrDetection::new(facet_string, locales);
-
-    let script = detection.script();
-    // Detect the language of the facet string only if several locales are explicitly provided.
-    let language = match locales {
-        Some(&[language]) => Some(language),
-        Some(multiple_locales) if multiple_locales.len() > 1 => detection.language(),
-        _ => None,
-    };
-
-    let token = Token {
-        lemma: std::borrow::Cow::Borrowed(facet_string),
-        script,
-        language,
-        ..Default::default()
-    };
-
-    // truncate the facet string to the max length
-    token
-        .normalize(&options)
-        .lemma
-        .char_indices()
-        .take_while(|(idx, _)| *idx < MAX_FACET_VALUE_LENGTH)
-        .map(|(_, c)| c)
-        .collect()
-}
-
 enum Operation {
     Write(Vec<u8>),
     Delete,
     Ignore,
 }
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+
+    /// Creates the chunks dumped by a sorter as named files in a directory, to count them.
+    struct ChunksIn<'a>(&'a Path);
+
+    impl ChunkCreator for ChunksIn<'_> {
+        type Chunk = tempfile::NamedTempFile;
+        type Error = std::io::Error;
+
+        fn create(&self) -> std::io::Result<Self::Chunk> {
+            tempfile::NamedTempFile::new_in(self.0)
+        }
+    }
+
+    fn insert_values<CC: ChunkCreator>(sorter: &mut Sorter<MergeDeladdBtreesetString, CC>) {
+        let mut buffer = Vec::new();
+        for i in 0..1000u16 {
+            let value = format!("value-{i}");
+            let set = BTreeSet::from_iter(std::iter::once(value.as_str()));
+
+            buffer.clear();
+            let mut obkv = KvWriterDelAdd::new(&mut buffer);
+            obkv.insert(DelAdd::Addition, SerdeJson::bytes_encode(&set).unwrap()).unwrap();
+            obkv.finish().unwrap();
+
+            let key_bytes = BEU16StrCodec::bytes_encode(&(0, value.as_str())).unwrap();
+            sorter.insert(key_bytes, &buffer).unwrap();
+        }
+    }
+
+    fn files_count(dir: &Path) -> usize {
+        std::fs::read_dir(dir).unwrap().count()
+    }
+
+    #[test]
+    fn normalized_facet_string_sorter_honors_memory_budget() {
+        // A generous budget keeps every entry in memory, no temporary file is
+        // created until the sorter dumps a single chunk when turned into readers.
+        let dir = tempfile::tempdir().unwrap();
+        let mut sorter =
+            normalized_facet_string_sorter(None, Some(100 * 1024 * 1024), ChunksIn(dir.path()));
+        insert_values(&mut sorter);
+        assert_eq!(files_count(dir.path()), 0);
+
+        let cursors = sorter.into_reader_cursors().unwrap();
+        assert_eq!(cursors.len(), 1);
+        assert_eq!(files_count(dir.path()), 1);
+
+        let mut builder = grenad::MergerBuilder::new(MergeDeladdBtreesetString);
+        builder.extend(cursors);
+        let mut iter = builder.build().into_stream_merger_iter().unwrap();
+        let mut count = 0;
+        while iter.next().unwrap().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 1000);
+
+        // a small budget makes the sorter spill to disk while the entries are inserted
+        let dir = tempfile::tempdir().unwrap();
+        let mut sorter = normalized_facet_string_sorter(None, Some(4 * 1024), ChunksIn(dir.path()));
+        insert_values(&mut sorter);
+        assert!(files_count(dir.path()) > 0);
+    }
+}
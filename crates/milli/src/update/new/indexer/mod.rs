@@ -11,7 +11,7 @@ use hashbrown::HashMap;
 use heed::types::DecodeIgnore;
 use heed::{BytesDecode, Database, RoTxn, RwTxn};
 pub use partial_dump::PartialDump;
-pub use post_processing::recompute_word_fst_from_word_docids_database;
+pub use post_processing::{recompute_word_fst_from_word_docids_database, recompute_word_prefixes};
 pub use settings_changes::settings_change_extract;
 pub use update_by_function::UpdateByFunction;
 pub use write::ChannelCongestion;
@@ -215,7 +215,9 @@ where
         indexing_context.progress.update_progress(IndexingStep::Finalizing);
 
         Ok(congestion) as Result<_>
-    })?;
+    });
+    // LMDB does not tell the size of the map it filled, e.g. while writing the facet search FSTs.
+    let congestion = congestion.map_err(|error| error.with_map_size(index.map_size()))?;
 
     // required to into_inner the new_fields_ids_map
     drop(fields_ids_map_store);
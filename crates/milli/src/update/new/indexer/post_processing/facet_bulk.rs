@@ -3,7 +3,7 @@ use std::io::BufReader;
 use std::{iter, mem};
 
 use grenad::CompressionType;
-use heed::types::{Bytes, LazyDecode};
+use heed::types::{Bytes, DecodeIgnore, LazyDecode};
 use heed::{Database, RwTxn};
 use rayon::prelude::*;
 use roaring::MultiOps;
@@ -12,7 +12,8 @@ use tempfile::tempfile;
 use crate::facet::FacetType;
 use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec};
 use crate::heed_codec::BytesRefCodec;
-use crate::update::facet::{FACET_GROUP_SIZE, FACET_MIN_LEVEL_SIZE};
+use crate::progress::Progress;
+use crate::update::facet::{capped_group_size, FacetConfig};
 use crate::update::{create_writer, writer_into_reader};
 use crate::{CboRoaringBitmapCodec, FieldId, Index};
 
@@ -21,11 +22,16 @@ use crate::{CboRoaringBitmapCodec, FieldId, Index};
 /// The function will generate all the group levels from
 /// the group 1 to the level n until the number of group
 /// is smaller than the minimum required size.
+///
+/// The levels are built with the group size and min level size of the given configuration, the
+/// group size is raised when needed to respect the maximum number of facet levels of the index.
 pub fn generate_facet_levels(
     index: &Index,
     wtxn: &mut RwTxn,
     field_id: FieldId,
     facet_type: FacetType,
+    config: FacetConfig,
+    progress: &Progress,
 ) -> crate::Result<()> {
     let db = match facet_type {
         FacetType::String => index
@@ -40,12 +46,38 @@ pub fn generate_facet_levels(
 
     clear_levels(db, wtxn, field_id)?;
 
+    let FacetConfig { group_size, min_level_size, .. } = config;
+    let group_size = match index.max_facet_levels(wtxn)? {
+        Some(max_levels) => {
+            let level_0_prefix = FacetGroupKey::<&[u8]> { field_id, level: 0, left_bound: &[] };
+            let mut level_0_len = 0;
+            for result in db.remap_data_type::<DecodeIgnore>().prefix_iter(wtxn, &level_0_prefix)? {
+                result?;
+                level_0_len += 1;
+            }
+            let capped = capped_group_size(level_0_len, group_size, min_level_size, max_levels);
+            if capped != group_size {
+                tracing::info!(
+                    %field_id,
+                    level_0_len,
+                    max_levels,
+                    from = group_size,
+                    to = capped,
+                    "raising the facet group size to respect the maximum number of facet levels"
+                );
+                progress.record_facet_effective_group_size(capped);
+            }
+            capped
+        }
+        None => group_size,
+    };
+
     let mut base_level: u8 = 0;
     // That's a do-while loop
     while {
         let mut level_size = 0;
         let level = base_level.checked_add(1).unwrap();
-        for reader in compute_level(index, wtxn, db, field_id, base_level)? {
+        for reader in compute_level(index, wtxn, db, field_id, base_level, group_size)? {
             let mut cursor = reader.into_cursor()?;
             while let Some((left_bound, facet_group_value)) = cursor.move_on_next()? {
                 level_size += 1;
@@ -61,7 +93,7 @@ pub fn generate_facet_levels(
         base_level = level;
 
         // If the next level will have the minimum required groups, continue.
-        (level_size / FACET_GROUP_SIZE as usize) >= FACET_MIN_LEVEL_SIZE as usize
+        (level_size / group_size as usize) >= min_level_size as usize
     } {}
 
     Ok(())
@@ -75,6 +107,7 @@ fn compute_level(
     db: Database<FacetGroupKeyCodec<BytesRefCodec>, LazyDecode<FacetGroupValueCodec>>,
     field_id: FieldId,
     base_level: u8,
+    group_size: u8,
 ) -> Result<Vec<grenad::Reader<BufReader<File>>>, crate::Error> {
     let thread_count = rayon::current_num_threads();
     let rtxns = iter::repeat_with(|| index.env.nested_read_txn(wtxn))
@@ -105,8 +138,8 @@ fn compute_level(
             for (i, result) in db.range(&rtxn, &range)?.enumerate() {
                 let (key, lazy_value) = result?;
 
-                let start_of_group = i % FACET_GROUP_SIZE as usize == 0;
-                let group_index = i / FACET_GROUP_SIZE as usize;
+                let start_of_group = i % group_size as usize == 0;
+                let group_index = i / group_size as usize;
                 let group_for_thread = group_index % thread_count == thread_id;
 
                 if group_for_thread {
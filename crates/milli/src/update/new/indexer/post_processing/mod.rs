@@ -1,17 +1,24 @@
 use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
 
 use facet_bulk::generate_facet_levels;
+use fst::{Automaton, IntoStreamer, Streamer};
 use heed::types::{Bytes, DecodeIgnore, Str};
 use heed::RwTxn;
 use itertools::{merge_join_by, EitherOrBoth};
 
 use super::document_changes::IndexingContext;
+use crate::facet::values_weight::rebuild_facet_values_weights;
 use crate::facet::FacetType;
 use crate::index::main_key::{WORDS_FST_KEY, WORDS_PREFIXES_FST_KEY};
+use crate::index::{FacetingMode, PrefixSearch};
 use crate::progress::Progress;
 use crate::update::del_add::DelAdd;
 use crate::update::facet::new_incremental::FacetsUpdateIncremental;
-use crate::update::facet::{FACET_GROUP_SIZE, FACET_MAX_GROUP_SIZE, FACET_MIN_LEVEL_SIZE};
+use crate::update::facet::{
+    clear_facet_levels, configured_update_methods, expected_cardinality_field_configs,
+    FacetRebalancing, FacetUpdateMethod,
+};
 use crate::update::new::facet_search_builder::FacetSearchBuilder;
 use crate::update::new::merger::FacetFieldIdDelta;
 use crate::update::new::steps::{IndexingStep, PostProcessingFacets, PostProcessingWords};
@@ -22,7 +29,7 @@ use crate::update::new::words_prefix_docids::{
 };
 use crate::update::new::FacetFieldIdsDelta;
 use crate::update::{FacetsUpdateBulk, GrenadParameters};
-use crate::{GlobalFieldsIdsMap, Index, Result};
+use crate::{FieldId, GlobalFieldsIdsMap, Index, InternalError, Prefix, Result};
 
 mod facet_bulk;
 
@@ -30,22 +37,47 @@ pub(super) fn post_process<MSP>(
     indexing_context: IndexingContext<MSP>,
     wtxn: &mut RwTxn<'_>,
     mut global_fields_ids_map: GlobalFieldsIdsMap<'_>,
-    facet_field_ids_delta: FacetFieldIdsDelta,
+    mut facet_field_ids_delta: FacetFieldIdsDelta,
 ) -> Result<()>
 where
     MSP: Fn() -> bool + Sync,
 {
     let index = indexing_context.index;
     indexing_context.progress.update_progress(IndexingStep::PostProcessingFacets);
-    compute_facet_level_database(
-        index,
-        wtxn,
-        facet_field_ids_delta,
-        &mut global_fields_ids_map,
-        indexing_context.grenad_parameters,
-        indexing_context.progress,
-    )?;
-    compute_facet_search_database(index, wtxn, global_fields_ids_map, indexing_context.progress)?;
+    if let Some(values_recency) = facet_field_ids_delta.consume_values_recency() {
+        values_recency.write(index, wtxn)?;
+    }
+    // the weights of the values can change with any document, they are read again from them all
+    rebuild_facet_values_weights(index, wtxn)?;
+    let facet_strings_changed = facet_field_ids_delta.has_facet_string_changes();
+    let modified_facet_strings = facet_field_ids_delta.modified_facet_strings();
+    let faceting_mode = index.faceting_mode(wtxn)?;
+    match faceting_mode {
+        FacetingMode::Eager => compute_facet_level_database(
+            index,
+            wtxn,
+            facet_field_ids_delta,
+            &mut global_fields_ids_map,
+            indexing_context.grenad_parameters,
+            indexing_context.must_stop_processing,
+            indexing_context.progress,
+        )?,
+        FacetingMode::Lazy => clear_modified_facet_levels(index, wtxn, facet_field_ids_delta)?,
+    }
+    // The facet search databases and FSTs only depend on the string facet values, there is no
+    // need to scan the `facet_id_string_docids` database when only numbers were modified.
+    if facet_strings_changed {
+        compute_facet_search_database(
+            index,
+            wtxn,
+            global_fields_ids_map,
+            modified_facet_strings,
+            indexing_context.grenad_parameters,
+            faceting_mode,
+            indexing_context.must_stop_processing,
+            indexing_context.progress,
+        )?;
+    }
     indexing_context.progress.update_progress(IndexingStep::PostProcessingWords);
     if let Some(prefix_delta) = compute_word_fst(index, wtxn, indexing_context.progress)? {
         compute_prefix_database(
@@ -68,6 +100,13 @@ fn compute_prefix_database(
     progress: &Progress,
 ) -> Result<()> {
     let PrefixDelta { modified, deleted } = prefix_delta;
+    progress.record_word_prefix_changes(modified.len(), deleted.len());
+    tracing::debug!(
+        target: "indexing::prefix",
+        recomputed = ?modified,
+        deleted = ?deleted,
+        "Updating the word prefix databases"
+    );
 
     progress.update_progress(PostProcessingWords::WordPrefixDocids);
     compute_word_prefix_docids(wtxn, index, &modified, &deleted, grenad_parameters)?;
@@ -94,6 +133,9 @@ fn compute_word_fst(
     let words_fst = index.words_fst(&rtxn)?;
     let mut word_fst_builder = WordFstBuilder::new(&words_fst)?;
     let prefix_settings = index.prefix_settings(&rtxn)?;
+    if prefix_settings.compute_prefixes == PrefixSearch::HotPrefixes {
+        index.put_hot_prefixes_query_log(wtxn)?;
+    }
     word_fst_builder.with_prefix_settings(prefix_settings);
 
     let previous_words = index.word_docids.iter(&rtxn)?.remap_data_type::<Bytes>();
@@ -154,13 +196,60 @@ pub fn recompute_word_fst_from_word_docids_database(
     Ok(())
 }
 
+/// Recomputes the given prefixes in the word prefix databases from the word databases,
+/// e.g. to repair entries suspected to be corrupted without reindexing the documents.
+///
+/// The prefixes missing from the prefixes FST are not part of the prefix databases,
+/// their entries are deleted instead, like an indexing would do.
+pub fn recompute_word_prefixes(
+    index: &Index,
+    wtxn: &mut RwTxn,
+    prefixes: &BTreeSet<Prefix>,
+    grenad_parameters: &GrenadParameters,
+    progress: &Progress,
+) -> Result<()> {
+    let prefixes_fst = index.words_prefixes_fst(wtxn)?;
+    let mut modified = BTreeSet::new();
+    let mut deleted = BTreeSet::new();
+    for prefix in prefixes {
+        if prefixes_fst.contains(prefix.as_bytes()) {
+            modified.insert(prefix.clone());
+        } else {
+            // the deletion removes the entries of the longer prefixes too, they are recomputed
+            let automaton = fst::automaton::Str::new(prefix).starts_with();
+            let mut stream = prefixes_fst.search(automaton).into_stream();
+            while let Some(longer) = stream.next() {
+                modified.insert(Prefix::from(std::str::from_utf8(longer)?));
+            }
+            deleted.insert(prefix.clone());
+        }
+    }
+    drop(prefixes_fst);
+
+    compute_prefix_database(
+        index,
+        wtxn,
+        PrefixDelta { modified, deleted },
+        grenad_parameters,
+        progress,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::facet_search")]
-fn compute_facet_search_database(
+fn compute_facet_search_database<MSP>(
     index: &Index,
     wtxn: &mut RwTxn,
     global_fields_ids_map: GlobalFieldsIdsMap,
+    modified_facet_strings: Vec<(FieldId, Option<Vec<String>>)>,
+    grenad_parameters: &GrenadParameters,
+    faceting_mode: FacetingMode,
+    must_stop_processing: &MSP,
     progress: &Progress,
-) -> Result<()> {
+) -> Result<()>
+where
+    MSP: Fn() -> bool + Sync,
+{
     let rtxn = index.read_txn()?;
     progress.update_progress(PostProcessingFacets::FacetSearch);
 
@@ -175,7 +264,20 @@ fn compute_facet_search_database(
         global_fields_ids_map,
         localized_attributes_rules.unwrap_or_default(),
         filterable_attributes_rules,
+        grenad_parameters.max_nb_chunks,
+        grenad_parameters.max_memory,
     );
+    // the document counts stored in the FSTs for these values changed
+    for (fid, values) in modified_facet_strings {
+        match values {
+            Some(values) => {
+                for value in values {
+                    facet_search_builder.register_modified_value(fid, &value)?;
+                }
+            }
+            None => facet_search_builder.register_modified_field(fid)?,
+        }
+    }
 
     let previous_facet_id_string_docids = index
         .facet_id_string_docids
@@ -211,26 +313,82 @@ fn compute_facet_search_database(
         }
     }
 
-    facet_search_builder.merge_and_write(index, wtxn, &rtxn)
+    facet_search_builder.merge_and_write(
+        index,
+        wtxn,
+        &rtxn,
+        faceting_mode,
+        must_stop_processing,
+        progress,
+    )
 }
 
+/// In lazy faceting mode, the level 0 of the facet databases is up to date but the
+/// upper levels of the modified fields are stale, we remove them until the next rebuild.
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::facet_field_ids")]
-fn compute_facet_level_database(
+fn clear_modified_facet_levels(
+    index: &Index,
+    wtxn: &mut RwTxn,
+    mut facet_field_ids_delta: FacetFieldIdsDelta,
+) -> Result<()> {
+    let string_fids: Vec<_> =
+        facet_field_ids_delta.consume_facet_string_delta().map(|(fid, _)| fid).collect();
+    clear_facet_levels(wtxn, &index.facet_id_string_docids.remap_types(), &string_fids)?;
+
+    let number_fids: Vec<_> =
+        facet_field_ids_delta.consume_facet_number_delta().map(|(fid, _)| fid).collect();
+    clear_facet_levels(wtxn, &index.facet_id_f64_docids.remap_types(), &number_fids)?;
+
+    Ok(())
+}
+
+/// Records how the facet levels of a field are updated, the internal geo fields are not reported.
+fn record_facet_update_decision(
+    progress: &Progress,
+    global_fields_ids_map: &mut GlobalFieldsIdsMap,
+    fid: FieldId,
+    facet_type: FacetType,
+    delta: &FacetFieldIdDelta,
+) {
+    match global_fields_ids_map.metadata(fid) {
+        Some(metadata) if !metadata.is_geo() && !metadata.geo_json => (),
+        _ => return,
+    }
+    if let Some(name) = global_fields_ids_map.name(fid) {
+        progress.record_facet_update_decision(name, facet_type, delta.decision());
+    }
+}
+
+#[tracing::instrument(level = "trace", skip_all, target = "indexing::facet_field_ids")]
+fn compute_facet_level_database<MSP>(
     index: &Index,
     wtxn: &mut RwTxn,
     mut facet_field_ids_delta: FacetFieldIdsDelta,
     global_fields_ids_map: &mut GlobalFieldsIdsMap,
     grenad_parameters: &GrenadParameters,
+    must_stop_processing: &MSP,
     progress: &Progress,
-) -> Result<()> {
+) -> Result<()>
+where
+    MSP: Fn() -> bool + Sync,
+{
     let rtxn = index.read_txn()?;
 
     let filterable_attributes_rules = index.filterable_attributes_rules(&rtxn)?;
-    let mut deltas: Vec<_> = facet_field_ids_delta.consume_facet_string_delta().collect();
+    let field_configs = expected_cardinality_field_configs(index, wtxn)?;
+    let update_methods = configured_update_methods(index, wtxn)?;
+    let mut rebalancing = FacetRebalancing::new(index, wtxn, FacetType::String)?;
+    let mut deltas: Vec<_> = facet_field_ids_delta
+        .consume_facet_string_delta()
+        .map(|(fid, delta)| (fid, rebalance(&rebalancing, &update_methods, fid, delta)))
+        .collect();
     // We move all bulks at the front and incrementals (others) at the end.
-    deltas.sort_by_key(|(_, delta)| if let FacetFieldIdDelta::Bulk = delta { 0 } else { 1 });
+    deltas.sort_by_key(|(_, delta)| if let FacetFieldIdDelta::Bulk { .. } = delta { 0 } else { 1 });
 
     for (fid, delta) in deltas {
+        if must_stop_processing() {
+            return Err(InternalError::AbortedIndexation.into());
+        }
         // skip field ids that should not be facet leveled
         let Some(metadata) = global_fields_ids_map.metadata(fid) else {
             continue;
@@ -238,19 +396,34 @@ fn compute_facet_level_database(
         if !metadata.require_facet_level_database(&filterable_attributes_rules) {
             continue;
         }
+        let config = field_configs.get(&fid).copied().unwrap_or_default();
+
+        record_facet_update_decision(
+            progress,
+            global_fields_ids_map,
+            fid,
+            FacetType::String,
+            &delta,
+        );
+        rebalancing.record(fid, delta.decision().method);
 
         let span = tracing::trace_span!(target: "indexing::facet_field_ids", "string");
         let _entered = span.enter();
         match delta {
-            FacetFieldIdDelta::Bulk => {
+            FacetFieldIdDelta::Bulk { .. } => {
                 progress.update_progress(PostProcessingFacets::StringsBulk);
                 if grenad_parameters.experimental_no_edition_2024_for_facet_post_processing {
                     tracing::debug!(%fid, "bulk string facet processing");
-                    FacetsUpdateBulk::new_not_updating_level_0(index, vec![fid], FacetType::String)
-                        .execute(wtxn)?
+                    let mut bulk_update = FacetsUpdateBulk::new_not_updating_level_0(
+                        index,
+                        vec![fid],
+                        FacetType::String,
+                    );
+                    bulk_update.progress(progress).must_stop_processing(must_stop_processing);
+                    bulk_update.execute(wtxn)?
                 } else {
                     tracing::debug!(%fid, "bulk string facet processing in parallel");
-                    generate_facet_levels(index, wtxn, fid, FacetType::String)?
+                    generate_facet_levels(index, wtxn, fid, FacetType::String, config, progress)?
                 }
             }
             FacetFieldIdDelta::Incremental(delta_data) => {
@@ -261,28 +434,48 @@ fn compute_facet_level_database(
                     FacetType::String,
                     fid,
                     delta_data,
-                    FACET_GROUP_SIZE,
-                    FACET_MIN_LEVEL_SIZE,
-                    FACET_MAX_GROUP_SIZE,
+                    config.group_size,
+                    config.min_level_size,
+                    config.max_group_size,
                 )
                 .execute(wtxn)?
             }
         }
     }
+    rebalancing.finish(index, wtxn)?;
 
-    let mut deltas: Vec<_> = facet_field_ids_delta.consume_facet_number_delta().collect();
+    let mut rebalancing = FacetRebalancing::new(index, wtxn, FacetType::Number)?;
+    let mut deltas: Vec<_> = facet_field_ids_delta
+        .consume_facet_number_delta()
+        .map(|(fid, delta)| (fid, rebalance(&rebalancing, &update_methods, fid, delta)))
+        .collect();
     // We move all bulks at the front and incrementals (others) at the end.
-    deltas.sort_by_key(|(_, delta)| if let FacetFieldIdDelta::Bulk = delta { 0 } else { 1 });
+    deltas.sort_by_key(|(_, delta)| if let FacetFieldIdDelta::Bulk { .. } = delta { 0 } else { 1 });
 
     for (fid, delta) in deltas {
+        if must_stop_processing() {
+            return Err(InternalError::AbortedIndexation.into());
+        }
+        let config = field_configs.get(&fid).copied().unwrap_or_default();
+        record_facet_update_decision(
+            progress,
+            global_fields_ids_map,
+            fid,
+            FacetType::Number,
+            &delta,
+        );
+        rebalancing.record(fid, delta.decision().method);
+
         let span = tracing::trace_span!(target: "indexing::facet_field_ids", "number");
         let _entered = span.enter();
         match delta {
-            FacetFieldIdDelta::Bulk => {
+            FacetFieldIdDelta::Bulk { .. } => {
                 progress.update_progress(PostProcessingFacets::NumbersBulk);
                 tracing::debug!(%fid, "bulk number facet processing");
-                FacetsUpdateBulk::new_not_updating_level_0(index, vec![fid], FacetType::Number)
-                    .execute(wtxn)?
+                let mut bulk_update =
+                    FacetsUpdateBulk::new_not_updating_level_0(index, vec![fid], FacetType::Number);
+                bulk_update.progress(progress).must_stop_processing(must_stop_processing);
+                bulk_update.execute(wtxn)?
             }
             FacetFieldIdDelta::Incremental(delta_data) => {
                 progress.update_progress(PostProcessingFacets::NumbersIncremental);
@@ -292,9 +485,9 @@ fn compute_facet_level_database(
                     FacetType::Number,
                     fid,
                     delta_data,
-                    FACET_GROUP_SIZE,
-                    FACET_MIN_LEVEL_SIZE,
-                    FACET_MAX_GROUP_SIZE,
+                    config.group_size,
+                    config.min_level_size,
+                    config.max_group_size,
                 )
                 .execute(wtxn)?
             }
@@ -304,12 +497,32 @@ fn compute_facet_level_database(
             wtxn,
             fid,
             FacetType::Number,
-            FACET_GROUP_SIZE as usize,
-            FACET_MIN_LEVEL_SIZE as usize,
-            FACET_MAX_GROUP_SIZE as usize,
+            config.group_size as usize,
+            config.min_level_size as usize,
+            config.max_group_size as usize,
         )
         .is_ok());
     }
+    rebalancing.finish(index, wtxn)?;
 
     Ok(())
 }
+
+/// Turns the incremental delta of a field whose levels were incrementally updated too often,
+/// see [`FacetRebalancing`], or that is configured to use the bulk method into a bulk one.
+fn rebalance(
+    rebalancing: &FacetRebalancing,
+    update_methods: &HashMap<FieldId, FacetUpdateMethod>,
+    fid: FieldId,
+    delta: FacetFieldIdDelta,
+) -> FacetFieldIdDelta {
+    let bulk_configured = update_methods.get(&fid) == Some(&FacetUpdateMethod::Bulk);
+    match delta {
+        FacetFieldIdDelta::Incremental(changes)
+            if bulk_configured || rebalancing.requires_bulk(fid) =>
+        {
+            FacetFieldIdDelta::Bulk { delta_size: changes.len() }
+        }
+        delta => delta,
+    }
+}
@@ -45,12 +45,7 @@ pub fn write_to_db(
                 let database_name = database.database_name();
                 let database = database.database(index);
                 if let Err(error) = database.put(wtxn, &key, &value) {
-                    return Err(Error::InternalError(InternalError::StorePut {
-                        database_name,
-                        key: bstr::BString::from(&key[..]),
-                        value_length: value.len(),
-                        error,
-                    }));
+                    return Err(store_put_error(database_name, &key, value.len(), error));
                 }
             }
             ReceiverAction::LargeVectors(large_vectors) => {
@@ -201,12 +196,7 @@ pub fn write_from_bbqueue(
                 match operation.key_value(frame) {
                     (key, Some(value)) => {
                         if let Err(error) = database.put(wtxn, key, value) {
-                            return Err(Error::InternalError(InternalError::StorePut {
-                                database_name,
-                                key: key.into(),
-                                value_length: value.len(),
-                                error,
-                            }));
+                            return Err(store_put_error(database_name, key, value.len(), error));
                         }
                     }
                     (key, None) => match database.delete(wtxn, key) {
@@ -289,3 +279,22 @@ pub fn write_from_bbqueue(
 
     Ok(())
 }
+
+/// Reports a full map as a [`UserError::MaxDatabaseSizeReached`] to let the caller resize
+/// the index, and the other errors along with the entry that could not be written.
+fn store_put_error(
+    database_name: &'static str,
+    key: &[u8],
+    value_length: usize,
+    error: heed::Error,
+) -> Error {
+    match error {
+        heed::Error::Mdb(heed::MdbError::MapFull) => Error::from(error),
+        error => Error::InternalError(InternalError::StorePut {
+            database_name,
+            key: key.into(),
+            value_length,
+            error,
+        }),
+    }
+}
@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::collections::BTreeSet;
 
 use hashbrown::HashMap;
 use heed::types::Bytes;
@@ -12,7 +13,9 @@ use super::extract::{
     merge_caches_sorted, transpose_and_freeze_caches, BalancedCaches, DelAddRoaringBitmap,
     FacetKind, GeoExtractorData,
 };
+use crate::facet::values_recency::FacetValuesRecencyDelta;
 use crate::update::facet::new_incremental::FacetFieldIdChange;
+use crate::update::facet::{configured_update_methods, FacetUpdateDecision, FacetUpdateMethod};
 use crate::update::new::extract::cellulite::GeoJsonExtractorData;
 use crate::{CboRoaringBitmapCodec, FieldId, GeoPoint, Index, InternalError, Result};
 
@@ -127,13 +130,26 @@ pub fn merge_and_send_facet_docids(
     let max_number_count = (index.facet_id_f64_docids.len(rtxn)? / 500) as usize;
     let max_string_count = max_string_count.clamp(1000, 100_000);
     let max_number_count = max_number_count.clamp(1000, 100_000);
+    let track_values_recency = index.track_facet_values_recency(rtxn)?;
+    let incremental_fields: BTreeSet<_> = configured_update_methods(index, rtxn)?
+        .into_iter()
+        .filter_map(|(fid, method)| (method == FacetUpdateMethod::Incremental).then_some(fid))
+        .collect();
+    let new_delta = || {
+        FacetFieldIdsDelta::new(max_string_count, max_number_count)
+            .with_values_recency(track_values_recency)
+            .with_incremental_fields(incremental_fields.clone())
+    };
     transpose_and_freeze_caches(&mut caches)?
         .into_par_iter()
         .map(|frozen| {
-            let mut facet_field_ids_delta =
-                FacetFieldIdsDelta::new(max_string_count, max_number_count);
+            let mut facet_field_ids_delta = new_delta();
             let rtxn = index.read_txn()?;
             merge_caches_sorted(frozen, |key, DelAddRoaringBitmap { del, add }| {
+                // a document updated with the same value is associated with it again
+                if add.as_ref().is_some_and(|add| !add.is_empty()) {
+                    facet_field_ids_delta.register_associated_from_key(key);
+                }
                 let current = database.get_cbo_roaring_bytes_value(&rtxn, key)?;
                 match merge_cbo_bitmaps(current, del, add)? {
                     Operation::Write(bitmap) => {
@@ -143,6 +159,7 @@ pub fn merge_and_send_facet_docids(
                     }
                     Operation::Delete => {
                         facet_field_ids_delta.register_from_key(key);
+                        facet_field_ids_delta.register_removed_from_key(key);
                         docids_sender.delete(key)?;
                         Ok(())
                     }
@@ -151,10 +168,7 @@ pub fn merge_and_send_facet_docids(
             })?;
             Ok(facet_field_ids_delta)
         })
-        .reduce(
-            || Ok(FacetFieldIdsDelta::new(max_string_count, max_number_count)),
-            |lhs, rhs| Ok(lhs?.merge(rhs?)),
-        )
+        .reduce(|| Ok(new_delta()), |lhs, rhs| Ok(lhs?.merge(rhs?)))
 }
 
 pub struct FacetDatabases<'a> {
@@ -185,17 +199,23 @@ impl<'a> FacetDatabases<'a> {
 
 #[derive(Debug)]
 pub enum FacetFieldIdDelta {
-    Bulk,
+    /// Too many facet values were modified, the levels are rebuilt with the bulk method.
+    Bulk {
+        /// The number of modified facet values.
+        delta_size: usize,
+    },
     Incremental(Vec<FacetFieldIdChange>),
 }
 
 impl FacetFieldIdDelta {
     fn push(&mut self, facet_value: &[u8], max_count: usize) {
-        *self = match std::mem::replace(self, FacetFieldIdDelta::Bulk) {
-            FacetFieldIdDelta::Bulk => FacetFieldIdDelta::Bulk,
+        *self = match std::mem::replace(self, FacetFieldIdDelta::Bulk { delta_size: 0 }) {
+            FacetFieldIdDelta::Bulk { delta_size } => {
+                FacetFieldIdDelta::Bulk { delta_size: delta_size + 1 }
+            }
             FacetFieldIdDelta::Incremental(mut v) => {
                 if v.len() >= max_count {
-                    FacetFieldIdDelta::Bulk
+                    FacetFieldIdDelta::Bulk { delta_size: v.len() + 1 }
                 } else {
                     v.push(FacetFieldIdChange { facet_value: facet_value.into() });
                     FacetFieldIdDelta::Incremental(v)
@@ -208,14 +228,17 @@ impl FacetFieldIdDelta {
         let Some(rhs) = rhs else {
             return;
         };
-        *self = match (std::mem::replace(self, FacetFieldIdDelta::Bulk), rhs) {
-            (FacetFieldIdDelta::Bulk, _) | (_, FacetFieldIdDelta::Bulk) => FacetFieldIdDelta::Bulk,
+        *self = match (std::mem::replace(self, FacetFieldIdDelta::Bulk { delta_size: 0 }), rhs) {
+            (left, right @ FacetFieldIdDelta::Bulk { .. })
+            | (left @ FacetFieldIdDelta::Bulk { .. }, right) => {
+                FacetFieldIdDelta::Bulk { delta_size: left.delta_size() + right.delta_size() }
+            }
             (
                 FacetFieldIdDelta::Incremental(mut left),
                 FacetFieldIdDelta::Incremental(mut right),
             ) => {
                 if left.len() + right.len() >= max_count {
-                    FacetFieldIdDelta::Bulk
+                    FacetFieldIdDelta::Bulk { delta_size: left.len() + right.len() }
                 } else {
                     left.append(&mut right);
                     FacetFieldIdDelta::Incremental(left)
@@ -223,6 +246,23 @@ impl FacetFieldIdDelta {
             }
         };
     }
+
+    /// The number of modified facet values.
+    pub fn delta_size(&self) -> usize {
+        match self {
+            FacetFieldIdDelta::Bulk { delta_size } => *delta_size,
+            FacetFieldIdDelta::Incremental(changes) => changes.len(),
+        }
+    }
+
+    /// The method used to update the facet levels and the delta size that drove the decision.
+    pub fn decision(&self) -> FacetUpdateDecision {
+        let method = match self {
+            FacetFieldIdDelta::Bulk { .. } => FacetUpdateMethod::Bulk,
+            FacetFieldIdDelta::Incremental(_) => FacetUpdateMethod::Incremental,
+        };
+        FacetUpdateDecision { method, delta_size: self.delta_size() as u64 }
+    }
 }
 
 #[derive(Debug)]
@@ -232,6 +272,12 @@ pub struct FacetFieldIdsDelta {
     modified_facet_number_ids: HashMap<FieldId, FacetFieldIdDelta, rustc_hash::FxBuildHasher>,
     max_string_count: usize,
     max_number_count: usize,
+    /// The string facet values associated with or removed from all their documents,
+    /// only collected when the index tracks the recency of its facet values.
+    values_recency: Option<FacetValuesRecencyDelta>,
+    /// The fields whose levels are always updated incrementally, their changes are kept
+    /// whatever their number.
+    incremental_fields: BTreeSet<FieldId>,
 }
 
 impl FacetFieldIdsDelta {
@@ -241,21 +287,44 @@ impl FacetFieldIdsDelta {
             max_number_count,
             modified_facet_string_ids: Default::default(),
             modified_facet_number_ids: Default::default(),
+            values_recency: None,
+            incremental_fields: BTreeSet::new(),
+        }
+    }
+
+    pub fn with_values_recency(mut self, track: bool) -> Self {
+        self.values_recency = track.then(FacetValuesRecencyDelta::default);
+        self
+    }
+
+    pub fn with_incremental_fields(mut self, incremental_fields: BTreeSet<FieldId>) -> Self {
+        self.incremental_fields = incremental_fields;
+        self
+    }
+
+    /// The number of changes from which the levels of the field are rebuilt with the bulk method.
+    fn max_count(&self, field_id: FieldId, max_count: usize) -> usize {
+        if self.incremental_fields.contains(&field_id) {
+            usize::MAX
+        } else {
+            max_count
         }
     }
 
     fn register_facet_string_id(&mut self, field_id: FieldId, facet_value: &[u8]) {
+        let max_count = self.max_count(field_id, self.max_string_count);
         self.modified_facet_string_ids
             .entry(field_id)
             .or_insert(FacetFieldIdDelta::Incremental(Default::default()))
-            .push(facet_value, self.max_string_count);
+            .push(facet_value, max_count);
     }
 
     fn register_facet_number_id(&mut self, field_id: FieldId, facet_value: &[u8]) {
+        let max_count = self.max_count(field_id, self.max_number_count);
         self.modified_facet_number_ids
             .entry(field_id)
             .or_insert(FacetFieldIdDelta::Incremental(Default::default()))
-            .push(facet_value, self.max_number_count);
+            .push(facet_value, max_count);
     }
 
     fn register_from_key(&mut self, key: &[u8]) {
@@ -271,6 +340,24 @@ impl FacetFieldIdsDelta {
         }
     }
 
+    fn register_associated_from_key(&mut self, key: &[u8]) {
+        let (facet_kind, field_id, facet_value) = self.extract_key_data(key);
+        if let (Some(recency), FacetKind::String, Some(facet_value)) =
+            (&mut self.values_recency, facet_kind, facet_value)
+        {
+            recency.associate(field_id, facet_value);
+        }
+    }
+
+    fn register_removed_from_key(&mut self, key: &[u8]) {
+        let (facet_kind, field_id, facet_value) = self.extract_key_data(key);
+        if let (Some(recency), FacetKind::String, Some(facet_value)) =
+            (&mut self.values_recency, facet_kind, facet_value)
+        {
+            recency.remove(field_id, facet_value);
+        }
+    }
+
     fn extract_key_data<'key>(&self, key: &'key [u8]) -> (FacetKind, FieldId, Option<&'key [u8]>) {
         let facet_kind = FacetKind::from(key[0]);
         let field_id = FieldId::from_be_bytes([key[1], key[2]]);
@@ -284,6 +371,33 @@ impl FacetFieldIdsDelta {
         (facet_kind, field_id, facet_value)
     }
 
+    /// Whether string facet values of any field were modified, the facet search
+    /// databases only depend on them.
+    pub fn has_facet_string_changes(&self) -> bool {
+        !self.modified_facet_string_ids.is_empty()
+    }
+
+    /// The string facet values modified in each field, `None` when there were too many of them
+    /// to be listed, see [`FacetFieldIdDelta::Bulk`].
+    pub fn modified_facet_strings(&self) -> Vec<(FieldId, Option<Vec<String>>)> {
+        self.modified_facet_string_ids
+            .iter()
+            .map(|(&field_id, delta)| {
+                let values = match delta {
+                    FacetFieldIdDelta::Bulk { .. } => None,
+                    FacetFieldIdDelta::Incremental(changes) => Some(
+                        changes
+                            .iter()
+                            .filter_map(|change| std::str::from_utf8(&change.facet_value).ok())
+                            .map(str::to_string)
+                            .collect(),
+                    ),
+                };
+                (field_id, values)
+            })
+            .collect()
+    }
+
     pub fn consume_facet_string_delta(
         &mut self,
     ) -> impl Iterator<Item = (FieldId, FacetFieldIdDelta)> + '_ {
@@ -296,19 +410,27 @@ impl FacetFieldIdsDelta {
         self.modified_facet_number_ids.drain()
     }
 
+    pub fn consume_values_recency(&mut self) -> Option<FacetValuesRecencyDelta> {
+        self.values_recency.take()
+    }
+
     pub fn merge(mut self, rhs: Self) -> Self {
-        // rhs.max_xx_count is assumed to be equal to self.max_xx_count, and so gets unused
-        let Self { modified_facet_number_ids, modified_facet_string_ids, .. } = rhs;
-        modified_facet_number_ids.into_iter().for_each(|(fid, mut delta)| {
+        // rhs.max_xx_count and rhs.incremental_fields are assumed to be equal
+        // to the ones of self, and so get unused
+        let Self { modified_facet_number_ids, modified_facet_string_ids, values_recency, .. } = rhs;
+        if let (Some(lhs), Some(rhs)) = (&mut self.values_recency, values_recency) {
+            lhs.merge(rhs);
+        }
+        for (fid, mut delta) in modified_facet_number_ids {
             let old_delta = self.modified_facet_number_ids.remove(&fid);
-            delta.merge(old_delta, self.max_number_count);
+            delta.merge(old_delta, self.max_count(fid, self.max_number_count));
             self.modified_facet_number_ids.insert(fid, delta);
-        });
-        modified_facet_string_ids.into_iter().for_each(|(fid, mut delta)| {
+        }
+        for (fid, mut delta) in modified_facet_string_ids {
             let old_delta = self.modified_facet_string_ids.remove(&fid);
-            delta.merge(old_delta, self.max_string_count);
+            delta.merge(old_delta, self.max_count(fid, self.max_string_count));
             self.modified_facet_string_ids.insert(fid, delta);
-        });
+        }
         self
     }
 }
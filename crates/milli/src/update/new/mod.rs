@@ -1,5 +1,7 @@
 pub use document::DocumentIdentifiers;
 pub use document_change::{DocumentChange, Insertion, Update};
+pub(crate) use extract::truncate_str;
+pub use extract::DelAddRoaringBitmap;
 pub use indexer::ChannelCongestion;
 pub use merger::{
     merge_and_send_docids, merge_and_send_facet_docids, FacetDatabases, FacetFieldIdsDelta,
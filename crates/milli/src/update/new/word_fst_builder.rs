@@ -81,6 +81,7 @@ pub struct PrefixDelta {
 
 struct PrefixFstBuilder {
     prefix_count_threshold: usize,
+    min_prefix_length: usize,
     max_prefix_length: usize,
     /// TODO: Replace the full memory allocation
     prefix_fst_builders: Vec<SetBuilder<Vec<u8>>>,
@@ -88,14 +89,21 @@ struct PrefixFstBuilder {
     current_prefix_count: Vec<usize>,
     modified_prefixes: BTreeSet<Prefix>,
     current_prefix_is_modified: Vec<bool>,
+    /// The only prefixes to materialize, all of them when `None`.
+    hot_prefixes: Option<BTreeSet<Prefix>>,
 }
 
 impl PrefixFstBuilder {
     pub fn new(prefix_settings: PrefixSettings) -> Option<Self> {
-        let PrefixSettings { prefix_count_threshold, max_prefix_length, compute_prefixes } =
-            prefix_settings;
+        let PrefixSettings {
+            prefix_count_threshold,
+            min_prefix_length,
+            max_prefix_length,
+            compute_prefixes,
+            hot_prefixes,
+        } = prefix_settings;
 
-        if compute_prefixes != crate::index::PrefixSearch::IndexingTime {
+        if compute_prefixes == crate::index::PrefixSearch::Disabled {
             return None;
         }
 
@@ -106,17 +114,21 @@ impl PrefixFstBuilder {
 
         Some(Self {
             prefix_count_threshold,
+            min_prefix_length,
             max_prefix_length,
             prefix_fst_builders,
             current_prefix: vec![Prefix::new(); max_prefix_length],
             current_prefix_count: vec![0; max_prefix_length],
             modified_prefixes: BTreeSet::new(),
             current_prefix_is_modified: vec![false; max_prefix_length],
+            hot_prefixes,
         })
     }
 
     fn insert_word(&mut self, bytes: &[u8], deladd: DelAdd, is_modified: bool) -> Result<()> {
-        for n in 0..self.max_prefix_length {
+        // Prefixes shorter than the minimum length are never stored, the ones
+        // previously materialized are deleted as they are not part of the new FST.
+        for n in self.min_prefix_length.saturating_sub(1)..self.max_prefix_length {
             let current_prefix = &mut self.current_prefix[n];
             let current_prefix_count = &mut self.current_prefix_count[n];
             let builder = &mut self.prefix_fst_builders[n];
@@ -130,6 +142,7 @@ impl PrefixFstBuilder {
                 Some(prefix) => prefix,
                 None => continue,
             };
+            let is_hot = self.hot_prefixes.as_ref().is_none_or(|hot| hot.contains(prefix));
 
             // This is the first iteration of the loop,
             // or the current word doesn't starts with the current prefix.
@@ -144,7 +157,7 @@ impl PrefixFstBuilder {
             }
 
             if is_modified && !*current_prefix_is_modified {
-                if *current_prefix_count > self.prefix_count_threshold {
+                if is_hot && *current_prefix_count > self.prefix_count_threshold {
                     self.modified_prefixes.insert(current_prefix.clone());
                 }
 
@@ -152,7 +165,7 @@ impl PrefixFstBuilder {
             }
 
             // There is enough words corresponding to this prefix to add it to the cache.
-            if *current_prefix_count == self.prefix_count_threshold {
+            if is_hot && *current_prefix_count == self.prefix_count_threshold {
                 builder.insert(prefix)?;
 
                 if *current_prefix_is_modified {
@@ -188,12 +201,19 @@ impl PrefixFstBuilder {
             }
         }
 
+        // The prefixes that were not materialized before, e.g. after lowering the
+        // minimum prefix length, must be computed even if none of their words changed.
+        let mut modified_prefixes = self.modified_prefixes;
+        {
+            let mut added_prefixes_stream = new_prefix_fst.op().add(&old_prefix_fst).difference();
+            while let Some(prefix) = added_prefixes_stream.next() {
+                modified_prefixes.insert(Prefix::from(std::str::from_utf8(prefix)?));
+            }
+        }
+
         Ok(PrefixData {
             prefixes_fst_mmap: prefix_fst_mmap,
-            prefix_delta: PrefixDelta {
-                modified: self.modified_prefixes,
-                deleted: deleted_prefixes,
-            },
+            prefix_delta: PrefixDelta { modified: modified_prefixes, deleted: deleted_prefixes },
         })
     }
 }
@@ -1,17 +1,14 @@
-use std::cell::RefCell;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{BufReader, BufWriter, Read, Seek, Write};
 use std::iter;
 
 use hashbrown::HashMap;
 use heed::types::{Bytes, DecodeIgnore, Str};
-use heed::{BytesDecode, Database, Error, RoTxn, RwTxn};
+use heed::{BytesDecode, Database, Error, PutFlags, RoTxn, RwTxn};
 use rayon::iter::{IndexedParallelIterator as _, IntoParallelIterator, ParallelIterator as _};
 use roaring::MultiOps;
-use tempfile::spooled_tempfile;
-use thread_local::ThreadLocal;
+use tempfile::{spooled_tempfile, SpooledTempFile};
 
-use super::ref_cell_ext::RefCellExt as _;
 use crate::heed_codec::StrBEU16Codec;
 use crate::update::GrenadParameters;
 use crate::{CboRoaringBitmapCodec, Index, Prefix, Result};
@@ -69,8 +66,8 @@ impl<'i> WordPrefixDocids<'i> {
 
         let outputs = rtxns
             .into_par_iter()
-            .enumerate()
-            .map(|(thread_id, rtxn)| {
+            .zip(sorted_prefix_chunks(prefix_to_compute, thread_count))
+            .map(|(rtxn, prefixes)| {
                 // `indexes` represent offsets at which prefixes computations were stored in the `file`.
                 let mut indexes = Vec::new();
                 let mut file = BufWriter::new(spooled_tempfile(
@@ -78,12 +75,7 @@ impl<'i> WordPrefixDocids<'i> {
                 ));
 
                 let mut buffer = Vec::new();
-                for (prefix_index, prefix) in prefix_to_compute.iter().enumerate() {
-                    // Is prefix for another thread?
-                    if prefix_index % thread_count != thread_id {
-                        continue;
-                    }
-
+                for prefix in prefixes {
                     let output = self
                         .database
                         .prefix_iter(&rtxn, prefix.as_bytes())?
@@ -101,25 +93,7 @@ impl<'i> WordPrefixDocids<'i> {
             })
             .collect::<Result<Vec<_>>>()?;
 
-        // We iterate over all the collected and serialized bitmaps through
-        // the files and entries to eventually put them in the final database.
-        let mut buffer = Vec::new();
-        for (index, file) in outputs {
-            let mut file = file.into_inner().map_err(|e| e.into_error())?;
-            file.rewind()?;
-            let mut file = BufReader::new(file);
-            for PrefixEntry { prefix, serialized_length } in index {
-                buffer.resize(serialized_length, 0);
-                file.read_exact(&mut buffer)?;
-                self.prefix_database.remap_data_type::<Bytes>().put(
-                    wtxn,
-                    prefix.as_bytes(),
-                    &buffer,
-                )?;
-            }
-        }
-
-        Ok(())
+        write_prefix_entries(wtxn, &self.prefix_database, outputs)
     }
 
     #[tracing::instrument(level = "trace", skip_all, target = "indexing::prefix")]
@@ -134,52 +108,37 @@ impl<'i> WordPrefixDocids<'i> {
 
         // We access this HashMap in parallel to compute the *union* of all
         // of them and *serialize* them into files. There is one file by CPU.
-        let local_entries = ThreadLocal::with_capacity(rayon::current_num_threads());
-        prefixes.into_par_iter().map(AsRef::as_ref).try_for_each(|prefix| {
-            let refcell = local_entries.get_or(|| {
-                let file = BufWriter::new(spooled_tempfile(
+        let thread_count = rayon::current_num_threads();
+        let outputs = sorted_prefix_chunks(prefixes, thread_count)
+            .into_par_iter()
+            .map(|prefixes| {
+                let mut index = Vec::new();
+                let mut file = BufWriter::new(spooled_tempfile(
                     self.max_memory_by_thread.unwrap_or(usize::MAX),
                 ));
-                RefCell::new((Vec::new(), file, Vec::new()))
-            });
 
-            let mut refmut = refcell.borrow_mut_or_yield();
-            let (ref mut index, ref mut file, ref mut buffer) = *refmut;
+                let mut buffer = Vec::new();
+                for prefix in prefixes {
+                    let output = frozen
+                        .bitmaps(prefix)
+                        .unwrap()
+                        .iter()
+                        .map(|bytes| CboRoaringBitmapCodec::deserialize_from(bytes))
+                        .union()?;
 
-            let output = frozen
-                .bitmaps(prefix)
-                .unwrap()
-                .iter()
-                .map(|bytes| CboRoaringBitmapCodec::deserialize_from(bytes))
-                .union()?;
+                    buffer.clear();
+                    CboRoaringBitmapCodec::serialize_into_vec(&output, &mut buffer);
+                    index.push(PrefixEntry { prefix, serialized_length: buffer.len() });
+                    file.write_all(&buffer)?;
+                }
 
-            buffer.clear();
-            CboRoaringBitmapCodec::serialize_into_vec(&output, buffer);
-            index.push(PrefixEntry { prefix, serialized_length: buffer.len() });
-            file.write_all(buffer)
-        })?;
+                Ok((index, file))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         drop(frozen);
 
-        // We iterate over all the collected and serialized bitmaps through
-        // the files and entries to eventually put them in the final database.
-        for refcell in local_entries {
-            let (index, file, mut buffer) = refcell.into_inner();
-            let mut file = file.into_inner().map_err(|e| e.into_error())?;
-            file.rewind()?;
-            let mut file = BufReader::new(file);
-            for PrefixEntry { prefix, serialized_length } in index {
-                buffer.resize(serialized_length, 0);
-                file.read_exact(&mut buffer)?;
-                self.prefix_database.remap_data_type::<Bytes>().put(
-                    wtxn,
-                    prefix.as_bytes(),
-                    &buffer,
-                )?;
-            }
-        }
-
-        Ok(())
+        write_prefix_entries(wtxn, &self.prefix_database, outputs)
     }
 }
 
@@ -189,6 +148,25 @@ struct PrefixEntry<'a> {
     serialized_length: usize,
 }
 
+/// An entry of the prefix databases whose bitmap was serialized in a file.
+trait SerializedPrefixEntry {
+    /// Writes the key of the entry in the prefix database into the buffer.
+    fn write_key(&self, buffer: &mut Vec<u8>);
+
+    /// The length of the serialized bitmap, `None` when the entry must be deleted.
+    fn serialized_length(&self) -> Option<usize>;
+}
+
+impl SerializedPrefixEntry for PrefixEntry<'_> {
+    fn write_key(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self.prefix.as_bytes());
+    }
+
+    fn serialized_length(&self) -> Option<usize> {
+        Some(self.serialized_length)
+    }
+}
+
 /// Stores prefixes along with all the pointers to the associated
 /// CBoRoaringBitmaps.
 ///
@@ -287,8 +265,8 @@ impl<'i> WordPrefixIntegerDocids<'i> {
 
         let outputs = rtxns
             .into_par_iter()
-            .enumerate()
-            .map(|(thread_id, rtxn)| {
+            .zip(sorted_prefix_chunks(prefixes, thread_count))
+            .map(|(rtxn, prefixes)| {
                 // `indexes` represent offsets at which prefixes computations were stored in the `file`.
                 let mut indexes = Vec::new();
                 let mut file = BufWriter::new(spooled_tempfile(
@@ -296,13 +274,8 @@ impl<'i> WordPrefixIntegerDocids<'i> {
                 ));
 
                 let mut buffer = Vec::new();
-                for (prefix_index, prefix) in prefixes.iter().enumerate() {
-                    // Is prefix for another thread?
-                    if prefix_index % thread_count != thread_id {
-                        continue;
-                    }
-
-                    let mut bitmap_bytes_at_positions = HashMap::new();
+                for prefix in prefixes {
+                    let mut bitmap_bytes_at_positions = BTreeMap::new();
                     for result in self
                         .database
                         .prefix_iter(&rtxn, prefix.as_bytes())?
@@ -356,37 +329,7 @@ impl<'i> WordPrefixIntegerDocids<'i> {
             })
             .collect::<Result<Vec<_>>>()?;
 
-        // We iterate over all the collected and serialized bitmaps through
-        // the files and entries to eventually put them in the final database.
-        let mut key_buffer = Vec::new();
-        let mut buffer = Vec::new();
-        for (index, file) in outputs {
-            let mut file = file.into_inner().map_err(|e| e.into_error())?;
-            file.rewind()?;
-            let mut file = BufReader::new(file);
-            for PrefixIntegerEntry { prefix, pos, serialized_length } in index {
-                key_buffer.clear();
-                key_buffer.extend_from_slice(prefix.as_bytes());
-                key_buffer.push(0);
-                key_buffer.extend_from_slice(&pos.to_be_bytes());
-                match serialized_length {
-                    Some(serialized_length) => {
-                        buffer.resize(serialized_length, 0);
-                        file.read_exact(&mut buffer)?;
-                        self.prefix_database.remap_data_type::<Bytes>().put(
-                            wtxn,
-                            &key_buffer,
-                            &buffer,
-                        )?;
-                    }
-                    None => {
-                        self.prefix_database.delete(wtxn, &key_buffer)?;
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        write_prefix_entries(wtxn, &self.prefix_database, outputs)
     }
 
     #[tracing::instrument(level = "trace", skip_all, target = "indexing::prefix")]
@@ -401,73 +344,44 @@ impl<'i> WordPrefixIntegerDocids<'i> {
 
         // We access this HashMap in parallel to compute the *union* of all
         // of them and *serialize* them into files. There is one file by CPU.
-        let local_entries = ThreadLocal::with_capacity(rayon::current_num_threads());
-        prefixes.into_par_iter().map(AsRef::as_ref).try_for_each(|prefix| -> Result<()> {
-            let refcell = local_entries.get_or(|| {
-                let file = BufWriter::new(spooled_tempfile(
+        let thread_count = rayon::current_num_threads();
+        let outputs = sorted_prefix_chunks(prefixes, thread_count)
+            .into_par_iter()
+            .map(|prefixes| {
+                let mut index = Vec::new();
+                let mut file = BufWriter::new(spooled_tempfile(
                     self.max_memory_by_thread.unwrap_or(usize::MAX),
                 ));
-                RefCell::new((Vec::new(), file, Vec::new()))
-            });
-
-            let mut refmut = refcell.borrow_mut_or_yield();
-            let (ref mut index, ref mut file, ref mut buffer) = *refmut;
 
-            for (&pos, bitmaps_bytes) in frozen.bitmaps(prefix).unwrap() {
-                if bitmaps_bytes.is_empty() {
-                    index.push(PrefixIntegerEntry { prefix, pos, serialized_length: None });
-                } else {
-                    let output = bitmaps_bytes
-                        .iter()
-                        .map(|bytes| CboRoaringBitmapCodec::deserialize_from(bytes))
-                        .union()?;
-                    buffer.clear();
-                    CboRoaringBitmapCodec::serialize_into_vec(&output, buffer);
-                    index.push(PrefixIntegerEntry {
-                        prefix,
-                        pos,
-                        serialized_length: Some(buffer.len()),
-                    });
-                    file.write_all(buffer)?;
+                let mut buffer = Vec::new();
+                for prefix in prefixes {
+                    for (&pos, bitmaps_bytes) in frozen.bitmaps(prefix).unwrap() {
+                        if bitmaps_bytes.is_empty() {
+                            index.push(PrefixIntegerEntry { prefix, pos, serialized_length: None });
+                        } else {
+                            let output = bitmaps_bytes
+                                .iter()
+                                .map(|bytes| CboRoaringBitmapCodec::deserialize_from(bytes))
+                                .union()?;
+                            buffer.clear();
+                            CboRoaringBitmapCodec::serialize_into_vec(&output, &mut buffer);
+                            index.push(PrefixIntegerEntry {
+                                prefix,
+                                pos,
+                                serialized_length: Some(buffer.len()),
+                            });
+                            file.write_all(&buffer)?;
+                        }
+                    }
                 }
-            }
 
-            Result::Ok(())
-        })?;
+                Ok((index, file))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         drop(frozen);
 
-        // We iterate over all the collected and serialized bitmaps through
-        // the files and entries to eventually put them in the final database.
-        let mut key_buffer = Vec::new();
-        for refcell in local_entries {
-            let (index, file, mut buffer) = refcell.into_inner();
-            let mut file = file.into_inner().map_err(|e| e.into_error())?;
-            file.rewind()?;
-            let mut file = BufReader::new(file);
-            for PrefixIntegerEntry { prefix, pos, serialized_length } in index {
-                key_buffer.clear();
-                key_buffer.extend_from_slice(prefix.as_bytes());
-                key_buffer.push(0);
-                key_buffer.extend_from_slice(&pos.to_be_bytes());
-                match serialized_length {
-                    Some(serialized_length) => {
-                        buffer.resize(serialized_length, 0);
-                        file.read_exact(&mut buffer)?;
-                        self.prefix_database.remap_data_type::<Bytes>().put(
-                            wtxn,
-                            &key_buffer,
-                            &buffer,
-                        )?;
-                    }
-                    None => {
-                        self.prefix_database.delete(wtxn, &key_buffer)?;
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        write_prefix_entries(wtxn, &self.prefix_database, outputs)
     }
 }
 
@@ -478,9 +392,21 @@ struct PrefixIntegerEntry<'a> {
     serialized_length: Option<usize>,
 }
 
+impl SerializedPrefixEntry for PrefixIntegerEntry<'_> {
+    fn write_key(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self.prefix.as_bytes());
+        buffer.push(0);
+        buffer.extend_from_slice(&self.pos.to_be_bytes());
+    }
+
+    fn serialized_length(&self) -> Option<usize> {
+        self.serialized_length
+    }
+}
+
 /// TODO doc
 struct FrozenPrefixIntegerBitmaps<'a, 'rtxn> {
-    prefixes_bitmaps: HashMap<&'a str, HashMap<u16, Vec<&'rtxn [u8]>>>,
+    prefixes_bitmaps: HashMap<&'a str, BTreeMap<u16, Vec<&'rtxn [u8]>>>,
 }
 
 impl<'a, 'rtxn> FrozenPrefixIntegerBitmaps<'a, 'rtxn> {
@@ -494,7 +420,7 @@ impl<'a, 'rtxn> FrozenPrefixIntegerBitmaps<'a, 'rtxn> {
 
         let mut prefixes_bitmaps = HashMap::new();
         for prefix in prefixes {
-            let mut positions = HashMap::new();
+            let mut positions = BTreeMap::new();
             for result in database.prefix_iter(rtxn, prefix.as_bytes())? {
                 let (key, bytes) = result?;
                 let (_word, pos) = StrBEU16Codec::bytes_decode(key).map_err(Error::Decoding)?;
@@ -506,13 +432,92 @@ impl<'a, 'rtxn> FrozenPrefixIntegerBitmaps<'a, 'rtxn> {
         Ok(Self { prefixes_bitmaps })
     }
 
-    pub fn bitmaps(&self, key: &'a str) -> Option<&HashMap<u16, Vec<&'rtxn [u8]>>> {
+    pub fn bitmaps(&self, key: &'a str) -> Option<&BTreeMap<u16, Vec<&'rtxn [u8]>>> {
         self.prefixes_bitmaps.get(&key)
     }
 }
 
 unsafe impl Sync for FrozenPrefixIntegerBitmaps<'_, '_> {}
 
+/// Splits the prefixes into at most `count` contiguous chunks of sorted prefixes.
+///
+/// Processing the chunks in parallel and writing their outputs one after the other
+/// ensures that the prefixes are written in a deterministic and sorted order.
+fn sorted_prefix_chunks(prefixes: &BTreeSet<Prefix>, count: usize) -> Vec<Vec<&str>> {
+    let prefixes: Vec<_> = prefixes.iter().map(|prefix| prefix.as_str()).collect();
+    let chunk_size = prefixes.len().div_ceil(count.max(1)).max(1);
+    prefixes.chunks(chunk_size).map(|chunk| chunk.to_vec()).collect()
+}
+
+/// Writes the collected and serialized bitmaps of the files in the prefix database.
+///
+/// The entries are sorted by key. When they all come after the last key of the database,
+/// e.g. when the prefixes are computed for the first time, they are appended with a cursor
+/// rather than inserted one by one, sparing LMDB from looking each of them up.
+#[tracing::instrument(level = "trace", skip_all, target = "indexing::prefix")]
+fn write_prefix_entries<E: SerializedPrefixEntry>(
+    wtxn: &mut RwTxn,
+    prefix_database: &Database<Bytes, CboRoaringBitmapCodec>,
+    outputs: Vec<(Vec<E>, BufWriter<SpooledTempFile>)>,
+) -> Result<()> {
+    let database = prefix_database.remap_data_type::<Bytes>();
+
+    let Some(first_entry) = outputs.iter().find_map(|(entries, _)| entries.first()) else {
+        return Ok(());
+    };
+    let mut first_key = Vec::new();
+    first_entry.write_key(&mut first_key);
+    let append = match database.remap_data_type::<DecodeIgnore>().last(wtxn)? {
+        Some((last_key, ())) => last_key < first_key.as_slice(),
+        None => true,
+    };
+
+    if append {
+        let mut cursor = database.iter_mut(wtxn)?;
+        for_each_serialized_entry(outputs, |key, bitmap| match bitmap {
+            // safety: we do not keep a reference on database entries.
+            Some(bitmap) => unsafe {
+                cursor.put_current_with_options::<Bytes>(PutFlags::APPEND, key, bitmap)
+            },
+            // the appended keys are not in the database, there is nothing to delete
+            None => Ok(()),
+        })
+    } else {
+        for_each_serialized_entry(outputs, |key, bitmap| match bitmap {
+            Some(bitmap) => database.put(wtxn, key, bitmap),
+            None => database.delete(wtxn, key).map(drop),
+        })
+    }
+}
+
+/// Reads the serialized bitmaps back from the files, in the order of the entries.
+fn for_each_serialized_entry<E: SerializedPrefixEntry>(
+    outputs: Vec<(Vec<E>, BufWriter<SpooledTempFile>)>,
+    mut f: impl FnMut(&[u8], Option<&[u8]>) -> heed::Result<()>,
+) -> Result<()> {
+    let mut key_buffer = Vec::new();
+    let mut buffer = Vec::new();
+    for (entries, file) in outputs {
+        let mut file = file.into_inner().map_err(|e| e.into_error())?;
+        file.rewind()?;
+        let mut file = BufReader::new(file);
+        for entry in entries {
+            key_buffer.clear();
+            entry.write_key(&mut key_buffer);
+            match entry.serialized_length() {
+                Some(serialized_length) => {
+                    buffer.resize(serialized_length, 0);
+                    file.read_exact(&mut buffer)?;
+                    f(&key_buffer, Some(&buffer))?;
+                }
+                None => f(&key_buffer, None)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::prefix")]
 fn delete_prefixes(
     wtxn: &mut RwTxn,
@@ -598,3 +603,193 @@ pub fn compute_word_prefix_position_docids(
     )
     .execute(wtxn, prefix_to_compute, prefix_to_delete)
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+    use std::io::{BufWriter, Write};
+
+    use heed::types::Bytes;
+    use heed::Database;
+    use rand::{Rng, SeedableRng};
+    use roaring::RoaringBitmap;
+    use tempfile::{spooled_tempfile, SpooledTempFile};
+
+    use super::{
+        sorted_prefix_chunks, write_prefix_entries, PrefixEntry, PrefixIntegerEntry,
+        SerializedPrefixEntry,
+    };
+    use crate::index::tests::{ordered_string, TempIndex};
+    use crate::{CboRoaringBitmapCodec, Prefix};
+
+    /// Serializes the bitmaps of the entries like the prefix computation does, one file per chunk.
+    fn serialize_chunks<E: SerializedPrefixEntry>(
+        chunks: Vec<Vec<(E, RoaringBitmap)>>,
+    ) -> Vec<(Vec<E>, BufWriter<SpooledTempFile>)> {
+        chunks
+            .into_iter()
+            .map(|chunk| {
+                let mut entries = Vec::new();
+                let mut file = BufWriter::new(spooled_tempfile(usize::MAX));
+                for (entry, bitmap) in chunk {
+                    if entry.serialized_length().is_some() {
+                        let mut buffer = Vec::new();
+                        CboRoaringBitmapCodec::serialize_into_vec(&bitmap, &mut buffer);
+                        assert_eq!(entry.serialized_length(), Some(buffer.len()));
+                        file.write_all(&buffer).unwrap();
+                    }
+                    entries.push(entry);
+                }
+                (entries, file)
+            })
+            .collect()
+    }
+
+    fn serialized_length(bitmap: &RoaringBitmap) -> usize {
+        let mut buffer = Vec::new();
+        CboRoaringBitmapCodec::serialize_into_vec(bitmap, &mut buffer);
+        buffer.len()
+    }
+
+    fn database_content(
+        rtxn: &heed::RoTxn,
+        database: Database<Bytes, CboRoaringBitmapCodec>,
+    ) -> Vec<(Vec<u8>, RoaringBitmap)> {
+        database
+            .iter(rtxn)
+            .unwrap()
+            .map(|result| result.map(|(key, bitmap)| (key.to_vec(), bitmap)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn appended_and_inserted_prefixes_are_identical() {
+        let index = TempIndex::new();
+        let database: Database<Bytes, CboRoaringBitmapCodec> =
+            index.word_prefix_docids.remap_key_type();
+        let prefixes = ["a", "ab", "abc", "b", "he", "hel", "w", "wor", "z"];
+        let chunks = || {
+            prefixes
+                .chunks(4)
+                .map(|chunk| {
+                    chunk
+                        .iter()
+                        .map(|&prefix| {
+                            let bitmap: RoaringBitmap = prefix.bytes().map(u32::from).collect();
+                            let serialized_length = serialized_length(&bitmap);
+                            (PrefixEntry { prefix, serialized_length }, bitmap)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // the database is empty, the entries are appended
+        let mut wtxn = index.write_txn().unwrap();
+        write_prefix_entries(&mut wtxn, &database, serialize_chunks(chunks())).unwrap();
+        let appended = database_content(&wtxn, database);
+        assert_eq!(appended.len(), prefixes.len());
+
+        // a key after the prefixes forces the entries to be inserted one by one
+        database.clear(&mut wtxn).unwrap();
+        database.put(&mut wtxn, b"zzz", &RoaringBitmap::from_iter([42])).unwrap();
+        write_prefix_entries(&mut wtxn, &database, serialize_chunks(chunks())).unwrap();
+        database.delete(&mut wtxn, b"zzz").unwrap();
+        let inserted = database_content(&wtxn, database);
+
+        assert_eq!(appended, inserted);
+    }
+
+    #[test]
+    fn appended_and_inserted_prefix_positions_are_identical() {
+        let index = TempIndex::new();
+        let database: Database<Bytes, CboRoaringBitmapCodec> =
+            index.word_prefix_position_docids.remap_key_type();
+        let entries = |deleted: bool| {
+            let mut keys = Vec::new();
+            for prefix in ["a", "ab", "b"] {
+                for pos in [0u16, 1, 300] {
+                    keys.push((prefix, pos, false));
+                }
+            }
+            if deleted {
+                keys.insert(keys.len() - 1, ("b", 2, true));
+            }
+            keys.chunks(2)
+                .map(|chunk| {
+                    chunk
+                        .iter()
+                        .map(|&(prefix, pos, deleted)| {
+                            let bitmap: RoaringBitmap = (0..pos as u32 + 10).collect();
+                            let serialized_length = (!deleted).then(|| serialized_length(&bitmap));
+                            (PrefixIntegerEntry { prefix, pos, serialized_length }, bitmap)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut wtxn = index.write_txn().unwrap();
+        write_prefix_entries(&mut wtxn, &database, serialize_chunks(entries(false))).unwrap();
+        let appended = database_content(&wtxn, database);
+        assert_eq!(appended.len(), 9);
+
+        // a position that no longer exists is deleted among the inserted entries
+        database.clear(&mut wtxn).unwrap();
+        let mut deleted_key = Vec::new();
+        PrefixIntegerEntry { prefix: "b", pos: 2, serialized_length: None }
+            .write_key(&mut deleted_key);
+        database.put(&mut wtxn, &deleted_key, &RoaringBitmap::from_iter([42])).unwrap();
+        write_prefix_entries(&mut wtxn, &database, serialize_chunks(entries(true))).unwrap();
+        let inserted = database_content(&wtxn, database);
+
+        assert_eq!(appended, inserted);
+    }
+
+    #[test]
+    fn prefixes_are_written_in_sorted_order() {
+        let prefixes: BTreeSet<Prefix> =
+            ["wor", "a", "ab", "zeb", "b", "hel", "he", "ca", "w", "abc", "z"]
+                .into_iter()
+                .map(Prefix::from)
+                .collect();
+
+        for thread_count in [1, 2, 3, 4, 8, 32] {
+            let chunks = sorted_prefix_chunks(&prefixes, thread_count);
+            assert!(chunks.len() <= thread_count);
+            assert!(chunks.iter().all(|chunk| !chunk.is_empty()));
+
+            // the chunk outputs are written one after the other
+            let write_order: Vec<_> = chunks.into_iter().flatten().collect();
+            let mut sorted = write_order.clone();
+            sorted.sort_unstable();
+            assert_eq!(write_order, sorted);
+            assert_eq!(write_order.len(), prefixes.len());
+        }
+
+        assert!(sorted_prefix_chunks(&BTreeSet::new(), 4).is_empty());
+    }
+
+    #[test]
+    fn random_prefixes_are_written_in_sorted_order() {
+        let mut rng = rand::rngs::SmallRng::from_seed([0; 32]);
+        for _ in 0..100 {
+            // the positions of the generated strings are their lexicographic order
+            let mut positions: Vec<usize> =
+                (0..rng.gen_range(0..500)).map(|_| rng.gen_range(1..26usize.pow(5))).collect();
+            positions.sort_unstable();
+            positions.dedup();
+            let prefixes: BTreeSet<Prefix> =
+                positions.iter().map(|&i| Prefix::from(ordered_string(i).as_str())).collect();
+
+            let thread_count = rng.gen_range(1..=64);
+            let chunks = sorted_prefix_chunks(&prefixes, thread_count);
+            assert!(chunks.len() <= thread_count);
+            assert!(chunks.iter().all(|chunk| !chunk.is_empty()));
+
+            let write_order: Vec<_> = chunks.into_iter().flatten().map(String::from).collect();
+            let expected: Vec<_> = positions.into_iter().map(ordered_string).collect();
+            assert_eq!(write_order, expected);
+        }
+    }
+}
@@ -19,16 +19,21 @@ use crate::constants::{RESERVED_GEOJSON_FIELD_NAME, RESERVED_GEO_FIELD_NAME};
 use crate::criterion::Criterion;
 use crate::disabled_typos_terms::DisabledTyposTerms;
 use crate::error::UserError::{self, InvalidChatSettingsDocumentTemplateMaxBytes};
+use crate::facet::values_weight::rebuild_facet_values_weights;
 use crate::fields_ids_map::metadata::{FieldIdMapWithMetadata, MetadataBuilder};
 use crate::filterable_attributes_rules::match_faceted_field;
 use crate::index::{
-    ChatConfig, PrefixSearch, SearchParameters, DEFAULT_MIN_WORD_LEN_ONE_TYPO,
+    ChatConfig, FacetUpdateMethodSetting, FacetingMode, FacetingWarmup, PrefixSearch,
+    PrefixSearchWarmup, SearchParameters, DEFAULT_MIN_WORD_LEN_ONE_TYPO,
     DEFAULT_MIN_WORD_LEN_TWO_TYPOS,
 };
 use crate::order_by_map::OrderByMap;
 use crate::progress::{EmbedderStats, Progress, VariableNameStep};
 use crate::prompt::{default_max_bytes, default_template_text, PromptData};
 use crate::proximity::ProximityPrecision;
+use crate::update::facet::{
+    build_deferred_facet_structures, clear_deferred_facet_structures, rebuild_facet_levels,
+};
 use crate::update::index_documents::IndexDocumentsMethod;
 use crate::update::new::indexer::reindex;
 use crate::update::new::steps::SettingsIndexerStep;
@@ -45,7 +50,8 @@ use crate::vector::{
     VectorStoreBackend,
 };
 use crate::{
-    ChannelCongestion, FieldId, FilterableAttributesRule, Index, LocalizedAttributesRule, Result,
+    ChannelCongestion, FieldId, FilterableAttributesRule, Index, LocalizedAttributesRule,
+    PinnedFacetDistribution, Result,
 };
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Copy)]
@@ -187,6 +193,14 @@ pub struct Settings<'a, 't, 'i> {
     /// Attributes on which typo tolerance is disabled.
     exact_attributes: Setting<HashSet<String>>,
     max_values_per_facet: Setting<usize>,
+    max_values_per_document_field: Setting<usize>,
+    track_facet_values_recency: Setting<bool>,
+    max_facet_levels: Setting<u8>,
+    facet_rebalancing_threshold: Setting<u64>,
+    facet_expected_cardinality: Setting<BTreeMap<String, u64>>,
+    facet_update_methods: Setting<BTreeMap<String, FacetUpdateMethodSetting>>,
+    facet_value_weight_fields: Setting<BTreeMap<String, String>>,
+    pinned_facet_distributions: Setting<Vec<PinnedFacetDistribution>>,
     sort_facet_values_by: Setting<OrderByMap>,
     pagination_max_total_hits: Setting<usize>,
     proximity_precision: Setting<ProximityPrecision>,
@@ -194,7 +208,11 @@ pub struct Settings<'a, 't, 'i> {
     search_cutoff: Setting<u64>,
     localized_attributes_rules: Setting<Vec<LocalizedAttributesRule>>,
     prefix_search: Setting<PrefixSearch>,
+    min_prefix_length: Setting<u8>,
+    prefix_search_warmup: Setting<PrefixSearchWarmup>,
     facet_search: Setting<bool>,
+    faceting_mode: Setting<FacetingMode>,
+    faceting_warmup: Setting<FacetingWarmup>,
     chat: Setting<ChatSettings>,
     vector_store: Setting<VectorStoreBackend>,
 }
@@ -227,6 +245,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             min_word_len_one_typo: Setting::NotSet,
             exact_attributes: Setting::NotSet,
             max_values_per_facet: Setting::NotSet,
+            max_values_per_document_field: Setting::NotSet,
+            track_facet_values_recency: Setting::NotSet,
+            max_facet_levels: Setting::NotSet,
+            facet_rebalancing_threshold: Setting::NotSet,
+            facet_expected_cardinality: Setting::NotSet,
+            facet_update_methods: Setting::NotSet,
+            facet_value_weight_fields: Setting::NotSet,
+            pinned_facet_distributions: Setting::NotSet,
             sort_facet_values_by: Setting::NotSet,
             pagination_max_total_hits: Setting::NotSet,
             proximity_precision: Setting::NotSet,
@@ -234,7 +260,11 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             search_cutoff: Setting::NotSet,
             localized_attributes_rules: Setting::NotSet,
             prefix_search: Setting::NotSet,
+            min_prefix_length: Setting::NotSet,
+            prefix_search_warmup: Setting::NotSet,
             facet_search: Setting::NotSet,
+            faceting_mode: Setting::NotSet,
+            faceting_warmup: Setting::NotSet,
             chat: Setting::NotSet,
             vector_store: Setting::NotSet,
             indexer_config,
@@ -403,6 +433,70 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.max_values_per_facet = Setting::Reset;
     }
 
+    pub fn set_max_values_per_document_field(&mut self, value: usize) {
+        self.max_values_per_document_field = Setting::Set(value);
+    }
+
+    pub fn reset_max_values_per_document_field(&mut self) {
+        self.max_values_per_document_field = Setting::Reset;
+    }
+
+    pub fn set_track_facet_values_recency(&mut self, value: bool) {
+        self.track_facet_values_recency = Setting::Set(value);
+    }
+
+    pub fn reset_track_facet_values_recency(&mut self) {
+        self.track_facet_values_recency = Setting::Reset;
+    }
+
+    pub fn set_max_facet_levels(&mut self, value: u8) {
+        self.max_facet_levels = Setting::Set(value);
+    }
+
+    pub fn reset_max_facet_levels(&mut self) {
+        self.max_facet_levels = Setting::Reset;
+    }
+
+    pub fn set_facet_rebalancing_threshold(&mut self, value: u64) {
+        self.facet_rebalancing_threshold = Setting::Set(value);
+    }
+
+    pub fn reset_facet_rebalancing_threshold(&mut self) {
+        self.facet_rebalancing_threshold = Setting::Reset;
+    }
+
+    pub fn set_facet_expected_cardinality(&mut self, value: BTreeMap<String, u64>) {
+        self.facet_expected_cardinality = Setting::Set(value);
+    }
+
+    pub fn reset_facet_expected_cardinality(&mut self) {
+        self.facet_expected_cardinality = Setting::Reset;
+    }
+
+    pub fn set_facet_update_methods(&mut self, value: BTreeMap<String, FacetUpdateMethodSetting>) {
+        self.facet_update_methods = Setting::Set(value);
+    }
+
+    pub fn reset_facet_update_methods(&mut self) {
+        self.facet_update_methods = Setting::Reset;
+    }
+
+    pub fn set_facet_value_weight_fields(&mut self, value: BTreeMap<String, String>) {
+        self.facet_value_weight_fields = Setting::Set(value);
+    }
+
+    pub fn reset_facet_value_weight_fields(&mut self) {
+        self.facet_value_weight_fields = Setting::Reset;
+    }
+
+    pub fn set_pinned_facet_distributions(&mut self, value: Vec<PinnedFacetDistribution>) {
+        self.pinned_facet_distributions = Setting::Set(value);
+    }
+
+    pub fn reset_pinned_facet_distributions(&mut self) {
+        self.pinned_facet_distributions = Setting::Reset;
+    }
+
     pub fn set_sort_facet_values_by(&mut self, value: OrderByMap) {
         self.sort_facet_values_by = Setting::Set(value);
     }
@@ -459,6 +553,26 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.prefix_search = Setting::Reset;
     }
 
+    /// Prefixes shorter than this number of bytes are not stored in the prefix databases.
+    ///
+    /// Default value is `1`, a value of `0` is considered as `1`.
+    pub fn set_min_prefix_length(&mut self, value: u8) {
+        self.min_prefix_length = Setting::Set(value);
+    }
+
+    pub fn reset_min_prefix_length(&mut self) {
+        self.min_prefix_length = Setting::Reset;
+    }
+
+    /// The word prefixes read when the index is opened to speed up the first prefix searches.
+    pub fn set_prefix_search_warmup(&mut self, value: PrefixSearchWarmup) {
+        self.prefix_search_warmup = Setting::Set(value);
+    }
+
+    pub fn reset_prefix_search_warmup(&mut self) {
+        self.prefix_search_warmup = Setting::Reset;
+    }
+
     pub fn set_facet_search(&mut self, value: bool) {
         self.facet_search = Setting::Set(value);
     }
@@ -467,6 +581,22 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.facet_search = Setting::Reset;
     }
 
+    pub fn set_faceting_mode(&mut self, value: FacetingMode) {
+        self.faceting_mode = Setting::Set(value);
+    }
+
+    pub fn reset_faceting_mode(&mut self) {
+        self.faceting_mode = Setting::Reset;
+    }
+
+    pub fn set_faceting_warmup(&mut self, value: FacetingWarmup) {
+        self.faceting_warmup = Setting::Set(value);
+    }
+
+    pub fn reset_faceting_warmup(&mut self) {
+        self.faceting_warmup = Setting::Reset;
+    }
+
     pub fn set_chat(&mut self, value: ChatSettings) {
         self.chat = Setting::Set(value);
     }
@@ -972,6 +1102,142 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(())
     }
 
+    fn update_max_values_per_document_field(&mut self) -> Result<bool> {
+        let changed = match self.max_values_per_document_field {
+            Setting::Set(new) => {
+                let old = self.index.max_values_per_document_field(self.wtxn)?;
+                if old == Some(new as u64) {
+                    false
+                } else {
+                    self.index.put_max_values_per_document_field(self.wtxn, new as u64)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_max_values_per_document_field(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    /// Only the values associated with documents from now on are recorded, the recorded
+    /// times are removed when the tracking is disabled to free their storage.
+    fn update_track_facet_values_recency(&mut self) -> Result<()> {
+        let track = match self.track_facet_values_recency {
+            Setting::Set(track) => {
+                self.index.put_track_facet_values_recency(self.wtxn, track)?;
+                track
+            }
+            Setting::Reset => {
+                self.index.delete_track_facet_values_recency(self.wtxn)?;
+                false
+            }
+            Setting::NotSet => return Ok(()),
+        };
+
+        if !track {
+            self.index.facet_id_string_recency.clear(self.wtxn)?;
+        }
+
+        Ok(())
+    }
+
+    fn update_max_facet_levels(&mut self) -> Result<bool> {
+        let changed = match self.max_facet_levels {
+            Setting::Set(new) => {
+                let old = self.index.max_facet_levels(self.wtxn)?;
+                if old == Some(new) {
+                    false
+                } else {
+                    self.index.put_max_facet_levels(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_max_facet_levels(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_facet_rebalancing_threshold(&mut self) -> Result<()> {
+        match self.facet_rebalancing_threshold {
+            Setting::Set(threshold) => {
+                self.index.put_facet_rebalancing_threshold(self.wtxn, threshold)?;
+            }
+            Setting::Reset => {
+                self.index.delete_facet_rebalancing_threshold(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+
+        Ok(())
+    }
+
+    fn update_facet_expected_cardinality(&mut self) -> Result<bool> {
+        let changed = match self.facet_expected_cardinality.as_ref() {
+            Setting::Set(new) => {
+                let old = self.index.facet_expected_cardinality(self.wtxn)?;
+                if &old == new {
+                    false
+                } else {
+                    self.index.put_facet_expected_cardinality(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_facet_expected_cardinality(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_facet_update_methods(&mut self) -> Result<()> {
+        match self.facet_update_methods.as_ref() {
+            Setting::Set(value) => {
+                self.index.put_facet_update_methods(self.wtxn, value)?;
+            }
+            Setting::Reset => {
+                self.index.delete_facet_update_methods(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+
+        Ok(())
+    }
+
+    fn update_facet_value_weight_fields(&mut self) -> Result<bool> {
+        let changed = match self.facet_value_weight_fields.as_ref() {
+            Setting::Set(new) => {
+                let old = self.index.facet_value_weight_fields(self.wtxn)?;
+                if &old == new {
+                    false
+                } else {
+                    self.index.put_facet_value_weight_fields(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_facet_value_weight_fields(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_pinned_facet_distributions(&mut self) -> Result<()> {
+        match self.pinned_facet_distributions.as_ref() {
+            Setting::Set(value) => {
+                self.index.put_pinned_facet_distributions(self.wtxn, value)?;
+            }
+            Setting::Reset => {
+                self.index.delete_pinned_facet_distributions(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+
+        Ok(())
+    }
+
     fn update_sort_facet_values_by(&mut self) -> Result<()> {
         match self.sort_facet_values_by.as_ref() {
             Setting::Set(value) => {
@@ -1036,6 +1302,42 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(changed)
     }
 
+    fn update_min_prefix_length(&mut self) -> Result<bool> {
+        let changed = match self.min_prefix_length {
+            Setting::Set(new) => {
+                let old = self.index.min_prefix_length(self.wtxn)?;
+                if old == new {
+                    false
+                } else {
+                    self.index.put_min_prefix_length(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_min_prefix_length(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_prefix_search_warmup(&mut self) -> Result<bool> {
+        let changed = match &self.prefix_search_warmup {
+            Setting::Set(new) => {
+                let old = self.index.prefix_search_warmup(self.wtxn)?;
+                if old.as_ref() == Some(new) {
+                    false
+                } else {
+                    self.index.put_prefix_search_warmup(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_prefix_search_warmup(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
     fn update_facet_search(&mut self) -> Result<bool> {
         let changed = match self.facet_search {
             Setting::Set(new) => {
@@ -1054,6 +1356,42 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(changed)
     }
 
+    /// Returns the new faceting mode if it changed.
+    fn update_faceting_mode(&mut self) -> Result<Option<FacetingMode>> {
+        let old = self.index.faceting_mode(self.wtxn)?;
+        let new = match self.faceting_mode {
+            Setting::Set(new) => {
+                self.index.put_faceting_mode(self.wtxn, new)?;
+                new
+            }
+            Setting::Reset => {
+                self.index.delete_faceting_mode(self.wtxn)?;
+                FacetingMode::default()
+            }
+            Setting::NotSet => return Ok(None),
+        };
+
+        Ok((old != new).then_some(new))
+    }
+
+    fn update_faceting_warmup(&mut self) -> Result<bool> {
+        let changed = match self.faceting_warmup {
+            Setting::Set(new) => {
+                let old = self.index.faceting_warmup(self.wtxn)?;
+                if old == Some(new) {
+                    false
+                } else {
+                    self.index.put_faceting_warmup(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_faceting_warmup(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
     fn update_embedding_configs(&mut self) -> Result<BTreeMap<String, EmbedderAction>> {
         match std::mem::take(&mut self.embedder_settings) {
             Setting::Set(configs) => self.update_embedding_configs_set(configs),
@@ -1448,6 +1786,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.update_exact_words()?;
         self.update_max_values_per_facet()?;
         self.update_sort_facet_values_by()?;
+        self.update_pinned_facet_distributions()?;
         self.update_pagination_max_total_hits()?;
         self.update_search_cutoff()?;
 
@@ -1463,7 +1802,18 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.update_exact_attributes()?;
         self.update_proximity_precision()?;
         self.update_prefix_search()?;
+        self.update_min_prefix_length()?;
+        self.update_prefix_search_warmup()?;
         self.update_facet_search()?;
+        self.update_max_values_per_document_field()?;
+        self.update_track_facet_values_recency()?;
+        let max_facet_levels_changed = self.update_max_facet_levels()?;
+        self.update_facet_rebalancing_threshold()?;
+        let facet_expected_cardinality_changed = self.update_facet_expected_cardinality()?;
+        self.update_facet_update_methods()?;
+        let facet_value_weight_fields_changed = self.update_facet_value_weight_fields()?;
+        let faceting_mode_change = self.update_faceting_mode()?;
+        self.update_faceting_warmup()?;
         self.update_localized_attributes_rules()?;
         self.update_disabled_typos_terms()?;
         self.update_chat_config()?;
@@ -1490,6 +1840,23 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             self.reindex(&progress_callback, &should_abort, inner_settings_diff, &embedder_stats)?;
         }
 
+        match faceting_mode_change {
+            Some(FacetingMode::Eager) => build_deferred_facet_structures(self.index, self.wtxn)?,
+            Some(FacetingMode::Lazy) => clear_deferred_facet_structures(self.index, self.wtxn)?,
+            // the existing trees may be deeper than the new cap allows or
+            // built from group sizes that no longer match the expected cardinalities
+            None if (max_facet_levels_changed || facet_expected_cardinality_changed)
+                && self.index.faceting_mode(self.wtxn)? == FacetingMode::Eager =>
+            {
+                rebuild_facet_levels(self.index, self.wtxn)?
+            }
+            None => (),
+        }
+
+        if facet_value_weight_fields_changed {
+            rebuild_facet_values_weights(self.index, self.wtxn)?;
+        }
+
         Ok(())
     }
 
@@ -1602,6 +1969,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             exact_words: Setting::NotSet, // TODO (require force reindexing of searchables)
             exact_attributes: _,
             max_values_per_facet: Setting::NotSet,
+            max_values_per_document_field: Setting::NotSet,
+            track_facet_values_recency: Setting::NotSet,
+            max_facet_levels: Setting::NotSet,
+            facet_rebalancing_threshold: Setting::NotSet,
+            facet_expected_cardinality: Setting::NotSet,
+            facet_update_methods: Setting::NotSet,
+            facet_value_weight_fields: Setting::NotSet,
+            pinned_facet_distributions: Setting::NotSet,
             sort_facet_values_by: Setting::NotSet,
             pagination_max_total_hits: Setting::NotSet,
             proximity_precision: _,
@@ -1609,7 +1984,11 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             search_cutoff: Setting::NotSet,
             localized_attributes_rules: Setting::NotSet, // TODO to start with
             prefix_search: Setting::NotSet,              // TODO continue with this
+            min_prefix_length: Setting::NotSet,
+            prefix_search_warmup: Setting::NotSet,
             facet_search: Setting::NotSet,
+            faceting_mode: Setting::NotSet,
+            faceting_warmup: Setting::NotSet,
             disable_on_numbers: Setting::NotSet, // TODO (require force reindexing of searchables)
             chat: Setting::NotSet,
             vector_store: Setting::NotSet,
@@ -1728,6 +2107,7 @@ impl InnerIndexSettingsDiff {
                 || old_settings.dictionary != new_settings.dictionary
                 || old_settings.proximity_precision != new_settings.proximity_precision
                 || old_settings.prefix_search != new_settings.prefix_search
+                || old_settings.min_prefix_length != new_settings.min_prefix_length
                 || old_settings.localized_attributes_rules
                     != new_settings.localized_attributes_rules
                 || old_settings.disabled_typos_terms != new_settings.disabled_typos_terms
@@ -1945,6 +2325,15 @@ impl InnerIndexSettingsDiff {
                         return true;
                     }
 
+                    // Check if the facet search normalization of the field changed.
+                    // If there is a difference, we need to reindex facet-search database.
+                    if is_new_facet_searchable
+                        && old_filterable_features.is_lossy_normalization()
+                            != new_filterable_features.is_lossy_normalization()
+                    {
+                        return true;
+                    }
+
                     // Check if the field needs a facet level database in the old and new settings.
                     // If there is a difference, we need to reindex facet level databases.
                     let old_facet_level_database = old_metadata
@@ -1964,6 +2353,7 @@ impl InnerIndexSettingsDiff {
     pub fn global_facet_settings_changed(&self) -> bool {
         self.old.localized_attributes_rules != self.new.localized_attributes_rules
             || self.old.facet_search != self.new.facet_search
+            || self.old.max_values_per_document_field != self.new.max_values_per_document_field
     }
 
     pub fn reindex_facets(&self) -> bool {
@@ -2014,7 +2404,9 @@ pub(crate) struct InnerIndexSettings {
     pub geo_fields_ids: Option<(FieldId, FieldId)>,
     pub geojson_fid: Option<FieldId>,
     pub prefix_search: PrefixSearch,
+    pub min_prefix_length: u8,
     pub facet_search: bool,
+    pub max_values_per_document_field: Option<usize>,
 }
 
 impl InnerIndexSettings {
@@ -2040,7 +2432,10 @@ impl InnerIndexSettings {
             .map(|r| r.map(|(k, v)| (k.to_string(), v)))
             .collect::<heed::Result<_>>()?;
         let prefix_search = index.prefix_search(rtxn)?.unwrap_or_default();
+        let min_prefix_length = index.min_prefix_length(rtxn)?;
         let facet_search = index.facet_search(rtxn)?;
+        let max_values_per_document_field =
+            index.max_values_per_document_field(rtxn)?.map(|max| max as usize);
         let geo_fields_ids = match fields_ids_map.id(RESERVED_GEO_FIELD_NAME) {
             Some(_) if index.is_geo_enabled(rtxn)? => {
                 // if `_geo` is faceted then we get the `lat` and `lng`
@@ -2086,7 +2481,9 @@ impl InnerIndexSettings {
             geo_fields_ids,
             geojson_fid: geo_json_fid,
             prefix_search,
+            min_prefix_length,
             facet_search,
+            max_values_per_document_field,
             disabled_typos_terms,
         })
     }
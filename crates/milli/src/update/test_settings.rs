@@ -852,6 +852,83 @@ fn update_exact_words_normalization() {
     }
 }
 
+#[test]
+fn set_and_reset_max_values_per_document_field() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|settings| {
+            settings.set_filterable_fields(vec![
+                FilterableAttributesRule::Field(S("genres")),
+                FilterableAttributesRule::Field(S("sizes")),
+            ]);
+            settings.set_max_values_per_document_field(2);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "genres": ["Drama", "Comedy", "drama", "Horror", "Action"], "sizes": [1, 2, 2, 3] },
+            { "id": 1, "genres": ["Horror"], "sizes": 3 }
+        ]))
+        .unwrap();
+
+    let filter = |filter: &str| {
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str(filter).unwrap().unwrap();
+        format!("{:?}", filter.evaluate(&rtxn, &index).unwrap())
+    };
+
+    // only the first two distinct values of the document are indexed
+    snapshot!(filter("genres = drama"), @"RoaringBitmap<[0]>");
+    snapshot!(filter("genres = comedy"), @"RoaringBitmap<[0]>");
+    snapshot!(filter("genres = horror"), @"RoaringBitmap<[1]>");
+    snapshot!(filter("genres = action"), @"RoaringBitmap<[]>");
+    snapshot!(filter("sizes = 2"), @"RoaringBitmap<[0]>");
+    snapshot!(filter("sizes = 3"), @"RoaringBitmap<[1]>");
+    // the field still exists even if some of its values are not indexed
+    snapshot!(filter("genres EXISTS"), @"RoaringBitmap<[0, 1]>");
+
+    // updating the document removes the values that were indexed
+    index
+        .add_documents(documents!([
+            { "id": 0, "genres": ["Action", "Horror", "Drama"], "sizes": [3] }
+        ]))
+        .unwrap();
+    snapshot!(filter("genres = drama"), @"RoaringBitmap<[]>");
+    snapshot!(filter("genres = comedy"), @"RoaringBitmap<[]>");
+    snapshot!(filter("genres = horror"), @"RoaringBitmap<[0, 1]>");
+    snapshot!(filter("genres = action"), @"RoaringBitmap<[0]>");
+    snapshot!(filter("sizes = 3"), @"RoaringBitmap<[0, 1]>");
+
+    // raising the cap reindexes the values that were ignored
+    index
+        .update_settings(|settings| {
+            settings.set_max_values_per_document_field(3);
+        })
+        .unwrap();
+    snapshot!(filter("genres = drama"), @"RoaringBitmap<[0]>");
+
+    // lowering it removes them again
+    index
+        .update_settings(|settings| {
+            settings.set_max_values_per_document_field(1);
+        })
+        .unwrap();
+    snapshot!(filter("genres = action"), @"RoaringBitmap<[0]>");
+    snapshot!(filter("genres = horror"), @"RoaringBitmap<[1]>");
+    snapshot!(filter("genres = drama"), @"RoaringBitmap<[]>");
+
+    // and resetting it indexes all the values
+    index
+        .update_settings(|settings| {
+            settings.reset_max_values_per_document_field();
+        })
+        .unwrap();
+    snapshot!(filter("genres = horror"), @"RoaringBitmap<[0, 1]>");
+    snapshot!(filter("genres = drama"), @"RoaringBitmap<[0]>");
+}
+
 #[test]
 fn test_correct_settings_init() {
     let index = TempIndex::new();
@@ -881,6 +958,14 @@ fn test_correct_settings_init() {
                 exact_words,
                 exact_attributes,
                 max_values_per_facet,
+                max_values_per_document_field,
+                track_facet_values_recency,
+                max_facet_levels,
+                facet_rebalancing_threshold,
+                facet_expected_cardinality,
+                facet_update_methods,
+                facet_value_weight_fields,
+                pinned_facet_distributions,
                 sort_facet_values_by,
                 pagination_max_total_hits,
                 proximity_precision,
@@ -888,7 +973,11 @@ fn test_correct_settings_init() {
                 search_cutoff,
                 localized_attributes_rules,
                 prefix_search,
+                min_prefix_length,
+                prefix_search_warmup,
                 facet_search,
+                faceting_mode,
+                faceting_warmup,
                 disable_on_numbers,
                 chat,
                 vector_store,
@@ -911,6 +1000,14 @@ fn test_correct_settings_init() {
             assert!(matches!(exact_words, Setting::NotSet));
             assert!(matches!(exact_attributes, Setting::NotSet));
             assert!(matches!(max_values_per_facet, Setting::NotSet));
+            assert!(matches!(max_values_per_document_field, Setting::NotSet));
+            assert!(matches!(track_facet_values_recency, Setting::NotSet));
+            assert!(matches!(max_facet_levels, Setting::NotSet));
+            assert!(matches!(facet_rebalancing_threshold, Setting::NotSet));
+            assert!(matches!(facet_expected_cardinality, Setting::NotSet));
+            assert!(matches!(facet_update_methods, Setting::NotSet));
+            assert!(matches!(facet_value_weight_fields, Setting::NotSet));
+            assert!(matches!(pinned_facet_distributions, Setting::NotSet));
             assert!(matches!(sort_facet_values_by, Setting::NotSet));
             assert!(matches!(pagination_max_total_hits, Setting::NotSet));
             assert!(matches!(proximity_precision, Setting::NotSet));
@@ -918,7 +1015,11 @@ fn test_correct_settings_init() {
             assert!(matches!(search_cutoff, Setting::NotSet));
             assert!(matches!(localized_attributes_rules, Setting::NotSet));
             assert!(matches!(prefix_search, Setting::NotSet));
+            assert!(matches!(min_prefix_length, Setting::NotSet));
+            assert!(matches!(prefix_search_warmup, Setting::NotSet));
             assert!(matches!(facet_search, Setting::NotSet));
+            assert!(matches!(faceting_mode, Setting::NotSet));
+            assert!(matches!(faceting_warmup, Setting::NotSet));
             assert!(matches!(disable_on_numbers, Setting::NotSet));
             assert!(matches!(chat, Setting::NotSet));
             assert!(matches!(vector_store, Setting::NotSet));
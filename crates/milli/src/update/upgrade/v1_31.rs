@@ -0,0 +1,25 @@
+use heed::RwTxn;
+
+use super::UpgradeIndex;
+use crate::progress::Progress;
+use crate::update::facet::upgrade_facet_search_fsts_without_counts;
+use crate::{Index, Result};
+
+pub(super) struct RecomputeFacetSearchFstCounts();
+
+impl UpgradeIndex for RecomputeFacetSearchFstCounts {
+    fn upgrade(&self, wtxn: &mut RwTxn, index: &Index, _progress: Progress) -> Result<bool> {
+        upgrade_facet_search_fsts_without_counts(index, wtxn)?;
+        Ok(false)
+    }
+    fn must_upgrade(&self, initial_version: (u32, u32, u32)) -> bool {
+        // The 1.31.0 databases may have been written with or without the counts. As they are
+        // not upgraded when opened by the same version, the indexes not marked as having the
+        // counts are also upgraded by the first update of their facet search FSTs.
+        initial_version <= (1, 31, 0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Rebuilding the facet search FSTs written without the document count of each facet value"
+    }
+}
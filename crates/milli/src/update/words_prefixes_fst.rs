@@ -1,21 +1,31 @@
+use std::collections::BTreeSet;
 use std::iter::{repeat_with, FromIterator};
 use std::str;
 
 use fst::{SetBuilder, Streamer};
 use heed::RwTxn;
 
-use crate::{Index, Result, SmallString32};
+use crate::{Index, Prefix, Result, SmallString32};
 
 pub struct WordsPrefixesFst<'t, 'i> {
     wtxn: &'t mut RwTxn<'i>,
     index: &'i Index,
     threshold: usize,
+    min_prefix_length: usize,
     max_prefix_length: usize,
+    hot_prefixes: Option<BTreeSet<Prefix>>,
 }
 
 impl<'t, 'i> WordsPrefixesFst<'t, 'i> {
     pub fn new(wtxn: &'t mut RwTxn<'i>, index: &'i Index) -> WordsPrefixesFst<'t, 'i> {
-        WordsPrefixesFst { wtxn, index, threshold: 100, max_prefix_length: 4 }
+        WordsPrefixesFst {
+            wtxn,
+            index,
+            threshold: 100,
+            min_prefix_length: 1,
+            max_prefix_length: 4,
+            hot_prefixes: None,
+        }
     }
 
     /// Set the number of words required to make a prefix be part of the words prefixes
@@ -29,6 +39,14 @@ impl<'t, 'i> WordsPrefixesFst<'t, 'i> {
         self
     }
 
+    /// Set the minimum length of prefixes in bytes, shorter prefixes are not stored.
+    ///
+    /// Default value is `1` byte.
+    pub fn min_prefix_length(&mut self, value: usize) -> &mut Self {
+        self.min_prefix_length = value;
+        self
+    }
+
     /// Set the maximum length of prefixes in bytes.
     ///
     /// Default value is `4` bytes. This value must be between 1 and 25 will be clamped
@@ -38,6 +56,15 @@ impl<'t, 'i> WordsPrefixesFst<'t, 'i> {
         self
     }
 
+    /// Restrict the stored prefixes to the given ones, the prefixes matching
+    /// too few words are still ignored.
+    ///
+    /// Default value is `None`, all the prefixes matching enough words are stored.
+    pub fn hot_prefixes(&mut self, value: Option<BTreeSet<Prefix>>) -> &mut Self {
+        self.hot_prefixes = value;
+        self
+    }
+
     #[tracing::instrument(
         level = "trace",
         skip_all,
@@ -54,7 +81,7 @@ impl<'t, 'i> WordsPrefixesFst<'t, 'i> {
 
         let mut stream = words_fst.stream();
         while let Some(bytes) = stream.next() {
-            for n in 0..self.max_prefix_length {
+            for n in self.min_prefix_length.saturating_sub(1)..self.max_prefix_length {
                 let current_prefix = &mut current_prefix[n];
                 let current_prefix_count = &mut current_prefix_count[n];
                 let builder = &mut builders[n];
@@ -78,7 +105,9 @@ impl<'t, 'i> WordsPrefixesFst<'t, 'i> {
                 *current_prefix_count += 1;
 
                 // There is enough words corresponding to this prefix to add it to the cache.
-                if *current_prefix_count >= self.threshold {
+                if *current_prefix_count >= self.threshold
+                    && self.hot_prefixes.as_ref().is_none_or(|hot| hot.contains(prefix))
+                {
                     builder.insert(prefix)?;
                 }
             }